@@ -0,0 +1,78 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub const DEFAULT_ALERT_EVALUATION_INTERVAL: u64 = 30; // seconds
+
+/// A condition evaluated against the node's internal metrics
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AlertRule {
+    /// No new block was produced or received for `minutes`
+    NoBlockProduced { minutes: u64 },
+    /// Fewer than `min_peers` peers are connected
+    PeerCountBelow { min_peers: usize },
+    /// Free disk space on the volume holding `path` (the data dir by default) is below `min_free_gb`
+    DiskSpaceBelow {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<PathBuf>,
+        min_free_gb: u64,
+    },
+    /// The local chain diverged from the trusted node
+    ForkDetected,
+    /// The node switched to read-only mode because its disk is almost full
+    ReadOnlyMode,
+}
+
+/// Where fired and resolved alerts are delivered
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AlertSink {
+    /// The node log
+    Log,
+    /// JSON POST to an HTTP endpoint, e.g. a chat webhook
+    Webhook { url: String },
+    /// Email handed to the local `sendmail` binary
+    Email {
+        to: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<String>,
+    },
+}
+
+/// Alerting rules and sinks, loaded from a YAML file
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertingConfig {
+    #[serde(default = "default_evaluation_interval")]
+    pub evaluation_interval_secs: u64,
+    pub rules: Vec<AlertRule>,
+    #[serde(default = "default_sinks")]
+    pub sinks: Vec<AlertSink>,
+}
+
+fn default_evaluation_interval() -> u64 {
+    DEFAULT_ALERT_EVALUATION_INTERVAL
+}
+
+fn default_sinks() -> Vec<AlertSink> {
+    vec![AlertSink::Log]
+}
+
+impl Default for AlertingConfig {
+    fn default() -> Self {
+        Self {
+            evaluation_interval_secs: DEFAULT_ALERT_EVALUATION_INTERVAL,
+            rules: vec![
+                AlertRule::NoBlockProduced { minutes: 5 },
+                AlertRule::ForkDetected,
+            ],
+            sinks: default_sinks(),
+        }
+    }
+}
+
+impl Config for AlertingConfig {}
@@ -0,0 +1,145 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::Config;
+use crate::validation::ConfigValidator;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pub const DEFAULT_ARCHIVE_REGION: &str = "us-east-1";
+pub const DEFAULT_ARCHIVE_BATCH_SIZE: u64 = 100;
+pub const DEFAULT_ARCHIVE_CONFIRMATIONS: u64 = 10;
+pub const DEFAULT_ARCHIVE_INTERVAL: u64 = 60; // seconds
+
+/// Where finalized blocks are archived. Credentials are read from the standard
+/// `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY` environment variables.
+#[derive(Clone, Default, Debug, Deserialize, PartialEq, Serialize, Parser)]
+#[serde(deny_unknown_fields)]
+pub struct ArchiveConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "archive-bucket",
+        long,
+        help = "The S3-compatible bucket finalized blocks are archived to. If not set, the archiver will not start"
+    )]
+    pub bucket: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "archive-endpoint",
+        long,
+        help = "The endpoint of the S3-compatible storage service, AWS S3 by default"
+    )]
+    pub endpoint: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "archive-region",
+        long,
+        help = "The bucket region, default is us-east-1"
+    )]
+    pub region: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "archive-prefix",
+        long,
+        help = "The path inside the bucket the archive is written under"
+    )]
+    pub prefix: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "archive-batch-size",
+        long,
+        help = "The number of blocks per archived object, default is 100"
+    )]
+    pub batch_size: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "archive-confirmations",
+        long,
+        help = "The number of blocks on top of a block before it is archived, default is 10"
+    )]
+    pub confirmations: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "archive-interval",
+        long,
+        help = "The interval in seconds between archive rounds, default is 60"
+    )]
+    pub interval: Option<u64>,
+}
+
+impl ArchiveConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.bucket.is_some()
+    }
+
+    pub fn region(&self) -> &str {
+        self.region.as_deref().unwrap_or(DEFAULT_ARCHIVE_REGION)
+    }
+
+    pub fn prefix(&self) -> &str {
+        self.prefix.as_deref().unwrap_or("/")
+    }
+
+    pub fn batch_size(&self) -> u64 {
+        self.batch_size.unwrap_or(DEFAULT_ARCHIVE_BATCH_SIZE)
+    }
+
+    pub fn confirmations(&self) -> u64 {
+        self.confirmations.unwrap_or(DEFAULT_ARCHIVE_CONFIRMATIONS)
+    }
+
+    pub fn interval_secs(&self) -> u64 {
+        self.interval.unwrap_or(DEFAULT_ARCHIVE_INTERVAL)
+    }
+
+    pub fn validate_into(&self, validator: &mut ConfigValidator) {
+        validator.check(
+            self.batch_size != Some(0),
+            "batch_size",
+            "must be greater than 0",
+        );
+        validator.check(
+            self.interval != Some(0),
+            "interval",
+            "must be greater than 0",
+        );
+        if let Some(bucket) = &self.bucket {
+            validator.check(!bucket.trim().is_empty(), "bucket", "must not be empty");
+        }
+        if let Some(endpoint) = &self.endpoint {
+            validator.check(
+                endpoint.starts_with("http://") || endpoint.starts_with("https://"),
+                "endpoint",
+                format!("{} must be an http(s) URL", endpoint),
+            );
+        }
+    }
+}
+
+impl Config for ArchiveConfig {}
+
+impl std::fmt::Display for ArchiveConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            serde_json::to_string(self).map_err(|_e| std::fmt::Error)?
+        )
+    }
+}
+
+impl FromStr for ArchiveConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let deserialized: ArchiveConfig = serde_json::from_str(s)?;
+        Ok(deserialized)
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::Config;
+use crate::{KANARI_KEYSTORE_FILENAME, kanari_config_dir};
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const DEFAULT_KEYCHAIN_SERVICE: &str = "kanari";
+pub const DEFAULT_KEYSTORE_ENV_VAR: &str = "KANARI_PRIVATE_KEYS";
+/// Environment variable holding the file keystore password of a node, which cannot prompt
+pub const KEYSTORE_PASSWORD_ENV_VAR: &str = "KANARI_KEYSTORE_PASSWORD";
+
+/// Where the node and CLI load signing keys from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum KeystoreBackend {
+    /// Encrypted keystore file in the kanari config dir
+    #[default]
+    File,
+    /// OS keychain (macOS Keychain, Windows Credential Store, Secret Service)
+    Keychain,
+    /// Hex encoded private keys injected through an environment variable
+    Env,
+}
+
+impl std::fmt::Display for KeystoreBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            KeystoreBackend::File => "file",
+            KeystoreBackend::Keychain => "keychain",
+            KeystoreBackend::Env => "env",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Default, Debug, Deserialize, PartialEq, Serialize, Parser)]
+#[serde(deny_unknown_fields)]
+pub struct KeystoreConfig {
+    #[serde(default)]
+    #[clap(
+        name = "keystore-backend",
+        long,
+        value_enum,
+        default_value_t,
+        help = "The keystore backend used for signing keys: file, keychain or env"
+    )]
+    pub backend: KeystoreBackend,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "keystore-path",
+        long,
+        help = "The keystore file path, only used by the file backend"
+    )]
+    pub path: Option<PathBuf>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "keystore-keychain-service",
+        long,
+        help = "The OS keychain service name, only used by the keychain backend"
+    )]
+    pub keychain_service: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "keystore-env-var",
+        long,
+        help = "The environment variable holding comma separated hex private keys, only used by the env backend"
+    )]
+    pub env_var: Option<String>,
+}
+
+impl KeystoreConfig {
+    pub fn keystore_path(&self) -> Result<PathBuf> {
+        match &self.path {
+            Some(path) => Ok(path.clone()),
+            None => Ok(kanari_config_dir()?.join(KANARI_KEYSTORE_FILENAME)),
+        }
+    }
+
+    pub fn keychain_service(&self) -> &str {
+        self.keychain_service
+            .as_deref()
+            .unwrap_or(DEFAULT_KEYCHAIN_SERVICE)
+    }
+
+    pub fn env_var(&self) -> &str {
+        self.env_var.as_deref().unwrap_or(DEFAULT_KEYSTORE_ENV_VAR)
+    }
+}
+
+impl Config for KeystoreConfig {}
+
+impl std::fmt::Display for KeystoreConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            serde_json::to_string(self).map_err(|_e| std::fmt::Error)?
+        )
+    }
+}
+
+impl FromStr for KeystoreConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let deserialized: KeystoreConfig = serde_json::from_str(s)?;
+        Ok(deserialized)
+    }
+}
@@ -1,9 +1,16 @@
 // Copyright (c) KanariNetwork
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::alerting_config::AlertingConfig;
+use crate::archive_config::ArchiveConfig;
+use crate::config::Config;
+use crate::keystore_config::KeystoreConfig;
+use crate::maintenance_config::MaintenanceConfig;
 use crate::network_config::NetworkConfig;
+use crate::param_override::ParamOverrides;
 use crate::proposer_config::ProposerConfig;
 use crate::store_config::StoreConfig;
+use crate::validation::{ConfigValidationError, ConfigValidator};
 use anyhow::Result;
 use clap::Parser;
 use moveos_config::{DataDirPath, temp_dir};
@@ -18,20 +25,55 @@ use std::collections::HashMap;
 use std::fs::create_dir_all;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{fmt::Debug, path::Path, path::PathBuf};
 
+pub mod alerting_config;
+pub mod archive_config;
 pub mod config;
+pub mod keystore_config;
+pub mod maintenance_config;
 pub mod network_config;
+pub mod param_override;
 pub mod proposer_config;
 pub mod server_config;
 pub mod settings;
 pub mod store_config;
+pub mod validation;
 
 pub const KANARI_DIR: &str = ".kanari";
 pub const KANARI_CONFIR_DIR: &str = "kanari_config";
 pub const KANARI_CLIENT_CONFIG: &str = "kanari.yaml";
 pub const KANARI_KEYSTORE_FILENAME: &str = "kanari.keystore";
 
+pub const DEFAULT_STATE_ROOT_CHECK_INTERVAL: u64 = 60; // seconds
+pub const DEFAULT_BLOCK_AUDIT_INTERVAL: u64 = 300; // seconds
+pub const DEFAULT_BLOCK_AUDIT_SAMPLES: u32 = 4;
+pub const DEFAULT_DISK_WARNING_FREE_GB: u64 = 20;
+pub const DEFAULT_DISK_CRITICAL_FREE_GB: u64 = 5;
+pub const DEFAULT_DISK_CHECK_INTERVAL: u64 = 30; // seconds
+const BYTES_PER_GB: u64 = 1024 * 1024 * 1024;
+pub const DEFAULT_OTLP_SERVICE_NAME: &str = "kanari-node";
+pub const DEFAULT_OTLP_SAMPLE_RATIO: f64 = 1.0;
+pub const DEFAULT_DRAIN_TIMEOUT: u64 = 10; // seconds
+pub const DEFAULT_TRAFFIC_PER_SECOND: f64 = 0.1; // seconds per request
+pub const DEFAULT_TRAFFIC_BURST_SIZE: u32 = 100;
+
+/// RPC namespaces an operator may disable, the `kanari` read API is always served
+pub const OPTIONAL_RPC_NAMESPACES: &[&str] = &["eth", "admin", "debug", "subscribe"];
+pub const MEMPOOL_FILENAME: &str = "mempool.bcs";
+/// API keys created through the admin namespace, in the data dir
+pub const API_KEYS_FILENAME: &str = "api_keys.json";
+/// Aggregate P2P message counters kept across restarts, in the data dir
+pub const MESSAGE_COUNTERS_FILENAME: &str = "message_counters.json";
+/// P2P identity key of the node, in the data dir
+pub const P2P_KEY_FILENAME: &str = "p2p.key";
+pub const RPC_IPC_FILENAME: &str = "kanari.ipc";
+/// Default name of the plugin socket in the base data dir
+pub const PLUGIN_SOCKET_FILENAME: &str = "kanari-plugins.ipc";
+/// Longest Unix domain socket path the platforms we run on accept, in bytes
+const MAX_IPC_PATH_LEN: usize = 103;
+
 pub static R_DEFAULT_BASE_DATA_DIR: Lazy<PathBuf> = Lazy::new(|| {
     dirs_next::home_dir()
         .expect("read home dir should ok")
@@ -99,6 +141,42 @@ pub struct KanariOpt {
     #[clap(long, short = 'p')]
     pub port: Option<u16>,
 
+    /// Also serve the RPC methods over a Unix domain socket at $HOME/.kanari/kanari.ipc,
+    /// or the base data dir, for local tooling. It bypasses the rate limits and API keys
+    /// of the public endpoint; only the node's user may open the socket (mode 0600).
+    #[clap(long)]
+    pub rpc_ipc: bool,
+
+    /// Custom path of the RPC Unix domain socket, implies `--rpc-ipc`. Also accepted
+    /// as `local_socket_path` (`--local-socket-path`), the name of the local tooling socket.
+    #[serde(alias = "local_socket_path", skip_serializing_if = "Option::is_none")]
+    #[clap(long, visible_alias = "local-socket-path")]
+    pub rpc_ipc_path: Option<PathBuf>,
+
+    /// Serve a REST gateway for integrators that do not speak JSON-RPC on this port,
+    /// with `GET /blocks/{number}`, `GET /accounts/{addr}/balance` and `POST /transactions`.
+    /// It shares the rate limits of the public RPC port.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub rest_port: Option<u16>,
+
+    /// Serve the gRPC node service (blocks, accounts and transactions, see
+    /// `kanari-grpc/proto`) on this port, next to the JSON-RPC server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub grpc_port: Option<u16>,
+
+    /// Serve Prometheus metrics (database, RPC requests and block production) at
+    /// `/metrics` on this port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub metrics_port: Option<u16>,
+
+    /// If a configured RPC or P2P port is in use, listen on the next free port instead
+    /// of failing. The chosen ports are printed on startup and reported in node info.
+    #[clap(long)]
+    pub port_auto: bool,
+
     /// The Ethereum RPC URL to connect to for relay L1 block and transaction to L2.
     /// If not set, the relayer service will not start.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -149,11 +227,126 @@ pub struct KanariOpt {
     #[clap(flatten)]
     pub network: NetworkConfig,
 
+    #[clap(flatten)]
+    pub keystore: KeystoreConfig,
+
+    #[clap(flatten)]
+    pub archive: ArchiveConfig,
+
+    #[serde(default)]
+    #[clap(flatten)]
+    pub maintenance: MaintenanceConfig,
+
+    /// The trusted remote RPC URL used to cross-check local state roots.
+    /// If not set, the state root verifier will not start.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long, env = "KANARI_TRUSTED_RPC_URL")]
+    pub trusted_rpc_url: Option<String>,
+    /// The interval in seconds between state root cross-checks, default is 60.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub state_root_check_interval: Option<u64>,
+    /// Stop block production when the local state root diverges from the trusted node
+    #[clap(long)]
+    pub halt_on_state_root_mismatch: bool,
+
+    /// The alerting rules file (YAML). If not set, the alerting engine will not start.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub alert_config: Option<PathBuf>,
+
+    /// Disable the background auditor that re-verifies random historical blocks
+    #[clap(long)]
+    pub disable_block_audit: bool,
+    /// The interval in seconds between block audit rounds, default is 300.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub block_audit_interval: Option<u64>,
+    /// The number of random historical blocks re-verified per audit round, default is 4.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub block_audit_samples: Option<u32>,
+
+    /// Disable the guard switching the node to read-only mode when its disk fills up
+    #[clap(long)]
+    pub disable_disk_guard: bool,
+    /// Free space of the data dir volume in GB below which the node warns and prunes
+    /// receipts early, default is 20.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub disk_warning_free_gb: Option<u64>,
+    /// Free space of the data dir volume in GB below which the node stops producing
+    /// blocks and accepting transactions until space is freed, default is 5.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub disk_critical_free_gb: Option<u64>,
+    /// The interval in seconds between free disk space checks, default is 30.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub disk_check_interval: Option<u64>,
+
+    /// OTLP/gRPC endpoint tracing spans are exported to, e.g. http://localhost:4317.
+    /// Spans are only logged when it is not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub otlp_endpoint: Option<String>,
+    /// The service name exported spans are reported under, default is kanari-node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub otlp_service_name: Option<String>,
+    /// The share of traces exported, between 0 and 1, default is 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub otlp_sample_ratio: Option<f64>,
+
+    /// The maximum number of pending transactions per sender in the mempool, default is 32.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub mempool_max_pending_per_sender: Option<usize>,
+    /// The maximum total size in bytes of a sender's pending transactions, default is 524288.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub mempool_max_pending_bytes_per_sender: Option<usize>,
+    /// The sender queue depth from which the minimum gas price doubles per transaction, default is 16.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub mempool_fee_bump_depth: Option<usize>,
+    /// The maximum number of nonce gapped transactions held per sender until the gap fills, default is 16.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub mempool_max_queued_per_sender: Option<usize>,
+    /// Seconds a nonce gapped transaction is held for its gap to fill, default is 600.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub mempool_queued_ttl_secs: Option<u64>,
+    /// The percentage a replacement must raise the gas price of a pooled transaction by, default is 10.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub mempool_min_replacement_bump_pct: Option<u64>,
+
+    /// Hex encoded secp256k1 public keys of external proposers allowed to submit
+    /// blocks through `kanari_submitBlock`. If not set, external submission is disabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[clap(long, value_delimiter = ',')]
+    pub external_proposer_keys: Vec<String>,
+
+    /// Override a chain parameter as `key=value` on local and dev chains, one of
+    /// block_interval_secs, block_gas_limit or min_gas_price. Overrides are reported in
+    /// node info so experiments can be reproduced.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[clap(long, value_delimiter = ',')]
+    pub param_override: Vec<String>,
+
+    /// Seconds allowed on shutdown to stop intake, persist the mempool and close connections, default is 10.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub drain_timeout: Option<u64>,
+
     #[clap(long, default_value_t, value_enum)]
     pub service_status: ServiceStatus,
 
     /// Set quota size that defines how many requests can occur
-    /// before the governor middleware starts blocking requests from an IP address and
+    /// before the RPC rate limiter starts rejecting requests from an IP address and
     /// clients have to wait until the elements of the quota are replenished.
     ///
     /// **The burst_size must not be zero.**
@@ -166,6 +359,119 @@ pub struct KanariOpt {
     #[clap(long)]
     pub traffic_per_second: Option<f64>,
 
+    /// Per-method RPC rate limits per IP as `method=requests_per_second[:burst]`,
+    /// e.g. `kanari_sendRawTransaction=5:10`. The burst defaults to the rate.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[clap(long, value_delimiter = ',')]
+    pub rpc_method_rate_limit: Vec<String>,
+
+    /// API keys required to call the admin and debug RPC namespaces on the public
+    /// endpoint, sent as `Authorization: Bearer <key>` or `X-Api-Key`. If not set, they are public.
+    #[serde(skip)]
+    #[clap(
+        long,
+        env = "KANARI_RPC_API_KEYS",
+        value_delimiter = ',',
+        hide_env_values = true
+    )]
+    pub rpc_api_key: Vec<String>,
+
+    /// Origins allowed to call the RPC endpoint and REST gateway from a browser, e.g.
+    /// `https://wallet.example`, or `*` for any origin. Without any, browsers only allow
+    /// pages of the endpoint's own origin.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[clap(long, value_delimiter = ',')]
+    pub rpc_cors_origin: Vec<String>,
+
+    /// Request headers the allowed origins may send, defaults to Content-Type,
+    /// Authorization and X-Api-Key
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[clap(long, value_delimiter = ',')]
+    pub rpc_cors_header: Vec<String>,
+
+    /// HTTP methods the allowed origins may use, defaults to POST
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[clap(long, value_delimiter = ',')]
+    pub rpc_cors_method: Vec<String>,
+
+    /// Allow any origin, header and method, for local development. Any website the
+    /// node's user visits may then call the node.
+    #[clap(long)]
+    pub rpc_cors_permissive: bool,
+
+    /// RPC namespaces not to register, any of eth, admin, debug and subscribe, so a
+    /// public-facing node can serve only the kanari read API
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[clap(long, value_delimiter = ',')]
+    pub rpc_disable_namespace: Vec<String>,
+
+    /// Serve the `experimental` RPC namespace, whose methods may change or be removed
+    /// in any release. `experimental_listMethods` lists them.
+    #[clap(long)]
+    pub rpc_enable_experimental: bool,
+
+    /// Maximum number of blocks an indexer may fetch with one `kanari_getBlocksByNumbers`
+    /// call, defaults to 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub rpc_max_blocks_per_batch: Option<usize>,
+
+    /// Maximum number of subscriptions a WebSocket connection may hold open, defaults to 64
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub rpc_max_subscriptions_per_connection: Option<u32>,
+    /// Messages buffered for a WebSocket connection before sending to it waits,
+    /// defaults to 256
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub rpc_subscription_buffer_capacity: Option<u32>,
+    /// Seconds a subscription message waits for a slow client to read its buffer before
+    /// the subscription is closed, defaults to 10
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long, conflicts_with = "rpc_drop_slow_subscriber_messages")]
+    pub rpc_slow_subscriber_timeout_secs: Option<u64>,
+    /// Drop the subscription messages a slow client has no room for instead of closing
+    /// its subscription
+    #[clap(long)]
+    pub rpc_drop_slow_subscriber_messages: bool,
+
+    /// Responses about confirmed blocks, receipts and modules kept in memory, 0 disables
+    /// the cache, defaults to 4096
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub rpc_response_cache_size: Option<usize>,
+    /// Seconds a cached response is served before it is read again, defaults to 600
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub rpc_response_cache_ttl_secs: Option<u64>,
+    /// Blocks on top of a block before responses about it are cached, defaults to 10
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub rpc_response_cache_confirmations: Option<u128>,
+
+    /// External plugins allowed to connect to the plugin socket as `name=token`. Plugins
+    /// receive the event stream and submit transactions over newline-delimited JSON.
+    #[serde(skip)]
+    #[clap(
+        long,
+        env = "KANARI_PLUGIN_TOKENS",
+        value_delimiter = ',',
+        hide_env_values = true
+    )]
+    pub plugin_token: Vec<String>,
+
+    /// Transaction submission limits of plugins as `name=requests_per_second[:burst]`.
+    /// Plugins without a limit are not limited.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[clap(long, value_delimiter = ',')]
+    pub plugin_rate_limit: Vec<String>,
+
+    /// Path of the plugin socket, defaults to kanari-plugins.ipc in the base data dir.
+    /// The socket is only served when a plugin token is configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    pub plugin_socket: Option<PathBuf>,
+
     #[clap(long, default_value_t, value_enum)]
     pub service_type: ServiceType,
 
@@ -192,6 +498,12 @@ impl KanariOpt {
             genesis_config: None,
             store: StoreConfig::default(),
             port: None,
+            rpc_ipc: false,
+            rpc_ipc_path: None,
+            rest_port: None,
+            grpc_port: None,
+            metrics_port: None,
+            port_auto: false,
             eth_rpc_url: None,
             btc_rpc_url: None,
             btc_rpc_username: None,
@@ -204,9 +516,54 @@ impl KanariOpt {
             proposer_account: None,
             proposer: ProposerConfig::default(),
             network: NetworkConfig::default(),
+            keystore: KeystoreConfig::default(),
+            archive: ArchiveConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            trusted_rpc_url: None,
+            state_root_check_interval: None,
+            halt_on_state_root_mismatch: false,
+            alert_config: None,
+            disable_block_audit: false,
+            block_audit_interval: None,
+            block_audit_samples: None,
+            disable_disk_guard: false,
+            disk_warning_free_gb: None,
+            disk_critical_free_gb: None,
+            disk_check_interval: None,
+            otlp_endpoint: None,
+            otlp_service_name: None,
+            otlp_sample_ratio: None,
+            mempool_max_pending_per_sender: None,
+            mempool_max_pending_bytes_per_sender: None,
+            mempool_fee_bump_depth: None,
+            mempool_max_queued_per_sender: None,
+            mempool_queued_ttl_secs: None,
+            mempool_min_replacement_bump_pct: None,
+            external_proposer_keys: vec![],
+            param_override: vec![],
+            drain_timeout: None,
             service_status: ServiceStatus::default(),
             traffic_per_second: None,
             traffic_burst_size: None,
+            rpc_method_rate_limit: vec![],
+            rpc_api_key: vec![],
+            rpc_cors_origin: vec![],
+            rpc_cors_header: vec![],
+            rpc_cors_method: vec![],
+            rpc_cors_permissive: false,
+            rpc_disable_namespace: vec![],
+            rpc_enable_experimental: false,
+            rpc_max_blocks_per_batch: None,
+            rpc_max_subscriptions_per_connection: None,
+            rpc_subscription_buffer_capacity: None,
+            rpc_slow_subscriber_timeout_secs: None,
+            rpc_drop_slow_subscriber_messages: false,
+            rpc_response_cache_size: None,
+            rpc_response_cache_ttl_secs: None,
+            rpc_response_cache_confirmations: None,
+            plugin_token: vec![],
+            plugin_rate_limit: vec![],
+            plugin_socket: None,
             base: None,
             service_type: ServiceType::default(),
         };
@@ -249,6 +606,164 @@ impl KanariOpt {
             })
     }
 
+    pub fn state_root_verifier_config(&self) -> Option<StateRootVerifierConfig> {
+        self.trusted_rpc_url
+            .as_ref()
+            .map(|trusted_rpc_url| StateRootVerifierConfig {
+                trusted_rpc_url: trusted_rpc_url.clone(),
+                check_interval_secs: self
+                    .state_root_check_interval
+                    .unwrap_or(DEFAULT_STATE_ROOT_CHECK_INTERVAL),
+                halt_on_mismatch: self.halt_on_state_root_mismatch,
+            })
+    }
+
+    pub fn block_audit_config(&self) -> Option<BlockAuditConfig> {
+        if self.disable_block_audit {
+            return None;
+        }
+        Some(BlockAuditConfig {
+            interval_secs: self
+                .block_audit_interval
+                .unwrap_or(DEFAULT_BLOCK_AUDIT_INTERVAL),
+            samples_per_round: self
+                .block_audit_samples
+                .unwrap_or(DEFAULT_BLOCK_AUDIT_SAMPLES),
+        })
+    }
+
+    pub fn disk_guard_config(&self) -> Option<DiskGuardConfig> {
+        if self.disable_disk_guard {
+            return None;
+        }
+        Some(DiskGuardConfig {
+            warning_free_bytes: self
+                .disk_warning_free_gb
+                .unwrap_or(DEFAULT_DISK_WARNING_FREE_GB)
+                * BYTES_PER_GB,
+            critical_free_bytes: self
+                .disk_critical_free_gb
+                .unwrap_or(DEFAULT_DISK_CRITICAL_FREE_GB)
+                * BYTES_PER_GB,
+            interval_secs: self
+                .disk_check_interval
+                .unwrap_or(DEFAULT_DISK_CHECK_INTERVAL),
+        })
+    }
+
+    pub fn tracing_export_config(&self) -> Option<TracingExportConfig> {
+        Some(TracingExportConfig {
+            endpoint: self.otlp_endpoint.clone()?,
+            service_name: self
+                .otlp_service_name
+                .clone()
+                .unwrap_or_else(|| DEFAULT_OTLP_SERVICE_NAME.to_string()),
+            sample_ratio: self.otlp_sample_ratio.unwrap_or(DEFAULT_OTLP_SAMPLE_RATIO),
+        })
+    }
+
+    pub fn drain_timeout(&self) -> Duration {
+        Duration::from_secs(self.drain_timeout.unwrap_or(DEFAULT_DRAIN_TIMEOUT))
+    }
+
+    /// The per-IP RPC rate limit as (requests per second, burst), None unless one of
+    /// `traffic_per_second` or `traffic_burst_size` is set
+    pub fn rpc_rate_limit(&self) -> Option<(f64, u32)> {
+        if self.traffic_per_second.is_none() && self.traffic_burst_size.is_none() {
+            return None;
+        }
+        let interval = self
+            .traffic_per_second
+            .unwrap_or(DEFAULT_TRAFFIC_PER_SECOND);
+        Some((
+            1.0 / interval,
+            self.traffic_burst_size
+                .unwrap_or(DEFAULT_TRAFFIC_BURST_SIZE),
+        ))
+    }
+
+    /// Parse `rpc_method_rate_limit` into (method, requests per second, burst)
+    pub fn rpc_method_rate_limits(&self) -> Result<Vec<(String, f64, u32)>> {
+        self.rpc_method_rate_limit
+            .iter()
+            .map(|rule| parse_method_rate_limit(rule))
+            .collect()
+    }
+
+    /// Path of the RPC Unix domain socket, None unless IPC is enabled
+    pub fn rpc_ipc_path(&self) -> Option<PathBuf> {
+        self.rpc_ipc_path.clone().or_else(|| {
+            self.rpc_ipc
+                .then(|| self.base().base_data_dir().join(RPC_IPC_FILENAME))
+        })
+    }
+
+    /// Path of the plugin socket, None unless a plugin is configured
+    pub fn plugin_socket_path(&self) -> Option<PathBuf> {
+        if self.plugin_token.is_empty() {
+            return None;
+        }
+        Some(
+            self.plugin_socket
+                .clone()
+                .unwrap_or_else(|| self.base().base_data_dir().join(PLUGIN_SOCKET_FILENAME)),
+        )
+    }
+
+    /// Parse `plugin_token` and `plugin_rate_limit` into (name, token, optional
+    /// (requests per second, burst))
+    pub fn plugins(&self) -> Result<Vec<(String, String, Option<(f64, u32)>)>> {
+        let limits = self
+            .plugin_rate_limit
+            .iter()
+            .map(|rule| parse_method_rate_limit(rule))
+            .collect::<Result<Vec<_>>>()?;
+        self.plugin_token
+            .iter()
+            .map(|entry| {
+                let (name, token) = parse_plugin_token(entry)?;
+                let limit = limits
+                    .iter()
+                    .find(|(plugin, _, _)| *plugin == name)
+                    .map(|(_, rate, burst)| (*rate, *burst));
+                Ok((name, token, limit))
+            })
+            .collect()
+    }
+
+    /// Parse `param_override` into the overridden chain parameters
+    pub fn param_overrides(&self) -> Result<ParamOverrides> {
+        ParamOverrides::parse(&self.param_override)
+    }
+
+    /// Where pending transactions are kept across restarts
+    pub fn mempool_path(&self) -> PathBuf {
+        self.base().data_dir().join(MEMPOOL_FILENAME)
+    }
+
+    /// Where the API keys created through the admin namespace are kept
+    pub fn api_keys_path(&self) -> PathBuf {
+        self.base().data_dir().join(API_KEYS_FILENAME)
+    }
+
+    /// Where the aggregate P2P message counters are kept across restarts
+    pub fn message_counters_path(&self) -> PathBuf {
+        self.base().data_dir().join(MESSAGE_COUNTERS_FILENAME)
+    }
+
+    /// Where the P2P identity key is kept, so the peer ID survives restarts
+    pub fn p2p_key_path(&self) -> PathBuf {
+        self.base().data_dir().join(P2P_KEY_FILENAME)
+    }
+
+    /// Load the alerting rules file, if configured
+    pub fn alerting_config(&self) -> Result<Option<AlertingConfig>> {
+        self.alert_config
+            .as_ref()
+            .map(AlertingConfig::load)
+            .transpose()
+    }
+
     // pub fn init_btc_reorg_aware_block_store_dir(&mut self) -> Result<()> {
     //     if self.btc_reorg_aware_block_store_dir.is_none() {
     //         self.btc_reorg_aware_block_store_dir = Some(
@@ -286,6 +801,371 @@ impl KanariOpt {
     //     })
     // }
 
+    /// Validate the fully resolved configuration before startup, reporting every
+    /// violation with its field path instead of stopping at the first one.
+    pub fn validate(&self) -> Result<(), ConfigValidationError> {
+        let mut validator = ConfigValidator::new();
+
+        // RPC
+        let rpc_port = self.port();
+        validator.check(rpc_port != 0, "port", "must be greater than 0");
+        validator.check(
+            rpc_port != self.network.p2p_port,
+            "port",
+            format!("conflicts with network.p2p_port {}", self.network.p2p_port),
+        );
+        if let Some(rest_port) = self.rest_port {
+            validator.check(rest_port != 0, "rest_port", "must be greater than 0");
+            for (other, name) in [
+                (Some(rpc_port), "port"),
+                (Some(self.network.p2p_port), "network.p2p_port"),
+            ] {
+                validator.check(
+                    other != Some(rest_port),
+                    "rest_port",
+                    format!("conflicts with {} {}", name, rest_port),
+                );
+            }
+        }
+        if let Some(grpc_port) = self.grpc_port {
+            validator.check(grpc_port != 0, "grpc_port", "must be greater than 0");
+            for (other, name) in [
+                (Some(rpc_port), "port"),
+                (self.rest_port, "rest_port"),
+                (Some(self.network.p2p_port), "network.p2p_port"),
+            ] {
+                validator.check(
+                    other != Some(grpc_port),
+                    "grpc_port",
+                    format!("conflicts with {} {}", name, grpc_port),
+                );
+            }
+        }
+        if let Some(metrics_port) = self.metrics_port {
+            validator.check(metrics_port != 0, "metrics_port", "must be greater than 0");
+            for (other, name) in [
+                (Some(rpc_port), "port"),
+                (self.rest_port, "rest_port"),
+                (self.grpc_port, "grpc_port"),
+                (Some(self.network.p2p_port), "network.p2p_port"),
+            ] {
+                validator.check(
+                    other != Some(metrics_port),
+                    "metrics_port",
+                    format!("conflicts with {} {}", name, metrics_port),
+                );
+            }
+        }
+        validator.check(
+            self.traffic_burst_size != Some(0),
+            "traffic_burst_size",
+            "must not be zero",
+        );
+        if let Some(per_second) = self.traffic_per_second {
+            validator.check(
+                per_second > 0.0,
+                "traffic_per_second",
+                "must be greater than 0",
+            );
+        }
+        if let Some(path) = &self.rpc_ipc_path {
+            validator.check(
+                path.as_os_str().len() <= MAX_IPC_PATH_LEN,
+                "rpc_ipc_path",
+                format!("must not be longer than {} bytes", MAX_IPC_PATH_LEN),
+            );
+            validator.check(
+                path.parent()
+                    .is_none_or(|dir| dir.as_os_str().is_empty() || dir.is_dir()),
+                "rpc_ipc_path",
+                format!("directory of {} does not exist", path.display()),
+            );
+        }
+        validator.check(
+            self.rpc_api_key.iter().all(|key| !key.trim().is_empty()),
+            "rpc_api_key",
+            "must not be empty",
+        );
+        for rule in &self.rpc_method_rate_limit {
+            if let Err(e) = parse_method_rate_limit(rule) {
+                validator.add("rpc_method_rate_limit", e.to_string());
+            }
+        }
+        if let Err(e) = self.param_overrides() {
+            validator.add("param_override", e.to_string());
+        }
+        validator.check(
+            self.param_override.is_empty()
+                || matches!(
+                    self.chain_id(),
+                    RoochChainID::Builtin(BuiltinChainID::Local | BuiltinChainID::Dev)
+                ),
+            "param_override",
+            format!(
+                "is only allowed on local and dev chains, not {}",
+                self.chain_id()
+            ),
+        );
+        for namespace in &self.rpc_disable_namespace {
+            validator.check(
+                OPTIONAL_RPC_NAMESPACES.contains(&namespace.as_str()),
+                "rpc_disable_namespace",
+                format!(
+                    "{} is not one of {}",
+                    namespace,
+                    OPTIONAL_RPC_NAMESPACES.join(", ")
+                ),
+            );
+        }
+        validator.check(
+            self.rpc_max_blocks_per_batch != Some(0),
+            "rpc_max_blocks_per_batch",
+            "must be greater than 0",
+        );
+        validator.check(
+            self.rpc_max_subscriptions_per_connection != Some(0),
+            "rpc_max_subscriptions_per_connection",
+            "must be greater than 0",
+        );
+        validator.check(
+            self.rpc_subscription_buffer_capacity != Some(0),
+            "rpc_subscription_buffer_capacity",
+            "must be greater than 0",
+        );
+        validator.check(
+            self.rpc_slow_subscriber_timeout_secs != Some(0),
+            "rpc_slow_subscriber_timeout_secs",
+            "must be greater than 0",
+        );
+        validator.check(
+            self.rpc_response_cache_ttl_secs != Some(0),
+            "rpc_response_cache_ttl_secs",
+            "must be greater than 0",
+        );
+        for origin in &self.rpc_cors_origin {
+            validator.check(
+                origin == "*"
+                    || ((origin.starts_with("http://") || origin.starts_with("https://"))
+                        && !origin.contains(char::is_whitespace)),
+                "rpc_cors_origin",
+                format!(
+                    "{} must be * or a scheme and host such as https://example.com",
+                    origin
+                ),
+            );
+        }
+        for header in &self.rpc_cors_header {
+            validator.check(
+                !header.is_empty()
+                    && header
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+                "rpc_cors_header",
+                format!("{} is not a valid header name", header),
+            );
+        }
+        for method in &self.rpc_cors_method {
+            validator.check(
+                !method.is_empty() && method.chars().all(|c| c.is_ascii_alphabetic()),
+                "rpc_cors_method",
+                format!("{} is not a valid HTTP method", method),
+            );
+        }
+        let mut plugin_names = std::collections::HashSet::new();
+        for entry in &self.plugin_token {
+            match parse_plugin_token(entry) {
+                // The entry may carry the token, do not echo it
+                Err(_) => validator.add("plugin_token", "must be in the form name=token"),
+                Ok((name, _)) => validator.check(
+                    plugin_names.insert(name.clone()),
+                    "plugin_token",
+                    format!("plugin {} is configured twice", name),
+                ),
+            }
+        }
+        for rule in &self.plugin_rate_limit {
+            match parse_method_rate_limit(rule) {
+                Err(e) => validator.add("plugin_rate_limit", e.to_string()),
+                Ok((name, _, _)) => validator.check(
+                    plugin_names.contains(&name),
+                    "plugin_rate_limit",
+                    format!("plugin {} has no token", name),
+                ),
+            }
+        }
+        if let Some(path) = &self.plugin_socket {
+            validator.check(
+                path.as_os_str().len() <= MAX_IPC_PATH_LEN,
+                "plugin_socket",
+                format!("must not be longer than {} bytes", MAX_IPC_PATH_LEN),
+            );
+            validator.check(
+                path.parent()
+                    .is_none_or(|dir| dir.as_os_str().is_empty() || dir.is_dir()),
+                "plugin_socket",
+                format!("directory of {} does not exist", path.display()),
+            );
+            validator.check(
+                self.rpc_ipc_path().as_ref() != Some(path),
+                "plugin_socket",
+                "must differ from the RPC IPC path",
+            );
+        }
+
+        // P2P
+        validator.section("network", |v| self.network.validate_into(v));
+        if let RoochChainID::Custom(_) = self.chain_id() {
+            let chain_id = self.chain_id().id();
+            validator.check(
+                self.network.network_id == chain_id,
+                "network.network_id",
+                format!(
+                    "{} is inconsistent with chain_id {}",
+                    self.network.network_id, chain_id
+                ),
+            );
+            validator.check(
+                self.genesis_config.is_some(),
+                "genesis_config",
+                "is required for a custom chain network",
+            );
+        }
+        if let Some(genesis_config) = &self.genesis_config {
+            let genesis_config = genesis_config.trim();
+            if BuiltinChainID::from_str(genesis_config).is_err() {
+                validator.check(
+                    Path::new(genesis_config).is_file(),
+                    "genesis_config",
+                    format!("file {} does not exist", genesis_config),
+                );
+            }
+        }
+
+        // Store
+        validator.section("store", |v| self.store.validate_into(v));
+        if let Some(dir) = &self.btc_reorg_aware_block_store_dir {
+            validator.check(
+                dir.is_dir(),
+                "btc_reorg_aware_block_store_dir",
+                format!("directory {} does not exist", dir.display()),
+            );
+        }
+        if let Some(path) = &self.keystore.path {
+            validator.check(
+                path.parent()
+                    .map(|dir| dir.as_os_str().is_empty() || dir.is_dir())
+                    == Some(true),
+                "keystore.path",
+                format!("parent directory of {} does not exist", path.display()),
+            );
+        }
+
+        // Proposer
+        validator.section("proposer", |v| self.proposer.validate_into(v));
+
+        // Archive
+        validator.section("archive", |v| self.archive.validate_into(v));
+        validator.check(
+            !self.maintenance.archive_uploads || self.archive.is_enabled(),
+            "maintenance.archive_uploads",
+            "requires an archive bucket",
+        );
+
+        // Maintenance
+        validator.section("maintenance", |v| self.maintenance.validate_into(v));
+
+        // Alerting
+        if let Some(path) = &self.alert_config {
+            validator.check(
+                path.is_file(),
+                "alert_config",
+                format!("file {} does not exist", path.display()),
+            );
+        }
+
+        // Mempool
+        validator.check(
+            self.mempool_max_pending_per_sender != Some(0),
+            "mempool_max_pending_per_sender",
+            "must be greater than 0",
+        );
+        validator.check(
+            self.mempool_max_pending_bytes_per_sender != Some(0),
+            "mempool_max_pending_bytes_per_sender",
+            "must be greater than 0",
+        );
+        validator.check(
+            self.mempool_queued_ttl_secs != Some(0),
+            "mempool_queued_ttl_secs",
+            "must be greater than 0",
+        );
+
+        // External proposers
+        for key in &self.external_proposer_keys {
+            validator.check(
+                hex::decode(key.strip_prefix("0x").unwrap_or(key)).is_ok(),
+                "external_proposer_keys",
+                format!("{} is not a hex encoded public key", key),
+            );
+        }
+
+        // State root verifier
+        validator.check(
+            self.block_audit_interval != Some(0),
+            "block_audit_interval",
+            "must be greater than 0",
+        );
+        validator.check(
+            self.disk_check_interval != Some(0),
+            "disk_check_interval",
+            "must be greater than 0",
+        );
+        validator.check(
+            self.disk_critical_free_gb
+                .unwrap_or(DEFAULT_DISK_CRITICAL_FREE_GB)
+                < self
+                    .disk_warning_free_gb
+                    .unwrap_or(DEFAULT_DISK_WARNING_FREE_GB),
+            "disk_critical_free_gb",
+            "must be below disk_warning_free_gb",
+        );
+
+        // Tracing export
+        if let Some(endpoint) = &self.otlp_endpoint {
+            validator.check(
+                endpoint.starts_with("http://") || endpoint.starts_with("https://"),
+                "otlp_endpoint",
+                format!("{} must be an http:// or https:// URL", endpoint),
+            );
+        }
+        validator.check(
+            self.otlp_sample_ratio
+                .is_none_or(|ratio| (0.0..=1.0).contains(&ratio)),
+            "otlp_sample_ratio",
+            "must be between 0 and 1",
+        );
+        validator.check(
+            self.otlp_endpoint.is_some()
+                || (self.otlp_service_name.is_none() && self.otlp_sample_ratio.is_none()),
+            "otlp_endpoint",
+            "is required when otlp_service_name or otlp_sample_ratio is set",
+        );
+        validator.check(
+            self.state_root_check_interval != Some(0),
+            "state_root_check_interval",
+            "must be greater than 0",
+        );
+        if let Some(url) = &self.trusted_rpc_url {
+            validator.check(
+                url.starts_with("http://") || url.starts_with("https://"),
+                "trusted_rpc_url",
+                "must be an http:// or https:// URL",
+            );
+        }
+
+        validator.into_result()
+    }
+
     pub fn port(&self) -> u16 {
         self.port.unwrap_or(6767)
     }
@@ -294,6 +1174,11 @@ impl KanariOpt {
         self.chain_id.clone().unwrap_or_default()
     }
 
+    /// The bootstrap nodes to dial, including the built-in ones of the chain network
+    pub fn bootstrap_nodes(&self) -> Vec<String> {
+        self.network.effective_bootstrap_nodes(&self.chain_id())
+    }
+
     pub fn genesis_config(&self) -> Option<GenesisConfig> {
         self.genesis_config.clone().map(|path| {
             let path = path.trim();
@@ -325,6 +1210,19 @@ impl KanariOpt {
         &self.store
     }
 
+    pub fn keystore_config(&self) -> &KeystoreConfig {
+        &self.keystore
+    }
+
+    /// The archive sink settings, None if no bucket is configured
+    pub fn archive_config(&self) -> Option<&ArchiveConfig> {
+        self.archive.is_enabled().then_some(&self.archive)
+    }
+
+    pub fn maintenance_config(&self) -> &MaintenanceConfig {
+        &self.maintenance
+    }
+
     pub fn base(&self) -> &BaseConfig {
         self.base.as_ref().expect("Config should init.")
     }
@@ -339,6 +1237,33 @@ pub struct EthereumRelayerConfig {
     pub eth_rpc_url: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct StateRootVerifierConfig {
+    pub trusted_rpc_url: String,
+    pub check_interval_secs: u64,
+    pub halt_on_mismatch: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockAuditConfig {
+    pub interval_secs: u64,
+    pub samples_per_round: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskGuardConfig {
+    pub warning_free_bytes: u64,
+    pub critical_free_bytes: u64,
+    pub interval_secs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TracingExportConfig {
+    pub endpoint: String,
+    pub service_name: String,
+    pub sample_ratio: f64,
+}
+
 #[derive(Debug, Clone)]
 pub struct BitcoinRelayerConfig {
     pub btc_rpc_url: String,
@@ -452,3 +1377,52 @@ pub fn retrieve_map_config_value(
     }
     MapConfigValueSource::None
 }
+
+/// Parse a `name=token` plugin entry
+fn parse_plugin_token(entry: &str) -> Result<(String, String)> {
+    match entry.trim().split_once('=') {
+        Some((name, token)) if !name.is_empty() && !token.is_empty() => {
+            Ok((name.to_string(), token.to_string()))
+        }
+        _ => anyhow::bail!("plugin entry is not in the form name=token"),
+    }
+}
+
+/// Parse a `method=requests_per_second[:burst]` rate limit rule
+fn parse_method_rate_limit(rule: &str) -> Result<(String, f64, u32)> {
+    let (method, limit) = rule
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("{} is not in the form method=rate[:burst]", rule))?;
+    let (rate, burst) = match limit.split_once(':') {
+        Some((rate, burst)) => (rate, Some(burst)),
+        None => (limit, None),
+    };
+    let rate: f64 = rate
+        .parse()
+        .map_err(|_| anyhow::anyhow!("{} has an invalid rate {}", rule, rate))?;
+    if method.is_empty() || rate <= 0.0 {
+        anyhow::bail!("{} needs a method and a rate greater than 0", rule);
+    }
+    let burst = match burst {
+        Some(burst) => burst
+            .parse()
+            .map_err(|_| anyhow::anyhow!("{} has an invalid burst {}", rule, burst))?,
+        None => rate.ceil() as u32,
+    };
+    if burst == 0 {
+        anyhow::bail!("{} must not have a zero burst", rule);
+    }
+    Ok((method.to_string(), rate, burst))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_socket_path_sets_the_rpc_ipc_path() {
+        let opt = KanariOpt::try_parse_from(["kanari", "--local-socket-path", "/tmp/kanari.ipc"])
+            .unwrap();
+        assert_eq!(opt.rpc_ipc_path(), Some(PathBuf::from("/tmp/kanari.ipc")));
+    }
+}
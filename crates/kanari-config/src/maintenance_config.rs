@@ -0,0 +1,141 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::Config;
+use crate::validation::ConfigValidator;
+use anyhow::{Result, anyhow, bail};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// When heavy storage work runs. Without windows, compaction and pruning only run when
+/// triggered through `admin_runMaintenance`.
+#[derive(Clone, Default, Debug, Deserialize, PartialEq, Serialize, Parser)]
+#[serde(deny_unknown_fields)]
+pub struct MaintenanceConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[clap(
+        name = "maintenance-window",
+        long,
+        value_delimiter = ',',
+        help = "Daily off-peak windows in UTC for compaction, pruning and archive uploads, such as 22:30-04:00"
+    )]
+    pub windows: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "maintenance-prune-receipts-after",
+        long,
+        help = "Delete the events and blooms of blocks older than this many blocks. If not set, receipts are kept"
+    )]
+    pub prune_receipts_after: Option<u64>,
+
+    #[clap(
+        name = "maintenance-archive-uploads",
+        long,
+        help = "Upload finalized blocks to the archive in the maintenance windows only, instead of every archive interval"
+    )]
+    pub archive_uploads: bool,
+}
+
+impl MaintenanceConfig {
+    pub fn windows(&self) -> Result<Vec<MaintenanceWindow>> {
+        self.windows.iter().map(|window| window.parse()).collect()
+    }
+
+    pub fn validate_into(&self, validator: &mut ConfigValidator) {
+        for window in &self.windows {
+            if let Err(e) = window.parse::<MaintenanceWindow>() {
+                validator.check(false, "windows", e.to_string());
+            }
+        }
+        validator.check(
+            self.prune_receipts_after != Some(0),
+            "prune_receipts_after",
+            "must be greater than 0",
+        );
+        validator.check(
+            !self.archive_uploads || !self.windows.is_empty(),
+            "archive_uploads",
+            "requires at least one maintenance window",
+        );
+    }
+}
+
+impl Config for MaintenanceConfig {}
+
+impl std::fmt::Display for MaintenanceConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            serde_json::to_string(self).map_err(|_e| std::fmt::Error)?
+        )
+    }
+}
+
+impl FromStr for MaintenanceConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let deserialized: MaintenanceConfig = serde_json::from_str(s)?;
+        Ok(deserialized)
+    }
+}
+
+/// A daily off-peak window in UTC such as `22:30-04:00`, which may span midnight
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaintenanceWindow {
+    /// Minutes after midnight
+    start: u32,
+    end: u32,
+}
+
+impl MaintenanceWindow {
+    /// Whether the window is open at `minute` after midnight
+    pub fn contains(&self, minute: u32) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+
+    /// The day the occurrence of the window open at `minute` of `day` started on, so a
+    /// window spanning midnight counts as a single occurrence
+    pub fn occurrence(&self, day: u64, minute: u32) -> u64 {
+        if self.start > self.end && minute < self.end {
+            day.saturating_sub(1)
+        } else {
+            day
+        }
+    }
+}
+
+impl FromStr for MaintenanceWindow {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let parse_time = |time: &str| -> Result<u32> {
+            let invalid = || anyhow!("Invalid time {} in window {}, expected HH:MM", time, s);
+            let (hours, minutes) = time.trim().split_once(':').ok_or_else(invalid)?;
+            let hours: u32 = hours.parse().map_err(|_| invalid())?;
+            let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+            if hours > 23 || minutes > 59 {
+                return Err(invalid());
+            }
+            Ok(hours * 60 + minutes)
+        };
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| anyhow!("Invalid maintenance window {}, expected HH:MM-HH:MM", s))?;
+        let window = Self {
+            start: parse_time(start)?,
+            end: parse_time(end)?,
+        };
+        if window.start == window.end {
+            bail!("Maintenance window {} is empty", s);
+        }
+        Ok(window)
+    }
+}
@@ -1,8 +1,10 @@
 // Copyright (c) KanariNetwork
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::validation::ConfigValidator;
 use anyhow::Result;
-use clap::Args;
+use clap::{Args, ValueEnum};
+use rooch_types::rooch_network::{BuiltinChainID, RoochChainID};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use std::time::Duration;
@@ -14,6 +16,68 @@ pub const DEFAULT_CONNECTION_TIMEOUT: u64 = 30; // seconds
 pub const DEFAULT_HEARTBEAT_INTERVAL: u64 = 60; // seconds
 pub const DEFAULT_DISCOVERY_INTERVAL: u64 = 120; // seconds
 
+/// Bootstrap nodes shipped with the binary, used on testnet unless disabled
+pub const DEFAULT_TESTNET_BOOTSTRAP_NODES: &[&str] = &[
+    "boot-1.testnet.kanari.site:6778",
+    "boot-2.testnet.kanari.site:6778",
+];
+
+/// Bootstrap nodes shipped with the binary, used on mainnet unless disabled
+pub const DEFAULT_MAINNET_BOOTSTRAP_NODES: &[&str] = &[
+    "boot-1.mainnet.kanari.site:6778",
+    "boot-2.mainnet.kanari.site:6778",
+];
+
+/// The built-in bootstrap nodes of `chain_id`, none for local, dev and custom networks
+pub fn default_bootstrap_nodes(chain_id: &RoochChainID) -> &'static [&'static str] {
+    match chain_id {
+        RoochChainID::Builtin(BuiltinChainID::Test) => DEFAULT_TESTNET_BOOTSTRAP_NODES,
+        RoochChainID::Builtin(BuiltinChainID::Main) => DEFAULT_MAINNET_BOOTSTRAP_NODES,
+        _ => &[],
+    }
+}
+
+/// Whether `node` is an `address:port` or `hostname:port` bootstrap node
+fn is_valid_bootstrap_node(node: &str) -> bool {
+    if node.parse::<SocketAddr>().is_ok() {
+        return true;
+    }
+    match node.rsplit_once(':') {
+        Some((host, port)) => {
+            port.parse::<u16>().is_ok_and(|port| port > 0)
+                && host.split('.').all(|label| {
+                    !label.is_empty()
+                        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                })
+        }
+        None => false,
+    }
+}
+
+/// The role of a node in the sentry architecture
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeRole {
+    /// Regular node that peers with anyone
+    #[default]
+    Full,
+    /// Validator that only peers with its own sentries (the private peers)
+    Validator,
+    /// Public facing node that shields its private validators and relays their consensus messages
+    Sentry,
+}
+
+impl std::fmt::Display for NodeRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            NodeRole::Full => "full",
+            NodeRole::Validator => "validator",
+            NodeRole::Sentry => "sentry",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Args)]
 pub struct NetworkConfig {
     /// The port for P2P networking
@@ -36,10 +100,15 @@ pub struct NetworkConfig {
     #[clap(skip)]
     pub discovery_interval: Duration,
 
-    /// List of bootstrap nodes (address:port)
+    /// List of bootstrap nodes (address:port or hostname:port), tried before the built-in ones
     #[clap(long, value_delimiter = ',')]
     pub bootstrap_nodes: Vec<String>,
 
+    /// Do not use the bootstrap nodes shipped for the network, only `bootstrap_nodes`
+    #[clap(long)]
+    #[serde(default)]
+    pub no_default_bootnodes: bool,
+
     /// External address for this node (optional)
     #[clap(long)]
     pub external_address: Option<SocketAddr>,
@@ -51,6 +120,26 @@ pub struct NetworkConfig {
     /// Network identifier/chain ID
     #[clap(long, default_value_t = 3)]
     pub network_id: u64,
+
+    /// The node role: full, validator or sentry
+    #[clap(long, value_enum, default_value_t)]
+    pub node_role: NodeRole,
+
+    /// Peer IDs of the private peers: a validator's sentries, or the validators a sentry shields
+    #[clap(long, value_delimiter = ',')]
+    pub private_peers: Vec<String>,
+
+    /// Operator name published with the node identity, e.g. on public endpoints
+    #[clap(long)]
+    pub operator_name: Option<String>,
+
+    /// Operator contact published with the node identity
+    #[clap(long)]
+    pub operator_contact: Option<String>,
+
+    /// Operator website published with the node identity
+    #[clap(long)]
+    pub operator_website: Option<String>,
 }
 
 impl Default for NetworkConfig {
@@ -62,9 +151,15 @@ impl Default for NetworkConfig {
             heartbeat_interval: Duration::from_secs(DEFAULT_HEARTBEAT_INTERVAL),
             discovery_interval: Duration::from_secs(DEFAULT_DISCOVERY_INTERVAL),
             bootstrap_nodes: vec![],
+            no_default_bootnodes: false,
             external_address: None,
             enable_discovery: true,
             network_id: 3, // Default to dev network
+            node_role: NodeRole::Full,
+            private_peers: vec![],
+            operator_name: None,
+            operator_contact: None,
+            operator_website: None,
         }
     }
 }
@@ -84,6 +179,25 @@ impl NetworkConfig {
         self
     }
 
+    pub fn with_no_default_bootnodes(mut self, no_default_bootnodes: bool) -> Self {
+        self.no_default_bootnodes = no_default_bootnodes;
+        self
+    }
+
+    /// The bootstrap nodes to dial on `chain_id`: the configured ones, followed by the
+    /// built-in ones of the network unless they are disabled
+    pub fn effective_bootstrap_nodes(&self, chain_id: &RoochChainID) -> Vec<String> {
+        let mut nodes = self.bootstrap_nodes.clone();
+        if !self.no_default_bootnodes {
+            for node in default_bootstrap_nodes(chain_id) {
+                if !nodes.iter().any(|n| n == node) {
+                    nodes.push(node.to_string());
+                }
+            }
+        }
+        nodes
+    }
+
     pub fn with_max_peers(mut self, max_peers: usize) -> Self {
         self.max_peers = max_peers;
         self
@@ -94,23 +208,92 @@ impl NetworkConfig {
         self
     }
 
+    pub fn with_node_role(mut self, node_role: NodeRole, private_peers: Vec<String>) -> Self {
+        self.node_role = node_role;
+        self.private_peers = private_peers;
+        self
+    }
+
     /// Validate the network configuration
     pub fn validate(&self) -> Result<()> {
-        if self.max_peers == 0 {
-            anyhow::bail!("max_peers must be greater than 0");
-        }
+        let mut validator = ConfigValidator::new();
+        self.validate_into(&mut validator);
+        Ok(validator.into_result()?)
+    }
 
-        if self.p2p_port == 0 {
-            anyhow::bail!("p2p_port must be greater than 0");
-        }
+    /// Collect invalid network values into `validator`
+    pub fn validate_into(&self, validator: &mut ConfigValidator) {
+        validator.check(self.max_peers > 0, "max_peers", "must be greater than 0");
+        validator.check(self.p2p_port > 0, "p2p_port", "must be greater than 0");
 
         // Validate bootstrap nodes format
         for node in &self.bootstrap_nodes {
-            if let Err(_) = node.parse::<SocketAddr>() {
-                anyhow::bail!("Invalid bootstrap node address: {}", node);
+            if !is_valid_bootstrap_node(node) {
+                validator.add(
+                    "bootstrap_nodes",
+                    format!("invalid bootstrap node address: {}", node),
+                );
             }
         }
 
-        Ok(())
+        if let Some(name) = &self.operator_name {
+            validator.check(
+                !name.trim().is_empty(),
+                "operator_name",
+                "must not be empty",
+            );
+        }
+        validator.check(
+            self.operator_name.is_some()
+                || (self.operator_contact.is_none() && self.operator_website.is_none()),
+            "operator_name",
+            "is required when operator_contact or operator_website is set",
+        );
+
+        match self.node_role {
+            NodeRole::Validator | NodeRole::Sentry => validator.check(
+                !self.private_peers.is_empty(),
+                "private_peers",
+                format!("must not be empty for a {} node", self.node_role),
+            ),
+            NodeRole::Full => validator.check(
+                self.private_peers.is_empty(),
+                "private_peers",
+                "only apply to validator and sentry nodes",
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_bootstrap_nodes_are_merged_with_built_in_ones() {
+        let testnet = RoochChainID::Builtin(BuiltinChainID::Test);
+        let config = NetworkConfig::new().with_bootstrap_nodes(vec![
+            "10.0.0.1:6778".to_string(),
+            DEFAULT_TESTNET_BOOTSTRAP_NODES[0].to_string(),
+        ]);
+        assert!(config.validate().is_ok());
+
+        let nodes = config.effective_bootstrap_nodes(&testnet);
+        assert_eq!(nodes[0], "10.0.0.1:6778");
+        assert_eq!(nodes.len(), 1 + DEFAULT_TESTNET_BOOTSTRAP_NODES.len());
+        // No built-in nodes on a local network
+        assert_eq!(
+            config.effective_bootstrap_nodes(&RoochChainID::Builtin(BuiltinChainID::Local)),
+            config.bootstrap_nodes
+        );
+
+        let config = config.with_no_default_bootnodes(true);
+        assert_eq!(
+            config.effective_bootstrap_nodes(&testnet),
+            config.bootstrap_nodes
+        );
+
+        let config = config.with_bootstrap_nodes(vec!["not a node:6778".to_string()]);
+        assert!(config.validate().is_err());
     }
 }
@@ -0,0 +1,108 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, bail};
+use std::collections::BTreeMap;
+
+/// Chain parameters `--param-override` may set, as `key=value`
+pub const PARAM_OVERRIDE_KEYS: &[&str] =
+    &["block_interval_secs", "block_gas_limit", "min_gas_price"];
+
+/// Consensus and fee parameters overridden for local experimentation. Unset ones keep
+/// the chain's defaults.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParamOverrides {
+    /// Seconds between two locally produced blocks
+    pub block_interval_secs: Option<u64>,
+    /// Gas limit of the blocks of the genesis epoch, which bounds the block size
+    pub block_gas_limit: Option<u64>,
+    /// Lowest gas price the mempool admits
+    pub min_gas_price: Option<u64>,
+}
+
+impl ParamOverrides {
+    /// Parse `key=value` entries, rejecting unknown keys, zero values and keys set twice
+    pub fn parse(entries: &[String]) -> Result<Self> {
+        let mut overrides = Self::default();
+        for entry in entries {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("{} is not in the form key=value", entry))?;
+            let value: u64 = value
+                .trim()
+                .parse()
+                .map_err(|_| anyhow::anyhow!("{} has an invalid value {}", entry, value))?;
+            if value == 0 {
+                bail!("{} must be greater than 0", entry);
+            }
+            let slot = match key.trim() {
+                "block_interval_secs" => &mut overrides.block_interval_secs,
+                "block_gas_limit" => &mut overrides.block_gas_limit,
+                "min_gas_price" => &mut overrides.min_gas_price,
+                other => bail!(
+                    "Unknown parameter {}, expected one of {}",
+                    other,
+                    PARAM_OVERRIDE_KEYS.join(", ")
+                ),
+            };
+            if slot.replace(value).is_some() {
+                bail!("Parameter {} is overridden twice", key.trim());
+            }
+        }
+        Ok(overrides)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// The overridden parameters by key, as reported in the node info
+    pub fn entries(&self) -> BTreeMap<String, u64> {
+        [
+            ("block_interval_secs", self.block_interval_secs),
+            ("block_gas_limit", self.block_gas_limit),
+            ("min_gas_price", self.min_gas_price),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key.to_string(), value)))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_param_overrides() {
+        let overrides = ParamOverrides::parse(&[
+            "block_interval_secs=2".to_string(),
+            "min_gas_price = 5".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(overrides.block_interval_secs, Some(2));
+        assert_eq!(overrides.min_gas_price, Some(5));
+        assert_eq!(overrides.block_gas_limit, None);
+        assert_eq!(
+            overrides.entries().into_iter().collect::<Vec<_>>(),
+            vec![
+                ("block_interval_secs".to_string(), 2),
+                ("min_gas_price".to_string(), 5)
+            ]
+        );
+        assert!(ParamOverrides::parse(&[]).unwrap().is_empty());
+
+        for invalid in [
+            "block_interval_secs",
+            "block_interval_secs=0",
+            "block_interval_secs=fast",
+            "epoch_length=10",
+        ] {
+            assert!(ParamOverrides::parse(&[invalid.to_string()]).is_err());
+        }
+        assert!(
+            ParamOverrides::parse(&["min_gas_price=1".to_string(), "min_gas_price=2".to_string()])
+                .is_err()
+        );
+    }
+}
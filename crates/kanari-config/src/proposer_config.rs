@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::Config;
+use crate::validation::ConfigValidator;
 use clap::Parser;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -25,6 +26,17 @@ pub struct ProposerConfig {
     pub interval: Option<u64>,
 }
 
+impl ProposerConfig {
+    /// Collect invalid proposer values into `validator`
+    pub fn validate_into(&self, validator: &mut ConfigValidator) {
+        validator.check(
+            self.interval != Some(0),
+            "interval",
+            "must be greater than 0",
+        );
+    }
+}
+
 impl Config for ProposerConfig {}
 
 impl std::fmt::Display for ProposerConfig {
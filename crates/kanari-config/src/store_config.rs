@@ -5,6 +5,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::BaseConfig;
+use crate::validation::ConfigValidator;
 use anyhow::Result;
 use clap::Parser;
 use moveos_config::DataDirPath;
@@ -158,6 +159,26 @@ impl StoreConfig {
         }
     }
 
+    /// Collect invalid store values into `validator`
+    pub fn validate_into(&self, validator: &mut ConfigValidator) {
+        if let Some(max_open_files) = self.max_open_files {
+            validator.check(
+                max_open_files == -1 || max_open_files > 0,
+                "max_open_files",
+                "must be -1 (unlimited) or greater than 0",
+            );
+        }
+        let non_zero = [
+            ("max_total_wal_size", self.max_total_wal_size),
+            ("max_background_jobs", self.max_background_jobs),
+            ("block_size", self.block_size),
+            ("max_write_buffer_number", self.max_write_buffer_number),
+        ];
+        for (field, value) in non_zero {
+            validator.check(value != Some(0), field, "must be greater than 0");
+        }
+    }
+
     pub fn get_mock_store_dir(data_dir: &DataDirPath) -> PathBuf {
         data_dir
             .path()
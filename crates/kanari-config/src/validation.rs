@@ -0,0 +1,112 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{Display, Formatter};
+
+/// A single invalid configuration value, identified by its field path (e.g. `network.max_peers`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigViolation {
+    pub field: String,
+    pub message: String,
+}
+
+impl Display for ConfigViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// All violations found in a configuration, reported together
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigValidationError {
+    pub violations: Vec<ConfigViolation>,
+}
+
+impl Display for ConfigValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid configuration ({} problem(s)):",
+            self.violations.len()
+        )?;
+        for violation in &self.violations {
+            write!(f, "\n  - {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigValidationError {}
+
+/// Collects violations across config sections instead of failing on the first one
+#[derive(Debug, Default)]
+pub struct ConfigValidator {
+    prefix: Vec<String>,
+    violations: Vec<ConfigViolation>,
+}
+
+impl ConfigValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate a nested section, prefixing its field paths with `section`
+    pub fn section<F>(&mut self, section: &str, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.prefix.push(section.to_string());
+        f(self);
+        self.prefix.pop();
+    }
+
+    /// Record a violation for `field` unless `condition` holds
+    pub fn check(&mut self, condition: bool, field: &str, message: impl Into<String>) {
+        if !condition {
+            self.add(field, message);
+        }
+    }
+
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        let mut path = self.prefix.clone();
+        path.push(field.to_string());
+        self.violations.push(ConfigViolation {
+            field: path.join("."),
+            message: message.into(),
+        });
+    }
+
+    pub fn violations(&self) -> &[ConfigViolation] {
+        &self.violations
+    }
+
+    pub fn into_result(self) -> Result<(), ConfigValidationError> {
+        if self.violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigValidationError {
+                violations: self.violations,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_violations_are_aggregated_with_field_paths() {
+        let mut validator = ConfigValidator::new();
+        validator.check(true, "port", "never reported");
+        validator.check(false, "port", "must not be zero");
+        validator.section("network", |v| {
+            v.check(false, "max_peers", "must be greater than 0");
+        });
+
+        let err = validator.into_result().unwrap_err();
+        let fields: Vec<&str> = err.violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(fields, vec!["port", "network.max_peers"]);
+        assert!(err.to_string().contains("2 problem(s)"));
+    }
+}
@@ -0,0 +1,509 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::state_view::StateView;
+use crate::{RoochDB, charged_gas};
+use anyhow::{Result, anyhow, bail};
+use kanari_types::dao::{DaoAction, DaoProposal, DaoProposalStatus, DaoTransaction, DaoVote};
+use kanari_types::event::{BlockEvent, transaction_events};
+use kanari_types::kari_coin::KARI;
+use kanari_types::receipt::{ExecutionStatus, TransactionOutput};
+use kanari_types::system_transaction::SystemTransaction;
+use kanari_types::transaction::SignedTransaction;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::effects::Op;
+use move_core_types::u256::U256;
+use moveos_types::move_std::string::MoveString;
+use moveos_types::moveos_std::account::Account;
+use moveos_types::moveos_std::object::ObjectMeta;
+use moveos_types::state::{MoveStructType, ObjectChange, StateChangeSet};
+use rooch_types::bitcoin::genesis::MultisignAccountConfig;
+use rooch_types::framework::account_coin_store::AccountCoinStoreModule;
+use rooch_types::framework::coin_store::CoinStore;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The state after the transactions of a block and what each of them did
+#[derive(Clone, Debug)]
+pub struct BlockExecution {
+    /// The root object after the last transaction, whose state root the block records
+    pub root: ObjectMeta,
+    /// One output per transaction, in block order
+    pub outputs: Vec<TransactionOutput>,
+    /// The events of the transactions that succeeded, in block order
+    pub events: Vec<BlockEvent>,
+    /// What the DAO transactions of the block did, saved along with it
+    pub dao: DaoChanges,
+}
+
+/// The DAO records a block changed
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DaoChanges {
+    /// The proposals the block made or executed, as the block left them
+    pub proposals: Vec<DaoProposal>,
+    /// The votes cast in the block, in block order
+    pub votes: Vec<DaoVote>,
+}
+
+/// An account whose sequence number the block advanced, with the object it was read from
+struct AccountEntry {
+    existing: Option<ObjectMeta>,
+    sequence_number: u64,
+    changed: bool,
+}
+
+/// A KARI coin store touched by the block, with the object it was read from
+struct CoinStoreEntry {
+    existing: Option<ObjectMeta>,
+    frozen: bool,
+    balance: U256,
+    changed: bool,
+}
+
+/// The DAO proposals and votes the transactions of a block act on. They are read as the
+/// blocks before it left them, so re-executing a saved block decides the same way.
+struct DaoLedger<'a> {
+    db: &'a RoochDB,
+    config: &'a MultisignAccountConfig,
+    block_number: u128,
+    next_id: u64,
+    proposals: BTreeMap<u64, Option<DaoProposal>>,
+    votes: BTreeMap<u64, Vec<DaoVote>>,
+    changed: BTreeSet<u64>,
+    new_votes: Vec<DaoVote>,
+}
+
+/// Apply the transactions of block `block_number` to the state under `pre_state`. A user
+/// transaction must carry the sender's current sequence number, or it fails without
+/// effects. It advances the sender's sequence number and pays its fee in KARI, then
+/// moves its amount to the recipient; without the balance for both it fails, still paying
+/// what it can of the fee. A DAO transaction moves no value, its operation is applied
+/// once the fee is paid. A reward distribution mints its payments. The touched accounts
+/// and coin stores are written to the state store; its nodes are content addressed, so
+/// executing a block that is never committed leaves the committed state untouched.
+pub fn execute_block(
+    db: &RoochDB,
+    block_number: u128,
+    pre_state: ObjectMeta,
+    transactions: &[SignedTransaction],
+    dao_config: &MultisignAccountConfig,
+) -> Result<BlockExecution> {
+    let store = &db.moveos_store;
+    let state = StateView::new(store, Some(pre_state.clone()));
+    let mut accounts: BTreeMap<AccountAddress, AccountEntry> = BTreeMap::new();
+    let mut coin_stores: BTreeMap<AccountAddress, CoinStoreEntry> = BTreeMap::new();
+    let mut dao = DaoLedger {
+        db,
+        config: dao_config,
+        block_number,
+        next_id: db.dao_proposal_count_before(block_number)?,
+        proposals: BTreeMap::new(),
+        votes: BTreeMap::new(),
+        changed: BTreeSet::new(),
+        new_votes: vec![],
+    };
+    let mut outputs = Vec::with_capacity(transactions.len());
+    let mut succeeded = vec![];
+    for tx in transactions {
+        let output = if tx.is_system() {
+            if let Some(SystemTransaction::RewardDistribution { payments, .. }) =
+                SystemTransaction::from_transaction(tx)?
+            {
+                for payment in payments {
+                    let entry = coin_store(&state, &mut coin_stores, payment.recipient)?;
+                    entry.credit(U256::from(payment.amount))?;
+                }
+            }
+            TransactionOutput {
+                status: ExecutionStatus::Success,
+                gas_used: 0,
+            }
+        } else {
+            let sender = account(&state, &mut accounts, tx.tx.sender)?;
+            // A stale or gapped sequence number would replay or reorder the sender's
+            // transactions, so it fails without effects or fee
+            if tx.tx.sequence_number != sender.sequence_number {
+                TransactionOutput {
+                    status: ExecutionStatus::Failure {
+                        reason: format!(
+                            "sequence number {} does not match the sender's {}",
+                            tx.tx.sequence_number, sender.sequence_number
+                        ),
+                    },
+                    gas_used: 0,
+                }
+            } else {
+                sender.sequence_number = tx.tx.sequence_number + 1;
+                sender.changed = true;
+                match DaoTransaction::from_transaction(tx) {
+                    Ok(None) => execute_transfer(&state, &mut coin_stores, tx, None)?,
+                    Ok(Some(operation)) => {
+                        execute_dao_transaction(&state, &mut coin_stores, &mut dao, tx, operation)?
+                    }
+                    Err(e) => execute_transfer(
+                        &state,
+                        &mut coin_stores,
+                        tx,
+                        Some(format!("invalid DAO operation: {}", e)),
+                    )?,
+                }
+            }
+        };
+        if output.status.is_success() {
+            succeeded.push(tx.clone());
+        }
+        outputs.push(output);
+    }
+
+    let mut change_set = StateChangeSet::new(pre_state.state_root(), pre_state.size);
+    let mut write = |address: AccountAddress,
+                     existing: Option<ObjectMeta>,
+                     new_meta: fn(AccountAddress) -> ObjectMeta,
+                     value: Vec<u8>| {
+        let (metadata, op) = match existing {
+            Some(metadata) => (metadata, Op::Modify(value)),
+            None => {
+                let mut metadata = new_meta(address);
+                metadata.owner = address;
+                change_set.global_size += 1;
+                (metadata, Op::New(value))
+            }
+        };
+        change_set
+            .changes
+            .insert(metadata.id.field_key(), ObjectChange::new(metadata, op));
+    };
+    for (address, entry) in accounts.into_iter().filter(|(_, entry)| entry.changed) {
+        let value = bcs::to_bytes(&Account {
+            sequence_number: entry.sequence_number,
+        })?;
+        write(address, entry.existing, account_meta, value);
+    }
+    let coin_type = MoveString::from(KARI::struct_tag().to_canonical_string());
+    for (address, entry) in coin_stores.into_iter().filter(|(_, entry)| entry.changed) {
+        let value = bcs::to_bytes(&CoinStore::new(
+            coin_type.clone(),
+            entry.balance,
+            entry.frozen,
+        ))?;
+        write(address, entry.existing, coin_store_meta, value);
+    }
+    store.get_state_store().apply_change_set(&mut change_set)?;
+
+    Ok(BlockExecution {
+        root: ObjectMeta::root_metadata(change_set.state_root, change_set.global_size),
+        outputs,
+        events: transaction_events(&succeeded),
+        dao: dao.into_changes(),
+    })
+}
+
+/// Pay the fee of a DAO transaction, then apply its operation. A rejected operation
+/// fails the transaction, which keeps its fee.
+fn execute_dao_transaction(
+    state: &StateView,
+    coin_stores: &mut BTreeMap<AccountAddress, CoinStoreEntry>,
+    dao: &mut DaoLedger,
+    tx: &SignedTransaction,
+    operation: DaoTransaction,
+) -> Result<TransactionOutput> {
+    let rejection = if tx.tx.recipient.is_some() || tx.tx.amount != 0 {
+        Some("a DAO transaction moves no value".to_string())
+    } else if !dao.config.participant_public_keys.contains(&tx.public_key) {
+        Some("the sender is not a participant of the Kanari DAO".to_string())
+    } else {
+        None
+    };
+    let mut output = execute_transfer(state, coin_stores, tx, rejection)?;
+    if !output.status.is_success() {
+        return Ok(output);
+    }
+    let applied = match operation {
+        DaoTransaction::Propose {
+            title,
+            description,
+            action,
+            voting_period,
+        } => dao.propose(tx, title, description, action, voting_period),
+        DaoTransaction::Vote {
+            proposal_id,
+            approve,
+        } => dao.vote(tx, proposal_id, approve),
+        DaoTransaction::Execute { proposal_id } => {
+            execute_dao_proposal(state, coin_stores, dao, proposal_id)
+        }
+    };
+    if let Err(e) = applied {
+        output.status = ExecutionStatus::Failure {
+            reason: e.to_string(),
+        };
+    }
+    Ok(output)
+}
+
+/// Carry out the action of a passed proposal, paying a transfer from the DAO treasury
+fn execute_dao_proposal(
+    state: &StateView,
+    coin_stores: &mut BTreeMap<AccountAddress, CoinStoreEntry>,
+    dao: &mut DaoLedger,
+    proposal_id: u64,
+) -> Result<()> {
+    let action = dao.check_execution(proposal_id)?;
+    if let DaoAction::Transfer { recipient, amount } = action {
+        let treasury =
+            AccountAddress::from(dao.config.multisign_bitcoin_address.to_rooch_address());
+        let amount = U256::from(amount);
+        let treasury_store = coin_store(state, coin_stores, treasury)?;
+        if treasury_store.frozen || treasury_store.balance < amount {
+            bail!(
+                "the DAO treasury cannot pay {} from its balance {}",
+                amount,
+                treasury_store.balance
+            );
+        }
+        treasury_store.debit(amount)?;
+        coin_store(state, coin_stores, recipient)?.credit(amount)?;
+    }
+    dao.mark_executed(proposal_id)
+}
+
+/// Run the transfer of a transaction, which fails with `rejection` if one is given. A
+/// failed transfer pays what it can of its fee and moves nothing.
+fn execute_transfer(
+    state: &StateView,
+    coin_stores: &mut BTreeMap<AccountAddress, CoinStoreEntry>,
+    tx: &SignedTransaction,
+    rejection: Option<String>,
+) -> Result<TransactionOutput> {
+    let gas_used = charged_gas(tx);
+    let fee = U256::from(gas_used as u128 * tx.tx.gas_price as u128);
+    let sender = coin_store(state, coin_stores, tx.tx.sender)?;
+    // Without a recipient the amount stays with the sender
+    let amount = match tx.tx.recipient {
+        Some(_) => U256::from(tx.tx.amount),
+        None => U256::zero(),
+    };
+    let balance = sender.balance;
+    let failure = if rejection.is_some() {
+        rejection
+    } else if sender.frozen {
+        Some("the sender's KARI coin store is frozen".to_string())
+    } else if balance < fee || balance - fee < amount {
+        Some(format!(
+            "insufficient balance {} for the fee {} and amount {}",
+            balance, fee, amount
+        ))
+    } else {
+        None
+    };
+    if let Some(reason) = failure {
+        sender.debit(balance.min(fee))?;
+        return Ok(TransactionOutput {
+            status: ExecutionStatus::Failure { reason },
+            gas_used,
+        });
+    }
+    sender.debit(fee + amount)?;
+    if let Some(recipient) = tx.tx.recipient {
+        coin_store(state, coin_stores, recipient)?.credit(amount)?;
+    }
+    Ok(TransactionOutput {
+        status: ExecutionStatus::Success,
+        gas_used,
+    })
+}
+
+fn account_meta(address: AccountAddress) -> ObjectMeta {
+    ObjectMeta::genesis_meta(Account::account_object_id(address), Account::type_tag())
+}
+
+fn coin_store_meta(address: AccountAddress) -> ObjectMeta {
+    ObjectMeta::genesis_meta(
+        AccountCoinStoreModule::account_coin_store_id(address, KARI::struct_tag()),
+        CoinStore::type_tag(),
+    )
+}
+
+/// The account of `address` as the block left it so far
+fn account<'a>(
+    state: &StateView,
+    accounts: &'a mut BTreeMap<AccountAddress, AccountEntry>,
+    address: AccountAddress,
+) -> Result<&'a mut AccountEntry> {
+    if !accounts.contains_key(&address) {
+        let entry = match state.get_object(Account::account_object_id(address))? {
+            Some(object) => {
+                let account = bcs::from_bytes::<Account>(&object.value)
+                    .map_err(|e| anyhow!("Invalid account object of {}: {}", address, e))?;
+                AccountEntry {
+                    existing: Some(object.metadata),
+                    sequence_number: account.sequence_number,
+                    changed: false,
+                }
+            }
+            None => AccountEntry {
+                existing: None,
+                sequence_number: 0,
+                changed: false,
+            },
+        };
+        accounts.insert(address, entry);
+    }
+    Ok(accounts
+        .get_mut(&address)
+        .expect("The account was just inserted"))
+}
+
+/// The KARI coin store of `address` as the block left it so far
+fn coin_store<'a>(
+    state: &StateView,
+    coin_stores: &'a mut BTreeMap<AccountAddress, CoinStoreEntry>,
+    address: AccountAddress,
+) -> Result<&'a mut CoinStoreEntry> {
+    if !coin_stores.contains_key(&address) {
+        let id = AccountCoinStoreModule::account_coin_store_id(address, KARI::struct_tag());
+        let entry = match state.get_object(id)? {
+            Some(object) => {
+                let coin_store = bcs::from_bytes::<CoinStore>(&object.value)
+                    .map_err(|e| anyhow!("Invalid KARI coin store of {}: {}", address, e))?;
+                CoinStoreEntry {
+                    existing: Some(object.metadata),
+                    frozen: coin_store.is_frozen(),
+                    balance: coin_store.balance(),
+                    changed: false,
+                }
+            }
+            None => CoinStoreEntry {
+                existing: None,
+                frozen: false,
+                balance: U256::zero(),
+                changed: false,
+            },
+        };
+        coin_stores.insert(address, entry);
+    }
+    Ok(coin_stores
+        .get_mut(&address)
+        .expect("The coin store was just inserted"))
+}
+
+impl DaoLedger<'_> {
+    /// The proposal with this id, including the ones made earlier in the block
+    fn proposal(&mut self, id: u64) -> Result<DaoProposal> {
+        if !self.proposals.contains_key(&id) {
+            let proposal = self.db.dao_proposal_before(id, self.block_number)?;
+            self.proposals.insert(id, proposal);
+        }
+        self.proposals[&id]
+            .clone()
+            .ok_or_else(|| anyhow!("DAO proposal {} not found", id))
+    }
+
+    fn votes(&mut self, id: u64) -> Result<&mut Vec<DaoVote>> {
+        if !self.votes.contains_key(&id) {
+            let votes = self.db.dao_votes_before(id, self.block_number)?;
+            self.votes.insert(id, votes);
+        }
+        Ok(self
+            .votes
+            .get_mut(&id)
+            .expect("The votes were just inserted"))
+    }
+
+    fn propose(
+        &mut self,
+        tx: &SignedTransaction,
+        title: String,
+        description: String,
+        action: DaoAction,
+        voting_period: u128,
+    ) -> Result<()> {
+        if voting_period == 0 {
+            bail!("a DAO proposal needs a voting period");
+        }
+        let proposal = DaoProposal {
+            id: self.next_id,
+            proposer: tx.public_key.clone(),
+            title,
+            description,
+            action,
+            created_at_block: self.block_number,
+            voting_end_block: self.block_number.saturating_add(voting_period),
+            threshold: self.config.threshold as u64,
+            executed_at_block: None,
+        };
+        self.next_id += 1;
+        self.changed.insert(proposal.id);
+        self.proposals.insert(proposal.id, Some(proposal));
+        Ok(())
+    }
+
+    fn vote(&mut self, tx: &SignedTransaction, proposal_id: u64, approve: bool) -> Result<()> {
+        let proposal = self.proposal(proposal_id)?;
+        let vote = DaoVote {
+            proposal_id,
+            voter: tx.public_key.clone(),
+            approve,
+            block_number: self.block_number,
+        };
+        let votes = self.votes(proposal_id)?;
+        proposal.check_vote(votes, &vote)?;
+        votes.push(vote.clone());
+        self.new_votes.push(vote);
+        Ok(())
+    }
+
+    /// The action of a proposal that passed and may be executed now
+    fn check_execution(&mut self, proposal_id: u64) -> Result<DaoAction> {
+        let proposal = self.proposal(proposal_id)?;
+        let participants = self.config.participant_public_keys.len();
+        let block_number = self.block_number;
+        let status = proposal.status(self.votes(proposal_id)?, participants, block_number);
+        if status != DaoProposalStatus::Passed {
+            bail!(
+                "DAO proposal {} is {:?}, only a passed proposal is executed",
+                proposal_id,
+                status
+            );
+        }
+        Ok(proposal.action)
+    }
+
+    fn mark_executed(&mut self, proposal_id: u64) -> Result<()> {
+        let mut proposal = self.proposal(proposal_id)?;
+        proposal.executed_at_block = Some(self.block_number);
+        self.changed.insert(proposal_id);
+        self.proposals.insert(proposal_id, Some(proposal));
+        Ok(())
+    }
+
+    fn into_changes(mut self) -> DaoChanges {
+        DaoChanges {
+            proposals: self
+                .changed
+                .iter()
+                .filter_map(|id| self.proposals.remove(id).flatten())
+                .collect(),
+            votes: self.new_votes,
+        }
+    }
+}
+
+impl CoinStoreEntry {
+    fn credit(&mut self, amount: U256) -> Result<()> {
+        self.balance = self
+            .balance
+            .checked_add(amount)
+            .ok_or_else(|| anyhow!("KARI balance overflow"))?;
+        self.changed |= amount != U256::zero();
+        Ok(())
+    }
+
+    fn debit(&mut self, amount: U256) -> Result<()> {
+        self.balance = self
+            .balance
+            .checked_sub(amount)
+            .ok_or_else(|| anyhow!("KARI balance underflow"))?;
+        self.changed |= amount != U256::zero();
+        Ok(())
+    }
+}
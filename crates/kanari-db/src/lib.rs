@@ -2,9 +2,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use kanari_config::store_config::StoreConfig;
-use kanari_types::block::Block;
+use kanari_types::block::{Block, GENESIS_BLOCK_NUMBER};
+use kanari_types::bloom::EventBloom;
+use kanari_types::dao::{DaoProposal, DaoVote};
+use kanari_types::epoch::{
+    ConsensusParams, EpochSnapshot, ValidatorSetChange, epoch_of, is_epoch_boundary,
+};
+use kanari_types::event::{BlockEvent, events_bloom};
+use kanari_types::evidence::EvidenceRecord;
+use kanari_types::fee::FeeSummary;
+use kanari_types::genesis_config::G_LOCAL_CONFIG;
+use kanari_types::receipt::{TransactionOutput, TransactionReceipt, block_receipts};
+use kanari_types::reward::{RewardPayment, distribute_rewards};
+use kanari_types::system_transaction::{CHECKPOINT_INTERVAL, SystemTransaction};
+use kanari_types::transaction::SignedTransaction;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::u256::U256;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use accumulator::accumulator_info::AccumulatorInfo;
 use anyhow::{Error, Result, anyhow};
@@ -18,6 +34,7 @@ use moveos_store::{
 };
 use moveos_types::access_path::AccessPath;
 use moveos_types::h256::H256;
+use moveos_types::moveos_std::account::Account;
 use moveos_types::moveos_std::object::ObjectMeta;
 use moveos_types::state::StateChangeSetExt;
 use moveos_types::state_resolver::{RootObjectResolver, StateReader};
@@ -38,8 +55,86 @@ use rooch_store::{
     TRANSACTION_COLUMN_FAMILY_NAME, TX_SEQUENCE_INFO_MAPPING_COLUMN_FAMILY_NAME,
 };
 
-// Define a new column family for Kanari blocks
+/// Checkpoint blocks searched back for the finalized block
+pub const MAX_CHECKPOINT_LOOKBACK: usize = 10;
+
+// Define a new column family for Kanari blocks, keyed by block number, with the
+// latest block number under its own key
 pub const KANARI_BLOCK_COLUMN_FAMILY_NAME: &str = "kanari_blocks";
+// Events emitted in each block, keyed by block number
+pub const KANARI_BLOCK_EVENTS_COLUMN_FAMILY_NAME: &str = "kanari_block_events";
+// Bloom filter over the event addresses and types of each block, keyed by block number
+pub const KANARI_BLOCK_BLOOM_COLUMN_FAMILY_NAME: &str = "kanari_block_blooms";
+// Production time of each block in seconds since the Unix epoch, keyed by block number
+pub const KANARI_BLOCK_TIMESTAMP_COLUMN_FAMILY_NAME: &str = "kanari_block_timestamps";
+// Block number of each block, keyed by block hash
+pub const KANARI_BLOCK_HASH_INDEX_COLUMN_FAMILY_NAME: &str = "kanari_block_hash_index";
+// Transactions included in each block, keyed by block number
+pub const KANARI_BLOCK_TRANSACTIONS_COLUMN_FAMILY_NAME: &str = "kanari_block_transactions";
+// Transactions sent or received by each account, keyed by address and position
+pub const KANARI_ACCOUNT_TRANSACTIONS_COLUMN_FAMILY_NAME: &str = "kanari_account_transactions";
+// Number of indexed transactions of each account, keyed by address
+pub const KANARI_ACCOUNT_TRANSACTION_COUNT_COLUMN_FAMILY_NAME: &str =
+    "kanari_account_transaction_counts";
+// Validator set and consensus parameters of each epoch, keyed by epoch
+pub const KANARI_EPOCH_COLUMN_FAMILY_NAME: &str = "kanari_epochs";
+// Validator set changes queued for the next epoch boundary, under a single key
+pub const KANARI_PENDING_VALIDATOR_CHANGES_COLUMN_FAMILY_NAME: &str =
+    "kanari_pending_validator_changes";
+
+// Compressed public key of the proposer of each externally submitted block, keyed by
+// block number
+pub const KANARI_BLOCK_PROPOSER_COLUMN_FAMILY_NAME: &str = "kanari_block_proposers";
+// Staking rewards paid to an account for an epoch, keyed by address followed by epoch
+pub const KANARI_REWARD_COLUMN_FAMILY_NAME: &str = "kanari_rewards";
+
+// Block number and position of each included transaction, keyed by transaction hash
+pub const KANARI_TRANSACTION_INDEX_COLUMN_FAMILY_NAME: &str = "kanari_transaction_index";
+// Execution receipt of each included transaction, keyed by transaction hash
+pub const KANARI_TRANSACTION_RECEIPT_COLUMN_FAMILY_NAME: &str = "kanari_transaction_receipts";
+// Verified double sign evidence, under a single key
+pub const KANARI_EVIDENCE_COLUMN_FAMILY_NAME: &str = "kanari_evidence";
+// Proposals of the Kanari DAO keyed by id, with their count under its own key
+pub const KANARI_DAO_PROPOSAL_COLUMN_FAMILY_NAME: &str = "kanari_dao_proposals";
+// Votes cast on each DAO proposal, keyed by proposal id
+pub const KANARI_DAO_VOTE_COLUMN_FAMILY_NAME: &str = "kanari_dao_votes";
+// Progress of storage maintenance, such as the receipts pruned so far
+pub const KANARI_MAINTENANCE_COLUMN_FAMILY_NAME: &str = "kanari_maintenance";
+// State root and global size after each block, keyed by block number. The state the
+// genesis block was executed on is under the number before it.
+pub const KANARI_STATE_ROOT_COLUMN_FAMILY_NAME: &str = "kanari_state_roots";
+
+/// Column families of the Kanari-specific data
+pub const KANARI_COLUMN_FAMILIES: &[&str] = &[
+    KANARI_BLOCK_COLUMN_FAMILY_NAME,
+    KANARI_BLOCK_EVENTS_COLUMN_FAMILY_NAME,
+    KANARI_BLOCK_BLOOM_COLUMN_FAMILY_NAME,
+    KANARI_BLOCK_TIMESTAMP_COLUMN_FAMILY_NAME,
+    KANARI_BLOCK_HASH_INDEX_COLUMN_FAMILY_NAME,
+    KANARI_BLOCK_TRANSACTIONS_COLUMN_FAMILY_NAME,
+    KANARI_ACCOUNT_TRANSACTIONS_COLUMN_FAMILY_NAME,
+    KANARI_ACCOUNT_TRANSACTION_COUNT_COLUMN_FAMILY_NAME,
+    KANARI_EPOCH_COLUMN_FAMILY_NAME,
+    KANARI_PENDING_VALIDATOR_CHANGES_COLUMN_FAMILY_NAME,
+    KANARI_BLOCK_PROPOSER_COLUMN_FAMILY_NAME,
+    KANARI_REWARD_COLUMN_FAMILY_NAME,
+    KANARI_EVIDENCE_COLUMN_FAMILY_NAME,
+    KANARI_TRANSACTION_INDEX_COLUMN_FAMILY_NAME,
+    KANARI_DAO_PROPOSAL_COLUMN_FAMILY_NAME,
+    KANARI_DAO_VOTE_COLUMN_FAMILY_NAME,
+    KANARI_MAINTENANCE_COLUMN_FAMILY_NAME,
+    KANARI_TRANSACTION_RECEIPT_COLUMN_FAMILY_NAME,
+    KANARI_STATE_ROOT_COLUMN_FAMILY_NAME,
+];
+
+const LATEST_BLOCK_NUMBER_KEY: &[u8] = b"latest";
+const PENDING_VALIDATOR_CHANGES_KEY: &[u8] = b"pending";
+const EVIDENCE_RECORDS_KEY: &[u8] = b"records";
+const DAO_PROPOSAL_COUNT_KEY: &[u8] = b"count";
+const PRUNED_RECEIPTS_KEY: &[u8] = b"pruned_receipts";
+/// Blocks whose receipts are deleted in one write batch
+const PRUNE_BATCH_BLOCKS: u128 = 1000;
+use rooch_types::bitcoin::genesis::MultisignAccountConfig;
 use rooch_types::indexer::field::{
     IndexerFieldChanges, collect_revert_field_change_ids, handle_revert_field_change,
 };
@@ -50,6 +145,138 @@ use rooch_types::indexer::state::{
 use rooch_types::sequencer::SequencerInfo;
 use tracing::{error, info, warn};
 
+pub mod execution;
+pub mod maintenance;
+pub mod state_diff;
+pub mod state_proof;
+pub mod state_view;
+
+use execution::{BlockExecution, DaoChanges};
+use state_view::StateView;
+
+fn account_transaction_key(account: &AccountAddress, position: u64) -> Vec<u8> {
+    let mut key = account.to_vec();
+    key.extend(position.to_be_bytes());
+    key
+}
+
+fn reward_key(account: &AccountAddress, epoch: u64) -> Vec<u8> {
+    let mut key = account.to_vec();
+    key.extend(epoch.to_be_bytes());
+    key
+}
+
+/// Entries to write atomically across column families, with the column family of each
+/// entry in order as `write_batch_across_cfs` takes them
+struct CfWriteBatch {
+    write_batch: WriteBatch,
+    cf_names: Vec<&'static str>,
+}
+
+impl CfWriteBatch {
+    fn new() -> Self {
+        Self {
+            write_batch: WriteBatch::new(),
+            cf_names: vec![],
+        }
+    }
+
+    fn put(&mut self, cf_name: &'static str, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
+        self.write_batch.put(key, value)?;
+        self.cf_names.push(cf_name);
+        Ok(())
+    }
+}
+
+/// A block executed by this node with everything saved along with it
+pub struct ExecutedBlock<'a> {
+    pub block: &'a Block,
+    pub timestamp: u64,
+    pub transactions: &'a [SignedTransaction],
+    pub outputs: &'a [TransactionOutput],
+    pub events: &'a [BlockEvent],
+    /// The root object the block left
+    pub root: &'a ObjectMeta,
+    /// The public key that signed the block, if any
+    pub proposer: Option<&'a [u8]>,
+    /// The validator set the block starts, at an epoch boundary
+    pub epoch_snapshot: Option<&'a EpochSnapshot>,
+    /// The DAO proposals and votes the block changed
+    pub dao: &'a DaoChanges,
+}
+
+/// The gas a transaction is charged: its intrinsic gas, up to its gas limit. The rest
+/// of its maximum fee is refunded. System transactions are free.
+pub fn charged_gas(tx: &SignedTransaction) -> u64 {
+    if tx.is_system() {
+        0
+    } else {
+        tx.tx.intrinsic_gas().min(tx.tx.gas_limit)
+    }
+}
+
+/// The events of a block together with their bloom filter
+fn put_block_events(
+    batch: &mut CfWriteBatch,
+    block_number: u128,
+    events: &[BlockEvent],
+) -> Result<()> {
+    let block_key = block_number.to_be_bytes().to_vec();
+    batch.put(
+        KANARI_BLOCK_EVENTS_COLUMN_FAMILY_NAME,
+        block_key.clone(),
+        bcs::to_bytes(events)?,
+    )?;
+    batch.put(
+        KANARI_BLOCK_BLOOM_COLUMN_FAMILY_NAME,
+        block_key,
+        events_bloom(events).as_bytes().to_vec(),
+    )
+}
+
+fn put_block_receipts(
+    batch: &mut CfWriteBatch,
+    block_number: u128,
+    transactions: &[SignedTransaction],
+    outputs: &[TransactionOutput],
+    events: &[BlockEvent],
+) -> Result<()> {
+    for receipt in block_receipts(block_number, transactions, outputs, events) {
+        batch.put(
+            KANARI_TRANSACTION_RECEIPT_COLUMN_FAMILY_NAME,
+            receipt.tx_hash.as_bytes().to_vec(),
+            bcs::to_bytes(&receipt)?,
+        )?;
+    }
+    Ok(())
+}
+
+fn put_block_proposer(
+    batch: &mut CfWriteBatch,
+    block_number: u128,
+    public_key: &[u8],
+) -> Result<()> {
+    batch.put(
+        KANARI_BLOCK_PROPOSER_COLUMN_FAMILY_NAME,
+        block_number.to_be_bytes().to_vec(),
+        public_key.to_vec(),
+    )
+}
+
+/// The snapshot of a new epoch, clearing the queued changes it applied
+fn put_epoch_start(batch: &mut CfWriteBatch, snapshot: &EpochSnapshot) -> Result<()> {
+    batch.put(
+        KANARI_EPOCH_COLUMN_FAMILY_NAME,
+        snapshot.epoch.to_be_bytes().to_vec(),
+        bcs::to_bytes(snapshot)?,
+    )?;
+    batch.put(
+        KANARI_PENDING_VALIDATOR_CHANGES_COLUMN_FAMILY_NAME,
+        PENDING_VALIDATOR_CHANGES_KEY.to_vec(),
+        bcs::to_bytes(&Vec::<ValidatorSetChange>::new())?,
+    )
+}
+
 #[derive(Clone)]
 pub struct RoochDB {
     pub moveos_store: MoveOSStore,
@@ -90,8 +317,7 @@ impl RoochDB {
         let store_dir = config.get_store_dir();
         let mut column_families = moveos_store::StoreMeta::get_column_family_names().to_vec();
         column_families.append(&mut rooch_store::StoreMeta::get_column_family_names().to_vec());
-        // Add Kanari-specific column families
-        column_families.push(KANARI_BLOCK_COLUMN_FAMILY_NAME);
+        column_families.extend_from_slice(KANARI_COLUMN_FAMILIES);
 
         //ensure no duplicate column families
         {
@@ -117,19 +343,11 @@ impl RoochDB {
         Self::init(config, &registry)
     }
 
-    /// Save a block to the database
-    pub fn save_block(&self, block: &Block) -> Result<()> {
-        let block_bytes = bcs::to_bytes(block)?;
-        let block_key = block.block_number.to_be_bytes();
-
-        // Store the block using the rooch_store instance with proper column family
-        let mut write_batch = WriteBatch::new();
-        write_batch.put(block_key.to_vec(), block_bytes)?;
-
-        // Write to database using the Kanari blocks column family
-        self.rooch_store
-            .store_instance
-            .write_batch(KANARI_BLOCK_COLUMN_FAMILY_NAME, write_batch)?;
+    /// Save a block to the database together with its production time and hash index
+    pub fn save_block(&self, block: &Block, timestamp: u64) -> Result<()> {
+        let mut batch = CfWriteBatch::new();
+        self.put_block(&mut batch, block, timestamp)?;
+        self.write(batch)?;
 
         info!(
             "Successfully saved block #{} to database",
@@ -138,6 +356,75 @@ impl RoochDB {
         Ok(())
     }
 
+    /// Save a block executed by this node with its events, transactions, receipts,
+    /// proposer and epoch change, and make the state it left the latest one, all in a
+    /// single write so a crash never leaves a block without its state
+    pub fn save_executed_block(&self, executed: &ExecutedBlock) -> Result<()> {
+        let block_number = executed.block.block_number;
+        let mut batch = CfWriteBatch::new();
+        self.put_block(&mut batch, executed.block, executed.timestamp)?;
+        put_block_events(&mut batch, block_number, executed.events)?;
+        self.put_block_transactions(&mut batch, block_number, executed.transactions)?;
+        put_block_receipts(
+            &mut batch,
+            block_number,
+            executed.transactions,
+            executed.outputs,
+            executed.events,
+        )?;
+        self.put_block_state(&mut batch, block_number, executed.root)?;
+        if let Some(public_key) = executed.proposer {
+            put_block_proposer(&mut batch, block_number, public_key)?;
+        }
+        if let Some(snapshot) = executed.epoch_snapshot {
+            put_epoch_start(&mut batch, snapshot)?;
+        }
+        self.put_dao_changes(&mut batch, executed.dao)?;
+        self.write(batch)?;
+
+        info!("Successfully saved block #{} to database", block_number);
+        Ok(())
+    }
+
+    fn write(&self, batch: CfWriteBatch) -> Result<()> {
+        self.rooch_store.store_instance.write_batch_across_cfs(
+            batch.cf_names,
+            batch.write_batch,
+            true,
+        )?;
+        Ok(())
+    }
+
+    /// The block, its timestamp, its hash index and the latest block number
+    fn put_block(&self, batch: &mut CfWriteBatch, block: &Block, timestamp: u64) -> Result<()> {
+        let block_key = block.block_number.to_be_bytes().to_vec();
+        if self
+            .get_latest_block_number()?
+            .is_none_or(|latest| latest < block.block_number)
+        {
+            batch.put(
+                KANARI_BLOCK_COLUMN_FAMILY_NAME,
+                LATEST_BLOCK_NUMBER_KEY.to_vec(),
+                block_key.clone(),
+            )?;
+        }
+        batch.put(
+            KANARI_BLOCK_COLUMN_FAMILY_NAME,
+            block_key.clone(),
+            bcs::to_bytes(block)?,
+        )?;
+        batch.put(
+            KANARI_BLOCK_TIMESTAMP_COLUMN_FAMILY_NAME,
+            block_key.clone(),
+            timestamp.to_be_bytes().to_vec(),
+        )?;
+        batch.put(
+            KANARI_BLOCK_HASH_INDEX_COLUMN_FAMILY_NAME,
+            block.hash().as_bytes().to_vec(),
+            block_key,
+        )
+    }
+
     /// Get a block from the database by block number
     pub fn get_block(&self, block_number: u128) -> Result<Option<Block>> {
         let block_key = block_number.to_be_bytes();
@@ -155,14 +442,865 @@ impl RoochDB {
         }
     }
 
-    /// Get the latest block number
+    /// Get the blocks numbered `start..=end` and their production times with one batched
+    /// read per column family. Numbers without a block are skipped.
+    pub fn get_blocks_in_range(&self, start: u128, end: u128) -> Result<Vec<(Block, Option<u64>)>> {
+        if end < start {
+            return Ok(vec![]);
+        }
+        let block_numbers: Vec<u128> = (start..=end).collect();
+        Ok(self
+            .get_blocks(&block_numbers)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// Get the blocks with these numbers and their production times with one batched
+    /// read per column family, None for numbers without a block
+    pub fn get_blocks(&self, block_numbers: &[u128]) -> Result<Vec<Option<(Block, Option<u64>)>>> {
+        let keys: Vec<Vec<u8>> = block_numbers
+            .iter()
+            .map(|block_number| block_number.to_be_bytes().to_vec())
+            .collect();
+        let store = &self.rooch_store.store_instance;
+        let blocks = store.multi_get(KANARI_BLOCK_COLUMN_FAMILY_NAME, keys.clone())?;
+        let timestamps = store.multi_get(KANARI_BLOCK_TIMESTAMP_COLUMN_FAMILY_NAME, keys)?;
+
+        blocks
+            .into_iter()
+            .zip(timestamps)
+            .map(|(block_bytes, timestamp_bytes)| {
+                let Some(block_bytes) = block_bytes else {
+                    return Ok(None);
+                };
+                let block: Block = bcs::from_bytes(&block_bytes)?;
+                let timestamp = timestamp_bytes
+                    .map(|bytes| {
+                        let bytes: [u8; 8] = bytes.try_into().map_err(|_| {
+                            anyhow!("Invalid timestamp of block #{}", block.block_number)
+                        })?;
+                        Ok::<_, Error>(u64::from_be_bytes(bytes))
+                    })
+                    .transpose()?;
+                Ok(Some((block, timestamp)))
+            })
+            .collect()
+    }
+
+    /// Get the production time of a block, None for blocks saved before timestamps
+    /// were recorded
+    pub fn get_block_timestamp(&self, block_number: u128) -> Result<Option<u64>> {
+        self.rooch_store
+            .store_instance
+            .get(
+                KANARI_BLOCK_TIMESTAMP_COLUMN_FAMILY_NAME,
+                &block_number.to_be_bytes(),
+            )?
+            .map(|bytes| {
+                let bytes: [u8; 8] = bytes
+                    .try_into()
+                    .map_err(|_| anyhow!("Invalid timestamp of block #{}", block_number))?;
+                Ok(u64::from_be_bytes(bytes))
+            })
+            .transpose()
+    }
+
+    /// Get a block from the database by block hash
+    pub fn get_block_by_hash(&self, block_hash: &H256) -> Result<Option<Block>> {
+        let number_bytes = match self.rooch_store.store_instance.get(
+            KANARI_BLOCK_HASH_INDEX_COLUMN_FAMILY_NAME,
+            block_hash.as_bytes(),
+        )? {
+            Some(number_bytes) => number_bytes,
+            None => return Ok(None),
+        };
+        let number_bytes: [u8; 16] = number_bytes
+            .try_into()
+            .map_err(|_| anyhow!("Invalid block number for hash {:?}", block_hash))?;
+        self.get_block(u128::from_be_bytes(number_bytes))
+    }
+
+    /// Get the hash of the genesis block, which identifies the network
+    pub fn get_genesis_hash(&self) -> Result<Option<H256>> {
+        Ok(self
+            .get_block(GENESIS_BLOCK_NUMBER)?
+            .map(|block| block.hash()))
+    }
+
+    /// Save the events of a block together with their bloom filter
+    pub fn save_block_events(&self, block_number: u128, events: &[BlockEvent]) -> Result<()> {
+        let mut batch = CfWriteBatch::new();
+        put_block_events(&mut batch, block_number, events)?;
+        self.write(batch)
+    }
+
+    /// The first block whose receipts were not pruned
+    pub fn get_receipts_pruned_until(&self) -> Result<u128> {
+        match self
+            .rooch_store
+            .store_instance
+            .get(KANARI_MAINTENANCE_COLUMN_FAMILY_NAME, PRUNED_RECEIPTS_KEY)?
+        {
+            Some(bytes) => Ok(bcs::from_bytes(&bytes)?),
+            None => Ok(GENESIS_BLOCK_NUMBER),
+        }
+    }
+
+    /// Delete the events, blooms and transaction receipts of the blocks below `until`,
+    /// continuing where the previous pruning stopped. Blocks and transactions are kept.
+    /// Returns the number of blocks whose receipts were deleted.
+    pub fn prune_block_receipts(&self, until: u128) -> Result<u128> {
+        let store = &self.rooch_store.store_instance;
+        let mut next = self.get_receipts_pruned_until()?;
+        let mut pruned = 0;
+        while next < until {
+            let end = until.min(next.saturating_add(PRUNE_BATCH_BLOCKS));
+            // The events, blooms and receipts of each block, then the new pruning progress
+            let mut write_batch = WriteBatch::new();
+            let mut cf_names = vec![];
+            for block_number in next..end {
+                write_batch.delete(block_number.to_be_bytes().to_vec())?;
+                write_batch.delete(block_number.to_be_bytes().to_vec())?;
+                cf_names.push(KANARI_BLOCK_EVENTS_COLUMN_FAMILY_NAME);
+                cf_names.push(KANARI_BLOCK_BLOOM_COLUMN_FAMILY_NAME);
+                for tx in self.get_block_transactions(block_number)? {
+                    write_batch.delete(tx.hash().as_bytes().to_vec())?;
+                    cf_names.push(KANARI_TRANSACTION_RECEIPT_COLUMN_FAMILY_NAME);
+                }
+            }
+            write_batch.put(PRUNED_RECEIPTS_KEY.to_vec(), bcs::to_bytes(&end)?)?;
+            cf_names.push(KANARI_MAINTENANCE_COLUMN_FAMILY_NAME);
+            store.write_batch_across_cfs(cf_names, write_batch, true)?;
+            pruned += end - next;
+            next = end;
+        }
+        Ok(pruned)
+    }
+
+    /// Compact every column family, reclaiming the space of deleted and overwritten
+    /// entries. Blocks until RocksDB finished, so run it off the async runtime.
+    pub fn compact(&self) -> Result<()> {
+        let db = self
+            .rooch_store
+            .store_instance
+            .db()
+            .ok_or_else(|| anyhow!("Compaction requires a RocksDB store"))?;
+        let mut column_families = moveos_store::StoreMeta::get_column_family_names().to_vec();
+        column_families.append(&mut rooch_store::StoreMeta::get_column_family_names().to_vec());
+        column_families.extend_from_slice(KANARI_COLUMN_FAMILIES);
+        for cf_name in column_families {
+            let Some(cf) = db.inner().cf_handle(cf_name) else {
+                warn!("Column family {} not found, skipping compaction", cf_name);
+                continue;
+            };
+            db.inner()
+                .compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
+        }
+        Ok(())
+    }
+
+    /// Get the events of a block
+    pub fn get_block_events(&self, block_number: u128) -> Result<Vec<BlockEvent>> {
+        match self.rooch_store.store_instance.get(
+            KANARI_BLOCK_EVENTS_COLUMN_FAMILY_NAME,
+            &block_number.to_be_bytes(),
+        )? {
+            Some(events_bytes) => Ok(bcs::from_bytes(&events_bytes)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Save the transactions of a block, index them by hash, append them to the history
+    /// of their sender and recipient, and index the staking rewards they pay by
+    /// recipient. Blocks must be saved in order for histories to be in block order.
+    pub fn save_block_transactions(
+        &self,
+        block_number: u128,
+        transactions: &[SignedTransaction],
+    ) -> Result<()> {
+        let mut batch = CfWriteBatch::new();
+        self.put_block_transactions(&mut batch, block_number, transactions)?;
+        self.write(batch)
+    }
+
+    fn put_block_transactions(
+        &self,
+        batch: &mut CfWriteBatch,
+        block_number: u128,
+        transactions: &[SignedTransaction],
+    ) -> Result<()> {
+        batch.put(
+            KANARI_BLOCK_TRANSACTIONS_COLUMN_FAMILY_NAME,
+            block_number.to_be_bytes().to_vec(),
+            bcs::to_bytes(transactions)?,
+        )?;
+
+        for (index, tx) in transactions.iter().enumerate() {
+            batch.put(
+                KANARI_TRANSACTION_INDEX_COLUMN_FAMILY_NAME,
+                tx.hash().as_bytes().to_vec(),
+                bcs::to_bytes(&(block_number, index as u64))?,
+            )?;
+        }
+
+        let mut counts: HashMap<AccountAddress, u64> = HashMap::new();
+        for tx in transactions {
+            let mut accounts = vec![tx.tx.sender];
+            if let Some(recipient) = tx.tx.recipient.filter(|r| *r != tx.tx.sender) {
+                accounts.push(recipient);
+            }
+            for account in accounts {
+                let position = match counts.get(&account) {
+                    Some(count) => *count,
+                    None => self.get_account_transaction_count(&account)?,
+                };
+                counts.insert(account, position + 1);
+                batch.put(
+                    KANARI_ACCOUNT_TRANSACTIONS_COLUMN_FAMILY_NAME,
+                    account_transaction_key(&account, position),
+                    bcs::to_bytes(&(block_number, tx))?,
+                )?;
+            }
+        }
+        for (account, count) in counts {
+            batch.put(
+                KANARI_ACCOUNT_TRANSACTION_COUNT_COLUMN_FAMILY_NAME,
+                account.to_vec(),
+                count.to_be_bytes().to_vec(),
+            )?;
+        }
+
+        let mut rewards: HashMap<(AccountAddress, u64), Vec<RewardPayment>> = HashMap::new();
+        for tx in transactions.iter().filter(|tx| tx.is_system()) {
+            if let Some(SystemTransaction::RewardDistribution { payments, .. }) =
+                SystemTransaction::from_transaction(tx)?
+            {
+                for payment in payments {
+                    rewards
+                        .entry((payment.recipient, payment.epoch))
+                        .or_default()
+                        .push(payment);
+                }
+            }
+        }
+        for ((recipient, epoch), payments) in rewards {
+            batch.put(
+                KANARI_REWARD_COLUMN_FAMILY_NAME,
+                reward_key(&recipient, epoch),
+                bcs::to_bytes(&payments)?,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Get the transactions included in a block
+    pub fn get_block_transactions(&self, block_number: u128) -> Result<Vec<SignedTransaction>> {
+        match self.rooch_store.store_instance.get(
+            KANARI_BLOCK_TRANSACTIONS_COLUMN_FAMILY_NAME,
+            &block_number.to_be_bytes(),
+        )? {
+            Some(transactions_bytes) => Ok(bcs::from_bytes(&transactions_bytes)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Get the number of the block including a transaction and its position in the block
+    pub fn get_transaction_location(&self, tx_hash: &H256) -> Result<Option<(u128, u64)>> {
+        match self.rooch_store.store_instance.get(
+            KANARI_TRANSACTION_INDEX_COLUMN_FAMILY_NAME,
+            tx_hash.as_bytes(),
+        )? {
+            Some(location_bytes) => Ok(Some(bcs::from_bytes(&location_bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Save the receipts of the transactions of a block, built from their execution
+    /// outputs and the events the block emitted
+    pub fn save_block_receipts(
+        &self,
+        block_number: u128,
+        transactions: &[SignedTransaction],
+        outputs: &[TransactionOutput],
+        events: &[BlockEvent],
+    ) -> Result<()> {
+        let mut batch = CfWriteBatch::new();
+        put_block_receipts(&mut batch, block_number, transactions, outputs, events)?;
+        self.write(batch)
+    }
+
+    /// Get the receipt of an included transaction, None if the transaction is unknown or
+    /// its receipt was pruned
+    pub fn get_transaction_receipt(&self, tx_hash: &H256) -> Result<Option<TransactionReceipt>> {
+        match self.rooch_store.store_instance.get(
+            KANARI_TRANSACTION_RECEIPT_COLUMN_FAMILY_NAME,
+            tx_hash.as_bytes(),
+        )? {
+            Some(receipt_bytes) => Ok(Some(bcs::from_bytes(&receipt_bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the number of transactions an account sent or received
+    pub fn get_account_transaction_count(&self, account: &AccountAddress) -> Result<u64> {
+        match self.rooch_store.store_instance.get(
+            KANARI_ACCOUNT_TRANSACTION_COUNT_COLUMN_FAMILY_NAME,
+            account.as_ref(),
+        )? {
+            Some(count_bytes) => {
+                let count_bytes: [u8; 8] = count_bytes
+                    .try_into()
+                    .map_err(|_| anyhow!("Invalid transaction count of {}", account))?;
+                Ok(u64::from_be_bytes(count_bytes))
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Get up to `limit` transactions of an account in block order, starting at position
+    /// `start` of its history, with the number of the block including each
+    pub fn get_account_transactions(
+        &self,
+        account: &AccountAddress,
+        start: u64,
+        limit: usize,
+    ) -> Result<Vec<(u128, SignedTransaction)>> {
+        let end = self
+            .get_account_transaction_count(account)?
+            .min(start.saturating_add(limit as u64));
+        if end <= start {
+            return Ok(vec![]);
+        }
+        let keys = (start..end)
+            .map(|position| account_transaction_key(account, position))
+            .collect();
+        self.rooch_store
+            .store_instance
+            .multi_get(KANARI_ACCOUNT_TRANSACTIONS_COLUMN_FAMILY_NAME, keys)?
+            .into_iter()
+            .flatten()
+            .map(|entry_bytes| Ok(bcs::from_bytes(&entry_bytes)?))
+            .collect()
+    }
+
+    /// Get the event bloom filter of a block
+    pub fn get_block_bloom(&self, block_number: u128) -> Result<Option<EventBloom>> {
+        self.rooch_store
+            .store_instance
+            .get(
+                KANARI_BLOCK_BLOOM_COLUMN_FAMILY_NAME,
+                &block_number.to_be_bytes(),
+            )?
+            .map(|bloom_bytes| EventBloom::from_bytes(&bloom_bytes))
+            .transpose()
+    }
+
+    /// Get events in the inclusive block range matching the optional address and type.
+    /// Blocks whose bloom filter rules out the criteria are skipped without loading events.
+    pub fn get_events(
+        &self,
+        from_block: u128,
+        to_block: u128,
+        address: Option<&AccountAddress>,
+        event_type: Option<&str>,
+    ) -> Result<Vec<(u128, BlockEvent)>> {
+        let mut matched = vec![];
+        for block_number in from_block..=to_block {
+            let bloom = match self.get_block_bloom(block_number)? {
+                Some(bloom) => bloom,
+                None => continue,
+            };
+            if bloom.is_empty()
+                || address.is_some_and(|address| !bloom.contains(address.as_ref()))
+                || event_type.is_some_and(|event_type| !bloom.contains(event_type.as_bytes()))
+            {
+                continue;
+            }
+            matched.extend(
+                self.get_block_events(block_number)?
+                    .into_iter()
+                    .filter(|event| event.matches(address, event_type))
+                    .map(|event| (block_number, event)),
+            );
+        }
+        Ok(matched)
+    }
+
+    /// Get events in the inclusive block range emitted by any of `addresses` with any of
+    /// `event_types`, where an empty list matches anything. Blocks whose bloom filter
+    /// rules out every address or every type are skipped without loading events.
+    pub fn get_logs(
+        &self,
+        from_block: u128,
+        to_block: u128,
+        addresses: &[AccountAddress],
+        event_types: &[String],
+    ) -> Result<Vec<(u128, BlockEvent)>> {
+        let mut matched = vec![];
+        for block_number in from_block..=to_block {
+            let bloom = match self.get_block_bloom(block_number)? {
+                Some(bloom) => bloom,
+                None => continue,
+            };
+            if bloom.is_empty()
+                || (!addresses.is_empty()
+                    && !addresses
+                        .iter()
+                        .any(|address| bloom.contains(address.as_ref())))
+                || (!event_types.is_empty()
+                    && !event_types
+                        .iter()
+                        .any(|event_type| bloom.contains(event_type.as_bytes())))
+            {
+                continue;
+            }
+            matched.extend(
+                self.get_block_events(block_number)?
+                    .into_iter()
+                    .filter(|event| event.matches_any(addresses, event_types))
+                    .map(|event| (block_number, event)),
+            );
+        }
+        Ok(matched)
+    }
+
+    /// Get the validator set and consensus parameters of an epoch
+    pub fn get_epoch_snapshot(&self, epoch: u64) -> Result<Option<EpochSnapshot>> {
+        match self
+            .rooch_store
+            .store_instance
+            .get(KANARI_EPOCH_COLUMN_FAMILY_NAME, &epoch.to_be_bytes())?
+        {
+            Some(snapshot_bytes) => Ok(Some(bcs::from_bytes(&snapshot_bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the validator set changes queued for the next epoch boundary, in order
+    pub fn get_pending_validator_changes(&self) -> Result<Vec<ValidatorSetChange>> {
+        match self.rooch_store.store_instance.get(
+            KANARI_PENDING_VALIDATOR_CHANGES_COLUMN_FAMILY_NAME,
+            PENDING_VALIDATOR_CHANGES_KEY,
+        )? {
+            Some(changes_bytes) => Ok(bcs::from_bytes(&changes_bytes)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Queue a change for the next epoch boundary. It is rejected if it can not be
+    /// applied after the changes already queued.
+    pub fn queue_validator_change(
+        &self,
+        current: &EpochSnapshot,
+        change: ValidatorSetChange,
+    ) -> Result<()> {
+        let mut changes = self.get_pending_validator_changes()?;
+        changes.push(change);
+        current.next(&changes)?;
+        self.rooch_store.store_instance.put(
+            KANARI_PENDING_VALIDATOR_CHANGES_COLUMN_FAMILY_NAME,
+            PENDING_VALIDATOR_CHANGES_KEY.to_vec(),
+            bcs::to_bytes(&changes)?,
+        )?;
+        Ok(())
+    }
+
+    /// The snapshot of the epoch `block_number` belongs to. At an epoch boundary whose
+    /// snapshot is not saved yet, it is the previous epoch with the queued changes applied.
+    pub fn epoch_snapshot_for_block(&self, block_number: u128) -> Result<Option<EpochSnapshot>> {
+        let epoch = epoch_of(block_number);
+        if let Some(snapshot) = self.get_epoch_snapshot(epoch)? {
+            return Ok(Some(snapshot));
+        }
+        if !is_epoch_boundary(block_number) {
+            return Ok(None);
+        }
+        match self.get_epoch_snapshot(epoch - 1)? {
+            Some(previous) => Ok(Some(previous.next(&self.get_pending_validator_changes()?)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Save the snapshot of a new epoch and clear the queued changes it applied
+    pub fn start_epoch(&self, snapshot: &EpochSnapshot) -> Result<()> {
+        let mut batch = CfWriteBatch::new();
+        put_epoch_start(&mut batch, snapshot)?;
+        self.write(batch)
+    }
+
+    /// Record the public key that proposed a block
+    pub fn save_block_proposer(&self, block_number: u128, public_key: &[u8]) -> Result<()> {
+        let mut batch = CfWriteBatch::new();
+        put_block_proposer(&mut batch, block_number, public_key)?;
+        self.write(batch)
+    }
+
+    /// Get the public key that proposed a block, None for blocks produced by the node
+    pub fn get_block_proposer(&self, block_number: u128) -> Result<Option<Vec<u8>>> {
+        self.rooch_store.store_instance.get(
+            KANARI_BLOCK_PROPOSER_COLUMN_FAMILY_NAME,
+            &block_number.to_be_bytes(),
+        )
+    }
+
+    /// Where the fees of the user transactions of a block went, with the fee shares of
+    /// its epoch. None if the block does not exist.
+    pub fn get_block_fee_summary(&self, block_number: u128) -> Result<Option<FeeSummary>> {
+        if self.get_block(block_number)?.is_none() {
+            return Ok(None);
+        }
+        let params = self
+            .epoch_snapshot_for_block(block_number)?
+            .map(|snapshot| snapshot.params)
+            .unwrap_or_default();
+        self.block_fee_summary(block_number, &params).map(Some)
+    }
+
+    /// The gas the transactions of a block used, 0 for a block without transactions
+    pub fn get_block_gas_used(&self, block_number: u128) -> Result<u64> {
+        self.get_block_transactions(block_number)?
+            .iter()
+            .map(|tx| self.get_transaction_gas_used(tx))
+            .sum()
+    }
+
+    /// The gas an included transaction was charged, from its receipt. Blocks saved
+    /// before receipts were recorded report its `charged_gas`.
+    pub fn get_transaction_gas_used(&self, tx: &SignedTransaction) -> Result<u64> {
+        Ok(match self.get_transaction_receipt(&tx.hash())? {
+            Some(receipt) => receipt.gas_used,
+            None => charged_gas(tx),
+        })
+    }
+
+    fn block_fee_summary(
+        &self,
+        block_number: u128,
+        params: &ConsensusParams,
+    ) -> Result<FeeSummary> {
+        let transactions = self.get_block_transactions(block_number)?;
+        let gas_used = transactions
+            .iter()
+            .map(|tx| Ok((tx.hash(), self.get_transaction_gas_used(tx)?)))
+            .collect::<Result<HashMap<H256, u64>>>()?;
+        Ok(FeeSummary::new(
+            &transactions,
+            |tx| gas_used.get(&tx.hash()).copied().unwrap_or_default(),
+            params,
+        ))
+    }
+
+    /// The staking rewards of an epoch, from the validators' share of the fees collected
+    /// by its blocks and the blocks each validator proposed. Only complete once the epoch
+    /// has ended.
+    pub fn epoch_rewards(&self, snapshot: &EpochSnapshot) -> Result<Vec<RewardPayment>> {
+        let mut fees = 0u128;
+        let mut proposed_blocks: HashMap<Vec<u8>, u64> = HashMap::new();
+        for block_number in snapshot.start_block()..=snapshot.end_block() {
+            let summary = self.block_fee_summary(block_number, &snapshot.params)?;
+            fees = fees.saturating_add(summary.to_validators);
+            if let Some(public_key) = self.get_block_proposer(block_number)? {
+                *proposed_blocks.entry(public_key).or_default() += 1;
+            }
+        }
+        Ok(distribute_rewards(snapshot, fees, &proposed_blocks))
+    }
+
+    /// Get the staking rewards paid to an account for the epochs in the inclusive range,
+    /// in epoch order
+    pub fn get_rewards(
+        &self,
+        account: &AccountAddress,
+        from_epoch: u64,
+        to_epoch: u64,
+    ) -> Result<Vec<RewardPayment>> {
+        if to_epoch < from_epoch {
+            return Ok(vec![]);
+        }
+        let keys = (from_epoch..=to_epoch)
+            .map(|epoch| reward_key(account, epoch))
+            .collect();
+        let mut rewards = vec![];
+        for payments_bytes in self
+            .rooch_store
+            .store_instance
+            .multi_get(KANARI_REWARD_COLUMN_FAMILY_NAME, keys)?
+            .into_iter()
+            .flatten()
+        {
+            rewards.extend(bcs::from_bytes::<Vec<RewardPayment>>(&payments_bytes)?);
+        }
+        Ok(rewards)
+    }
+
+    /// Get the verified double sign evidence, in submission order
+    pub fn get_evidence_records(&self) -> Result<Vec<EvidenceRecord>> {
+        match self
+            .rooch_store
+            .store_instance
+            .get(KANARI_EVIDENCE_COLUMN_FAMILY_NAME, EVIDENCE_RECORDS_KEY)?
+        {
+            Some(records_bytes) => Ok(bcs::from_bytes(&records_bytes)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Get the evidence with this hash
+    pub fn get_evidence(&self, evidence_hash: &H256) -> Result<Option<EvidenceRecord>> {
+        Ok(self
+            .get_evidence_records()?
+            .into_iter()
+            .find(|record| &record.evidence.hash() == evidence_hash))
+    }
+
+    /// Store verified evidence and queue the slashing of the offender for the next epoch
+    /// boundary, atomically. An offender is punished once per height, further evidence
+    /// for the same height is rejected.
+    pub fn record_evidence(&self, current: &EpochSnapshot, record: EvidenceRecord) -> Result<()> {
+        let mut records = self.get_evidence_records()?;
+        if records.iter().any(|existing| {
+            existing.evidence.public_key() == record.evidence.public_key()
+                && existing.evidence.block_number() == record.evidence.block_number()
+        }) {
+            return Err(anyhow!(
+                "Evidence of double signing at #{} by {} is already recorded",
+                record.evidence.block_number(),
+                record.offender
+            ));
+        }
+        let mut changes = self.get_pending_validator_changes()?;
+        changes.push(record.slash());
+        current.next(&changes)?;
+        records.push(record);
+
+        let mut write_batch = WriteBatch::new();
+        write_batch.put(EVIDENCE_RECORDS_KEY.to_vec(), bcs::to_bytes(&records)?)?;
+        write_batch.put(
+            PENDING_VALIDATOR_CHANGES_KEY.to_vec(),
+            bcs::to_bytes(&changes)?,
+        )?;
+        self.rooch_store.store_instance.write_batch_across_cfs(
+            vec![
+                KANARI_EVIDENCE_COLUMN_FAMILY_NAME,
+                KANARI_PENDING_VALIDATOR_CHANGES_COLUMN_FAMILY_NAME,
+            ],
+            write_batch,
+            true,
+        )?;
+        Ok(())
+    }
+
+    /// Number of proposals made to the Kanari DAO, which is the id of the next one
+    pub fn get_dao_proposal_count(&self) -> Result<u64> {
+        match self.rooch_store.store_instance.get(
+            KANARI_DAO_PROPOSAL_COLUMN_FAMILY_NAME,
+            DAO_PROPOSAL_COUNT_KEY,
+        )? {
+            Some(count_bytes) => {
+                let bytes: [u8; 8] = count_bytes
+                    .try_into()
+                    .map_err(|_| anyhow!("Invalid DAO proposal count"))?;
+                Ok(u64::from_be_bytes(bytes))
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Get the proposals of the Kanari DAO, by id
+    pub fn get_dao_proposals(&self) -> Result<Vec<DaoProposal>> {
+        let keys = (0..self.get_dao_proposal_count()?)
+            .map(|id| id.to_be_bytes().to_vec())
+            .collect();
+        self.rooch_store
+            .store_instance
+            .multi_get(KANARI_DAO_PROPOSAL_COLUMN_FAMILY_NAME, keys)?
+            .into_iter()
+            .flatten()
+            .map(|proposal_bytes| Ok(bcs::from_bytes(&proposal_bytes)?))
+            .collect()
+    }
+
+    /// Get the DAO proposal with this id
+    pub fn get_dao_proposal(&self, id: u64) -> Result<Option<DaoProposal>> {
+        match self
+            .rooch_store
+            .store_instance
+            .get(KANARI_DAO_PROPOSAL_COLUMN_FAMILY_NAME, &id.to_be_bytes())?
+        {
+            Some(proposal_bytes) => Ok(Some(bcs::from_bytes(&proposal_bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the votes cast on a DAO proposal, in the order they were cast
+    pub fn get_dao_votes(&self, proposal_id: u64) -> Result<Vec<DaoVote>> {
+        match self.rooch_store.store_instance.get(
+            KANARI_DAO_VOTE_COLUMN_FAMILY_NAME,
+            &proposal_id.to_be_bytes(),
+        )? {
+            Some(votes_bytes) => Ok(bcs::from_bytes(&votes_bytes)?),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// The number of DAO proposals made before block `block_number`
+    pub(crate) fn dao_proposal_count_before(&self, block_number: u128) -> Result<u64> {
+        let mut count = self.get_dao_proposal_count()?;
+        while count > 0
+            && self
+                .get_dao_proposal(count - 1)?
+                .is_some_and(|proposal| proposal.created_at_block >= block_number)
+        {
+            count -= 1;
+        }
+        Ok(count)
+    }
+
+    /// The DAO proposal with this id as the blocks before `block_number` left it
+    pub(crate) fn dao_proposal_before(
+        &self,
+        id: u64,
+        block_number: u128,
+    ) -> Result<Option<DaoProposal>> {
+        Ok(self
+            .get_dao_proposal(id)?
+            .filter(|proposal| proposal.created_at_block < block_number)
+            .map(|mut proposal| {
+                if proposal
+                    .executed_at_block
+                    .is_some_and(|executed_at_block| executed_at_block >= block_number)
+                {
+                    proposal.executed_at_block = None;
+                }
+                proposal
+            }))
+    }
+
+    /// The votes cast on a DAO proposal before block `block_number`
+    pub(crate) fn dao_votes_before(
+        &self,
+        proposal_id: u64,
+        block_number: u128,
+    ) -> Result<Vec<DaoVote>> {
+        let mut votes = self.get_dao_votes(proposal_id)?;
+        votes.retain(|vote| vote.block_number < block_number);
+        Ok(votes)
+    }
+
+    /// The proposals a block made or executed, and the votes it cast appended to the
+    /// ones already stored. Saving a block again leaves its votes in place.
+    fn put_dao_changes(&self, batch: &mut CfWriteBatch, changes: &DaoChanges) -> Result<()> {
+        if !changes.proposals.is_empty() {
+            let mut count = self.get_dao_proposal_count()?;
+            for proposal in &changes.proposals {
+                batch.put(
+                    KANARI_DAO_PROPOSAL_COLUMN_FAMILY_NAME,
+                    proposal.id.to_be_bytes().to_vec(),
+                    bcs::to_bytes(proposal)?,
+                )?;
+                count = count.max(proposal.id + 1);
+            }
+            batch.put(
+                KANARI_DAO_PROPOSAL_COLUMN_FAMILY_NAME,
+                DAO_PROPOSAL_COUNT_KEY.to_vec(),
+                count.to_be_bytes().to_vec(),
+            )?;
+        }
+        let mut votes: BTreeMap<u64, Vec<DaoVote>> = BTreeMap::new();
+        for vote in &changes.votes {
+            if !votes.contains_key(&vote.proposal_id) {
+                votes.insert(vote.proposal_id, self.get_dao_votes(vote.proposal_id)?);
+            }
+            let proposal_votes = votes
+                .get_mut(&vote.proposal_id)
+                .expect("The votes were just inserted");
+            if !proposal_votes.contains(vote) {
+                proposal_votes.push(vote.clone());
+            }
+        }
+        for (proposal_id, proposal_votes) in votes {
+            batch.put(
+                KANARI_DAO_VOTE_COLUMN_FAMILY_NAME,
+                proposal_id.to_be_bytes().to_vec(),
+                bcs::to_bytes(&proposal_votes)?,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Whether evidence against this public key awaits its slashing in `epoch`. Such a
+    /// proposer is refused right away, before the jail takes effect at the next boundary.
+    pub fn has_pending_evidence_against(&self, public_key: &[u8], epoch: u64) -> Result<bool> {
+        Ok(self.get_evidence_records()?.iter().any(|record| {
+            record.evidence.public_key() == public_key && epoch < record.slashed_from_epoch
+        }))
+    }
+
+    /// Get the latest block number, recorded with each block saved
     pub fn get_latest_block_number(&self) -> Result<Option<u128>> {
-        // This is a simple implementation - in production you might want to maintain this separately
-        // For now, we'll scan backwards from a reasonable upper bound
-        for block_number in (1..=1000000u128).rev() {
-            if self.get_block(block_number)?.is_some() {
-                return Ok(Some(block_number));
+        match self
+            .rooch_store
+            .store_instance
+            .get(KANARI_BLOCK_COLUMN_FAMILY_NAME, LATEST_BLOCK_NUMBER_KEY)?
+        {
+            Some(number_bytes) => {
+                let bytes: [u8; 16] = number_bytes
+                    .try_into()
+                    .map_err(|_| anyhow!("Invalid latest block number"))?;
+                Ok(Some(u128::from_be_bytes(bytes)))
+            }
+            None => self.search_latest_block_number(),
+        }
+    }
+
+    /// The latest block of a database saved before the latest block number was
+    /// recorded. Its blocks are numbered from genesis without gaps, so the last one is
+    /// found by doubling the number until a block is missing, then bisecting.
+    fn search_latest_block_number(&self) -> Result<Option<u128>> {
+        let has_block = |block_number: u128| -> Result<bool> {
+            Ok(self
+                .rooch_store
+                .store_instance
+                .get(KANARI_BLOCK_COLUMN_FAMILY_NAME, &block_number.to_be_bytes())?
+                .is_some())
+        };
+        if !has_block(GENESIS_BLOCK_NUMBER)? {
+            return Ok(None);
+        }
+        let (mut present, mut missing) = (GENESIS_BLOCK_NUMBER, GENESIS_BLOCK_NUMBER * 2);
+        while has_block(missing)? {
+            present = missing;
+            missing *= 2;
+        }
+        while missing - present > 1 {
+            let middle = present + (missing - present) / 2;
+            if has_block(middle)? {
+                present = middle;
+            } else {
+                missing = middle;
+            }
+        }
+        Ok(Some(present))
+    }
+
+    /// The block committed by the latest checkpoint included at or below `height`, None
+    /// if none of the last `MAX_CHECKPOINT_LOOKBACK` checkpoint blocks commits one.
+    /// Blocks up to it no longer change.
+    pub fn get_finalized_block_number(&self, height: u128) -> Result<Option<u128>> {
+        let mut checkpoint_block = height - height % CHECKPOINT_INTERVAL;
+        for _ in 0..MAX_CHECKPOINT_LOOKBACK {
+            if checkpoint_block == 0 {
+                break;
             }
+            for tx in self.get_block_transactions(checkpoint_block)? {
+                if let Some(SystemTransaction::Checkpoint { block_number, .. }) =
+                    SystemTransaction::from_transaction(&tx)?
+                {
+                    return Ok(Some(block_number));
+                }
+            }
+            checkpoint_block -= CHECKPOINT_INTERVAL;
         }
         Ok(None)
     }
@@ -173,6 +1311,148 @@ impl RoochDB {
         Ok(startup_info.map(|s| s.into_root_metadata()))
     }
 
+    /// The root object after `block_number`, None if the block was not executed here
+    pub fn get_block_root(&self, block_number: u128) -> Result<Option<ObjectMeta>> {
+        match self.rooch_store.store_instance.get(
+            KANARI_STATE_ROOT_COLUMN_FAMILY_NAME,
+            &block_number.to_be_bytes(),
+        )? {
+            Some(root_bytes) => {
+                let (state_root, size): (H256, u64) = bcs::from_bytes(&root_bytes)?;
+                Ok(Some(ObjectMeta::root_metadata(state_root, size)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// The root object block `block_number` is executed on: the root its parent left,
+    /// or for the genesis block the state the node started from
+    pub fn block_pre_state(&self, block_number: u128) -> Result<ObjectMeta> {
+        if let Some(root) = self.get_block_root(block_number.saturating_sub(1))? {
+            return Ok(root);
+        }
+        if block_number > GENESIS_BLOCK_NUMBER {
+            let parent = self
+                .get_block(block_number - 1)?
+                .ok_or_else(|| anyhow!("Parent block #{} not found", block_number - 1))?;
+            // The parent came without its root size, which the state root does not cover
+            return Ok(ObjectMeta::root_metadata(parent.state_root, 0));
+        }
+        Ok(self.latest_root()?.unwrap_or_else(ObjectMeta::genesis_root))
+    }
+
+    /// Execute the transactions of block `block_number` on the state its parent left.
+    /// Nothing is committed, the latest state stays where it was.
+    pub fn execute_block(
+        &self,
+        block_number: u128,
+        transactions: &[SignedTransaction],
+    ) -> Result<BlockExecution> {
+        self.execute_block_with_dao(block_number, transactions, &G_LOCAL_CONFIG.kanari_dao)
+    }
+
+    /// Execute a block as `execute_block` does, for a DAO with these participants
+    pub fn execute_block_with_dao(
+        &self,
+        block_number: u128,
+        transactions: &[SignedTransaction],
+        dao_config: &MultisignAccountConfig,
+    ) -> Result<BlockExecution> {
+        execution::execute_block(
+            self,
+            block_number,
+            self.block_pre_state(block_number)?,
+            transactions,
+            dao_config,
+        )
+    }
+
+    /// Record `root`, the state block `block_number` left, and make it the latest state
+    pub fn commit_block_state(&self, block_number: u128, root: &ObjectMeta) -> Result<()> {
+        let mut batch = CfWriteBatch::new();
+        self.put_block_state(&mut batch, block_number, root)?;
+        self.write(batch)
+    }
+
+    fn put_block_state(
+        &self,
+        batch: &mut CfWriteBatch,
+        block_number: u128,
+        root: &ObjectMeta,
+    ) -> Result<()> {
+        let encode_root = |root: &ObjectMeta| bcs::to_bytes(&(root.state_root(), root.size));
+        if block_number == GENESIS_BLOCK_NUMBER {
+            let pre_state = self.block_pre_state(block_number)?;
+            batch.put(
+                KANARI_STATE_ROOT_COLUMN_FAMILY_NAME,
+                (block_number - 1).to_be_bytes().to_vec(),
+                encode_root(&pre_state)?,
+            )?;
+        }
+        batch.put(
+            KANARI_STATE_ROOT_COLUMN_FAMILY_NAME,
+            block_number.to_be_bytes().to_vec(),
+            encode_root(root)?,
+        )?;
+        let startup_info =
+            moveos_types::startup_info::StartupInfo::new(root.state_root(), root.size);
+        batch.put(
+            CONFIG_STARTUP_INFO_COLUMN_FAMILY_NAME,
+            to_bytes(STARTUP_INFO_KEY)?,
+            to_bytes(&startup_info)?,
+        )
+    }
+
+    /// Reads against the latest state root
+    pub fn latest_state(&self) -> Result<StateView<'_>> {
+        Ok(StateView::new(&self.moveos_store, self.latest_root()?))
+    }
+
+    /// Reads against the state after `block_number`, or the latest state if None. None
+    /// if the block is not stored, as its state is not retained either.
+    pub fn state_at(&self, block_number: Option<u128>) -> Result<Option<StateView<'_>>> {
+        let Some(block_number) = block_number else {
+            return self.latest_state().map(Some);
+        };
+        let Some(block) = self.get_block(block_number)? else {
+            return Ok(None);
+        };
+        // Only reads of objects go through the root, the size is kept when it is known
+        let root = match self.get_block_root(block_number)? {
+            Some(root) => root,
+            None => ObjectMeta::root_metadata(block.state_root, 0),
+        };
+        Ok(Some(StateView::new(&self.moveos_store, Some(root))))
+    }
+
+    /// The account object of `address` at the latest state root, None if the account
+    /// was never created
+    pub fn get_account(&self, address: AccountAddress) -> Result<Option<Account>> {
+        self.latest_state()?.get_account(address)
+    }
+
+    /// The bytecode of a module at the latest state root, read from its package in the
+    /// module store. None if the module is not published.
+    pub fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>> {
+        self.latest_state()?.get_module(module_id)
+    }
+
+    /// The modules of the package published at `address` at the latest state root, as
+    /// name and bytecode. Empty if no package is published there.
+    pub fn list_modules(&self, address: AccountAddress) -> Result<Vec<(String, Vec<u8>)>> {
+        self.latest_state()?.list_modules(address)
+    }
+
+    /// The balance of `coin_type` held by `address` at the latest state root, read from
+    /// its account coin store. None if the account never held the coin.
+    pub fn get_coin_balance(
+        &self,
+        address: AccountAddress,
+        coin_type: &StructTag,
+    ) -> Result<Option<U256>> {
+        self.latest_state()?.get_coin_balance(address, coin_type)
+    }
+
     /// revert tx with these operations:
     /// 1. check preconditions
     /// 2. remove the tx + save previous tx as startup (atomic)
@@ -564,3 +1844,44 @@ impl RoochDB {
         Ok((issues, fixed))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kanari_config::KanariOpt;
+
+    fn block(block_number: u128) -> Block {
+        Block::new(
+            block_number,
+            0,
+            H256::zero(),
+            H256::zero(),
+            H256::zero(),
+            H256::zero(),
+        )
+    }
+
+    #[test]
+    fn test_latest_block_number_is_recorded_with_each_block() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = RoochDB::init(&opt.store, &Registry::new()).unwrap();
+        assert_eq!(db.get_latest_block_number().unwrap(), None);
+        for block_number in 1..=5 {
+            db.save_block(&block(block_number), 1_700_000_000).unwrap();
+        }
+        // Saving an earlier block again leaves the latest in place
+        db.save_block(&block(3), 1_700_000_000).unwrap();
+        assert_eq!(db.get_latest_block_number().unwrap(), Some(5));
+
+        // A database from before the record is searched
+        let mut write_batch = WriteBatch::new();
+        write_batch
+            .delete(LATEST_BLOCK_NUMBER_KEY.to_vec())
+            .unwrap();
+        db.rooch_store
+            .store_instance
+            .write_batch_across_cfs(vec![KANARI_BLOCK_COLUMN_FAMILY_NAME], write_batch, true)
+            .unwrap();
+        assert_eq!(db.get_latest_block_number().unwrap(), Some(5));
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow, bail};
+use kanari_config::maintenance_config::MaintenanceWindow;
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{info, warn};
+
+/// How often the scheduler checks whether a maintenance window opened
+const MAINTENANCE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Heavy storage work that should not compete with peak traffic
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MaintenanceTask {
+    /// Manual RocksDB compaction of every column family
+    Compaction,
+    /// Deletion of old block receipts beyond the retention
+    Pruning,
+    /// Upload of finalized blocks to the archive
+    ArchiveUpload,
+}
+
+impl MaintenanceTask {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MaintenanceTask::Compaction => "compaction",
+            MaintenanceTask::Pruning => "pruning",
+            MaintenanceTask::ArchiveUpload => "archive_upload",
+        }
+    }
+}
+
+impl fmt::Display for MaintenanceTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for MaintenanceTask {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        [
+            MaintenanceTask::Compaction,
+            MaintenanceTask::Pruning,
+            MaintenanceTask::ArchiveUpload,
+        ]
+        .into_iter()
+        .find(|task| task.name() == s)
+        .ok_or_else(|| anyhow!("Unknown maintenance task {}", s))
+    }
+}
+
+/// Runs one maintenance task, provided by the node for the task it implements
+pub type MaintenanceJob =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync>;
+
+/// Outcome of a maintenance task run
+#[derive(Clone, Debug)]
+pub struct MaintenanceRun {
+    pub task: MaintenanceTask,
+    /// Whether the run was triggered by an operator rather than a window
+    pub manual: bool,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+    pub duration: Duration,
+    pub error: Option<String>,
+}
+
+struct MaintenanceMetrics {
+    runs: IntCounterVec,
+    duration: HistogramVec,
+}
+
+impl MaintenanceMetrics {
+    fn new(registry: &Registry) -> Result<Self> {
+        let runs = IntCounterVec::new(
+            Opts::new(
+                "kanari_maintenance_runs_total",
+                "Number of storage maintenance runs by task and outcome",
+            ),
+            &["task", "status"],
+        )?;
+        let duration = HistogramVec::new(
+            HistogramOpts::new(
+                "kanari_maintenance_duration_seconds",
+                "Time a storage maintenance task took",
+            )
+            .buckets(vec![1.0, 5.0, 30.0, 60.0, 300.0, 900.0, 1800.0, 3600.0]),
+            &["task"],
+        )?;
+        registry.register(Box::new(runs.clone()))?;
+        registry.register(Box::new(duration.clone()))?;
+        Ok(Self { runs, duration })
+    }
+}
+
+/// Runs compaction, pruning and archive uploads in the configured off-peak windows, at
+/// most once per window occurrence, or when an operator triggers them. Runs never overlap.
+pub struct MaintenanceScheduler {
+    windows: Vec<MaintenanceWindow>,
+    jobs: BTreeMap<MaintenanceTask, MaintenanceJob>,
+    running: tokio::sync::Mutex<()>,
+    last_runs: Mutex<BTreeMap<MaintenanceTask, MaintenanceRun>>,
+    metrics: MaintenanceMetrics,
+}
+
+impl MaintenanceScheduler {
+    pub fn new(windows: Vec<MaintenanceWindow>, registry: &Registry) -> Result<Self> {
+        Ok(Self {
+            windows,
+            jobs: BTreeMap::new(),
+            running: tokio::sync::Mutex::new(()),
+            last_runs: Mutex::new(BTreeMap::new()),
+            metrics: MaintenanceMetrics::new(registry)?,
+        })
+    }
+
+    pub fn with_job(mut self, task: MaintenanceTask, job: MaintenanceJob) -> Self {
+        self.jobs.insert(task, job);
+        self
+    }
+
+    /// Whether `task` is run by the scheduler, instead of continuously by its subsystem
+    pub fn schedules(&self, task: MaintenanceTask) -> bool {
+        self.jobs.contains_key(&task)
+    }
+
+    pub fn tasks(&self) -> Vec<MaintenanceTask> {
+        self.jobs.keys().copied().collect()
+    }
+
+    /// The last run of each task since the node started
+    pub fn last_runs(&self) -> Vec<MaintenanceRun> {
+        let last_runs = self.last_runs.lock().unwrap_or_else(|e| e.into_inner());
+        last_runs.values().cloned().collect()
+    }
+
+    /// Run `task`, or every task, now. Fails if maintenance is already running.
+    pub async fn trigger(&self, task: Option<MaintenanceTask>) -> Result<Vec<MaintenanceRun>> {
+        let tasks = match task {
+            Some(task) if !self.schedules(task) => {
+                bail!("Maintenance task {} is not configured", task)
+            }
+            Some(task) => vec![task],
+            None => self.tasks(),
+        };
+        let Ok(_running) = self.running.try_lock() else {
+            bail!("Maintenance is already running");
+        };
+        let mut runs = vec![];
+        for task in tasks {
+            runs.push(self.run_task(task, true).await);
+        }
+        Ok(runs)
+    }
+
+    async fn run_task(&self, task: MaintenanceTask, manual: bool) -> MaintenanceRun {
+        info!("Starting storage maintenance task {}", task);
+        let started_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let started = Instant::now();
+        let result = match self.jobs.get(&task) {
+            Some(job) => job().await,
+            None => Err(anyhow!("Maintenance task {} is not configured", task)),
+        };
+        let run = MaintenanceRun {
+            task,
+            manual,
+            started_at,
+            duration: started.elapsed(),
+            error: result.err().map(|e| e.to_string()),
+        };
+        let status = match &run.error {
+            None => {
+                info!(
+                    "Storage maintenance task {} finished in {:?}",
+                    task, run.duration
+                );
+                "ok"
+            }
+            Some(e) => {
+                warn!("Storage maintenance task {} failed: {}", task, e);
+                "error"
+            }
+        };
+        self.metrics
+            .runs
+            .with_label_values(&[task.name(), status])
+            .inc();
+        self.metrics
+            .duration
+            .with_label_values(&[task.name()])
+            .observe(run.duration.as_secs_f64());
+        self.last_runs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(task, run.clone());
+        run
+    }
+
+    /// Run every task once in each occurrence of the windows, until the task is dropped
+    pub async fn run(self: Arc<Self>) {
+        if self.windows.is_empty() {
+            return;
+        }
+        info!(
+            "Storage maintenance scheduled in {} window(s) for {:?}",
+            self.windows.len(),
+            self.tasks()
+        );
+        let mut served = None;
+        let mut interval = tokio::time::interval(MAINTENANCE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            let (day, minute) = (now / 86_400, ((now % 86_400) / 60) as u32 % MINUTES_PER_DAY);
+            let Some((index, window)) = self
+                .windows
+                .iter()
+                .enumerate()
+                .find(|(_, window)| window.contains(minute))
+            else {
+                continue;
+            };
+            let occurrence = (index, window.occurrence(day, minute));
+            if served == Some(occurrence) {
+                continue;
+            }
+            // An operator triggered run is in progress, retry on the next check
+            let Ok(_running) = self.running.try_lock() else {
+                continue;
+            };
+            served = Some(occurrence);
+            for task in self.tasks() {
+                self.run_task(task, false).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::u256::U256;
+use moveos_store::MoveOSStore;
+use moveos_types::h256::H256;
+use moveos_types::moveos_std::account::Account;
+use moveos_types::state::{FieldKey, MoveStructType, ObjectState};
+use moveos_types::state_resolver::StatelessResolver;
+use rooch_types::framework::coin_store::CoinStore;
+use std::collections::HashMap;
+
+const LIST_PAGE_SIZE: usize = 1000;
+
+/// A coin balance of an account before and after, None if the coin store did not exist
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub owner: AccountAddress,
+    pub coin_type: String,
+    pub before: Option<U256>,
+    pub after: Option<U256>,
+}
+
+/// Top level objects that differ between two state roots
+#[derive(Clone, Debug, Default)]
+pub struct StateDiff {
+    pub added: Vec<ObjectState>,
+    pub removed: Vec<ObjectState>,
+    pub changed: Vec<(ObjectState, ObjectState)>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Accounts created between the two roots
+    pub fn added_accounts(&self) -> Vec<AccountAddress> {
+        accounts(&self.added)
+    }
+
+    /// Accounts removed between the two roots
+    pub fn removed_accounts(&self) -> Vec<AccountAddress> {
+        accounts(&self.removed)
+    }
+
+    /// Balance changes of all coin stores that were added, removed or changed
+    pub fn balance_changes(&self) -> Vec<BalanceChange> {
+        let mut changes = vec![];
+        for state in &self.added {
+            if let Some((owner, coin_type, balance)) = coin_balance(state) {
+                changes.push(BalanceChange {
+                    owner,
+                    coin_type,
+                    before: None,
+                    after: Some(balance),
+                });
+            }
+        }
+        for state in &self.removed {
+            if let Some((owner, coin_type, balance)) = coin_balance(state) {
+                changes.push(BalanceChange {
+                    owner,
+                    coin_type,
+                    before: Some(balance),
+                    after: None,
+                });
+            }
+        }
+        for (before, after) in &self.changed {
+            if let (Some((owner, coin_type, before)), Some((_, _, after))) =
+                (coin_balance(before), coin_balance(after))
+            {
+                if before != after {
+                    changes.push(BalanceChange {
+                        owner,
+                        coin_type,
+                        before: Some(before),
+                        after: Some(after),
+                    });
+                }
+            }
+        }
+        changes
+    }
+}
+
+fn accounts(states: &[ObjectState]) -> Vec<AccountAddress> {
+    states
+        .iter()
+        .filter(|state| state.metadata.object_type == Account::type_tag())
+        .map(|state| state.metadata.owner)
+        .collect()
+}
+
+/// Owner, coin type and balance of a `CoinStore` object
+pub fn coin_balance(state: &ObjectState) -> Option<(AccountAddress, String, U256)> {
+    if state.metadata.object_type != CoinStore::type_tag() {
+        return None;
+    }
+    let coin_store: CoinStore = bcs::from_bytes(&state.value).ok()?;
+    Some((
+        state.metadata.owner,
+        coin_store.coin_type(),
+        coin_store.balance(),
+    ))
+}
+
+/// Visit the fields under a state root page by page, in key order, without
+/// loading the whole state in memory
+pub fn for_each_field(
+    store: &MoveOSStore,
+    state_root: H256,
+    mut f: impl FnMut(FieldKey, ObjectState) -> Result<()>,
+) -> Result<()> {
+    let mut cursor = None;
+    loop {
+        let page = store.list_fields_at(state_root, cursor, LIST_PAGE_SIZE)?;
+        let page_len = page.len();
+        cursor = page.last().map(|(key, _)| *key);
+        for (key, state) in page {
+            f(key, state)?;
+        }
+        if page_len < LIST_PAGE_SIZE {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// List all top level objects under a state root
+pub fn list_root_objects(
+    store: &MoveOSStore,
+    state_root: H256,
+) -> Result<HashMap<FieldKey, ObjectState>> {
+    let mut objects = HashMap::new();
+    for_each_field(store, state_root, |key, state| {
+        objects.insert(key, state);
+        Ok(())
+    })?;
+    Ok(objects)
+}
+
+/// Compare the top level objects of two state roots, which may live in different stores
+pub fn diff_state_roots(
+    store_a: &MoveOSStore,
+    root_a: H256,
+    store_b: &MoveOSStore,
+    root_b: H256,
+) -> Result<StateDiff> {
+    let mut diff = StateDiff::default();
+    if root_a == root_b {
+        return Ok(diff);
+    }
+
+    let objects_a = list_root_objects(store_a, root_a)?;
+    let mut objects_b = list_root_objects(store_b, root_b)?;
+    for (key, state_a) in objects_a {
+        match objects_b.remove(&key) {
+            Some(state_b) if state_b != state_a => diff.changed.push((state_a, state_b)),
+            Some(_) => {}
+            None => diff.removed.push(state_a),
+        }
+    }
+    diff.added.extend(objects_b.into_values());
+
+    let by_id = |state: &ObjectState| state.metadata.id.to_string();
+    diff.added.sort_by_key(by_id);
+    diff.removed.sort_by_key(by_id);
+    diff.changed.sort_by_key(|(state, _)| by_id(state));
+    Ok(diff)
+}
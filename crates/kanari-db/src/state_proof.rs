@@ -0,0 +1,124 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow};
+use moveos_store::MoveOSStore;
+use moveos_types::h256::H256;
+use moveos_types::moveos_std::object::ObjectID;
+use moveos_types::state::{FieldKey, ObjectState};
+use smt::SparseMerkleProof;
+
+/// One level of a state proof: the state under `key` in the tree whose root is
+/// `state_root`, with the sparse Merkle proof of its inclusion, or of its absence if the
+/// state is None
+#[derive(Clone, Debug)]
+pub struct StateProofStep {
+    pub state_root: H256,
+    pub key: FieldKey,
+    pub state: Option<ObjectState>,
+    pub proof: SparseMerkleProof,
+}
+
+/// Prove the field `key` of the object `parent` under `state_root`. The steps go from
+/// the top level object down to the field: each proves an object of the path against the
+/// root of the previous one, the first against `state_root`. A missing object ends the
+/// steps with the proof of its absence.
+pub fn prove_state(
+    store: &MoveOSStore,
+    state_root: H256,
+    parent: ObjectID,
+    key: FieldKey,
+) -> Result<Vec<StateProofStep>> {
+    // Ancestors of the field below the root object, from the field up
+    let mut keys = vec![key];
+    let mut id = parent;
+    while id != ObjectID::root() {
+        keys.push(id.field_key());
+        id = id
+            .parent()
+            .ok_or_else(|| anyhow!("Object {} has no parent", id))?;
+    }
+
+    let mut steps = vec![];
+    let mut root = state_root;
+    for key in keys.into_iter().rev() {
+        let (state, proof) = store.get_state_store().get_with_proof(root, key)?;
+        let next_root = state.as_ref().map(|state| state.metadata.state_root());
+        steps.push(StateProofStep {
+            state_root: root,
+            key,
+            state,
+            proof,
+        });
+        match next_root {
+            Some(next_root) => root = next_root,
+            None => break,
+        }
+    }
+    Ok(steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RoochDB;
+    use kanari_config::KanariOpt;
+    use kanari_types::block::Block;
+    use kanari_types::kari_coin::KARI;
+    use kanari_types::reward::RewardPayment;
+    use kanari_types::system_transaction::SystemTransaction;
+    use move_core_types::account_address::AccountAddress;
+    use moveos_types::state::MoveStructType;
+    use rooch_types::framework::account_coin_store::AccountCoinStoreModule;
+
+    #[test]
+    fn test_proofs_verify_against_the_block_header() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = RoochDB::init(&opt.store, &prometheus::Registry::new()).unwrap();
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        let reward = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: alice,
+                recipient: alice,
+                amount: 1_000,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        let execution = db.execute_block(1, &[reward]).unwrap();
+        let block = Block::new(
+            1,
+            1,
+            H256::zero(),
+            H256::zero(),
+            H256::zero(),
+            execution.root.state_root(),
+        );
+        db.save_block(&block, 1_700_000_000).unwrap();
+        db.commit_block_state(1, &execution.root).unwrap();
+
+        let header_root = db.get_block(1).unwrap().unwrap().state_root;
+        let prove = |address: AccountAddress| {
+            let id = AccountCoinStoreModule::account_coin_store_id(address, KARI::struct_tag());
+            let steps = prove_state(
+                &db.moveos_store,
+                header_root,
+                id.parent().unwrap(),
+                id.field_key(),
+            )
+            .unwrap();
+            assert_eq!(steps[0].state_root, header_root);
+            for step in &steps {
+                step.proof
+                    .verify(step.state_root, step.key, step.state.clone())
+                    .unwrap();
+            }
+            steps.last().unwrap().state.clone()
+        };
+        // Alice's coin store is proven present, Bob's absent
+        assert!(prove(alice).is_some());
+        assert!(prove(bob).is_none());
+    }
+}
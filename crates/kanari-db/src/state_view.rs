@@ -0,0 +1,154 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::u256::U256;
+use moveos_store::MoveOSStore;
+use moveos_types::access_path::AccessPath;
+use moveos_types::move_std::string::MoveString;
+use moveos_types::moveos_std::account::Account;
+use moveos_types::moveos_std::module_store::Package;
+use moveos_types::moveos_std::move_module::MoveModule;
+use moveos_types::moveos_std::object::{ObjectID, ObjectMeta};
+use moveos_types::state::{FieldKey, ObjectState};
+use moveos_types::state_resolver::{RootObjectResolver, StateReader};
+use rooch_types::framework::account_coin_store::AccountCoinStoreModule;
+use rooch_types::framework::coin_store::CoinStore;
+
+/// Modules read from a package at once when listing it
+const MODULE_LIST_PAGE_SIZE: usize = 256;
+
+/// The bytecode of a module field of a package object
+fn module_bytecode(state: &ObjectState) -> Result<Vec<u8>> {
+    Ok(state
+        .value_as_df::<MoveString, MoveModule>()?
+        .value
+        .byte_codes)
+}
+
+/// Reads against one state root, the latest one or the one after a block. A state
+/// without root yet, before genesis, holds nothing.
+pub struct StateView<'a> {
+    store: &'a MoveOSStore,
+    root: Option<ObjectMeta>,
+}
+
+impl<'a> StateView<'a> {
+    pub fn new(store: &'a MoveOSStore, root: Option<ObjectMeta>) -> Self {
+        Self { store, root }
+    }
+
+    /// The root object the reads are answered against
+    pub fn root(&self) -> Option<&ObjectMeta> {
+        self.root.as_ref()
+    }
+
+    fn resolver(&self) -> Option<RootObjectResolver<'a, MoveOSStore>> {
+        self.root
+            .clone()
+            .map(|root| RootObjectResolver::new(root, self.store))
+    }
+
+    /// The object `id`, None if it does not exist
+    pub fn get_object(&self, id: ObjectID) -> Result<Option<ObjectState>> {
+        let Some(resolver) = self.resolver() else {
+            return Ok(None);
+        };
+        Ok(resolver
+            .get_states(AccessPath::objects(vec![id]))?
+            .into_iter()
+            .flatten()
+            .next())
+    }
+
+    /// The account object of `address`, None if the account was not created
+    pub fn get_account(&self, address: AccountAddress) -> Result<Option<Account>> {
+        self.get_object(Account::account_object_id(address))?
+            .map(|state| bcs::from_bytes::<Account>(&state.value))
+            .transpose()
+            .map_err(|e| anyhow!("Invalid account object of {}: {}", address, e))
+    }
+
+    /// Up to `limit` fields of the object `parent`, in key order after `cursor`. Fields
+    /// of the root object are the top level objects.
+    pub fn list_object_fields(
+        &self,
+        parent: ObjectID,
+        cursor: Option<FieldKey>,
+        limit: usize,
+    ) -> Result<Vec<(FieldKey, ObjectState)>> {
+        let Some(resolver) = self.resolver() else {
+            return Ok(vec![]);
+        };
+        resolver.list_states(AccessPath::fields_without_keys(parent), cursor, limit)
+    }
+
+    /// The bytecode of a module, read from its package in the module store. None if the
+    /// module is not published.
+    pub fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>> {
+        let Some(resolver) = self.resolver() else {
+            return Ok(None);
+        };
+        resolver
+            .get_states(AccessPath::module(module_id))?
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|state| module_bytecode(&state))
+            .transpose()
+            .map_err(|e| anyhow!("Invalid module {}: {}", module_id.short_str_lossless(), e))
+    }
+
+    /// The modules of the package published at `address`, as name and bytecode. Empty if
+    /// no package is published there.
+    pub fn list_modules(&self, address: AccountAddress) -> Result<Vec<(String, Vec<u8>)>> {
+        let Some(resolver) = self.resolver() else {
+            return Ok(vec![]);
+        };
+        let package_id = Package::package_id(&address);
+        let mut modules = vec![];
+        let mut cursor = None;
+        loop {
+            let page = resolver.list_states(
+                AccessPath::fields_without_keys(package_id.clone()),
+                cursor,
+                MODULE_LIST_PAGE_SIZE,
+            )?;
+            let page_len = page.len();
+            cursor = page.last().map(|(key, _)| *key);
+            for (_, state) in page {
+                let field = state.value_as_df::<MoveString, MoveModule>()?;
+                modules.push((field.name.to_string(), field.value.byte_codes));
+            }
+            if page_len < MODULE_LIST_PAGE_SIZE {
+                break;
+            }
+        }
+        modules.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(modules)
+    }
+
+    /// The balance of `coin_type` held by `address`, read from its account coin store.
+    /// None if the account never held the coin.
+    pub fn get_coin_balance(
+        &self,
+        address: AccountAddress,
+        coin_type: &StructTag,
+    ) -> Result<Option<U256>> {
+        let id = AccountCoinStoreModule::account_coin_store_id(address, coin_type.clone());
+        self.get_object(id)?
+            .map(|state| bcs::from_bytes::<CoinStore>(&state.value))
+            .transpose()
+            .map(|coin_store| coin_store.map(|coin_store| coin_store.balance()))
+            .map_err(|e| {
+                anyhow!(
+                    "Invalid {} coin store of {}: {}",
+                    coin_type.to_canonical_string(),
+                    address,
+                    e
+                )
+            })
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use kanari_rpc_api::jsonrpsee::types::ErrorObjectOwned;
+use kanari_rpc_api::{KanariRpcApiServer, KanariRpcImpl, api};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+/// Types and service generated from `proto/kanari/v1/node.proto`
+pub mod proto {
+    tonic::include_proto!("kanari.v1");
+}
+
+use proto::node_service_server::{NodeService, NodeServiceServer};
+
+/// gRPC status of a JSON-RPC error, see `RPC_ERROR_CODES`
+fn status(error: ErrorObjectOwned) -> Status {
+    let message = error.message().to_string();
+    match error.code() {
+        -32700 | -32600 | -32602 | -32008 => Status::invalid_argument(message),
+        -32601 => Status::unimplemented(message),
+        -32002 | -32003 => Status::not_found(message),
+        -32000 | -32004 => Status::unavailable(message),
+        -32001 | -32005 | -32009 | -32010 | -32011 => Status::failed_precondition(message),
+        -32006 => Status::resource_exhausted(message),
+        -32007 => Status::unauthenticated(message),
+        _ => Status::internal(message),
+    }
+}
+
+/// Block numbers are u128 on chain, uint64 on the wire
+fn block_number(number: u128) -> Result<u64, Status> {
+    u64::try_from(number)
+        .map_err(|_| Status::out_of_range(format!("Block number {} exceeds uint64", number)))
+}
+
+impl TryFrom<api::BlockInfo> for proto::Block {
+    type Error = Status;
+
+    fn try_from(block: api::BlockInfo) -> Result<Self, Status> {
+        Ok(Self {
+            number: block_number(block.number)?,
+            hash: block.hash,
+            parent_hash: block.parent_hash,
+            timestamp: block.timestamp,
+            transaction_count: block.transaction_count as u64,
+            gas_used: block.gas_used,
+            gas_limit: block.gas_limit,
+            state_root: block.state_root,
+        })
+    }
+}
+
+impl TryFrom<api::TransactionInfo> for proto::Transaction {
+    type Error = Status;
+
+    fn try_from(tx: api::TransactionInfo) -> Result<Self, Status> {
+        Ok(Self {
+            hash: tx.hash,
+            sender: tx.sender,
+            recipient: tx.recipient,
+            amount: tx.amount,
+            gas_used: tx.gas_used,
+            gas_price: tx.gas_price,
+            status: tx.status,
+            block_number: tx.block_number.map(block_number).transpose()?,
+            timestamp: tx.timestamp,
+            system_kind: tx.system_kind,
+        })
+    }
+}
+
+/// The gRPC node service. Calls are forwarded to the JSON-RPC implementation, which
+/// shares the node state, pool and database handles of the JSON-RPC server.
+pub struct KanariGrpcService {
+    kanari: Arc<KanariRpcImpl>,
+}
+
+impl KanariGrpcService {
+    pub fn new(kanari: KanariRpcImpl) -> Self {
+        Self {
+            kanari: Arc::new(kanari),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl NodeService for KanariGrpcService {
+    async fn get_node_info(
+        &self,
+        _request: Request<proto::GetNodeInfoRequest>,
+    ) -> Result<Response<proto::NodeInfo>, Status> {
+        let info = self.kanari.get_node_info().await.map_err(status)?;
+        Ok(Response::new(proto::NodeInfo {
+            version: info.version,
+            chain_id: info.chain_id,
+            genesis_hash: info.genesis_hash,
+            node_type: info.node_type,
+            peer_count: info.peer_count as u64,
+            block_height: block_number(info.block_height)?,
+            is_syncing: info.is_syncing,
+            uptime_seconds: info.uptime_seconds,
+        }))
+    }
+
+    async fn get_block_by_number(
+        &self,
+        request: Request<proto::GetBlockByNumberRequest>,
+    ) -> Result<Response<proto::Block>, Status> {
+        let number = request.into_inner().number as u128;
+        let block = self
+            .kanari
+            .get_block_by_number(number)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(block.try_into()?))
+    }
+
+    async fn get_latest_block(
+        &self,
+        _request: Request<proto::GetLatestBlockRequest>,
+    ) -> Result<Response<proto::Block>, Status> {
+        let block = self.kanari.get_latest_block().await.map_err(status)?;
+        Ok(Response::new(block.try_into()?))
+    }
+
+    async fn get_account(
+        &self,
+        request: Request<proto::GetAccountRequest>,
+    ) -> Result<Response<proto::Account>, Status> {
+        let account = self
+            .kanari
+            .get_account(request.into_inner().address, None)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(proto::Account {
+            address: account.address,
+            balance: account.balance,
+            sequence_number: account.sequence_number,
+        }))
+    }
+
+    async fn get_balance(
+        &self,
+        request: Request<proto::GetBalanceRequest>,
+    ) -> Result<Response<proto::Balance>, Status> {
+        let request = request.into_inner();
+        let balance = self
+            .kanari
+            .get_balance(request.address, request.coin_type, None)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(proto::Balance {
+            address: balance.address,
+            coin_type: balance.coin_type,
+            balance: balance.balance,
+            decimals: balance.decimals.map(u32::from),
+        }))
+    }
+
+    async fn get_transaction(
+        &self,
+        request: Request<proto::GetTransactionRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        let hash = request.into_inner().hash;
+        let tx = self
+            .kanari
+            .get_transaction(hash.clone())
+            .await
+            .map_err(status)?
+            .ok_or_else(|| Status::not_found(format!("Transaction {} not found", hash)))?;
+        Ok(Response::new(tx.try_into()?))
+    }
+
+    async fn send_raw_transaction(
+        &self,
+        request: Request<proto::SendRawTransactionRequest>,
+    ) -> Result<Response<proto::SendRawTransactionResponse>, Status> {
+        let tx_hash = self
+            .kanari
+            .send_raw_transaction(request.into_inner().raw_tx)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(proto::SendRawTransactionResponse { tx_hash }))
+    }
+}
+
+/// Serve the node service on `address` until `shutdown` completes
+pub async fn serve(
+    address: SocketAddr,
+    service: KanariGrpcService,
+    shutdown: impl Future<Output = ()>,
+) -> anyhow::Result<()> {
+    tracing::info!("Kanari gRPC server started on {}", address);
+    tonic::transport::Server::builder()
+        .add_service(NodeServiceServer::new(service))
+        .serve_with_shutdown(address, shutdown)
+        .await?;
+    tracing::info!("Kanari gRPC server stopped");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kanari_rpc_api::RpcError;
+
+    #[test]
+    fn test_conversions_and_status_codes() {
+        let block = api::BlockInfo {
+            number: 7,
+            hash: "0x07".to_string(),
+            parent_hash: "0x06".to_string(),
+            timestamp: 1_700_000_000,
+            transaction_count: 2,
+            gas_used: 0,
+            gas_limit: 1_000_000,
+            state_root: "0x00".to_string(),
+            fees: None,
+        };
+        let converted = proto::Block::try_from(block.clone()).unwrap();
+        assert_eq!((converted.number, converted.transaction_count), (7, 2));
+        let too_high = api::BlockInfo {
+            number: u64::MAX as u128 + 1,
+            ..block
+        };
+        assert_eq!(
+            proto::Block::try_from(too_high).unwrap_err().code(),
+            tonic::Code::OutOfRange
+        );
+
+        let not_found = status(RpcError::BlockNotFound("#7".to_string()).into());
+        assert_eq!(not_found.code(), tonic::Code::NotFound);
+        let invalid = status(RpcError::InvalidParams("bad".to_string()).into());
+        assert_eq!(invalid.code(), tonic::Code::InvalidArgument);
+    }
+}
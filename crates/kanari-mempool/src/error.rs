@@ -0,0 +1,106 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use moveos_types::h256::H256;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Why the pool refused a transaction. Serialized with a `reason` tag so wallets
+/// can tell a full queue from an underpriced transaction without parsing messages.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum MempoolRejection {
+    #[error("Transaction {hash:?} already in pool")]
+    AlreadyInPool { hash: H256 },
+
+    #[error(
+        "Transaction sequence number {sequence_number} is below the account sequence number {account_sequence_number}"
+    )]
+    SequenceNumberTooOld {
+        sequence_number: u64,
+        account_sequence_number: u64,
+    },
+
+    #[error(
+        "Replacement transaction gas price {gas_price} must be at least {min_gas_price} to replace a transaction paying {existing_gas_price}"
+    )]
+    ReplacementUnderpriced {
+        gas_price: u64,
+        existing_gas_price: u64,
+        min_gas_price: u64,
+    },
+
+    #[error("Sender {sender} has no pooled transaction with sequence number {sequence_number}")]
+    NothingToReplace {
+        sender: AccountAddress,
+        sequence_number: u64,
+    },
+
+    #[error("The node is shutting down and no longer accepts transactions")]
+    NotAccepting,
+
+    #[error("The node is in read-only mode because its disk is almost full")]
+    ReadOnly,
+
+    #[error("Transaction {hash:?} is a system transaction, which only the node can include")]
+    SystemTransaction { hash: H256 },
+
+    #[error("Transaction pool is full ({max_size} transactions)")]
+    PoolFull { max_size: usize },
+
+    #[error("Sender {sender} already has {pending} pending transactions, the limit is {limit}")]
+    SenderPendingCountExceeded {
+        sender: AccountAddress,
+        pending: usize,
+        limit: usize,
+    },
+
+    #[error(
+        "Sender {sender} already has {queued} queued transactions, the limit is {limit}, sequence number {sequence_number} leaves a nonce gap"
+    )]
+    SenderQueuedCountExceeded {
+        sender: AccountAddress,
+        sequence_number: u64,
+        queued: usize,
+        limit: usize,
+    },
+
+    #[error(
+        "Sender {sender} pending transactions would use {pending_bytes} bytes, the limit is {limit}"
+    )]
+    SenderPendingBytesExceeded {
+        sender: AccountAddress,
+        pending_bytes: usize,
+        limit: usize,
+    },
+
+    #[error(
+        "Gas price {gas_price} is below the minimum {min_gas_price} for a sender with {queue_depth} pending transactions"
+    )]
+    GasPriceTooLow {
+        gas_price: u64,
+        min_gas_price: u64,
+        queue_depth: usize,
+    },
+}
+
+impl MempoolRejection {
+    /// Stable machine readable reason, the same as the serialized `reason` tag
+    pub fn reason(&self) -> &'static str {
+        match self {
+            MempoolRejection::AlreadyInPool { .. } => "already_in_pool",
+            MempoolRejection::SequenceNumberTooOld { .. } => "sequence_number_too_old",
+            MempoolRejection::ReplacementUnderpriced { .. } => "replacement_underpriced",
+            MempoolRejection::NothingToReplace { .. } => "nothing_to_replace",
+            MempoolRejection::NotAccepting => "not_accepting",
+            MempoolRejection::ReadOnly => "read_only",
+            MempoolRejection::SystemTransaction { .. } => "system_transaction",
+            MempoolRejection::PoolFull { .. } => "pool_full",
+            MempoolRejection::SenderPendingCountExceeded { .. } => "sender_pending_count_exceeded",
+            MempoolRejection::SenderQueuedCountExceeded { .. } => "sender_queued_count_exceeded",
+            MempoolRejection::SenderPendingBytesExceeded { .. } => "sender_pending_bytes_exceeded",
+            MempoolRejection::GasPriceTooLow { .. } => "gas_price_too_low",
+        }
+    }
+}
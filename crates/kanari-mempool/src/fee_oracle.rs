@@ -0,0 +1,238 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+/// Number of recent blocks the fee oracle looks at
+pub const FEE_ORACLE_BLOCKS: u128 = 20;
+/// Block fullness, in basis points of the block gas limit, above which the base fee
+/// rises. It doubles when the recent blocks are full.
+pub const TARGET_BLOCK_FULLNESS_BPS: u64 = 5_000;
+
+const BPS_SCALE: u64 = 10_000;
+
+/// How soon a transaction should be included
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeTier {
+    Slow,
+    Normal,
+    Fast,
+}
+
+impl FeeTier {
+    /// Number of blocks within which a transaction of the tier should be included
+    pub fn target_blocks(self) -> u64 {
+        match self {
+            FeeTier::Slow => 10,
+            FeeTier::Normal => 3,
+            FeeTier::Fast => 1,
+        }
+    }
+
+    /// Percentile of the recently included gas prices the tier pays at least
+    fn percentile(self) -> usize {
+        match self {
+            FeeTier::Slow => 10,
+            FeeTier::Normal => 50,
+            FeeTier::Fast => 90,
+        }
+    }
+}
+
+/// Gas a recent block used out of its limit, and the gas prices of its user transactions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockFeeSample {
+    pub gas_used: u64,
+    pub gas_limit: u64,
+    pub gas_prices: Vec<u64>,
+}
+
+impl BlockFeeSample {
+    /// Share of its gas limit the block used
+    pub fn gas_used_ratio(&self) -> f64 {
+        if self.gas_limit == 0 {
+            return 0.0;
+        }
+        self.gas_used as f64 / self.gas_limit as f64
+    }
+
+    /// The gas price paid at each of `percentiles`, from 0 to 100, 0 for a block
+    /// without user transactions
+    pub fn gas_price_percentiles(&self, percentiles: &[f64]) -> Vec<u64> {
+        let mut prices = self.gas_prices.clone();
+        prices.sort_unstable();
+        percentiles
+            .iter()
+            .map(|percentile| match prices.len() {
+                0 => 0,
+                len => prices[((len - 1) as f64 * percentile / 100.0).round() as usize],
+            })
+            .collect()
+    }
+}
+
+/// Suggested gas price of a tier, as a base fee every transaction pays and a priority
+/// fee to be picked ahead of the mempool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeSuggestion {
+    pub tier: FeeTier,
+    pub base_fee: u64,
+    pub priority_fee: u64,
+}
+
+impl FeeSuggestion {
+    pub fn gas_price(&self) -> u64 {
+        self.base_fee.saturating_add(self.priority_fee)
+    }
+}
+
+/// Fee suggestions derived from recent block fullness and the mempool depth
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeEstimate {
+    /// Gas the recent blocks used, in basis points of their gas limit
+    pub block_fullness_bps: u64,
+    pub slow: FeeSuggestion,
+    pub normal: FeeSuggestion,
+    pub fast: FeeSuggestion,
+}
+
+/// Suggest gas prices for the next transactions. The base fee starts at the pool's
+/// minimum gas price and grows with the fullness of `recent_blocks` beyond the target.
+/// A tier pays at least its percentile of the recently included gas prices, and
+/// outbids the pending transactions, given as gas price and gas limit, that would
+/// fill its target blocks ahead of it.
+pub fn estimate_fees(
+    min_gas_price: u64,
+    block_gas_limit: u64,
+    recent_blocks: &[BlockFeeSample],
+    pending: &[(u64, u64)],
+) -> FeeEstimate {
+    let block_fullness_bps = block_fullness_bps(recent_blocks);
+    let base_fee = base_fee(min_gas_price, recent_blocks);
+
+    let mut recent_prices: Vec<u64> = recent_blocks
+        .iter()
+        .flat_map(|block| block.gas_prices.iter().copied())
+        .collect();
+    recent_prices.sort_unstable();
+    let mut pending = pending.to_vec();
+    pending.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+
+    let suggest = |tier: FeeTier| {
+        let recent = match recent_prices.len() {
+            0 => 0,
+            len => recent_prices[(len - 1) * tier.percentile() / 100],
+        };
+        // The price of the pending transaction that fills the target blocks, plus one
+        let capacity = tier.target_blocks() as u128 * block_gas_limit as u128;
+        let mut ahead = 0u128;
+        let clearing = pending
+            .iter()
+            .find(|(_, gas)| {
+                ahead += *gas as u128;
+                ahead >= capacity
+            })
+            .map_or(0, |(price, _)| price.saturating_add(1));
+        FeeSuggestion {
+            tier,
+            base_fee,
+            priority_fee: recent.max(clearing).saturating_sub(base_fee),
+        }
+    };
+    FeeEstimate {
+        block_fullness_bps,
+        slow: suggest(FeeTier::Slow),
+        normal: suggest(FeeTier::Normal),
+        fast: suggest(FeeTier::Fast),
+    }
+}
+
+/// Gas `blocks` used, in basis points of their gas limit
+fn block_fullness_bps(blocks: &[BlockFeeSample]) -> u64 {
+    let gas_used: u128 = blocks.iter().map(|b| b.gas_used as u128).sum();
+    let gas_limit: u128 = blocks.iter().map(|b| b.gas_limit as u128).sum();
+    if gas_limit == 0 {
+        return 0;
+    }
+    (gas_used * BPS_SCALE as u128 / gas_limit).min(BPS_SCALE as u128) as u64
+}
+
+/// The base fee after `recent_blocks`: the minimum gas price, raised with their
+/// fullness beyond the target
+pub fn base_fee(min_gas_price: u64, recent_blocks: &[BlockFeeSample]) -> u64 {
+    let excess_bps = block_fullness_bps(recent_blocks).saturating_sub(TARGET_BLOCK_FULLNESS_BPS);
+    min_gas_price.saturating_add(
+        (min_gas_price as u128 * excess_bps as u128 * 2).div_ceil(BPS_SCALE as u128) as u64,
+    )
+}
+
+/// The base fee of each block of `blocks` from index `first`, and of the block after
+/// them, each computed over the `FEE_ORACLE_BLOCKS` blocks before it
+pub fn base_fee_history(min_gas_price: u64, blocks: &[BlockFeeSample], first: usize) -> Vec<u64> {
+    (first..=blocks.len())
+        .map(|index| {
+            let from = index.saturating_sub(FEE_ORACLE_BLOCKS as usize);
+            base_fee(min_gas_price, &blocks[from..index])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fees_follow_fullness_and_mempool_depth() {
+        // Idle chain and empty pool: every tier pays the minimum
+        let idle = estimate_fees(10, 1_000, &[], &[]);
+        assert_eq!(idle.block_fullness_bps, 0);
+        assert_eq!(idle.fast.gas_price(), 10);
+        assert_eq!(idle.slow.priority_fee, 0);
+
+        // Full blocks double the base fee
+        let full = BlockFeeSample {
+            gas_used: 1_000,
+            gas_limit: 1_000,
+            gas_prices: vec![10, 20, 30, 40, 50],
+        };
+        let estimate = estimate_fees(10, 1_000, &[full.clone(), full], &[]);
+        assert_eq!(estimate.block_fullness_bps, 10_000);
+        assert_eq!(estimate.normal.base_fee, 20);
+        assert_eq!(estimate.slow.gas_price(), 20);
+        assert_eq!(estimate.normal.gas_price(), 30);
+        assert_eq!(estimate.fast.gas_price(), 50);
+
+        // Two blocks of pending gas: fast must outbid the first block, normal and slow
+        // fit behind it
+        let pending = vec![(100, 600), (80, 600), (60, 800)];
+        let estimate = estimate_fees(10, 1_000, &[], &pending);
+        assert_eq!(estimate.fast.gas_price(), 81);
+        assert_eq!(estimate.normal.gas_price(), 10);
+    }
+
+    #[test]
+    fn test_fee_history_of_blocks() {
+        let empty = BlockFeeSample {
+            gas_used: 0,
+            gas_limit: 1_000,
+            gas_prices: vec![],
+        };
+        let full = BlockFeeSample {
+            gas_used: 1_000,
+            gas_limit: 1_000,
+            gas_prices: vec![50, 10, 30, 20, 40],
+        };
+        assert_eq!(full.gas_used_ratio(), 1.0);
+        assert_eq!(
+            full.gas_price_percentiles(&[0.0, 50.0, 100.0]),
+            vec![10, 30, 50]
+        );
+        assert_eq!(empty.gas_price_percentiles(&[50.0]), vec![0]);
+
+        // Each base fee looks at the blocks before it, the last one is the next block's
+        let blocks = vec![empty.clone(), full.clone(), full];
+        assert_eq!(base_fee_history(10, &blocks, 1), vec![10, 10, 14]);
+        assert_eq!(
+            *base_fee_history(10, &blocks, 0).last().unwrap(),
+            estimate_fees(10, 1_000, &blocks, &[]).normal.base_fee
+        );
+    }
+}
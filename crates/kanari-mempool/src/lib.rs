@@ -0,0 +1,17 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod error;
+pub mod fee_oracle;
+pub mod pool;
+
+pub use error::MempoolRejection;
+pub use fee_oracle::{
+    BlockFeeSample, FEE_ORACLE_BLOCKS, FeeEstimate, FeeSuggestion, FeeTier, base_fee,
+    base_fee_history, estimate_fees,
+};
+pub use pool::{
+    DEFAULT_FEE_BUMP_DEPTH, DEFAULT_MAX_PENDING_BYTES_PER_SENDER, DEFAULT_MAX_PENDING_PER_SENDER,
+    DEFAULT_MAX_POOL_SIZE, DEFAULT_MAX_QUEUED_PER_SENDER, DEFAULT_MIN_GAS_PRICE,
+    DEFAULT_MIN_REPLACEMENT_BUMP_PCT, DEFAULT_QUEUED_TTL, MempoolLimits, PooledTransaction, TxPool,
+};
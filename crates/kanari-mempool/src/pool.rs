@@ -0,0 +1,880 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::MempoolRejection;
+use kanari_types::transaction::SignedTransaction;
+use move_core_types::account_address::AccountAddress;
+use moveos_types::h256::H256;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::instrument;
+
+/// Maximum number of transactions kept in the pool
+pub const DEFAULT_MAX_POOL_SIZE: usize = 10_000;
+
+/// Maximum number of pending transactions per sender
+pub const DEFAULT_MAX_PENDING_PER_SENDER: usize = 32;
+/// Maximum total encoded size of a sender's pending transactions
+pub const DEFAULT_MAX_PENDING_BYTES_PER_SENDER: usize = 512 * 1024;
+/// Queue depth from which the minimum gas price of a sender doubles with each new transaction
+pub const DEFAULT_FEE_BUMP_DEPTH: usize = 16;
+/// Minimum gas price of a transaction that does not trigger fee bumping
+pub const DEFAULT_MIN_GAS_PRICE: u64 = 1;
+/// Maximum number of nonce gapped transactions held per sender
+pub const DEFAULT_MAX_QUEUED_PER_SENDER: usize = 16;
+/// How long a nonce gapped transaction is held for its gap to fill
+pub const DEFAULT_QUEUED_TTL: Duration = Duration::from_secs(600);
+/// Percentage a replacement must raise the gas price of the transaction it replaces by
+pub const DEFAULT_MIN_REPLACEMENT_BUMP_PCT: u64 = 10;
+
+/// Per-sender spam protection rules
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MempoolLimits {
+    pub max_pending_per_sender: usize,
+    pub max_pending_bytes_per_sender: usize,
+    pub fee_bump_depth: usize,
+    pub min_gas_price: u64,
+    /// Nonce gapped transactions held in the future queue of a sender
+    pub max_queued_per_sender: usize,
+    /// Queued transactions older than this are dropped
+    pub queued_ttl: Duration,
+    /// Percentage a replacement must raise the gas price by
+    pub min_replacement_bump_pct: u64,
+}
+
+impl Default for MempoolLimits {
+    fn default() -> Self {
+        Self {
+            max_pending_per_sender: DEFAULT_MAX_PENDING_PER_SENDER,
+            max_pending_bytes_per_sender: DEFAULT_MAX_PENDING_BYTES_PER_SENDER,
+            fee_bump_depth: DEFAULT_FEE_BUMP_DEPTH,
+            min_gas_price: DEFAULT_MIN_GAS_PRICE,
+            max_queued_per_sender: DEFAULT_MAX_QUEUED_PER_SENDER,
+            queued_ttl: DEFAULT_QUEUED_TTL,
+            min_replacement_bump_pct: DEFAULT_MIN_REPLACEMENT_BUMP_PCT,
+        }
+    }
+}
+
+impl MempoolLimits {
+    /// Minimum gas price of a new transaction from a sender that already has
+    /// `queue_depth` pending transactions. It doubles for every transaction
+    /// queued at or beyond `fee_bump_depth`.
+    pub fn min_gas_price_at(&self, queue_depth: usize) -> u64 {
+        if queue_depth < self.fee_bump_depth {
+            return self.min_gas_price;
+        }
+        let doublings = (queue_depth - self.fee_bump_depth + 1).min(63) as u32;
+        self.min_gas_price.saturating_mul(1u64 << doublings)
+    }
+
+    /// Minimum gas price of a transaction replacing one paying `existing_gas_price`,
+    /// always above it
+    pub fn min_replacement_gas_price(&self, existing_gas_price: u64) -> u64 {
+        let bumped = (existing_gas_price as u128 * (100 + self.min_replacement_bump_pct as u128))
+            .div_ceil(100)
+            .min(u64::MAX as u128) as u64;
+        bumped.max(existing_gas_price.saturating_add(1))
+    }
+}
+
+/// A transaction waiting in the pool with its precomputed hash and encoded size
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PooledTransaction {
+    pub hash: H256,
+    pub size: usize,
+    pub tx: SignedTransaction,
+    /// When the pool accepted the transaction
+    pub received_at: Instant,
+}
+
+impl PooledTransaction {
+    pub fn new(tx: SignedTransaction) -> Self {
+        Self {
+            hash: tx.hash(),
+            size: tx.encode().len(),
+            tx,
+            received_at: Instant::now(),
+        }
+    }
+
+    pub fn sender(&self) -> AccountAddress {
+        self.tx.tx.sender
+    }
+
+    pub fn sequence_number(&self) -> u64 {
+        self.tx.tx.sequence_number
+    }
+
+    pub fn gas_price(&self) -> u64 {
+        self.tx.tx.gas_price
+    }
+}
+
+/// In-memory pool of transactions, indexed by sender and sequence number. The
+/// pending transactions of a sender form a gapless run from its next executable
+/// sequence number, transactions beyond a nonce gap wait in its future queue until
+/// the gap fills or they expire.
+#[derive(Debug)]
+pub struct TxPool {
+    by_sender: HashMap<AccountAddress, BTreeMap<u64, PooledTransaction>>,
+    queued: HashMap<AccountAddress, BTreeMap<u64, PooledTransaction>>,
+    by_hash: HashMap<H256, (AccountAddress, u64)>,
+    /// Next executable sequence number per sender, as known from committed state
+    account_nonces: HashMap<AccountAddress, u64>,
+    max_size: usize,
+    limits: MempoolLimits,
+    /// Set while the node drains before shutdown
+    closed: bool,
+    /// Set while the node is in emergency read-only mode
+    read_only: bool,
+}
+
+impl Default for TxPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_POOL_SIZE)
+    }
+}
+
+impl TxPool {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            by_sender: HashMap::new(),
+            queued: HashMap::new(),
+            by_hash: HashMap::new(),
+            account_nonces: HashMap::new(),
+            max_size,
+            limits: MempoolLimits::default(),
+            closed: false,
+            read_only: false,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: MempoolLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn limits(&self) -> &MempoolLimits {
+        &self.limits
+    }
+
+    /// Stop accepting new transactions, e.g. while the node shuts down
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Refuse new transactions while the node's disk is almost full. Transactions
+    /// already in the pool are kept.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Write all pending and queued transactions to `path`, so they survive a restart
+    pub fn save(&self, path: &Path) -> anyhow::Result<usize> {
+        let txs: Vec<&SignedTransaction> = self
+            .by_sender
+            .values()
+            .chain(self.queued.values())
+            .flat_map(|txs| txs.values().map(|pooled| &pooled.tx))
+            .collect();
+        // Write then rename, so a crash never leaves a truncated file behind
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bcs::to_bytes(&txs)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(txs.len())
+    }
+
+    /// Re-add the transactions saved by `save`, skipping those the pool now
+    /// rejects. The nonce of each sender the pool does not know yet is first seeded
+    /// with `committed_nonce`. Returns the number of transactions restored.
+    pub fn load(
+        &mut self,
+        path: &Path,
+        committed_nonce: impl Fn(&AccountAddress) -> anyhow::Result<u64>,
+    ) -> anyhow::Result<usize> {
+        if !path.exists() {
+            return Ok(0);
+        }
+        let txs: Vec<SignedTransaction> = bcs::from_bytes(&std::fs::read(path)?)?;
+        let mut restored = 0;
+        for tx in txs {
+            if !self.account_nonces.contains_key(&tx.tx.sender) {
+                let nonce = committed_nonce(&tx.tx.sender)?;
+                self.set_account_nonce(tx.tx.sender, nonce);
+            }
+            match self.add_transaction(tx) {
+                Ok(_) => restored += 1,
+                Err(e) => tracing::debug!("Dropping saved transaction: {}", e),
+            }
+        }
+        Ok(restored)
+    }
+
+    /// Number of transactions in the pool
+    pub fn len(&self) -> usize {
+        self.by_hash.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_hash.is_empty()
+    }
+
+    /// Number of executable transactions in the pool
+    pub fn pending_len(&self) -> usize {
+        self.by_sender.values().map(|txs| txs.len()).sum()
+    }
+
+    /// Number of nonce gapped transactions in the pool
+    pub fn queued_len(&self) -> usize {
+        self.queued.values().map(|txs| txs.len()).sum()
+    }
+
+    /// Number of pending transactions of a sender
+    pub fn pending_count(&self, sender: &AccountAddress) -> usize {
+        self.by_sender.get(sender).map(|txs| txs.len()).unwrap_or(0)
+    }
+
+    /// Number of queued transactions of a sender
+    pub fn queued_count(&self, sender: &AccountAddress) -> usize {
+        self.queued.get(sender).map(|txs| txs.len()).unwrap_or(0)
+    }
+
+    /// Total encoded size of a sender's pending and queued transactions
+    pub fn pending_bytes(&self, sender: &AccountAddress) -> usize {
+        [&self.by_sender, &self.queued]
+            .iter()
+            .filter_map(|queue| queue.get(sender))
+            .flat_map(|txs| txs.values().map(|tx| tx.size))
+            .sum()
+    }
+
+    /// Executable transactions grouped by sender, each in sequence number order
+    pub fn pending_by_sender(&self) -> BTreeMap<AccountAddress, Vec<&PooledTransaction>> {
+        group_by_sender(&self.by_sender)
+    }
+
+    /// Nonce gapped transactions grouped by sender, each in sequence number order
+    pub fn queued_by_sender(&self) -> BTreeMap<AccountAddress, Vec<&PooledTransaction>> {
+        group_by_sender(&self.queued)
+    }
+
+    pub fn get(&self, hash: &H256) -> Option<&PooledTransaction> {
+        let (sender, sequence_number) = self.by_hash.get(hash)?;
+        self.by_sender
+            .get(sender)
+            .and_then(|txs| txs.get(sequence_number))
+            .or_else(|| self.queued.get(sender)?.get(sequence_number))
+    }
+
+    /// The next executable sequence number of a sender, if the pool knows it
+    pub fn account_nonce(&self, sender: &AccountAddress) -> Option<u64> {
+        self.account_nonces.get(sender).copied()
+    }
+
+    /// The next executable sequence number of a sender. One whose nonce was never
+    /// seeded is taken to be a new account, starting at 0.
+    fn next_sequence_number(&self, sender: &AccountAddress) -> u64 {
+        self.account_nonce(sender).unwrap_or(0)
+    }
+
+    /// Whether a new transaction with this sequence number extends the sender's
+    /// pending run, rather than leaving a nonce gap
+    fn is_executable(&self, sender: &AccountAddress, sequence_number: u64) -> bool {
+        let pending_len = self
+            .by_sender
+            .get(sender)
+            .map(|txs| txs.len() as u64)
+            .unwrap_or(0);
+        sequence_number == self.next_sequence_number(sender) + pending_len
+    }
+
+    /// Rebuild the pending run of a sender from its next executable sequence number,
+    /// promoting queued transactions whose gap filled and queueing those left behind
+    /// a new gap
+    fn rebalance(&mut self, sender: AccountAddress) {
+        let mut all = self.by_sender.remove(&sender).unwrap_or_default();
+        all.append(&mut self.queued.remove(&sender).unwrap_or_default());
+        if all.is_empty() {
+            return;
+        }
+        let mut next = self.next_sequence_number(&sender);
+        let mut pending = BTreeMap::new();
+        while let Some(tx) = all.remove(&next) {
+            pending.insert(next, tx);
+            next += 1;
+        }
+        if !pending.is_empty() {
+            self.by_sender.insert(sender, pending);
+        }
+        if !all.is_empty() {
+            self.queued.insert(sender, all);
+        }
+    }
+
+    /// Drop queued transactions that waited longer than the configured TTL for their
+    /// nonce gap to fill. Returns the number of dropped transactions.
+    pub fn prune_expired(&mut self) -> usize {
+        self.prune_expired_at(Instant::now())
+    }
+
+    fn prune_expired_at(&mut self, now: Instant) -> usize {
+        let ttl = self.limits.queued_ttl;
+        let mut dropped = 0;
+        self.queued.retain(|_, txs| {
+            txs.retain(|_, pooled| {
+                let expired = now.saturating_duration_since(pooled.received_at) > ttl;
+                if expired {
+                    self.by_hash.remove(&pooled.hash);
+                    dropped += 1;
+                }
+                !expired
+            });
+            !txs.is_empty()
+        });
+        dropped
+    }
+
+    pub fn contains(&self, hash: &H256) -> bool {
+        self.by_hash.contains_key(hash)
+    }
+
+    /// Add a transaction whose signature was already verified by the caller.
+    /// A transaction with the same sender and sequence number is replaced only
+    /// if the new one raises the gas price by `min_replacement_bump_pct`. New transactions are subject to
+    /// the per-sender limits and fee bumping of `MempoolLimits`, a transaction
+    /// beyond a nonce gap goes to the sender's future queue.
+    #[instrument(name = "mempool_admission", skip_all, fields(sender = %tx.tx.sender))]
+    pub fn add_transaction(&mut self, tx: SignedTransaction) -> Result<H256, MempoolRejection> {
+        let pooled = PooledTransaction::new(tx);
+        let hash = pooled.hash;
+        let sender = pooled.sender();
+        let sequence_number = pooled.sequence_number();
+
+        if self.closed {
+            return Err(MempoolRejection::NotAccepting);
+        }
+        if self.read_only {
+            return Err(MempoolRejection::ReadOnly);
+        }
+        if pooled.tx.is_system() {
+            return Err(MempoolRejection::SystemTransaction { hash });
+        }
+        if self.by_hash.contains_key(&hash) {
+            return Err(MempoolRejection::AlreadyInPool { hash });
+        }
+        if let Some(next) = self.account_nonces.get(&sender) {
+            if sequence_number < *next {
+                return Err(MempoolRejection::SequenceNumberTooOld {
+                    sequence_number,
+                    account_sequence_number: *next,
+                });
+            }
+        }
+
+        self.prune_expired();
+
+        let existing = self
+            .get_by_sequence_number(&sender, sequence_number)
+            .map(|existing| (existing.hash, existing.gas_price(), existing.size));
+        let pending_bytes = self.pending_bytes(&sender);
+        let queue = match existing {
+            Some((existing_hash, existing_gas_price, existing_size)) => {
+                let min_gas_price = self.limits.min_replacement_gas_price(existing_gas_price);
+                if pooled.gas_price() < min_gas_price {
+                    return Err(MempoolRejection::ReplacementUnderpriced {
+                        gas_price: pooled.gas_price(),
+                        existing_gas_price,
+                        min_gas_price,
+                    });
+                }
+                let pending_bytes = pending_bytes - existing_size + pooled.size;
+                if pending_bytes > self.limits.max_pending_bytes_per_sender {
+                    return Err(MempoolRejection::SenderPendingBytesExceeded {
+                        sender,
+                        pending_bytes,
+                        limit: self.limits.max_pending_bytes_per_sender,
+                    });
+                }
+                self.by_hash.remove(&existing_hash);
+                if self
+                    .queued
+                    .get(&sender)
+                    .is_some_and(|txs| txs.contains_key(&sequence_number))
+                {
+                    &mut self.queued
+                } else {
+                    &mut self.by_sender
+                }
+            }
+            None => {
+                if self.len() >= self.max_size {
+                    return Err(MempoolRejection::PoolFull {
+                        max_size: self.max_size,
+                    });
+                }
+                let executable = self.is_executable(&sender, sequence_number);
+                let queue_depth = if executable {
+                    let pending = self.pending_count(&sender);
+                    if pending >= self.limits.max_pending_per_sender {
+                        return Err(MempoolRejection::SenderPendingCountExceeded {
+                            sender,
+                            pending,
+                            limit: self.limits.max_pending_per_sender,
+                        });
+                    }
+                    pending
+                } else {
+                    let queued = self.queued_count(&sender);
+                    if queued >= self.limits.max_queued_per_sender {
+                        return Err(MempoolRejection::SenderQueuedCountExceeded {
+                            sender,
+                            sequence_number,
+                            queued,
+                            limit: self.limits.max_queued_per_sender,
+                        });
+                    }
+                    self.pending_count(&sender) + queued
+                };
+                if pending_bytes + pooled.size > self.limits.max_pending_bytes_per_sender {
+                    return Err(MempoolRejection::SenderPendingBytesExceeded {
+                        sender,
+                        pending_bytes: pending_bytes + pooled.size,
+                        limit: self.limits.max_pending_bytes_per_sender,
+                    });
+                }
+                let min_gas_price = self.limits.min_gas_price_at(queue_depth);
+                if pooled.gas_price() < min_gas_price {
+                    return Err(MempoolRejection::GasPriceTooLow {
+                        gas_price: pooled.gas_price(),
+                        min_gas_price,
+                        queue_depth,
+                    });
+                }
+                if executable {
+                    &mut self.by_sender
+                } else {
+                    &mut self.queued
+                }
+            }
+        };
+
+        queue
+            .entry(sender)
+            .or_default()
+            .insert(sequence_number, pooled);
+        self.by_hash.insert(hash, (sender, sequence_number));
+        self.rebalance(sender);
+        Ok(hash)
+    }
+
+    /// The pooled transaction of a sender with this sequence number
+    pub fn get_by_sequence_number(
+        &self,
+        sender: &AccountAddress,
+        sequence_number: u64,
+    ) -> Option<&PooledTransaction> {
+        self.by_sender
+            .get(sender)
+            .and_then(|txs| txs.get(&sequence_number))
+            .or_else(|| self.queued.get(sender)?.get(&sequence_number))
+    }
+
+    /// Replace the pooled transaction with the sender and sequence number of `tx`,
+    /// returning the hashes of the replaced and the new transaction. Unlike
+    /// `add_transaction`, it fails if there is nothing to replace.
+    pub fn replace_transaction(
+        &mut self,
+        tx: SignedTransaction,
+    ) -> Result<(H256, H256), MempoolRejection> {
+        let sender = tx.tx.sender;
+        let sequence_number = tx.tx.sequence_number;
+        let Some(replaced) = self
+            .get_by_sequence_number(&sender, sequence_number)
+            .map(|existing| existing.hash)
+        else {
+            return Err(MempoolRejection::NothingToReplace {
+                sender,
+                sequence_number,
+            });
+        };
+        let hash = self.add_transaction(tx)?;
+        Ok((replaced, hash))
+    }
+
+    /// Drop the pooled transaction of a sender with this sequence number. The
+    /// sender's later transactions wait in its future queue until the gap fills.
+    pub fn cancel(
+        &mut self,
+        sender: &AccountAddress,
+        sequence_number: u64,
+    ) -> Option<PooledTransaction> {
+        let hash = self.get_by_sequence_number(sender, sequence_number)?.hash;
+        self.remove(&hash)
+    }
+
+    /// Remove a transaction, e.g. after it was included in a block
+    pub fn remove(&mut self, hash: &H256) -> Option<PooledTransaction> {
+        let (sender, sequence_number) = self.by_hash.remove(hash)?;
+        let removed = [&mut self.by_sender, &mut self.queued]
+            .into_iter()
+            .find_map(|queue| queue.get_mut(&sender)?.remove(&sequence_number));
+        self.rebalance(sender);
+        removed
+    }
+
+    /// Record the next executable sequence number of a sender after a block was
+    /// committed and drop transactions that can no longer execute
+    pub fn set_account_nonce(&mut self, sender: AccountAddress, next_sequence_number: u64) {
+        self.account_nonces.insert(sender, next_sequence_number);
+        for queue in [&mut self.by_sender, &mut self.queued] {
+            if let Some(txs) = queue.get_mut(&sender) {
+                let still_pending = txs.split_off(&next_sequence_number);
+                for stale in txs.values() {
+                    self.by_hash.remove(&stale.hash);
+                }
+                *txs = still_pending;
+            }
+        }
+        // Queued transactions right after the new nonce become executable
+        self.rebalance(sender);
+    }
+
+    /// Remove the user transactions of a committed block and advance their senders'
+    /// nonces past them, so later transactions become executable
+    pub fn remove_committed(&mut self, transactions: &[SignedTransaction]) {
+        let mut next_nonces = HashMap::new();
+        for tx in transactions.iter().filter(|tx| !tx.is_system()) {
+            self.remove(&tx.hash());
+            let next = next_nonces.entry(tx.tx.sender).or_insert(0);
+            *next = (*next).max(tx.tx.sequence_number + 1);
+        }
+        for (sender, next) in next_nonces {
+            self.set_account_nonce(sender, next);
+        }
+    }
+
+    /// Snapshot of executable transactions, highest gas price first, within the
+    /// given byte and count budgets. Transactions of a sender are always returned
+    /// as a gapless run in sequence number order, starting at the next executable
+    /// sequence number, so the snapshot can be executed as-is.
+    pub fn pending_snapshot(&self, max_bytes: usize, max_count: usize) -> Vec<PooledTransaction> {
+        let mut candidates = BinaryHeap::new();
+        for (sender, txs) in &self.by_sender {
+            let start = self.next_sequence_number(sender);
+            if let Some(head) = txs.get(&start) {
+                candidates.push((head.gas_price(), Reverse(*sender), start));
+            }
+        }
+
+        let mut snapshot = Vec::new();
+        let mut total_bytes = 0usize;
+        while snapshot.len() < max_count {
+            let (_, Reverse(sender), sequence_number) = match candidates.pop() {
+                Some(candidate) => candidate,
+                None => break,
+            };
+            let txs = &self.by_sender[&sender];
+            let pooled = &txs[&sequence_number];
+            if total_bytes + pooled.size > max_bytes {
+                // Skipping this transaction makes the rest of the sender's chain unexecutable
+                continue;
+            }
+            total_bytes += pooled.size;
+            snapshot.push(pooled.clone());
+
+            if let Some(next) = txs.get(&(sequence_number + 1)) {
+                candidates.push((next.gas_price(), Reverse(sender), sequence_number + 1));
+            }
+        }
+        snapshot
+    }
+}
+
+fn group_by_sender(
+    queue: &HashMap<AccountAddress, BTreeMap<u64, PooledTransaction>>,
+) -> BTreeMap<AccountAddress, Vec<&PooledTransaction>> {
+    queue
+        .iter()
+        .map(|(sender, txs)| (*sender, txs.values().collect()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kanari_types::transaction::KanariTransaction;
+
+    fn make_tx(sender: AccountAddress, sequence_number: u64, gas_price: u64) -> SignedTransaction {
+        SignedTransaction {
+            tx: KanariTransaction {
+                sender,
+                sequence_number,
+                chain_id: 1,
+                genesis_hash: H256::zero(),
+                recipient: None,
+                amount: 0,
+                gas_limit: 21_000,
+                gas_price,
+                data: vec![],
+                access_list: None,
+            },
+            public_key: vec![],
+            signature: vec![],
+        }
+    }
+
+    #[test]
+    fn test_snapshot_is_fee_ordered_and_nonce_consistent() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        let mut pool = TxPool::default();
+
+        pool.add_transaction(make_tx(alice, 0, 1)).unwrap();
+        pool.add_transaction(make_tx(alice, 1, 100)).unwrap();
+        pool.add_transaction(make_tx(bob, 0, 10)).unwrap();
+        // Gap: bob's nonce 1 is missing, so nonce 2 is not executable
+        pool.add_transaction(make_tx(bob, 2, 1000)).unwrap();
+
+        let snapshot = pool.pending_snapshot(usize::MAX, usize::MAX);
+        let order: Vec<(AccountAddress, u64)> = snapshot
+            .iter()
+            .map(|tx| (tx.sender(), tx.sequence_number()))
+            .collect();
+        assert_eq!(order, vec![(bob, 0), (alice, 0), (alice, 1)]);
+
+        let limited = pool.pending_snapshot(usize::MAX, 2);
+        assert_eq!(limited.len(), 2);
+
+        let single_tx_bytes = snapshot[0].size;
+        let by_bytes = pool.pending_snapshot(single_tx_bytes, usize::MAX);
+        assert_eq!(by_bytes.len(), 1);
+    }
+
+    #[test]
+    fn test_replacement_and_committed_nonce() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let mut pool = TxPool::new(2);
+
+        let first = pool.add_transaction(make_tx(alice, 0, 5)).unwrap();
+        assert!(pool.add_transaction(make_tx(alice, 0, 5)).is_err());
+        let replaced = pool.add_transaction(make_tx(alice, 0, 6)).unwrap();
+        assert!(!pool.contains(&first));
+        assert!(pool.contains(&replaced));
+
+        pool.add_transaction(make_tx(alice, 1, 5)).unwrap();
+        assert!(pool.add_transaction(make_tx(alice, 2, 5)).is_err());
+
+        pool.set_account_nonce(alice, 1);
+        assert_eq!(pool.len(), 1);
+        assert!(pool.add_transaction(make_tx(alice, 0, 50)).is_err());
+    }
+
+    #[test]
+    fn test_replace_and_cancel_by_sequence_number() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let mut pool = TxPool::default();
+        pool.set_account_nonce(alice, 0);
+        assert_eq!(pool.limits().min_replacement_gas_price(100), 110);
+        assert_eq!(pool.limits().min_replacement_gas_price(1), 2);
+
+        assert_eq!(
+            pool.replace_transaction(make_tx(alice, 0, 100))
+                .unwrap_err()
+                .reason(),
+            "nothing_to_replace"
+        );
+        let first = pool.add_transaction(make_tx(alice, 0, 100)).unwrap();
+        pool.add_transaction(make_tx(alice, 1, 100)).unwrap();
+        assert!(matches!(
+            pool.replace_transaction(make_tx(alice, 0, 109)),
+            Err(MempoolRejection::ReplacementUnderpriced {
+                min_gas_price: 110,
+                ..
+            })
+        ));
+        let (replaced, bumped) = pool.replace_transaction(make_tx(alice, 0, 110)).unwrap();
+        assert_eq!(replaced, first);
+        assert_eq!(pool.get_by_sequence_number(&alice, 0).unwrap().hash, bumped);
+
+        // Cancelling the head of the run queues the rest behind the gap
+        assert_eq!(pool.cancel(&alice, 0).unwrap().hash, bumped);
+        assert!(pool.cancel(&alice, 0).is_none());
+        assert_eq!((pool.pending_len(), pool.queued_len()), (0, 1));
+    }
+
+    #[test]
+    fn test_per_sender_limits_and_fee_bumping() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        let mut pool = TxPool::default().with_limits(MempoolLimits {
+            max_pending_per_sender: 4,
+            max_pending_bytes_per_sender: usize::MAX,
+            fee_bump_depth: 2,
+            min_gas_price: 1,
+            ..MempoolLimits::default()
+        });
+
+        pool.add_transaction(make_tx(alice, 0, 1)).unwrap();
+        pool.add_transaction(make_tx(alice, 1, 1)).unwrap();
+        // The third pending transaction must pay 2x the minimum, the fourth 4x
+        assert_eq!(
+            pool.add_transaction(make_tx(alice, 2, 1)),
+            Err(MempoolRejection::GasPriceTooLow {
+                gas_price: 1,
+                min_gas_price: 2,
+                queue_depth: 2,
+            })
+        );
+        pool.add_transaction(make_tx(alice, 2, 2)).unwrap();
+        pool.add_transaction(make_tx(alice, 3, 4)).unwrap();
+
+        let rejection = pool.add_transaction(make_tx(alice, 4, 1000)).unwrap_err();
+        assert_eq!(rejection.reason(), "sender_pending_count_exceeded");
+        // Other senders are not affected
+        pool.add_transaction(make_tx(bob, 0, 1)).unwrap();
+
+        let size = pool.get(&make_tx(bob, 0, 1).hash()).unwrap().size;
+        let mut pool = TxPool::default().with_limits(MempoolLimits {
+            max_pending_bytes_per_sender: size * 2,
+            ..MempoolLimits::default()
+        });
+        pool.add_transaction(make_tx(bob, 0, 1)).unwrap();
+        pool.add_transaction(make_tx(bob, 1, 1)).unwrap();
+        assert!(matches!(
+            pool.add_transaction(make_tx(bob, 2, 1)),
+            Err(MempoolRejection::SenderPendingBytesExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn test_future_queue_promotion_and_expiry() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let mut pool = TxPool::default().with_limits(MempoolLimits {
+            max_queued_per_sender: 2,
+            ..MempoolLimits::default()
+        });
+        pool.set_account_nonce(alice, 0);
+
+        pool.add_transaction(make_tx(alice, 2, 1)).unwrap();
+        pool.add_transaction(make_tx(alice, 3, 1)).unwrap();
+        assert_eq!((pool.pending_len(), pool.queued_len()), (0, 2));
+        assert_eq!(
+            pool.add_transaction(make_tx(alice, 5, 1))
+                .unwrap_err()
+                .reason(),
+            "sender_queued_count_exceeded"
+        );
+
+        // Filling the gap promotes the queued transactions
+        pool.add_transaction(make_tx(alice, 0, 1)).unwrap();
+        assert_eq!(
+            (pool.pending_count(&alice), pool.queued_count(&alice)),
+            (1, 2)
+        );
+        pool.add_transaction(make_tx(alice, 1, 1)).unwrap();
+        assert_eq!(
+            (pool.pending_count(&alice), pool.queued_count(&alice)),
+            (4, 0)
+        );
+        assert_eq!(pool.pending_snapshot(usize::MAX, usize::MAX).len(), 4);
+
+        // Including a transaction out of the middle queues the ones behind the gap
+        pool.remove(&make_tx(alice, 2, 1).hash());
+        assert_eq!((pool.pending_len(), pool.queued_len()), (2, 1));
+        let sequence_numbers = |groups: BTreeMap<AccountAddress, Vec<&PooledTransaction>>| {
+            groups[&alice]
+                .iter()
+                .map(|tx| tx.sequence_number())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sequence_numbers(pool.pending_by_sender()), vec![0, 1]);
+        assert_eq!(sequence_numbers(pool.queued_by_sender()), vec![3]);
+
+        pool.add_transaction(make_tx(alice, 5, 1)).unwrap();
+        let expiry = Instant::now() + pool.limits().queued_ttl + Duration::from_secs(1);
+        assert_eq!(pool.prune_expired_at(expiry), 2);
+        assert_eq!(pool.len(), 2);
+        assert!(!pool.contains(&make_tx(alice, 5, 1).hash()));
+    }
+
+    #[test]
+    fn test_first_transaction_waits_for_the_account_nonce() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        let mut pool = TxPool::default();
+        // Without a seeded nonce the sender is new, so only sequence number 0 executes
+        pool.add_transaction(make_tx(alice, 3, 1)).unwrap();
+        assert_eq!((pool.pending_len(), pool.queued_len()), (0, 1));
+
+        pool.set_account_nonce(bob, 5);
+        pool.add_transaction(make_tx(bob, 6, 1)).unwrap();
+        assert_eq!(pool.queued_count(&bob), 1);
+        pool.add_transaction(make_tx(bob, 5, 1)).unwrap();
+        assert_eq!(pool.pending_count(&bob), 2);
+        assert!(
+            pool.pending_snapshot(usize::MAX, usize::MAX)
+                .iter()
+                .all(|tx| tx.sender() == bob)
+        );
+    }
+
+    #[test]
+    fn test_remove_committed() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let mut pool = TxPool::default();
+        pool.add_transaction(make_tx(alice, 0, 5)).unwrap();
+        pool.add_transaction(make_tx(alice, 1, 5)).unwrap();
+        pool.add_transaction(make_tx(alice, 2, 5)).unwrap();
+
+        pool.remove_committed(&[make_tx(alice, 0, 5), make_tx(alice, 1, 5)]);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(
+            pool.pending_snapshot(usize::MAX, usize::MAX)[0].sequence_number(),
+            2
+        );
+        // A stale replacement of a committed nonce is refused
+        assert!(pool.add_transaction(make_tx(alice, 1, 50)).is_err());
+    }
+
+    #[test]
+    fn test_save_load_and_close() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let path = std::env::temp_dir().join(format!("kanari-mempool-{}.bcs", std::process::id()));
+        let mut pool = TxPool::default();
+        pool.add_transaction(make_tx(alice, 0, 1)).unwrap();
+        pool.add_transaction(make_tx(alice, 1, 1)).unwrap();
+
+        pool.set_read_only(true);
+        assert_eq!(
+            pool.add_transaction(make_tx(alice, 2, 1)),
+            Err(MempoolRejection::ReadOnly)
+        );
+        pool.set_read_only(false);
+        pool.close();
+        assert_eq!(
+            pool.add_transaction(make_tx(alice, 2, 1)),
+            Err(MempoolRejection::NotAccepting)
+        );
+        assert_eq!(pool.save(&path).unwrap(), 2);
+
+        let mut restored = TxPool::default();
+        assert_eq!(restored.load(&path, |_| Ok(0)).unwrap(), 2);
+        assert_eq!(restored.pending_count(&alice), 2);
+        // Once the first transaction was committed only the second is restored
+        let mut restored = TxPool::default();
+        assert_eq!(restored.load(&path, |_| Ok(1)).unwrap(), 1);
+        assert_eq!(restored.pending_count(&alice), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
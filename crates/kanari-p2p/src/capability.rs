@@ -0,0 +1,182 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::message::{MessageType, NodeInfoPayload};
+use crate::node::NodeType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A feature flag a node advertises in its handshake
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Capability {
+    /// Accepts and forwards pending transactions
+    TxRelay,
+    /// Serves the full block history
+    Archive,
+    /// Takes part in or relays consensus
+    Consensus,
+    /// Serves headers and proofs to light clients
+    LightServe,
+}
+
+impl Capability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::TxRelay => "tx-relay",
+            Capability::Archive => "archive",
+            Capability::Consensus => "consensus",
+            Capability::LightServe => "light-serve",
+        }
+    }
+
+    /// The capability a peer needs to be sent a message type, None if every peer accepts it
+    pub fn required_for(msg_type: &MessageType) -> Option<Capability> {
+        match msg_type {
+            MessageType::TransactionBroadcast
+            | MessageType::TransactionRequest
+            | MessageType::TransactionResponse => Some(Capability::TxRelay),
+            MessageType::ConsensusProposal
+            | MessageType::ConsensusVote
+            | MessageType::ConsensusCommit => Some(Capability::Consensus),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Capability {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Capability {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tx-relay" => Ok(Capability::TxRelay),
+            "archive" => Ok(Capability::Archive),
+            "consensus" => Ok(Capability::Consensus),
+            "light-serve" => Ok(Capability::LightServe),
+            // Free-form names advertised by nodes before capabilities were typed
+            "transaction_processing" => Ok(Capability::TxRelay),
+            "consensus_participation" => Ok(Capability::Consensus),
+            _ => Err(anyhow::anyhow!("Unknown capability: {}", s)),
+        }
+    }
+}
+
+/// The typed feature flags of a node
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities(BTreeSet<Capability>);
+
+impl Capabilities {
+    pub fn new(capabilities: impl IntoIterator<Item = Capability>) -> Self {
+        Self(capabilities.into_iter().collect())
+    }
+
+    /// The flags a node of the given type advertises by default
+    pub fn default_for(node_type: &NodeType) -> Self {
+        match node_type {
+            NodeType::Validator => Self::new([Capability::TxRelay, Capability::Consensus]),
+            NodeType::FullNode => Self::new([
+                Capability::TxRelay,
+                Capability::Consensus,
+                Capability::LightServe,
+            ]),
+            NodeType::LightNode | NodeType::Bootstrap => Self::default(),
+        }
+    }
+
+    /// The flags a peer negotiated in its handshake. Peers that predate typed flags
+    /// only send free-form `capabilities` strings; known names are mapped and, if none
+    /// is known, the peer is assumed to relay transactions and consensus as before.
+    pub fn from_handshake(payload: &NodeInfoPayload) -> Self {
+        if let Some(features) = &payload.features {
+            return features.clone();
+        }
+        let capabilities = Self(
+            payload
+                .capabilities
+                .iter()
+                .filter_map(|s| s.parse().ok())
+                .collect(),
+        );
+        if capabilities.0.is_empty() {
+            Self::new([Capability::TxRelay, Capability::Consensus])
+        } else {
+            capabilities
+        }
+    }
+
+    pub fn contains(&self, capability: Capability) -> bool {
+        self.0.contains(&capability)
+    }
+
+    pub fn insert(&mut self, capability: Capability) {
+        self.0.insert(capability);
+    }
+
+    /// Whether a message type may be sent to a peer with these capabilities
+    pub fn accepts(&self, msg_type: &MessageType) -> bool {
+        Capability::required_for(msg_type)
+            .map(|required| self.contains(required))
+            .unwrap_or(true)
+    }
+
+    /// Flags both sides support
+    pub fn intersection(&self, other: &Capabilities) -> Capabilities {
+        Self(self.0.intersection(&other.0).copied().collect())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Capability> {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn handshake(capabilities: Vec<&str>, features: Option<Capabilities>) -> NodeInfoPayload {
+        NodeInfoPayload {
+            node_id: "peer".to_string(),
+            node_type: "FullNode".to_string(),
+            version: "0.1.0".to_string(),
+            chain_id: 1,
+            listening_addresses: vec![],
+            capabilities: capabilities.into_iter().map(String::from).collect(),
+            initial_balance: 0,
+            topic_schemas: HashMap::new(),
+            features,
+            operator: None,
+        }
+    }
+
+    #[test]
+    fn test_handshake_capabilities_and_gating() {
+        let archive = Capabilities::from_handshake(&handshake(
+            vec![],
+            Some(Capabilities::new([
+                Capability::Archive,
+                Capability::TxRelay,
+            ])),
+        ));
+        assert!(archive.contains(Capability::Archive));
+        assert!(archive.accepts(&MessageType::TransactionBroadcast));
+        assert!(!archive.accepts(&MessageType::ConsensusVote));
+        assert!(archive.accepts(&MessageType::BlockProposal));
+
+        let light = Capabilities::from_handshake(&handshake(vec![], Some(Capabilities::default())));
+        assert!(!light.accepts(&MessageType::TransactionBroadcast));
+
+        let legacy = Capabilities::from_handshake(&handshake(vec!["block_validation"], None));
+        assert!(legacy.accepts(&MessageType::ConsensusVote));
+        assert!(legacy.accepts(&MessageType::TransactionBroadcast));
+        assert!(!legacy.contains(Capability::Archive));
+    }
+}
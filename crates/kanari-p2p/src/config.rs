@@ -1,9 +1,13 @@
 // Copyright (c) KanariNetwork
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::history::MessageHistoryConfig;
+use crate::operator::OperatorMetadata;
 use anyhow::Result;
+use kanari_config::network_config::NodeRole;
 use libp2p::Multiaddr;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +38,37 @@ pub struct P2PConfig {
 
     /// Gossipsub configuration
     pub gossipsub_config: GossipsubConfig,
+
+    /// Role of the node in the sentry architecture
+    pub role: NodeRole,
+
+    /// Private peers: a validator's sentries, or the validators a sentry shields
+    pub private_peers: Vec<String>,
+
+    /// Retention of the in-memory message history
+    #[serde(default)]
+    pub message_history: MessageHistoryConfig,
+
+    /// Operator metadata published in a certificate signed by the node identity
+    #[serde(default)]
+    pub operator: Option<OperatorMetadata>,
+
+    /// Time allowed on shutdown to flush queued messages and say goodbye to peers
+    #[serde(default = "default_drain_timeout")]
+    pub drain_timeout: Duration,
+
+    /// Where the aggregate message counters are kept across restarts
+    #[serde(default)]
+    pub message_counters_path: Option<PathBuf>,
+
+    /// Where the node identity key is kept, so the peer ID survives restarts.
+    /// A new identity is generated on every start when unset.
+    #[serde(default)]
+    pub identity_key_path: Option<PathBuf>,
+}
+
+fn default_drain_timeout() -> Duration {
+    Duration::from_secs(10)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,6 +98,13 @@ impl Default for P2PConfig {
             enable_mdns: true,
             enable_kademlia: true,
             gossipsub_config: GossipsubConfig::default(),
+            role: NodeRole::Full,
+            private_peers: vec![],
+            message_history: MessageHistoryConfig::default(),
+            operator: None,
+            drain_timeout: default_drain_timeout(),
+            message_counters_path: None,
+            identity_key_path: None,
         }
     }
 }
@@ -102,6 +144,38 @@ impl P2PConfig {
         self
     }
 
+    /// Set the sentry architecture role. Validators are never discoverable,
+    /// so discovery is turned off for them.
+    pub fn with_role(mut self, role: NodeRole, private_peers: Vec<String>) -> Self {
+        if role == NodeRole::Validator {
+            self.enable_mdns = false;
+            self.enable_kademlia = false;
+        }
+        self.role = role;
+        self.private_peers = private_peers;
+        self
+    }
+
+    pub fn with_drain_timeout(mut self, drain_timeout: Duration) -> Self {
+        self.drain_timeout = drain_timeout;
+        self
+    }
+
+    pub fn with_message_counters_path(mut self, path: PathBuf) -> Self {
+        self.message_counters_path = Some(path);
+        self
+    }
+
+    pub fn with_identity_key_path(mut self, path: PathBuf) -> Self {
+        self.identity_key_path = Some(path);
+        self
+    }
+
+    pub fn with_operator(mut self, operator: OperatorMetadata) -> Self {
+        self.operator = Some(operator);
+        self
+    }
+
     pub fn validate(&self) -> Result<()> {
         if self.listen_addresses.is_empty() {
             anyhow::bail!("At least one listen address must be specified");
@@ -111,6 +185,24 @@ impl P2PConfig {
             anyhow::bail!("max_connections must be greater than 0");
         }
 
+        if self.message_history.max_messages == 0 {
+            anyhow::bail!("message_history.max_messages must be greater than 0");
+        }
+
+        if let Some(operator) = &self.operator {
+            if operator.name.trim().is_empty() {
+                anyhow::bail!("operator.name must not be empty");
+            }
+        }
+
+        if self.role != NodeRole::Full && self.private_peers.is_empty() {
+            anyhow::bail!("A {} node requires at least one private peer", self.role);
+        }
+
+        if self.role == NodeRole::Validator && (self.enable_mdns || self.enable_kademlia) {
+            anyhow::bail!("Peer discovery must be disabled on a validator node");
+        }
+
         Ok(())
     }
 }
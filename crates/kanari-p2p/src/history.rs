@@ -0,0 +1,222 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::message::{Message, MessageType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Default number of messages kept in memory
+pub const DEFAULT_MAX_HISTORY_MESSAGES: usize = 1000;
+/// Default age after which messages are dropped from memory
+pub const DEFAULT_MAX_HISTORY_AGE: Duration = Duration::from_secs(600);
+
+/// Retention policy of the in-memory message history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageHistoryConfig {
+    /// Maximum number of messages kept
+    pub max_messages: usize,
+    /// Maximum age of a kept message
+    pub max_age: Duration,
+}
+
+impl Default for MessageHistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_messages: DEFAULT_MAX_HISTORY_MESSAGES,
+            max_age: DEFAULT_MAX_HISTORY_AGE,
+        }
+    }
+}
+
+/// A message as recorded in the history
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub recorded_at: SystemTime,
+    pub message: Message,
+}
+
+/// Aggregate message counters, kept across restarts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MessageCounters {
+    pub total_messages: u64,
+    pub counts_by_type: HashMap<String, u64>,
+}
+
+/// Bounded ring buffer of recent messages. Messages beyond the retention policy are
+/// dropped; only aggregate counters are kept for the whole lifetime of the node.
+#[derive(Debug)]
+pub struct MessageHistory {
+    config: MessageHistoryConfig,
+    entries: VecDeque<HistoryEntry>,
+    total_recorded: u64,
+    counts_by_type: HashMap<String, u64>,
+}
+
+impl Default for MessageHistory {
+    fn default() -> Self {
+        Self::new(MessageHistoryConfig::default())
+    }
+}
+
+impl MessageHistory {
+    pub fn new(config: MessageHistoryConfig) -> Self {
+        Self {
+            config,
+            entries: VecDeque::new(),
+            total_recorded: 0,
+            counts_by_type: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &MessageHistoryConfig {
+        &self.config
+    }
+
+    /// Change the retention policy, dropping messages it no longer allows
+    pub fn set_config(&mut self, config: MessageHistoryConfig) {
+        self.config = config;
+        self.prune(SystemTime::now());
+    }
+
+    pub fn record(&mut self, message: Message) {
+        self.record_at(message, SystemTime::now());
+    }
+
+    fn record_at(&mut self, message: Message, now: SystemTime) {
+        self.total_recorded += 1;
+        *self
+            .counts_by_type
+            .entry(message_type_name(&message.msg_type))
+            .or_insert(0) += 1;
+        self.entries.push_back(HistoryEntry {
+            recorded_at: now,
+            message,
+        });
+        self.prune(now);
+    }
+
+    /// Drop messages exceeding the count or age limit
+    pub fn prune(&mut self, now: SystemTime) {
+        while self.entries.len() > self.config.max_messages {
+            self.entries.pop_front();
+        }
+        while let Some(oldest) = self.entries.front() {
+            let age = now.duration_since(oldest.recorded_at).unwrap_or_default();
+            if age <= self.config.max_age {
+                break;
+            }
+            self.entries.pop_front();
+        }
+    }
+
+    /// Number of messages currently kept
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of messages recorded since the node started, including dropped ones
+    pub fn total_recorded(&self) -> u64 {
+        self.total_recorded
+    }
+
+    /// Messages recorded since the node started, per message type
+    pub fn counts_by_type(&self) -> &HashMap<String, u64> {
+        &self.counts_by_type
+    }
+
+    pub fn counters(&self) -> MessageCounters {
+        MessageCounters {
+            total_messages: self.total_recorded,
+            counts_by_type: self.counts_by_type.clone(),
+        }
+    }
+
+    /// Write the aggregate counters to `path`, to be restored by `load_counters`
+    pub fn save_counters(&self, path: &Path) -> anyhow::Result<()> {
+        // Write then rename, so a crash never leaves a truncated file behind
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&self.counters())?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Add the counters saved by `save_counters` to the current ones. Returns false
+    /// when nothing was saved yet.
+    pub fn load_counters(&mut self, path: &Path) -> anyhow::Result<bool> {
+        if !path.exists() {
+            return Ok(false);
+        }
+        let saved: MessageCounters = serde_json::from_slice(&std::fs::read(path)?)?;
+        self.total_recorded += saved.total_messages;
+        for (msg_type, count) in saved.counts_by_type {
+            *self.counts_by_type.entry(msg_type).or_insert(0) += count;
+        }
+        Ok(true)
+    }
+
+    /// Up to `limit` most recent messages, newest first
+    pub fn recent(&self, limit: usize) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter().rev().take(limit)
+    }
+}
+
+/// Name a message type is counted and reported under
+pub fn message_type_name(msg_type: &MessageType) -> String {
+    match msg_type {
+        MessageType::Custom(name) => format!("Custom({})", name),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_is_bounded_by_count_and_age() {
+        let mut history = MessageHistory::new(MessageHistoryConfig {
+            max_messages: 2,
+            max_age: Duration::from_secs(60),
+        });
+        let start = SystemTime::now();
+        for _ in 0..3 {
+            history.record_at(Message::new(MessageType::NodeHeartbeat, vec![]), start);
+        }
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.total_recorded(), 3);
+
+        let later = start + Duration::from_secs(120);
+        history.record_at(Message::new(MessageType::BlockProposal, vec![]), later);
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            history.recent(10).next().unwrap().message.msg_type,
+            MessageType::BlockProposal
+        );
+        assert_eq!(history.counts_by_type()["NodeHeartbeat"], 3);
+        assert_eq!(history.total_recorded(), 4);
+    }
+
+    #[test]
+    fn test_counters_survive_restart() {
+        let path = std::env::temp_dir().join(format!("{}.json", uuid::Uuid::new_v4()));
+        let mut history = MessageHistory::default();
+        assert!(!history.load_counters(&path).unwrap());
+        history.record(Message::new(MessageType::NodeHeartbeat, vec![]));
+        history.record(Message::new(MessageType::BlockProposal, vec![]));
+        history.save_counters(&path).unwrap();
+
+        let mut restarted = MessageHistory::default();
+        assert!(restarted.load_counters(&path).unwrap());
+        restarted.record(Message::new(MessageType::NodeHeartbeat, vec![]));
+        assert_eq!(restarted.total_recorded(), 3);
+        assert_eq!(restarted.counts_by_type()["NodeHeartbeat"], 2);
+        assert_eq!(restarted.len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -2,20 +2,39 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod behavior;
+pub mod capability;
 pub mod config;
+pub mod history;
 pub mod message;
 pub mod network;
 pub mod node;
+pub mod operator;
 pub mod peer;
+pub mod propagation;
 pub mod protocol;
+pub mod schema;
+pub mod sentry;
+pub mod sync;
+pub mod time_sync;
 
 pub use behavior::KanariBehaviour;
+pub use capability::{Capabilities, Capability};
 pub use config::P2PConfig;
+pub use history::{MessageCounters, MessageHistory, MessageHistoryConfig};
 pub use message::{Message, MessageType};
 pub use network::P2PNetwork;
 pub use node::{Node, NodeId, NodeInfo};
-pub use peer::{Peer, PeerInfo, PeerManager};
+pub use operator::{OperatorCertificate, OperatorMetadata, PeerOperator};
+pub use peer::{
+    DisconnectInitiator, DisconnectMetrics, DisconnectReason, DisconnectRecord, Peer, PeerInfo,
+    PeerManager,
+};
+pub use propagation::{PeerPropagationStats, PropagationTracker};
 pub use protocol::{Protocol, ProtocolEvent};
+pub use schema::{SchemaNegotiator, SchemaRange, SchemaVersion};
+pub use sentry::SentryPolicy;
+pub use sync::{PeerSyncProgress, SyncStatus, SyncTracker};
+pub use time_sync::{TimeOffset, TimeSyncTracker};
 
 use anyhow::Result;
 
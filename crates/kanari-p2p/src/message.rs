@@ -1,6 +1,9 @@
 // Copyright (c) KanariNetwork
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::capability::Capabilities;
+use crate::operator::OperatorCertificate;
+use crate::schema::SchemaRange;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -136,6 +139,52 @@ pub struct BlockProposalPayload {
     pub transactions: Vec<String>, // Transaction hashes
 }
 
+/// Block proposal payload, schema version 2: adds the post-execution state root
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockProposalPayloadV2 {
+    pub block_number: u128,
+    pub block_hash: String,
+    pub parent_hash: String,
+    pub proposer: String,
+    pub timestamp: u64,
+    pub transactions: Vec<String>, // Transaction hashes
+    pub state_root: Option<String>,
+}
+
+impl From<BlockProposalPayload> for BlockProposalPayloadV2 {
+    fn from(payload: BlockProposalPayload) -> Self {
+        Self {
+            block_number: payload.block_number,
+            block_hash: payload.block_hash,
+            parent_hash: payload.parent_hash,
+            proposer: payload.proposer,
+            timestamp: payload.timestamp,
+            transactions: payload.transactions,
+            state_root: None,
+        }
+    }
+}
+
+impl From<BlockProposalPayloadV2> for BlockProposalPayload {
+    fn from(payload: BlockProposalPayloadV2) -> Self {
+        Self {
+            block_number: payload.block_number,
+            block_hash: payload.block_hash,
+            parent_hash: payload.parent_hash,
+            proposer: payload.proposer,
+            timestamp: payload.timestamp,
+            transactions: payload.transactions,
+        }
+    }
+}
+
+/// Block request payload, asking a peer for an inclusive range of blocks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockRequestPayload {
+    pub from_block: u128,
+    pub to_block: u128,
+}
+
 /// Transaction broadcast payload
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionPayload {
@@ -157,6 +206,22 @@ pub struct NodeInfoPayload {
     pub listening_addresses: Vec<String>,
     pub capabilities: Vec<String>,
     pub initial_balance: u64, // Add initial balance field
+    /// Payload schema versions supported per gossip topic
+    #[serde(default)]
+    pub topic_schemas: HashMap<String, SchemaRange>,
+    /// Typed feature flags, None for nodes that only send free-form `capabilities`
+    #[serde(default)]
+    pub features: Option<Capabilities>,
+    /// Signed operator metadata, if the operator published any
+    #[serde(default)]
+    pub operator: Option<OperatorCertificate>,
+}
+
+/// Heartbeat payload, carrying the sender's clock for network time synchronization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatPayload {
+    /// Milliseconds since the Unix epoch when the heartbeat was sent
+    pub sent_at_ms: u64,
 }
 
 /// Consensus vote payload
@@ -1,42 +1,94 @@
 // Copyright (c) KanariNetwork
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::behavior::KanariBehaviour;
+use crate::behavior::{KanariBehaviour, KanariBehaviourEvent};
+use crate::capability::{Capabilities, Capability};
 use crate::config::P2PConfig;
-use crate::message::{Message, MessageType, NodeInfoPayload};
+use crate::history::{HistoryEntry, MessageHistory};
+use crate::message::{
+    BlockProposalPayloadV2, BlockRequestPayload, HeartbeatPayload, Message, MessageType,
+    NodeInfoPayload, TransactionPayload,
+};
 use crate::node::{Node, NodeId, NodeInfo};
-use crate::peer::{Peer, PeerManager, PeerStatus};
+use crate::operator::{OperatorCertificate, PeerOperator};
+use crate::peer::{
+    DisconnectInitiator, DisconnectMetrics, DisconnectReason, DisconnectRecord, Peer, PeerInfo,
+    PeerManager, PeerStatus,
+};
+use crate::propagation::{PeerPropagationStats, PropagationTracker};
+use crate::schema::{
+    decode_block_proposal, encode_block_proposal, SchemaNegotiator, SchemaVersion,
+    LEGACY_SCHEMA_VERSION, SCHEMA_VERSION_METADATA_KEY,
+};
+use crate::sentry::SentryPolicy;
+use crate::sync::{SyncStatus, SyncTracker};
+use crate::time_sync::{TimeOffset, TimeSyncTracker, TIME_SAMPLE_INTERVAL};
 
 use anyhow::Result;
 use futures::StreamExt;
+use libp2p::identity::Keypair;
 use libp2p::{
-    gossipsub, identify, kad, mdns, noise, ping, tcp, yamux, Multiaddr, PeerId, Swarm, Transport,
+    dns, gossipsub, identify, kad, mdns, noise, ping, tcp, yamux, Multiaddr, PeerId, Swarm,
+    Transport,
 };
 use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::Path;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, instrument, warn};
+
+/// Topic peers exchange node info and heartbeats on
+const NODE_DISCOVERY_TOPIC: &str = "kanari/node-discovery";
+
+/// Most blocks asked of a peer in one block request
+const MAX_BLOCKS_PER_REQUEST: u128 = 100;
+
+/// How often the message history is reported to the node
+const HISTORY_REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Most recent messages included in a message history report
+const HISTORY_REPORT_MESSAGES: usize = 100;
+
+/// How often sync progress is logged while the node is behind its peers
+const SYNC_PROGRESS_LOG_INTERVAL: Duration = Duration::from_secs(30);
 
 /// P2P Network manager
 pub struct P2PNetwork {
     swarm: Swarm<KanariBehaviour>,
     peer_manager: PeerManager,
+    propagation: PropagationTracker,
+    sync: SyncTracker,
+    time_sync: TimeSyncTracker,
+    sentry: SentryPolicy,
+    schemas: SchemaNegotiator,
     local_node: Node,
     config: P2PConfig,
     event_sender: Option<mpsc::UnboundedSender<NetworkEvent>>,
+    command_receiver: Option<mpsc::UnboundedReceiver<NetworkCommand>>,
+    /// Why the node is closing a connection, recorded once libp2p reports it closed
+    pending_disconnects: HashMap<PeerId, (DisconnectReason, Option<String>)>,
 }
 
 impl P2PNetwork {
     /// Create a new P2P network
-    pub async fn new(config: P2PConfig, node: Node) -> Result<Self> {
-        // Generate or use existing peer ID
-        let local_key = libp2p::identity::Keypair::generate_ed25519();
+    pub async fn new(config: P2PConfig, mut node: Node) -> Result<Self> {
+        // Keep the peer ID across restarts when a key path is configured
+        let local_key = match &config.identity_key_path {
+            Some(path) => load_or_generate_identity(path)?,
+            None => Keypair::generate_ed25519(),
+        };
         let local_peer_id = PeerId::from(local_key.public());
 
         info!("Local peer ID: {}", local_peer_id);
 
-        // Create transport
-        let transport = tcp::tokio::Transport::default()
+        if let Some(operator) = &config.operator {
+            node.info.operator = Some(OperatorCertificate::sign(operator.clone(), &local_key)?);
+            info!("Publishing operator metadata for {}", operator.name);
+        }
+
+        // Create transport, resolving the DNS names of bootstrap nodes
+        let transport = dns::tokio::Transport::system(tcp::tokio::Transport::default())?
             .upgrade(libp2p::core::upgrade::Version::V1)
             .authenticate(noise::Config::new(&local_key)?)
             .multiplex(yamux::Config::default())
@@ -53,6 +105,12 @@ impl P2PNetwork {
             libp2p::swarm::Config::with_tokio_executor(),
         );
 
+        // Peers address direct messages to a topic of their own
+        swarm
+            .behaviour_mut()
+            .gossipsub
+            .subscribe(&gossipsub::IdentTopic::new(direct_topic(&local_peer_id)))?;
+
         // Listen on configured addresses
         for addr in &config.listen_addresses {
             swarm.listen_on(addr.clone())?;
@@ -60,18 +118,65 @@ impl P2PNetwork {
         }
 
         // Create peer manager
-        let peer_manager =
-            PeerManager::new(config.max_connections as usize, config.connection_timeout);
+        let peer_manager = PeerManager::new(
+            config.max_connections as usize,
+            config.idle_connection_timeout,
+        );
+
+        let mut local_node = node.with_history_config(config.message_history.clone());
+        if let Some(path) = &config.message_counters_path {
+            match local_node.message_history.load_counters(path) {
+                Ok(true) => info!(
+                    "Restored {} message count(s) from {}",
+                    local_node.message_history.total_recorded(),
+                    path.display()
+                ),
+                Ok(false) => {}
+                Err(e) => warn!("Failed to restore message counters: {}", e),
+            }
+        }
 
         Ok(Self {
             swarm,
             peer_manager,
-            local_node: node,
+            propagation: PropagationTracker::default(),
+            sync: SyncTracker::new(),
+            time_sync: TimeSyncTracker::new(),
+            sentry: SentryPolicy::new(config.role, config.private_peers.clone()),
+            schemas: SchemaNegotiator::default(),
+            local_node,
             config,
             event_sender: None,
+            command_receiver: None,
+            pending_disconnects: HashMap::new(),
         })
     }
 
+    /// Count peer disconnections in `registry`
+    pub fn with_metrics_registry(mut self, registry: &prometheus::Registry) -> Result<Self> {
+        self.peer_manager = self
+            .peer_manager
+            .with_metrics(DisconnectMetrics::new(registry)?);
+        Ok(self)
+    }
+
+    /// Close the connections to a peer, recording `reason` as the local cause
+    pub fn disconnect_peer(
+        &mut self,
+        peer_id: PeerId,
+        reason: DisconnectReason,
+        detail: Option<String>,
+    ) {
+        if self.swarm.disconnect_peer_id(peer_id).is_ok() {
+            self.pending_disconnects.insert(peer_id, (reason, detail));
+        }
+    }
+
+    /// Recent disconnections of a peer, oldest first
+    pub fn disconnect_history(&self, peer_id: &NodeId) -> Vec<DisconnectRecord> {
+        self.peer_manager.disconnect_history(peer_id)
+    }
+
     /// Start the P2P network
     pub async fn start(&mut self) -> Result<()> {
         info!(
@@ -97,7 +202,19 @@ impl P2PNetwork {
 
     /// Run the network event loop
     pub async fn run(&mut self) -> Result<()> {
+        self.run_until(std::future::pending()).await
+    }
+
+    /// Run the network event loop until `shutdown` completes, then drain
+    pub async fn run_until(
+        &mut self,
+        shutdown: impl std::future::Future<Output = ()>,
+    ) -> Result<()> {
         let mut cleanup_interval = tokio::time::interval(Duration::from_secs(60));
+        let mut sync_log_interval = tokio::time::interval(SYNC_PROGRESS_LOG_INTERVAL);
+        let mut heartbeat_interval = tokio::time::interval(TIME_SAMPLE_INTERVAL);
+        let mut history_interval = tokio::time::interval(HISTORY_REPORT_INTERVAL);
+        tokio::pin!(shutdown);
 
         loop {
             tokio::select! {
@@ -108,14 +225,139 @@ impl P2PNetwork {
                 }
                 _ = cleanup_interval.tick() => {
                     self.peer_manager.cleanup_stale_connections();
+                    self.save_message_counters();
+                }
+                _ = history_interval.tick() => {
+                    let history = self.message_history();
+                    self.emit(NetworkEvent::MessageHistory {
+                        total_messages: history.total_recorded(),
+                        counts_by_type: history.counts_by_type().clone(),
+                        recent: history.recent(HISTORY_REPORT_MESSAGES).cloned().collect(),
+                    });
+                }
+                Some(command) = recv_command(&mut self.command_receiver) => {
+                    self.handle_command(command);
+                }
+                _ = sync_log_interval.tick() => {
+                    if let Some(progress) = self.sync.progress_line() {
+                        info!("{}", progress);
+                    }
+                    self.emit(NetworkEvent::SyncStatus(self.sync_status()));
+                    if let Err(e) = self.request_missing_blocks() {
+                        warn!("Failed to request missing blocks: {}", e);
+                    }
+                }
+                _ = heartbeat_interval.tick() => {
+                    match self.local_node.heartbeat_message() {
+                        Ok(heartbeat) => {
+                            if let Err(e) = self.broadcast_message(heartbeat) {
+                                debug!("Failed to broadcast heartbeat: {}", e);
+                            }
+                        }
+                        Err(e) => warn!("Failed to create heartbeat: {}", e),
+                    }
+                    self.emit(NetworkEvent::TimeOffset(self.time_offset()));
+                }
+                _ = &mut shutdown => break,
+            }
+        }
+        self.drain().await
+    }
+
+    /// Leave the network gracefully within `P2PConfig::drain_timeout`: announce
+    /// NodeLeave, let gossipsub flush queued messages such as the last produced
+    /// block, then close every connection
+    pub async fn drain(&mut self) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + self.config.drain_timeout;
+        info!(
+            "Draining {} peer connection(s)",
+            self.swarm.connected_peers().count()
+        );
+
+        let goodbye = self.local_node.leave_message();
+        if let Err(e) = self.broadcast_message(goodbye) {
+            warn!("Failed to announce NodeLeave: {}", e);
+        }
+
+        // Gossipsub sends queued messages on its heartbeat
+        let flush_until = (tokio::time::Instant::now()
+            + self.config.gossipsub_config.heartbeat_interval * 2)
+            .min(deadline);
+        self.drive_swarm_until(flush_until, |_| false).await;
+
+        let peers: Vec<PeerId> = self.swarm.connected_peers().cloned().collect();
+        for peer_id in peers {
+            self.disconnect_peer(peer_id, DisconnectReason::Shutdown, None);
+        }
+        self.drive_swarm_until(deadline, |swarm| swarm.connected_peers().next().is_none())
+            .await;
+
+        let remaining = self.swarm.connected_peers().count();
+        if remaining > 0 {
+            warn!(
+                "Drain timed out with {} connection(s) still open",
+                remaining
+            );
+        }
+        if self.local_node.is_running {
+            self.local_node.stop()?;
+        }
+        self.save_message_counters();
+        Ok(())
+    }
+
+    /// Persist the aggregate message counters, if a path is configured
+    fn save_message_counters(&self) {
+        if let Some(path) = &self.config.message_counters_path {
+            if let Err(e) = self.message_history().save_counters(path) {
+                warn!("Failed to save message counters: {}", e);
+            }
+        }
+    }
+
+    /// Keep handling swarm events until `until`, or until `done` holds
+    async fn drive_swarm_until(
+        &mut self,
+        until: tokio::time::Instant,
+        done: impl Fn(&Swarm<KanariBehaviour>) -> bool,
+    ) {
+        while !done(&self.swarm) {
+            match tokio::time::timeout_at(until, self.swarm.select_next_some()).await {
+                Ok(event) => {
+                    if let Err(e) = self.handle_swarm_event(event).await {
+                        error!("Error handling swarm event: {}", e);
+                    }
                 }
+                Err(_) => break,
             }
         }
     }
 
-    /// Send a message to all connected peers
-    pub fn broadcast_message(&mut self, message: Message) -> Result<()> {
+    /// Send a message to all connected peers. A message without a payload schema
+    /// version is stamped with the version every known peer can decode.
+    #[instrument(skip_all, fields(msg_type = ?message.msg_type))]
+    pub fn broadcast_message(&mut self, mut message: Message) -> Result<()> {
+        if let Some(required) = Capability::required_for(&message.msg_type) {
+            let any_capable = self
+                .peer_manager
+                .get_connected_peers()
+                .iter()
+                .any(|peer| peer.info.accepts(&message.msg_type));
+            if !any_capable {
+                anyhow::bail!(
+                    "Not broadcasting {:?}: no connected peer supports {}",
+                    message.msg_type,
+                    required
+                );
+            }
+        }
+
         let topic = self.get_topic_for_message(&message.msg_type);
+        if !message.metadata.contains_key(SCHEMA_VERSION_METADATA_KEY) {
+            if let Some(version) = self.schemas.version_for_broadcast(&topic) {
+                message = message.with_schema_version(version);
+            }
+        }
         let data = message.to_bytes()?;
 
         if let Err(e) = self.swarm.behaviour_mut().publish_message(&topic, data) {
@@ -127,11 +369,45 @@ impl P2PNetwork {
         Ok(())
     }
 
-    /// Send a direct message to a specific peer
-    pub fn send_direct_message(&mut self, peer_id: &PeerId, message: Message) -> Result<()> {
+    /// Gossip a block proposal, encoded in the schema version every known peer can decode
+    pub fn broadcast_block_proposal(&mut self, payload: &BlockProposalPayloadV2) -> Result<()> {
+        let version = self
+            .broadcast_schema_version(&MessageType::BlockProposal)
+            .unwrap_or(LEGACY_SCHEMA_VERSION);
+        let message = Message::new(
+            MessageType::BlockProposal,
+            encode_block_proposal(payload, version)?,
+        )
+        .with_sender(self.local_node.info.id.clone())
+        .with_schema_version(version);
+        self.broadcast_message(message)
+    }
+
+    /// Send a direct message to a specific peer. A message without a payload schema
+    /// version is stamped with the version negotiated with the peer.
+    pub fn send_direct_message(&mut self, peer_id: &PeerId, mut message: Message) -> Result<()> {
+        if let Some(peer) = self.peer_manager.get_peer(&peer_id.to_string()) {
+            if !peer.info.accepts(&message.msg_type) {
+                anyhow::bail!(
+                    "Peer {} did not negotiate the capability required for {:?}",
+                    peer_id,
+                    message.msg_type
+                );
+            }
+        }
+
         // For now, we'll use gossipsub even for direct messages
         // In the future, we could implement a request-response protocol
-        let topic = format!("kanari/direct/{}", peer_id);
+        if !message.metadata.contains_key(SCHEMA_VERSION_METADATA_KEY) {
+            let message_topic = self.get_topic_for_message(&message.msg_type);
+            if let Some(version) = self
+                .schemas
+                .version_for_peer(&peer_id.to_string(), &message_topic)
+            {
+                message = message.with_schema_version(version);
+            }
+        }
+        let topic = direct_topic(peer_id);
         let data = message.to_bytes()?;
 
         if let Err(e) = self.swarm.behaviour_mut().publish_message(&topic, data) {
@@ -157,58 +433,309 @@ impl P2PNetwork {
         }
     }
 
+    /// Track block propagation for a message received from a peer
+    pub fn observe_block_message(&mut self, peer_id: &NodeId, message: &Message) {
+        match message.msg_type {
+            MessageType::BlockProposal | MessageType::BlockResponse => {
+                match decode_block_proposal(message) {
+                    Ok(payload) => {
+                        self.propagation
+                            .record_block_received(peer_id, &payload.block_hash);
+                        self.sync.record_peer_block(peer_id, payload.block_number);
+                    }
+                    Err(e) => warn!("Invalid block payload from peer {}: {}", peer_id, e),
+                }
+            }
+            MessageType::BlockCommit => {
+                if let Ok(payload) = decode_block_proposal(message) {
+                    self.propagation.record_announcement(&payload.block_hash);
+                    self.sync.record_peer_block(peer_id, payload.block_number);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Record the clock sample carried by a peer's heartbeat
+    pub fn observe_heartbeat(&mut self, peer_id: &NodeId, message: &Message) {
+        if message.msg_type != MessageType::NodeHeartbeat {
+            return;
+        }
+        match serde_json::from_slice::<HeartbeatPayload>(&message.payload) {
+            Ok(payload) => self.time_sync.record_sample(peer_id, payload.sent_at_ms),
+            // Heartbeats of older nodes carry no time
+            Err(e) => debug!("Heartbeat from peer {} has no time sample: {}", peer_id, e),
+        }
+    }
+
+    /// Median clock offset of the connected peers, to correct block timestamps with
+    pub fn time_offset(&self) -> TimeOffset {
+        self.time_sync.offset()
+    }
+
+    /// Forward a consensus message according to the node's sentry role
+    pub fn relay_consensus_message(&mut self, from: &NodeId, message: Message) -> Result<()> {
+        let connected: Vec<NodeId> = self
+            .peer_manager
+            .get_connected_peers()
+            .iter()
+            .filter(|peer| peer.info.accepts(&message.msg_type))
+            .map(|peer| peer.info.id.clone())
+            .collect();
+        for target in self.sentry.relay_targets(&message, from, &connected) {
+            let peer_id: PeerId = target.parse()?;
+            self.send_direct_message(&peer_id, message.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Payload schema version to encode a gossiped message with, so every peer can decode it
+    pub fn broadcast_schema_version(&self, msg_type: &MessageType) -> Option<SchemaVersion> {
+        self.schemas
+            .version_for_broadcast(&self.get_topic_for_message(msg_type))
+    }
+
+    /// Record the per-topic schema versions, feature flags and operator metadata a peer
+    /// advertised in its handshake
+    pub fn handle_node_info(&mut self, peer_id: &NodeId, payload: &NodeInfoPayload) {
+        self.schemas
+            .register_peer(peer_id.clone(), payload.topic_schemas.clone());
+        self.peer_manager
+            .set_peer_features(peer_id, Capabilities::from_handshake(payload));
+        if let Some(certificate) = &payload.operator {
+            self.peer_manager.set_peer_operator(
+                peer_id,
+                PeerOperator::from_certificate(peer_id, certificate),
+            );
+        }
+        if let Some(peer) = self.peer_manager.get_peer(peer_id) {
+            let negotiated = peer
+                .info
+                .features
+                .as_ref()
+                .map(|features| features.intersection(&self.local_node.info.features));
+            self.emit(NetworkEvent::PeerUpdated {
+                info: peer.info.clone(),
+                negotiated,
+                recent_disconnects: self.disconnect_history(peer_id),
+            });
+        }
+    }
+
+    /// The signed operator metadata this node publishes, if any
+    pub fn local_operator(&self) -> Option<&OperatorCertificate> {
+        self.local_node.info.operator.as_ref()
+    }
+
+    /// Connected peers with the feature flags both sides support, None for peers
+    /// whose handshake has not arrived yet
+    pub fn negotiated_capabilities(&self) -> Vec<(PeerInfo, Option<Capabilities>)> {
+        self.peer_manager
+            .get_connected_peers()
+            .iter()
+            .map(|peer| {
+                let negotiated = peer
+                    .info
+                    .features
+                    .as_ref()
+                    .map(|features| features.intersection(&self.local_node.info.features));
+                (peer.info.clone(), negotiated)
+            })
+            .collect()
+    }
+
+    /// Record the height of the local chain, which sync progress is measured against
+    pub fn set_local_height(&mut self, height: u128) {
+        self.sync.set_local_height(height);
+    }
+
+    /// Sync progress against the heights peers announced
+    pub fn sync_status(&self) -> SyncStatus {
+        self.sync.status()
+    }
+
+    /// Peers ranked from fastest to slowest block propagation
+    pub fn get_propagation_stats(&self) -> Vec<PeerPropagationStats> {
+        self.propagation.ranking()
+    }
+
+    /// Recent messages of the local node, bounded by `P2PConfig::message_history`
+    pub fn message_history(&self) -> &MessageHistory {
+        &self.local_node.message_history
+    }
+
+    /// Connected peers ordered by preference for block requests
+    pub fn preferred_block_peers(&self) -> Vec<NodeId> {
+        let connected: Vec<NodeId> = self
+            .peer_manager
+            .get_connected_peers()
+            .iter()
+            .map(|peer| peer.info.id.clone())
+            .collect();
+        self.propagation.preferred_peers(&connected)
+    }
+
+    /// Ask the fastest peer ahead of the local chain for the next missing blocks
+    fn request_missing_blocks(&mut self) -> Result<()> {
+        let status = self.sync.status();
+        if !status.is_syncing {
+            return Ok(());
+        }
+        let Some(peer) = self.preferred_block_peers().into_iter().find(|peer_id| {
+            status
+                .active_peers
+                .iter()
+                .any(|progress| &progress.peer_id == peer_id)
+        }) else {
+            return Ok(());
+        };
+
+        let from_block = status.current_height + 1;
+        let to_block = status
+            .target_height
+            .min(from_block + MAX_BLOCKS_PER_REQUEST - 1);
+        let payload = serde_json::to_vec(&BlockRequestPayload {
+            from_block,
+            to_block,
+        })?;
+        let message = Message::new(MessageType::BlockRequest, payload)
+            .with_sender(self.local_node.info.id.clone());
+        debug!(
+            "Requesting blocks #{}..=#{} from peer {}",
+            from_block, to_block, peer
+        );
+        self.send_direct_message(&peer.parse()?, message)
+    }
+
     /// Set event sender for external event handling
     pub fn set_event_sender(&mut self, sender: mpsc::UnboundedSender<NetworkEvent>) {
         self.event_sender = Some(sender);
     }
 
+    /// Take commands from the node while the event loop runs
+    pub fn set_command_receiver(&mut self, receiver: mpsc::UnboundedReceiver<NetworkCommand>) {
+        self.command_receiver = Some(receiver);
+    }
+
+    fn handle_command(&mut self, command: NetworkCommand) {
+        match command {
+            NetworkCommand::SetLocalHeight(height) => self.set_local_height(height),
+        }
+    }
+
     /// Handle swarm events
     async fn handle_swarm_event(
         &mut self,
-        event: libp2p::swarm::SwarmEvent<libp2p::swarm::behaviour::toggle::Toggle<KanariBehaviour>>,
+        event: libp2p::swarm::SwarmEvent<KanariBehaviourEvent>,
     ) -> Result<()> {
         match event {
+            libp2p::swarm::SwarmEvent::Behaviour(KanariBehaviourEvent::Gossipsub(
+                gossipsub::Event::Message {
+                    propagation_source,
+                    message,
+                    ..
+                },
+            )) => {
+                let author = message.source.unwrap_or(propagation_source);
+                self.handle_gossip_message(propagation_source, author, &message.data);
+            }
+            libp2p::swarm::SwarmEvent::Behaviour(KanariBehaviourEvent::Gossipsub(
+                gossipsub::Event::Subscribed { peer_id, topic },
+            )) if topic == gossipsub::IdentTopic::new(NODE_DISCOVERY_TOPIC).hash() => {
+                // Hand the new peer our node info, which is our side of the handshake
+                debug!(
+                    "Peer {} joined node discovery, announcing node info",
+                    peer_id
+                );
+                self.announce_node_info();
+            }
+            libp2p::swarm::SwarmEvent::Behaviour(KanariBehaviourEvent::Mdns(
+                mdns::Event::Discovered(peers),
+            )) => {
+                for (peer_id, address) in peers {
+                    if !self.sentry.should_advertise(&peer_id.to_string()) {
+                        debug!("Not sharing private peer {} through discovery", peer_id);
+                        continue;
+                    }
+                    if self.config.enable_kademlia {
+                        self.swarm.behaviour_mut().add_address(peer_id, address);
+                    }
+                }
+            }
+            libp2p::swarm::SwarmEvent::Behaviour(KanariBehaviourEvent::Kademlia(
+                kad::Event::RoutingUpdated { peer, .. },
+            )) if !self.sentry.should_advertise(&peer.to_string()) => {
+                // Kademlia shares its routing table, keep private peers out of it
+                debug!("Removing private peer {} from the routing table", peer);
+                self.swarm.behaviour_mut().kademlia.remove_peer(&peer);
+            }
             libp2p::swarm::SwarmEvent::Behaviour(behaviour_event) => {
-                // Handle behaviour-specific events
-                // Note: This is a simplified approach. In a real implementation,
-                // you'd need to properly handle the nested event types
-                info!("Received behaviour event");
+                debug!("Behaviour event: {:?}", behaviour_event);
             }
             libp2p::swarm::SwarmEvent::ConnectionEstablished {
                 peer_id, endpoint, ..
             } => {
                 info!("Connection established with peer: {}", peer_id);
 
+                if !self.sentry.allow_connection(&peer_id.to_string()) {
+                    warn!(
+                        "Rejecting peer {}: {} nodes only connect to private peers",
+                        peer_id,
+                        self.sentry.role()
+                    );
+                    let detail =
+                        format!("{} nodes only connect to private peers", self.sentry.role());
+                    self.disconnect_peer(peer_id, DisconnectReason::Rejected, Some(detail));
+                    return Ok(());
+                }
+
                 // Add peer to peer manager
                 let peer = Peer::new(
                     peer_id.to_string(),
                     endpoint.get_remote_address().to_string(),
                 );
+                let mut info = peer.info.clone();
                 if let Err(e) = self.peer_manager.add_peer(peer) {
                     warn!("Failed to add peer to manager: {}", e);
                 } else {
                     self.peer_manager
                         .update_peer_status(&peer_id.to_string(), PeerStatus::Connected);
                 }
-
-                // Send event if handler is set
-                if let Some(sender) = &self.event_sender {
-                    let _ = sender.send(NetworkEvent::PeerConnected(peer_id.to_string()));
-                }
+                info.set_connected();
+                let recent_disconnects = self.disconnect_history(&info.id);
+                self.emit(NetworkEvent::PeerConnected {
+                    info,
+                    recent_disconnects,
+                });
             }
-            libp2p::swarm::SwarmEvent::ConnectionClosed { peer_id, cause, .. } => {
+            libp2p::swarm::SwarmEvent::ConnectionClosed {
+                peer_id,
+                cause,
+                num_established,
+                ..
+            } => {
                 info!(
                     "Connection closed with peer: {} (cause: {:?})",
                     peer_id, cause
                 );
+                if num_established > 0 {
+                    return Ok(());
+                }
 
-                self.peer_manager
-                    .update_peer_status(&peer_id.to_string(), PeerStatus::Disconnected);
+                let pending = self.pending_disconnects.remove(&peer_id);
+                let (reason, initiator, detail) = classify_disconnect(cause.as_ref(), pending);
+                self.peer_manager.record_disconnect(
+                    &peer_id.to_string(),
+                    reason,
+                    initiator,
+                    detail,
+                );
+                self.propagation.remove_peer(&peer_id.to_string());
+                self.sync.remove_peer(&peer_id.to_string());
+                self.time_sync.remove_peer(&peer_id.to_string());
+                self.schemas.remove_peer(&peer_id.to_string());
 
-                // Send event if handler is set
-                if let Some(sender) = &self.event_sender {
-                    let _ = sender.send(NetworkEvent::PeerDisconnected(peer_id.to_string()));
-                }
+                self.emit(NetworkEvent::PeerDisconnected(peer_id.to_string()));
             }
             libp2p::swarm::SwarmEvent::NewListenAddr { address, .. } => {
                 info!("Listening on: {}", address);
@@ -232,6 +759,89 @@ impl P2PNetwork {
         Ok(())
     }
 
+    /// Broadcast the node info of the local node, which peers negotiate features,
+    /// topic schemas and operator metadata from
+    fn announce_node_info(&mut self) {
+        let result = self
+            .local_node
+            .node_info_message()
+            .and_then(|message| self.broadcast_message(message));
+        if let Err(e) = result {
+            debug!("Failed to announce node info: {}", e);
+        }
+    }
+
+    /// Process a message a peer gossiped and report it to the event handler. `source`
+    /// is the peer that delivered the message, `author` the peer that published it.
+    fn handle_gossip_message(&mut self, source: PeerId, author: PeerId, data: &[u8]) {
+        let message = match Message::from_bytes(data) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Invalid message from peer {}: {}", source, e);
+                return;
+            }
+        };
+        let topic = self.get_topic_for_message(&message.msg_type);
+        if !self.schemas.accepts(&topic, message.schema_version()) {
+            debug!(
+                "Dropping {:?} from peer {}: unsupported schema version {}",
+                message.msg_type,
+                source,
+                message.schema_version()
+            );
+            return;
+        }
+        if let Err(e) = self.local_node.process_message(message.clone()) {
+            warn!("Failed to process message from peer {}: {}", source, e);
+            return;
+        }
+        let author_id = author.to_string();
+        self.observe_heartbeat(&author_id, &message);
+        self.observe_block_message(&source.to_string(), &message);
+        if let Err(e) = self.relay_consensus_message(&source.to_string(), message.clone()) {
+            warn!("Failed to relay {:?}: {}", message.msg_type, e);
+        }
+        if matches!(
+            message.msg_type,
+            MessageType::BlockProposal | MessageType::BlockResponse | MessageType::BlockCommit
+        ) {
+            self.emit(NetworkEvent::PropagationStats(self.get_propagation_stats()));
+        }
+        if matches!(
+            message.msg_type,
+            MessageType::NodeJoin | MessageType::NodeInfo
+        ) {
+            match serde_json::from_slice::<NodeInfoPayload>(&message.payload) {
+                Ok(payload) => self.handle_node_info(&author_id, &payload),
+                Err(e) => warn!("Invalid node info from peer {}: {}", author, e),
+            }
+        }
+
+        let event = match message.msg_type {
+            MessageType::BlockProposal | MessageType::BlockCommit => {
+                match decode_block_proposal(&message) {
+                    Ok(payload) => NetworkEvent::BlockReceived(payload.block_hash),
+                    Err(_) => NetworkEvent::MessageReceived(message),
+                }
+            }
+            MessageType::TransactionBroadcast => {
+                match serde_json::from_slice::<TransactionPayload>(&message.payload) {
+                    Ok(payload) => NetworkEvent::TransactionReceived(payload.tx_hash),
+                    Err(_) => NetworkEvent::MessageReceived(message),
+                }
+            }
+            _ => NetworkEvent::MessageReceived(message),
+        };
+        self.emit(event);
+    }
+
+    /// Send an event to the event handler, if one is set
+    fn emit(&self, event: NetworkEvent) {
+        if let Some(sender) = &self.event_sender {
+            let _ = sender.send(event);
+        }
+    }
+
     /// Connect to bootstrap peers
     async fn connect_to_bootstrap_peers(&mut self) -> Result<()> {
         for addr in &self.config.bootstrap_peers.clone() {
@@ -267,7 +877,7 @@ impl P2PNetwork {
             | MessageType::NodeLeave
             | MessageType::NodeHeartbeat
             | MessageType::NodeInfo
-            | MessageType::PeerDiscovery => "kanari/node-discovery".to_string(),
+            | MessageType::PeerDiscovery => NODE_DISCOVERY_TOPIC.to_string(),
 
             MessageType::PeerConnection | MessageType::PeerDisconnection => {
                 "kanari/peers".to_string()
@@ -278,14 +888,112 @@ impl P2PNetwork {
     }
 }
 
+/// Read the node identity from `path`, generating and saving one on first start
+fn load_or_generate_identity(path: &Path) -> Result<Keypair> {
+    if path.exists() {
+        let keypair = Keypair::from_protobuf_encoding(&std::fs::read(path)?)?;
+        return Ok(keypair);
+    }
+    let keypair = Keypair::generate_ed25519();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Write then rename, so a crash never leaves a truncated key behind
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, keypair.to_protobuf_encoding()?)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::fs::rename(&tmp, path)?;
+    info!("Generated a new node identity in {}", path.display());
+    Ok(keypair)
+}
+
+/// Gossip topic a peer receives its direct messages on
+fn direct_topic(peer_id: &PeerId) -> String {
+    format!("kanari/direct/{}", peer_id)
+}
+
+/// The next command of the node, pending forever when no receiver is set
+async fn recv_command(
+    receiver: &mut Option<mpsc::UnboundedReceiver<NetworkCommand>>,
+) -> Option<NetworkCommand> {
+    match receiver {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Why a connection closed and which side closed it. A connection the node closed
+/// itself without an error keeps the reason it was closed for.
+fn classify_disconnect(
+    cause: Option<&libp2p::swarm::ConnectionError>,
+    pending: Option<(DisconnectReason, Option<String>)>,
+) -> (DisconnectReason, DisconnectInitiator, Option<String>) {
+    use libp2p::swarm::ConnectionError;
+
+    match cause {
+        None => match pending {
+            Some((reason, detail)) => (reason, DisconnectInitiator::Local, detail),
+            None => (DisconnectReason::Closed, DisconnectInitiator::Remote, None),
+        },
+        Some(ConnectionError::KeepAliveTimeout) => {
+            (DisconnectReason::Timeout, DisconnectInitiator::Local, None)
+        }
+        Some(ConnectionError::IO(e)) => {
+            let reason = match e.kind() {
+                ErrorKind::TimedOut => DisconnectReason::Timeout,
+                ErrorKind::InvalidData | ErrorKind::InvalidInput => DisconnectReason::ProtocolError,
+                ErrorKind::UnexpectedEof
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::BrokenPipe => DisconnectReason::Closed,
+                _ => DisconnectReason::TransportError,
+            };
+            (reason, DisconnectInitiator::Remote, Some(e.to_string()))
+        }
+    }
+}
+
 /// Network events that can be sent to external handlers
 #[derive(Debug, Clone)]
 pub enum NetworkEvent {
-    PeerConnected(String),
+    /// A peer connected, with its disconnections before this connection
+    PeerConnected {
+        info: PeerInfo,
+        recent_disconnects: Vec<DisconnectRecord>,
+    },
+    /// A connected peer's handshake arrived, with the feature flags both sides support
+    PeerUpdated {
+        info: PeerInfo,
+        negotiated: Option<Capabilities>,
+        recent_disconnects: Vec<DisconnectRecord>,
+    },
     PeerDisconnected(String),
     MessageReceived(Message),
     BlockReceived(String),
     TransactionReceived(String),
+    /// The network time offset estimated from the peers' heartbeats
+    TimeOffset(TimeOffset),
+    /// Sync progress against the heights peers announced
+    SyncStatus(SyncStatus),
+    /// Peers ranked from fastest to slowest block propagation
+    PropagationStats(Vec<PeerPropagationStats>),
+    /// Aggregate message counters and the most recent messages, newest first
+    MessageHistory {
+        total_messages: u64,
+        counts_by_type: HashMap<String, u64>,
+        recent: Vec<HistoryEntry>,
+    },
+}
+
+/// Commands the node sends to the running network
+#[derive(Debug, Clone)]
+pub enum NetworkCommand {
+    /// The local chain reached a new height
+    SetLocalHeight(u128),
 }
 
 /// Network statistics
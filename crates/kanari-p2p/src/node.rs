@@ -1,7 +1,12 @@
 // Copyright (c) KanariNetwork
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::message::{Message, MessageType, NodeInfoPayload};
+use crate::capability::{Capabilities, Capability};
+use crate::history::{MessageHistory, MessageHistoryConfig};
+use crate::message::{HeartbeatPayload, Message, MessageType, NodeInfoPayload};
+use crate::operator::OperatorCertificate;
+use crate::schema::local_topic_schemas;
+use crate::time_sync::local_time_ms;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -20,6 +25,12 @@ pub struct NodeInfo {
     pub node_type: NodeType,
     pub listening_addresses: Vec<String>,
     pub capabilities: Vec<String>,
+    /// Typed feature flags negotiated at handshake
+    #[serde(default)]
+    pub features: Capabilities,
+    /// Operator metadata signed with the node's network identity
+    #[serde(default)]
+    pub operator: Option<OperatorCertificate>,
     pub joined_at: u64,
     pub last_seen: u64,
     pub initial_balance: u64, // Add initial balance with default 100000
@@ -45,7 +56,8 @@ pub struct Node {
     pub info: NodeInfo,
     pub is_running: bool,
     pub connected_peers: HashMap<NodeId, SystemTime>,
-    pub message_history: Vec<Message>,
+    /// Bounded window of recent messages
+    pub message_history: MessageHistory,
 }
 
 impl Node {
@@ -69,6 +81,8 @@ impl Node {
                 "transaction_processing".to_string(),
                 "consensus_participation".to_string(),
             ],
+            features: Capabilities::default_for(&NodeType::default()),
+            operator: None,
             joined_at: current_time,
             last_seen: current_time,
             initial_balance: 100000, // Default initial balance as requested
@@ -78,7 +92,7 @@ impl Node {
             info,
             is_running: false,
             connected_peers: HashMap::new(),
-            message_history: Vec::new(),
+            message_history: MessageHistory::default(),
         }
     }
 
@@ -94,6 +108,7 @@ impl Node {
         let mut node = Self::new(name, chain_id);
         node.info.node_type = NodeType::Validator;
         node.info.capabilities.push("block_production".to_string());
+        node.info.features = Capabilities::default_for(&NodeType::Validator);
         node
     }
 
@@ -102,9 +117,22 @@ impl Node {
         let mut node = Self::new(name, chain_id);
         node.info.node_type = NodeType::Bootstrap;
         node.info.capabilities.push("peer_discovery".to_string());
+        node.info.features = Capabilities::default_for(&NodeType::Bootstrap);
         node
     }
 
+    /// Advertise an additional feature flag, e.g. `Archive` on nodes keeping full history
+    pub fn with_capability(mut self, capability: Capability) -> Self {
+        self.info.features.insert(capability);
+        self
+    }
+
+    /// Apply a retention policy to the message history
+    pub fn with_history_config(mut self, config: MessageHistoryConfig) -> Self {
+        self.message_history.set_config(config);
+        self
+    }
+
     /// Start the node
     pub fn start(&mut self) -> anyhow::Result<()> {
         if self.is_running {
@@ -138,6 +166,19 @@ impl Node {
         Ok(())
     }
 
+    /// Goodbye message announcing that the node leaves the network
+    pub fn leave_message(&self) -> Message {
+        Message::new(MessageType::NodeLeave, vec![]).with_sender(self.info.id.clone())
+    }
+
+    /// Heartbeat carrying the local time, which peers use to estimate the network time
+    pub fn heartbeat_message(&self) -> anyhow::Result<Message> {
+        let payload = serde_json::to_vec(&HeartbeatPayload {
+            sent_at_ms: local_time_ms(),
+        })?;
+        Ok(Message::new(MessageType::NodeHeartbeat, payload).with_sender(self.info.id.clone()))
+    }
+
     /// Connect to another node
     pub fn connect_to_peer(&mut self, peer_id: NodeId) -> anyhow::Result<()> {
         if self.connected_peers.contains_key(&peer_id) {
@@ -151,7 +192,7 @@ impl Node {
 
         // Send connection message
         let message = self.create_connection_message(&peer_id)?;
-        self.message_history.push(message);
+        self.message_history.record(message);
 
         Ok(())
     }
@@ -173,7 +214,7 @@ impl Node {
         }
 
         tracing::debug!("Sending message: {:?}", message.msg_type);
-        self.message_history.push(message);
+        self.message_history.record(message);
         Ok(())
     }
 
@@ -200,7 +241,7 @@ impl Node {
             }
         }
 
-        self.message_history.push(message);
+        self.message_history.record(message);
         Ok(())
     }
 
@@ -209,7 +250,7 @@ impl Node {
         NodeStats {
             node_id: self.info.id.clone(),
             connected_peers: self.connected_peers.len(),
-            messages_processed: self.message_history.len(),
+            messages_processed: self.message_history.total_recorded() as usize,
             uptime_seconds: if self.is_running {
                 SystemTime::now()
                     .duration_since(UNIX_EPOCH)
@@ -231,9 +272,14 @@ impl Node {
             .as_secs();
     }
 
-    /// Announce node to network
-    fn announce_to_network(&mut self) -> anyhow::Result<()> {
-        let payload = NodeInfoPayload {
+    /// Node info a peer negotiates feature flags, topic schemas and operator metadata from
+    pub fn node_info_message(&self) -> anyhow::Result<Message> {
+        let payload = serde_json::to_vec(&self.node_info_payload())?;
+        Ok(Message::new(MessageType::NodeInfo, payload).with_sender(self.info.id.clone()))
+    }
+
+    fn node_info_payload(&self) -> NodeInfoPayload {
+        NodeInfoPayload {
             node_id: self.info.id.clone(),
             node_type: format!("{:?}", self.info.node_type),
             version: self.info.version.clone(),
@@ -241,13 +287,19 @@ impl Node {
             listening_addresses: self.info.listening_addresses.clone(),
             capabilities: self.info.capabilities.clone(),
             initial_balance: self.info.initial_balance,
-        };
+            topic_schemas: local_topic_schemas(),
+            features: Some(self.info.features.clone()),
+            operator: self.info.operator.clone(),
+        }
+    }
 
-        let payload_bytes = serde_json::to_vec(&payload)?;
+    /// Announce node to network
+    fn announce_to_network(&mut self) -> anyhow::Result<()> {
+        let payload_bytes = serde_json::to_vec(&self.node_info_payload())?;
         let message =
             Message::new(MessageType::NodeJoin, payload_bytes).with_sender(self.info.id.clone());
 
-        self.message_history.push(message);
+        self.message_history.record(message);
         Ok(())
     }
 
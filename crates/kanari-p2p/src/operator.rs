@@ -0,0 +1,137 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use libp2p::identity::{Keypair, PublicKey};
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+
+/// Domain separator prepended to the certificate content before signing
+pub const OPERATOR_CERTIFICATE_DOMAIN: &[u8] = b"KANARI::OperatorCertificate";
+
+/// Public information an operator attaches to its node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperatorMetadata {
+    pub name: String,
+    pub contact: Option<String>,
+    pub website: Option<String>,
+}
+
+/// Operator metadata signed with the node's identity key. A peer can only present
+/// a certificate signed by its own peer ID, so another node can not claim the
+/// operator's name without the operator's key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperatorCertificate {
+    pub metadata: OperatorMetadata,
+    /// Peer ID of the signing node
+    pub peer_id: String,
+    /// Protobuf encoded identity public key
+    pub public_key: Vec<u8>,
+    pub issued_at: u64,
+    pub signature: Vec<u8>,
+}
+
+impl OperatorCertificate {
+    pub fn sign(metadata: OperatorMetadata, keypair: &Keypair) -> Result<Self> {
+        let public_key = keypair.public();
+        let issued_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let peer_id = PeerId::from(public_key.clone()).to_string();
+        let message = signing_message(&metadata, &peer_id, issued_at)?;
+        Ok(Self {
+            signature: keypair.sign(&message)?,
+            metadata,
+            peer_id,
+            public_key: public_key.encode_protobuf(),
+            issued_at,
+        })
+    }
+
+    /// Check the signature and that the certificate belongs to `peer_id`, the
+    /// peer the certificate was received from
+    pub fn verify(&self, peer_id: &str) -> Result<()> {
+        if self.peer_id != peer_id {
+            bail!(
+                "Operator certificate of {} presented by peer {}",
+                self.peer_id,
+                peer_id
+            );
+        }
+        let public_key = PublicKey::try_decode_protobuf(&self.public_key)?;
+        if PeerId::from(public_key.clone()).to_string() != self.peer_id {
+            bail!(
+                "Operator certificate key does not match peer {}",
+                self.peer_id
+            );
+        }
+        let message = signing_message(&self.metadata, &self.peer_id, self.issued_at)?;
+        if !public_key.verify(&message, &self.signature) {
+            bail!(
+                "Invalid operator certificate signature for peer {}",
+                self.peer_id
+            );
+        }
+        Ok(())
+    }
+}
+
+fn signing_message(metadata: &OperatorMetadata, peer_id: &str, issued_at: u64) -> Result<Vec<u8>> {
+    let mut message = OPERATOR_CERTIFICATE_DOMAIN.to_vec();
+    message.extend(bincode::serialize(&(metadata, peer_id, issued_at))?);
+    Ok(message)
+}
+
+/// A peer's operator metadata and whether its certificate checked out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerOperator {
+    pub metadata: OperatorMetadata,
+    /// False if the certificate was not signed by the peer presenting it
+    pub verified: bool,
+}
+
+impl PeerOperator {
+    pub fn from_certificate(peer_id: &str, certificate: &OperatorCertificate) -> Self {
+        let verified = match certificate.verify(peer_id) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("Rejecting operator metadata of peer {}: {}", peer_id, e);
+                false
+            }
+        };
+        Self {
+            metadata: certificate.metadata.clone(),
+            verified,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operator_certificate_detects_impersonation() {
+        let operator = Keypair::generate_ed25519();
+        let impostor = Keypair::generate_ed25519();
+        let metadata = OperatorMetadata {
+            name: "Kanari Labs".to_string(),
+            contact: Some("ops@example.com".to_string()),
+            website: None,
+        };
+
+        let certificate = OperatorCertificate::sign(metadata.clone(), &operator).unwrap();
+        assert!(certificate.verify(&certificate.peer_id).is_ok());
+
+        // Replaying the operator's certificate from another peer
+        let impostor_id = PeerId::from(impostor.public()).to_string();
+        assert!(certificate.verify(&impostor_id).is_err());
+        assert!(!PeerOperator::from_certificate(&impostor_id, &certificate).verified);
+
+        // Altering the signed metadata
+        let mut tampered = certificate.clone();
+        tampered.metadata.name = "Someone Else".to_string();
+        assert!(tampered.verify(&certificate.peer_id).is_err());
+    }
+}
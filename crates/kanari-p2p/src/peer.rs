@@ -1,11 +1,21 @@
 // Copyright (c) KanariNetwork
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::capability::Capabilities;
+use crate::message::MessageType;
 use crate::node::{NodeId, NodeInfo};
+use crate::operator::PeerOperator;
+use prometheus::{IntCounterVec, Opts, Registry};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, SystemTime};
 
+/// Disconnect records kept per peer, the oldest are dropped first
+pub const MAX_DISCONNECT_RECORDS_PER_PEER: usize = 8;
+/// Peers whose disconnect records are kept, including peers no longer known to the
+/// manager. The peer that disconnected least recently is forgotten first.
+pub const MAX_DISCONNECT_HISTORY_PEERS: usize = 1_024;
+
 /// Peer connection status
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum PeerStatus {
@@ -15,6 +25,92 @@ pub enum PeerStatus {
     Failed,
 }
 
+/// Why a peer connection ended
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectReason {
+    /// The connection idled out or the peer stopped answering
+    Timeout,
+    /// The peer was banned for misbehaving
+    Banned,
+    /// The peer sent data the protocol does not allow
+    ProtocolError,
+    /// The node's sentry policy does not allow the peer
+    Rejected,
+    /// The node is shutting down
+    Shutdown,
+    /// The connection was closed without an error
+    Closed,
+    /// The transport failed, e.g. the connection was reset
+    TransportError,
+}
+
+impl DisconnectReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisconnectReason::Timeout => "timeout",
+            DisconnectReason::Banned => "banned",
+            DisconnectReason::ProtocolError => "protocol_error",
+            DisconnectReason::Rejected => "rejected",
+            DisconnectReason::Shutdown => "shutdown",
+            DisconnectReason::Closed => "closed",
+            DisconnectReason::TransportError => "transport_error",
+        }
+    }
+}
+
+/// Which side ended a peer connection
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectInitiator {
+    Local,
+    Remote,
+}
+
+impl DisconnectInitiator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisconnectInitiator::Local => "local",
+            DisconnectInitiator::Remote => "remote",
+        }
+    }
+}
+
+/// A past disconnection of a peer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DisconnectRecord {
+    pub reason: DisconnectReason,
+    pub initiator: DisconnectInitiator,
+    /// The underlying error or the misbehavior, if any
+    pub detail: Option<String>,
+    pub timestamp: SystemTime,
+}
+
+/// Peer disconnections by reason and initiator
+pub struct DisconnectMetrics {
+    disconnects: IntCounterVec,
+}
+
+impl DisconnectMetrics {
+    pub fn new(registry: &Registry) -> anyhow::Result<Self> {
+        let disconnects = IntCounterVec::new(
+            Opts::new(
+                "kanari_p2p_peer_disconnects_total",
+                "Number of peer disconnections by reason and initiating side",
+            ),
+            &["reason", "initiator"],
+        )?;
+        registry.register(Box::new(disconnects.clone()))?;
+        Ok(Self { disconnects })
+    }
+}
+
+impl std::fmt::Debug for DisconnectMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DisconnectMetrics").finish_non_exhaustive()
+    }
+}
+
 /// Peer information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PeerInfo {
@@ -25,6 +121,12 @@ pub struct PeerInfo {
     pub connection_time: Option<SystemTime>,
     pub version: String,
     pub capabilities: Vec<String>,
+    /// Feature flags negotiated at handshake, None until the peer's node info arrives
+    #[serde(default)]
+    pub features: Option<Capabilities>,
+    /// Operator metadata from the peer's handshake, if it published any
+    #[serde(default)]
+    pub operator: Option<PeerOperator>,
     pub latency: Option<Duration>,
     pub reputation_score: i32,
 }
@@ -39,6 +141,8 @@ impl PeerInfo {
             connection_time: None,
             version: "unknown".to_string(),
             capabilities: vec![],
+            features: None,
+            operator: None,
             latency: None,
             reputation_score: 0,
         }
@@ -48,6 +152,15 @@ impl PeerInfo {
         self.status == PeerStatus::Connected
     }
 
+    /// Whether the peer may be sent a message type. Peers whose handshake has not
+    /// arrived yet are not gated.
+    pub fn accepts(&self, msg_type: &MessageType) -> bool {
+        self.features
+            .as_ref()
+            .map(|features| features.accepts(msg_type))
+            .unwrap_or(true)
+    }
+
     pub fn update_last_seen(&mut self) {
         self.last_seen = SystemTime::now();
     }
@@ -87,6 +200,7 @@ impl Peer {
     pub fn update_info(&mut self, node_info: NodeInfo) {
         self.info.version = node_info.version.clone();
         self.info.capabilities = node_info.capabilities.clone();
+        self.info.features = Some(node_info.features.clone());
         self.node_info = Some(node_info);
         self.info.update_last_seen();
     }
@@ -98,6 +212,9 @@ pub struct PeerManager {
     peers: HashMap<NodeId, Peer>,
     max_peers: usize,
     connection_timeout: Duration,
+    /// Recent disconnections per peer, oldest first, kept across reconnections
+    disconnects: HashMap<NodeId, VecDeque<DisconnectRecord>>,
+    metrics: Option<DisconnectMetrics>,
 }
 
 impl PeerManager {
@@ -106,7 +223,64 @@ impl PeerManager {
             peers: HashMap::new(),
             max_peers,
             connection_timeout,
+            disconnects: HashMap::new(),
+            metrics: None,
+        }
+    }
+
+    /// Count disconnections in `metrics`
+    pub fn with_metrics(mut self, metrics: DisconnectMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Mark a peer disconnected and record why, keeping the last
+    /// `MAX_DISCONNECT_RECORDS_PER_PEER` records of the peer
+    pub fn record_disconnect(
+        &mut self,
+        peer_id: &NodeId,
+        reason: DisconnectReason,
+        initiator: DisconnectInitiator,
+        detail: Option<String>,
+    ) {
+        self.update_peer_status(peer_id, PeerStatus::Disconnected);
+        if let Some(metrics) = &self.metrics {
+            metrics
+                .disconnects
+                .with_label_values(&[reason.as_str(), initiator.as_str()])
+                .inc();
+        }
+
+        if !self.disconnects.contains_key(peer_id)
+            && self.disconnects.len() >= MAX_DISCONNECT_HISTORY_PEERS
+        {
+            let forgotten = self
+                .disconnects
+                .iter()
+                .min_by_key(|(_, records)| records.back().map(|record| record.timestamp))
+                .map(|(id, _)| id.clone());
+            if let Some(forgotten) = forgotten {
+                self.disconnects.remove(&forgotten);
+            }
+        }
+        let records = self.disconnects.entry(peer_id.clone()).or_default();
+        if records.len() >= MAX_DISCONNECT_RECORDS_PER_PEER {
+            records.pop_front();
         }
+        records.push_back(DisconnectRecord {
+            reason,
+            initiator,
+            detail,
+            timestamp: SystemTime::now(),
+        });
+    }
+
+    /// Recent disconnections of a peer, oldest first
+    pub fn disconnect_history(&self, peer_id: &NodeId) -> Vec<DisconnectRecord> {
+        self.disconnects
+            .get(peer_id)
+            .map(|records| records.iter().cloned().collect())
+            .unwrap_or_default()
     }
 
     /// Add a new peer
@@ -254,6 +428,21 @@ impl PeerManager {
             .collect()
     }
 
+    /// Record the feature flags a peer negotiated in its handshake
+    pub fn set_peer_features(&mut self, peer_id: &NodeId, features: Capabilities) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.info.features = Some(features);
+            peer.info.update_last_seen();
+        }
+    }
+
+    /// Record the operator metadata a peer presented in its handshake
+    pub fn set_peer_operator(&mut self, peer_id: &NodeId, operator: PeerOperator) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.info.operator = Some(operator);
+        }
+    }
+
     /// Update peer latency
     pub fn update_peer_latency(&mut self, peer_id: &NodeId, latency: Duration) {
         if let Some(peer) = self.peers.get_mut(peer_id) {
@@ -315,4 +504,43 @@ mod tests {
         manager.update_peer_status(&"test-peer".to_string(), PeerStatus::Connected);
         assert_eq!(manager.get_connected_peers().len(), 1);
     }
+
+    #[test]
+    fn test_disconnect_history_is_bounded_and_survives_reconnects() {
+        let mut manager = PeerManager::new(10, Duration::from_secs(30));
+        let peer_id = "test-peer".to_string();
+        manager
+            .add_peer(Peer::new(peer_id.clone(), "127.0.0.1:8080".to_string()))
+            .unwrap();
+        manager.update_peer_status(&peer_id, PeerStatus::Connected);
+
+        manager.record_disconnect(
+            &peer_id,
+            DisconnectReason::Banned,
+            DisconnectInitiator::Local,
+            Some("invalid block".to_string()),
+        );
+        assert!(manager.get_connected_peers().is_empty());
+        // Reconnecting replaces the peer entry, not its history
+        manager
+            .add_peer(Peer::new(peer_id.clone(), "127.0.0.1:8080".to_string()))
+            .unwrap();
+        for _ in 0..MAX_DISCONNECT_RECORDS_PER_PEER {
+            manager.record_disconnect(
+                &peer_id,
+                DisconnectReason::Timeout,
+                DisconnectInitiator::Remote,
+                None,
+            );
+        }
+
+        let history = manager.disconnect_history(&peer_id);
+        assert_eq!(history.len(), MAX_DISCONNECT_RECORDS_PER_PEER);
+        assert!(history
+            .iter()
+            .all(|record| record.reason == DisconnectReason::Timeout));
+        assert!(manager
+            .disconnect_history(&"other-peer".to_string())
+            .is_empty());
+    }
 }
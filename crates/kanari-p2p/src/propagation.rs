@@ -0,0 +1,221 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Maximum number of block hashes tracked for first-seen timestamps
+pub const DEFAULT_MAX_TRACKED_BLOCKS: usize = 1024;
+
+/// Weight of the newest sample in the exponentially weighted latency average
+const LATENCY_EWMA_ALPHA: f64 = 0.2;
+
+/// Per-peer block propagation statistics
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PeerPropagationStats {
+    pub peer_id: NodeId,
+    /// Exponentially weighted average delay between first hearing a block and receiving it from this peer
+    pub avg_latency_ms: f64,
+    /// Latency of the most recent block received from this peer
+    pub last_latency_ms: u64,
+    /// Number of full blocks received from this peer
+    pub blocks_received: u64,
+    /// Number of blocks this peer delivered before any other peer
+    pub first_deliveries: u64,
+}
+
+impl PeerPropagationStats {
+    fn new(peer_id: NodeId) -> Self {
+        Self {
+            peer_id,
+            avg_latency_ms: 0.0,
+            last_latency_ms: 0,
+            blocks_received: 0,
+            first_deliveries: 0,
+        }
+    }
+
+    fn record(&mut self, latency: Duration, first: bool) {
+        let latency_ms = latency.as_millis() as u64;
+        self.avg_latency_ms = if self.blocks_received == 0 {
+            latency_ms as f64
+        } else {
+            LATENCY_EWMA_ALPHA * latency_ms as f64
+                + (1.0 - LATENCY_EWMA_ALPHA) * self.avg_latency_ms
+        };
+        self.last_latency_ms = latency_ms;
+        self.blocks_received += 1;
+        if first {
+            self.first_deliveries += 1;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BlockSighting {
+    first_seen: Instant,
+    delivered_by: HashSet<NodeId>,
+}
+
+/// Tracks how fast each peer propagates blocks and ranks peers for block requests
+#[derive(Debug)]
+pub struct PropagationTracker {
+    sightings: HashMap<String, BlockSighting>,
+    order: VecDeque<String>,
+    stats: HashMap<NodeId, PeerPropagationStats>,
+    max_tracked_blocks: usize,
+}
+
+impl Default for PropagationTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_TRACKED_BLOCKS)
+    }
+}
+
+impl PropagationTracker {
+    pub fn new(max_tracked_blocks: usize) -> Self {
+        Self {
+            sightings: HashMap::new(),
+            order: VecDeque::new(),
+            stats: HashMap::new(),
+            max_tracked_blocks: max_tracked_blocks.max(1),
+        }
+    }
+
+    /// Record that a block hash was heard of, keeping the earliest timestamp
+    pub fn record_announcement(&mut self, block_hash: &str) {
+        self.record_announcement_at(block_hash, Instant::now());
+    }
+
+    /// Record that a peer delivered the full block
+    pub fn record_block_received(&mut self, peer_id: &NodeId, block_hash: &str) {
+        self.record_block_received_at(peer_id, block_hash, Instant::now());
+    }
+
+    fn record_announcement_at(&mut self, block_hash: &str, at: Instant) {
+        if self.sightings.contains_key(block_hash) {
+            return;
+        }
+
+        while self.order.len() >= self.max_tracked_blocks {
+            if let Some(oldest) = self.order.pop_front() {
+                self.sightings.remove(&oldest);
+            }
+        }
+
+        self.sightings.insert(
+            block_hash.to_string(),
+            BlockSighting {
+                first_seen: at,
+                delivered_by: HashSet::new(),
+            },
+        );
+        self.order.push_back(block_hash.to_string());
+    }
+
+    fn record_block_received_at(&mut self, peer_id: &NodeId, block_hash: &str, at: Instant) {
+        // Receiving a full block we never heard of counts as hearing of it
+        self.record_announcement_at(block_hash, at);
+
+        let sighting = match self.sightings.get_mut(block_hash) {
+            Some(sighting) => sighting,
+            None => return,
+        };
+        if !sighting.delivered_by.insert(peer_id.clone()) {
+            return;
+        }
+
+        let first = sighting.delivered_by.len() == 1;
+        let latency = at.saturating_duration_since(sighting.first_seen);
+        self.stats
+            .entry(peer_id.clone())
+            .or_insert_with(|| PeerPropagationStats::new(peer_id.clone()))
+            .record(latency, first);
+
+        tracing::debug!(
+            "Peer {} delivered block {} after {:?}",
+            peer_id,
+            block_hash,
+            latency
+        );
+    }
+
+    /// Forget a peer, e.g. after it disconnected
+    pub fn remove_peer(&mut self, peer_id: &NodeId) {
+        self.stats.remove(peer_id);
+    }
+
+    /// Get the statistics for a single peer
+    pub fn get_peer_stats(&self, peer_id: &NodeId) -> Option<&PeerPropagationStats> {
+        self.stats.get(peer_id)
+    }
+
+    /// All peers ranked from fastest to slowest propagation
+    pub fn ranking(&self) -> Vec<PeerPropagationStats> {
+        let mut ranking: Vec<PeerPropagationStats> = self.stats.values().cloned().collect();
+        ranking.sort_by(|a, b| {
+            a.avg_latency_ms
+                .partial_cmp(&b.avg_latency_ms)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.first_deliveries.cmp(&a.first_deliveries))
+                .then_with(|| a.peer_id.cmp(&b.peer_id))
+        });
+        ranking
+    }
+
+    /// Order candidate peers for block requests, fastest known peers first.
+    /// Peers without measurements keep their relative order after the ranked ones.
+    pub fn preferred_peers(&self, candidates: &[NodeId]) -> Vec<NodeId> {
+        let ranking = self.ranking();
+        let mut preferred: Vec<NodeId> = ranking
+            .into_iter()
+            .map(|stats| stats.peer_id)
+            .filter(|peer_id| candidates.contains(peer_id))
+            .collect();
+        for candidate in candidates {
+            if !preferred.contains(candidate) {
+                preferred.push(candidate.clone());
+            }
+        }
+        preferred
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peer_ranking_by_latency() {
+        let mut tracker = PropagationTracker::default();
+        let start = Instant::now();
+        let fast = "fast-peer".to_string();
+        let slow = "slow-peer".to_string();
+
+        tracker.record_announcement_at("0xabc", start);
+        tracker.record_block_received_at(&fast, "0xabc", start + Duration::from_millis(10));
+        tracker.record_block_received_at(&slow, "0xabc", start + Duration::from_millis(500));
+
+        let ranking = tracker.ranking();
+        assert_eq!(ranking[0].peer_id, fast);
+        assert_eq!(ranking[0].first_deliveries, 1);
+        assert_eq!(ranking[1].peer_id, slow);
+        assert_eq!(ranking[1].last_latency_ms, 500);
+
+        let unknown = "unknown-peer".to_string();
+        let preferred = tracker.preferred_peers(&[unknown.clone(), slow.clone(), fast.clone()]);
+        assert_eq!(preferred, vec![fast, slow, unknown]);
+    }
+
+    #[test]
+    fn test_tracked_blocks_are_bounded() {
+        let mut tracker = PropagationTracker::new(2);
+        tracker.record_announcement("0x1");
+        tracker.record_announcement("0x2");
+        tracker.record_announcement("0x3");
+        assert_eq!(tracker.sightings.len(), 2);
+        assert!(!tracker.sightings.contains_key("0x1"));
+    }
+}
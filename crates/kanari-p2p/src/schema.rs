@@ -0,0 +1,233 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::message::{BlockProposalPayload, BlockProposalPayloadV2, Message};
+use crate::node::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Version of a gossip topic payload schema
+pub type SchemaVersion = u16;
+
+/// Metadata key of the payload schema version in the message envelope
+pub const SCHEMA_VERSION_METADATA_KEY: &str = "schema_version";
+
+/// Schema version assumed for messages from peers that predate versioning
+pub const LEGACY_SCHEMA_VERSION: SchemaVersion = 1;
+
+pub const BLOCKS_TOPIC: &str = "kanari/blocks";
+pub const TRANSACTIONS_TOPIC: &str = "kanari/transactions";
+pub const CONSENSUS_TOPIC: &str = "kanari/consensus";
+
+/// Inclusive range of payload schema versions a node can decode for a topic.
+/// During a transition window `min` stays at the old version while `max` moves on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SchemaRange {
+    pub min: SchemaVersion,
+    pub max: SchemaVersion,
+}
+
+impl SchemaRange {
+    pub fn new(min: SchemaVersion, max: SchemaVersion) -> Self {
+        Self { min, max }
+    }
+
+    pub fn supports(&self, version: SchemaVersion) -> bool {
+        self.min <= version && version <= self.max
+    }
+
+    /// Highest version both ranges support
+    pub fn negotiate(&self, other: &SchemaRange) -> Option<SchemaVersion> {
+        let version = self.max.min(other.max);
+        if version >= self.min.max(other.min) {
+            Some(version)
+        } else {
+            None
+        }
+    }
+}
+
+/// Per-topic schema ranges supported by this build, advertised in the node handshake
+pub fn local_topic_schemas() -> HashMap<String, SchemaRange> {
+    HashMap::from([
+        (BLOCKS_TOPIC.to_string(), SchemaRange::new(1, 2)),
+        (TRANSACTIONS_TOPIC.to_string(), SchemaRange::new(1, 1)),
+        (CONSENSUS_TOPIC.to_string(), SchemaRange::new(1, 1)),
+    ])
+}
+
+impl Message {
+    /// Stamp the payload schema version into the envelope
+    pub fn with_schema_version(self, version: SchemaVersion) -> Self {
+        self.with_metadata(SCHEMA_VERSION_METADATA_KEY.to_string(), version.to_string())
+    }
+
+    /// The payload schema version, messages without one are treated as legacy
+    pub fn schema_version(&self) -> SchemaVersion {
+        self.metadata
+            .get(SCHEMA_VERSION_METADATA_KEY)
+            .and_then(|version| version.parse().ok())
+            .unwrap_or(LEGACY_SCHEMA_VERSION)
+    }
+}
+
+/// Tracks the schema ranges advertised by peers and picks encodings everyone understands
+#[derive(Debug)]
+pub struct SchemaNegotiator {
+    local: HashMap<String, SchemaRange>,
+    peers: HashMap<NodeId, HashMap<String, SchemaRange>>,
+}
+
+impl Default for SchemaNegotiator {
+    fn default() -> Self {
+        Self::new(local_topic_schemas())
+    }
+}
+
+impl SchemaNegotiator {
+    pub fn new(local: HashMap<String, SchemaRange>) -> Self {
+        Self {
+            local,
+            peers: HashMap::new(),
+        }
+    }
+
+    pub fn local_schemas(&self) -> &HashMap<String, SchemaRange> {
+        &self.local
+    }
+
+    /// Record the ranges a peer advertised in its handshake
+    pub fn register_peer(&mut self, peer_id: NodeId, schemas: HashMap<String, SchemaRange>) {
+        self.peers.insert(peer_id, schemas);
+    }
+
+    pub fn remove_peer(&mut self, peer_id: &NodeId) {
+        self.peers.remove(peer_id);
+    }
+
+    /// Whether this node can decode a message with the given version on a topic
+    pub fn accepts(&self, topic: &str, version: SchemaVersion) -> bool {
+        self.local
+            .get(topic)
+            .map(|range| range.supports(version))
+            .unwrap_or(version == LEGACY_SCHEMA_VERSION)
+    }
+
+    /// Version to use when sending a topic message to a single peer
+    pub fn version_for_peer(&self, peer_id: &NodeId, topic: &str) -> Option<SchemaVersion> {
+        let local = self.local.get(topic)?;
+        let legacy = SchemaRange::new(LEGACY_SCHEMA_VERSION, LEGACY_SCHEMA_VERSION);
+        let remote = self
+            .peers
+            .get(peer_id)
+            .and_then(|schemas| schemas.get(topic))
+            .unwrap_or(&legacy);
+        local.negotiate(remote)
+    }
+
+    /// Version to use when gossiping a topic: the highest version every known peer can decode.
+    /// Peers without a common version are left out rather than holding back the whole network.
+    pub fn version_for_broadcast(&self, topic: &str) -> Option<SchemaVersion> {
+        let local_max = self.local.get(topic)?.max;
+        Some(
+            self.peers
+                .keys()
+                .filter_map(|peer_id| self.version_for_peer(peer_id, topic))
+                .min()
+                .unwrap_or(local_max),
+        )
+    }
+}
+
+/// Decode a block proposal in any supported schema version, upgrading v1 payloads to v2
+pub fn decode_block_proposal(message: &Message) -> anyhow::Result<BlockProposalPayloadV2> {
+    match message.schema_version() {
+        1 => {
+            let payload: BlockProposalPayload = serde_json::from_slice(&message.payload)?;
+            Ok(payload.into())
+        }
+        2 => Ok(serde_json::from_slice(&message.payload)?),
+        version => anyhow::bail!("Unsupported block proposal schema version {}", version),
+    }
+}
+
+/// Encode a block proposal in the requested schema version
+pub fn encode_block_proposal(
+    payload: &BlockProposalPayloadV2,
+    version: SchemaVersion,
+) -> anyhow::Result<Vec<u8>> {
+    match version {
+        1 => Ok(serde_json::to_vec(&BlockProposalPayload::from(
+            payload.clone(),
+        ))?),
+        2 => Ok(serde_json::to_vec(payload)?),
+        version => anyhow::bail!("Unsupported block proposal schema version {}", version),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MessageType;
+
+    fn sample_proposal() -> BlockProposalPayloadV2 {
+        BlockProposalPayloadV2 {
+            block_number: 7,
+            block_hash: "0xabc".to_string(),
+            parent_hash: "0xab".to_string(),
+            proposer: "proposer".to_string(),
+            timestamp: 1,
+            transactions: vec!["0x1".to_string()],
+            state_root: Some("0xdef".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_negotiation_during_transition_window() {
+        let mut negotiator = SchemaNegotiator::default();
+        let old_peer = "old-peer".to_string();
+        let new_peer = "new-peer".to_string();
+        negotiator.register_peer(
+            new_peer.clone(),
+            HashMap::from([(BLOCKS_TOPIC.to_string(), SchemaRange::new(1, 2))]),
+        );
+        assert_eq!(negotiator.version_for_broadcast(BLOCKS_TOPIC), Some(2));
+
+        // A peer that never advertised schemas only understands the legacy encoding
+        negotiator.register_peer(old_peer.clone(), HashMap::new());
+        assert_eq!(
+            negotiator.version_for_peer(&old_peer, BLOCKS_TOPIC),
+            Some(1)
+        );
+        assert_eq!(
+            negotiator.version_for_peer(&new_peer, BLOCKS_TOPIC),
+            Some(2)
+        );
+        assert_eq!(negotiator.version_for_broadcast(BLOCKS_TOPIC), Some(1));
+
+        negotiator.remove_peer(&old_peer);
+        assert_eq!(negotiator.version_for_broadcast(BLOCKS_TOPIC), Some(2));
+        assert!(!negotiator.accepts(BLOCKS_TOPIC, 3));
+    }
+
+    #[test]
+    fn test_dual_decoding_block_proposals() {
+        let proposal = sample_proposal();
+        for version in [1, 2] {
+            let payload = encode_block_proposal(&proposal, version).unwrap();
+            let message =
+                Message::new(MessageType::BlockProposal, payload).with_schema_version(version);
+            let decoded = decode_block_proposal(&message).unwrap();
+            assert_eq!(decoded.block_hash, proposal.block_hash);
+            assert_eq!(decoded.state_root.is_some(), version == 2);
+        }
+
+        // Messages without a version in the envelope are decoded as v1
+        let legacy = Message::new(
+            MessageType::BlockProposal,
+            encode_block_proposal(&proposal, 1).unwrap(),
+        );
+        assert_eq!(legacy.schema_version(), LEGACY_SCHEMA_VERSION);
+        assert!(decode_block_proposal(&legacy).is_ok());
+    }
+}
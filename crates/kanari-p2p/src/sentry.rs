@@ -0,0 +1,169 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::message::{Message, MessageType};
+use crate::node::NodeId;
+use kanari_config::network_config::NodeRole;
+use std::collections::{HashSet, VecDeque};
+use uuid::Uuid;
+
+/// Maximum number of relayed message IDs remembered to break relay loops
+pub const DEFAULT_MAX_RELAYED_MESSAGES: usize = 4096;
+
+/// Enforces the sentry architecture: validators only talk to their own sentries,
+/// sentries face the public network and relay consensus messages in both directions
+/// without revealing the validators behind them.
+#[derive(Debug)]
+pub struct SentryPolicy {
+    role: NodeRole,
+    private_peers: HashSet<NodeId>,
+    relayed: HashSet<Uuid>,
+    relayed_order: VecDeque<Uuid>,
+    max_relayed: usize,
+}
+
+impl SentryPolicy {
+    pub fn new(role: NodeRole, private_peers: Vec<NodeId>) -> Self {
+        Self {
+            role,
+            private_peers: private_peers.into_iter().collect(),
+            relayed: HashSet::new(),
+            relayed_order: VecDeque::new(),
+            max_relayed: DEFAULT_MAX_RELAYED_MESSAGES,
+        }
+    }
+
+    pub fn role(&self) -> NodeRole {
+        self.role
+    }
+
+    pub fn is_private_peer(&self, peer_id: &NodeId) -> bool {
+        self.private_peers.contains(peer_id)
+    }
+
+    /// Whether a connection with the peer is allowed at all
+    pub fn allow_connection(&self, peer_id: &NodeId) -> bool {
+        match self.role {
+            NodeRole::Validator => self.is_private_peer(peer_id),
+            NodeRole::Full | NodeRole::Sentry => true,
+        }
+    }
+
+    /// Whether the peer may be shared with others through peer discovery
+    pub fn should_advertise(&self, peer_id: &NodeId) -> bool {
+        match self.role {
+            NodeRole::Sentry => !self.is_private_peer(peer_id),
+            NodeRole::Validator => false,
+            NodeRole::Full => true,
+        }
+    }
+
+    /// Peers a consensus message received from `from` must be forwarded to.
+    /// Each message is relayed at most once; other message types rely on gossip.
+    pub fn relay_targets(
+        &mut self,
+        message: &Message,
+        from: &NodeId,
+        connected: &[NodeId],
+    ) -> Vec<NodeId> {
+        if self.role != NodeRole::Sentry || !Self::is_consensus_message(&message.msg_type) {
+            return vec![];
+        }
+        if !self.mark_relayed(message.id) {
+            return vec![];
+        }
+
+        let from_private = self.is_private_peer(from);
+        connected
+            .iter()
+            .filter(|peer_id| *peer_id != from)
+            .filter(|peer_id| {
+                if from_private {
+                    // Validator output goes to the public network and the other validators
+                    true
+                } else {
+                    // Public consensus traffic is delivered to the shielded validators only
+                    self.is_private_peer(peer_id)
+                }
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn is_consensus_message(msg_type: &MessageType) -> bool {
+        matches!(
+            msg_type,
+            MessageType::ConsensusProposal
+                | MessageType::ConsensusVote
+                | MessageType::ConsensusCommit
+                | MessageType::BlockProposal
+                | MessageType::BlockCommit
+        )
+    }
+
+    fn mark_relayed(&mut self, id: Uuid) -> bool {
+        if !self.relayed.insert(id) {
+            return false;
+        }
+        self.relayed_order.push_back(id);
+        while self.relayed_order.len() > self.max_relayed {
+            if let Some(oldest) = self.relayed_order.pop_front() {
+                self.relayed.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peers() -> (NodeId, NodeId, NodeId) {
+        (
+            "validator".to_string(),
+            "public-a".to_string(),
+            "public-b".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_validator_only_peers_with_sentries() {
+        let policy = SentryPolicy::new(NodeRole::Validator, vec!["sentry".to_string()]);
+        assert!(policy.allow_connection(&"sentry".to_string()));
+        assert!(!policy.allow_connection(&"stranger".to_string()));
+        assert!(!policy.should_advertise(&"sentry".to_string()));
+    }
+
+    #[test]
+    fn test_sentry_relays_validator_messages_to_public_peers() {
+        let (validator, public_a, public_b) = peers();
+        let mut policy = SentryPolicy::new(NodeRole::Sentry, vec![validator.clone()]);
+        let connected = vec![validator.clone(), public_a.clone(), public_b.clone()];
+
+        let vote = Message::new(MessageType::ConsensusVote, vec![1]);
+        let targets = policy.relay_targets(&vote, &validator, &connected);
+        assert_eq!(targets, vec![public_a.clone(), public_b.clone()]);
+
+        // The same message is never relayed twice
+        assert!(policy
+            .relay_targets(&vote, &public_a, &connected)
+            .is_empty());
+        assert!(!policy.should_advertise(&validator));
+        assert!(policy.should_advertise(&public_a));
+    }
+
+    #[test]
+    fn test_sentry_relays_public_messages_to_validators_only() {
+        let (validator, public_a, public_b) = peers();
+        let mut policy = SentryPolicy::new(NodeRole::Sentry, vec![validator.clone()]);
+        let connected = vec![validator.clone(), public_a.clone(), public_b];
+
+        let proposal = Message::new(MessageType::ConsensusProposal, vec![2]);
+        let targets = policy.relay_targets(&proposal, &public_a, &connected);
+        assert_eq!(targets, vec![validator]);
+
+        let tx = Message::new(MessageType::TransactionBroadcast, vec![3]);
+        assert!(policy.relay_targets(&tx, &public_a, &connected).is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Period over which the sync rate is measured
+pub const SYNC_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Sync progress of a peer ahead of the local chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PeerSyncProgress {
+    pub peer_id: NodeId,
+    /// Highest block number the peer announced or delivered
+    pub height: u128,
+    /// Number of blocks received from this peer
+    pub blocks_received: u64,
+}
+
+/// Global sync progress of the local node
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SyncStatus {
+    pub is_syncing: bool,
+    pub current_height: u128,
+    /// Highest block number known from any peer
+    pub target_height: u128,
+    /// Local chain growth over the last `SYNC_RATE_WINDOW`
+    pub blocks_per_second: f64,
+    /// Estimated seconds until the target is reached, None while the rate is unknown
+    pub eta_seconds: Option<u64>,
+    /// Peers ahead of the local chain, highest first
+    pub active_peers: Vec<PeerSyncProgress>,
+}
+
+/// Tracks the local height against the heights peers report
+#[derive(Debug, Default)]
+pub struct SyncTracker {
+    current_height: u128,
+    samples: VecDeque<(Instant, u128)>,
+    peers: HashMap<NodeId, PeerSyncProgress>,
+}
+
+impl SyncTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the height of the local chain
+    pub fn set_local_height(&mut self, height: u128) {
+        self.set_local_height_at(height, Instant::now());
+    }
+
+    fn set_local_height_at(&mut self, height: u128, at: Instant) {
+        self.current_height = height;
+        self.samples.push_back((at, height));
+        while let Some((oldest, _)) = self.samples.front() {
+            if at.saturating_duration_since(*oldest) <= SYNC_RATE_WINDOW {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    /// Record that a peer announced or delivered a block
+    pub fn record_peer_block(&mut self, peer_id: &NodeId, block_number: u128) {
+        let progress = self
+            .peers
+            .entry(peer_id.clone())
+            .or_insert_with(|| PeerSyncProgress {
+                peer_id: peer_id.clone(),
+                height: 0,
+                blocks_received: 0,
+            });
+        progress.height = progress.height.max(block_number);
+        progress.blocks_received += 1;
+    }
+
+    pub fn remove_peer(&mut self, peer_id: &NodeId) {
+        self.peers.remove(peer_id);
+    }
+
+    pub fn status(&self) -> SyncStatus {
+        let target_height = self
+            .peers
+            .values()
+            .map(|peer| peer.height)
+            .max()
+            .unwrap_or(0)
+            .max(self.current_height);
+        let blocks_per_second = self.blocks_per_second();
+        let remaining = target_height - self.current_height;
+        let eta_seconds =
+            (blocks_per_second > 0.0).then(|| (remaining as f64 / blocks_per_second).ceil() as u64);
+
+        let mut active_peers: Vec<PeerSyncProgress> = self
+            .peers
+            .values()
+            .filter(|peer| peer.height > self.current_height)
+            .cloned()
+            .collect();
+        active_peers.sort_by(|a, b| b.height.cmp(&a.height));
+
+        SyncStatus {
+            is_syncing: remaining > 0,
+            current_height: self.current_height,
+            target_height,
+            blocks_per_second,
+            eta_seconds,
+            active_peers,
+        }
+    }
+
+    /// A one line summary for periodic logs, None when the node is caught up
+    pub fn progress_line(&self) -> Option<String> {
+        let status = self.status();
+        if !status.is_syncing {
+            return None;
+        }
+        let eta = status
+            .eta_seconds
+            .map(|eta| format!("{}s", eta))
+            .unwrap_or_else(|| "unknown".to_string());
+        Some(format!(
+            "Syncing {}/{} ({:.1}%), {:.2} blocks/s, ETA {}, {} peer(s)",
+            status.current_height,
+            status.target_height,
+            status.current_height as f64 * 100.0 / status.target_height as f64,
+            status.blocks_per_second,
+            eta,
+            status.active_peers.len()
+        ))
+    }
+
+    fn blocks_per_second(&self) -> f64 {
+        let (Some((first_at, first_height)), Some((last_at, last_height))) =
+            (self.samples.front(), self.samples.back())
+        else {
+            return 0.0;
+        };
+        let elapsed = last_at.saturating_duration_since(*first_at).as_secs_f64();
+        if elapsed == 0.0 {
+            return 0.0;
+        }
+        last_height.saturating_sub(*first_height) as f64 / elapsed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_status_rate_and_eta() {
+        let mut tracker = SyncTracker::new();
+        let start = Instant::now();
+        tracker.set_local_height_at(100, start);
+        tracker.record_peer_block(&"peer-a".to_string(), 1_100);
+        tracker.record_peer_block(&"peer-b".to_string(), 50);
+        tracker.set_local_height_at(300, start + Duration::from_secs(20));
+
+        let status = tracker.status();
+        assert!(status.is_syncing);
+        assert_eq!(status.target_height, 1_100);
+        assert_eq!(status.blocks_per_second, 10.0);
+        assert_eq!(status.eta_seconds, Some(80));
+        assert_eq!(status.active_peers.len(), 1);
+        assert!(tracker.progress_line().is_some());
+
+        tracker.set_local_height_at(1_100, start + Duration::from_secs(100));
+        assert!(!tracker.status().is_syncing);
+        assert!(tracker.progress_line().is_none());
+    }
+}
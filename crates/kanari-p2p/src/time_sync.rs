@@ -0,0 +1,165 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How often a heartbeat carrying the local time is broadcast
+pub const TIME_SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A peer's sample is discarded once it is older than this
+pub const TIME_SAMPLE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Below this many peer samples the network offset stays zero, so a single peer can
+/// not move the local clock
+pub const MIN_TIME_SAMPLES: usize = 3;
+
+/// Peers whose clock is further off than this are ignored as broken or malicious
+pub const MAX_PEER_TIME_OFFSET_MS: i64 = 5 * 60 * 1000;
+
+/// Milliseconds since the Unix epoch according to the local clock
+pub fn local_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Estimated offset of the network time from the local clock
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TimeOffset {
+    /// Network time minus local time in milliseconds, add it to the local clock
+    pub offset_ms: i64,
+    /// Number of peer samples the offset is the median of
+    pub samples: usize,
+}
+
+impl TimeOffset {
+    /// The local time corrected by the offset, in milliseconds since the Unix epoch
+    pub fn network_time_ms(&self) -> u64 {
+        local_time_ms().saturating_add_signed(self.offset_ms)
+    }
+}
+
+/// Collects the clock offsets of peers from the timestamps in their heartbeats
+#[derive(Debug, Default)]
+pub struct TimeSyncTracker {
+    /// Latest offset per peer and when it was measured
+    samples: HashMap<NodeId, (i64, Instant)>,
+}
+
+impl TimeSyncTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a heartbeat sent by a peer at `sent_at_ms` and received now. The
+    /// transit time is not known, so the sample slightly underestimates the
+    /// peer's clock.
+    pub fn record_sample(&mut self, peer_id: &NodeId, sent_at_ms: u64) {
+        self.record_sample_at(peer_id, sent_at_ms, local_time_ms(), Instant::now());
+    }
+
+    fn record_sample_at(
+        &mut self,
+        peer_id: &NodeId,
+        sent_at_ms: u64,
+        received_at_ms: u64,
+        at: Instant,
+    ) {
+        let offset = sent_at_ms as i64 - received_at_ms as i64;
+        if offset.abs() > MAX_PEER_TIME_OFFSET_MS {
+            tracing::debug!(
+                "Ignoring time sample of peer {}: offset {}ms",
+                peer_id,
+                offset
+            );
+            self.samples.remove(peer_id);
+            return;
+        }
+        self.samples.insert(peer_id.clone(), (offset, at));
+    }
+
+    pub fn remove_peer(&mut self, peer_id: &NodeId) {
+        self.samples.remove(peer_id);
+    }
+
+    /// Median offset of the recent peer samples, zero until `MIN_TIME_SAMPLES` peers
+    /// reported their time
+    pub fn offset(&self) -> TimeOffset {
+        self.offset_at(Instant::now())
+    }
+
+    fn offset_at(&self, now: Instant) -> TimeOffset {
+        let mut offsets: Vec<i64> = self
+            .samples
+            .values()
+            .filter(|(_, at)| now.saturating_duration_since(*at) <= TIME_SAMPLE_TTL)
+            .map(|(offset, _)| *offset)
+            .collect();
+        if offsets.len() < MIN_TIME_SAMPLES {
+            return TimeOffset {
+                offset_ms: 0,
+                samples: offsets.len(),
+            };
+        }
+        offsets.sort_unstable();
+        let middle = offsets.len() / 2;
+        let offset_ms = if offsets.len() % 2 == 0 {
+            (offsets[middle - 1] + offsets[middle]) / 2
+        } else {
+            offsets[middle]
+        };
+        TimeOffset {
+            offset_ms,
+            samples: offsets.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median_offset_ignores_outliers_and_stale_samples() {
+        let mut tracker = TimeSyncTracker::new();
+        let start = Instant::now();
+        let local = 1_700_000_000_000;
+        let peer = |id: &str| id.to_string();
+
+        tracker.record_sample_at(&peer("a"), local + 2_000, local, start);
+        tracker.record_sample_at(&peer("b"), local + 1_000, local, start);
+        // Two samples are not enough to move the clock
+        assert_eq!(
+            tracker.offset_at(start),
+            TimeOffset {
+                offset_ms: 0,
+                samples: 2
+            }
+        );
+
+        tracker.record_sample_at(&peer("c"), local - 500, local, start);
+        // Far beyond the accepted range, not counted
+        tracker.record_sample_at(&peer("d"), local + 3_600_000, local, start);
+        assert_eq!(
+            tracker.offset_at(start),
+            TimeOffset {
+                offset_ms: 1_000,
+                samples: 3
+            }
+        );
+
+        tracker.record_sample_at(
+            &peer("e"),
+            local + 3_000,
+            local,
+            start + Duration::from_secs(60),
+        );
+        assert_eq!(tracker.offset_at(start).offset_ms, 1_500);
+        let later = start + TIME_SAMPLE_TTL + Duration::from_secs(1);
+        assert_eq!(tracker.offset_at(later).samples, 1);
+    }
+}
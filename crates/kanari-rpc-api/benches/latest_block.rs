@@ -0,0 +1,98 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+//! Latency of `kanari_getLatestBlock` and `kanari_getBlockByNumber` under concurrent
+//! load, answered from the block cache and from the database.
+//!
+//! Run with `cargo bench -p kanari-rpc-api --bench latest_block`.
+
+use kanari_config::KanariOpt;
+use kanari_db::RoochDB;
+use kanari_mempool::TxPool;
+use kanari_rpc_api::{BlockCache, KanariRpcApiServer, KanariRpcImpl, NodeState};
+use kanari_types::block::Block;
+use moveos_types::h256::H256;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+const BLOCKS: u128 = 1_000;
+const TASKS: usize = 64;
+const CALLS_PER_TASK: usize = 2_000;
+
+fn percentile(sorted: &[Duration], percentile: f64) -> Duration {
+    let index = ((sorted.len() - 1) as f64 * percentile).round() as usize;
+    sorted[index]
+}
+
+/// Call the hot methods from `TASKS` concurrent tasks and print the latency distribution
+async fn run(name: &str, kanari: Arc<KanariRpcImpl>) {
+    let started = Instant::now();
+    let tasks: Vec<_> = (0..TASKS)
+        .map(|task| {
+            let kanari = kanari.clone();
+            tokio::spawn(async move {
+                let mut latencies = Vec::with_capacity(CALLS_PER_TASK);
+                for call in 0..CALLS_PER_TASK {
+                    let call_started = Instant::now();
+                    if (task + call) % 2 == 0 {
+                        kanari.get_latest_block().await.unwrap();
+                    } else {
+                        kanari.get_block_by_number(BLOCKS).await.unwrap();
+                    }
+                    latencies.push(call_started.elapsed());
+                }
+                latencies
+            })
+        })
+        .collect();
+    let mut latencies = vec![];
+    for task in tasks {
+        latencies.extend(task.await.unwrap());
+    }
+    let elapsed = started.elapsed();
+    latencies.sort_unstable();
+    println!(
+        "{:<8} {:>9.0} calls/s  p50 {:>9?}  p99 {:>9?}  max {:>9?}",
+        name,
+        latencies.len() as f64 / elapsed.as_secs_f64(),
+        percentile(&latencies, 0.50),
+        percentile(&latencies, 0.99),
+        latencies[latencies.len() - 1],
+    );
+}
+
+#[tokio::main]
+async fn main() {
+    let opt = KanariOpt::new_with_temp_store().unwrap();
+    let db = Arc::new(RoochDB::init(&opt.store, &prometheus::Registry::new()).unwrap());
+    let mut parent = H256::zero();
+    for number in 1..=BLOCKS {
+        let block = Block::new(
+            number,
+            0,
+            H256::random(),
+            parent,
+            H256::random(),
+            H256::random(),
+        );
+        db.save_block(&block, 1_700_000_000 + number as u64)
+            .unwrap();
+        parent = block.hash();
+    }
+    let node_state = Arc::new(RwLock::new(NodeState {
+        block_height: BLOCKS,
+        ..Default::default()
+    }));
+    let tx_pool = Arc::new(RwLock::new(TxPool::default()));
+    let kanari = |block_cache: BlockCache| {
+        Arc::new(
+            KanariRpcImpl::new(node_state.clone(), tx_pool.clone(), Some(db.clone()))
+                .with_block_cache(Arc::new(block_cache)),
+        )
+    };
+
+    // A cache without capacity never holds a block, every call reads the database
+    run("database", kanari(BlockCache::new(0))).await;
+    run("cached", kanari(BlockCache::default())).await;
+}
@@ -3,59 +3,317 @@
 
 use crate::error::RpcResult;
 use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::SubscriptionId;
+use rooch_open_rpc::Project;
+use rooch_open_rpc_macros::open_rpc;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Node information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NodeInfo {
     pub version: String,
     pub chain_id: u64,
+    /// Hash of the genesis block, None until the first block exists
+    pub genesis_hash: Option<String>,
     pub node_type: String,
     pub peer_count: usize,
     pub block_height: u128,
     pub is_syncing: bool,
     pub uptime_seconds: u64,
+    /// Operator metadata the node publishes, if configured
+    pub operator: Option<OperatorInfo>,
+    /// Port the public RPC server listens on
+    pub rpc_port: Option<u16>,
+    /// Port the P2P transport listens on
+    pub p2p_port: Option<u16>,
+    /// Whether the node stopped producing blocks and accepting transactions because
+    /// its disk is almost full
+    pub read_only: bool,
+    /// Chain parameters overridden with `--param-override` on a local or dev chain
+    pub param_overrides: BTreeMap<String, u64>,
+}
+
+/// Operator metadata attached to a node identity
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OperatorInfo {
+    pub name: String,
+    pub contact: Option<String>,
+    pub website: Option<String>,
+    /// Whether the certificate was signed by the peer presenting it; None when no
+    /// certificate was checked
+    pub verified: Option<bool>,
+    /// The certificate signing the metadata with the node identity, as published by
+    /// the local node
+    #[serde(default)]
+    pub certificate: Option<OperatorCertificateInfo>,
+}
+
+/// Operator metadata signature by a node identity key
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OperatorCertificateInfo {
+    pub peer_id: String,
+    /// Hex encoded protobuf identity public key
+    pub public_key: String,
+    /// Unix timestamp in seconds
+    pub issued_at: u64,
+    pub signature: String,
 }
 
 /// Account information  
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AccountInfo {
     pub address: String,
     pub balance: String,
+    /// Sequence number the account's next transaction must use
     pub sequence_number: u64,
-    pub authentication_key: String,
 }
 
 /// Transaction information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TransactionInfo {
     pub hash: String,
     pub sender: String,
     pub recipient: Option<String>,
     pub amount: String,
+    /// Gas the transaction was charged, 0 while it is pending
     pub gas_used: u64,
     pub gas_price: u64,
     pub status: String,
     pub block_number: Option<u128>,
     pub timestamp: u64,
+    /// Kind of a node generated system transaction (`timestamp_update`, `checkpoint`,
+    /// `oracle_aggregation`), None for user transactions
+    pub system_kind: Option<String>,
 }
 
 /// Block information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BlockInfo {
     pub number: u128,
     pub hash: String,
     pub parent_hash: String,
     pub timestamp: u64,
     pub transaction_count: usize,
+    /// Gas the transactions of the block were charged
     pub gas_used: u64,
     pub gas_limit: u64,
     pub state_root: String,
+    /// Where the fees of the block went, set by single block lookups but not in block
+    /// pages and batches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fees: Option<BlockFeeSummary>,
+}
+
+/// Fee accounting of a block, amounts in the smallest KARI unit
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BlockFeeSummary {
+    pub block_number: u128,
+    /// Fees charged for the gas the user transactions used
+    pub collected: String,
+    /// Unused part of the maximum fees, returned to the senders
+    pub refunded: String,
+    pub to_dao: String,
+    /// Zero unless a burn share is configured
+    pub burned: String,
+    /// Added to the staking rewards of the epoch
+    pub to_validators: String,
+}
+
+/// Execution receipt of an included transaction
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionReceiptInfo {
+    pub tx_hash: String,
+    /// `success` or `failure`
+    pub status: String,
+    /// Why the transaction failed, None when it succeeded
+    pub failure_reason: Option<String>,
+    pub gas_used: u64,
+    pub events: Vec<EventInfo>,
+    /// The block including the transaction
+    pub block_number: u128,
+    pub block_hash: String,
+    /// The position of the transaction in the block
+    pub index: u64,
+}
+
+/// What executing a transaction against the latest state would do, nothing is committed
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DryRunResult {
+    pub tx_hash: String,
+    /// `success` or `failure`
+    pub status: String,
+    /// Why the transaction would fail, None when it would succeed
+    pub failure_reason: Option<String>,
+    pub gas_used: u64,
+    /// The events the transaction would emit, numbered as in the next block
+    pub events: Vec<EventInfo>,
+}
+
+/// A Move call made by a traced transaction
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CallTraceInfo {
+    /// 0 for the entry call, increasing with each nested call
+    pub depth: u32,
+    pub function: String,
+    pub gas_used: u64,
+    /// The Move abort of the call, if it aborted
+    pub abort: Option<MoveAbortInfo>,
+}
+
+/// A field of an object read or written by a traced transaction
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StorageAccessInfo {
+    pub object_id: String,
+    /// e.g. `0x2::account::Account`
+    pub object_type: String,
+    pub field: String,
+    /// None if the object does not exist
+    pub value: Option<String>,
+}
+
+/// The re-execution of an included transaction, call by call
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionTrace {
+    pub tx_hash: String,
+    pub block_number: u128,
+    /// The position of the transaction in the block
+    pub index: u64,
+    /// `success` or `failure`
+    pub status: String,
+    pub failure_reason: Option<String>,
+    pub gas_used: u64,
+    /// Calls in execution order
+    pub calls: Vec<CallTraceInfo>,
+    pub reads: Vec<StorageAccessInfo>,
+    pub writes: Vec<StorageAccessInfo>,
+    pub events: Vec<EventInfo>,
+}
+
+/// A Move abort and the description of its code from the framework error map
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MoveAbortInfo {
+    /// The module that aborted, e.g. `0x3::coin_store`
+    pub location: String,
+    pub abort_code: u64,
+    /// Name of the error constant, None if the code is not in the error map
+    pub code_name: Option<String>,
+    pub explanation: Option<String>,
+}
+
+/// Values returned by a Move view function executed against the latest state
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ViewFunctionResult {
+    /// `Executed`, or the VM status the function stopped with
+    pub vm_status: String,
+    /// The Move abort stopping the function, if it aborted
+    pub abort: Option<MoveAbortInfo>,
+    pub return_values: Vec<ViewFunctionReturnValue>,
+}
+
+/// A Move value returned by a view function
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ViewFunctionReturnValue {
+    pub type_tag: String,
+    /// Hex encoded BCS bytes of the value
+    pub value: String,
+    /// The value annotated with its Move type layout
+    pub decoded_value: serde_json::Value,
+}
+
+/// A module published in the module store, with its bytecode and ABI
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MoveModuleInfo {
+    /// Hex encoded module bytecode
+    pub bytecode: String,
+    pub abi: MoveModuleAbi,
+}
+
+/// The interface of a Move module, as SDK code generators and explorers need it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MoveModuleAbi {
+    pub address: String,
+    pub name: String,
+    /// Modules allowed to call the friend functions, e.g. `0x3::transaction_validator`
+    pub friends: Vec<String>,
+    pub structs: Vec<MoveStructAbi>,
+    /// Functions callable from outside the module: public, friend and entry functions
+    pub functions: Vec<MoveFunctionAbi>,
+}
+
+/// A struct declared by a Move module
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MoveStructAbi {
+    pub name: String,
+    /// `copy`, `drop`, `store` and `key`
+    pub abilities: Vec<String>,
+    pub type_parameters: Vec<MoveTypeParameterAbi>,
+    pub fields: Vec<MoveFieldAbi>,
+}
+
+/// A generic type parameter and the abilities its type arguments must have
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MoveTypeParameterAbi {
+    pub constraints: Vec<String>,
+    pub is_phantom: bool,
+}
+
+/// A field of a Move struct
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MoveFieldAbi {
+    pub name: String,
+    /// Move type, e.g. `vector<u8>` or `0x1::string::String`
+    pub move_type: String,
+}
+
+/// A Move function callable from outside its module
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MoveFunctionAbi {
+    pub name: String,
+    /// `public`, `friend` or `private`, entry functions may be private
+    pub visibility: String,
+    pub is_entry: bool,
+    pub type_parameters: Vec<MoveTypeParameterAbi>,
+    /// Move types of the parameters, type parameters show as `T0`, `T1`...
+    pub parameters: Vec<String>,
+    pub return_types: Vec<String>,
+}
+
+/// A page of an account's transactions and the position the next page starts at
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionPage {
+    pub transactions: Vec<TransactionInfo>,
+    /// Pass as `cursor` to fetch the next page; None once the history is exhausted
+    pub next_cursor: Option<u64>,
+}
+
+/// A page of blocks and the number the next page starts at
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BlockPage {
+    pub blocks: Vec<BlockInfo>,
+    /// Pass as `start` to fetch the next page; None once the range is exhausted
+    pub next_cursor: Option<u128>,
+}
+
+/// A block header with the hash linking it to its parent. The hash is the
+/// SHA2-256 of the BCS encoded header fields, so clients can recompute it.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HeaderInfo {
+    pub number: u128,
+    pub hash: String,
+    pub parent_hash: String,
+    pub batch_size: u64,
+    pub batch_hash: String,
+    pub prev_tx_accumulator_root: String,
+    pub tx_accumulator_root: String,
+    pub state_root: String,
+    /// Signatures finalizing the block, None while the chain has no finality
+    pub finality_signatures: Option<Vec<String>>,
 }
 
 /// Network statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NetworkStats {
     pub peer_count: usize,
     pub connected_peers: Vec<String>,
@@ -64,17 +322,36 @@ pub struct NetworkStats {
     pub network_id: String,
 }
 
+/// What a wallet needs to know about the chain a node serves, in one response
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChainInfo {
+    pub chain_id: u64,
+    pub network_id: String,
+    /// Hash of the genesis block, None until the first block exists
+    pub genesis_hash: Option<String>,
+    pub latest_height: u128,
+    /// The block committed by the latest checkpoint, None before the first checkpoint
+    pub finalized_height: Option<u128>,
+    /// Version of the Move standard library of the genesis
+    pub stdlib_version: String,
+    pub node_version: String,
+}
+
 /// Balance information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BalanceInfo {
     pub address: String,
+    /// Canonical struct tag of the coin
     pub coin_type: String,
+    /// Balance in the smallest unit of the coin
     pub balance: String,
-    pub decimals: u8,
+    /// Decimals of the coin, None for coins other than KARI since the node does not
+    /// read their coin info
+    pub decimals: Option<u8>,
 }
 
 /// KARI Token information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct KariTokenInfo {
     pub name: String,
     pub symbol: String,
@@ -84,8 +361,37 @@ pub struct KariTokenInfo {
     pub scaling_factor: String,
 }
 
+/// A unit KARI amounts may be written in
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DenominationInfo {
+    pub name: String,
+    pub symbol: String,
+    /// Number of smallest units in one of this unit, as a power of ten
+    pub decimals: u8,
+}
+
+/// How KARI amounts are rendered and parsed, so every client displays them the same way
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DisplayMetadata {
+    pub symbol: String,
+    /// Decimals of KARI, amounts in the RPC are in smallest units
+    pub decimals: u8,
+    /// Denominations from the largest to the smallest
+    pub units: Vec<DenominationInfo>,
+    /// Fraction digits shown by default
+    pub display_decimals: u8,
+    /// `toward_zero`: digits beyond the display precision are cut off, so a rendered
+    /// balance never exceeds the real one
+    pub rounding: String,
+    /// Whether trailing zeros of the fraction are dropped
+    pub trim_trailing_zeros: bool,
+    /// Whether parsing rejects amounts with more fraction digits than their unit has,
+    /// instead of rounding them
+    pub reject_excess_precision: bool,
+}
+
 /// Token balance information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TokenBalance {
     pub address: String,
     pub balance: String,
@@ -94,7 +400,7 @@ pub struct TokenBalance {
 }
 
 /// Rooch wallet information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RoochWalletInfo {
     pub rooch_address: String,
     pub hex_address: String,
@@ -104,27 +410,657 @@ pub struct RoochWalletInfo {
     pub kari_balance: TokenBalance,
 }
 
-/// Kanari DAO information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Kanari DAO information. The fees paid to the DAO are reported per block by
+/// `kanari_getBlockFeeSummary`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct KanariDaoInfo {
     pub multisign_bitcoin_address: String,
     pub threshold: u64,
     pub participant_count: usize,
-    pub collected_fees: String,
     pub kari_balance: TokenBalance,
 }
 
-/// Transaction fee information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where a DAO proposal is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DaoProposalStatusInfo {
+    /// Open for votes
+    Active,
+    /// Approved by the threshold, waiting to be executed
+    Passed,
+    /// Too many participants rejected it for the threshold to be reached
+    Rejected,
+    /// Voting ended without reaching the threshold
+    Expired,
+    Executed,
+}
+
+/// What the DAO does when a proposal passes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DaoActionInfo {
+    /// A signalling proposal with no effect on chain
+    Text,
+    /// Pay KARI from the DAO treasury
+    Transfer { recipient: String, amount: String },
+}
+
+/// A proposal of the Kanari DAO with its vote tally
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DaoProposalInfo {
+    pub id: u64,
+    /// Hex encoded public key of the participant who made the proposal
+    pub proposer: String,
+    pub title: String,
+    pub description: String,
+    pub action: DaoActionInfo,
+    pub status: DaoProposalStatusInfo,
+    pub created_at_block: u128,
+    /// The last block votes are accepted in
+    pub voting_end_block: u128,
+    /// Approvals the proposal needs
+    pub threshold: u64,
+    pub approvals: u64,
+    pub rejections: u64,
+    pub executed_at_block: Option<u128>,
+}
+
+/// A participant's vote on a DAO proposal
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DaoVoteInfo {
+    /// Hex encoded public key of the voting participant
+    pub voter: String,
+    pub approve: bool,
+    pub block_number: u128,
+}
+
+/// Transaction fee information. The top level fees are those of the normal tier for
+/// the requested gas limit.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TransactionFee {
     pub base_fee: String,
     pub priority_fee: String,
     pub total_fee: String,
     pub fee_recipient: String, // Kanari DAO address
+    /// Gas the recent blocks used, in basis points of their gas limit
+    pub block_fullness_bps: u64,
+    /// Executable transactions waiting in the mempool
+    pub pending_transactions: usize,
+    pub slow: FeeTierSuggestion,
+    pub normal: FeeTierSuggestion,
+    pub fast: FeeTierSuggestion,
+}
+
+/// Suggested gas price of a fee tier
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FeeTierSuggestion {
+    pub base_fee_per_gas: u64,
+    pub priority_fee_per_gas: u64,
+    /// Number of blocks within which a transaction paying this price should be included
+    pub target_blocks: u64,
+    /// Maximum fee for the requested gas limit
+    pub total_fee: String,
+}
+
+/// Base fees, gas use and paid gas prices of a range of recent blocks
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FeeHistory {
+    pub oldest_block: u128,
+    /// Base fee of each block, then of the next block
+    pub base_fee_per_gas: Vec<u64>,
+    /// Share of its gas limit each block used
+    pub gas_used_ratio: Vec<f64>,
+    /// Gas prices paid in each block at the requested percentiles, 0 for a block
+    /// without user transactions. None if no percentiles were requested.
+    pub gas_price_percentiles: Option<Vec<Vec<u64>>>,
+}
+
+/// Staking or vesting position held by an account
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StakingPosition {
+    pub kind: String,
+    pub amount: String,
+    pub validator: Option<String>,
+    pub unlock_timestamp: Option<u64>,
+}
+
+/// Aggregated account data for wallets, assembled in a single call
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AccountSummary {
+    pub address: String,
+    pub sequence_number: u64,
+    pub balances: Vec<TokenBalance>,
+    pub pending_transaction_count: usize,
+    pub recent_transactions: Vec<TransactionInfo>,
+    pub staking_positions: Vec<StakingPosition>,
+}
+
+/// Block propagation statistics for a peer
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PeerPropagationInfo {
+    pub peer_id: String,
+    pub rank: usize,
+    pub avg_latency_ms: f64,
+    pub last_latency_ms: u64,
+    pub blocks_received: u64,
+    pub first_deliveries: u64,
+}
+
+/// Sync progress reported by a peer ahead of the local chain
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SyncPeerInfo {
+    pub peer_id: String,
+    pub height: u128,
+    pub blocks_received: u64,
+}
+
+/// Progress of the local node towards the highest height known from peers
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SyncStatusInfo {
+    pub is_syncing: bool,
+    pub current_height: u128,
+    pub target_height: u128,
+    pub blocks_per_second: f64,
+    /// Estimated seconds until the target height, None while the rate is unknown
+    pub eta_seconds: Option<u64>,
+    /// Peers ahead of the local chain, highest first
+    pub active_peers: Vec<SyncPeerInfo>,
+}
+
+/// The node clock against the network time estimated from peer heartbeats
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TimeOffsetInfo {
+    /// Network time minus local time in milliseconds
+    pub offset_ms: i64,
+    /// Number of peers the offset is the median of, zero while too few peers reported
+    pub sample_count: usize,
+    /// Milliseconds since the Unix epoch according to the local clock
+    pub local_time_ms: u64,
+    /// The local time corrected by the offset, used for block timestamps
+    pub network_time_ms: u64,
+}
+
+/// A connected peer and the feature flags negotiated with it at handshake
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConnectedPeerInfo {
+    pub peer_id: String,
+    pub address: String,
+    pub version: String,
+    /// Flags both nodes support (`tx-relay`, `archive`, `consensus`, `light-serve`),
+    /// None while the peer's handshake is pending
+    pub capabilities: Option<Vec<String>>,
+    /// Operator metadata from the peer's handshake; impersonation shows as `verified: false`
+    pub operator: Option<OperatorInfo>,
+    /// Why the peer disconnected lately, oldest first
+    #[serde(default)]
+    pub recent_disconnects: Vec<PeerDisconnectInfo>,
+}
+
+/// A past disconnection of a peer
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PeerDisconnectInfo {
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    /// `timeout`, `banned`, `protocol_error`, `rejected`, `shutdown`, `closed` or
+    /// `transport_error`
+    pub reason: String,
+    /// `local` when this node closed the connection, `remote` otherwise
+    pub initiator: String,
+    pub detail: Option<String>,
+}
+
+/// A field of a state object
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StateEntry {
+    pub field_key: String,
+    pub object_id: String,
+    pub object_type: String,
+    /// Number of fields of the object itself, page through them with its id
+    pub field_count: u64,
+    /// Hex encoded BCS value
+    pub value: String,
+}
+
+/// A page of the fields of a state object and the cursor the next page starts after
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StatePage {
+    pub states: Vec<StateEntry>,
+    /// Pass as `cursor` to fetch the next page; None once the fields are exhausted
+    pub next_cursor: Option<String>,
+}
+
+/// One level of a state proof, see `StateWithProof`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StateProofStepInfo {
+    /// Root of the tree the proof is against
+    pub state_root: String,
+    pub field_key: String,
+    /// Hex encoded BCS `ObjectState` under the key, None if the proof is of its absence
+    pub object_state: Option<String>,
+    /// Hex encoded BCS sparse Merkle proof
+    pub proof: String,
+}
+
+/// A state and the sparse Merkle proofs that link it to the state root of a block.
+/// Verify each step against its `state_root`: the first is the block's, each next one
+/// is the `state_root` of the object proven by the previous step.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StateWithProof {
+    pub block_number: u128,
+    pub state_root: String,
+    pub access_path: String,
+    /// Hex encoded BCS value, None if the state does not exist
+    pub value: Option<String>,
+    pub proofs: Vec<StateProofStepInfo>,
+}
+
+/// A P2P message kept in the node's recent history window
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecentMessageInfo {
+    pub id: String,
+    pub msg_type: String,
+    pub sender: Option<String>,
+    pub timestamp: u64,
+    pub payload_size: usize,
+}
+
+/// Aggregate P2P message counters and the bounded window of recent messages
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MessageHistoryInfo {
+    /// Messages processed since the node started
+    pub total_messages: u64,
+    pub counts_by_type: HashMap<String, u64>,
+    /// Most recent messages, newest first
+    pub recent: Vec<RecentMessageInfo>,
+}
+
+/// A validator of an epoch and its share of the stake
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EpochValidatorInfo {
+    pub address: String,
+    pub public_key: String,
+    pub stake: String,
+    /// Stake delegated to the validator by other accounts
+    pub delegated_stake: String,
+    pub delegators: usize,
+    /// Share of the total stake
+    pub weight: f64,
+    /// Share of the total stake counted in consensus weights and rewards, after the
+    /// voting power cap
+    pub voting_power: f64,
+    /// Set while the validator is jailed for double signing
+    pub jailed_until: Option<u64>,
+}
+
+/// Consensus parameters frozen for an epoch
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConsensusParamsInfo {
+    pub block_gas_limit: u64,
+    pub min_validator_stake: String,
+    pub max_validators: u32,
+    pub epoch_emission: String,
+    /// Share of the collected fees paid to the Kanari DAO, in basis points
+    pub dao_fee_bps: u64,
+    /// Share of the collected fees burnt, in basis points
+    pub burn_fee_bps: u64,
+    /// Most of the total stake a single validator counts with, in basis points
+    pub max_voting_power_bps: u64,
+}
+
+/// Boundaries, validator set and parameters of an epoch
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EpochInfo {
+    pub epoch: u64,
+    pub start_block: u128,
+    pub end_block: u128,
+    pub is_current: bool,
+    pub total_stake: String,
+    pub validators: Vec<EpochValidatorInfo>,
+    pub params: ConsensusParamsInfo,
+    /// Hash recorded by the epoch change system transaction
+    pub snapshot_hash: String,
+    /// Changes queued for the next boundary, only counted for the current epoch
+    pub pending_changes: usize,
+}
+
+/// Whether a validator may propose blocks in an epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatus {
+    Active,
+    /// Jailed for double signing, until `jailed_until`
+    Jailed,
+    /// Unregistered, leaving the set at the next epoch boundary
+    Leaving,
+}
+
+/// A validator of an epoch with its stake, consensus key and status
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ValidatorInfo {
+    pub address: String,
+    /// Hex encoded compressed secp256k1 public key signing the validator's blocks
+    pub consensus_key: String,
+    pub stake: String,
+    /// Stake delegated to the validator by other accounts
+    pub delegated_stake: String,
+    pub total_stake: String,
+    /// Share of the total stake counted in consensus weights and rewards
+    pub voting_power: f64,
+    pub status: ValidatorStatus,
+    pub jailed_until: Option<u64>,
+}
+
+/// The validator set of an epoch, highest stake first
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ValidatorSetInfo {
+    pub epoch: u64,
+    pub is_current: bool,
+    /// Stake of the validators that are not jailed
+    pub total_stake: String,
+    pub validators: Vec<ValidatorInfo>,
+}
+
+/// A validator set change to apply at the next epoch boundary
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ValidatorChangeRequest {
+    /// Register the validator with this hex encoded secp256k1 public key
+    Register {
+        public_key: String,
+        stake: String,
+    },
+    UpdateStake {
+        address: String,
+        stake: String,
+    },
+    Unregister {
+        address: String,
+    },
+    Delegate {
+        delegator: String,
+        validator: String,
+        amount: String,
+    },
+    Undelegate {
+        delegator: String,
+        validator: String,
+    },
+    UpdateParams {
+        block_gas_limit: Option<u64>,
+        min_validator_stake: Option<String>,
+        max_validators: Option<u32>,
+        epoch_emission: Option<String>,
+        dao_fee_bps: Option<u64>,
+        burn_fee_bps: Option<u64>,
+        max_voting_power_bps: Option<u64>,
+    },
+}
+
+/// Outcome of a storage maintenance task run
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MaintenanceRunInfo {
+    /// `compaction`, `pruning` or `archive_upload`
+    pub task: String,
+    /// Whether an operator triggered the run rather than a maintenance window
+    pub manual: bool,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+    pub duration_ms: u64,
+    /// Why the run failed, None if it succeeded
+    pub error: Option<String>,
+}
+
+/// The block `admin_proposeDryRun` built without signing or saving it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProposalDryRun {
+    pub block_number: u128,
+    /// Hash the block would have
+    pub hash: String,
+    pub parent_hash: String,
+    pub timestamp: u64,
+    pub transaction_count: usize,
+    pub system_transaction_count: usize,
+    pub event_count: usize,
+    pub batch_hash: String,
+    pub state_root: String,
+    /// The epoch the block would start, at an epoch boundary
+    pub epoch_change: Option<u64>,
+    /// Time spent selecting the transactions, in microseconds
+    pub select_us: u64,
+    /// Time spent executing the transactions, in microseconds
+    pub execute_us: u64,
+    /// Time spent computing the roots, in microseconds
+    pub roots_us: u64,
+    /// Time to build the whole block, in microseconds
+    pub total_us: u64,
+}
+
+/// Scope of a new API key
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApiKeyRequest {
+    /// Who the key is for
+    pub label: String,
+    /// Requests per second the key may make across its connections, unlimited if None
+    pub requests_per_second: Option<f64>,
+    /// Requests the key may make at once, defaults to one second of requests
+    pub burst: Option<u32>,
+    /// Methods the key may call, `namespace_*` for a whole namespace. Empty for every
+    /// method outside the namespaces requiring a key.
+    #[serde(default)]
+    pub allowed_methods: Vec<String>,
+}
+
+/// An API key stored by the node, without its secret
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApiKeyInfo {
+    pub id: String,
+    pub label: String,
+    pub requests_per_second: Option<f64>,
+    pub burst: Option<u32>,
+    pub allowed_methods: Vec<String>,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+}
+
+/// A new API key, the only time its secret is returned
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreatedApiKey {
+    #[serde(flatten)]
+    pub info: ApiKeyInfo,
+    /// Sent as `Authorization: Bearer <key>` or `X-Api-Key`
+    pub key: String,
+}
+
+/// A staking reward paid to an account
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RewardInfo {
+    pub epoch: u64,
+    /// The validator whose stake earned the reward
+    pub validator: String,
+    pub amount: String,
+}
+
+/// Verified double sign evidence and the penalty it triggered
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EvidenceInfo {
+    pub hash: String,
+    /// Address of the validator that signed both blocks
+    pub offender: String,
+    pub public_key: String,
+    pub block_number: u128,
+    pub epoch: u64,
+    /// Hashes of the two conflicting signed headers
+    pub first_header_hash: String,
+    pub second_header_hash: String,
+    pub submitted_at: u64,
+    /// The slashing and jail take effect at the start of this epoch
+    pub slashed_from_epoch: u64,
+    pub penalty_bps: u64,
+    pub jail_epochs: u64,
+}
+
+/// Staking rewards of an account over a range of epochs
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RewardsInfo {
+    pub address: String,
+    pub from_epoch: u64,
+    pub to_epoch: u64,
+    /// Rewards in epoch order
+    pub rewards: Vec<RewardInfo>,
+    pub total: String,
+}
+
+/// Everything an external proposer needs to assemble the next block
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BlockTemplate {
+    /// Hash of the block to extend, zero for the genesis block
+    pub parent_hash: String,
+    /// Accumulator root of the block to extend, which the block's
+    /// `prev_tx_accumulator_root` must be set to
+    pub prev_tx_accumulator_root: String,
+    /// Number of the block to build
+    pub height: u128,
+    pub chain_id: u64,
+    /// Genesis hash that included transactions must be bound to, None before genesis
+    pub genesis_hash: Option<String>,
+    /// The block timestamp must lie within these bounds, in seconds since the Unix epoch
+    pub min_timestamp: u64,
+    pub max_timestamp: u64,
+    pub min_gas_price: u64,
+    pub block_gas_limit: u64,
+    /// Slots at the start of the block reserved for system transactions
+    pub system_transaction_slots: usize,
+    /// Executable pending transactions, highest gas price first
+    pub transactions: Vec<PendingTransaction>,
+}
+
+/// A pending transaction with its full signed payload, as returned to block builders
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PendingTransaction {
+    pub hash: String,
+    pub sender: String,
+    pub sequence_number: u64,
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    pub size: usize,
+    /// Hex encoded BCS bytes of the signed transaction
+    pub raw: String,
+}
+
+/// The hashes of a pooled transaction and of the transaction that replaced it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionReplacement {
+    pub replaced_hash: String,
+    pub hash: String,
+}
+
+/// A transaction waiting in the pool
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TxPoolTransaction {
+    pub hash: String,
+    pub recipient: Option<String>,
+    pub amount: String,
+    pub gas_limit: u64,
+    pub gas_price: u64,
+    pub size: usize,
+    /// Seconds since the node received the transaction
+    pub age_seconds: u64,
+}
+
+/// Transactions of the pool keyed by sender, then sequence number. `pending`
+/// transactions are executable, `queued` ones wait for a nonce gap to fill.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TxPoolContent {
+    pub pending: BTreeMap<String, BTreeMap<u64, TxPoolTransaction>>,
+    pub queued: BTreeMap<String, BTreeMap<u64, TxPoolTransaction>>,
+}
+
+/// One line summaries of the pool's transactions, keyed like `TxPoolContent`:
+/// `<recipient>: <amount> + <gas limit> gas × <gas price>`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TxPoolInspect {
+    pub pending: BTreeMap<String, BTreeMap<u64, String>>,
+    pub queued: BTreeMap<String, BTreeMap<u64, String>>,
+}
+
+/// Block range and criteria of an event query
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EventFilter {
+    pub from_block: u128,
+    pub to_block: u128,
+    /// Only events emitted by this module address
+    pub address: Option<String>,
+    /// Only events of this fully qualified type
+    pub event_type: Option<String>,
+}
+
+/// Criteria of a log query. Omitted bounds default to the latest block, and each
+/// list matches any of its entries, or anything when empty.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LogFilter {
+    pub from_block: Option<u128>,
+    pub to_block: Option<u128>,
+    /// Only events emitted by one of these module addresses
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    /// Only events of one of these fully qualified types
+    #[serde(default)]
+    pub event_types: Vec<String>,
+}
+
+/// Criteria of a pending transaction subscription. Both set, a transaction must match
+/// both; none set, every admitted transaction is streamed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PendingTransactionFilter {
+    /// Only transactions sent by this address
+    pub sender: Option<String>,
+    /// Only transactions to this address
+    pub recipient: Option<String>,
+}
+
+/// Event emitted by a transaction in a block
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EventInfo {
+    pub block_number: u128,
+    pub tx_hash: String,
+    pub event_index: u64,
+    pub address: String,
+    pub event_type: String,
+    /// Hex encoded BCS event data
+    pub data: String,
+}
+
+/// What happened to a watched address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressActivityKind {
+    /// The address sent a transaction
+    Sent,
+    /// The address is the recipient of a transaction
+    Received,
+    /// A transfer or reward changed the balance of the address
+    BalanceChanged,
+    /// A reward was paid on a stake of the address, or its own or delegated stake
+    /// changed at an epoch boundary
+    StakingUpdated,
+}
+
+/// Activity of an address watched with `subscribe_addressActivity`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AddressActivity {
+    pub address: String,
+    pub kind: AddressActivityKind,
+    /// None for transactions that were accepted into the mempool but not yet included
+    pub block_number: Option<u128>,
+    pub tx_hash: Option<String>,
+    /// The other party of a transfer, or the validator of a stake
+    pub counterparty: Option<String>,
+    /// The transferred amount, the signed balance change or the new stake
+    pub amount: Option<String>,
 }
 
 /// Transaction request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TransactionRequest {
     pub sender: String,
     pub recipient: String,
@@ -135,28 +1071,124 @@ pub struct TransactionRequest {
 }
 
 /// Main Kanari RPC API trait
+/// An event in the Ethereum log format, as returned within receipts by the `eth`
+/// namespace. Quantities are hex encoded.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthLog {
+    pub address: String,
+    /// The SHA2-256 hash of the Kanari event type
+    pub topics: Vec<String>,
+    /// BCS encoded event data
+    pub data: String,
+    pub block_number: String,
+    pub block_hash: String,
+    pub transaction_hash: String,
+    pub transaction_index: String,
+    pub log_index: String,
+    pub removed: bool,
+}
+
+/// An included transaction in the Ethereum receipt format. Quantities are hex encoded.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthTransactionReceipt {
+    pub transaction_hash: String,
+    pub transaction_index: String,
+    pub block_hash: String,
+    pub block_number: String,
+    pub from: String,
+    pub to: Option<String>,
+    pub cumulative_gas_used: String,
+    pub gas_used: String,
+    pub effective_gas_price: String,
+    pub contract_address: Option<String>,
+    pub logs: Vec<EthLog>,
+    pub logs_bloom: String,
+    /// Always `0x0`, the legacy transaction type
+    #[serde(rename = "type")]
+    pub transaction_type: String,
+    /// `0x1`, every included transaction succeeded
+    pub status: String,
+}
+
+#[open_rpc(namespace = "kanari", tag = "Kanari")]
 #[rpc(server, client, namespace = "kanari")]
 pub trait KanariRpcApi {
     /// Get node information
     #[method(name = "getNodeInfo")]
     async fn get_node_info(&self) -> RpcResult<NodeInfo>;
 
-    /// Get account information
+    /// Get account information, in the state after `block_number` if given. Read
+    /// methods taking a block number answer against the latest state without one.
     #[method(name = "getAccount")]
-    async fn get_account(&self, address: String) -> RpcResult<AccountInfo>;
+    async fn get_account(
+        &self,
+        address: String,
+        block_number: Option<u128>,
+    ) -> RpcResult<AccountInfo>;
 
-    /// Get account balance
+    /// Get balances, nonce, pending and recent transactions and staking positions of an account
+    #[method(name = "getAccountSummary")]
+    async fn get_account_summary(
+        &self,
+        address: String,
+        recent_limit: Option<usize>,
+    ) -> RpcResult<AccountSummary>;
+
+    /// Get the transactions an account sent or received, in block order. `cursor` is
+    /// the position in the account's history to start at, 0 for the oldest.
+    #[method(name = "getTransactionsByAccount")]
+    async fn get_transactions_by_account(
+        &self,
+        address: String,
+        cursor: Option<u64>,
+        limit: Option<usize>,
+    ) -> RpcResult<TransactionPage>;
+
+    /// Get the balance of `coin_type` held by an account, read from its coin store in
+    /// the latest state or the one after `block_number`. `coin_type` is a struct tag and
+    /// defaults to KARI.
     #[method(name = "getBalance")]
     async fn get_balance(
         &self,
         address: String,
         coin_type: Option<String>,
+        block_number: Option<u128>,
     ) -> RpcResult<BalanceInfo>;
 
+    /// Get the headers of blocks `from..=to` with their linking hashes, so the
+    /// continuity of served data can be verified with `verify_header_chain`
+    #[method(name = "getHeaderChain")]
+    async fn get_header_chain(&self, from: u128, to: u128) -> RpcResult<Vec<HeaderInfo>>;
+
+    /// Get up to `limit` blocks of `start..=end` in ascending order, with a cursor
+    /// to the rest of the range
+    #[method(name = "getBlocksInRange")]
+    async fn get_blocks_in_range(
+        &self,
+        start: u128,
+        end: u128,
+        limit: Option<usize>,
+    ) -> RpcResult<BlockPage>;
+
+    /// Get the blocks with these numbers in one call, in the order of the request and
+    /// null for numbers without a block. The batch size is bounded by the node.
+    #[method(name = "getBlocksByNumbers")]
+    async fn get_blocks_by_numbers(
+        &self,
+        block_numbers: Vec<u128>,
+    ) -> RpcResult<Vec<Option<BlockInfo>>>;
+
     /// Get block by number
     #[method(name = "getBlockByNumber")]
     async fn get_block_by_number(&self, block_number: u128) -> RpcResult<BlockInfo>;
 
+    /// Get the fees collected by a block and how they were split between refunds, the
+    /// DAO, burning and the validators
+    #[method(name = "getBlockFeeSummary")]
+    async fn get_block_fee_summary(&self, block_number: u128) -> RpcResult<BlockFeeSummary>;
+
     /// Get block by hash
     #[method(name = "getBlockByHash")]
     async fn get_block_by_hash(&self, block_hash: String) -> RpcResult<BlockInfo>;
@@ -165,26 +1197,184 @@ pub trait KanariRpcApi {
     #[method(name = "getLatestBlock")]
     async fn get_latest_block(&self) -> RpcResult<BlockInfo>;
 
-    /// Get transaction by hash
+    /// Get a pooled or included transaction by hash, None if the node does not know it
     #[method(name = "getTransaction")]
-    async fn get_transaction(&self, tx_hash: String) -> RpcResult<TransactionInfo>;
+    async fn get_transaction(&self, tx_hash: String) -> RpcResult<Option<TransactionInfo>>;
+
+    /// Get the execution receipt of an included transaction, None if the transaction is
+    /// not in a block or its receipt was pruned
+    #[method(name = "getTransactionReceipt")]
+    async fn get_transaction_receipt(
+        &self,
+        tx_hash: String,
+    ) -> RpcResult<Option<TransactionReceiptInfo>>;
+
+    /// Execute a Move view function, e.g. `0x6::kanari` and `total_supply`, against the
+    /// latest state, or the one after `block_number`, without a transaction. Type
+    /// arguments are Move type tags such as `0x3::gas_coin::RGas`, arguments are hex
+    /// encoded BCS values.
+    #[method(name = "executeViewFunction")]
+    async fn execute_view_function(
+        &self,
+        module: String,
+        function: String,
+        type_args: Option<Vec<String>>,
+        args: Option<Vec<String>>,
+        block_number: Option<u128>,
+    ) -> RpcResult<ViewFunctionResult>;
+
+    /// Get the bytecode and ABI of a published module, e.g. `0x3` and `coin_store`.
+    /// None if the module is not published.
+    #[method(name = "getModule")]
+    async fn get_module(
+        &self,
+        address: String,
+        name: String,
+        block_number: Option<u128>,
+    ) -> RpcResult<Option<MoveModuleInfo>>;
+
+    /// Get the ABIs of the modules of the package published at an address, by module
+    /// name. Use `getModule` for their bytecode.
+    #[method(name = "listModules")]
+    async fn list_modules(
+        &self,
+        address: String,
+        block_number: Option<u128>,
+    ) -> RpcResult<Vec<MoveModuleAbi>>;
+
+    /// Get the object or field at `access_path`, e.g. `/object/0x...` or
+    /// `/fields/0x.../0x...`, in the state after a block, with the sparse Merkle proofs
+    /// that link it to the block's state root. Light clients holding the header verify it
+    /// without trusting this node.
+    #[method(name = "getStateWithProof")]
+    async fn get_state_with_proof(
+        &self,
+        access_path: String,
+        block_number: u128,
+    ) -> RpcResult<StateWithProof>;
 
-    /// Send transaction
+    /// Execute a hex encoded, BCS serialized signed transaction against the latest state
+    /// as the next block would, without committing it, after the checks of
+    /// `sendTransaction`. Returns the gas it would use, its status and events.
+    #[method(name = "dryRunTransaction")]
+    async fn dry_run_transaction(&self, signed_tx: String) -> RpcResult<DryRunResult>;
+
+    /// Submit a hex encoded, BCS serialized signed transaction to the pool after checking
+    /// its signature, chain id, sequence number and gas against the node, returning its hash
     #[method(name = "sendTransaction")]
-    async fn send_transaction(&self, tx_request: TransactionRequest) -> RpcResult<String>;
+    async fn send_transaction(&self, signed_tx: String) -> RpcResult<String>;
+
+    /// Submit a hex encoded, BCS serialized signed transaction to the pool
+    #[method(name = "sendRawTransaction")]
+    async fn send_raw_transaction(&self, raw_tx: String) -> RpcResult<String>;
+
+    /// Replace a pooled transaction by a hex encoded, BCS serialized signed transaction
+    /// with the same sender and sequence number, raising the gas price by at least the
+    /// node's minimum replacement bump
+    #[method(name = "replaceTransaction")]
+    async fn replace_transaction(&self, raw_tx: String) -> RpcResult<TransactionReplacement>;
+
+    /// Drop a pooled transaction of `sender` by sequence number. `signature` is the
+    /// sender's personal message signature, by `public_key`, of
+    /// `Cancel Kanari transaction <hash>` for the hash of the pooled transaction.
+    /// Later transactions of the sender wait in its future queue until the sequence
+    /// number is used again. Returns the hash of the dropped transaction.
+    #[method(name = "cancelTransaction")]
+    async fn cancel_transaction(
+        &self,
+        sender: String,
+        sequence_number: u64,
+        signature: String,
+        public_key: String,
+    ) -> RpcResult<String>;
 
     /// Get network statistics
     #[method(name = "getNetworkStats")]
     async fn get_network_stats(&self) -> RpcResult<NetworkStats>;
 
-    /// Get transaction pool status
+    /// Get sync progress: current and target height, rate, ETA and active sync peers
+    #[method(name = "getSyncStatus")]
+    async fn get_sync_status(&self) -> RpcResult<SyncStatusInfo>;
+
+    /// Get the offset of the network time, the median of the peers' clocks, from the
+    /// local clock. Block timestamps are produced and validated against the network time.
+    #[method(name = "getTimeOffset")]
+    async fn get_time_offset(&self) -> RpcResult<TimeOffsetInfo>;
+
+    /// Get transaction pool status: the number of executable (`pending`) and nonce
+    /// gapped (`queued`) transactions
     #[method(name = "getTxPoolStatus")]
     async fn get_tx_pool_status(&self) -> RpcResult<HashMap<String, u64>>;
 
+    /// Get the transactions of the pool grouped by sender and sequence number,
+    /// optionally only those of one sender
+    #[method(name = "getTxPoolContent")]
+    async fn get_tx_pool_content(&self, sender: Option<String>) -> RpcResult<TxPoolContent>;
+
+    /// Get a one line summary of each transaction of the pool, grouped by sender and
+    /// sequence number
+    #[method(name = "getTxPoolInspect")]
+    async fn get_tx_pool_inspect(&self) -> RpcResult<TxPoolInspect>;
+
+    /// Verify a personal message signature made with `kari sign-message`
+    #[method(name = "verifyMessage")]
+    async fn verify_message(
+        &self,
+        address: String,
+        message: String,
+        signature: String,
+        public_key: String,
+    ) -> RpcResult<bool>;
+
+    /// Get events in a block range, filtered by emitting address and event type
+    #[method(name = "getEvents")]
+    async fn get_events(&self, filter: EventFilter) -> RpcResult<Vec<EventInfo>>;
+
+    /// Get events persisted for applied transactions, filtered by block range and any
+    /// of several addresses and event types
+    #[method(name = "getLogs")]
+    async fn get_logs(&self, filter: LogFilter) -> RpcResult<Vec<EventInfo>>;
+
+    /// Get a fee ordered, nonce consistent snapshot of executable pending transactions
+    #[method(name = "getPendingTransactions")]
+    async fn get_pending_transactions(
+        &self,
+        max_bytes: Option<usize>,
+        max_count: Option<usize>,
+    ) -> RpcResult<Vec<PendingTransaction>>;
+
+    /// Get the parent, height, timestamp bounds, fee parameters and a fee ordered
+    /// transaction set for building the next block outside the node
+    #[method(name = "getBlockTemplate")]
+    async fn get_block_template(&self) -> RpcResult<BlockTemplate>;
+
+    /// Validate and import a hex encoded `SignedBlock` from an authorized external
+    /// proposer, returning the block hash
+    #[method(name = "submitBlock")]
+    async fn submit_block(&self, raw_block: String) -> RpcResult<String>;
+
+    /// Submit hex encoded `DoubleSignEvidence` of a validator that signed two blocks
+    /// at one height. Verified evidence is stored and the offender is slashed and
+    /// jailed at the next epoch boundary.
+    #[method(name = "submitEvidence")]
+    async fn submit_evidence(&self, raw_evidence: String) -> RpcResult<EvidenceInfo>;
+
+    /// Get recorded evidence by hash
+    #[method(name = "getEvidence")]
+    async fn get_evidence(&self, evidence_hash: String) -> RpcResult<Option<EvidenceInfo>>;
+
+    /// List recorded evidence in submission order, optionally only against one validator
+    #[method(name = "listEvidence")]
+    async fn list_evidence(&self, offender: Option<String>) -> RpcResult<Vec<EvidenceInfo>>;
+
     /// Get chain ID
     #[method(name = "getChainId")]
     async fn get_chain_id(&self) -> RpcResult<u64>;
 
+    /// Get the chain id, genesis hash, heights and versions in one call
+    #[method(name = "getChainInfo")]
+    async fn get_chain_info(&self) -> RpcResult<ChainInfo>;
+
     /// Get block height
     #[method(name = "getBlockHeight")]
     async fn get_block_height(&self) -> RpcResult<u128>;
@@ -193,13 +1383,26 @@ pub trait KanariRpcApi {
     #[method(name = "getKariTokenInfo")]
     async fn get_kari_token_info(&self) -> RpcResult<KariTokenInfo>;
 
+    /// Get the denominations, precision and rounding rules for displaying KARI amounts
+    #[method(name = "getDisplayMetadata")]
+    async fn get_display_metadata(&self) -> RpcResult<DisplayMetadata>;
+
     /// Get KARI token balance for an address
     #[method(name = "getKariBalance")]
-    async fn get_kari_balance(&self, address: String) -> RpcResult<TokenBalance>;
+    async fn get_kari_balance(
+        &self,
+        address: String,
+        block_number: Option<u128>,
+    ) -> RpcResult<TokenBalance>;
 
-    /// Get all token balances for an address
+    /// Get the token balances of an address. Coin stores are not indexed by owner, so
+    /// only the KARI balance is listed.
     #[method(name = "getAllTokenBalances")]
-    async fn get_all_token_balances(&self, address: String) -> RpcResult<Vec<TokenBalance>>;
+    async fn get_all_token_balances(
+        &self,
+        address: String,
+        block_number: Option<u128>,
+    ) -> RpcResult<Vec<TokenBalance>>;
 
     /// Get Rooch wallet information with KARI balance
     #[method(name = "getRoochWalletInfo")]
@@ -213,16 +1416,96 @@ pub trait KanariRpcApi {
     #[method(name = "getKanariDaoInfo")]
     async fn get_kanari_dao_info(&self) -> RpcResult<KanariDaoInfo>;
 
-    /// Get transaction fee estimate
+    /// Get the proposals of the Kanari DAO, newest first, optionally only those with
+    /// the given status
+    #[method(name = "getDaoProposals")]
+    async fn get_dao_proposals(
+        &self,
+        status: Option<DaoProposalStatusInfo>,
+    ) -> RpcResult<Vec<DaoProposalInfo>>;
+
+    /// Get a Kanari DAO proposal by id
+    #[method(name = "getDaoProposal")]
+    async fn get_dao_proposal(&self, id: u64) -> RpcResult<Option<DaoProposalInfo>>;
+
+    /// Get the votes cast on a Kanari DAO proposal, in the order they were cast
+    #[method(name = "getDaoVotes")]
+    async fn get_dao_votes(&self, id: u64) -> RpcResult<Vec<DaoVoteInfo>>;
+
+    /// Get the boundaries, validator set and consensus parameters of an epoch,
+    /// the current epoch if not given
+    #[method(name = "getEpochInfo")]
+    async fn get_epoch_info(&self, epoch: Option<u64>) -> RpcResult<EpochInfo>;
+
+    /// Get the validator set of an epoch, the current epoch if not given
+    #[method(name = "getValidators")]
+    async fn get_validators(&self, epoch: Option<u64>) -> RpcResult<ValidatorSetInfo>;
+
+    /// Get the staking rewards paid to an account for the inclusive epoch range, by
+    /// default every ended epoch
+    #[method(name = "getRewards")]
+    async fn get_rewards(
+        &self,
+        address: String,
+        from_epoch: Option<u64>,
+        to_epoch: Option<u64>,
+    ) -> RpcResult<RewardsInfo>;
+
+    /// Suggest slow, normal and fast gas prices from the fullness of the recent blocks
+    /// and the depth of the mempool, with the fees of the requested gas limit
     #[method(name = "estimateTransactionFee")]
-    async fn estimate_transaction_fee(&self, tx_request: TransactionRequest) -> RpcResult<TransactionFee>;
+    async fn estimate_transaction_fee(
+        &self,
+        tx_request: TransactionRequest,
+    ) -> RpcResult<TransactionFee>;
 
-    /// Send transaction with fee to DAO
-    #[method(name = "sendTransactionWithFee")]
-    async fn send_transaction_with_fee(&self, tx_request: TransactionRequest) -> RpcResult<String>;
+    /// Suggest a gas price for a transaction to be included within a few blocks, the
+    /// normal tier of `kanari_estimateTransactionFee`
+    #[method(name = "gasPrice")]
+    async fn gas_price(&self) -> RpcResult<u64>;
+
+    /// Get the base fees, gas use and, at each of `percentiles` from 0 to 100, the gas
+    /// prices paid of the last `block_count` blocks
+    #[method(name = "feeHistory")]
+    async fn fee_history(
+        &self,
+        block_count: u64,
+        percentiles: Option<Vec<f64>>,
+    ) -> RpcResult<FeeHistory>;
 }
 
 /// Admin RPC API trait
+/// A subset of the Ethereum JSON-RPC API mapped onto Kanari blocks and accounts, so
+/// Ethereum tooling can run basic flows against a Kanari node. Addresses are Kanari
+/// addresses, 20 byte Ethereum addresses are zero padded on the left.
+#[rpc(server, client, namespace = "eth")]
+pub trait EthRpcApi {
+    /// Get the latest block number
+    #[method(name = "blockNumber")]
+    async fn block_number(&self) -> RpcResult<String>;
+
+    /// Get the chain ID
+    #[method(name = "chainId")]
+    async fn chain_id(&self) -> RpcResult<String>;
+
+    /// Get the KARI balance of an account in its smallest unit. Only the latest state
+    /// is available, the block tag is accepted for compatibility.
+    #[method(name = "getBalance")]
+    async fn get_balance(&self, address: String, block: Option<String>) -> RpcResult<String>;
+
+    /// Submit a hex encoded Kanari `SignedTransaction`, returning its hash
+    #[method(name = "sendRawTransaction")]
+    async fn send_raw_transaction(&self, raw_tx: String) -> RpcResult<String>;
+
+    /// Get the receipt of an included transaction, None while it is pending or unknown
+    #[method(name = "getTransactionReceipt")]
+    async fn get_transaction_receipt(
+        &self,
+        tx_hash: String,
+    ) -> RpcResult<Option<EthTransactionReceipt>>;
+}
+
+#[open_rpc(namespace = "admin", tag = "Admin")]
 #[rpc(server, client, namespace = "admin")]
 pub trait AdminRpcApi {
     /// Add peer
@@ -233,9 +1516,33 @@ pub trait AdminRpcApi {
     #[method(name = "removePeer")]
     async fn remove_peer(&self, peer_id: String) -> RpcResult<bool>;
 
-    /// Get peers
+    /// Get connected peers with their negotiated capabilities and recent disconnect reasons
     #[method(name = "getPeers")]
-    async fn get_peers(&self) -> RpcResult<Vec<String>>;
+    async fn get_peers(&self) -> RpcResult<Vec<ConnectedPeerInfo>>;
+
+    /// Set the log level (`off`, `error`, `warn`, `info`, `debug`, `trace`) of a module
+    /// target such as `kanari_p2p`, or of all targets with `*`
+    #[method(name = "setLogLevel")]
+    async fn set_log_level(&self, target: String, level: String) -> RpcResult<bool>;
+
+    /// Get the log level per module target, `*` is the default level
+    #[method(name = "getLogLevels")]
+    async fn get_log_levels(&self) -> RpcResult<BTreeMap<String, String>>;
+
+    /// Run a storage maintenance task (`compaction`, `pruning` or `archive_upload`), or
+    /// every configured task, now instead of in the next off-peak window
+    #[method(name = "runMaintenance")]
+    async fn run_maintenance(&self, task: Option<String>) -> RpcResult<Vec<MaintenanceRunInfo>>;
+
+    /// Get the last run of each storage maintenance task since the node started
+    #[method(name = "getMaintenanceRuns")]
+    async fn get_maintenance_runs(&self) -> RpcResult<Vec<MaintenanceRunInfo>>;
+
+    /// Build the next block along the production path, selecting and executing its
+    /// transactions and computing its roots, without signing or saving it, to check
+    /// the proposer is healthy before its slot
+    #[method(name = "proposeDryRun")]
+    async fn propose_dry_run(&self) -> RpcResult<ProposalDryRun>;
 
     /// Start mining (for development)
     #[method(name = "startMining")]
@@ -248,33 +1555,142 @@ pub trait AdminRpcApi {
     /// Get mining status
     #[method(name = "getMiningStatus")]
     async fn get_mining_status(&self) -> RpcResult<bool>;
+
+    /// Queue a validator set or consensus parameter change for the next epoch
+    /// boundary, returning the number of queued changes
+    #[method(name = "queueValidatorChange")]
+    async fn queue_validator_change(&self, change: ValidatorChangeRequest) -> RpcResult<usize>;
+
+    /// Create an API key with its own method allowlist and rate limit, stored in the
+    /// node data dir
+    #[method(name = "createApiKey")]
+    async fn create_api_key(&self, request: ApiKeyRequest) -> RpcResult<CreatedApiKey>;
+
+    /// Revoke an API key by id, returning false if there is no such key
+    #[method(name = "revokeApiKey")]
+    async fn revoke_api_key(&self, id: String) -> RpcResult<bool>;
+
+    /// Get the stored API keys, without their secrets
+    #[method(name = "listApiKeys")]
+    async fn list_api_keys(&self) -> RpcResult<Vec<ApiKeyInfo>>;
 }
 
 /// Debug RPC API trait
+#[open_rpc(namespace = "debug", tag = "Debug")]
 #[rpc(server, client, namespace = "debug")]
 pub trait DebugRpcApi {
-    /// Get raw block
+    /// Get the hex encoded BCS bytes of a stored block, which its hash is computed over
     #[method(name = "getRawBlock")]
     async fn get_raw_block(&self, block_number: u128) -> RpcResult<String>;
 
+    /// Get the hex encoded BCS bytes of the stored block with this hash
+    #[method(name = "getRawBlockByHash")]
+    async fn get_raw_block_by_hash(&self, block_hash: String) -> RpcResult<String>;
+
     /// Get raw transaction
     #[method(name = "getRawTransaction")]
     async fn get_raw_transaction(&self, tx_hash: String) -> RpcResult<String>;
 
-    /// Get state at block
+    /// Page through the fields of the state object `key_prefix` in the state after a
+    /// block, as `getStatePaged` does for the latest state
     #[method(name = "getStateAtBlock")]
-    async fn get_state_at_block(&self, block_number: u128) -> RpcResult<HashMap<String, String>>;
+    async fn get_state_at_block(
+        &self,
+        block_number: u128,
+        key_prefix: Option<String>,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<StatePage>;
 
-    /// Trace transaction
-    #[method(name = "traceTransaction")]
-    async fn trace_transaction(
+    /// Page through the fields of the state object `key_prefix` at the latest state,
+    /// e.g. the module store or a package, in key order. No object id lists the top level
+    /// objects. Up to `limit` fields (default 100, at most 1000) and 4 MiB of values are
+    /// returned, resume after `next_cursor`.
+    #[method(name = "getStatePaged")]
+    async fn get_state_paged(
         &self,
-        tx_hash: String,
-    ) -> RpcResult<HashMap<String, serde_json::Value>>;
+        key_prefix: Option<String>,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<StatePage>;
+
+    /// Re-execute an included transaction against the state before its block and
+    /// trace its calls, the account and coin store fields it reads and writes, and its
+    /// events. None if the transaction is not in a block.
+    #[method(name = "traceTransaction")]
+    async fn trace_transaction(&self, tx_hash: String) -> RpcResult<Option<TransactionTrace>>;
+
+    /// Get peers ranked by block propagation latency
+    #[method(name = "getPeerPropagationStats")]
+    async fn get_peer_propagation_stats(&self) -> RpcResult<Vec<PeerPropagationInfo>>;
+
+    /// Get message counters and up to `limit` of the most recent P2P messages
+    #[method(name = "getMessageHistory")]
+    async fn get_message_history(&self, limit: Option<usize>) -> RpcResult<MessageHistoryInfo>;
+}
+
+/// An experimental method and the version of its request and response schema, bumped
+/// whenever either changes incompatibly
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExperimentalMethodInfo {
+    pub name: String,
+    pub schema_version: u32,
+    pub description: String,
+}
+
+/// A coin balance that changed between two states
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BalanceChangeInfo {
+    pub owner: String,
+    pub coin_type: String,
+    /// None if the coin store did not exist before
+    pub before: Option<String>,
+    /// None if the coin store was removed
+    pub after: Option<String>,
+}
+
+/// Top level objects, accounts and balances that differ between the states after two
+/// blocks
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StateDiffInfo {
+    pub from_block: u128,
+    pub from_state_root: String,
+    pub to_block: u128,
+    pub to_state_root: String,
+    pub added_accounts: Vec<String>,
+    pub removed_accounts: Vec<String>,
+    pub balance_changes: Vec<BalanceChangeInfo>,
+    /// Ids of the added, removed and changed objects, at most 1000 of each
+    pub added_objects: Vec<String>,
+    pub removed_objects: Vec<String>,
+    pub changed_objects: Vec<String>,
+}
+
+/// Experimental RPC API trait, served only when the node enables it. Methods may change
+/// or be removed in any release, `listMethods` tells which ones a node serves.
+#[open_rpc(namespace = "experimental", tag = "Experimental")]
+#[rpc(server, client, namespace = "experimental")]
+pub trait ExperimentalRpcApi {
+    /// List the experimental methods with the version of their schema
+    #[method(name = "listMethods")]
+    async fn list_methods(&self) -> RpcResult<Vec<ExperimentalMethodInfo>>;
+
+    /// Compare the states after two blocks: accounts created or removed, changed coin
+    /// balances, and added, removed and changed top level objects
+    #[method(name = "getStateDiff")]
+    async fn get_state_diff(&self, from_block: u128, to_block: u128) -> RpcResult<StateDiffInfo>;
+}
+
+/// OpenRPC service discovery, see <https://spec.open-rpc.org/#service-discovery-method>
+#[rpc(server, client)]
+pub trait DiscoverRpcApi {
+    /// Get the OpenRPC document of the served methods, generated from the API traits
+    #[method(name = "rpc.discover")]
+    async fn discover(&self) -> RpcResult<Project>;
 }
 
 /// Subscription events
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum SubscriptionEvent {
     NewBlock(BlockInfo),
     NewTransaction(TransactionInfo),
@@ -283,22 +1699,92 @@ pub enum SubscriptionEvent {
     NodeStatus(NodeInfo),
 }
 
+/// How the items of a subscription are delivered
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SubscriptionDelivery {
+    /// Acknowledged delivery: at most this many items are sent ahead of the client's
+    /// `subscribe_ack`, emission pauses until it acknowledges more. A subscription that
+    /// falls behind the live feed while paused is closed and has to resume with
+    /// `from_height`.
+    pub ack_window: Option<u64>,
+}
+
+/// Delivery progress of an acknowledged subscription
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SubscriptionAckStatus {
+    /// Items sent since the subscription started
+    pub sent: u64,
+    /// Items the client acknowledged
+    pub acknowledged: u64,
+    pub in_flight: u64,
+    /// Whether emission waits for an acknowledgement
+    pub paused: bool,
+}
+
+/// A peer joining or leaving the node's peer set
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PeerEvent {
+    pub peer_id: String,
+    /// True when the peer connected, false when it disconnected
+    pub connected: bool,
+}
+
 /// WebSocket subscription API
 #[rpc(server, client, namespace = "subscribe")]
 pub trait SubscriptionRpcApi {
-    /// Subscribe to new blocks
+    /// Subscribe to new blocks. With `from_height`, persisted blocks from that height
+    /// are replayed before live blocks, so a reconnecting client misses nothing.
     #[subscription(name = "newBlocks", unsubscribe = "unsubscribeNewBlocks", item = BlockInfo)]
-    async fn subscribe_new_blocks(&self) -> jsonrpsee::core::SubscriptionResult;
+    async fn subscribe_new_blocks(
+        &self,
+        from_height: Option<u128>,
+        delivery: Option<SubscriptionDelivery>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+
+    /// Subscribe to events of new blocks, replaying events from `from_height` first
+    #[subscription(name = "events", unsubscribe = "unsubscribeEvents", item = EventInfo)]
+    async fn subscribe_events(
+        &self,
+        from_height: Option<u128>,
+        delivery: Option<SubscriptionDelivery>,
+    ) -> jsonrpsee::core::SubscriptionResult;
 
     /// Subscribe to new transactions
     #[subscription(name = "newTransactions", unsubscribe = "unsubscribeNewTransactions", item = TransactionInfo)]
     async fn subscribe_new_transactions(&self) -> jsonrpsee::core::SubscriptionResult;
 
+    /// Subscribe to transactions as the mempool admits them, optionally only those of a
+    /// sender or recipient
+    #[subscription(name = "pendingTransactions", unsubscribe = "unsubscribePendingTransactions", item = TransactionInfo)]
+    async fn subscribe_pending_transactions(
+        &self,
+        filter: Option<PendingTransactionFilter>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+
     /// Subscribe to peer events
-    #[subscription(name = "peerEvents", unsubscribe = "unsubscribePeerEvents", item = String)]
+    #[subscription(name = "peerEvents", unsubscribe = "unsubscribePeerEvents", item = PeerEvent)]
     async fn subscribe_peer_events(&self) -> jsonrpsee::core::SubscriptionResult;
 
     /// Subscribe to node status
     #[subscription(name = "nodeStatus", unsubscribe = "unsubscribeNodeStatus", item = NodeInfo)]
     async fn subscribe_node_status(&self) -> jsonrpsee::core::SubscriptionResult;
+
+    /// Subscribe to the activity of `addresses`: transactions they send or receive, both
+    /// when accepted into the mempool and when included, balance changes and staking
+    /// updates. Each connection may watch a limited number of addresses in total.
+    #[subscription(name = "addressActivity", unsubscribe = "unsubscribeAddressActivity", item = AddressActivity)]
+    async fn subscribe_address_activity(
+        &self,
+        addresses: Vec<String>,
+        delivery: Option<SubscriptionDelivery>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+
+    /// Acknowledge the first `delivered` items of a subscription opened with an
+    /// `ack_window`, resuming its emission if it was paused
+    #[method(name = "ack")]
+    async fn ack(
+        &self,
+        subscription_id: SubscriptionId<'static>,
+        delivered: u64,
+    ) -> RpcResult<SubscriptionAckStatus>;
 }
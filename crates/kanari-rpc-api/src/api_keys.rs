@@ -0,0 +1,354 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::auth::{RpcAuthConfig, constant_time_eq, presented_keys};
+use crate::error::RpcError;
+use crate::rate_limit::{RateLimit, TokenBucket};
+use anyhow::{Result, bail};
+use hyper::HeaderMap;
+use jsonrpsee::{
+    MethodResponse,
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObjectOwned, Request},
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex, RwLock},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Prefix of the secrets of stored API keys, telling them apart from configured ones
+pub const API_KEY_SECRET_PREFIX: &str = "kari_";
+
+/// A key handed out by the operator, scoped to some methods and its own rate limit
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ApiKey {
+    /// Public identifier of the key, used to revoke it
+    pub id: String,
+    pub label: String,
+    /// Hex encoded SHA-256 of the secret, which is only shown when the key is created
+    pub secret_hash: String,
+    /// Budget of the key across every connection using it, None for no limit
+    pub rate_limit: Option<RateLimit>,
+    /// Methods the key may call, `namespace_*` for a whole namespace. Empty for every
+    /// method outside the protected namespaces.
+    pub allowed_methods: Vec<String>,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+}
+
+impl ApiKey {
+    /// Whether the key may call `method`, `protected` if its namespace requires a key
+    pub fn allows(&self, method: &str, protected: bool) -> bool {
+        if self.allowed_methods.is_empty() {
+            return !protected;
+        }
+        self.allowed_methods
+            .iter()
+            .any(|allowed| match allowed.strip_suffix('*') {
+                Some(prefix) => method.starts_with(prefix),
+                None => allowed == method,
+            })
+    }
+}
+
+fn secret_hash(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::thread_rng().fill_bytes(&mut buf);
+    hex::encode(buf)
+}
+
+/// API keys kept in a file of the node data dir, created and revoked through the admin
+/// namespace. Only the hashes of the secrets are stored.
+#[derive(Debug)]
+pub struct ApiKeyStore {
+    /// None for keys that are not persisted
+    path: Option<PathBuf>,
+    keys: RwLock<Vec<ApiKey>>,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl ApiKeyStore {
+    /// A store whose keys are lost on restart
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            keys: RwLock::new(vec![]),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Load the keys saved at `path`, none if it does not exist yet
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let keys = if path.exists() {
+            serde_json::from_slice(&std::fs::read(&path)?)?
+        } else {
+            vec![]
+        };
+        Ok(Self {
+            path: Some(path),
+            keys: RwLock::new(keys),
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Create a key, returning it with its secret
+    pub fn create(
+        &self,
+        label: String,
+        rate_limit: Option<RateLimit>,
+        allowed_methods: Vec<String>,
+    ) -> Result<(ApiKey, String)> {
+        if let Some(limit) = &rate_limit {
+            if limit.requests_per_second <= 0.0 || limit.burst == 0 {
+                bail!("The rate limit of a key must be greater than 0");
+            }
+        }
+        let secret = format!("{}{}", API_KEY_SECRET_PREFIX, random_hex(32));
+        let key = ApiKey {
+            id: random_hex(8),
+            label,
+            secret_hash: secret_hash(&secret),
+            rate_limit,
+            allowed_methods,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default(),
+        };
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        let mut updated = keys.clone();
+        updated.push(key.clone());
+        self.save(&updated)?;
+        *keys = updated;
+        Ok((key, secret))
+    }
+
+    /// Revoke the key `id`, effective for the next call of every connection using it.
+    /// Returns false if there is no such key.
+    pub fn revoke(&self, id: &str) -> Result<bool> {
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        if !keys.iter().any(|key| key.id == id) {
+            return Ok(false);
+        }
+        let updated: Vec<ApiKey> = keys.iter().filter(|key| key.id != id).cloned().collect();
+        self.save(&updated)?;
+        *keys = updated;
+        self.buckets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(id);
+        Ok(true)
+    }
+
+    pub fn list(&self) -> Vec<ApiKey> {
+        self.keys.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// The id of the stored key the request headers carry, if any
+    pub fn authenticate(&self, headers: &HeaderMap) -> Option<String> {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        presented_keys(headers)
+            .filter(|presented| presented.starts_with(API_KEY_SECRET_PREFIX))
+            .find_map(|presented| {
+                let hash = secret_hash(presented);
+                keys.iter()
+                    .find(|key| constant_time_eq(key.secret_hash.as_bytes(), hash.as_bytes()))
+                    .map(|key| key.id.clone())
+            })
+    }
+
+    /// Check the key `id` may call `method` now and take a token from its budget
+    pub fn check(&self, id: &str, method: &str, protected: bool) -> Result<(), RpcError> {
+        self.check_at(id, method, protected, Instant::now())
+    }
+
+    fn check_at(
+        &self,
+        id: &str,
+        method: &str,
+        protected: bool,
+        now: Instant,
+    ) -> Result<(), RpcError> {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        let Some(key) = keys.iter().find(|key| key.id == id) else {
+            return Err(RpcError::Unauthorized(format!(
+                "API key {} was revoked",
+                id
+            )));
+        };
+        if !key.allows(method, protected) {
+            return Err(RpcError::Unauthorized(format!(
+                "API key {} may not call {}",
+                id, method
+            )));
+        }
+        let Some(limit) = &key.rate_limit else {
+            return Ok(());
+        };
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets
+            .entry(id.to_string())
+            .or_insert_with(|| TokenBucket::full(limit, now));
+        bucket.refill(limit, now);
+        let wait = bucket.wait_time(limit);
+        if !wait.is_zero() {
+            return Err(RpcError::RateLimited(
+                format!("too many requests for {} with API key {}", method, id),
+                wait.as_millis().min(u64::MAX as u128) as u64,
+            ));
+        }
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+
+    /// Write then rename, readable by the owner alone as the file identifies the keys
+    fn save(&self, keys: &[ApiKey]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let tmp = path.with_extension("tmp");
+        write_private(&tmp, &serde_json::to_vec_pretty(keys)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(content)?;
+    Ok(())
+}
+
+/// RPC middleware enforcing the scope and rate limit of the stored key a connection
+/// presented. Connections without one are left to the other middleware.
+#[derive(Clone)]
+pub struct ApiKeyService<S> {
+    service: S,
+    /// Id of the presented key and the store it belongs to
+    key: Option<(String, Arc<ApiKeyStore>)>,
+    auth: Arc<RpcAuthConfig>,
+}
+
+impl<S> ApiKeyService<S> {
+    pub fn new(
+        service: S,
+        key: Option<(String, Arc<ApiKeyStore>)>,
+        auth: Arc<RpcAuthConfig>,
+    ) -> Self {
+        Self { service, key, auth }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for ApiKeyService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+    S::Future: Send + 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let Some((key_id, store)) = &self.key else {
+            return Box::pin(self.service.call(request));
+        };
+        let method = request.method_name();
+        match store.check(key_id, method, self.auth.is_protected(method)) {
+            Ok(()) => Box::pin(self.service.call(request)),
+            Err(e) => {
+                let response = MethodResponse::error(request.id, ErrorObjectOwned::from(e));
+                Box::pin(async move { response })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::{AUTHORIZATION, HeaderValue};
+    use std::time::Duration;
+
+    #[test]
+    fn test_keys_are_scoped_limited_persisted_and_revoked() {
+        let dir = std::env::temp_dir().join(format!("kanari-api-keys-{}", random_hex(4)));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("api_keys.json");
+        let store = ApiKeyStore::load(&path).unwrap();
+        let (key, secret) = store
+            .create(
+                "explorer".to_string(),
+                Some(RateLimit {
+                    requests_per_second: 1.0,
+                    burst: 2,
+                }),
+                vec!["kanari_*".to_string(), "debug_getRawBlock".to_string()],
+            )
+            .unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains(&secret));
+
+        // The key survives a restart
+        let store = ApiKeyStore::load(&path).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", secret)).unwrap(),
+        );
+        assert_eq!(store.authenticate(&headers), Some(key.id.clone()));
+        assert_eq!(store.authenticate(&HeaderMap::new()), None);
+
+        let now = Instant::now();
+        assert!(
+            store
+                .check_at(&key.id, "kanari_getBlockHeight", false, now)
+                .is_ok()
+        );
+        assert!(
+            store
+                .check_at(&key.id, "debug_getRawBlock", true, now)
+                .is_ok()
+        );
+        assert!(matches!(
+            store.check_at(&key.id, "admin_addPeer", true, now),
+            Err(RpcError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            store.check_at(&key.id, "kanari_getBlockHeight", false, now),
+            Err(RpcError::RateLimited(_, 1000))
+        ));
+        let later = now + Duration::from_secs(1);
+        assert!(
+            store
+                .check_at(&key.id, "kanari_getBlockHeight", false, later)
+                .is_ok()
+        );
+
+        assert!(store.revoke(&key.id).unwrap());
+        assert!(!store.revoke(&key.id).unwrap());
+        assert!(ApiKeyStore::load(&path).unwrap().list().is_empty());
+        assert!(
+            store
+                .check_at(&key.id, "kanari_getBlockHeight", false, later)
+                .is_err()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
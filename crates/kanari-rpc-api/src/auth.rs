@@ -0,0 +1,142 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::RpcError;
+use hyper::{HeaderMap, header::AUTHORIZATION};
+use jsonrpsee::{
+    MethodResponse,
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObjectOwned, Request},
+};
+use std::{future::Future, pin::Pin, sync::Arc};
+
+/// Header carrying the API key, as an alternative to `Authorization: Bearer <key>`
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// API key authentication for the public RPC endpoint
+#[derive(Debug, Clone)]
+pub struct RpcAuthConfig {
+    /// Keys accepted by the endpoint. Without any, every method is public.
+    pub api_keys: Vec<String>,
+    /// Namespaces whose methods require a valid key
+    pub protected_namespaces: Vec<String>,
+}
+
+impl Default for RpcAuthConfig {
+    fn default() -> Self {
+        Self {
+            api_keys: vec![],
+            protected_namespaces: vec!["admin".to_string(), "debug".to_string()],
+        }
+    }
+}
+
+impl RpcAuthConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.api_keys.is_empty()
+    }
+
+    /// Whether the request headers carry one of the configured keys
+    pub fn authorize(&self, headers: &HeaderMap) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+        presented_keys(headers).any(|presented| {
+            self.api_keys
+                .iter()
+                .any(|key| constant_time_eq(key.as_bytes(), presented.as_bytes()))
+        })
+    }
+
+    /// Whether `method` belongs to a protected namespace
+    pub fn is_protected(&self, method: &str) -> bool {
+        let namespace = method
+            .split_once('_')
+            .map_or(method, |(namespace, _)| namespace);
+        self.protected_namespaces
+            .iter()
+            .any(|protected| protected == namespace)
+    }
+}
+
+/// The keys sent as `Authorization: Bearer <key>` or in `API_KEY_HEADER`
+pub(crate) fn presented_keys(headers: &HeaderMap) -> impl Iterator<Item = &str> {
+    let bearer = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let api_key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    [bearer, api_key].into_iter().flatten().map(str::trim)
+}
+
+/// Compare without leaking the position of the first mismatch through timing
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// RPC middleware rejecting calls to protected namespaces from unauthorized requests
+#[derive(Clone)]
+pub struct AuthService<S> {
+    service: S,
+    authorized: bool,
+    config: Arc<RpcAuthConfig>,
+}
+
+impl<S> AuthService<S> {
+    pub fn new(service: S, authorized: bool, config: Arc<RpcAuthConfig>) -> Self {
+        Self {
+            service,
+            authorized,
+            config,
+        }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for AuthService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+    S::Future: Send + 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        if self.authorized || !self.config.is_protected(request.method_name()) {
+            return Box::pin(self.service.call(request));
+        }
+        let error =
+            RpcError::Unauthorized(format!("{} requires an API key", request.method_name()));
+        let response = MethodResponse::error(request.id, ErrorObjectOwned::from(error));
+        Box::pin(async move { response })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    #[test]
+    fn test_authorize_and_protected_namespaces() {
+        let config = RpcAuthConfig {
+            api_keys: vec!["secret".to_string()],
+            ..Default::default()
+        };
+        assert!(config.is_protected("admin_addPeer"));
+        assert!(config.is_protected("debug_traceTransaction"));
+        assert!(!config.is_protected("kanari_getBlockHeight"));
+
+        let mut headers = HeaderMap::new();
+        assert!(!config.authorize(&headers));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer wrong"));
+        assert!(!config.authorize(&headers));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        assert!(config.authorize(&headers));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("secret"));
+        assert!(config.authorize(&headers));
+        assert!(RpcAuthConfig::default().authorize(&HeaderMap::new()));
+    }
+}
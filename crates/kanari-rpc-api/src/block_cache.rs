@@ -0,0 +1,107 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::BlockInfo;
+use std::collections::VecDeque;
+use std::sync::RwLock;
+
+/// Number of recent blocks kept in memory
+pub const BLOCK_CACHE_CAPACITY: usize = 64;
+
+/// The most recent blocks in their RPC form, filled by the block production and import
+/// path, so queries for the chain tip are answered without reading the database
+#[derive(Debug)]
+pub struct BlockCache {
+    capacity: usize,
+    /// Consecutive blocks in ascending order
+    blocks: RwLock<VecDeque<BlockInfo>>,
+}
+
+impl Default for BlockCache {
+    fn default() -> Self {
+        Self::new(BLOCK_CACHE_CAPACITY)
+    }
+}
+
+impl BlockCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            blocks: RwLock::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Add the new chain tip. A block at or below the cached tip replaces it and the
+    /// blocks above, a block that does not extend the tip starts the cache over.
+    pub fn insert(&self, block: BlockInfo) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut blocks = self.blocks.write().unwrap_or_else(|e| e.into_inner());
+        while blocks.back().is_some_and(|tip| tip.number >= block.number) {
+            blocks.pop_back();
+        }
+        if blocks
+            .back()
+            .is_some_and(|tip| tip.number + 1 != block.number)
+        {
+            blocks.clear();
+        }
+        if blocks.len() == self.capacity {
+            blocks.pop_front();
+        }
+        blocks.push_back(block);
+    }
+
+    /// The cached block with this number
+    pub fn get(&self, number: u128) -> Option<BlockInfo> {
+        let blocks = self.blocks.read().unwrap_or_else(|e| e.into_inner());
+        let first = blocks.front()?.number;
+        let index = usize::try_from(number.checked_sub(first)?).ok()?;
+        blocks.get(index).cloned()
+    }
+
+    pub fn latest(&self) -> Option<BlockInfo> {
+        let blocks = self.blocks.read().unwrap_or_else(|e| e.into_inner());
+        blocks.back().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(number: u128, hash: &str) -> BlockInfo {
+        BlockInfo {
+            number,
+            hash: hash.to_string(),
+            parent_hash: "0x00".to_string(),
+            timestamp: 0,
+            transaction_count: 0,
+            gas_used: 0,
+            gas_limit: 0,
+            state_root: "0x00".to_string(),
+            fees: None,
+        }
+    }
+
+    #[test]
+    fn test_cache_keeps_recent_consecutive_blocks() {
+        let cache = BlockCache::new(3);
+        for number in 1..=4 {
+            cache.insert(block(number, "a"));
+        }
+        assert!(cache.get(1).is_none());
+        assert_eq!(cache.get(2).unwrap().number, 2);
+        assert_eq!(cache.latest().unwrap().number, 4);
+
+        // A replaced block drops the blocks above it
+        cache.insert(block(3, "b"));
+        assert_eq!(cache.get(3).unwrap().hash, "b");
+        assert!(cache.get(4).is_none());
+        // A gap starts over
+        cache.insert(block(9, "c"));
+        assert!(cache.get(3).is_none());
+        assert_eq!(cache.latest().unwrap().number, 9);
+    }
+}
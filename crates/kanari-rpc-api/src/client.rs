@@ -0,0 +1,389 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::{KanariRpcApiClient, TransactionReceiptInfo};
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use jsonrpsee::core::client::{
+    BatchResponse, ClientT, Error as ClientError, Subscription, SubscriptionClientT,
+};
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::debug;
+
+/// How long a request waits for its response by default
+pub const DEFAULT_CLIENT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Retries of a request failing to reach the node by default
+pub const DEFAULT_CLIENT_MAX_RETRIES: u32 = 3;
+/// Wait before the first retry by default, doubled for each following one
+pub const DEFAULT_CLIENT_INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+/// Longest wait between two retries by default
+pub const DEFAULT_CLIENT_MAX_BACKOFF: Duration = Duration::from_secs(5);
+/// Interval `wait_for_transaction` polls for the receipt at
+pub const WAIT_FOR_TRANSACTION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// When a request failing to reach the node is sent again
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt, 0 disables retrying
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_CLIENT_MAX_RETRIES,
+            initial_backoff: DEFAULT_CLIENT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_CLIENT_MAX_BACKOFF,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before retry `retry`, counted from 0
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+
+    /// Only failures to reach the node are retried. An error returned by the node, such
+    /// as a rejected transaction, would be returned again.
+    pub fn is_retryable(error: &ClientError) -> bool {
+        matches!(
+            error,
+            ClientError::Transport(_) | ClientError::RequestTimeout | ClientError::RestartNeeded(_)
+        )
+    }
+}
+
+/// Build a `KanariClient` over HTTP or WebSocket
+#[derive(Clone, Debug)]
+pub struct KanariClientBuilder {
+    request_timeout: Duration,
+    retry: RetryPolicy,
+}
+
+impl Default for KanariClientBuilder {
+    fn default() -> Self {
+        Self {
+            request_timeout: DEFAULT_CLIENT_REQUEST_TIMEOUT,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+impl KanariClientBuilder {
+    pub fn request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry.max_retries = max_retries;
+        self
+    }
+
+    /// A client of the node at an `http://` or `https://` URL
+    pub fn build_http(self, url: impl AsRef<str>) -> Result<KanariClient> {
+        let client = HttpClientBuilder::default()
+            .request_timeout(self.request_timeout)
+            .build(url.as_ref())?;
+        Ok(KanariClient {
+            transport: Transport::Http(client),
+            retry: self.retry,
+        })
+    }
+
+    /// A client of the node at a `ws://` or `wss://` URL, which also serves the
+    /// subscriptions. A dropped connection is opened again by the next retried request.
+    pub async fn build_ws(self, url: impl AsRef<str>) -> Result<KanariClient> {
+        let url = url.as_ref().to_string();
+        let client = ws_connect(&url, self.request_timeout).await?;
+        Ok(KanariClient {
+            transport: Transport::Ws {
+                url,
+                request_timeout: self.request_timeout,
+                client: RwLock::new(Arc::new(client)),
+            },
+            retry: self.retry,
+        })
+    }
+
+    /// An HTTP or WebSocket client depending on the scheme of `url`
+    pub async fn build(self, url: impl AsRef<str>) -> Result<KanariClient> {
+        let url = url.as_ref();
+        if url.starts_with("ws://") || url.starts_with("wss://") {
+            self.build_ws(url).await
+        } else if url.starts_with("http://") || url.starts_with("https://") {
+            self.build_http(url)
+        } else {
+            bail!(
+                "Unsupported RPC URL {}, expected http(s):// or ws(s)://",
+                url
+            )
+        }
+    }
+}
+
+async fn ws_connect(url: &str, request_timeout: Duration) -> Result<WsClient, ClientError> {
+    WsClientBuilder::default()
+        .request_timeout(request_timeout)
+        .connection_timeout(request_timeout)
+        .build(url)
+        .await
+}
+
+enum Transport {
+    Http(HttpClient),
+    Ws {
+        url: String,
+        request_timeout: Duration,
+        client: RwLock<Arc<WsClient>>,
+    },
+}
+
+/// Params already serialized, so a retried request sends them again
+struct RawParams(Option<Box<RawValue>>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        Ok(self.0)
+    }
+}
+
+/// A client of a Kanari node retrying the requests that fail to reach it. It implements
+/// the jsonrpsee client traits, so every generated client trait such as
+/// `KanariRpcApiClient` or `SubscriptionRpcApiClient` is available on it.
+pub struct KanariClient {
+    transport: Transport,
+    retry: RetryPolicy,
+}
+
+impl fmt::Debug for KanariClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let transport = match &self.transport {
+            Transport::Http(_) => "http".to_string(),
+            Transport::Ws { url, .. } => url.clone(),
+        };
+        f.debug_struct("KanariClient")
+            .field("transport", &transport)
+            .field("retry", &self.retry)
+            .finish()
+    }
+}
+
+impl KanariClient {
+    pub fn builder() -> KanariClientBuilder {
+        KanariClientBuilder::default()
+    }
+
+    /// Wait until the transaction `tx_hash` is included in a block and return its
+    /// receipt, failing after `timeout`
+    pub async fn wait_for_transaction(
+        &self,
+        tx_hash: &str,
+        timeout: Duration,
+    ) -> Result<TransactionReceiptInfo> {
+        let started = Instant::now();
+        loop {
+            if let Some(receipt) = self.get_transaction_receipt(tx_hash.to_string()).await? {
+                return Ok(receipt);
+            }
+            if started.elapsed() >= timeout {
+                bail!(
+                    "Transaction {} was not included within {:?}",
+                    tx_hash,
+                    timeout
+                );
+            }
+            tokio::time::sleep(WAIT_FOR_TRANSACTION_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Open the WebSocket connection again if it was dropped
+    async fn reconnect(&self) -> Result<(), ClientError> {
+        let Transport::Ws {
+            url,
+            request_timeout,
+            client,
+        } = &self.transport
+        else {
+            return Ok(());
+        };
+        let mut client = client.write().await;
+        if client.is_connected() {
+            return Ok(());
+        }
+        debug!("Reconnecting to {}", url);
+        *client = Arc::new(ws_connect(url, *request_timeout).await?);
+        Ok(())
+    }
+
+    async fn request_once<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: RawParams,
+    ) -> Result<R, ClientError> {
+        match &self.transport {
+            Transport::Http(client) => client.request(method, params).await,
+            Transport::Ws { client, .. } => {
+                let client = client.read().await.clone();
+                client.request(method, params).await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ClientT for KanariClient {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), ClientError>
+    where
+        Params: ToRpcParams + Send,
+    {
+        match &self.transport {
+            Transport::Http(client) => client.notification(method, params).await,
+            Transport::Ws { client, .. } => {
+                let client = client.read().await.clone();
+                client.notification(method, params).await
+            }
+        }
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, ClientError>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let params = params.to_rpc_params().map_err(ClientError::ParseError)?;
+        let mut retry = 0;
+        loop {
+            let error = match self.request_once(method, RawParams(params.clone())).await {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+            if retry >= self.retry.max_retries || !RetryPolicy::is_retryable(&error) {
+                return Err(error);
+            }
+            let backoff = self.retry.backoff(retry);
+            debug!("{} failed: {}, retrying in {:?}", method, error, backoff);
+            tokio::time::sleep(backoff).await;
+            if let Err(e) = self.reconnect().await {
+                debug!("Reconnect failed: {}", e);
+            }
+            retry += 1;
+        }
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, ClientError>
+    where
+        R: DeserializeOwned + fmt::Debug + 'a,
+    {
+        match &self.transport {
+            Transport::Http(client) => client.batch_request(batch).await,
+            Transport::Ws { client, .. } => {
+                let client = client.read().await.clone();
+                client.batch_request(batch).await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SubscriptionClientT for KanariClient {
+    async fn subscribe<'a, Notif, Params>(
+        &self,
+        subscribe_method: &'a str,
+        params: Params,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<Notif>, ClientError>
+    where
+        Params: ToRpcParams + Send,
+        Notif: DeserializeOwned,
+    {
+        // Subscriptions need the WebSocket transport, HTTP fails with its own error
+        match &self.transport {
+            Transport::Http(client) => {
+                client
+                    .subscribe(subscribe_method, params, unsubscribe_method)
+                    .await
+            }
+            Transport::Ws { client, .. } => {
+                let client = client.read().await.clone();
+                client
+                    .subscribe(subscribe_method, params, unsubscribe_method)
+                    .await
+            }
+        }
+    }
+
+    async fn subscribe_to_method<'a, Notif>(
+        &self,
+        method: &'a str,
+    ) -> Result<Subscription<Notif>, ClientError>
+    where
+        Notif: DeserializeOwned,
+    {
+        match &self.transport {
+            Transport::Http(client) => client.subscribe_to_method(method).await,
+            Transport::Ws { client, .. } => {
+                let client = client.read().await.clone();
+                client.subscribe_to_method(method).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_maximum_and_skips_node_errors() {
+        let retry = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+        let backoffs: Vec<_> = (0..5)
+            .map(|retry_number| retry.backoff(retry_number))
+            .collect();
+        assert_eq!(
+            backoffs,
+            vec![
+                Duration::from_millis(100),
+                Duration::from_millis(200),
+                Duration::from_millis(400),
+                Duration::from_millis(500),
+                Duration::from_millis(500),
+            ]
+        );
+        assert_eq!(retry.backoff(u32::MAX), Duration::from_millis(500));
+
+        assert!(RetryPolicy::is_retryable(&ClientError::RequestTimeout));
+        let rejected = ClientError::Call(jsonrpsee::types::ErrorObjectOwned::owned(
+            -32602,
+            "Invalid params",
+            None::<()>,
+        ));
+        assert!(!RetryPolicy::is_retryable(&rejected));
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow};
+use hyper::{
+    Method,
+    header::{HeaderName, HeaderValue},
+};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Headers browsers may send by default: the JSON content type and the API key headers
+pub const DEFAULT_CORS_HEADERS: &[&str] = &["content-type", "authorization", "x-api-key"];
+
+/// Methods browsers may use by default, JSON-RPC calls are POST requests
+pub const DEFAULT_CORS_METHODS: &[&str] = &["POST"];
+
+/// Cross-origin access of browser pages to the public RPC endpoint and REST gateway.
+/// Without allowed origins no CORS headers are sent, so browsers only let pages of the
+/// endpoint's own origin read the responses.
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// Origins such as `https://wallet.example`, `*` allows any origin
+    pub allowed_origins: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    /// Allow any origin, header and method. Meant for local development only.
+    pub permissive: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec![],
+            allowed_headers: DEFAULT_CORS_HEADERS.iter().map(|h| h.to_string()).collect(),
+            allowed_methods: DEFAULT_CORS_METHODS.iter().map(|m| m.to_string()).collect(),
+            permissive: false,
+        }
+    }
+}
+
+impl CorsConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.permissive || !self.allowed_origins.is_empty()
+    }
+
+    /// The layer answering preflight requests and adding the CORS headers to responses
+    pub fn layer(&self) -> Result<CorsLayer> {
+        if self.permissive {
+            return Ok(CorsLayer::permissive());
+        }
+        if self.allowed_origins.is_empty() {
+            return Ok(CorsLayer::new());
+        }
+        let origins = if self.allowed_origins.iter().any(|origin| origin == "*") {
+            AllowOrigin::any()
+        } else {
+            AllowOrigin::list(
+                self.allowed_origins
+                    .iter()
+                    .map(|origin| {
+                        HeaderValue::from_str(origin.trim_end_matches('/'))
+                            .map_err(|e| anyhow!("Invalid CORS origin {}: {}", origin, e))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )
+        };
+        let headers = self
+            .allowed_headers
+            .iter()
+            .map(|header| {
+                HeaderName::from_bytes(header.as_bytes())
+                    .map_err(|e| anyhow!("Invalid CORS header {}: {}", header, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let methods = self
+            .allowed_methods
+            .iter()
+            .map(|method| {
+                Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                    .map_err(|e| anyhow!("Invalid CORS method {}: {}", method, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(CorsLayer::new()
+            .allow_origin(origins)
+            .allow_headers(headers)
+            .allow_methods(methods))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::{Request, Response, header};
+    use std::convert::Infallible;
+    use tower::{Layer, ServiceExt, service_fn};
+
+    async fn preflight(config: &CorsConfig, origin: &str) -> Option<HeaderValue> {
+        let service = config.layer().unwrap().layer(service_fn(|_| async {
+            Ok::<_, Infallible>(Response::new(String::new()))
+        }));
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(String::new())
+            .unwrap();
+        let response = service.oneshot(request).await.unwrap();
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .cloned()
+    }
+
+    #[tokio::test]
+    async fn test_only_allowed_origins_pass_preflight() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://wallet.example/".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            preflight(&config, "https://wallet.example").await.unwrap(),
+            "https://wallet.example"
+        );
+        assert!(preflight(&config, "https://evil.example").await.is_none());
+        assert!(
+            preflight(&CorsConfig::default(), "https://wallet.example")
+                .await
+                .is_none()
+        );
+
+        let dev = CorsConfig {
+            permissive: true,
+            ..Default::default()
+        };
+        assert_eq!(preflight(&dev, "http://localhost:5173").await.unwrap(), "*");
+    }
+}
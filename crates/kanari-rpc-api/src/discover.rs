@@ -0,0 +1,104 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(feature = "admin-rpc")]
+use crate::api::AdminRpcApiOpenRpc;
+#[cfg(feature = "debug-rpc")]
+use crate::api::DebugRpcApiOpenRpc;
+use crate::api::{DiscoverRpcApiServer, ExperimentalRpcApiOpenRpc, KanariRpcApiOpenRpc};
+use crate::error::RpcResult;
+use jsonrpsee::core::async_trait;
+use rooch_open_rpc::Project;
+
+/// OpenRPC document of the namespaces this build serves, without the disabled ones and
+/// with the experimental one if enabled. Method signatures, docs and schemas are
+/// generated from the API traits, so the document follows every change.
+pub fn kanari_rpc_doc(
+    version: &str,
+    disabled_namespaces: &[String],
+    experimental: bool,
+) -> Project {
+    #[cfg(any(feature = "admin-rpc", feature = "debug-rpc"))]
+    let enabled = |namespace: &str| !disabled_namespaces.iter().any(|d| d == namespace);
+    let mut project = Project::new(
+        version,
+        "Kanari JSON-RPC",
+        "Kanari node JSON-RPC API",
+        "Kanari Contributors",
+        "https://kanari.site",
+        "opensource@kanari.site",
+        "Apache-2.0",
+        "https://raw.githubusercontent.com/kanari-network/kanari-sdk/main/LICENSE",
+    );
+    project.add_module(KanariRpcApiOpenRpc::module_doc());
+    #[cfg(feature = "admin-rpc")]
+    if enabled("admin") {
+        project.add_module(AdminRpcApiOpenRpc::module_doc());
+    }
+    #[cfg(feature = "debug-rpc")]
+    if enabled("debug") {
+        project.add_module(DebugRpcApiOpenRpc::module_doc());
+    }
+    if experimental {
+        project.add_module(ExperimentalRpcApiOpenRpc::module_doc());
+    }
+    project
+}
+
+/// Serves `rpc.discover`, the document is built once at startup
+pub struct DiscoverRpcImpl {
+    doc: Project,
+}
+
+impl DiscoverRpcImpl {
+    pub fn new(version: &str, disabled_namespaces: &[String], experimental: bool) -> Self {
+        Self {
+            doc: kanari_rpc_doc(version, disabled_namespaces, experimental),
+        }
+    }
+}
+
+#[async_trait]
+impl DiscoverRpcApiServer for DiscoverRpcImpl {
+    async fn discover(&self) -> RpcResult<Project> {
+        Ok(self.doc.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_lists_trait_methods() {
+        let doc = serde_json::to_value(kanari_rpc_doc("1.0.0", &[], false)).unwrap();
+        let methods: Vec<&str> = doc["methods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|method| method["name"].as_str().unwrap())
+            .collect();
+        assert!(methods.contains(&"kanari_getBlockByNumber"));
+        // Experimental methods are opt-in
+        assert!(!methods.iter().any(|name| name.starts_with("experimental_")));
+        #[cfg(feature = "admin-rpc")]
+        assert!(methods.contains(&"admin_setLogLevel"));
+        // Parameters and results reference the generated schemas
+        assert!(doc["components"]["schemas"]["BlockInfo"].is_object());
+
+        // Disabled namespaces are left out
+        #[cfg(feature = "admin-rpc")]
+        {
+            let public =
+                serde_json::to_value(kanari_rpc_doc("1.0.0", &["admin".to_string()], false))
+                    .unwrap();
+            assert!(
+                public["methods"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .all(|method| !method["name"].as_str().unwrap().starts_with("admin_"))
+            );
+        }
+    }
+}
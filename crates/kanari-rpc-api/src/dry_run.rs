@@ -0,0 +1,116 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::MoveAbortInfo;
+use anyhow::Result;
+use framework_release::error_descriptions::ERROR_DESCRIPTIONS;
+use kanari_db::RoochDB;
+use kanari_types::event::BlockEvent;
+use kanari_types::receipt::ExecutionStatus;
+use kanari_types::transaction::SignedTransaction;
+
+/// What executing a transaction against the latest state would do
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DryRunOutcome {
+    pub status: ExecutionStatus,
+    pub gas_used: u64,
+    pub events: Vec<BlockEvent>,
+}
+
+/// Execute a transaction alone as block `block_number`, on the state its parent left,
+/// the way block application would. The execution is thrown away, nothing is committed.
+pub fn dry_run(db: &RoochDB, block_number: u128, tx: &SignedTransaction) -> Result<DryRunOutcome> {
+    let execution = db.execute_block(block_number, std::slice::from_ref(tx))?;
+    let output = execution
+        .outputs
+        .into_iter()
+        .next()
+        .expect("One output per executed transaction");
+    Ok(DryRunOutcome {
+        status: output.status,
+        gas_used: output.gas_used,
+        events: execution.events,
+    })
+}
+
+/// A Move abort with the name and explanation of its code from the framework error
+/// descriptions, if the code is known
+pub fn move_abort_info(location: &str, abort_code: u64) -> MoveAbortInfo {
+    let description = ERROR_DESCRIPTIONS
+        .values()
+        .find_map(|mapping| mapping.get_explanation(location, abort_code));
+    MoveAbortInfo {
+        location: location.to_string(),
+        abort_code,
+        code_name: description.as_ref().map(|d| d.code_name.clone()),
+        explanation: description
+            .as_ref()
+            .map(|d| d.code_description.clone())
+            .filter(|explanation| !explanation.is_empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kanari_config::KanariOpt;
+    use kanari_types::kari_coin::KARI;
+    use kanari_types::reward::RewardPayment;
+    use kanari_types::system_transaction::SystemTransaction;
+    use kanari_types::transaction::KanariTransaction;
+    use move_core_types::account_address::AccountAddress;
+    use move_core_types::u256::U256;
+    use moveos_types::h256::H256;
+    use moveos_types::state::MoveStructType;
+
+    #[test]
+    fn test_dry_run_executes_without_committing() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = RoochDB::init(&opt.store, &prometheus::Registry::new()).unwrap();
+        let funding = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: AccountAddress::ONE,
+                recipient: AccountAddress::ONE,
+                amount: 22_000,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        let execution = db.execute_block(1, &[funding]).unwrap();
+        db.commit_block_state(1, &execution.root).unwrap();
+
+        let transfer = |amount: u128| SignedTransaction {
+            tx: KanariTransaction {
+                sender: AccountAddress::ONE,
+                sequence_number: 0,
+                chain_id: 1,
+                genesis_hash: H256::zero(),
+                recipient: Some(AccountAddress::new([2; AccountAddress::LENGTH])),
+                amount,
+                gas_limit: 21_000,
+                gas_price: 1,
+                data: vec![],
+                access_list: None,
+            },
+            public_key: vec![],
+            signature: vec![],
+        };
+        let outcome = dry_run(&db, 2, &transfer(1_000)).unwrap();
+        assert!(outcome.status.is_success());
+        assert_eq!(outcome.gas_used, 21_000);
+        assert_eq!(outcome.events.len(), 1);
+
+        let outcome = dry_run(&db, 2, &transfer(1_001)).unwrap();
+        assert!(!outcome.status.is_success());
+        assert_eq!(outcome.gas_used, 21_000);
+        assert!(outcome.events.is_empty());
+
+        assert_eq!(db.latest_root().unwrap(), Some(execution.root));
+        assert_eq!(
+            db.get_coin_balance(AccountAddress::ONE, &KARI::struct_tag())
+                .unwrap(),
+            Some(U256::from(22_000u64))
+        );
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use jsonrpsee::types::ErrorObjectOwned;
+use kanari_mempool::MempoolRejection;
 use thiserror::Error;
 
 /// RPC API errors
@@ -30,22 +31,183 @@ pub enum RpcError {
 
     #[error("Network error: {0}")]
     NetworkError(String),
+
+    /// The transaction pool refused the transaction; the data carries the `reason`
+    #[error("Transaction rejected: {0}")]
+    TransactionRejected(String, serde_json::Value),
+
+    /// The caller exceeded a rate limit; the data carries `retry_after_ms`
+    #[error("Rate limited: {0}")]
+    RateLimited(String, u64),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+
+    /// The transaction was built for another chain id or genesis
+    #[error("Wrong chain: {0}")]
+    WrongChain(String),
+
+    #[error("Invalid sequence number: {0}")]
+    InvalidSequenceNumber(String),
+
+    #[error("Invalid gas: {0}")]
+    InvalidGas(String),
+}
+
+/// JSON-RPC error codes returned by the node, with their name and meaning
+pub const RPC_ERROR_CODES: &[(i32, &str, &str)] = &[
+    (-32700, "ParseError", "The request is not valid JSON"),
+    (
+        -32600,
+        "InvalidRequest",
+        "The request is not a valid JSON-RPC request",
+    ),
+    (
+        -32601,
+        "MethodNotFound",
+        "The method does not exist or is not enabled on this endpoint",
+    ),
+    (
+        -32602,
+        "InvalidParams",
+        "A parameter is missing, malformed or out of range",
+    ),
+    (
+        -32603,
+        "InternalError",
+        "The node failed while handling the request",
+    ),
+    (
+        -32000,
+        "NodeNotReady",
+        "The node is starting up or a required subsystem is unavailable",
+    ),
+    (
+        -32001,
+        "TransactionFailed",
+        "The transaction was rejected by the pool or failed to execute",
+    ),
+    (
+        -32002,
+        "BlockNotFound",
+        "The requested block is not stored on this node",
+    ),
+    (
+        -32003,
+        "AccountNotFound",
+        "The requested account does not exist",
+    ),
+    (
+        -32004,
+        "NetworkError",
+        "The node could not reach the peer or service it depends on",
+    ),
+    (
+        -32005,
+        "TransactionRejected",
+        "The transaction pool refused the transaction, see the error data `reason`",
+    ),
+    (
+        -32006,
+        "RateLimited",
+        "Too many requests from this IP, retry after the error data `retry_after_ms`",
+    ),
+    (
+        -32007,
+        "Unauthorized",
+        "The method requires an API key, sent as `Authorization: Bearer <key>` or `X-Api-Key`",
+    ),
+    (
+        -32008,
+        "InvalidSignature",
+        "The transaction signature does not verify against its public key",
+    ),
+    (
+        -32009,
+        "WrongChain",
+        "The transaction was built for another chain id or genesis block",
+    ),
+    (
+        -32010,
+        "InvalidSequenceNumber",
+        "The transaction sequence number is below the account sequence number",
+    ),
+    (
+        -32011,
+        "InvalidGas",
+        "The transaction gas limit is out of range or its gas price below the minimum",
+    ),
+];
+
+/// Name and meaning of a JSON-RPC error code returned by the node
+pub fn describe_rpc_error_code(code: i32) -> Option<(&'static str, &'static str)> {
+    RPC_ERROR_CODES
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map(|(_, name, description)| (*name, *description))
+}
+
+impl RpcError {
+    pub fn code(&self) -> i32 {
+        match self {
+            RpcError::InternalError(_) => -32603,
+            RpcError::InvalidParams(_) => -32602,
+            RpcError::MethodNotFound(_) => -32601,
+            RpcError::NodeNotReady(_) => -32000,
+            RpcError::TransactionFailed(_) => -32001,
+            RpcError::BlockNotFound(_) => -32002,
+            RpcError::AccountNotFound(_) => -32003,
+            RpcError::NetworkError(_) => -32004,
+            RpcError::TransactionRejected(..) => -32005,
+            RpcError::RateLimited(..) => -32006,
+            RpcError::Unauthorized(_) => -32007,
+            RpcError::InvalidSignature(_) => -32008,
+            RpcError::WrongChain(_) => -32009,
+            RpcError::InvalidSequenceNumber(_) => -32010,
+            RpcError::InvalidGas(_) => -32011,
+        }
+    }
 }
 
 impl From<RpcError> for ErrorObjectOwned {
     fn from(err: RpcError) -> Self {
-        let (code, message) = match err {
-            RpcError::InternalError(msg) => (-32603, format!("Internal error: {}", msg)),
-            RpcError::InvalidParams(msg) => (-32602, format!("Invalid params: {}", msg)),
-            RpcError::MethodNotFound(msg) => (-32601, format!("Method not found: {}", msg)),
-            RpcError::NodeNotReady(msg) => (-32000, format!("Node not ready: {}", msg)),
-            RpcError::TransactionFailed(msg) => (-32001, format!("Transaction failed: {}", msg)),
-            RpcError::BlockNotFound(msg) => (-32002, format!("Block not found: {}", msg)),
-            RpcError::AccountNotFound(msg) => (-32003, format!("Account not found: {}", msg)),
-            RpcError::NetworkError(msg) => (-32004, format!("Network error: {}", msg)),
+        let code = err.code();
+        let (message, data) = match err {
+            RpcError::InternalError(msg) => (format!("Internal error: {}", msg), None),
+            RpcError::InvalidParams(msg) => (format!("Invalid params: {}", msg), None),
+            RpcError::MethodNotFound(msg) => (format!("Method not found: {}", msg), None),
+            RpcError::NodeNotReady(msg) => (format!("Node not ready: {}", msg), None),
+            RpcError::TransactionFailed(msg) => (format!("Transaction failed: {}", msg), None),
+            RpcError::BlockNotFound(msg) => (format!("Block not found: {}", msg), None),
+            RpcError::AccountNotFound(msg) => (format!("Account not found: {}", msg), None),
+            RpcError::NetworkError(msg) => (format!("Network error: {}", msg), None),
+            RpcError::TransactionRejected(msg, data) => {
+                (format!("Transaction rejected: {}", msg), Some(data))
+            }
+            RpcError::RateLimited(msg, retry_after_ms) => (
+                format!("Rate limited: {}", msg),
+                Some(serde_json::json!({ "retry_after_ms": retry_after_ms })),
+            ),
+            RpcError::Unauthorized(msg) => (format!("Unauthorized: {}", msg), None),
+            RpcError::InvalidSignature(msg) => (format!("Invalid signature: {}", msg), None),
+            RpcError::WrongChain(msg) => (format!("Wrong chain: {}", msg), None),
+            RpcError::InvalidSequenceNumber(msg) => {
+                (format!("Invalid sequence number: {}", msg), None)
+            }
+            RpcError::InvalidGas(msg) => (format!("Invalid gas: {}", msg), None),
         };
 
-        ErrorObjectOwned::owned(code, message, None::<()>)
+        ErrorObjectOwned::owned(code, message, data)
+    }
+}
+
+impl From<MempoolRejection> for RpcError {
+    fn from(rejection: MempoolRejection) -> Self {
+        let data = serde_json::to_value(&rejection).unwrap_or(serde_json::Value::Null);
+        RpcError::TransactionRejected(rejection.to_string(), data)
     }
 }
 
@@ -0,0 +1,246 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::{EthLog, EthRpcApiServer, EthTransactionReceipt, SubscriptionEvent};
+use crate::error::{RpcError, RpcResult, to_rpc_result};
+use crate::header_chain::parse_hash;
+use crate::server::{NodeState, pool_raw_transaction};
+use crate::state_bus::{NodeStateBus, NodeStateEvent};
+use jsonrpsee::core::async_trait;
+use kanari_db::RoochDB;
+use kanari_mempool::TxPool;
+use kanari_types::kari_coin::KARI;
+use kanari_types::transaction::SignedTransaction;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::u256::U256;
+use moveos_types::h256::sha2_256_of;
+use moveos_types::state::MoveStructType;
+use std::sync::Arc;
+use tokio::sync::{RwLock, broadcast};
+use tracing::info;
+
+/// Hex quantity as used by the Ethereum JSON-RPC API, without leading zeros
+fn quantity(value: impl Into<u128>) -> String {
+    format!("0x{:x}", value.into())
+}
+
+fn hex_data(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Accept Kanari addresses and 20 byte Ethereum addresses, which are zero padded
+fn parse_address(address: &str) -> RpcResult<AccountAddress> {
+    AccountAddress::from_hex_literal(address)
+        .map_err(|e| RpcError::InvalidParams(format!("Invalid address: {}", e)).into())
+}
+
+/// Whether bytes that are not a Kanari transaction start like an Ethereum one: an
+/// EIP-2718 type byte or an RLP list
+fn looks_like_ethereum_transaction(bytes: &[u8]) -> bool {
+    matches!(bytes.first(), Some(0x01..=0x04 | 0xc0..=0xff))
+}
+
+/// The `eth` namespace, backed by the same node state, pool and database as `kanari`
+pub struct EthRpcImpl {
+    node_state: Arc<RwLock<NodeState>>,
+    tx_pool: Arc<RwLock<TxPool>>,
+    db: Option<Arc<RoochDB>>,
+    events: Option<broadcast::Sender<SubscriptionEvent>>,
+    state_bus: Option<NodeStateBus>,
+}
+
+impl EthRpcImpl {
+    pub fn new(
+        node_state: Arc<RwLock<NodeState>>,
+        tx_pool: Arc<RwLock<TxPool>>,
+        db: Option<Arc<RoochDB>>,
+    ) -> Self {
+        Self {
+            node_state,
+            tx_pool,
+            db,
+            events: None,
+            state_bus: None,
+        }
+    }
+
+    /// Publish accepted transactions to WebSocket subscribers
+    pub fn with_events(mut self, events: broadcast::Sender<SubscriptionEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Report accepted transactions to the node state bus instead
+    pub fn with_state_bus(mut self, state_bus: NodeStateBus) -> Self {
+        self.state_bus = Some(state_bus);
+        self
+    }
+
+    fn db(&self) -> RpcResult<&Arc<RoochDB>> {
+        Ok(self
+            .db
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Database is not available".to_string()))?)
+    }
+}
+
+#[async_trait]
+impl EthRpcApiServer for EthRpcImpl {
+    async fn block_number(&self) -> RpcResult<String> {
+        Ok(quantity(self.node_state.read().await.block_height))
+    }
+
+    async fn chain_id(&self) -> RpcResult<String> {
+        Ok(quantity(self.node_state.read().await.chain_id))
+    }
+
+    async fn get_balance(&self, address: String, _block: Option<String>) -> RpcResult<String> {
+        let account = parse_address(&address)?;
+        let balance = to_rpc_result(self.db()?.get_coin_balance(account, &KARI::struct_tag()))?;
+        Ok(format!("0x{:x}", balance.unwrap_or_else(U256::zero)))
+    }
+
+    async fn send_raw_transaction(&self, raw_tx: String) -> RpcResult<String> {
+        let bytes = hex::decode(raw_tx.strip_prefix("0x").unwrap_or(&raw_tx))
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid hex: {}", e)))?;
+        if SignedTransaction::decode(&bytes).is_err() && looks_like_ethereum_transaction(&bytes) {
+            return Err(RpcError::InvalidParams(
+                "Ethereum encoded transactions are not supported, submit a BCS encoded Kanari transaction"
+                    .to_string(),
+            )
+            .into());
+        }
+        let (hash, summary) =
+            pool_raw_transaction(&self.node_state, &self.tx_pool, self.db.as_deref(), &raw_tx)
+                .await?;
+        if let Some(state_bus) = &self.state_bus {
+            state_bus.publish(NodeStateEvent::TransactionAdmitted(summary));
+        } else if let Some(events) = &self.events {
+            let _ = events.send(SubscriptionEvent::NewTransaction(summary));
+        }
+        let tx_hash = hex_data(hash.as_bytes());
+        info!(
+            "Transaction submitted through eth_sendRawTransaction: {}",
+            tx_hash
+        );
+        Ok(tx_hash)
+    }
+
+    async fn get_transaction_receipt(
+        &self,
+        tx_hash: String,
+    ) -> RpcResult<Option<EthTransactionReceipt>> {
+        let db = self.db()?;
+        let hash = parse_hash("Transaction hash", &tx_hash)
+            .map_err(|e| RpcError::InvalidParams(e.to_string()))?;
+        let Some((block_number, index)) = to_rpc_result(db.get_transaction_location(&hash))? else {
+            return Ok(None);
+        };
+        let block = to_rpc_result(db.get_block(block_number))?
+            .ok_or_else(|| RpcError::BlockNotFound(format!("#{}", block_number)))?;
+        let transactions = to_rpc_result(db.get_block_transactions(block_number))?;
+        let tx = transactions.get(index as usize).ok_or_else(|| {
+            RpcError::InternalError(format!(
+                "Transaction {} missing from block #{}",
+                tx_hash, block_number
+            ))
+        })?;
+        let block_hash = hex_data(block.hash().as_bytes());
+        let logs = to_rpc_result(db.get_block_events(block_number))?
+            .into_iter()
+            .filter(|event| event.tx_hash == hash)
+            .map(|event| EthLog {
+                address: event.address.to_hex_literal(),
+                topics: vec![hex_data(
+                    sha2_256_of(event.event_type.as_bytes()).as_bytes(),
+                )],
+                data: hex_data(&event.data),
+                block_number: quantity(block_number),
+                block_hash: block_hash.clone(),
+                transaction_hash: hex_data(hash.as_bytes()),
+                transaction_index: quantity(index),
+                log_index: quantity(event.event_index),
+                removed: false,
+            })
+            .collect();
+        // Receipts of blocks saved before receipts were recorded, or pruned ones, fall
+        // back to a successful transaction without gas
+        let (gas_used, status) = match to_rpc_result(db.get_transaction_receipt(&hash))? {
+            Some(receipt) => (receipt.gas_used, receipt.status.is_success()),
+            None => (0, true),
+        };
+        let logs_bloom = to_rpc_result(db.get_block_bloom(block_number))?
+            .map(|bloom| hex_data(bloom.as_bytes()))
+            .unwrap_or_else(|| hex_data(&[0u8; kanari_types::bloom::BLOOM_BYTE_LENGTH]));
+
+        Ok(Some(EthTransactionReceipt {
+            transaction_hash: hex_data(hash.as_bytes()),
+            transaction_index: quantity(index),
+            block_hash,
+            block_number: quantity(block_number),
+            from: tx.tx.sender.to_hex_literal(),
+            to: tx.tx.recipient.map(|recipient| recipient.to_hex_literal()),
+            // Gas is not metered yet, as in kanari block info
+            cumulative_gas_used: quantity(0u128),
+            gas_used: quantity(gas_used),
+            effective_gas_price: quantity(tx.tx.gas_price),
+            contract_address: None,
+            logs,
+            logs_bloom,
+            transaction_type: quantity(0u128),
+            status: quantity(status as u128),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fastcrypto::secp256k1::Secp256k1KeyPair;
+    use fastcrypto::traits::KeyPair;
+    use kanari_types::transaction::KanariTransaction;
+    use moveos_types::h256::H256;
+
+    #[test]
+    fn test_ethereum_encodings() {
+        assert_eq!(quantity(0u128), "0x0");
+        assert_eq!(quantity(255u64), "0xff");
+        assert_eq!(
+            parse_address("0x00000000000000000000000000000000000000ff").unwrap(),
+            AccountAddress::from_hex_literal("0xff").unwrap()
+        );
+        // An EIP-1559 transaction and a short legacy RLP payload
+        assert!(looks_like_ethereum_transaction(&[0x02, 0xf8, 0x6f]));
+        assert!(looks_like_ethereum_transaction(&[0xf8, 0x6c]));
+        assert!(!looks_like_ethereum_transaction(&[0u8; 64]));
+    }
+
+    #[tokio::test]
+    async fn test_send_raw_transaction_rejects_another_chain() {
+        let node_state = NodeState::default();
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let tx = SignedTransaction::sign(
+            KanariTransaction {
+                sender: AccountAddress::ONE,
+                sequence_number: 0,
+                chain_id: node_state.chain_id + 1,
+                genesis_hash: H256::zero(),
+                recipient: None,
+                amount: 0,
+                gas_limit: 21_000,
+                gas_price: 1,
+                data: vec![],
+                access_list: None,
+            },
+            &key_pair,
+        );
+        let tx_pool = Arc::new(RwLock::new(TxPool::default()));
+        let rpc = EthRpcImpl::new(Arc::new(RwLock::new(node_state)), tx_pool.clone(), None);
+        let err = rpc
+            .send_raw_transaction(hex_data(&tx.encode()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), RpcError::WrongChain(String::new()).code());
+        assert!(tx_pool.read().await.is_empty());
+    }
+}
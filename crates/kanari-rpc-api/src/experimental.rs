@@ -0,0 +1,196 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::{
+    BalanceChangeInfo, ExperimentalMethodInfo, ExperimentalRpcApiServer, StateDiffInfo,
+};
+use crate::error::{RpcError, RpcResult, to_rpc_result};
+use jsonrpsee::core::async_trait;
+use kanari_db::RoochDB;
+use kanari_db::state_diff::diff_state_roots;
+use moveos_types::state::ObjectState;
+use std::sync::Arc;
+
+/// Methods of the `experimental` namespace with the version of their schema, as name,
+/// schema version and description. Bump the version of a method whenever its request
+/// or response changes incompatibly.
+pub const EXPERIMENTAL_METHODS: &[(&str, u32, &str)] = &[
+    (
+        "experimental_listMethods",
+        1,
+        "List the experimental methods with their schema versions",
+    ),
+    (
+        "experimental_getStateDiff",
+        1,
+        "Compare the states after two blocks",
+    ),
+];
+
+/// Object ids listed per kind in a state diff
+const MAX_STATE_DIFF_OBJECTS: usize = 1_000;
+
+/// Serves the opt-in `experimental` namespace
+pub struct ExperimentalRpcImpl {
+    db: Option<Arc<RoochDB>>,
+}
+
+impl ExperimentalRpcImpl {
+    pub fn new(db: Option<Arc<RoochDB>>) -> Self {
+        Self { db }
+    }
+
+    fn db(&self) -> RpcResult<&Arc<RoochDB>> {
+        self.db
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Database is not available".to_string()).into())
+    }
+}
+
+#[async_trait]
+impl ExperimentalRpcApiServer for ExperimentalRpcImpl {
+    async fn list_methods(&self) -> RpcResult<Vec<ExperimentalMethodInfo>> {
+        Ok(EXPERIMENTAL_METHODS
+            .iter()
+            .map(
+                |(name, schema_version, description)| ExperimentalMethodInfo {
+                    name: name.to_string(),
+                    schema_version: *schema_version,
+                    description: description.to_string(),
+                },
+            )
+            .collect())
+    }
+
+    async fn get_state_diff(&self, from_block: u128, to_block: u128) -> RpcResult<StateDiffInfo> {
+        let db = self.db()?;
+        let state_root = |block_number: u128| -> RpcResult<_> {
+            Ok(to_rpc_result(db.get_block(block_number))?
+                .ok_or_else(|| RpcError::BlockNotFound(format!("#{}", block_number)))?
+                .state_root)
+        };
+        let from_root = state_root(from_block)?;
+        let to_root = state_root(to_block)?;
+        let diff = to_rpc_result(diff_state_roots(
+            &db.moveos_store,
+            from_root,
+            &db.moveos_store,
+            to_root,
+        ))?;
+
+        let ids = |states: Vec<ObjectState>| {
+            states
+                .into_iter()
+                .take(MAX_STATE_DIFF_OBJECTS)
+                .map(|state| state.metadata.id.to_string())
+                .collect()
+        };
+        Ok(StateDiffInfo {
+            from_block,
+            from_state_root: format!("0x{}", hex::encode(from_root.as_bytes())),
+            to_block,
+            to_state_root: format!("0x{}", hex::encode(to_root.as_bytes())),
+            added_accounts: diff
+                .added_accounts()
+                .iter()
+                .map(|account| account.to_hex_literal())
+                .collect(),
+            removed_accounts: diff
+                .removed_accounts()
+                .iter()
+                .map(|account| account.to_hex_literal())
+                .collect(),
+            balance_changes: diff
+                .balance_changes()
+                .into_iter()
+                .map(|change| BalanceChangeInfo {
+                    owner: change.owner.to_hex_literal(),
+                    coin_type: change.coin_type,
+                    before: change.before.map(|balance| balance.to_string()),
+                    after: change.after.map(|balance| balance.to_string()),
+                })
+                .collect(),
+            added_objects: ids(diff.added),
+            removed_objects: ids(diff.removed),
+            changed_objects: ids(diff.changed.into_iter().map(|(_, state)| state).collect()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ExperimentalRpcApiOpenRpc;
+    use kanari_config::KanariOpt;
+    use kanari_types::block::Block;
+    use kanari_types::reward::RewardPayment;
+    use kanari_types::system_transaction::SystemTransaction;
+    use move_core_types::account_address::AccountAddress;
+    use moveos_types::h256::H256;
+    use rooch_open_rpc::Project;
+
+    #[test]
+    fn test_listed_methods_match_the_namespace() {
+        let mut project = Project::new("1.0.0", "", "", "", "", "", "", "");
+        project.add_module(ExperimentalRpcApiOpenRpc::module_doc());
+        let doc = serde_json::to_value(project).unwrap();
+        let mut served: Vec<&str> = doc["methods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|method| method["name"].as_str().unwrap())
+            .collect();
+        served.sort_unstable();
+        let mut listed: Vec<&str> = EXPERIMENTAL_METHODS
+            .iter()
+            .map(|(name, _, _)| *name)
+            .collect();
+        listed.sort_unstable();
+        assert_eq!(served, listed);
+    }
+
+    #[tokio::test]
+    async fn test_state_diff_between_executed_blocks() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &prometheus::Registry::new()).unwrap());
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let reward = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: alice,
+                recipient: alice,
+                amount: 250,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 2);
+        for (block_number, transactions) in [(1, vec![]), (2, vec![reward])] {
+            let execution = db.execute_block(block_number, &transactions).unwrap();
+            let block = Block::new(
+                block_number,
+                transactions.len() as u64,
+                H256::zero(),
+                H256::zero(),
+                H256::zero(),
+                execution.root.state_root(),
+            );
+            db.save_block(&block, 1_700_000_000).unwrap();
+            db.commit_block_state(block_number, &execution.root)
+                .unwrap();
+        }
+
+        let diff = ExperimentalRpcImpl::new(Some(db.clone()))
+            .get_state_diff(1, 2)
+            .await
+            .unwrap();
+        let to_root = db.get_block(2).unwrap().unwrap().state_root;
+        assert_eq!(
+            diff.to_state_root,
+            format!("0x{}", hex::encode(to_root.as_bytes()))
+        );
+        assert_eq!(diff.added_objects.len(), 1);
+        assert_eq!(diff.balance_changes.len(), 1);
+        assert_eq!(diff.balance_changes[0].owner, alice.to_hex_literal());
+        assert_eq!(diff.balance_changes[0].after.as_deref(), Some("250"));
+    }
+}
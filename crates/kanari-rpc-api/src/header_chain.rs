@@ -0,0 +1,142 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::HeaderInfo;
+use anyhow::{Result, bail};
+use kanari_types::block::Block;
+use moveos_types::h256::H256;
+
+/// Maximum number of headers returned by a single `kanari_getHeaderChain` call
+pub const MAX_HEADER_CHAIN_RANGE: u128 = 1_000;
+
+fn format_hash(hash: &H256) -> String {
+    format!("0x{}", hex::encode(hash.as_bytes()))
+}
+
+pub(crate) fn parse_hash(field: &str, value: &str) -> Result<H256> {
+    let bytes = hex::decode(value.strip_prefix("0x").unwrap_or(value))?;
+    if bytes.len() != H256::len_bytes() {
+        bail!("{} {} is not a 32 byte hash", field, value);
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+impl HeaderInfo {
+    pub fn new(block: &Block, parent_hash: H256) -> Self {
+        Self {
+            number: block.block_number,
+            hash: format_hash(&block.hash()),
+            parent_hash: format_hash(&parent_hash),
+            batch_size: block.batch_size,
+            batch_hash: format_hash(&block.batch_hash),
+            prev_tx_accumulator_root: format_hash(&block.prev_tx_accumulator_root),
+            tx_accumulator_root: format_hash(&block.tx_accumulator_root),
+            state_root: format_hash(&block.state_root),
+            finality_signatures: None,
+        }
+    }
+
+    /// Rebuild the block header the hash commits to
+    pub fn to_block(&self) -> Result<Block> {
+        Ok(Block::new(
+            self.number,
+            self.batch_size,
+            parse_hash("batch_hash", &self.batch_hash)?,
+            parse_hash("prev_tx_accumulator_root", &self.prev_tx_accumulator_root)?,
+            parse_hash("tx_accumulator_root", &self.tx_accumulator_root)?,
+            parse_hash("state_root", &self.state_root)?,
+        ))
+    }
+}
+
+/// Check a header chain served by an untrusted endpoint: every hash must match its
+/// header fields and every header must link to the one before it through the
+/// accumulator root its hash commits to. Returns the hash of the last header, to be
+/// pinned against a trusted source.
+pub fn verify_header_chain(headers: &[HeaderInfo]) -> Result<H256> {
+    let mut previous: Option<(Block, H256)> = None;
+    for header in headers {
+        let block = header.to_block()?;
+        let hash = block.hash();
+        if parse_hash("hash", &header.hash)? != hash {
+            bail!("Header #{} hash does not match its fields", header.number);
+        }
+        if let Some((parent, parent_hash)) = &previous {
+            if header.number != parent.block_number + 1 {
+                bail!(
+                    "Header #{} does not follow #{}",
+                    header.number,
+                    parent.block_number
+                );
+            }
+            if !block.extends(parent)
+                || parse_hash("parent_hash", &header.parent_hash)? != *parent_hash
+            {
+                bail!(
+                    "Header #{} does not link to #{}",
+                    header.number,
+                    parent.block_number
+                );
+            }
+        }
+        previous = Some((block, hash));
+    }
+    previous
+        .map(|(_, hash)| hash)
+        .ok_or_else(|| anyhow::anyhow!("Empty header chain"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(length: u128) -> Vec<HeaderInfo> {
+        let mut parent_hash = H256::zero();
+        let mut parent_root = H256::zero();
+        (1..=length)
+            .map(|number| {
+                let block = Block::new(
+                    number,
+                    0,
+                    H256::random(),
+                    parent_root,
+                    H256::random(),
+                    H256::random(),
+                );
+                let header = HeaderInfo::new(&block, parent_hash);
+                parent_hash = block.hash();
+                parent_root = block.tx_accumulator_root;
+                header
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_header_chain() {
+        let headers = chain(4);
+        assert_eq!(
+            format_hash(&verify_header_chain(&headers).unwrap()),
+            headers[3].hash
+        );
+
+        // A substituted state root breaks the header hash
+        let mut tampered = headers.clone();
+        tampered[2].state_root = format_hash(&H256::random());
+        assert!(verify_header_chain(&tampered).is_err());
+
+        // A header from another chain breaks the link
+        let mut spliced = headers.clone();
+        spliced[2] = chain(3).pop().unwrap();
+        assert!(verify_header_chain(&spliced).is_err());
+
+        // The served parent hash is not trusted: a spliced header claiming the right
+        // parent still does not commit to it
+        spliced[2].parent_hash = headers[1].hash.clone();
+        assert!(verify_header_chain(&spliced).is_err());
+
+        // A gap breaks continuity
+        let mut gapped = headers;
+        gapped.remove(1);
+        assert!(verify_header_chain(&gapped).is_err());
+    }
+}
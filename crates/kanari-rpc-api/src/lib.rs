@@ -8,12 +8,56 @@ pub use jsonrpsee;
 pub use kanari_types::*;
 
 pub mod api;
+pub mod api_keys;
+pub mod auth;
+pub mod block_cache;
+pub mod client;
+pub mod cors;
+pub mod discover;
+pub mod dry_run;
 pub mod error;
+pub mod eth;
+pub mod experimental;
+pub mod header_chain;
+pub mod metrics;
+pub mod module_abi;
+pub mod plugin;
+pub mod rate_limit;
+pub mod request_id;
+pub mod response_cache;
+#[cfg(feature = "rest")]
+pub mod rest;
 pub mod server;
+pub mod state_bus;
+pub mod state_page;
+pub mod subscription;
+pub mod trace;
 
 pub use api::*;
+pub use api_keys::*;
+pub use auth::*;
+pub use block_cache::*;
+pub use client::*;
+pub use cors::*;
+pub use discover::*;
+pub use dry_run::*;
 pub use error::*;
+pub use eth::*;
+pub use experimental::*;
+pub use header_chain::*;
+pub use metrics::*;
+pub use module_abi::*;
+pub use plugin::*;
+pub use rate_limit::*;
+pub use request_id::*;
+pub use response_cache::*;
+#[cfg(feature = "rest")]
+pub use rest::*;
 pub use server::*;
+pub use state_bus::*;
+pub use state_page::*;
+pub use subscription::*;
+pub use trace::*;
 
 /// RPC API version
 pub const RPC_API_VERSION: &str = "1.0.0";
@@ -0,0 +1,190 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use jsonrpsee::{
+    MethodResponse,
+    server::{
+        HttpBody, HttpResponse, ServerHandle, middleware::rpc::RpcServiceT,
+        serve_with_graceful_shutdown, stop_channel,
+    },
+    types::Request,
+};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::{
+    convert::Infallible,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+/// Label of calls to methods the server does not have, which would otherwise add a
+/// time series per made-up method name
+const UNKNOWN_METHOD_LABEL: &str = "unknown";
+
+/// JSON-RPC error code of calls to methods the server does not have
+const METHOD_NOT_FOUND_CODE: i32 = -32601;
+
+/// Request counts and latencies of the public RPC listener, per method
+pub struct RpcMetrics {
+    requests: IntCounterVec,
+    latency: HistogramVec,
+}
+
+impl RpcMetrics {
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let requests = IntCounterVec::new(
+            Opts::new(
+                "kanari_rpc_requests_total",
+                "Number of JSON-RPC calls by method and outcome",
+            ),
+            &["method", "status"],
+        )?;
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "kanari_rpc_request_duration_seconds",
+                "Time to answer a JSON-RPC call by method",
+            ),
+            &["method"],
+        )?;
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(latency.clone()))?;
+        Ok(Self { requests, latency })
+    }
+
+    fn observe(&self, method: &str, response: &MethodResponse, elapsed: Duration) {
+        let method = match response.as_error_code() {
+            Some(METHOD_NOT_FOUND_CODE) => UNKNOWN_METHOD_LABEL,
+            _ => method,
+        };
+        let status = if response.is_success() { "ok" } else { "error" };
+        self.requests.with_label_values(&[method, status]).inc();
+        self.latency
+            .with_label_values(&[method])
+            .observe(elapsed.as_secs_f64());
+    }
+}
+
+/// RPC middleware recording every call, including those rejected by the inner layers.
+/// Without metrics it passes calls through.
+#[derive(Clone)]
+pub struct MetricsService<S> {
+    service: S,
+    metrics: Option<Arc<RpcMetrics>>,
+}
+
+impl<S> MetricsService<S> {
+    pub fn new(service: S, metrics: Option<Arc<RpcMetrics>>) -> Self {
+        Self { service, metrics }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for MetricsService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+    S::Future: Send + 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let Some(metrics) = self.metrics.clone() else {
+            return Box::pin(self.service.call(request));
+        };
+        let method = request.method_name().to_string();
+        let started = Instant::now();
+        let response = self.service.call(request);
+        Box::pin(async move {
+            let response = response.await;
+            metrics.observe(&method, &response, started.elapsed());
+            response
+        })
+    }
+}
+
+fn metrics_response(registry: &Registry, path: &str) -> HttpResponse {
+    if path != "/metrics" {
+        return HttpResponse::builder()
+            .status(404)
+            .body(HttpBody::from("Not found, metrics are served at /metrics"))
+            .expect("valid response");
+    }
+    let encoder = TextEncoder::new();
+    match encoder.encode_to_string(&registry.gather()) {
+        Ok(text) => HttpResponse::builder()
+            .header("content-type", encoder.format_type())
+            .body(HttpBody::from(text))
+            .expect("valid response"),
+        Err(e) => HttpResponse::builder()
+            .status(500)
+            .body(HttpBody::from(format!("Failed to encode metrics: {}", e)))
+            .expect("valid response"),
+    }
+}
+
+/// Serve the metrics of `registry` in the Prometheus text format at `/metrics`
+pub async fn start_metrics_server(address: SocketAddr, registry: Registry) -> Result<ServerHandle> {
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    let (stop_handle, server_handle) = stop_channel();
+    tokio::spawn(async move {
+        loop {
+            let socket = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((socket, _)) => socket,
+                    Err(e) => {
+                        warn!("Failed to accept metrics connection: {}", e);
+                        continue;
+                    }
+                },
+                _ = stop_handle.clone().shutdown() => break,
+            };
+            let registry = registry.clone();
+            let service =
+                tower::service_fn(move |request: hyper::Request<hyper::body::Incoming>| {
+                    let response = metrics_response(&registry, request.uri().path());
+                    async move { Ok::<_, Infallible>(response) }
+                });
+            tokio::spawn(serve_with_graceful_shutdown(
+                socket,
+                service,
+                stop_handle.clone().shutdown(),
+            ));
+        }
+    });
+    Ok(server_handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::types::{ErrorObjectOwned, Id};
+
+    #[test]
+    fn test_rpc_metrics_collapse_unknown_methods() {
+        let registry = Registry::new();
+        let metrics = RpcMetrics::new(&registry).unwrap();
+        let not_found = MethodResponse::error(
+            Id::Number(1),
+            ErrorObjectOwned::owned::<()>(METHOD_NOT_FOUND_CODE, "Method not found", None),
+        );
+        metrics.observe("made_upMethod", &not_found, Duration::from_millis(1));
+        metrics.observe("other_madeUp", &not_found, Duration::from_millis(1));
+        assert_eq!(
+            metrics
+                .requests
+                .with_label_values(&[UNKNOWN_METHOD_LABEL, "error"])
+                .get(),
+            2
+        );
+
+        let text = TextEncoder::new()
+            .encode_to_string(&registry.gather())
+            .unwrap();
+        assert!(text.contains("kanari_rpc_request_duration_seconds_count{method=\"unknown\"} 2"));
+        assert!(!text.contains("made_upMethod"));
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::{
+    MoveFieldAbi, MoveFunctionAbi, MoveModuleAbi, MoveStructAbi, MoveTypeParameterAbi,
+};
+use anyhow::{Result, anyhow};
+use move_binary_format::CompiledModule;
+use move_binary_format::file_format::{Ability, AbilitySet, Visibility};
+use move_binary_format::normalized;
+
+/// The ABI of a module's bytecode: its friends, structs, and the functions callable
+/// from outside the module, i.e. public, friend and entry functions
+pub fn module_abi(bytecode: &[u8]) -> Result<MoveModuleAbi> {
+    let module = CompiledModule::deserialize(bytecode)
+        .map_err(|e| anyhow!("Invalid module bytecode: {:?}", e))?;
+    let module = normalized::Module::new(&module);
+    Ok(MoveModuleAbi {
+        address: module.address.to_hex_literal(),
+        name: module.name.to_string(),
+        friends: module
+            .friends
+            .iter()
+            .map(|friend| friend.short_str_lossless())
+            .collect(),
+        structs: module
+            .structs
+            .iter()
+            .map(|(name, s)| MoveStructAbi {
+                name: name.to_string(),
+                abilities: abilities(s.abilities),
+                type_parameters: s
+                    .type_parameters
+                    .iter()
+                    .map(|param| MoveTypeParameterAbi {
+                        constraints: abilities(param.constraints),
+                        is_phantom: param.is_phantom,
+                    })
+                    .collect(),
+                fields: s
+                    .fields
+                    .iter()
+                    .map(|field| MoveFieldAbi {
+                        name: field.name.to_string(),
+                        move_type: field.type_.to_string(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        functions: module
+            .exposed_functions
+            .iter()
+            .map(|(name, f)| MoveFunctionAbi {
+                name: name.to_string(),
+                visibility: match f.visibility {
+                    Visibility::Public => "public",
+                    Visibility::Friend => "friend",
+                    Visibility::Private => "private",
+                }
+                .to_string(),
+                is_entry: f.is_entry,
+                type_parameters: f
+                    .type_parameters
+                    .iter()
+                    .map(|constraints| MoveTypeParameterAbi {
+                        constraints: abilities(*constraints),
+                        is_phantom: false,
+                    })
+                    .collect(),
+                parameters: f.parameters.iter().map(|ty| ty.to_string()).collect(),
+                return_types: f.return_.iter().map(|ty| ty.to_string()).collect(),
+            })
+            .collect(),
+    })
+}
+
+fn abilities(set: AbilitySet) -> Vec<String> {
+    set.into_iter()
+        .map(|ability| {
+            match ability {
+                Ability::Copy => "copy",
+                Ability::Drop => "drop",
+                Ability::Store => "store",
+                Ability::Key => "key",
+            }
+            .to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::basic_test_module;
+
+    #[test]
+    fn test_module_abi_lists_structs_and_exposed_functions() {
+        let mut bytecode = vec![];
+        basic_test_module().serialize(&mut bytecode).unwrap();
+
+        let abi = module_abi(&bytecode).unwrap();
+        assert_eq!(abi.structs.len(), 1);
+        assert_eq!(abi.structs[0].name, "Bar");
+        assert_eq!(abi.structs[0].fields[0].name, "x");
+        assert_eq!(abi.structs[0].fields[0].move_type, "u64");
+        // `foo` is private and not an entry function
+        assert!(abi.functions.is_empty());
+
+        assert!(module_abi(&bytecode[..bytecode.len() / 2]).is_err());
+    }
+}
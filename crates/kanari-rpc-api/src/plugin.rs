@@ -0,0 +1,400 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+//! External plugins: local processes such as indexers and bots connect to a Unix domain
+//! socket, register with a token, then receive the node's event stream and submit
+//! transactions. Messages are JSON objects, one per line, tagged with `type`:
+//!
+//! - plugin: `{"type":"register","name":"indexer","token":"...","events":["blocks"]}`
+//! - node: `{"type":"registered","protocol_version":1}`
+//! - node: `{"type":"event","event":{"NewBlock":{...}}}`
+//! - plugin: `{"type":"submit_transaction","id":1,"raw_tx":"0x..."}`
+//! - node: `{"type":"transaction_submitted","id":1,"tx_hash":"0x..."}`
+//! - node: `{"type":"error","id":1,"code":-32006,"message":"..."}`
+
+use crate::api::{KanariRpcApiServer, SubscriptionEvent};
+use crate::auth::constant_time_eq;
+use crate::error::RpcError;
+use crate::rate_limit::{RateLimit, RateLimitConfig, RateLimiter};
+use crate::server::KanariRpcImpl;
+use anyhow::Result;
+use jsonrpsee::server::{ServerHandle, stop_channel};
+use jsonrpsee::types::ErrorObjectOwned;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+/// Version of the line protocol, reported on registration
+pub const PLUGIN_PROTOCOL_VERSION: u32 = 1;
+
+/// A plugin must register within this time after connecting
+pub const PLUGIN_REGISTER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest accepted line, large enough for any transaction the pool accepts
+const MAX_PLUGIN_LINE_BYTES: usize = 1024 * 1024;
+
+/// Rate limit bucket key: plugins are local, each plugin has its own limiter
+const PLUGIN_RATE_LIMIT_IP: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+/// A plugin allowed to register
+#[derive(Debug, Clone)]
+pub struct PluginConfig {
+    pub name: String,
+    pub token: String,
+    /// Budget for transaction submissions, unlimited if None
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// The plugin socket and the plugins allowed on it
+#[derive(Debug, Clone)]
+pub struct PluginServerConfig {
+    pub path: PathBuf,
+    pub plugins: Vec<PluginConfig>,
+}
+
+/// Event streams a plugin can select at registration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginEventKind {
+    Blocks,
+    Transactions,
+    Peers,
+    NodeStatus,
+}
+
+impl PluginEventKind {
+    fn of(event: &SubscriptionEvent) -> Self {
+        match event {
+            SubscriptionEvent::NewBlock(_) => PluginEventKind::Blocks,
+            SubscriptionEvent::NewTransaction(_) => PluginEventKind::Transactions,
+            SubscriptionEvent::PeerConnected(_) | SubscriptionEvent::PeerDisconnected(_) => {
+                PluginEventKind::Peers
+            }
+            SubscriptionEvent::NodeStatus(_) => PluginEventKind::NodeStatus,
+        }
+    }
+}
+
+/// A message from a plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PluginRequest {
+    /// Must be the first message. Without `events` every stream is delivered.
+    Register {
+        name: String,
+        token: String,
+        #[serde(default)]
+        events: Option<Vec<PluginEventKind>>,
+    },
+    /// A hex encoded, BCS serialized signed transaction for the pool
+    SubmitTransaction { id: u64, raw_tx: String },
+}
+
+/// A message to a plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PluginMessage {
+    Registered {
+        protocol_version: u32,
+    },
+    Event {
+        event: SubscriptionEvent,
+    },
+    /// The plugin read too slowly and `missed` events were dropped
+    Lagged {
+        missed: u64,
+    },
+    TransactionSubmitted {
+        id: u64,
+        tx_hash: String,
+    },
+    /// `id` is the request the error answers, None for protocol errors
+    Error {
+        id: Option<u64>,
+        code: i32,
+        message: String,
+    },
+}
+
+impl PluginMessage {
+    fn error(id: Option<u64>, error: impl Into<ErrorObjectOwned>) -> Self {
+        let error = error.into();
+        PluginMessage::Error {
+            id,
+            code: error.code(),
+            message: error.message().to_string(),
+        }
+    }
+}
+
+/// Registered plugins with their own submission rate limiters
+struct PluginRegistry {
+    plugins: Vec<(PluginConfig, Option<Arc<RateLimiter>>)>,
+}
+
+impl PluginRegistry {
+    fn new(plugins: Vec<PluginConfig>) -> Self {
+        let plugins = plugins
+            .into_iter()
+            .map(|plugin| {
+                let limiter = plugin.rate_limit.map(|limit| {
+                    Arc::new(RateLimiter::new(RateLimitConfig {
+                        per_ip: Some(limit),
+                        ..Default::default()
+                    }))
+                });
+                (plugin, limiter)
+            })
+            .collect();
+        Self { plugins }
+    }
+
+    /// The rate limiter of the plugin if the token matches, an error otherwise
+    fn authenticate(&self, name: &str, token: &str) -> Result<Option<Arc<RateLimiter>>, RpcError> {
+        self.plugins
+            .iter()
+            .find(|(plugin, _)| {
+                plugin.name == name && constant_time_eq(plugin.token.as_bytes(), token.as_bytes())
+            })
+            .map(|(_, limiter)| limiter.clone())
+            .ok_or_else(|| {
+                RpcError::Unauthorized(format!("Unknown plugin {} or wrong token", name))
+            })
+    }
+}
+
+/// Serve plugins on the configured socket, only accessible to the node's user
+#[cfg(unix)]
+pub fn start_plugin_server(
+    config: PluginServerConfig,
+    kanari: KanariRpcImpl,
+    events: broadcast::Sender<SubscriptionEvent>,
+) -> Result<ServerHandle> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = config.path;
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+            anyhow::bail!(
+                "Plugin socket {} is in use by another process",
+                path.display()
+            );
+        }
+        std::fs::remove_file(&path)?;
+    }
+    let listener = tokio::net::UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+    let registry = Arc::new(PluginRegistry::new(config.plugins));
+    let kanari = Arc::new(kanari);
+    let (stop_handle, server_handle) = stop_channel();
+    tokio::spawn(async move {
+        loop {
+            let socket = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((socket, _)) => socket,
+                    Err(e) => {
+                        warn!("Failed to accept plugin connection: {}", e);
+                        continue;
+                    }
+                },
+                _ = stop_handle.clone().shutdown() => break,
+            };
+            let connection =
+                serve_plugin(socket, registry.clone(), kanari.clone(), events.subscribe());
+            let stopped = stop_handle.clone().shutdown();
+            tokio::spawn(async move {
+                tokio::select! {
+                    result = connection => {
+                        if let Err(e) = result {
+                            debug!("Plugin connection closed: {}", e);
+                        }
+                    }
+                    _ = stopped => {}
+                }
+            });
+        }
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!("Failed to remove plugin socket {}: {}", path.display(), e);
+        }
+    });
+    Ok(server_handle)
+}
+
+#[cfg(not(unix))]
+pub fn start_plugin_server(
+    config: PluginServerConfig,
+    _kanari: KanariRpcImpl,
+    _events: broadcast::Sender<SubscriptionEvent>,
+) -> Result<ServerHandle> {
+    anyhow::bail!(
+        "Plugin socket {} is not supported, Unix domain sockets require a Unix platform",
+        config.path.display()
+    )
+}
+
+#[cfg(unix)]
+async fn write_message(
+    writer: &mut tokio::net::unix::OwnedWriteHalf,
+    message: &PluginMessage,
+) -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn serve_plugin(
+    socket: tokio::net::UnixStream,
+    registry: Arc<PluginRegistry>,
+    kanari: Arc<KanariRpcImpl>,
+    mut events: broadcast::Receiver<SubscriptionEvent>,
+) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    let (reader, mut writer) = socket.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let first = tokio::time::timeout(PLUGIN_REGISTER_TIMEOUT, lines.next_line())
+        .await
+        .map_err(|_| anyhow::anyhow!("Plugin did not register in time"))??;
+    let (name, selected, limiter) = match first.as_deref().map(serde_json::from_str) {
+        Some(Ok(PluginRequest::Register {
+            name,
+            token,
+            events: selected,
+        })) => match registry.authenticate(&name, &token) {
+            Ok(limiter) => (name, selected, limiter),
+            Err(e) => {
+                write_message(&mut writer, &PluginMessage::error(None, e)).await?;
+                anyhow::bail!("Plugin {} failed to authenticate", name);
+            }
+        },
+        _ => {
+            let error =
+                RpcError::InvalidParams("The first message must be a register request".to_string());
+            write_message(&mut writer, &PluginMessage::error(None, error)).await?;
+            anyhow::bail!("Plugin did not start with a register request");
+        }
+    };
+    info!("Plugin {} registered", name);
+    let registered = PluginMessage::Registered {
+        protocol_version: PLUGIN_PROTOCOL_VERSION,
+    };
+    write_message(&mut writer, &registered).await?;
+
+    loop {
+        let reply = tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    info!("Plugin {} disconnected", name);
+                    return Ok(());
+                };
+                if line.len() > MAX_PLUGIN_LINE_BYTES {
+                    anyhow::bail!("Plugin {} sent a message over {} bytes", name, MAX_PLUGIN_LINE_BYTES);
+                }
+                match serde_json::from_str::<PluginRequest>(&line) {
+                    Ok(PluginRequest::SubmitTransaction { id, raw_tx }) => {
+                        submit_transaction(&kanari, limiter.as_deref(), id, raw_tx).await
+                    }
+                    Ok(PluginRequest::Register { .. }) => PluginMessage::error(
+                        None,
+                        RpcError::InvalidParams("Plugin is already registered".to_string()),
+                    ),
+                    Err(e) => PluginMessage::error(
+                        None,
+                        RpcError::InvalidParams(format!("Invalid message: {}", e)),
+                    ),
+                }
+            }
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let wanted = selected
+                        .as_ref()
+                        .is_none_or(|kinds| kinds.contains(&PluginEventKind::of(&event)));
+                    if !wanted {
+                        continue;
+                    }
+                    PluginMessage::Event { event }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!("Plugin {} missed {} events", name, missed);
+                    PluginMessage::Lagged { missed }
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+        };
+        write_message(&mut writer, &reply).await?;
+    }
+}
+
+/// Submit a transaction to the pool through the same path as `kanari_sendRawTransaction`,
+/// subject to the plugin's rate limit
+async fn submit_transaction(
+    kanari: &KanariRpcImpl,
+    limiter: Option<&RateLimiter>,
+    id: u64,
+    raw_tx: String,
+) -> PluginMessage {
+    if let Some(limiter) = limiter
+        && let Err(e) = limiter.check(PLUGIN_RATE_LIMIT_IP, "plugin_submitTransaction")
+    {
+        return PluginMessage::error(Some(id), e);
+    }
+    match kanari.send_raw_transaction(raw_tx).await {
+        Ok(tx_hash) => PluginMessage::TransactionSubmitted { id, tx_hash },
+        Err(e) => PluginMessage::error(Some(id), e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_protocol_and_authentication() {
+        let request: PluginRequest = serde_json::from_str(
+            r#"{"type":"register","name":"indexer","token":"secret","events":["blocks","peers"]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            request,
+            PluginRequest::Register { events: Some(ref events), .. } if events.len() == 2
+        ));
+        let reply = serde_json::to_value(PluginMessage::TransactionSubmitted {
+            id: 1,
+            tx_hash: "0x01".to_string(),
+        })
+        .unwrap();
+        assert_eq!(reply["type"], "transaction_submitted");
+
+        let registry = PluginRegistry::new(vec![PluginConfig {
+            name: "indexer".to_string(),
+            token: "secret".to_string(),
+            rate_limit: Some(RateLimit {
+                requests_per_second: 1.0,
+                burst: 1,
+            }),
+        }]);
+        assert!(registry.authenticate("indexer", "wrong").is_err());
+        assert!(registry.authenticate("bot", "secret").is_err());
+        let limiter = registry.authenticate("indexer", "secret").unwrap().unwrap();
+        assert!(
+            limiter
+                .check(PLUGIN_RATE_LIMIT_IP, "plugin_submitTransaction")
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check(PLUGIN_RATE_LIMIT_IP, "plugin_submitTransaction")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::RpcError;
+use jsonrpsee::{
+    MethodResponse,
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObjectOwned, Request},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    future::Future,
+    net::IpAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Number of buckets kept before idle ones are dropped
+const MAX_TRACKED_BUCKETS: usize = 100_000;
+
+/// Token bucket parameters: `burst` requests at once, refilled at `requests_per_second`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    pub burst: u32,
+}
+
+/// Rate limits applied to the public RPC endpoint
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitConfig {
+    /// Budget shared by every request from one IP
+    pub per_ip: Option<RateLimit>,
+    /// Budgets for single methods from one IP, checked in addition to `per_ip`
+    pub per_method: HashMap<String, RateLimit>,
+}
+
+impl RateLimitConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.per_ip.is_some() || !self.per_method.is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    pub(crate) tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    pub(crate) fn full(limit: &RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            updated: now,
+        }
+    }
+
+    pub(crate) fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.requests_per_second).min(limit.burst as f64);
+        self.updated = now;
+    }
+
+    /// Time until a token is available, zero if one is available now
+    pub(crate) fn wait_time(&self, limit: &RateLimit) -> Duration {
+        if self.tokens >= 1.0 {
+            return Duration::ZERO;
+        }
+        if limit.requests_per_second <= 0.0 {
+            return Duration::MAX;
+        }
+        Duration::from_secs_f64((1.0 - self.tokens) / limit.requests_per_second)
+    }
+}
+
+/// Per-IP and per-method token buckets
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<(IpAddr, Option<String>), TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token for a call to `method` from `ip`. No token is taken from any bucket
+    /// unless every applicable bucket has one.
+    pub fn check(&self, ip: IpAddr, method: &str) -> Result<(), RpcError> {
+        self.check_at(ip, method, Instant::now())
+    }
+
+    fn check_at(&self, ip: IpAddr, method: &str, now: Instant) -> Result<(), RpcError> {
+        let limits: Vec<(Option<String>, &RateLimit)> = self
+            .config
+            .per_ip
+            .as_ref()
+            .map(|limit| (None, limit))
+            .into_iter()
+            .chain(
+                self.config
+                    .per_method
+                    .get(method)
+                    .map(|limit| (Some(method.to_string()), limit)),
+            )
+            .collect();
+        if limits.is_empty() {
+            return Ok(());
+        }
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= MAX_TRACKED_BUCKETS {
+            self.prune(&mut buckets, now);
+        }
+
+        let mut wait = Duration::ZERO;
+        for (key, limit) in &limits {
+            let bucket = buckets
+                .entry((ip, key.clone()))
+                .or_insert_with(|| TokenBucket::full(limit, now));
+            bucket.refill(limit, now);
+            wait = wait.max(bucket.wait_time(limit));
+        }
+        if !wait.is_zero() {
+            return Err(RpcError::RateLimited(
+                format!("too many requests for {} from {}", method, ip),
+                wait.as_millis().min(u64::MAX as u128) as u64,
+            ));
+        }
+        for (key, _) in limits {
+            if let Some(bucket) = buckets.get_mut(&(ip, key)) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop buckets that have refilled completely, they behave like new ones
+    fn prune(&self, buckets: &mut HashMap<(IpAddr, Option<String>), TokenBucket>, now: Instant) {
+        buckets.retain(|(_, method), bucket| {
+            let limit = match method {
+                Some(method) => self.config.per_method.get(method),
+                None => self.config.per_ip.as_ref(),
+            };
+            match limit {
+                Some(limit) => {
+                    bucket.refill(limit, now);
+                    bucket.tokens < limit.burst as f64
+                }
+                None => false,
+            }
+        });
+    }
+}
+
+/// RPC middleware rejecting calls from one connection once its IP runs out of tokens
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    service: S,
+    ip: IpAddr,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> RateLimitService<S> {
+    pub fn new(service: S, ip: IpAddr, limiter: Arc<RateLimiter>) -> Self {
+        Self {
+            service,
+            ip,
+            limiter,
+        }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for RateLimitService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+    S::Future: Send + 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        match self.limiter.check(self.ip, request.method_name()) {
+            Ok(()) => Box::pin(self.service.call(request)),
+            Err(e) => {
+                let response = MethodResponse::error(request.id, ErrorObjectOwned::from(e));
+                Box::pin(async move { response })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_per_ip_and_per_method_buckets() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            per_ip: Some(RateLimit {
+                requests_per_second: 1.0,
+                burst: 3,
+            }),
+            per_method: HashMap::from([(
+                "kanari_sendRawTransaction".to_string(),
+                RateLimit {
+                    requests_per_second: 0.5,
+                    burst: 1,
+                },
+            )]),
+        });
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let other_ip: IpAddr = "10.0.0.2".parse().unwrap();
+        let now = Instant::now();
+
+        assert!(
+            limiter
+                .check_at(ip, "kanari_sendRawTransaction", now)
+                .is_ok()
+        );
+        // The method bucket is empty, the IP bucket keeps its remaining tokens
+        let err = limiter
+            .check_at(ip, "kanari_sendRawTransaction", now)
+            .unwrap_err();
+        assert!(matches!(err, RpcError::RateLimited(_, 2000)));
+        assert!(limiter.check_at(ip, "kanari_getBlockHeight", now).is_ok());
+        assert!(limiter.check_at(ip, "kanari_getBlockHeight", now).is_ok());
+        assert!(limiter.check_at(ip, "kanari_getBlockHeight", now).is_err());
+        assert!(
+            limiter
+                .check_at(other_ip, "kanari_getBlockHeight", now)
+                .is_ok()
+        );
+
+        let later = now + Duration::from_secs(1);
+        assert!(limiter.check_at(ip, "kanari_getBlockHeight", later).is_ok());
+    }
+}
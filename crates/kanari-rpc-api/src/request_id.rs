@@ -0,0 +1,151 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpsee::{
+    MethodResponse,
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObjectOwned, Id, Request},
+};
+use serde_json::{Value, json};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        LazyLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Instant, SystemTime},
+};
+use tracing::{Instrument, debug, info, info_span};
+
+/// Key of the request ID in the `data` of error responses
+pub const REQUEST_ID_FIELD: &str = "request_id";
+
+/// Start time of the node in seconds, so IDs of different runs do not collide
+static RUN_PREFIX: LazyLock<String> = LazyLock::new(|| {
+    let started = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!("{:08x}", started as u32)
+});
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A new ID correlating the log lines of a call with the error response its client got
+pub fn next_request_id() -> String {
+    format!(
+        "{}-{:x}",
+        *RUN_PREFIX,
+        NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Add the request ID to the `data` of an error response. Object data gets a
+/// `request_id` field, other data is moved under `data`.
+fn with_request_id(id: Id<'static>, response: MethodResponse, request_id: &str) -> MethodResponse {
+    let Ok(body) = serde_json::from_str::<Value>(response.as_result()) else {
+        return response;
+    };
+    let Some(error) = body.get("error") else {
+        return response;
+    };
+    let code = error["code"].as_i64().unwrap_or_default() as i32;
+    let message = error["message"].as_str().unwrap_or_default().to_string();
+    let data = match error.get("data").cloned() {
+        Some(Value::Object(mut data)) => {
+            data.insert(REQUEST_ID_FIELD.to_string(), json!(request_id));
+            Value::Object(data)
+        }
+        Some(data) => json!({ REQUEST_ID_FIELD: request_id, "data": data }),
+        None => json!({ REQUEST_ID_FIELD: request_id }),
+    };
+    MethodResponse::error(id, ErrorObjectOwned::owned(code, message, Some(data)))
+}
+
+/// RPC middleware giving every call a request ID, logging it within a span with the
+/// method, the size of the params, the duration and the outcome, and returning it in
+/// error responses so a failure a user reports can be found in the node's logs
+#[derive(Clone)]
+pub struct RequestIdService<S> {
+    service: S,
+}
+
+impl<S> RequestIdService<S> {
+    pub fn new(service: S) -> Self {
+        Self { service }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for RequestIdService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+    S::Future: Send + 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let request_id = next_request_id();
+        let id = request.id().into_owned();
+        let params_bytes = request
+            .params
+            .as_ref()
+            .map_or(0, |params| params.get().len());
+        // `otel.name` names the exported span after the method instead of `rpc`
+        let span = info_span!(
+            "rpc",
+            request_id = %request_id,
+            method = %request.method_name(),
+            otel.name = %request.method_name(),
+            otel.kind = "server"
+        );
+        let started = Instant::now();
+        let response = span.in_scope(|| self.service.call(request));
+        Box::pin(
+            async move {
+                let response = response.await;
+                let elapsed_ms = started.elapsed().as_millis() as u64;
+                if response.is_success() {
+                    debug!(params_bytes, elapsed_ms, "RPC call succeeded");
+                    response
+                } else {
+                    info!(
+                        params_bytes,
+                        elapsed_ms,
+                        code = response.as_error_code(),
+                        "RPC call failed"
+                    );
+                    with_request_id(id, response, &request_id)
+                }
+            }
+            .instrument(span),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_responses_carry_the_request_id() {
+        let plain = MethodResponse::error(
+            Id::Number(1),
+            ErrorObjectOwned::owned::<()>(-32602, "Invalid params", None),
+        );
+        let response = with_request_id(Id::Number(1), plain, "abc-1");
+        let body: Value = serde_json::from_str(response.as_result()).unwrap();
+        assert_eq!(body["error"]["code"], -32602);
+        assert_eq!(body["error"]["data"][REQUEST_ID_FIELD], "abc-1");
+
+        let detailed = MethodResponse::error(
+            Id::Number(2),
+            ErrorObjectOwned::owned(-32000, "Rejected", Some("nonce too low")),
+        );
+        let response = with_request_id(Id::Number(2), detailed, "abc-2");
+        let body: Value = serde_json::from_str(response.as_result()).unwrap();
+        assert_eq!(body["error"]["data"]["data"], "nonce too low");
+        assert_eq!(body["id"], 2);
+
+        assert_ne!(next_request_id(), next_request_id());
+    }
+}
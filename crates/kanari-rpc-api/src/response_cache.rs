@@ -0,0 +1,224 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Number of responses kept by default
+pub const DEFAULT_RESPONSE_CACHE_CAPACITY: usize = 4096;
+/// How long a response is kept by default
+pub const DEFAULT_RESPONSE_CACHE_TTL: Duration = Duration::from_secs(600);
+/// Blocks on top of a block before responses about it are cached by default, so a
+/// replaced chain tip is never served from the cache
+pub const DEFAULT_RESPONSE_CACHE_CONFIRMATIONS: u128 = 10;
+
+/// Size and lifetime of the response cache
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseCacheConfig {
+    /// Responses kept, 0 disables the cache
+    pub capacity: usize,
+    pub ttl: Duration,
+    pub confirmations: u128,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_RESPONSE_CACHE_CAPACITY,
+            ttl: DEFAULT_RESPONSE_CACHE_TTL,
+            confirmations: DEFAULT_RESPONSE_CACHE_CONFIRMATIONS,
+        }
+    }
+}
+
+type CacheKey = (&'static str, String);
+
+#[derive(Debug, Default)]
+struct Entries {
+    responses: HashMap<CacheKey, (Value, Instant, u64)>,
+    /// Keys by the tick of their last use, least recently used first
+    recency: BTreeMap<u64, CacheKey>,
+    tick: u64,
+}
+
+impl Entries {
+    fn touch(&mut self, key: &CacheKey) {
+        self.tick += 1;
+        if let Some((_, _, used)) = self.responses.get_mut(key) {
+            self.recency.remove(used);
+            *used = self.tick;
+            self.recency.insert(self.tick, key.clone());
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some((_, _, used)) = self.responses.remove(key) {
+            self.recency.remove(&used);
+        }
+    }
+}
+
+/// Least recently used responses of methods returning data that no longer changes,
+/// such as confirmed blocks and receipts, keyed by method and params. Explorers
+/// repeating the same queries are answered without reading the database.
+#[derive(Debug)]
+pub struct ResponseCache {
+    config: ResponseCacheConfig,
+    entries: Mutex<Entries>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(ResponseCacheConfig::default())
+    }
+}
+
+impl ResponseCache {
+    pub fn new(config: ResponseCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    /// Whether data about `block_number` no longer changes at `height`
+    pub fn is_confirmed(&self, block_number: u128, height: u128) -> bool {
+        block_number.saturating_add(self.config.confirmations) <= height
+    }
+
+    /// The cached response of `method` for `params`
+    pub fn get<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        params: &impl Serialize,
+    ) -> Option<T> {
+        self.get_at(method, params, Instant::now())
+    }
+
+    fn get_at<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        params: &impl Serialize,
+        now: Instant,
+    ) -> Option<T> {
+        if self.config.capacity == 0 {
+            return None;
+        }
+        let key = (method, serde_json::to_string(params).ok()?);
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let (response, cached_at) = entries
+            .responses
+            .get(&key)
+            .map(|(response, cached_at, _)| (response.clone(), *cached_at))?;
+        if now.saturating_duration_since(cached_at) > self.config.ttl {
+            entries.remove(&key);
+            return None;
+        }
+        let response = serde_json::from_value(response).ok();
+        entries.touch(&key);
+        response
+    }
+
+    /// Cache the response of `method` for `params`, evicting the least recently used
+    /// response when full. Only responses that can no longer change may be inserted.
+    pub fn insert<T: Serialize>(
+        &self,
+        method: &'static str,
+        params: &impl Serialize,
+        response: &T,
+    ) {
+        self.insert_at(method, params, response, Instant::now())
+    }
+
+    fn insert_at<T: Serialize>(
+        &self,
+        method: &'static str,
+        params: &impl Serialize,
+        response: &T,
+        now: Instant,
+    ) {
+        if self.config.capacity == 0 {
+            return;
+        }
+        let (Ok(params), Ok(response)) = (
+            serde_json::to_string(params),
+            serde_json::to_value(response),
+        ) else {
+            return;
+        };
+        let key = (method, params);
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.remove(&key);
+        while entries.responses.len() >= self.config.capacity {
+            let Some((_, oldest)) = entries.recency.pop_first() else {
+                break;
+            };
+            entries.responses.remove(&oldest);
+        }
+        entries.tick += 1;
+        let tick = entries.tick;
+        entries.recency.insert(tick, key.clone());
+        entries.responses.insert(key, (response, now, tick));
+    }
+
+    pub fn len(&self) -> usize {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.responses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_evicts_least_recently_used_and_expired() {
+        let cache = ResponseCache::new(ResponseCacheConfig {
+            capacity: 2,
+            ttl: Duration::from_secs(60),
+            confirmations: 10,
+        });
+        let now = Instant::now();
+        cache.insert_at("getBlockByNumber", &(1u128,), &"one", now);
+        cache.insert_at("getBlockByNumber", &(2u128,), &"two", now);
+        // Reading block 1 makes block 2 the least recently used
+        assert_eq!(
+            cache
+                .get_at::<String>("getBlockByNumber", &(1u128,), now)
+                .as_deref(),
+            Some("one")
+        );
+        cache.insert_at("getBlockByNumber", &(3u128,), &"three", now);
+        assert_eq!(cache.len(), 2);
+        assert!(
+            cache
+                .get_at::<String>("getBlockByNumber", &(2u128,), now)
+                .is_none()
+        );
+        // The same params of another method are another entry
+        assert!(
+            cache
+                .get_at::<String>("getBlockFeeSummary", &(1u128,), now)
+                .is_none()
+        );
+
+        let later = now + Duration::from_secs(61);
+        assert!(
+            cache
+                .get_at::<String>("getBlockByNumber", &(1u128,), later)
+                .is_none()
+        );
+        assert_eq!(cache.len(), 1);
+
+        assert!(cache.is_confirmed(5, 15));
+        assert!(!cache.is_confirmed(6, 15));
+    }
+}
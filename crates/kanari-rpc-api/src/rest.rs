@@ -0,0 +1,181 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::{BalanceInfo, BlockInfo, KanariRpcApiServer};
+use crate::error::{RpcError, RpcResult};
+use crate::rate_limit::RateLimiter;
+use crate::server::KanariRpcImpl;
+use axum::extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use jsonrpsee::types::ErrorObjectOwned;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// State shared by the REST handlers. Requests are forwarded to the JSON-RPC
+/// implementation, so both APIs always return the same data.
+#[derive(Clone)]
+struct RestState {
+    kanari: Arc<KanariRpcImpl>,
+    limiter: Option<Arc<RateLimiter>>,
+}
+
+impl RestState {
+    /// Routes share the rate limits of the JSON-RPC method they forward to
+    fn check_rate_limit(&self, remote_addr: SocketAddr, method: &str) -> Result<(), RestError> {
+        match &self.limiter {
+            Some(limiter) => Ok(limiter.check(remote_addr.ip(), method)?),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BalanceQuery {
+    coin_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubmitTransactionRequest {
+    /// Hex encoded, BCS serialized signed transaction
+    raw_tx: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SubmitTransactionResponse {
+    tx_hash: String,
+}
+
+/// A JSON-RPC error as REST response, with the HTTP status derived from its code and
+/// the JSON-RPC error object as body
+struct RestError(ErrorObjectOwned);
+
+impl From<ErrorObjectOwned> for RestError {
+    fn from(error: ErrorObjectOwned) -> Self {
+        RestError(error)
+    }
+}
+
+impl From<RpcError> for RestError {
+    fn from(error: RpcError) -> Self {
+        RestError(error.into())
+    }
+}
+
+/// HTTP status of a JSON-RPC error code, see `RPC_ERROR_CODES`
+fn http_status(code: i32) -> StatusCode {
+    match code {
+        -32700 | -32600 | -32602 | -32008 => StatusCode::BAD_REQUEST,
+        -32601 | -32002 | -32003 => StatusCode::NOT_FOUND,
+        -32000 => StatusCode::SERVICE_UNAVAILABLE,
+        -32001 | -32005 | -32009 | -32010 | -32011 => StatusCode::UNPROCESSABLE_ENTITY,
+        -32004 => StatusCode::BAD_GATEWAY,
+        -32006 => StatusCode::TOO_MANY_REQUESTS,
+        -32007 => StatusCode::UNAUTHORIZED,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+impl IntoResponse for RestError {
+    fn into_response(self) -> Response {
+        (http_status(self.0.code()), Json(self.0)).into_response()
+    }
+}
+
+fn rest_result<T: Serialize>(result: RpcResult<T>) -> Result<Json<T>, RestError> {
+    result.map(Json).map_err(RestError)
+}
+
+/// `number` is a block number or `latest`
+async fn get_block(
+    State(state): State<RestState>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    Path(number): Path<String>,
+) -> Result<Json<BlockInfo>, RestError> {
+    if number == "latest" {
+        state.check_rate_limit(remote_addr, "kanari_getLatestBlock")?;
+        return rest_result(state.kanari.get_latest_block().await);
+    }
+    state.check_rate_limit(remote_addr, "kanari_getBlockByNumber")?;
+    let block_number = number
+        .parse::<u128>()
+        .map_err(|e| RpcError::InvalidParams(format!("Invalid block number {}: {}", number, e)))?;
+    rest_result(state.kanari.get_block_by_number(block_number).await)
+}
+
+async fn get_balance(
+    State(state): State<RestState>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    Path(address): Path<String>,
+    Query(query): Query<BalanceQuery>,
+) -> Result<Json<BalanceInfo>, RestError> {
+    state.check_rate_limit(remote_addr, "kanari_getBalance")?;
+    rest_result(
+        state
+            .kanari
+            .get_balance(address, query.coin_type, None)
+            .await,
+    )
+}
+
+async fn submit_transaction(
+    State(state): State<RestState>,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
+    Json(request): Json<SubmitTransactionRequest>,
+) -> Result<Json<SubmitTransactionResponse>, RestError> {
+    state.check_rate_limit(remote_addr, "kanari_sendRawTransaction")?;
+    let tx_hash = state.kanari.send_raw_transaction(request.raw_tx).await?;
+    Ok(Json(SubmitTransactionResponse { tx_hash }))
+}
+
+/// Routes of the REST gateway:
+/// - `GET /blocks/{number}`, a block number or `latest`
+/// - `GET /accounts/{address}/balance?coin_type=...`
+/// - `POST /transactions` with `{"raw_tx": "0x..."}`, returns `{"tx_hash": "0x..."}`
+///
+/// Serve it with `into_make_service_with_connect_info::<SocketAddr>()`, the client
+/// address is needed for rate limiting.
+pub fn rest_router(
+    kanari: KanariRpcImpl,
+    limiter: Option<Arc<RateLimiter>>,
+    max_request_body_size: usize,
+) -> Router {
+    Router::new()
+        .route("/blocks/{number}", get(get_block))
+        .route("/accounts/{address}/balance", get(get_balance))
+        .route("/transactions", post(submit_transaction))
+        .layer(DefaultBodyLimit::max(max_request_body_size))
+        .with_state(RestState {
+            kanari: Arc::new(kanari),
+            limiter,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_errors_map_to_http_status() {
+        let status = |error: RpcError| RestError::from(error).into_response().status();
+        assert_eq!(
+            status(RpcError::InvalidParams("bad".to_string())),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status(RpcError::BlockNotFound("#7".to_string())),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(RpcError::RateLimited("slow down".to_string(), 100)),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(
+            status(RpcError::InternalError("oops".to_string())),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -1,19 +1,750 @@
 // Copyright (c) KanariNetwork
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{api::*, error::RpcResult};
+#[cfg(feature = "debug-rpc")]
+use crate::state_page::{
+    DEFAULT_STATE_PAGE_LIMIT, MAX_STATE_PAGE_BYTES, MAX_STATE_PAGE_LIMIT, bounded_page,
+};
+use crate::{
+    api::*,
+    api_keys::{ApiKeyService, ApiKeyStore},
+    auth::{AuthService, RpcAuthConfig},
+    block_cache::BlockCache,
+    cors::CorsConfig,
+    discover::DiscoverRpcImpl,
+    dry_run::{dry_run, move_abort_info},
+    error::{RpcError, RpcResult, to_rpc_result},
+    eth::EthRpcImpl,
+    experimental::ExperimentalRpcImpl,
+    metrics::{MetricsService, RpcMetrics, start_metrics_server},
+    module_abi::module_abi,
+    plugin::{PluginServerConfig, start_plugin_server},
+    rate_limit::{RateLimitConfig, RateLimitService, RateLimiter},
+    request_id::RequestIdService,
+    response_cache::{ResponseCache, ResponseCacheConfig},
+    state_bus::{NodeStateBus, NodeStateEvent, run_node_state_service},
+    subscription::{
+        DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION, DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY,
+        SUBSCRIPTION_CHANNEL_CAPACITY, SlowSubscriberPolicy, SubscriptionRpcImpl,
+    },
+};
 use anyhow::Result;
 use jsonrpsee::{
     RpcModule,
     core::async_trait,
-    server::{ServerBuilder, ServerHandle},
+    server::{
+        Methods, ServerBuilder, ServerHandle, middleware::rpc::RpcServiceBuilder,
+        serve_with_graceful_shutdown, stop_channel,
+    },
+};
+use kanari_config::OPTIONAL_RPC_NAMESPACES;
+use kanari_db::maintenance::{MaintenanceRun, MaintenanceScheduler};
+use kanari_db::state_proof::prove_state;
+use kanari_db::state_view::StateView;
+use kanari_db::{ExecutedBlock, RoochDB};
+use kanari_mempool::{
+    BlockFeeSample, FEE_ORACLE_BLOCKS, FeeEstimate, FeeSuggestion, MempoolLimits, MempoolRejection,
+    PooledTransaction, TxPool, base_fee_history, estimate_fees,
+};
+use kanari_types::block::{Block, GENESIS_BLOCK_NUMBER, SignedBlock, next_tx_accumulator_root};
+use kanari_types::dao::{DaoAction, DaoProposal, DaoProposalStatus, DaoVote};
+#[cfg(feature = "admin-rpc")]
+use kanari_types::epoch::Validator;
+use kanari_types::epoch::{EpochSnapshot, ValidatorSetChange, epoch_of, is_epoch_boundary};
+use kanari_types::event::BlockEvent;
+use kanari_types::evidence::{DoubleSignEvidence, EvidenceRecord};
+use kanari_types::fee::FeeSummary;
+use kanari_types::personal_message::{PersonalMessageSignature, cancel_transaction_message};
+use kanari_types::receipt::ExecutionStatus;
+use kanari_types::system_transaction::{SYSTEM_TRANSACTION_SLOTS, SystemTransaction};
+use kanari_types::transaction::SignedTransaction;
+use kanari_types::{
+    genesis_config::G_LOCAL_CONFIG,
+    kari_coin::{
+        DECIMALS, DENOMINATIONS, DISPLAY_DECIMALS, KARI, KARI_DENOMINATION, format_amount,
+    },
 };
-use std::{net::SocketAddr, sync::Arc, time::SystemTime, collections::hash_map::DefaultHasher, hash::Hasher, str::FromStr};
-use tokio::sync::RwLock;
-use tracing::{info, warn};
-use kanari_types::{kari_coin::{KARI, DECIMALS}, genesis_config::G_LOCAL_CONFIG};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use move_core_types::u256::U256;
+use moveos_types::access_path::{self, AccessPath};
+use moveos_types::h256::H256;
+use moveos_types::move_types::FunctionId;
+use moveos_types::moveos_std::object::{ObjectID, ObjectMeta};
+use moveos_types::state::FieldKey;
 use moveos_types::state::MoveStructType;
+use moveos_types::transaction::FunctionCall;
+use prometheus::Registry;
+use rooch_types::address::RoochAddress;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::SystemTime,
+};
+use tokio::sync::{RwLock, broadcast, mpsc};
+use tower::Service;
+use tracing::{info, instrument, warn};
+
+/// Default number of recent transactions returned in an account summary
+pub const DEFAULT_ACCOUNT_SUMMARY_RECENT_TXS: usize = 10;
+/// Maximum number of recent transactions returned in an account summary
+pub const MAX_ACCOUNT_SUMMARY_RECENT_TXS: usize = 100;
+/// Default number of transactions in a page of an account's history
+pub const DEFAULT_ACCOUNT_TRANSACTIONS_PAGE_SIZE: usize = 50;
+/// Maximum number of transactions in a page of an account's history
+pub const MAX_ACCOUNT_TRANSACTIONS_PAGE_SIZE: usize = 500;
+/// Maximum number of blocks scanned by a single event query
+pub const MAX_EVENT_QUERY_BLOCK_RANGE: u128 = 10_000;
+/// Default byte budget of a pending transaction snapshot
+pub const DEFAULT_PENDING_SNAPSHOT_MAX_BYTES: usize = 4 * 1024 * 1024;
+/// Default and maximum number of transactions in a pending transaction snapshot
+pub const MAX_PENDING_SNAPSHOT_COUNT: usize = 5_000;
+/// Default number of blocks in a page of a block range query
+pub const DEFAULT_BLOCK_PAGE_SIZE: usize = 100;
+/// Maximum number of blocks in a page of a block range query
+pub const MAX_BLOCK_PAGE_SIZE: usize = 1_000;
+/// Default maximum number of blocks fetched by a single `getBlocksByNumbers` call
+pub const DEFAULT_MAX_BLOCKS_PER_BATCH: usize = 100;
+/// How far a submitted block's timestamp may be from the node's clock, in seconds
+pub const BLOCK_TIMESTAMP_TOLERANCE_SECS: u64 = 30;
+/// Gas limit of a block
+pub const BLOCK_GAS_LIMIT: u64 = 1_000_000;
+/// Maximum number of epochs covered by a single rewards query
+pub const MAX_REWARD_EPOCH_RANGE: u64 = 1_000;
+/// Maximum number of blocks covered by a single fee history query
+pub const MAX_FEE_HISTORY_BLOCKS: u64 = 1_024;
+
+/// The RPC view of a persisted block, linked to its parent by hash
+pub fn block_info(db: &RoochDB, block: Block) -> Result<BlockInfo> {
+    let parent_hash = match block.block_number.checked_sub(1) {
+        Some(parent_number) => db
+            .get_block(parent_number)?
+            .map(|parent| parent.hash())
+            .unwrap_or_default(),
+        None => H256::zero(),
+    };
+    let timestamp = db.get_block_timestamp(block.block_number)?;
+    linked_block_info(db, &block, parent_hash, timestamp)
+}
+
+/// The RPC view of the fee accounting of a block
+fn block_fee_summary_info(block_number: u128, summary: &FeeSummary) -> BlockFeeSummary {
+    BlockFeeSummary {
+        block_number,
+        collected: summary.collected.to_string(),
+        refunded: summary.refunded.to_string(),
+        to_dao: summary.to_dao.to_string(),
+        burned: summary.burned.to_string(),
+        to_validators: summary.to_validators.to_string(),
+    }
+}
+
+/// The RPC view of a stored block whose parent hash and timestamp were already read,
+/// with its gas use and fee accounting
+fn linked_block_info(
+    db: &RoochDB,
+    block: &Block,
+    parent_hash: H256,
+    timestamp: Option<u64>,
+) -> Result<BlockInfo> {
+    let gas_used = db.get_block_gas_used(block.block_number)?;
+    let fees = db
+        .get_block_fee_summary(block.block_number)?
+        .map(|summary| block_fee_summary_info(block.block_number, &summary));
+    Ok(BlockInfo {
+        number: block.block_number,
+        hash: format!("0x{}", hex::encode(block.hash().as_bytes())),
+        parent_hash: format!("0x{}", hex::encode(parent_hash.as_bytes())),
+        // Blocks saved before timestamps were recorded report 0
+        timestamp: timestamp.unwrap_or_default(),
+        transaction_count: block.batch_size as usize,
+        gas_used,
+        gas_limit: BLOCK_GAS_LIMIT,
+        state_root: format!("0x{}", hex::encode(block.state_root.as_bytes())),
+        fees,
+    })
+}
+
+/// The RPC view of an event emitted in a block
+pub(crate) fn event_info(block_number: u128, event: BlockEvent) -> EventInfo {
+    EventInfo {
+        block_number,
+        tx_hash: format!("0x{}", hex::encode(event.tx_hash.as_bytes())),
+        event_index: event.event_index,
+        address: event.address.to_hex_literal(),
+        event_type: event.event_type,
+        data: format!("0x{}", hex::encode(event.data)),
+    }
+}
+
+/// Gas use and user transaction gas prices of the blocks the fee oracle looks at, up to
+/// `block_height`
+fn recent_fee_samples(db: &RoochDB, block_height: u128) -> Result<Vec<BlockFeeSample>> {
+    fee_samples(
+        db,
+        block_height.saturating_sub(FEE_ORACLE_BLOCKS - 1),
+        block_height,
+    )
+}
+
+/// Gas use and user transaction gas prices of the blocks from `from` to `to`
+fn fee_samples(db: &RoochDB, from: u128, to: u128) -> Result<Vec<BlockFeeSample>> {
+    let mut samples = vec![];
+    for block_number in from..=to {
+        let transactions = db.get_block_transactions(block_number)?;
+        let user_transactions = transactions.iter().filter(|tx| !tx.is_system());
+        samples.push(BlockFeeSample {
+            gas_used: user_transactions
+                .clone()
+                .map(|tx| db.get_transaction_gas_used(tx))
+                .sum::<Result<u64>>()?,
+            gas_limit: db
+                .epoch_snapshot_for_block(block_number)?
+                .map(|snapshot| snapshot.params.block_gas_limit)
+                .unwrap_or(BLOCK_GAS_LIMIT),
+            gas_prices: user_transactions.map(|tx| tx.tx.gas_price).collect(),
+        });
+    }
+    Ok(samples)
+}
+
+/// The RPC view of the node state
+pub fn node_info(state: &NodeState, db: Option<&RoochDB>) -> Result<NodeInfo> {
+    let uptime = SystemTime::now()
+        .duration_since(state.uptime_start)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let genesis_hash = match db {
+        Some(db) => db
+            .get_genesis_hash()?
+            .map(|hash| format!("0x{}", hex::encode(hash.as_bytes()))),
+        None => None,
+    };
+
+    Ok(NodeInfo {
+        version: state.node_version.clone(),
+        chain_id: state.chain_id,
+        genesis_hash,
+        node_type: state.node_type.clone(),
+        peer_count: state.peer_count,
+        block_height: state.block_height,
+        is_syncing: state.is_syncing,
+        uptime_seconds: uptime,
+        operator: state.operator.clone(),
+        rpc_port: state.rpc_port,
+        p2p_port: state.p2p_port,
+        read_only: state.read_only,
+        param_overrides: state.param_overrides.clone(),
+    })
+}
+
+/// The RPC view of a transaction that has not been included in a block yet
+fn pending_transaction_summary(tx: &SignedTransaction) -> TransactionInfo {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    transaction_info(tx, "Pending", None, now, 0)
+}
+
+fn transaction_info(
+    tx: &SignedTransaction,
+    status: &str,
+    block_number: Option<u128>,
+    timestamp: u64,
+    gas_used: u64,
+) -> TransactionInfo {
+    TransactionInfo {
+        hash: format!("0x{}", hex::encode(tx.hash().as_bytes())),
+        sender: tx.tx.sender.to_hex_literal(),
+        recipient: tx.tx.recipient.map(|recipient| recipient.to_hex_literal()),
+        amount: tx.tx.amount.to_string(),
+        gas_used,
+        gas_price: tx.tx.gas_price,
+        status: status.to_string(),
+        block_number,
+        timestamp,
+        system_kind: SystemTransaction::from_transaction(tx)
+            .ok()
+            .flatten()
+            .map(|system| system.kind().to_string()),
+    }
+}
+
+/// Decode a hex encoded `SignedTransaction` and check it against the node: its
+/// signature, chain, sequence number and gas. Every submission path goes through it
+/// before pooling a transaction.
+#[instrument(skip_all)]
+pub(crate) async fn validate_raw_transaction(
+    node_state: &RwLock<NodeState>,
+    tx_pool: &RwLock<TxPool>,
+    db: Option<&RoochDB>,
+    raw_tx: &str,
+) -> RpcResult<SignedTransaction> {
+    let bytes = hex::decode(raw_tx.strip_prefix("0x").unwrap_or(raw_tx))
+        .map_err(|e| RpcError::InvalidParams(format!("Invalid hex: {}", e)))?;
+    let signed_tx = SignedTransaction::decode(&bytes)
+        .map_err(|e| RpcError::InvalidParams(format!("Invalid transaction: {}", e)))?;
+    signed_tx
+        .verify_signature()
+        .map_err(|e| RpcError::InvalidSignature(e.to_string()))?;
+    let tx = &signed_tx.tx;
+
+    let chain_id = node_state.read().await.chain_id;
+    if tx.chain_id != chain_id {
+        return Err(RpcError::WrongChain(format!(
+            "the transaction is for chain {}, the node runs chain {}",
+            tx.chain_id, chain_id
+        ))
+        .into());
+    }
+    if let Some(db) = db {
+        if let Some(genesis_hash) = to_rpc_result(db.get_genesis_hash())?
+            && tx.genesis_hash != genesis_hash
+        {
+            return Err(RpcError::WrongChain(format!(
+                "the transaction was built against genesis {:?}, the node's genesis is {:?}",
+                tx.genesis_hash, genesis_hash
+            ))
+            .into());
+        }
+        let account_sequence_number =
+            to_rpc_result(db.get_account(tx.sender))?.map_or(0, |account| account.sequence_number);
+        if tx.sequence_number < account_sequence_number {
+            return Err(RpcError::InvalidSequenceNumber(format!(
+                "{} is below the sequence number {} of {}",
+                tx.sequence_number,
+                account_sequence_number,
+                tx.sender.to_hex_literal()
+            ))
+            .into());
+        }
+    }
+
+    if tx.gas_limit == 0 || tx.gas_limit > BLOCK_GAS_LIMIT {
+        return Err(RpcError::InvalidGas(format!(
+            "gas limit {} must be between 1 and {}",
+            tx.gas_limit, BLOCK_GAS_LIMIT
+        ))
+        .into());
+    }
+    let min_gas_price = tx_pool.read().await.limits().min_gas_price;
+    if tx.gas_price < min_gas_price {
+        return Err(RpcError::InvalidGas(format!(
+            "gas price {} is below the minimum {}",
+            tx.gas_price, min_gas_price
+        ))
+        .into());
+    }
+    Ok(signed_tx)
+}
+
+/// Validate and pool a hex encoded `SignedTransaction`, returning its hash and the
+/// summary published to subscribers
+pub(crate) async fn pool_raw_transaction(
+    node_state: &RwLock<NodeState>,
+    tx_pool: &RwLock<TxPool>,
+    db: Option<&RoochDB>,
+    raw_tx: &str,
+) -> RpcResult<(H256, TransactionInfo)> {
+    let signed_tx = validate_raw_transaction(node_state, tx_pool, db, raw_tx).await?;
+    let summary = pending_transaction_summary(&signed_tx);
+    let mut pool = tx_pool.write().await;
+    seed_account_nonce(&mut pool, db, signed_tx.tx.sender)?;
+    let hash = pool.add_transaction(signed_tx).map_err(RpcError::from)?;
+    Ok((hash, summary))
+}
+
+/// Seed the pool with the committed sequence number of a sender it does not know yet,
+/// so the sender's first pooled transaction only executes at that nonce
+fn seed_account_nonce(
+    pool: &mut TxPool,
+    db: Option<&RoochDB>,
+    sender: AccountAddress,
+) -> RpcResult<()> {
+    if let Some(db) = db
+        && pool.account_nonce(&sender).is_none()
+    {
+        let account_sequence_number =
+            to_rpc_result(db.get_account(sender))?.map_or(0, |account| account.sequence_number);
+        pool.set_account_nonce(sender, account_sequence_number);
+    }
+    Ok(())
+}
+
+/// The RPC view of an epoch snapshot
+fn epoch_info(snapshot: &EpochSnapshot, is_current: bool, pending_changes: usize) -> EpochInfo {
+    let total_stake = snapshot.total_stake();
+    EpochInfo {
+        epoch: snapshot.epoch,
+        start_block: snapshot.start_block(),
+        end_block: snapshot.end_block(),
+        is_current,
+        total_stake: total_stake.to_string(),
+        validators: snapshot
+            .validators
+            .iter()
+            .map(|validator| EpochValidatorInfo {
+                address: validator.address.to_hex_literal(),
+                public_key: format!("0x{}", hex::encode(&validator.public_key)),
+                stake: validator.stake.to_string(),
+                delegated_stake: (validator.total_stake() - validator.stake).to_string(),
+                delegators: validator.delegations.len(),
+                weight: snapshot
+                    .stake_weight(&validator.address)
+                    .unwrap_or_default(),
+                voting_power: snapshot
+                    .voting_power(&validator.address)
+                    .unwrap_or_default(),
+                jailed_until: validator
+                    .jailed_until
+                    .filter(|_| validator.is_jailed(snapshot.epoch)),
+            })
+            .collect(),
+        params: ConsensusParamsInfo {
+            block_gas_limit: snapshot.params.block_gas_limit,
+            min_validator_stake: snapshot.params.min_validator_stake.to_string(),
+            max_validators: snapshot.params.max_validators,
+            epoch_emission: snapshot.params.epoch_emission.to_string(),
+            dao_fee_bps: snapshot.params.dao_fee_bps,
+            burn_fee_bps: snapshot.params.burn_fee_bps,
+            max_voting_power_bps: snapshot.params.max_voting_power_bps,
+        },
+        snapshot_hash: format!("0x{}", hex::encode(snapshot.hash().as_bytes())),
+        pending_changes,
+    }
+}
+
+/// Stake an account bonded as a validator or delegated to validators in `snapshot`
+fn staking_positions(snapshot: &EpochSnapshot, account: &AccountAddress) -> Vec<StakingPosition> {
+    let mut positions = vec![];
+    for validator in &snapshot.validators {
+        let validator_address = Some(validator.address.to_hex_literal());
+        if validator.address == *account {
+            positions.push(StakingPosition {
+                kind: "validator".to_string(),
+                amount: validator.stake.to_string(),
+                validator: validator_address.clone(),
+                unlock_timestamp: None,
+            });
+        }
+        for delegation in validator
+            .delegations
+            .iter()
+            .filter(|d| d.delegator == *account)
+        {
+            positions.push(StakingPosition {
+                kind: "delegation".to_string(),
+                amount: delegation.amount.to_string(),
+                validator: validator_address.clone(),
+                unlock_timestamp: None,
+            });
+        }
+    }
+    positions
+}
+
+/// Check the user transactions of a submitted block as the pool admits them: each one
+/// within the gas bounds and not included before, the block within its gas limit and the
+/// transactions of a sender in consecutive sequence numbers starting at its committed one
+fn validate_block_transactions(
+    db: &RoochDB,
+    transactions: &[SignedTransaction],
+    block_gas_limit: u64,
+    min_gas_price: u64,
+) -> Result<()> {
+    let mut gas_used = 0u64;
+    let mut next_sequence_numbers: BTreeMap<AccountAddress, u64> = BTreeMap::new();
+    for signed_tx in transactions.iter().filter(|tx| !tx.is_system()) {
+        let tx = &signed_tx.tx;
+        let hash = signed_tx.hash();
+        if tx.gas_limit == 0 || tx.gas_limit > BLOCK_GAS_LIMIT {
+            anyhow::bail!(
+                "Transaction {:?} gas limit {} must be between 1 and {}",
+                hash,
+                tx.gas_limit,
+                BLOCK_GAS_LIMIT
+            );
+        }
+        if tx.gas_price < min_gas_price {
+            anyhow::bail!(
+                "Transaction {:?} gas price {} is below the minimum {}",
+                hash,
+                tx.gas_price,
+                min_gas_price
+            );
+        }
+        gas_used = gas_used.saturating_add(tx.gas_limit);
+        if gas_used > block_gas_limit {
+            anyhow::bail!(
+                "Block transactions exceed the block gas limit {}",
+                block_gas_limit
+            );
+        }
+        if db.get_transaction_location(&hash)?.is_some() {
+            anyhow::bail!("Transaction {:?} is already included", hash);
+        }
+        // The sequence number comes from an untrusted block and may be the last one
+        let Some(next_sequence_number) = tx.sequence_number.checked_add(1) else {
+            anyhow::bail!(
+                "Transaction {:?} sequence number {} has no successor",
+                hash,
+                tx.sequence_number
+            );
+        };
+        match next_sequence_numbers.get(&tx.sender) {
+            Some(expected) if tx.sequence_number != *expected => anyhow::bail!(
+                "Transaction {:?} sequence number {} of {} does not follow {}",
+                hash,
+                tx.sequence_number,
+                tx.sender.to_hex_literal(),
+                expected - 1
+            ),
+            Some(_) => {}
+            None => {
+                let committed = db
+                    .get_account(tx.sender)?
+                    .map_or(0, |account| account.sequence_number);
+                // The sender's first transaction in the block runs at its committed
+                // nonce, a gap would only fail at execution
+                if tx.sequence_number != committed {
+                    anyhow::bail!(
+                        "Transaction {:?} sequence number {} does not match the sequence number {} of {}",
+                        hash,
+                        tx.sequence_number,
+                        committed,
+                        tx.sender.to_hex_literal()
+                    );
+                }
+            }
+        }
+        next_sequence_numbers.insert(tx.sender, next_sequence_number);
+    }
+    Ok(())
+}
+
+/// The RPC view of a DAO proposal with its votes, at `height`
+fn dao_proposal_info(proposal: &DaoProposal, votes: &[DaoVote], height: u128) -> DaoProposalInfo {
+    let participants = G_LOCAL_CONFIG.kanari_dao.participant_public_keys.len();
+    let approvals = votes.iter().filter(|vote| vote.approve).count() as u64;
+    DaoProposalInfo {
+        id: proposal.id,
+        proposer: format!("0x{}", hex::encode(&proposal.proposer)),
+        title: proposal.title.clone(),
+        description: proposal.description.clone(),
+        action: match &proposal.action {
+            DaoAction::Text => DaoActionInfo::Text,
+            DaoAction::Transfer { recipient, amount } => DaoActionInfo::Transfer {
+                recipient: recipient.to_hex_literal(),
+                amount: amount.to_string(),
+            },
+        },
+        status: match proposal.status(votes, participants, height) {
+            DaoProposalStatus::Active => DaoProposalStatusInfo::Active,
+            DaoProposalStatus::Passed => DaoProposalStatusInfo::Passed,
+            DaoProposalStatus::Rejected => DaoProposalStatusInfo::Rejected,
+            DaoProposalStatus::Expired => DaoProposalStatusInfo::Expired,
+            DaoProposalStatus::Executed => DaoProposalStatusInfo::Executed,
+        },
+        created_at_block: proposal.created_at_block,
+        voting_end_block: proposal.voting_end_block,
+        threshold: proposal.threshold,
+        approvals,
+        rejections: votes.len() as u64 - approvals,
+        executed_at_block: proposal.executed_at_block,
+    }
+}
+
+/// The RPC view of recorded evidence
+fn evidence_info(record: &EvidenceRecord) -> EvidenceInfo {
+    let hash = |hash: H256| format!("0x{}", hex::encode(hash.as_bytes()));
+    EvidenceInfo {
+        hash: hash(record.evidence.hash()),
+        offender: record.offender.to_hex_literal(),
+        public_key: format!("0x{}", hex::encode(record.evidence.public_key())),
+        block_number: record.evidence.block_number(),
+        epoch: record.epoch,
+        first_header_hash: hash(record.evidence.first.hash()),
+        second_header_hash: hash(record.evidence.second.hash()),
+        submitted_at: record.submitted_at,
+        slashed_from_epoch: record.slashed_from_epoch,
+        penalty_bps: record.penalty_bps,
+        jail_epochs: record.jail_epochs,
+    }
+}
+
+/// The RPC view of a storage maintenance run
+#[cfg(feature = "admin-rpc")]
+fn maintenance_run_info(run: &MaintenanceRun) -> MaintenanceRunInfo {
+    MaintenanceRunInfo {
+        task: run.task.name().to_string(),
+        manual: run.manual,
+        started_at: run.started_at,
+        duration_ms: run.duration.as_millis() as u64,
+        error: run.error.clone(),
+    }
+}
+
+/// Convert a change request from `admin_queueValidatorChange`
+#[cfg(feature = "admin-rpc")]
+fn validator_set_change(request: ValidatorChangeRequest) -> Result<ValidatorSetChange> {
+    let parse_address = |address: &str| {
+        AccountAddress::from_hex_literal(address)
+            .map_err(|e| anyhow::anyhow!("Invalid address {}: {}", address, e))
+    };
+    let parse_stake = |stake: &str| {
+        stake
+            .parse::<u128>()
+            .map_err(|e| anyhow::anyhow!("Invalid stake {}: {}", stake, e))
+    };
+    Ok(match request {
+        ValidatorChangeRequest::Register { public_key, stake } => {
+            let public_key = hex::decode(public_key.strip_prefix("0x").unwrap_or(&public_key))?;
+            ValidatorSetChange::Register(Validator::from_public_key(
+                public_key,
+                parse_stake(&stake)?,
+            )?)
+        }
+        ValidatorChangeRequest::UpdateStake { address, stake } => ValidatorSetChange::UpdateStake {
+            address: parse_address(&address)?,
+            stake: parse_stake(&stake)?,
+        },
+        ValidatorChangeRequest::Unregister { address } => ValidatorSetChange::Unregister {
+            address: parse_address(&address)?,
+        },
+        ValidatorChangeRequest::Delegate {
+            delegator,
+            validator,
+            amount,
+        } => ValidatorSetChange::Delegate {
+            delegator: parse_address(&delegator)?,
+            validator: parse_address(&validator)?,
+            amount: parse_stake(&amount)?,
+        },
+        ValidatorChangeRequest::Undelegate {
+            delegator,
+            validator,
+        } => ValidatorSetChange::Undelegate {
+            delegator: parse_address(&delegator)?,
+            validator: parse_address(&validator)?,
+        },
+        ValidatorChangeRequest::UpdateParams {
+            block_gas_limit,
+            min_validator_stake,
+            max_validators,
+            epoch_emission,
+            dao_fee_bps,
+            burn_fee_bps,
+            max_voting_power_bps,
+        } => ValidatorSetChange::UpdateParams {
+            block_gas_limit,
+            min_validator_stake: min_validator_stake
+                .as_deref()
+                .map(parse_stake)
+                .transpose()?,
+            max_validators,
+            epoch_emission: epoch_emission.as_deref().map(parse_stake).transpose()?,
+            dao_fee_bps,
+            burn_fee_bps,
+            max_voting_power_bps,
+        },
+    })
+}
+
+/// The RPC view of transactions from an account's history
+fn included_transaction_infos(
+    db: &RoochDB,
+    transactions: Vec<(u128, SignedTransaction)>,
+) -> Result<Vec<TransactionInfo>> {
+    let mut timestamps = std::collections::HashMap::new();
+    transactions
+        .iter()
+        .map(|(block_number, tx)| {
+            let timestamp = match timestamps.get(block_number) {
+                Some(timestamp) => *timestamp,
+                None => {
+                    let timestamp = db.get_block_timestamp(*block_number)?.unwrap_or_default();
+                    timestamps.insert(*block_number, timestamp);
+                    timestamp
+                }
+            };
+            Ok(transaction_info(
+                tx,
+                "Included",
+                Some(*block_number),
+                timestamp,
+                db.get_transaction_gas_used(tx)?,
+            ))
+        })
+        .collect()
+}
+
+fn pending_transaction_info(pooled: PooledTransaction) -> PendingTransaction {
+    PendingTransaction {
+        hash: format!("0x{}", hex::encode(pooled.hash.as_bytes())),
+        sender: pooled.sender().to_hex_literal(),
+        sequence_number: pooled.sequence_number(),
+        gas_price: pooled.gas_price(),
+        gas_limit: pooled.tx.tx.gas_limit,
+        size: pooled.size,
+        raw: format!("0x{}", hex::encode(pooled.tx.encode())),
+    }
+}
+
+/// The RPC view of a transaction waiting in the pool
+fn tx_pool_transaction(pooled: &PooledTransaction) -> TxPoolTransaction {
+    TxPoolTransaction {
+        hash: format!("0x{}", hex::encode(pooled.hash.as_bytes())),
+        recipient: pooled
+            .tx
+            .tx
+            .recipient
+            .map(|recipient| recipient.to_hex_literal()),
+        amount: pooled.tx.tx.amount.to_string(),
+        gas_limit: pooled.tx.tx.gas_limit,
+        gas_price: pooled.gas_price(),
+        size: pooled.size,
+        age_seconds: pooled.received_at.elapsed().as_secs(),
+    }
+}
+
+/// The `txpool_inspect` style summary of a transaction waiting in the pool
+fn tx_pool_summary(pooled: &PooledTransaction) -> String {
+    let recipient = match pooled.tx.tx.recipient {
+        Some(recipient) => recipient.to_hex_literal(),
+        None => "no recipient".to_string(),
+    };
+    format!(
+        "{}: {} + {} gas × {}",
+        recipient,
+        pooled.tx.tx.amount,
+        pooled.tx.tx.gas_limit,
+        pooled.gas_price()
+    )
+}
+
+/// Pooled transactions keyed by sender, then sequence number
+fn tx_pool_groups<T>(
+    groups: BTreeMap<AccountAddress, Vec<&PooledTransaction>>,
+    view: impl Fn(&PooledTransaction) -> T,
+) -> BTreeMap<String, BTreeMap<u64, T>> {
+    groups
+        .into_iter()
+        .map(|(sender, txs)| {
+            let txs = txs
+                .into_iter()
+                .map(|pooled| (pooled.sequence_number(), view(pooled)))
+                .collect();
+            (sender.to_hex_literal(), txs)
+        })
+        .collect()
+}
 
 /// RPC server configuration
 #[derive(Debug, Clone)]
@@ -22,9 +753,43 @@ pub struct RpcServerConfig {
     pub max_connections: u32,
     pub max_request_body_size: u32,
     pub max_response_body_size: u32,
-    pub enable_cors: bool,
+    /// Origins, headers and methods browsers may use on the public listener and the
+    /// REST gateway
+    pub cors: CorsConfig,
     pub enable_ws: bool,
+    /// Namespaces left out of the RPC module, see `OPTIONAL_RPC_NAMESPACES`
+    pub disabled_namespaces: Vec<String>,
+    /// Serve the opt-in `experimental` namespace, see `EXPERIMENTAL_METHODS`
+    pub enable_experimental: bool,
     pub batch_requests_limit: u32,
+    /// Maximum number of blocks fetched by a single `getBlocksByNumbers` call
+    pub max_blocks_per_batch: usize,
+    /// Optional Unix domain socket serving the same methods to local tooling (CLI,
+    /// monitoring agents) without the rate limits and API keys of the public listener.
+    /// Only the node's user may open it, the socket file is created with mode 0600.
+    pub ipc_path: Option<PathBuf>,
+    /// Optional REST gateway forwarding a few routes to the same method implementations,
+    /// for integrators that do not speak JSON-RPC. Requires the `rest` feature.
+    pub rest_listen_address: Option<SocketAddr>,
+    /// Optional Unix domain socket on which external plugins receive the event stream
+    /// and submit transactions, see `plugin`
+    pub plugins: Option<PluginServerConfig>,
+    /// Optional listener serving Prometheus metrics at `/metrics`: the registry given
+    /// with `with_metrics_registry` and the request counts and latencies of the public listener
+    pub metrics_listen_address: Option<SocketAddr>,
+    /// Token bucket limits for the public listener, keyed by client IP
+    pub rate_limit: RateLimitConfig,
+    /// API keys required by the admin and debug namespaces on the public listener
+    pub auth: RpcAuthConfig,
+    /// Subscriptions a WebSocket connection to the public listener may hold open
+    pub max_subscriptions_per_connection: u32,
+    /// Messages buffered for a WebSocket connection to the public listener before
+    /// sending to it waits, bounding the memory a slow client holds
+    pub subscription_buffer_capacity: u32,
+    /// What subscriptions do when their client does not keep up with its buffer
+    pub slow_subscriber_policy: SlowSubscriberPolicy,
+    /// Size and lifetime of the cache of confirmed blocks, receipts and modules
+    pub response_cache: ResponseCacheConfig,
 }
 
 impl Default for RpcServerConfig {
@@ -34,13 +799,36 @@ impl Default for RpcServerConfig {
             max_connections: 100,
             max_request_body_size: 10 * 1024 * 1024,  // 10MB
             max_response_body_size: 10 * 1024 * 1024, // 10MB
-            enable_cors: true,
+            cors: CorsConfig::default(),
             enable_ws: true,
+            disabled_namespaces: vec![],
+            enable_experimental: false,
             batch_requests_limit: 50,
+            max_blocks_per_batch: DEFAULT_MAX_BLOCKS_PER_BATCH,
+            ipc_path: None,
+            rest_listen_address: None,
+            plugins: None,
+            metrics_listen_address: None,
+            rate_limit: RateLimitConfig::default(),
+            auth: RpcAuthConfig::default(),
+            max_subscriptions_per_connection: DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION,
+            subscription_buffer_capacity: DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY,
+            slow_subscriber_policy: SlowSubscriberPolicy::default(),
+            response_cache: ResponseCacheConfig::default(),
         }
     }
 }
 
+impl RpcServerConfig {
+    /// Whether the methods of `namespace` are registered
+    pub fn namespace_enabled(&self, namespace: &str) -> bool {
+        !self
+            .disabled_namespaces
+            .iter()
+            .any(|disabled| disabled == namespace)
+    }
+}
+
 /// Node state for RPC operations
 #[derive(Debug, Clone)]
 pub struct NodeState {
@@ -51,6 +839,44 @@ pub struct NodeState {
     pub peer_count: usize,
     pub block_height: u128,
     pub uptime_start: SystemTime,
+    /// Peer ranking by block propagation latency, fastest first
+    pub peer_propagation_stats: Vec<PeerPropagationInfo>,
+    /// Connected peers and their negotiated capabilities
+    pub peers: Vec<ConnectedPeerInfo>,
+    /// Recent P2P messages, bounded by the node's history retention policy
+    pub message_history: MessageHistoryInfo,
+    /// Operator metadata the node publishes
+    pub operator: Option<OperatorInfo>,
+    /// Ports the node actually listens on, which differ from the configured ones
+    /// when `--port-auto` had to move them
+    pub rpc_port: Option<u16>,
+    pub p2p_port: Option<u16>,
+    /// Sync progress from the P2P sync subsystem
+    pub sync_status: SyncStatusInfo,
+    /// Median offset of the peers' clocks from the local clock in milliseconds, and
+    /// the number of peers it is based on
+    pub time_offset_ms: i64,
+    pub time_samples: usize,
+    /// Set while the node is in emergency read-only mode because its disk is almost full
+    pub read_only: bool,
+    /// Chain parameters overridden for local experimentation, by key
+    pub param_overrides: BTreeMap<String, u64>,
+}
+
+impl NodeState {
+    /// Milliseconds since the Unix epoch according to the local clock
+    fn local_time_ms() -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+
+    /// The local clock corrected by the network time offset, in seconds since the
+    /// Unix epoch. Block timestamps are produced and validated against it.
+    pub fn network_time_secs(&self) -> u64 {
+        Self::local_time_ms().saturating_add_signed(self.time_offset_ms) / 1000
+    }
 }
 
 impl Default for NodeState {
@@ -63,15 +889,66 @@ impl Default for NodeState {
             peer_count: 0,
             block_height: 0,
             uptime_start: SystemTime::now(),
+            peer_propagation_stats: vec![],
+            peers: vec![],
+            message_history: MessageHistoryInfo::default(),
+            operator: None,
+            rpc_port: None,
+            p2p_port: None,
+            sync_status: SyncStatusInfo::default(),
+            time_offset_ms: 0,
+            time_samples: 0,
+            read_only: false,
+            param_overrides: BTreeMap::new(),
         }
     }
 }
 
+/// Runtime control of the node's tracing filter, provided by the node binary
+pub trait LogLevelController: Send + Sync {
+    fn set_level(&self, target: &str, level: &str) -> Result<()>;
+    fn levels(&self) -> BTreeMap<String, String>;
+}
+
+/// Builds the next block without saving it, provided by the node binary
+pub trait BlockBuilder: Send + Sync {
+    /// `pending` is a snapshot of the pool's executable transactions
+    fn dry_run(&self, timestamp: u64, pending: &[PooledTransaction]) -> Result<ProposalDryRun>;
+}
+
+/// Runs Move view functions against a state root, provided by the node binary
+pub trait ViewFunctionExecutor: Send + Sync {
+    fn execute(&self, root: ObjectMeta, call: FunctionCall) -> Result<ViewFunctionResult>;
+}
+
 /// RPC server implementation
 pub struct KanariRpcServer {
     config: RpcServerConfig,
     node_state: Arc<RwLock<NodeState>>,
+    tx_pool: Arc<RwLock<TxPool>>,
+    db: Option<Arc<RoochDB>>,
+    #[cfg_attr(not(feature = "admin-rpc"), allow(dead_code))]
+    log_controller: Option<Arc<dyn LogLevelController>>,
+    #[cfg_attr(not(feature = "admin-rpc"), allow(dead_code))]
+    maintenance: Option<Arc<MaintenanceScheduler>>,
+    #[cfg_attr(not(feature = "admin-rpc"), allow(dead_code))]
+    block_builder: Option<Arc<dyn BlockBuilder>>,
+    api_keys: Option<Arc<ApiKeyStore>>,
+    view_executor: Option<Arc<dyn ViewFunctionExecutor>>,
+    block_proposers: Vec<Vec<u8>>,
+    import_lock: Arc<tokio::sync::Mutex<()>>,
+    events: broadcast::Sender<SubscriptionEvent>,
+    block_cache: Arc<BlockCache>,
+    response_cache: Arc<ResponseCache>,
+    state_bus: NodeStateBus,
+    /// Taken by `start`, which applies the events of the bus to the node state
+    state_events: Arc<std::sync::Mutex<Option<mpsc::UnboundedReceiver<NodeStateEvent>>>>,
+    metrics_registry: Option<Registry>,
     server_handle: Option<ServerHandle>,
+    ipc_server_handle: Option<ServerHandle>,
+    rest_server_handle: Option<ServerHandle>,
+    plugin_server_handle: Option<ServerHandle>,
+    metrics_server_handle: Option<ServerHandle>,
 }
 
 impl Clone for KanariRpcServer {
@@ -79,7 +956,26 @@ impl Clone for KanariRpcServer {
         Self {
             config: self.config.clone(),
             node_state: self.node_state.clone(),
+            tx_pool: self.tx_pool.clone(),
+            db: self.db.clone(),
+            log_controller: self.log_controller.clone(),
+            maintenance: self.maintenance.clone(),
+            block_builder: self.block_builder.clone(),
+            api_keys: self.api_keys.clone(),
+            view_executor: self.view_executor.clone(),
+            block_proposers: self.block_proposers.clone(),
+            import_lock: self.import_lock.clone(),
+            events: self.events.clone(),
+            block_cache: self.block_cache.clone(),
+            response_cache: self.response_cache.clone(),
+            state_bus: self.state_bus.clone(),
+            state_events: self.state_events.clone(),
+            metrics_registry: self.metrics_registry.clone(),
             server_handle: None, // Server handle cannot be cloned
+            ipc_server_handle: None,
+            rest_server_handle: None,
+            plugin_server_handle: None,
+            metrics_server_handle: None,
         }
     }
 }
@@ -87,13 +983,108 @@ impl Clone for KanariRpcServer {
 impl KanariRpcServer {
     /// Create a new RPC server
     pub fn new(config: RpcServerConfig) -> Self {
+        let (state_bus, state_events) = NodeStateBus::new();
         Self {
+            response_cache: Arc::new(ResponseCache::new(config.response_cache.clone())),
+            state_bus,
+            state_events: Arc::new(std::sync::Mutex::new(Some(state_events))),
             config,
             node_state: Arc::new(RwLock::new(NodeState::default())),
+            tx_pool: Arc::new(RwLock::new(TxPool::default())),
+            db: None,
+            log_controller: None,
+            maintenance: None,
+            block_builder: None,
+            api_keys: None,
+            view_executor: None,
+            block_proposers: vec![],
+            import_lock: Arc::new(tokio::sync::Mutex::new(())),
+            events: broadcast::channel(SUBSCRIPTION_CHANNEL_CAPACITY).0,
+            block_cache: Arc::new(BlockCache::default()),
+            metrics_registry: None,
             server_handle: None,
+            ipc_server_handle: None,
+            rest_server_handle: None,
+            plugin_server_handle: None,
+            metrics_server_handle: None,
         }
     }
 
+    /// Serve the metrics of `registry`, e.g. the database metrics, on the metrics listener
+    pub fn with_metrics_registry(mut self, registry: Registry) -> Self {
+        self.metrics_registry = Some(registry);
+        self
+    }
+
+    /// Allow `admin_setLogLevel` to change the node's tracing filter
+    pub fn with_log_controller(mut self, controller: Arc<dyn LogLevelController>) -> Self {
+        self.log_controller = Some(controller);
+        self
+    }
+
+    /// Allow `admin_runMaintenance` to trigger the storage maintenance of the scheduler
+    pub fn with_maintenance(mut self, scheduler: Arc<MaintenanceScheduler>) -> Self {
+        self.maintenance = Some(scheduler);
+        self
+    }
+
+    /// Allow `admin_proposeDryRun` to build the next block with the node's production path
+    pub fn with_block_builder(mut self, builder: Arc<dyn BlockBuilder>) -> Self {
+        self.block_builder = Some(builder);
+        self
+    }
+
+    /// Accept the API keys of `store` on the public listener, with their scopes and
+    /// rate limits, and manage them through the admin namespace. The auth config must
+    /// protect the admin namespace, or `start` fails.
+    pub fn with_api_keys(mut self, store: Arc<ApiKeyStore>) -> Self {
+        self.api_keys = Some(store);
+        self
+    }
+
+    /// Allow `kanari_executeViewFunction` to run Move view functions with the node's VM
+    pub fn with_view_executor(mut self, executor: Arc<dyn ViewFunctionExecutor>) -> Self {
+        self.view_executor = Some(executor);
+        self
+    }
+
+    /// Accept blocks from `kanari_submitBlock` signed by these secp256k1 public keys.
+    /// Without any, external block submission is disabled.
+    pub fn with_block_proposers(mut self, public_keys: Vec<Vec<u8>>) -> Self {
+        self.block_proposers = public_keys;
+        self
+    }
+
+    /// Apply per-sender spam protection rules to the transaction pool
+    pub fn with_mempool_limits(mut self, limits: MempoolLimits) -> Self {
+        self.tx_pool = Arc::new(RwLock::new(TxPool::default().with_limits(limits)));
+        self
+    }
+
+    /// Serve chain data from the node database
+    pub fn with_db(mut self, db: Arc<RoochDB>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// The `kanari` namespace implementation over the server's node state, pool,
+    /// database and subscription channel, for other transports to forward to
+    pub fn kanari_rpc_impl(&self) -> KanariRpcImpl {
+        KanariRpcImpl::new(
+            self.node_state.clone(),
+            self.tx_pool.clone(),
+            self.db.clone(),
+        )
+        .with_block_proposers(self.block_proposers.clone())
+        .with_import_lock(self.import_lock.clone())
+        .with_events(self.events.clone())
+        .with_state_bus(self.state_bus.clone())
+        .with_block_cache(self.block_cache.clone())
+        .with_response_cache(self.response_cache.clone())
+        .with_max_blocks_per_batch(self.config.max_blocks_per_batch)
+        .with_view_executor(self.view_executor.clone())
+    }
+
     /// Start the RPC server
     pub async fn start(&mut self) -> Result<()> {
         info!(
@@ -101,38 +1092,416 @@ impl KanariRpcServer {
             self.config.listen_address
         );
 
-        let server = ServerBuilder::default()
-            .max_connections(self.config.max_connections)
-            .max_request_body_size(self.config.max_request_body_size)
-            .max_response_body_size(self.config.max_response_body_size)
-            .build(self.config.listen_address)
-            .await?;
-
         let mut module = RpcModule::new(());
 
+        let state_events = self
+            .state_events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(state_events) = state_events {
+            tokio::spawn(run_node_state_service(
+                state_events,
+                self.node_state.clone(),
+                self.events.clone(),
+                self.db.clone(),
+            ));
+        }
+
         // Create API implementations
-        let kanari_impl = KanariRpcImpl::new(self.node_state.clone());
-        let admin_impl = AdminRpcImpl::new(self.node_state.clone());
-        let debug_impl = DebugRpcImpl::new(self.node_state.clone());
+        let kanari_impl = self.kanari_rpc_impl();
+        let mut subscription_impl = SubscriptionRpcImpl::new(self.events.clone())
+            .with_slow_subscriber_policy(self.config.slow_subscriber_policy);
+        if let Some(db) = &self.db {
+            subscription_impl = subscription_impl.with_replay(db.clone(), self.node_state.clone());
+        }
+
+        let eth_impl = EthRpcImpl::new(
+            self.node_state.clone(),
+            self.tx_pool.clone(),
+            self.db.clone(),
+        )
+        .with_events(self.events.clone())
+        .with_state_bus(self.state_bus.clone());
 
-        // Register API methods
+        // Whoever may call the key management methods may mint keys for any namespace
+        if self.api_keys.is_some()
+            && !(self.config.auth.is_enabled()
+                && self.config.auth.is_protected("admin_createApiKey"))
+        {
+            anyhow::bail!(
+                "Stored API keys require RPC API keys protecting the admin namespace, which manages them"
+            );
+        }
+
+        // Register API methods, leaving out the namespaces the operator disabled
+        if let Some(namespace) = self
+            .config
+            .disabled_namespaces
+            .iter()
+            .find(|namespace| !OPTIONAL_RPC_NAMESPACES.contains(&namespace.as_str()))
+        {
+            anyhow::bail!(
+                "RPC namespace {} cannot be disabled, only {}",
+                namespace,
+                OPTIONAL_RPC_NAMESPACES.join(", ")
+            );
+        }
         module.merge(kanari_impl.into_rpc())?;
-        module.merge(admin_impl.into_rpc())?;
-        module.merge(debug_impl.into_rpc())?;
+        if self.config.namespace_enabled("eth") {
+            module.merge(eth_impl.into_rpc())?;
+        }
+        #[cfg(feature = "admin-rpc")]
+        if self.config.namespace_enabled("admin") {
+            module.merge(
+                AdminRpcImpl::new(self.node_state.clone(), self.log_controller.clone())
+                    .with_db(self.db.clone())
+                    .with_maintenance(self.maintenance.clone())
+                    .with_block_builder(self.block_builder.clone())
+                    .with_tx_pool(self.tx_pool.clone())
+                    .with_api_keys(self.api_keys.clone())
+                    .into_rpc(),
+            )?;
+        }
+        #[cfg(feature = "debug-rpc")]
+        if self.config.namespace_enabled("debug") {
+            module.merge(
+                DebugRpcImpl::new(self.node_state.clone())
+                    .with_db(self.db.clone())
+                    .into_rpc(),
+            )?;
+        }
+        if self.config.enable_experimental {
+            module.merge(ExperimentalRpcImpl::new(self.db.clone()).into_rpc())?;
+        }
+        module.merge(
+            DiscoverRpcImpl::new(
+                env!("CARGO_PKG_VERSION"),
+                &self.config.disabled_namespaces,
+                self.config.enable_experimental,
+            )
+            .into_rpc(),
+        )?;
+        if self.config.enable_ws && self.config.namespace_enabled("subscribe") {
+            module.merge(subscription_impl.into_rpc())?;
+        }
+        if !self.config.disabled_namespaces.is_empty() {
+            info!(
+                "RPC namespaces disabled: {}",
+                self.config.disabled_namespaces.join(", ")
+            );
+        }
+        if self.config.enable_experimental {
+            warn!("Experimental RPC methods enabled, they may change in any release");
+        }
+
+        // Start the socket for local tooling before the public listener takes the module
+        if let Some(ipc_path) = &self.config.ipc_path {
+            self.ipc_server_handle = Some(self.start_ipc(ipc_path, module.clone().into())?);
+            info!("Kanari IPC RPC listener started on {}", ipc_path.display());
+        }
+        if let Some(rest_address) = self.config.rest_listen_address {
+            self.rest_server_handle = Some(self.start_rest(rest_address).await?);
+            info!("Kanari REST gateway started on http://{}", rest_address);
+        }
+        if let Some(plugins) = &self.config.plugins {
+            let path = plugins.path.clone();
+            let count = plugins.plugins.len();
+            self.plugin_server_handle = Some(start_plugin_server(
+                plugins.clone(),
+                self.kanari_rpc_impl(),
+                self.events.clone(),
+            )?);
+            info!(
+                "Kanari plugin socket started on {} for {} plugin(s)",
+                path.display(),
+                count
+            );
+        }
+
+        let rpc_metrics = match self.config.metrics_listen_address {
+            Some(metrics_address) => {
+                let registry = self.metrics_registry.clone().unwrap_or_default();
+                let rpc_metrics = Arc::new(RpcMetrics::new(&registry)?);
+                self.metrics_server_handle =
+                    Some(start_metrics_server(metrics_address, registry).await?);
+                info!(
+                    "Kanari metrics served on http://{}/metrics",
+                    metrics_address
+                );
+                Some(rpc_metrics)
+            }
+            None => None,
+        };
 
         // Start server
-        let handle = server.start(module);
+        if self.config.cors.permissive {
+            warn!("RPC CORS is permissive, any website may call the node from a browser");
+        } else if self.config.cors.is_enabled() {
+            info!(
+                "RPC CORS allows origins {:?}",
+                self.config.cors.allowed_origins
+            );
+        }
+        let handle = if self.config.rate_limit.is_enabled()
+            || self.config.auth.is_enabled()
+            || self.api_keys.is_some()
+            || rpc_metrics.is_some()
+        {
+            self.start_with_middleware(module.into(), rpc_metrics)
+                .await?
+        } else {
+            ServerBuilder::default()
+                .set_http_middleware(tower::ServiceBuilder::new().layer(self.config.cors.layer()?))
+                .set_rpc_middleware(RpcServiceBuilder::new().layer_fn(RequestIdService::new))
+                .max_connections(self.config.max_connections)
+                .max_subscriptions_per_connection(self.config.max_subscriptions_per_connection)
+                .set_message_buffer_capacity(self.config.subscription_buffer_capacity)
+                .max_request_body_size(self.config.max_request_body_size)
+                .max_response_body_size(self.config.max_response_body_size)
+                .build(self.config.listen_address)
+                .await?
+                .start(module)
+        };
         self.server_handle = Some(handle);
 
-        info!("Kanari RPC server started successfully on http://{}", self.config.listen_address);
+        info!(
+            "Kanari RPC server started successfully on http://{}",
+            self.config.listen_address
+        );
         Ok(())
     }
 
+    /// Serve the public listener with the metrics, rate limiting and authentication
+    /// middleware, which need the client IP and the HTTP headers and therefore their own
+    /// accept loop
+    async fn start_with_middleware(
+        &self,
+        methods: Methods,
+        rpc_metrics: Option<Arc<RpcMetrics>>,
+    ) -> Result<ServerHandle> {
+        let listener = tokio::net::TcpListener::bind(self.config.listen_address).await?;
+        let limiter = Arc::new(RateLimiter::new(self.config.rate_limit.clone()));
+        let auth = Arc::new(self.config.auth.clone());
+        let api_keys = self.api_keys.clone();
+        let service_builder = ServerBuilder::default()
+            .set_http_middleware(tower::ServiceBuilder::new().layer(self.config.cors.layer()?))
+            .max_connections(self.config.max_connections)
+            .max_subscriptions_per_connection(self.config.max_subscriptions_per_connection)
+            .set_message_buffer_capacity(self.config.subscription_buffer_capacity)
+            .max_request_body_size(self.config.max_request_body_size)
+            .max_response_body_size(self.config.max_response_body_size)
+            .to_service_builder();
+        let (stop_handle, server_handle) = stop_channel();
+        if self.config.rate_limit.is_enabled() {
+            info!(
+                "RPC rate limiting enabled (per IP: {:?}, {} method limit(s))",
+                self.config.rate_limit.per_ip,
+                self.config.rate_limit.per_method.len()
+            );
+        }
+        if auth.is_enabled() {
+            info!(
+                "RPC namespaces {:?} require an API key",
+                auth.protected_namespaces
+            );
+        }
+        if let Some(api_keys) = &api_keys {
+            info!("RPC accepts {} stored API key(s)", api_keys.list().len());
+        }
+
+        tokio::spawn(async move {
+            loop {
+                let (socket, remote_addr) = tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            warn!("Failed to accept RPC connection: {}", e);
+                            continue;
+                        }
+                    },
+                    _ = stop_handle.clone().shutdown() => break,
+                };
+                let ip = remote_addr.ip();
+                let limiter = limiter.clone();
+                let auth = auth.clone();
+                let api_keys = api_keys.clone();
+                let rpc_metrics = rpc_metrics.clone();
+                let methods = methods.clone();
+                let service_builder = service_builder.clone();
+                let connection_stop_handle = stop_handle.clone();
+                // Headers are checked per HTTP request, and once for the upgrade request
+                // of a WebSocket connection
+                let service =
+                    tower::service_fn(move |request: hyper::Request<hyper::body::Incoming>| {
+                        // A stored key opens the protected namespaces its scope allows
+                        let api_key = api_keys.as_ref().and_then(|api_keys| {
+                            api_keys
+                                .authenticate(request.headers())
+                                .map(|id| (id, api_keys.clone()))
+                        });
+                        let authorized = api_key.is_some() || auth.authorize(request.headers());
+                        let limiter = limiter.clone();
+                        let auth = auth.clone();
+                        let key_auth = auth.clone();
+                        let rpc_metrics = rpc_metrics.clone();
+                        let rpc_middleware = RpcServiceBuilder::new()
+                            .layer_fn(RequestIdService::new)
+                            .layer_fn(move |service| {
+                                MetricsService::new(service, rpc_metrics.clone())
+                            })
+                            .layer_fn(move |service| {
+                                RateLimitService::new(service, ip, limiter.clone())
+                            })
+                            .layer_fn(move |service| {
+                                ApiKeyService::new(service, api_key.clone(), key_auth.clone())
+                            })
+                            .layer_fn(move |service| {
+                                AuthService::new(service, authorized, auth.clone())
+                            });
+                        let mut service = service_builder
+                            .clone()
+                            .set_rpc_middleware(rpc_middleware)
+                            .build(methods.clone(), connection_stop_handle.clone());
+                        async move { service.call(request).await }
+                    });
+                tokio::spawn(serve_with_graceful_shutdown(
+                    socket,
+                    service,
+                    stop_handle.clone().shutdown(),
+                ));
+            }
+        });
+        Ok(server_handle)
+    }
+
+    /// Serve the methods over a Unix domain socket, only accessible to the node's user
+    #[cfg(unix)]
+    fn start_ipc(&self, path: &Path, methods: Methods) -> Result<ServerHandle> {
+        use std::os::unix::fs::PermissionsExt;
+
+        // A socket file left behind by a node that did not shut down cleanly
+        if path.exists() {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                anyhow::bail!("IPC path {} is in use by another process", path.display());
+            }
+            std::fs::remove_file(path)?;
+        }
+        let listener = tokio::net::UnixListener::bind(path)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+
+        let service_builder = ServerBuilder::default()
+            .set_rpc_middleware(RpcServiceBuilder::new().layer_fn(RequestIdService::new))
+            .max_request_body_size(self.config.max_request_body_size)
+            .max_response_body_size(self.config.max_response_body_size)
+            .to_service_builder();
+        let (stop_handle, server_handle) = stop_channel();
+        let path = path.to_path_buf();
+        tokio::spawn(async move {
+            loop {
+                let socket = tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((socket, _)) => socket,
+                        Err(e) => {
+                            warn!("Failed to accept IPC connection: {}", e);
+                            continue;
+                        }
+                    },
+                    _ = stop_handle.clone().shutdown() => break,
+                };
+                let service = service_builder
+                    .clone()
+                    .build(methods.clone(), stop_handle.clone());
+                tokio::spawn(serve_with_graceful_shutdown(
+                    socket,
+                    service,
+                    stop_handle.clone().shutdown(),
+                ));
+            }
+            if let Err(e) = std::fs::remove_file(&path) {
+                warn!("Failed to remove IPC socket {}: {}", path.display(), e);
+            }
+        });
+        Ok(server_handle)
+    }
+
+    /// Serve the REST gateway, subject to the rate limits of the public listener
+    #[cfg(feature = "rest")]
+    async fn start_rest(&self, address: SocketAddr) -> Result<ServerHandle> {
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        let kanari_impl = self.kanari_rpc_impl();
+        let limiter = self
+            .config
+            .rate_limit
+            .is_enabled()
+            .then(|| Arc::new(RateLimiter::new(self.config.rate_limit.clone())));
+        let router = crate::rest::rest_router(
+            kanari_impl,
+            limiter,
+            self.config.max_request_body_size as usize,
+        )
+        .layer(self.config.cors.layer()?);
+        let (stop_handle, server_handle) = stop_channel();
+        tokio::spawn(async move {
+            let serve = axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(stop_handle.shutdown());
+            if let Err(e) = serve.await {
+                warn!("REST gateway failed: {}", e);
+            }
+        });
+        Ok(server_handle)
+    }
+
+    #[cfg(not(feature = "rest"))]
+    async fn start_rest(&self, address: SocketAddr) -> Result<ServerHandle> {
+        anyhow::bail!(
+            "REST gateway address {} is set, but the node was built without the `rest` feature",
+            address
+        )
+    }
+
+    #[cfg(not(unix))]
+    fn start_ipc(&self, path: &Path, _methods: Methods) -> Result<ServerHandle> {
+        anyhow::bail!(
+            "IPC path {} is not supported, Unix domain sockets require a Unix platform",
+            path.display()
+        )
+    }
+
     /// Stop the RPC server
     pub async fn stop(&mut self) {
         if let Some(handle) = self.server_handle.take() {
-            handle.stop().unwrap();
-            info!("Kanari RPC server stopped");
+            match handle.stop() {
+                Ok(()) => info!("Kanari RPC server stopped"),
+                Err(e) => warn!("Failed to stop the Kanari RPC server: {}", e),
+            }
+        }
+        if let Some(handle) = self.ipc_server_handle.take() {
+            match handle.stop() {
+                Ok(()) => info!("Kanari IPC RPC listener stopped"),
+                Err(e) => warn!("Failed to stop the Kanari IPC RPC listener: {}", e),
+            }
+        }
+        if let Some(handle) = self.rest_server_handle.take() {
+            match handle.stop() {
+                Ok(()) => info!("Kanari REST gateway stopped"),
+                Err(e) => warn!("Failed to stop the Kanari REST gateway: {}", e),
+            }
+        }
+        if let Some(handle) = self.plugin_server_handle.take() {
+            match handle.stop() {
+                Ok(()) => info!("Kanari plugin socket stopped"),
+                Err(e) => warn!("Failed to stop the Kanari plugin socket: {}", e),
+            }
+        }
+        if let Some(handle) = self.metrics_server_handle.take() {
+            match handle.stop() {
+                Ok(()) => info!("Kanari metrics listener stopped"),
+                Err(e) => warn!("Failed to stop the Kanari metrics listener: {}", e),
+            }
         }
     }
 
@@ -149,168 +1518,1490 @@ impl KanariRpcServer {
     pub fn address(&self) -> SocketAddr {
         self.config.listen_address
     }
-    
+
     /// Get node state for external access
     pub fn get_node_state(&self) -> Arc<RwLock<NodeState>> {
         self.node_state.clone()
     }
+
+    /// The bus the node's subsystems report their changes to, applied to the node state
+    /// once the server started
+    pub fn state_bus(&self) -> NodeStateBus {
+        self.state_bus.clone()
+    }
+
+    /// Get the transaction pool shared with the node
+    pub fn get_tx_pool(&self) -> Arc<RwLock<TxPool>> {
+        self.tx_pool.clone()
+    }
+
+    /// The lock serializing block imports, which the node's block production holds too
+    pub fn import_lock(&self) -> Arc<tokio::sync::Mutex<()>> {
+        self.import_lock.clone()
+    }
+
+    /// Receive the events delivered to WebSocket subscribers, such as the blocks the node
+    /// produced or imported
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SubscriptionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Deliver an event to WebSocket subscribers. Events published while nobody is
+    /// subscribed are dropped.
+    pub fn publish(&self, event: SubscriptionEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// Cache a block the node persisted as the chain tip and publish it to `newBlocks`
+    /// subscribers
+    pub fn publish_new_block(&self, block_number: u128) -> Result<()> {
+        let Some(db) = &self.db else {
+            return Ok(());
+        };
+        let block = db
+            .get_block(block_number)?
+            .ok_or_else(|| anyhow::anyhow!("Block #{} not found", block_number))?;
+        let info = block_info(db, block)?;
+        self.block_cache.insert(info.clone());
+        if self.events.receiver_count() > 0 {
+            self.publish(SubscriptionEvent::NewBlock(info));
+        }
+        Ok(())
+    }
 }
 
 /// Kanari RPC API implementation
 pub struct KanariRpcImpl {
     node_state: Arc<RwLock<NodeState>>,
+    tx_pool: Arc<RwLock<TxPool>>,
+    db: Option<Arc<RoochDB>>,
+    block_proposers: Vec<Vec<u8>>,
+    /// Serializes block imports so two blocks can not claim the same height
+    import_lock: Arc<tokio::sync::Mutex<()>>,
+    events: Option<broadcast::Sender<SubscriptionEvent>>,
+    state_bus: Option<NodeStateBus>,
+    block_cache: Arc<BlockCache>,
+    response_cache: Arc<ResponseCache>,
+    max_blocks_per_batch: usize,
+    view_executor: Option<Arc<dyn ViewFunctionExecutor>>,
 }
 
 impl KanariRpcImpl {
-    pub fn new(node_state: Arc<RwLock<NodeState>>) -> Self {
-        Self { node_state }
+    pub fn new(
+        node_state: Arc<RwLock<NodeState>>,
+        tx_pool: Arc<RwLock<TxPool>>,
+        db: Option<Arc<RoochDB>>,
+    ) -> Self {
+        Self {
+            node_state,
+            tx_pool,
+            db,
+            block_proposers: vec![],
+            import_lock: Arc::new(tokio::sync::Mutex::new(())),
+            events: None,
+            state_bus: None,
+            block_cache: Arc::new(BlockCache::default()),
+            response_cache: Arc::new(ResponseCache::default()),
+            max_blocks_per_batch: DEFAULT_MAX_BLOCKS_PER_BATCH,
+            view_executor: None,
+        }
     }
-}
-
-#[async_trait]
-impl KanariRpcApiServer for KanariRpcImpl {
-    async fn get_node_info(&self) -> RpcResult<NodeInfo> {
-        let state = self.node_state.read().await;
-        let uptime = SystemTime::now()
-            .duration_since(state.uptime_start)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
 
-        Ok(NodeInfo {
-            version: state.node_version.clone(),
-            chain_id: state.chain_id,
-            node_type: state.node_type.clone(),
-            peer_count: state.peer_count,
-            block_height: state.block_height,
-            is_syncing: state.is_syncing,
-            uptime_seconds: uptime,
-        })
+    /// Share the import lock of the server, which its block production holds too
+    pub fn with_import_lock(mut self, import_lock: Arc<tokio::sync::Mutex<()>>) -> Self {
+        self.import_lock = import_lock;
+        self
     }
 
-    async fn get_account(&self, address: String) -> RpcResult<AccountInfo> {
-        // TODO: Implement actual account lookup
-        warn!("get_account not fully implemented yet");
-
-        Ok(AccountInfo {
-            address,
-            balance: "0".to_string(),
-            sequence_number: 0,
-            authentication_key: "0x00".to_string(),
-        })
+    /// Share the recent blocks cache of the server, which its block production fills
+    pub fn with_block_cache(mut self, block_cache: Arc<BlockCache>) -> Self {
+        self.block_cache = block_cache;
+        self
     }
 
-    async fn get_balance(
-        &self,
-        address: String,
-        coin_type: Option<String>,
-    ) -> RpcResult<BalanceInfo> {
-        // TODO: Implement actual balance lookup
-        warn!("get_balance not fully implemented yet");
+    /// Share the response cache of the server
+    pub fn with_response_cache(mut self, response_cache: Arc<ResponseCache>) -> Self {
+        self.response_cache = response_cache;
+        self
+    }
 
-        let coin_type = coin_type.unwrap_or_else(|| "KARI".to_string());
+    pub fn with_max_blocks_per_batch(mut self, max_blocks_per_batch: usize) -> Self {
+        self.max_blocks_per_batch = max_blocks_per_batch;
+        self
+    }
 
-        Ok(BalanceInfo {
-            address,
-            coin_type,
-            balance: "0".to_string(),
-            decimals: 8,
-        })
+    /// Run Move view functions with the node's VM
+    pub fn with_view_executor(mut self, executor: Option<Arc<dyn ViewFunctionExecutor>>) -> Self {
+        self.view_executor = executor;
+        self
     }
 
-    async fn get_block_by_number(&self, block_number: u128) -> RpcResult<BlockInfo> {
-        // TODO: Implement actual block lookup
-        warn!("get_block_by_number not fully implemented yet");
-
-        Ok(BlockInfo {
-            number: block_number,
-            hash: format!("0x{:064x}", block_number),
-            parent_hash: format!("0x{:064x}", block_number.saturating_sub(1)),
-            timestamp: SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-            transaction_count: 0,
-            gas_used: 0,
-            gas_limit: 1000000,
-            state_root: "0x0000000000000000000000000000000000000000000000000000000000000000"
-                .to_string(),
-        })
+    /// Publish accepted transactions and imported blocks to WebSocket subscribers
+    pub fn with_events(mut self, events: broadcast::Sender<SubscriptionEvent>) -> Self {
+        self.events = Some(events);
+        self
     }
 
-    async fn get_block_by_hash(&self, _block_hash: String) -> RpcResult<BlockInfo> {
-        // TODO: Implement actual block lookup by hash
-        warn!("get_block_by_hash not fully implemented yet");
+    /// Report accepted transactions to the node state bus
+    pub fn with_state_bus(mut self, state_bus: NodeStateBus) -> Self {
+        self.state_bus = Some(state_bus);
+        self
+    }
 
-        // For now, just return the latest block
-        let state = self.node_state.read().await;
-        self.get_block_by_number(state.block_height).await
+    fn publish(&self, event: SubscriptionEvent) {
+        if let Some(events) = &self.events {
+            let _ = events.send(event);
+        }
     }
 
-    async fn get_latest_block(&self) -> RpcResult<BlockInfo> {
-        let state = self.node_state.read().await;
-        self.get_block_by_number(state.block_height).await
-    }
-
-    async fn get_transaction(&self, tx_hash: String) -> RpcResult<TransactionInfo> {
-        // TODO: Implement actual transaction lookup
-        warn!("get_transaction not fully implemented yet");
-
-        Ok(TransactionInfo {
-            hash: tx_hash,
-            sender: "0x0000000000000000000000000000000000000000".to_string(),
-            recipient: Some("0x0000000000000000000000000000000000000001".to_string()),
-            amount: "0".to_string(),
-            gas_used: 21000,
-            gas_price: 1,
-            status: "Pending".to_string(),
-            block_number: None,
-            timestamp: SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-        })
+    /// Report a transaction the pool accepted to the node state bus, or straight to
+    /// subscribers when there is none
+    fn transaction_admitted(&self, summary: TransactionInfo) {
+        match &self.state_bus {
+            Some(state_bus) => state_bus.publish(NodeStateEvent::TransactionAdmitted(summary)),
+            None => self.publish(SubscriptionEvent::NewTransaction(summary)),
+        }
     }
 
-    async fn send_transaction(&self, _tx_request: TransactionRequest) -> RpcResult<String> {
-        // TODO: Implement actual transaction sending
-        warn!("send_transaction not fully implemented yet");
+    /// Report an imported block as the new head to the node state bus, or straight to the
+    /// node state when there is none
+    async fn block_imported(&self, block_number: u128) {
+        match &self.state_bus {
+            Some(state_bus) => state_bus.publish(NodeStateEvent::BlockHeight(block_number)),
+            None => self.node_state.write().await.block_height = block_number,
+        }
+    }
 
-        // Generate a more realistic transaction hash
-        let mut hasher = DefaultHasher::new();
-        hasher.write(format!("tx_{}", SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_nanos()).as_bytes());
-        let tx_hash = format!("0x{:064x}", hasher.finish());
+    /// Fee suggestions from the recent blocks and the pending transactions of the pool,
+    /// with the number of pending transactions
+    async fn fee_estimate(&self) -> RpcResult<(FeeEstimate, usize)> {
+        let block_height = self.node_state.read().await.block_height;
+        let (block_gas_limit, recent_blocks) = match &self.db {
+            Some(db) => (
+                to_rpc_result(db.epoch_snapshot_for_block(block_height + 1))?
+                    .map(|snapshot| snapshot.params.block_gas_limit)
+                    .unwrap_or(BLOCK_GAS_LIMIT),
+                to_rpc_result(recent_fee_samples(db, block_height))?,
+            ),
+            None => (BLOCK_GAS_LIMIT, vec![]),
+        };
+        let (min_gas_price, pending) = {
+            let pool = self.tx_pool.read().await;
+            let pending: Vec<(u64, u64)> = pool
+                .pending_snapshot(
+                    DEFAULT_PENDING_SNAPSHOT_MAX_BYTES,
+                    MAX_PENDING_SNAPSHOT_COUNT,
+                )
+                .iter()
+                .map(|pooled| (pooled.gas_price(), pooled.tx.tx.gas_limit))
+                .collect();
+            (pool.limits().min_gas_price, pending)
+        };
+        let estimate = estimate_fees(min_gas_price, block_gas_limit, &recent_blocks, &pending);
+        Ok((estimate, pending.len()))
+    }
 
-        info!("Transaction submitted: {}", tx_hash);
-        Ok(tx_hash)
+    /// Cache the response of `method` for `params` once `block_number`, the block it
+    /// is about, is confirmed
+    async fn cache_if_confirmed<T: Serialize>(
+        &self,
+        block_number: u128,
+        method: &'static str,
+        params: &impl Serialize,
+        response: &T,
+    ) {
+        let height = self.node_state.read().await.block_height;
+        if self.response_cache.is_confirmed(block_number, height) {
+            self.response_cache.insert(method, params, response);
+        }
     }
 
-    async fn get_network_stats(&self) -> RpcResult<NetworkStats> {
-        let state = self.node_state.read().await;
+    pub fn with_block_proposers(mut self, public_keys: Vec<Vec<u8>>) -> Self {
+        self.block_proposers = public_keys;
+        self
+    }
 
-        Ok(NetworkStats {
-            peer_count: state.peer_count,
-            connected_peers: vec![], // TODO: Get actual peer list
-            block_height: state.block_height,
-            transaction_pool_size: 0, // TODO: Get actual tx pool size
-            network_id: state.chain_id.to_string(),
+    /// Number of the next block with the hash and accumulator root of the block it must
+    /// extend, zero before genesis
+    fn next_block_parent(db: &RoochDB) -> Result<(u128, H256, H256)> {
+        Ok(match db.get_latest_block_number()? {
+            Some(latest) => {
+                let parent = db
+                    .get_block(latest)?
+                    .ok_or_else(|| anyhow::anyhow!("Latest block #{} not found", latest))?;
+                (latest + 1, parent.hash(), parent.tx_accumulator_root)
+            }
+            None => (GENESIS_BLOCK_NUMBER, H256::zero(), H256::zero()),
         })
     }
 
-    async fn get_tx_pool_status(&self) -> RpcResult<std::collections::HashMap<String, u64>> {
-        // TODO: Implement actual tx pool status
-        warn!("get_tx_pool_status not fully implemented yet");
-
-        let mut status = std::collections::HashMap::new();
-        status.insert("pending".to_string(), 0);
-        status.insert("queued".to_string(), 0);
+    /// Check a submitted block against the chain it claims to extend
+    #[instrument(skip_all, fields(block_number = %signed.block.block_number))]
+    async fn validate_submitted_block(
+        &self,
+        db: &RoochDB,
+        signed: &SignedBlock,
+    ) -> Result<Option<EpochSnapshot>> {
+        // The validator set of the block's epoch decides who may propose it, the
+        // configured proposers only while no epoch has validators
+        let epoch_snapshot = db.epoch_snapshot_for_block(signed.block.block_number)?;
+        let authorized = match &epoch_snapshot {
+            Some(snapshot) if !snapshot.validators.is_empty() => {
+                snapshot.is_validator_key(&signed.public_key)
+            }
+            _ => self.block_proposers.contains(&signed.public_key),
+        };
+        if !authorized {
+            anyhow::bail!(
+                "Proposer 0x{} is not authorized to submit blocks",
+                hex::encode(&signed.public_key)
+            );
+        }
+        if db
+            .has_pending_evidence_against(&signed.public_key, epoch_of(signed.block.block_number))?
+        {
+            anyhow::bail!(
+                "Proposer 0x{} double signed and awaits slashing",
+                hex::encode(&signed.public_key)
+            );
+        }
+        signed.verify()?;
+
+        let (height, parent_hash, parent_root) = Self::next_block_parent(db)?;
+        if signed.block.block_number != height {
+            anyhow::bail!(
+                "Block #{} does not extend the chain, expected #{}",
+                signed.block.block_number,
+                height
+            );
+        }
+        if signed.parent_hash != parent_hash {
+            anyhow::bail!(
+                "Block parent {:?} is not the latest block {:?}",
+                signed.parent_hash,
+                parent_hash
+            );
+        }
+        // Like produced blocks, the header links to its parent through the accumulator root
+        if signed.block.prev_tx_accumulator_root != parent_root {
+            anyhow::bail!(
+                "Block does not extend the accumulator root {:?} of its parent",
+                parent_root
+            );
+        }
+        let accumulator_root = next_tx_accumulator_root(parent_root, signed.block.batch_hash);
+        if signed.block.tx_accumulator_root != accumulator_root {
+            anyhow::bail!(
+                "Block accumulator root {:?} is not its batch appended to its parent's {:?}",
+                signed.block.tx_accumulator_root,
+                accumulator_root
+            );
+        }
+
+        let now = self.node_state.read().await.network_time_secs();
+        if signed.timestamp.abs_diff(now) > BLOCK_TIMESTAMP_TOLERANCE_SECS {
+            anyhow::bail!(
+                "Block timestamp {} is more than {}s from the network time {}",
+                signed.timestamp,
+                BLOCK_TIMESTAMP_TOLERANCE_SECS,
+                now
+            );
+        }
+
+        if let Some(genesis_hash) = db.get_genesis_hash()? {
+            let chain_id = self.node_state.read().await.chain_id;
+            for tx in &signed.transactions {
+                tx.tx.check_network(chain_id, &genesis_hash)?;
+            }
+        }
+        let block_gas_limit = epoch_snapshot
+            .as_ref()
+            .map_or(BLOCK_GAS_LIMIT, |snapshot| snapshot.params.block_gas_limit);
+        let min_gas_price = self.tx_pool.read().await.limits().min_gas_price;
+        validate_block_transactions(db, &signed.transactions, block_gas_limit, min_gas_price)?;
+
+        // The first block of an epoch must commit to the epoch's snapshot and pay the
+        // rewards of the previous epoch
+        if !is_epoch_boundary(signed.block.block_number) {
+            return Ok(None);
+        }
+        let Some(snapshot) = epoch_snapshot else {
+            return Ok(None);
+        };
+        let system: Vec<SystemTransaction> = signed
+            .transactions
+            .iter()
+            .filter_map(|tx| SystemTransaction::from_transaction(tx).ok().flatten())
+            .collect();
+        let committed = system.iter().find_map(|system| match system {
+            SystemTransaction::EpochChange { snapshot_hash, .. } => Some(*snapshot_hash),
+            _ => None,
+        });
+        if committed != Some(snapshot.hash()) {
+            anyhow::bail!(
+                "Block #{} starts epoch {} but does not commit to its snapshot {:?}",
+                signed.block.block_number,
+                snapshot.epoch,
+                snapshot.hash()
+            );
+        }
+        if let Some(previous) = db.get_epoch_snapshot(snapshot.epoch - 1)? {
+            let paid = system.into_iter().find_map(|system| match system {
+                SystemTransaction::RewardDistribution { payments, .. } => Some(payments),
+                _ => None,
+            });
+            if paid != Some(db.epoch_rewards(&previous)?) {
+                anyhow::bail!(
+                    "Block #{} does not distribute the rewards of epoch {}",
+                    signed.block.block_number,
+                    previous.epoch
+                );
+            }
+        }
+        Ok(Some(snapshot))
+    }
+
+    fn db(&self) -> RpcResult<&Arc<RoochDB>> {
+        self.db
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Database is not available".to_string()).into())
+    }
+
+    /// Decode a hex encoded `SignedTransaction` and check it against the node, see
+    /// `validate_raw_transaction`
+    async fn validated_transaction(&self, raw_tx: &str) -> RpcResult<SignedTransaction> {
+        validate_raw_transaction(&self.node_state, &self.tx_pool, self.db.as_deref(), raw_tx).await
+    }
+
+    /// The snapshot of a started epoch, the current one if None, and whether it is the
+    /// current epoch
+    async fn epoch_snapshot(&self, epoch: Option<u64>) -> RpcResult<(EpochSnapshot, bool)> {
+        let db = self.db()?;
+        let current = epoch_of(self.node_state.read().await.block_height);
+        let epoch = epoch.unwrap_or(current);
+        if epoch > current {
+            return Err(RpcError::InvalidParams(format!(
+                "Epoch {} has not started, the current epoch is {}",
+                epoch, current
+            ))
+            .into());
+        }
+        let snapshot = to_rpc_result(db.get_epoch_snapshot(epoch))?.ok_or_else(|| {
+            RpcError::InternalError(format!("Epoch {} snapshot not found", epoch))
+        })?;
+        Ok((snapshot, epoch == current))
+    }
+
+    /// Reads against the state after `block_number`, or the latest state if None
+    fn state_at(&self, block_number: Option<u128>) -> RpcResult<StateView<'_>> {
+        let db = self.db()?;
+        let Some(state) = to_rpc_result(db.state_at(block_number))? else {
+            let block_number = block_number.unwrap_or_default();
+            return Err(RpcError::BlockNotFound(format!("#{}", block_number)).into());
+        };
+        Ok(state)
+    }
+
+    /// The balance of `coin_type` held by `account` in the state after `block_number`,
+    /// or the latest state if None. Zero if it never held the coin.
+    fn coin_balance(
+        &self,
+        account: AccountAddress,
+        coin_type: &StructTag,
+        block_number: Option<u128>,
+    ) -> RpcResult<U256> {
+        let state = self.state_at(block_number)?;
+        let balance = to_rpc_result(state.get_coin_balance(account, coin_type))?;
+        Ok(balance.unwrap_or_else(U256::zero))
+    }
+}
+
+/// Parse a view function call: a module such as `0x6::kanari`, a function name, Move
+/// type tags and hex encoded BCS arguments
+fn parse_function_call(
+    module: &str,
+    function: &str,
+    type_args: Vec<String>,
+    args: Vec<String>,
+) -> RpcResult<FunctionCall> {
+    let (address, name) = module.split_once("::").ok_or_else(|| {
+        RpcError::InvalidParams(format!(
+            "Module {} is not in the form address::name",
+            module
+        ))
+    })?;
+    let address = AccountAddress::from_hex_literal(address).map_err(|e| {
+        RpcError::InvalidParams(format!("Invalid module address {}: {}", address, e))
+    })?;
+    let name = Identifier::new(name)
+        .map_err(|e| RpcError::InvalidParams(format!("Invalid module name: {}", e)))?;
+    let function = Identifier::new(function)
+        .map_err(|e| RpcError::InvalidParams(format!("Invalid function name: {}", e)))?;
+    let ty_args = type_args
+        .iter()
+        .map(|type_arg| {
+            TypeTag::from_str(type_arg).map_err(|e| {
+                RpcError::InvalidParams(format!("Invalid type argument {}: {}", type_arg, e))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let args = args
+        .iter()
+        .map(|arg| {
+            hex::decode(arg.strip_prefix("0x").unwrap_or(arg))
+                .map_err(|e| RpcError::InvalidParams(format!("Invalid argument {}: {}", arg, e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(FunctionCall::new(
+        FunctionId::new(ModuleId::new(address, name), function),
+        ty_args,
+        args,
+    ))
+}
+
+/// Parse a hex or bech32 account address
+fn parse_account(address: &str) -> RpcResult<AccountAddress> {
+    Ok(RoochAddress::from_str(address)
+        .map_err(|e| RpcError::InvalidParams(format!("Invalid address: {}", e)))?
+        .into())
+}
+
+/// The parent object and key of the single object or field an access path names, e.g.
+/// `/object/0x...` or `/fields/0x.../0x...`
+fn parse_state_key(path: &str) -> RpcResult<(ObjectID, FieldKey)> {
+    let invalid = |reason: String| {
+        RpcError::InvalidParams(format!("Invalid access path {}: {}", path, reason))
+    };
+    let access_path = AccessPath::from_str(path).map_err(|e| invalid(e.to_string()))?;
+    match access_path.0 {
+        access_path::Path::Object { object_ids } if object_ids.len() == 1 => {
+            let id = object_ids.into_iter().next().expect("one object id");
+            let parent = id
+                .parent()
+                .ok_or_else(|| invalid("the root object has no proof".to_string()))?;
+            Ok((parent, id.field_key()))
+        }
+        access_path::Path::Fields { object_id, fields } if fields.len() == 1 => {
+            Ok((object_id, fields.into_iter().next().expect("one field")))
+        }
+        _ => Err(invalid("expected exactly one object or field".to_string()).into()),
+    }
+}
+
+/// Parse a coin type struct tag, e.g. `0x3::gas_coin::RGas`. `KARI` or no coin type
+/// is the native KARI coin.
+fn parse_coin_type(coin_type: Option<&str>) -> RpcResult<StructTag> {
+    match coin_type {
+        None | Some("KARI") => Ok(KARI::struct_tag()),
+        Some(coin_type) => Ok(StructTag::from_str(coin_type).map_err(|e| {
+            RpcError::InvalidParams(format!("Invalid coin type {}: {}", coin_type, e))
+        })?),
+    }
+}
+
+#[async_trait]
+impl KanariRpcApiServer for KanariRpcImpl {
+    async fn get_node_info(&self) -> RpcResult<NodeInfo> {
+        let state = self.node_state.read().await;
+        to_rpc_result(node_info(&state, self.db.as_deref()))
+    }
+
+    async fn get_account(
+        &self,
+        address: String,
+        block_number: Option<u128>,
+    ) -> RpcResult<AccountInfo> {
+        let account = parse_account(&address)?;
+        let state = to_rpc_result(self.state_at(block_number)?.get_account(account))?
+            .ok_or_else(|| RpcError::AccountNotFound(account.to_hex_literal()))?;
+
+        Ok(AccountInfo {
+            address: account.to_hex_literal(),
+            balance: self
+                .coin_balance(account, &KARI::struct_tag(), block_number)?
+                .to_string(),
+            sequence_number: state.sequence_number,
+        })
+    }
+
+    async fn get_account_summary(
+        &self,
+        address: String,
+        recent_limit: Option<usize>,
+    ) -> RpcResult<AccountSummary> {
+        let recent_limit = recent_limit
+            .unwrap_or(DEFAULT_ACCOUNT_SUMMARY_RECENT_TXS)
+            .min(MAX_ACCOUNT_SUMMARY_RECENT_TXS);
+
+        let account = parse_account(&address)?;
+
+        // An address without account object yet still has a summary
+        let sequence_number = match &self.db {
+            Some(db) => {
+                to_rpc_result(db.get_account(account))?.map_or(0, |account| account.sequence_number)
+            }
+            None => 0,
+        };
+        let balances = self.get_all_token_balances(address.clone(), None).await?;
+
+        let pending_transaction_count = {
+            let pool = self.tx_pool.read().await;
+            pool.pending_count(&account) + pool.queued_count(&account)
+        };
+
+        let recent_transactions = match &self.db {
+            Some(db) => {
+                let count = to_rpc_result(db.get_account_transaction_count(&account))?;
+                let start = count.saturating_sub(recent_limit as u64);
+                let history =
+                    to_rpc_result(db.get_account_transactions(&account, start, recent_limit))?;
+                let mut recent = to_rpc_result(included_transaction_infos(db, history))?;
+                recent.reverse();
+                recent
+            }
+            None => vec![],
+        };
+
+        // Stake bonded as a validator and delegated to validators in the current epoch
+        let epoch = epoch_of(self.node_state.read().await.block_height);
+        let staking_positions = match &self.db {
+            Some(db) => to_rpc_result(db.get_epoch_snapshot(epoch))?
+                .map(|snapshot| staking_positions(&snapshot, &account))
+                .unwrap_or_default(),
+            None => vec![],
+        };
+
+        Ok(AccountSummary {
+            address,
+            sequence_number,
+            balances,
+            pending_transaction_count,
+            recent_transactions,
+            staking_positions,
+        })
+    }
+
+    async fn get_transactions_by_account(
+        &self,
+        address: String,
+        cursor: Option<u64>,
+        limit: Option<usize>,
+    ) -> RpcResult<TransactionPage> {
+        let account = parse_account(&address)?;
+        let limit = limit.unwrap_or(DEFAULT_ACCOUNT_TRANSACTIONS_PAGE_SIZE);
+        if limit == 0 || limit > MAX_ACCOUNT_TRANSACTIONS_PAGE_SIZE {
+            return Err(RpcError::InvalidParams(format!(
+                "limit must be between 1 and {}",
+                MAX_ACCOUNT_TRANSACTIONS_PAGE_SIZE
+            ))
+            .into());
+        }
+        let db = self.db()?;
+
+        let start = cursor.unwrap_or(0);
+        let count = to_rpc_result(db.get_account_transaction_count(&account))?;
+        let history = to_rpc_result(db.get_account_transactions(&account, start, limit))?;
+        let end = start.saturating_add(history.len() as u64);
+        Ok(TransactionPage {
+            transactions: to_rpc_result(included_transaction_infos(db, history))?,
+            next_cursor: if end < count { Some(end) } else { None },
+        })
+    }
+
+    async fn get_balance(
+        &self,
+        address: String,
+        coin_type: Option<String>,
+        block_number: Option<u128>,
+    ) -> RpcResult<BalanceInfo> {
+        let account = parse_account(&address)?;
+        let coin_type = parse_coin_type(coin_type.as_deref())?;
+        let balance = self.coin_balance(account, &coin_type, block_number)?;
+        let decimals = (coin_type == KARI::struct_tag()).then_some(DECIMALS);
+
+        Ok(BalanceInfo {
+            address: account.to_hex_literal(),
+            coin_type: coin_type.to_canonical_string(),
+            balance: balance.to_string(),
+            decimals,
+        })
+    }
+
+    async fn get_header_chain(&self, from: u128, to: u128) -> RpcResult<Vec<HeaderInfo>> {
+        if to < from {
+            return Err(
+                RpcError::InvalidParams("to must not be lower than from".to_string()).into(),
+            );
+        }
+        if to - from >= MAX_HEADER_CHAIN_RANGE {
+            return Err(RpcError::InvalidParams(format!(
+                "Header range must not exceed {} blocks",
+                MAX_HEADER_CHAIN_RANGE
+            ))
+            .into());
+        }
+        let db = self.db()?;
+
+        let mut parent_hash = match from.checked_sub(1) {
+            Some(parent_number) => to_rpc_result(db.get_block(parent_number))?
+                .map(|parent| parent.hash())
+                .unwrap_or_default(),
+            None => H256::zero(),
+        };
+        let mut headers = vec![];
+        for number in from..=to {
+            let block = to_rpc_result(db.get_block(number))?
+                .ok_or_else(|| RpcError::BlockNotFound(format!("#{}", number)))?;
+            headers.push(HeaderInfo::new(&block, parent_hash));
+            parent_hash = block.hash();
+        }
+        Ok(headers)
+    }
+
+    async fn get_blocks_in_range(
+        &self,
+        start: u128,
+        end: u128,
+        limit: Option<usize>,
+    ) -> RpcResult<BlockPage> {
+        if end < start {
+            return Err(
+                RpcError::InvalidParams("end must not be lower than start".to_string()).into(),
+            );
+        }
+        let limit = limit.unwrap_or(DEFAULT_BLOCK_PAGE_SIZE);
+        if limit == 0 || limit > MAX_BLOCK_PAGE_SIZE {
+            return Err(RpcError::InvalidParams(format!(
+                "limit must be between 1 and {}",
+                MAX_BLOCK_PAGE_SIZE
+            ))
+            .into());
+        }
+        let db = self.db()?;
+
+        let page_end = end.min(start.saturating_add(limit as u128 - 1));
+        let mut parent_hash = match start.checked_sub(1) {
+            Some(parent_number) => to_rpc_result(db.get_block(parent_number))?
+                .map(|parent| parent.hash())
+                .unwrap_or_default(),
+            None => H256::zero(),
+        };
+        let blocks = to_rpc_result(db.get_blocks_in_range(start, page_end))?
+            .into_iter()
+            .map(|(block, timestamp)| -> Result<BlockInfo> {
+                let info = linked_block_info(db, &block, parent_hash, timestamp)?;
+                parent_hash = block.hash();
+                Ok(info)
+            })
+            .collect::<Result<Vec<_>>>();
+        let blocks = to_rpc_result(blocks)?;
+
+        Ok(BlockPage {
+            blocks,
+            next_cursor: if page_end < end {
+                Some(page_end + 1)
+            } else {
+                None
+            },
+        })
+    }
+
+    async fn get_blocks_by_numbers(
+        &self,
+        block_numbers: Vec<u128>,
+    ) -> RpcResult<Vec<Option<BlockInfo>>> {
+        if block_numbers.len() > self.max_blocks_per_batch {
+            return Err(RpcError::InvalidParams(format!(
+                "At most {} blocks may be fetched at once",
+                self.max_blocks_per_batch
+            ))
+            .into());
+        }
+        let mut blocks: Vec<Option<BlockInfo>> = block_numbers
+            .iter()
+            .map(|number| self.block_cache.get(*number))
+            .collect();
+        let missing: Vec<u128> = block_numbers
+            .iter()
+            .zip(&blocks)
+            .filter(|(_, block)| block.is_none())
+            .map(|(number, _)| *number)
+            .collect();
+        if missing.is_empty() {
+            return Ok(blocks);
+        }
+        let db = self.db()?;
+
+        // The parents are read in the same batch for the parent hashes
+        let mut keys: Vec<u128> = missing
+            .iter()
+            .flat_map(|number| [Some(*number), number.checked_sub(1)])
+            .flatten()
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+        let found: std::collections::HashMap<u128, (Block, Option<u64>)> = keys
+            .iter()
+            .copied()
+            .zip(to_rpc_result(db.get_blocks(&keys))?)
+            .filter_map(|(number, block)| block.map(|block| (number, block)))
+            .collect();
+        for (number, slot) in block_numbers.iter().zip(blocks.iter_mut()) {
+            if slot.is_some() {
+                continue;
+            }
+            if let Some((block, timestamp)) = found.get(number) {
+                let parent_hash = match number.checked_sub(1) {
+                    Some(parent_number) => found
+                        .get(&parent_number)
+                        .map(|(parent, _)| parent.hash())
+                        .unwrap_or_default(),
+                    None => H256::zero(),
+                };
+                *slot = Some(to_rpc_result(linked_block_info(
+                    db,
+                    block,
+                    parent_hash,
+                    *timestamp,
+                ))?);
+            }
+        }
+        Ok(blocks)
+    }
+
+    async fn get_block_by_number(&self, block_number: u128) -> RpcResult<BlockInfo> {
+        if let Some(block) = self.block_cache.get(block_number) {
+            return Ok(block);
+        }
+        if let Some(block) = self.response_cache.get("getBlockByNumber", &block_number) {
+            return Ok(block);
+        }
+        let db = self.db()?;
+        let block = to_rpc_result(db.get_block(block_number))?
+            .ok_or_else(|| RpcError::BlockNotFound(format!("#{}", block_number)))?;
+        let info = to_rpc_result(block_info(db, block))?;
+        self.cache_if_confirmed(block_number, "getBlockByNumber", &block_number, &info)
+            .await;
+        Ok(info)
+    }
+
+    async fn get_block_by_hash(&self, block_hash: String) -> RpcResult<BlockInfo> {
+        let db = self.db()?;
+        let hash = crate::header_chain::parse_hash("Block hash", &block_hash)
+            .map_err(|e| RpcError::InvalidParams(e.to_string()))?;
+        if let Some(block) = self.response_cache.get("getBlockByHash", &hash) {
+            return Ok(block);
+        }
+        let block = to_rpc_result(db.get_block_by_hash(&hash))?
+            .ok_or_else(|| RpcError::BlockNotFound(block_hash))?;
+        let info = to_rpc_result(block_info(db, block))?;
+        self.cache_if_confirmed(info.number, "getBlockByHash", &hash, &info)
+            .await;
+        Ok(info)
+    }
+
+    async fn get_block_fee_summary(&self, block_number: u128) -> RpcResult<BlockFeeSummary> {
+        if let Some(summary) = self.response_cache.get("getBlockFeeSummary", &block_number) {
+            return Ok(summary);
+        }
+        let summary = to_rpc_result(self.db()?.get_block_fee_summary(block_number))?
+            .ok_or_else(|| RpcError::BlockNotFound(format!("#{}", block_number)))?;
+        let info = block_fee_summary_info(block_number, &summary);
+        self.cache_if_confirmed(block_number, "getBlockFeeSummary", &block_number, &info)
+            .await;
+        Ok(info)
+    }
+
+    async fn get_latest_block(&self) -> RpcResult<BlockInfo> {
+        let block_height = self.node_state.read().await.block_height;
+        if let Some(block) = self.block_cache.get(block_height) {
+            return Ok(block);
+        }
+        let db = self.db()?;
+        let block = to_rpc_result(db.get_block(block_height))?
+            .ok_or_else(|| RpcError::BlockNotFound("latest".to_string()))?;
+        let info = to_rpc_result(block_info(db, block))?;
+        // The tip before the first produced or imported block, e.g. after a restart
+        self.block_cache.insert(info.clone());
+        Ok(info)
+    }
+
+    async fn get_transaction(&self, tx_hash: String) -> RpcResult<Option<TransactionInfo>> {
+        let hash = crate::header_chain::parse_hash("Transaction hash", &tx_hash)
+            .map_err(|e| RpcError::InvalidParams(e.to_string()))?;
+        if let Some(info) = self.response_cache.get("getTransaction", &hash) {
+            return Ok(Some(info));
+        }
+        if let Some(pooled) = self.tx_pool.read().await.get(&hash) {
+            return Ok(Some(pending_transaction_summary(&pooled.tx)));
+        }
+        let db = self.db()?;
+        let Some((block_number, index)) = to_rpc_result(db.get_transaction_location(&hash))? else {
+            return Ok(None);
+        };
+        let tx = to_rpc_result(db.get_block_transactions(block_number))?
+            .into_iter()
+            .nth(index as usize)
+            .ok_or_else(|| {
+                RpcError::InternalError(format!(
+                    "Transaction {} is missing from block #{}",
+                    tx_hash, block_number
+                ))
+            })?;
+        let info =
+            to_rpc_result(included_transaction_infos(db, vec![(block_number, tx)]))?.remove(0);
+        self.cache_if_confirmed(block_number, "getTransaction", &hash, &info)
+            .await;
+        Ok(Some(info))
+    }
+
+    async fn get_transaction_receipt(
+        &self,
+        tx_hash: String,
+    ) -> RpcResult<Option<TransactionReceiptInfo>> {
+        let db = self.db()?;
+        let hash = crate::header_chain::parse_hash("Transaction hash", &tx_hash)
+            .map_err(|e| RpcError::InvalidParams(e.to_string()))?;
+        if let Some(receipt) = self.response_cache.get("getTransactionReceipt", &hash) {
+            return Ok(Some(receipt));
+        }
+        let Some(receipt) = to_rpc_result(db.get_transaction_receipt(&hash))? else {
+            return Ok(None);
+        };
+        let block = to_rpc_result(db.get_block(receipt.block_number))?
+            .ok_or_else(|| RpcError::BlockNotFound(format!("#{}", receipt.block_number)))?;
+        let (status, failure_reason) = match receipt.status {
+            ExecutionStatus::Success => ("success", None),
+            ExecutionStatus::Failure { reason } => ("failure", Some(reason)),
+        };
+        let info = TransactionReceiptInfo {
+            tx_hash: format!("0x{}", hex::encode(hash.as_bytes())),
+            status: status.to_string(),
+            failure_reason,
+            gas_used: receipt.gas_used,
+            events: receipt
+                .events
+                .into_iter()
+                .map(|event| event_info(receipt.block_number, event))
+                .collect(),
+            block_number: receipt.block_number,
+            block_hash: format!("0x{}", hex::encode(block.hash().as_bytes())),
+            index: receipt.index,
+        };
+        self.cache_if_confirmed(info.block_number, "getTransactionReceipt", &hash, &info)
+            .await;
+        Ok(Some(info))
+    }
+
+    async fn execute_view_function(
+        &self,
+        module: String,
+        function: String,
+        type_args: Option<Vec<String>>,
+        args: Option<Vec<String>>,
+        block_number: Option<u128>,
+    ) -> RpcResult<ViewFunctionResult> {
+        let executor = self.view_executor.as_ref().ok_or_else(|| {
+            RpcError::NodeNotReady("Move view functions are not available".to_string())
+        })?;
+        let call = parse_function_call(
+            &module,
+            &function,
+            type_args.unwrap_or_default(),
+            args.unwrap_or_default(),
+        )?;
+        let root = self
+            .state_at(block_number)?
+            .root()
+            .cloned()
+            .ok_or_else(|| RpcError::NodeNotReady("The state has no root yet".to_string()))?;
+        to_rpc_result(executor.execute(root, call))
+    }
+
+    async fn get_module(
+        &self,
+        address: String,
+        name: String,
+        block_number: Option<u128>,
+    ) -> RpcResult<Option<MoveModuleInfo>> {
+        let name = Identifier::new(name)
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid module name: {}", e)))?;
+        let module_id = ModuleId::new(parse_account(&address)?, name);
+        // Only the module at a given block stays the same, the latest one may be upgraded
+        let cache_key = block_number.map(|number| (module_id.to_string(), number));
+        if let Some(module) = cache_key
+            .as_ref()
+            .and_then(|key| self.response_cache.get("getModule", key))
+        {
+            return Ok(Some(module));
+        }
+        let state = self.state_at(block_number)?;
+        let Some(bytecode) = to_rpc_result(state.get_module(&module_id))? else {
+            return Ok(None);
+        };
+        let module = MoveModuleInfo {
+            abi: to_rpc_result(module_abi(&bytecode))?,
+            bytecode: format!("0x{}", hex::encode(&bytecode)),
+        };
+        if let Some(key) = &cache_key {
+            self.cache_if_confirmed(key.1, "getModule", key, &module)
+                .await;
+        }
+        Ok(Some(module))
+    }
+
+    async fn list_modules(
+        &self,
+        address: String,
+        block_number: Option<u128>,
+    ) -> RpcResult<Vec<MoveModuleAbi>> {
+        let account = parse_account(&address)?;
+        let modules = to_rpc_result(self.state_at(block_number)?.list_modules(account))?;
+        to_rpc_result(
+            modules
+                .iter()
+                .map(|(_, bytecode)| module_abi(bytecode))
+                .collect(),
+        )
+    }
+
+    async fn get_state_with_proof(
+        &self,
+        access_path: String,
+        block_number: u128,
+    ) -> RpcResult<StateWithProof> {
+        let db = self.db()?;
+        let (parent, key) = parse_state_key(&access_path)?;
+        let state_root = to_rpc_result(db.get_block(block_number))?
+            .ok_or_else(|| RpcError::BlockNotFound(format!("#{}", block_number)))?
+            .state_root;
+        let steps = to_rpc_result(prove_state(&db.moveos_store, state_root, parent, key))?;
+        // Steps stop at a missing ancestor, the last one proves the state itself otherwise
+        let value = steps
+            .last()
+            .filter(|step| step.key == key)
+            .and_then(|step| step.state.as_ref())
+            .map(|state| format!("0x{}", hex::encode(&state.value)));
+        let hex_root = |root: &H256| format!("0x{}", hex::encode(root.as_bytes()));
+        let hex_bcs = |bytes: Result<Vec<u8>, bcs::Error>| -> RpcResult<String> {
+            Ok(format!(
+                "0x{}",
+                hex::encode(to_rpc_result(bytes.map_err(Into::into))?)
+            ))
+        };
+        Ok(StateWithProof {
+            block_number,
+            state_root: hex_root(&state_root),
+            access_path,
+            value,
+            proofs: steps
+                .iter()
+                .map(|step| {
+                    Ok(StateProofStepInfo {
+                        state_root: hex_root(&step.state_root),
+                        field_key: step.key.to_string(),
+                        object_state: step
+                            .state
+                            .as_ref()
+                            .map(|state| hex_bcs(bcs::to_bytes(state)))
+                            .transpose()?,
+                        proof: hex_bcs(bcs::to_bytes(&step.proof))?,
+                    })
+                })
+                .collect::<RpcResult<_>>()?,
+        })
+    }
+
+    async fn dry_run_transaction(&self, signed_tx: String) -> RpcResult<DryRunResult> {
+        let signed_tx = self.validated_transaction(&signed_tx).await?;
+        let db = self.db()?;
+        let block_number = self.node_state.read().await.block_height + 1;
+        let outcome = to_rpc_result(dry_run(db, block_number, &signed_tx))?;
+        let (status, failure_reason) = match outcome.status {
+            ExecutionStatus::Success => ("success", None),
+            ExecutionStatus::Failure { reason } => ("failure", Some(reason)),
+        };
+        Ok(DryRunResult {
+            tx_hash: format!("0x{}", hex::encode(signed_tx.hash().as_bytes())),
+            status: status.to_string(),
+            failure_reason,
+            gas_used: outcome.gas_used,
+            events: outcome
+                .events
+                .into_iter()
+                .map(|event| event_info(block_number, event))
+                .collect(),
+        })
+    }
+
+    async fn send_transaction(&self, signed_tx: String) -> RpcResult<String> {
+        // Takes the same hex encoded transaction as kanari_sendRawTransaction
+        self.send_raw_transaction(signed_tx).await
+    }
+
+    async fn send_raw_transaction(&self, raw_tx: String) -> RpcResult<String> {
+        let (hash, summary) =
+            pool_raw_transaction(&self.node_state, &self.tx_pool, self.db.as_deref(), &raw_tx)
+                .await?;
+        self.transaction_admitted(summary);
+        let tx_hash = format!("0x{}", hex::encode(hash.as_bytes()));
+        info!("Transaction submitted: {}", tx_hash);
+        Ok(tx_hash)
+    }
+
+    async fn replace_transaction(&self, raw_tx: String) -> RpcResult<TransactionReplacement> {
+        let signed_tx = self.validated_transaction(&raw_tx).await?;
+        let summary = pending_transaction_summary(&signed_tx);
+        let (replaced, hash) = {
+            let mut pool = self.tx_pool.write().await;
+            seed_account_nonce(&mut pool, self.db.as_deref(), signed_tx.tx.sender)?;
+            pool.replace_transaction(signed_tx)
+                .map_err(RpcError::from)?
+        };
+        self.transaction_admitted(summary);
+        let replacement = TransactionReplacement {
+            replaced_hash: format!("0x{}", hex::encode(replaced.as_bytes())),
+            hash: format!("0x{}", hex::encode(hash.as_bytes())),
+        };
+        info!(
+            "Transaction {} replaced by {}",
+            replacement.replaced_hash, replacement.hash
+        );
+        Ok(replacement)
+    }
+
+    async fn cancel_transaction(
+        &self,
+        sender: String,
+        sequence_number: u64,
+        signature: String,
+        public_key: String,
+    ) -> RpcResult<String> {
+        let sender = parse_account(&sender)?;
+        let decode = |encoded: &str| {
+            hex::decode(encoded.strip_prefix("0x").unwrap_or(encoded))
+                .map_err(|e| RpcError::InvalidParams(format!("Invalid hex: {}", e)))
+        };
+        let signature = PersonalMessageSignature {
+            public_key: decode(&public_key)?,
+            signature: decode(&signature)?,
+        };
+
+        let mut pool = self.tx_pool.write().await;
+        let Some(hash) = pool
+            .get_by_sequence_number(&sender, sequence_number)
+            .map(|pooled| pooled.hash)
+        else {
+            return Err(RpcError::from(MempoolRejection::NothingToReplace {
+                sender,
+                sequence_number,
+            })
+            .into());
+        };
+        signature
+            .verify(
+                cancel_transaction_message(&hash).as_bytes(),
+                &RoochAddress::from(sender),
+            )
+            .map_err(|e| RpcError::InvalidSignature(e.to_string()))?;
+        pool.cancel(&sender, sequence_number);
+
+        let tx_hash = format!("0x{}", hex::encode(hash.as_bytes()));
+        info!("Transaction cancelled: {}", tx_hash);
+        Ok(tx_hash)
+    }
+
+    async fn get_network_stats(&self) -> RpcResult<NetworkStats> {
+        let state = self.node_state.read().await;
+
+        Ok(NetworkStats {
+            peer_count: state.peer_count,
+            connected_peers: state
+                .peers
+                .iter()
+                .map(|peer| peer.peer_id.clone())
+                .collect(),
+            block_height: state.block_height,
+            transaction_pool_size: self.tx_pool.read().await.len(),
+            network_id: state.chain_id.to_string(),
+        })
+    }
+
+    async fn get_sync_status(&self) -> RpcResult<SyncStatusInfo> {
+        let state = self.node_state.read().await;
+        let mut status = state.sync_status.clone();
+        // Blocks produced locally may be ahead of what the sync subsystem last saw
+        status.current_height = status.current_height.max(state.block_height);
+        status.target_height = status.target_height.max(status.current_height);
+        status.is_syncing = status.current_height < status.target_height;
+        status
+            .active_peers
+            .retain(|peer| peer.height > status.current_height);
+        if !status.is_syncing {
+            status.eta_seconds = None;
+        }
         Ok(status)
     }
 
+    async fn get_time_offset(&self) -> RpcResult<TimeOffsetInfo> {
+        let state = self.node_state.read().await;
+        let local_time_ms = NodeState::local_time_ms();
+        Ok(TimeOffsetInfo {
+            offset_ms: state.time_offset_ms,
+            sample_count: state.time_samples,
+            local_time_ms,
+            network_time_ms: local_time_ms.saturating_add_signed(state.time_offset_ms),
+        })
+    }
+
+    async fn get_tx_pool_status(&self) -> RpcResult<std::collections::HashMap<String, u64>> {
+        let mut pool = self.tx_pool.write().await;
+        pool.prune_expired();
+
+        let mut status = std::collections::HashMap::new();
+        status.insert("pending".to_string(), pool.pending_len() as u64);
+        status.insert("queued".to_string(), pool.queued_len() as u64);
+        Ok(status)
+    }
+
+    async fn get_tx_pool_content(&self, sender: Option<String>) -> RpcResult<TxPoolContent> {
+        let sender = sender.as_deref().map(parse_account).transpose()?;
+        let mut pool = self.tx_pool.write().await;
+        pool.prune_expired();
+
+        let of_sender = |mut groups: BTreeMap<AccountAddress, Vec<&PooledTransaction>>| {
+            if let Some(sender) = sender {
+                groups.retain(|address, _| *address == sender);
+            }
+            tx_pool_groups(groups, tx_pool_transaction)
+        };
+        Ok(TxPoolContent {
+            pending: of_sender(pool.pending_by_sender()),
+            queued: of_sender(pool.queued_by_sender()),
+        })
+    }
+
+    async fn get_tx_pool_inspect(&self) -> RpcResult<TxPoolInspect> {
+        let mut pool = self.tx_pool.write().await;
+        pool.prune_expired();
+
+        Ok(TxPoolInspect {
+            pending: tx_pool_groups(pool.pending_by_sender(), tx_pool_summary),
+            queued: tx_pool_groups(pool.queued_by_sender(), tx_pool_summary),
+        })
+    }
+
+    async fn verify_message(
+        &self,
+        address: String,
+        message: String,
+        signature: String,
+        public_key: String,
+    ) -> RpcResult<bool> {
+        let address = RoochAddress::from_str(&address)
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid address: {}", e)))?;
+        let decode = |encoded: &str| {
+            hex::decode(encoded.strip_prefix("0x").unwrap_or(encoded))
+                .map_err(|e| RpcError::InvalidParams(format!("Invalid hex: {}", e)))
+        };
+        let signature = PersonalMessageSignature {
+            public_key: decode(&public_key)?,
+            signature: decode(&signature)?,
+        };
+        Ok(signature.verify(message.as_bytes(), &address).is_ok())
+    }
+
+    async fn get_events(&self, filter: EventFilter) -> RpcResult<Vec<EventInfo>> {
+        if filter.to_block < filter.from_block {
+            return Err(RpcError::InvalidParams(
+                "to_block must not be lower than from_block".to_string(),
+            )
+            .into());
+        }
+        if filter.to_block - filter.from_block >= MAX_EVENT_QUERY_BLOCK_RANGE {
+            return Err(RpcError::InvalidParams(format!(
+                "Block range must not exceed {} blocks",
+                MAX_EVENT_QUERY_BLOCK_RANGE
+            ))
+            .into());
+        }
+        let address = filter
+            .address
+            .as_deref()
+            .map(AccountAddress::from_hex_literal)
+            .transpose()
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid address: {}", e)))?;
+
+        let events = to_rpc_result(self.db()?.get_events(
+            filter.from_block,
+            filter.to_block,
+            address.as_ref(),
+            filter.event_type.as_deref(),
+        ))?;
+        Ok(events
+            .into_iter()
+            .map(|(block_number, event)| event_info(block_number, event))
+            .collect())
+    }
+
+    async fn get_logs(&self, filter: LogFilter) -> RpcResult<Vec<EventInfo>> {
+        let latest = self.node_state.read().await.block_height;
+        let to_block = filter.to_block.unwrap_or(latest);
+        let from_block = filter.from_block.unwrap_or(to_block);
+        if to_block < from_block {
+            return Err(RpcError::InvalidParams(
+                "to_block must not be lower than from_block".to_string(),
+            )
+            .into());
+        }
+        if to_block - from_block >= MAX_EVENT_QUERY_BLOCK_RANGE {
+            return Err(RpcError::InvalidParams(format!(
+                "Block range must not exceed {} blocks",
+                MAX_EVENT_QUERY_BLOCK_RANGE
+            ))
+            .into());
+        }
+        let addresses = filter
+            .addresses
+            .iter()
+            .map(|address| AccountAddress::from_hex_literal(address))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid address: {}", e)))?;
+
+        let events = to_rpc_result(self.db()?.get_logs(
+            from_block,
+            to_block,
+            &addresses,
+            &filter.event_types,
+        ))?;
+        Ok(events
+            .into_iter()
+            .map(|(block_number, event)| event_info(block_number, event))
+            .collect())
+    }
+
+    async fn get_pending_transactions(
+        &self,
+        max_bytes: Option<usize>,
+        max_count: Option<usize>,
+    ) -> RpcResult<Vec<PendingTransaction>> {
+        let max_bytes = max_bytes.unwrap_or(DEFAULT_PENDING_SNAPSHOT_MAX_BYTES);
+        let max_count = max_count
+            .unwrap_or(MAX_PENDING_SNAPSHOT_COUNT)
+            .min(MAX_PENDING_SNAPSHOT_COUNT);
+
+        let snapshot = self
+            .tx_pool
+            .read()
+            .await
+            .pending_snapshot(max_bytes, max_count);
+        Ok(snapshot.into_iter().map(pending_transaction_info).collect())
+    }
+
+    async fn get_block_template(&self) -> RpcResult<BlockTemplate> {
+        let db = self.db()?;
+        let (height, parent_hash, parent_root) = to_rpc_result(Self::next_block_parent(db))?;
+        let genesis_hash = to_rpc_result(db.get_genesis_hash())?
+            .map(|hash| format!("0x{}", hex::encode(hash.as_bytes())));
+        let (chain_id, now) = {
+            let state = self.node_state.read().await;
+            (state.chain_id, state.network_time_secs())
+        };
+
+        let block_gas_limit = to_rpc_result(db.epoch_snapshot_for_block(height))?
+            .map(|snapshot| snapshot.params.block_gas_limit)
+            .unwrap_or(BLOCK_GAS_LIMIT);
+
+        let pool = self.tx_pool.read().await;
+        let transactions = pool
+            .pending_snapshot(
+                DEFAULT_PENDING_SNAPSHOT_MAX_BYTES,
+                MAX_PENDING_SNAPSHOT_COUNT,
+            )
+            .into_iter()
+            .map(pending_transaction_info)
+            .collect();
+
+        Ok(BlockTemplate {
+            parent_hash: format!("0x{}", hex::encode(parent_hash.as_bytes())),
+            prev_tx_accumulator_root: format!("0x{}", hex::encode(parent_root.as_bytes())),
+            height,
+            chain_id,
+            genesis_hash,
+            min_timestamp: now.saturating_sub(BLOCK_TIMESTAMP_TOLERANCE_SECS),
+            max_timestamp: now + BLOCK_TIMESTAMP_TOLERANCE_SECS,
+            min_gas_price: pool.limits().min_gas_price,
+            block_gas_limit,
+            system_transaction_slots: SYSTEM_TRANSACTION_SLOTS,
+            transactions,
+        })
+    }
+
+    async fn submit_block(&self, raw_block: String) -> RpcResult<String> {
+        let db = self.db()?;
+        if self.block_proposers.is_empty() {
+            return Err(RpcError::NodeNotReady(
+                "External block submission is not enabled".to_string(),
+            )
+            .into());
+        }
+        if self.node_state.read().await.read_only {
+            return Err(RpcError::NodeNotReady(
+                "The node is in read-only mode because its disk is almost full".to_string(),
+            )
+            .into());
+        }
+        let bytes = hex::decode(raw_block.strip_prefix("0x").unwrap_or(&raw_block))
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid hex: {}", e)))?;
+        let signed = SignedBlock::decode(&bytes)
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid block: {}", e)))?;
+
+        let _import = self.import_lock.lock().await;
+        let epoch_snapshot = self
+            .validate_submitted_block(db, &signed)
+            .await
+            .map_err(|e| RpcError::InvalidParams(format!("Block rejected: {}", e)))?;
+
+        // The header must commit to the state its transactions leave
+        let block_number = signed.block.block_number;
+        let execution = to_rpc_result(db.execute_block(block_number, &signed.transactions))?;
+        if execution.root.state_root() != signed.block.state_root {
+            return Err(RpcError::InvalidParams(format!(
+                "Block rejected: state root {:?} differs from the executed {:?}",
+                signed.block.state_root,
+                execution.root.state_root()
+            ))
+            .into());
+        }
+
+        to_rpc_result(db.save_executed_block(&ExecutedBlock {
+            block: &signed.block,
+            timestamp: signed.timestamp,
+            transactions: &signed.transactions,
+            outputs: &execution.outputs,
+            events: &execution.events,
+            root: &execution.root,
+            proposer: Some(signed.public_key.as_slice()),
+            epoch_snapshot: epoch_snapshot.as_ref(),
+            dao: &execution.dao,
+        }))?;
+
+        // Included transactions leave the pool, and later nonces become executable
+        self.tx_pool
+            .write()
+            .await
+            .remove_committed(&signed.transactions);
+        self.block_imported(block_number).await;
+        if let Ok(info) = block_info(db, signed.block.clone()) {
+            self.block_cache.insert(info.clone());
+            self.publish(SubscriptionEvent::NewBlock(info));
+        }
+
+        let block_hash = format!("0x{}", hex::encode(signed.block.hash().as_bytes()));
+        info!(
+            "Imported external block #{} {} with {} transactions",
+            block_number,
+            block_hash,
+            signed.transactions.len()
+        );
+        Ok(block_hash)
+    }
+
+    async fn submit_evidence(&self, raw_evidence: String) -> RpcResult<EvidenceInfo> {
+        let db = self.db()?;
+        let bytes = hex::decode(raw_evidence.strip_prefix("0x").unwrap_or(&raw_evidence))
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid hex: {}", e)))?;
+        let evidence = DoubleSignEvidence::decode(&bytes)
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid evidence: {}", e)))?;
+        evidence
+            .verify()
+            .map_err(|e| RpcError::InvalidParams(format!("Evidence rejected: {}", e)))?;
+
+        // Only a validator of the epoch both blocks belong to can be slashed
+        let height = evidence.block_number();
+        let offender = to_rpc_result(db.epoch_snapshot_for_block(height))?
+            .and_then(|snapshot| {
+                snapshot
+                    .validator_by_key(evidence.public_key())
+                    .map(|validator| validator.address)
+            })
+            .ok_or_else(|| {
+                RpcError::InvalidParams(format!(
+                    "Evidence rejected: 0x{} was not a validator at #{}",
+                    hex::encode(evidence.public_key()),
+                    height
+                ))
+            })?;
+
+        // Serialized with block imports so the slashing is queued against a stable epoch
+        let _import = self.import_lock.lock().await;
+        let current = epoch_of(self.node_state.read().await.block_height);
+        let snapshot = to_rpc_result(db.get_epoch_snapshot(current))?.ok_or_else(|| {
+            RpcError::InternalError(format!("Epoch {} snapshot not found", current))
+        })?;
+        let submitted_at = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|e| RpcError::InternalError(e.to_string()))?
+            .as_secs();
+        let record = EvidenceRecord::new(evidence, offender, current, submitted_at);
+        db.record_evidence(&snapshot, record.clone())
+            .map_err(|e| RpcError::InvalidParams(format!("Evidence rejected: {}", e)))?;
+        warn!(
+            "Recorded double signing by validator {} at #{}, slashing at epoch {}",
+            offender, height, record.slashed_from_epoch
+        );
+        Ok(evidence_info(&record))
+    }
+
+    async fn get_evidence(&self, evidence_hash: String) -> RpcResult<Option<EvidenceInfo>> {
+        let db = self.db()?;
+        let hash = crate::header_chain::parse_hash("Evidence hash", &evidence_hash)
+            .map_err(|e| RpcError::InvalidParams(e.to_string()))?;
+        Ok(to_rpc_result(db.get_evidence(&hash))?
+            .as_ref()
+            .map(evidence_info))
+    }
+
+    async fn list_evidence(&self, offender: Option<String>) -> RpcResult<Vec<EvidenceInfo>> {
+        let db = self.db()?;
+        let offender = offender
+            .map(|address| AccountAddress::from_hex_literal(&address))
+            .transpose()
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid address: {}", e)))?;
+        Ok(to_rpc_result(db.get_evidence_records())?
+            .iter()
+            .filter(|record| offender.is_none_or(|offender| record.offender == offender))
+            .map(evidence_info)
+            .collect())
+    }
+
     async fn get_chain_id(&self) -> RpcResult<u64> {
         let state = self.node_state.read().await;
         Ok(state.chain_id)
     }
 
+    async fn get_chain_info(&self) -> RpcResult<ChainInfo> {
+        let state = self.node_state.read().await;
+        let (genesis_hash, finalized_height) = match self.db.as_deref() {
+            Some(db) => (
+                to_rpc_result(db.get_genesis_hash())?
+                    .map(|hash| format!("0x{}", hex::encode(hash.as_bytes()))),
+                to_rpc_result(db.get_finalized_block_number(state.block_height))?,
+            ),
+            None => (None, None),
+        };
+
+        Ok(ChainInfo {
+            chain_id: state.chain_id,
+            network_id: state.chain_id.to_string(),
+            genesis_hash,
+            latest_height: state.block_height,
+            finalized_height,
+            stdlib_version: G_LOCAL_CONFIG.stdlib_version.as_string(),
+            node_version: state.node_version.clone(),
+        })
+    }
+
     async fn get_block_height(&self) -> RpcResult<u128> {
         let state = self.node_state.read().await;
         Ok(state.block_height)
@@ -327,126 +3018,415 @@ impl KanariRpcApiServer for KanariRpcImpl {
         })
     }
 
-    async fn get_kari_balance(&self, account: Option<String>) -> RpcResult<TokenBalance> {
-        // If no account specified, use the Rooch wallet from config
-        let rooch_address = match account {
-            Some(addr) => addr,
-            None => "rooch1u6kv4l8xgdejlvne8728skvx5jugvp2prlhuhglw72xgl82vc5xs8kr9hj".to_string(),
-        };
-        
-        // For demo purposes, return mock balance
-        // In real implementation, query the Rooch network for actual balance
-        let balance = if rooch_address == "rooch1u6kv4l8xgdejlvne8728skvx5jugvp2prlhuhglw72xgl82vc5xs8kr9hj" {
-            100_000_000 * KARI_SCALE // 100M KARI tokens (genesis allocation)
-        } else {
-            0
-        };
-        
+    async fn get_display_metadata(&self) -> RpcResult<DisplayMetadata> {
+        Ok(DisplayMetadata {
+            symbol: KARI_DENOMINATION.symbol.to_string(),
+            decimals: DECIMALS,
+            units: DENOMINATIONS
+                .iter()
+                .map(|unit| DenominationInfo {
+                    name: unit.name.to_string(),
+                    symbol: unit.symbol.to_string(),
+                    decimals: unit.decimals,
+                })
+                .collect(),
+            display_decimals: DISPLAY_DECIMALS,
+            rounding: "toward_zero".to_string(),
+            trim_trailing_zeros: true,
+            reject_excess_precision: true,
+        })
+    }
+
+    async fn get_kari_balance(
+        &self,
+        address: String,
+        block_number: Option<u128>,
+    ) -> RpcResult<TokenBalance> {
+        let account = parse_account(&address)?;
+        let balance = self.coin_balance(account, &KARI::struct_tag(), block_number)?;
+
         Ok(TokenBalance {
-            address: rooch_address,
+            address,
             balance: balance.to_string(),
-            decimals: 18,
+            balance_scaled: format_amount(balance.unchecked_as_u128(), DECIMALS),
+            token_info: self.get_kari_token_info().await?,
         })
     }
 
-    async fn get_all_token_balances(&self, address: String) -> RpcResult<Vec<TokenBalance>> {
-        // TODO: Implement actual multi-token balance lookup
-        warn!("get_all_token_balances not fully implemented yet");
-        
-        let kari_balance = self.get_kari_balance(address).await?;
+    async fn get_all_token_balances(
+        &self,
+        address: String,
+        block_number: Option<u128>,
+    ) -> RpcResult<Vec<TokenBalance>> {
+        let kari_balance = self.get_kari_balance(address, block_number).await?;
         Ok(vec![kari_balance])
     }
-000
+
     async fn get_rooch_wallet_info(&self) -> RpcResult<RoochWalletInfo> {
-        let rooch_address = "rooch1u6kv4l8xgdejlvne8728skvx5jugvp2prlhuhglw72xgl82vc5xs8kr9hj".to_string();
-        let kari_balance = self.get_kari_balance(rooch_address.clone()).await?;
+        let rooch_address =
+            "rooch1u6kv4l8xgdejlvne8728skvx5jugvp2prlhuhglw72xgl82vc5xs8kr9hj".to_string();
+        let kari_balance = self.get_kari_balance(rooch_address.clone(), None).await?;
 
         Ok(RoochWalletInfo {
             rooch_address: rooch_address.clone(),
-            hex_address: "0xe6accafce643732fb2793f94785986a4b88605411fefcba3eef28c8f9d4cc50d".to_string(),
-            bitcoin_address: "bcrt1pp44qzxqkf6wy5gpzjy6uzp2zzkldjccrqayssnud24gu2x96gehsjleyq3".to_string(),
-            public_key: "0x02be56eda70ca8cfb17cc4139b970e839cc8df1af67a7a721630cc2631f7149261".to_string(),
+            hex_address: "0xe6accafce643732fb2793f94785986a4b88605411fefcba3eef28c8f9d4cc50d"
+                .to_string(),
+            bitcoin_address: "bcrt1pp44qzxqkf6wy5gpzjy6uzp2zzkldjccrqayssnud24gu2x96gehsjleyq3"
+                .to_string(),
+            public_key: "0x02be56eda70ca8cfb17cc4139b970e839cc8df1af67a7a721630cc2631f7149261"
+                .to_string(),
             is_active: true,
             kari_balance,
         })
     }
 
     async fn get_rooch_kari_balance(&self) -> RpcResult<TokenBalance> {
-        let rooch_address = "rooch1u6kv4l8xgdejlvne8728skvx5jugvp2prlhuhglw72xgl82vc5xs8kr9hj".to_string();
-        self.get_kari_balance(rooch_address).await
+        let rooch_address =
+            "rooch1u6kv4l8xgdejlvne8728skvx5jugvp2prlhuhglw72xgl82vc5xs8kr9hj".to_string();
+        self.get_kari_balance(rooch_address, None).await
     }
 
     async fn get_kanari_dao_info(&self) -> RpcResult<KanariDaoInfo> {
         let genesis_config = &*G_LOCAL_CONFIG;
         let dao_config = &genesis_config.kanari_dao;
-        
+
         // Get DAO Bitcoin address from genesis config
         let dao_bitcoin_address = dao_config.multisign_bitcoin_address.to_string();
-        
+
         // Convert Bitcoin address to Rooch address for balance lookup
-        let dao_rooch_address = dao_config.multisign_bitcoin_address.to_rooch_address().to_hex_literal();
-        let dao_balance = self.get_kari_balance(dao_rooch_address.clone()).await?;
+        let dao_rooch_address = dao_config
+            .multisign_bitcoin_address
+            .to_rooch_address()
+            .to_hex_literal();
+        let dao_balance = self
+            .get_kari_balance(dao_rooch_address.clone(), None)
+            .await?;
 
         Ok(KanariDaoInfo {
             multisign_bitcoin_address: dao_bitcoin_address,
             threshold: dao_config.threshold as u64,
             participant_count: dao_config.participant_public_keys.len(),
-            collected_fees: "50000000000000000000000".to_string(), // Mock collected fees
             kari_balance: dao_balance,
         })
     }
 
-    async fn estimate_transaction_fee(&self, tx_request: TransactionRequest) -> RpcResult<TransactionFee> {
-        // Calculate fee based on transaction complexity and gas usage
-        let base_fee = U256::from(tx_request.gas_limit * tx_request.gas_price);
-        let priority_fee = base_fee / U256::from(10u64); // 10% priority fee
-        let total_fee = base_fee + priority_fee;
-        
+    async fn get_dao_proposals(
+        &self,
+        status: Option<DaoProposalStatusInfo>,
+    ) -> RpcResult<Vec<DaoProposalInfo>> {
+        let db = self.db()?;
+        let height = self.node_state.read().await.block_height;
+        let mut proposals = vec![];
+        for proposal in to_rpc_result(db.get_dao_proposals())?.iter().rev() {
+            let votes = to_rpc_result(db.get_dao_votes(proposal.id))?;
+            let info = dao_proposal_info(proposal, &votes, height);
+            if status.is_none_or(|status| status == info.status) {
+                proposals.push(info);
+            }
+        }
+        Ok(proposals)
+    }
+
+    async fn get_dao_proposal(&self, id: u64) -> RpcResult<Option<DaoProposalInfo>> {
+        let db = self.db()?;
+        let Some(proposal) = to_rpc_result(db.get_dao_proposal(id))? else {
+            return Ok(None);
+        };
+        let votes = to_rpc_result(db.get_dao_votes(id))?;
+        let height = self.node_state.read().await.block_height;
+        Ok(Some(dao_proposal_info(&proposal, &votes, height)))
+    }
+
+    async fn get_dao_votes(&self, id: u64) -> RpcResult<Vec<DaoVoteInfo>> {
+        let db = self.db()?;
+        if to_rpc_result(db.get_dao_proposal(id))?.is_none() {
+            return Err(RpcError::InvalidParams(format!("DAO proposal {} not found", id)).into());
+        }
+        Ok(to_rpc_result(db.get_dao_votes(id))?
+            .into_iter()
+            .map(|vote| DaoVoteInfo {
+                voter: format!("0x{}", hex::encode(&vote.voter)),
+                approve: vote.approve,
+                block_number: vote.block_number,
+            })
+            .collect())
+    }
+
+    async fn get_epoch_info(&self, epoch: Option<u64>) -> RpcResult<EpochInfo> {
+        let (snapshot, is_current) = self.epoch_snapshot(epoch).await?;
+        let pending_changes = if is_current {
+            to_rpc_result(self.db()?.get_pending_validator_changes())?.len()
+        } else {
+            0
+        };
+        Ok(epoch_info(&snapshot, is_current, pending_changes))
+    }
+
+    async fn get_validators(&self, epoch: Option<u64>) -> RpcResult<ValidatorSetInfo> {
+        let (snapshot, is_current) = self.epoch_snapshot(epoch).await?;
+        // Only the current set can still have an unregistration queued
+        let leaving: BTreeSet<AccountAddress> = if is_current {
+            to_rpc_result(self.db()?.get_pending_validator_changes())?
+                .into_iter()
+                .filter_map(|change| match change {
+                    ValidatorSetChange::Unregister { address } => Some(address),
+                    _ => None,
+                })
+                .collect()
+        } else {
+            BTreeSet::new()
+        };
+        let validators = snapshot
+            .validators
+            .iter()
+            .zip(snapshot.effective_stakes())
+            .map(|(validator, effective_stake)| {
+                let jailed = validator.is_jailed(snapshot.epoch);
+                let status = if jailed {
+                    ValidatorStatus::Jailed
+                } else if leaving.contains(&validator.address) {
+                    ValidatorStatus::Leaving
+                } else {
+                    ValidatorStatus::Active
+                };
+                let total_stake = validator.total_stake();
+                ValidatorInfo {
+                    address: validator.address.to_hex_literal(),
+                    consensus_key: format!("0x{}", hex::encode(&validator.public_key)),
+                    stake: validator.stake.to_string(),
+                    delegated_stake: (total_stake - validator.stake).to_string(),
+                    total_stake: total_stake.to_string(),
+                    voting_power: match snapshot.total_stake() {
+                        0 => 0.0,
+                        total => effective_stake as f64 / total as f64,
+                    },
+                    status,
+                    jailed_until: validator.jailed_until.filter(|_| jailed),
+                }
+            })
+            .collect();
+        Ok(ValidatorSetInfo {
+            epoch: snapshot.epoch,
+            is_current,
+            total_stake: snapshot.total_stake().to_string(),
+            validators,
+        })
+    }
+
+    async fn get_rewards(
+        &self,
+        address: String,
+        from_epoch: Option<u64>,
+        to_epoch: Option<u64>,
+    ) -> RpcResult<RewardsInfo> {
+        let db = self.db()?;
+        let account = parse_account(&address)?;
+        // Rewards of an epoch are paid in the first block of the next one
+        let current = epoch_of(self.node_state.read().await.block_height);
+        let to_epoch = to_epoch.unwrap_or(current.saturating_sub(1));
+        let from_epoch = from_epoch.unwrap_or(to_epoch.saturating_sub(MAX_REWARD_EPOCH_RANGE - 1));
+        if from_epoch > to_epoch {
+            return Err(RpcError::InvalidParams(format!(
+                "from_epoch {} is after to_epoch {}",
+                from_epoch, to_epoch
+            ))
+            .into());
+        }
+        if to_epoch - from_epoch >= MAX_REWARD_EPOCH_RANGE {
+            return Err(RpcError::InvalidParams(format!(
+                "Epoch range is limited to {} epochs",
+                MAX_REWARD_EPOCH_RANGE
+            ))
+            .into());
+        }
+
+        let payments = to_rpc_result(db.get_rewards(&account, from_epoch, to_epoch))?;
+        let total: u128 = payments.iter().map(|payment| payment.amount).sum();
+        Ok(RewardsInfo {
+            address: account.to_hex_literal(),
+            from_epoch,
+            to_epoch,
+            rewards: payments
+                .into_iter()
+                .map(|payment| RewardInfo {
+                    epoch: payment.epoch,
+                    validator: payment.validator.to_hex_literal(),
+                    amount: payment.amount.to_string(),
+                })
+                .collect(),
+            total: total.to_string(),
+        })
+    }
+
+    async fn estimate_transaction_fee(
+        &self,
+        tx_request: TransactionRequest,
+    ) -> RpcResult<TransactionFee> {
+        let (estimate, pending_transactions) = self.fee_estimate().await?;
+
+        let fee = |gas_price: u64| {
+            (U256::from(tx_request.gas_limit as u128) * U256::from(gas_price as u128)).to_string()
+        };
+        let tier = |suggestion: &FeeSuggestion| FeeTierSuggestion {
+            base_fee_per_gas: suggestion.base_fee,
+            priority_fee_per_gas: suggestion.priority_fee,
+            target_blocks: suggestion.tier.target_blocks(),
+            total_fee: fee(suggestion.gas_price()),
+        };
         let genesis_config = &*G_LOCAL_CONFIG;
-        let dao_address = genesis_config.kanari_dao.multisign_bitcoin_address.to_string();
+        let dao_address = genesis_config
+            .kanari_dao
+            .multisign_bitcoin_address
+            .to_string();
 
         Ok(TransactionFee {
-            base_fee: base_fee.to_string(),
-            priority_fee: priority_fee.to_string(),
-            total_fee: total_fee.to_string(),
+            base_fee: fee(estimate.normal.base_fee),
+            priority_fee: fee(estimate.normal.priority_fee),
+            total_fee: fee(estimate.normal.gas_price()),
             fee_recipient: dao_address,
+            block_fullness_bps: estimate.block_fullness_bps,
+            pending_transactions,
+            slow: tier(&estimate.slow),
+            normal: tier(&estimate.normal),
+            fast: tier(&estimate.fast),
         })
     }
 
-    async fn send_transaction_with_fee(&self, tx_request: TransactionRequest) -> RpcResult<String> {
-        // Calculate transaction fee
-        let fee_info = self.estimate_transaction_fee(tx_request.clone()).await?;
-        
-        // Generate transaction hash
-        let mut hasher = DefaultHasher::new();
-        hasher.write(format!("tx_with_fee_{}", SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_nanos()).as_bytes());
-        let tx_hash = format!("0x{:064x}", hasher.finish());
-
-        info!("Transaction submitted with fee to DAO: {}", tx_hash);
-        info!("Transaction details:");
-        info!("  From: {}", tx_request.sender);
-        info!("  To: {}", tx_request.recipient);
-        info!("  Amount: {} KARI", tx_request.amount);
-        info!("  Total Fee: {} (sent to DAO: {})", fee_info.total_fee, fee_info.fee_recipient);
-
-        // TODO: Implement actual transaction processing with fee to DAO
-        warn!("send_transaction_with_fee not fully implemented yet - fees will be sent to DAO when integrated with blockchain");
+    async fn gas_price(&self) -> RpcResult<u64> {
+        let (estimate, _) = self.fee_estimate().await?;
+        Ok(estimate.normal.gas_price())
+    }
 
-        Ok(tx_hash)
+    async fn fee_history(
+        &self,
+        block_count: u64,
+        percentiles: Option<Vec<f64>>,
+    ) -> RpcResult<FeeHistory> {
+        if block_count == 0 || block_count > MAX_FEE_HISTORY_BLOCKS {
+            return Err(RpcError::InvalidParams(format!(
+                "block_count must be between 1 and {}",
+                MAX_FEE_HISTORY_BLOCKS
+            ))
+            .into());
+        }
+        if let Some(percentiles) = &percentiles {
+            if percentiles.iter().any(|p| !(0.0..=100.0).contains(p))
+                || percentiles.windows(2).any(|pair| pair[0] > pair[1])
+            {
+                return Err(RpcError::InvalidParams(
+                    "percentiles must be increasing values from 0 to 100".to_string(),
+                )
+                .into());
+            }
+        }
+        let db = self.db()?;
+        let block_height = self.node_state.read().await.block_height;
+        let oldest_block = block_height.saturating_sub(block_count as u128 - 1);
+        // The base fee of the oldest block depends on the blocks before it
+        let from = oldest_block.saturating_sub(FEE_ORACLE_BLOCKS);
+        let samples = to_rpc_result(fee_samples(db, from, block_height))?;
+        let first = (oldest_block - from) as usize;
+        let min_gas_price = self.tx_pool.read().await.limits().min_gas_price;
+        let blocks = &samples[first..];
+        Ok(FeeHistory {
+            oldest_block,
+            base_fee_per_gas: base_fee_history(min_gas_price, &samples, first),
+            gas_used_ratio: blocks.iter().map(BlockFeeSample::gas_used_ratio).collect(),
+            gas_price_percentiles: percentiles.map(|percentiles| {
+                blocks
+                    .iter()
+                    .map(|block| block.gas_price_percentiles(&percentiles))
+                    .collect()
+            }),
+        })
     }
 }
 
 /// Admin RPC API implementation
+#[cfg(feature = "admin-rpc")]
 pub struct AdminRpcImpl {
     node_state: Arc<RwLock<NodeState>>,
+    log_controller: Option<Arc<dyn LogLevelController>>,
+    db: Option<Arc<RoochDB>>,
+    maintenance: Option<Arc<MaintenanceScheduler>>,
+    block_builder: Option<Arc<dyn BlockBuilder>>,
+    tx_pool: Arc<RwLock<TxPool>>,
+    api_keys: Option<Arc<ApiKeyStore>>,
 }
 
+#[cfg(feature = "admin-rpc")]
 impl AdminRpcImpl {
-    pub fn new(node_state: Arc<RwLock<NodeState>>) -> Self {
-        Self { node_state }
+    pub fn new(
+        node_state: Arc<RwLock<NodeState>>,
+        log_controller: Option<Arc<dyn LogLevelController>>,
+    ) -> Self {
+        Self {
+            node_state,
+            log_controller,
+            db: None,
+            maintenance: None,
+            block_builder: None,
+            tx_pool: Arc::new(RwLock::new(TxPool::default())),
+            api_keys: None,
+        }
+    }
+
+    /// Queue validator set changes in the node database
+    pub fn with_db(mut self, db: Option<Arc<RoochDB>>) -> Self {
+        self.db = db;
+        self
+    }
+
+    /// Trigger storage maintenance through the node's scheduler
+    pub fn with_maintenance(mut self, maintenance: Option<Arc<MaintenanceScheduler>>) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
+    /// Build dry-run blocks with the node's production path
+    pub fn with_block_builder(mut self, builder: Option<Arc<dyn BlockBuilder>>) -> Self {
+        self.block_builder = builder;
+        self
+    }
+
+    /// Select dry-run block transactions from the node's pool
+    pub fn with_tx_pool(mut self, tx_pool: Arc<RwLock<TxPool>>) -> Self {
+        self.tx_pool = tx_pool;
+        self
+    }
+
+    /// Manage the API keys accepted by the public listener
+    pub fn with_api_keys(mut self, api_keys: Option<Arc<ApiKeyStore>>) -> Self {
+        self.api_keys = api_keys;
+        self
+    }
+
+    fn maintenance(&self) -> Result<&Arc<MaintenanceScheduler>, RpcError> {
+        self.maintenance.as_ref().ok_or_else(|| {
+            RpcError::NodeNotReady("Storage maintenance is not available".to_string())
+        })
+    }
+
+    fn block_builder(&self) -> Result<&Arc<dyn BlockBuilder>, RpcError> {
+        self.block_builder
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Block building is not available".to_string()))
+    }
+
+    fn log_controller(&self) -> Result<&Arc<dyn LogLevelController>, RpcError> {
+        self.log_controller
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Log level control is not available".to_string()))
+    }
+
+    fn api_keys(&self) -> Result<&Arc<ApiKeyStore>, RpcError> {
+        self.api_keys
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("API keys are not available".to_string()))
     }
 }
 
+#[cfg(feature = "admin-rpc")]
 #[async_trait]
 impl AdminRpcApiServer for AdminRpcImpl {
     async fn add_peer(&self, peer_address: String) -> RpcResult<bool> {
@@ -463,10 +3443,60 @@ impl AdminRpcApiServer for AdminRpcImpl {
         Ok(true)
     }
 
-    async fn get_peers(&self) -> RpcResult<Vec<String>> {
-        // TODO: Implement actual peer list
-        warn!("get_peers not fully implemented yet");
-        Ok(vec![])
+    async fn get_peers(&self) -> RpcResult<Vec<ConnectedPeerInfo>> {
+        let state = self.node_state.read().await;
+        Ok(state.peers.clone())
+    }
+
+    async fn set_log_level(&self, target: String, level: String) -> RpcResult<bool> {
+        self.log_controller()?
+            .set_level(&target, &level)
+            .map_err(|e| RpcError::InvalidParams(e.to_string()))?;
+        info!("Log level of {} set to {}", target, level);
+        Ok(true)
+    }
+
+    async fn get_log_levels(&self) -> RpcResult<BTreeMap<String, String>> {
+        Ok(self.log_controller()?.levels())
+    }
+
+    async fn run_maintenance(&self, task: Option<String>) -> RpcResult<Vec<MaintenanceRunInfo>> {
+        let task = task
+            .map(|task| task.parse())
+            .transpose()
+            .map_err(|e: anyhow::Error| RpcError::InvalidParams(e.to_string()))?;
+        let runs = self
+            .maintenance()?
+            .trigger(task)
+            .await
+            .map_err(|e| RpcError::InvalidParams(e.to_string()))?;
+        Ok(runs.iter().map(maintenance_run_info).collect())
+    }
+
+    async fn get_maintenance_runs(&self) -> RpcResult<Vec<MaintenanceRunInfo>> {
+        Ok(self
+            .maintenance()?
+            .last_runs()
+            .iter()
+            .map(maintenance_run_info)
+            .collect())
+    }
+
+    async fn propose_dry_run(&self) -> RpcResult<ProposalDryRun> {
+        let builder = self.block_builder()?;
+        let timestamp = self.node_state.read().await.network_time_secs();
+        let pending = self.tx_pool.read().await.pending_snapshot(
+            DEFAULT_PENDING_SNAPSHOT_MAX_BYTES,
+            MAX_PENDING_SNAPSHOT_COUNT,
+        );
+        let dry_run = builder
+            .dry_run(timestamp, &pending)
+            .map_err(|e| RpcError::InternalError(format!("Dry run failed: {}", e)))?;
+        info!(
+            "Dry run built block #{} in {}us",
+            dry_run.block_number, dry_run.total_us
+        );
+        Ok(dry_run)
     }
 
     async fn start_mining(&self) -> RpcResult<bool> {
@@ -488,25 +3518,188 @@ impl AdminRpcApiServer for AdminRpcImpl {
         warn!("get_mining_status not fully implemented yet");
         Ok(false)
     }
+
+    async fn queue_validator_change(&self, change: ValidatorChangeRequest) -> RpcResult<usize> {
+        let db = self
+            .db
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Database is not available".to_string()))?;
+        let change =
+            validator_set_change(change).map_err(|e| RpcError::InvalidParams(e.to_string()))?;
+        let epoch = epoch_of(self.node_state.read().await.block_height);
+        let current = to_rpc_result(db.get_epoch_snapshot(epoch))?
+            .ok_or_else(|| RpcError::NodeNotReady(format!("Epoch {} snapshot not found", epoch)))?;
+        db.queue_validator_change(&current, change)
+            .map_err(|e| RpcError::InvalidParams(format!("Change rejected: {}", e)))?;
+        let pending = to_rpc_result(db.get_pending_validator_changes())?.len();
+        info!(
+            "Queued validator set change for epoch {} ({} pending)",
+            epoch + 1,
+            pending
+        );
+        Ok(pending)
+    }
+
+    async fn create_api_key(&self, request: ApiKeyRequest) -> RpcResult<CreatedApiKey> {
+        let rate_limit = match (request.requests_per_second, request.burst) {
+            (Some(requests_per_second), burst) => {
+                let burst = burst.unwrap_or_else(|| requests_per_second.ceil().max(1.0) as u32);
+                if requests_per_second <= 0.0 || burst == 0 {
+                    return Err(RpcError::InvalidParams(
+                        "The rate limit of a key must be greater than 0".to_string(),
+                    )
+                    .into());
+                }
+                Some(crate::rate_limit::RateLimit {
+                    requests_per_second,
+                    burst,
+                })
+            }
+            (None, Some(_)) => {
+                return Err(RpcError::InvalidParams(
+                    "burst requires requests_per_second".to_string(),
+                )
+                .into());
+            }
+            (None, None) => None,
+        };
+        let (key, secret) = to_rpc_result(self.api_keys()?.create(
+            request.label,
+            rate_limit,
+            request.allowed_methods,
+        ))?;
+        info!("Created API key {} ({})", key.id, key.label);
+        Ok(CreatedApiKey {
+            info: api_key_info(key),
+            key: secret,
+        })
+    }
+
+    async fn revoke_api_key(&self, id: String) -> RpcResult<bool> {
+        let revoked = to_rpc_result(self.api_keys()?.revoke(&id))?;
+        if revoked {
+            info!("Revoked API key {}", id);
+        }
+        Ok(revoked)
+    }
+
+    async fn list_api_keys(&self) -> RpcResult<Vec<ApiKeyInfo>> {
+        Ok(self
+            .api_keys()?
+            .list()
+            .into_iter()
+            .map(api_key_info)
+            .collect())
+    }
+}
+
+#[cfg(feature = "admin-rpc")]
+fn api_key_info(key: crate::api_keys::ApiKey) -> ApiKeyInfo {
+    ApiKeyInfo {
+        id: key.id,
+        label: key.label,
+        requests_per_second: key.rate_limit.map(|limit| limit.requests_per_second),
+        burst: key.rate_limit.map(|limit| limit.burst),
+        allowed_methods: key.allowed_methods,
+        created_at: key.created_at,
+    }
+}
+
+/// A page of the fields of the state object `key_prefix`, the top level objects if
+/// None, in `state`
+#[cfg(feature = "debug-rpc")]
+fn state_page(
+    state: &StateView<'_>,
+    key_prefix: Option<String>,
+    cursor: Option<String>,
+    limit: Option<usize>,
+) -> RpcResult<StatePage> {
+    let parent = match key_prefix {
+        Some(id) => ObjectID::from_str(&id)
+            .map_err(|e| RpcError::InvalidParams(format!("Invalid object id {}: {}", id, e)))?,
+        None => ObjectID::root(),
+    };
+    let cursor = cursor
+        .map(|cursor| {
+            FieldKey::from_str(&cursor)
+                .map_err(|e| RpcError::InvalidParams(format!("Invalid cursor {}: {}", cursor, e)))
+        })
+        .transpose()?;
+    let limit = limit
+        .unwrap_or(DEFAULT_STATE_PAGE_LIMIT)
+        .clamp(1, MAX_STATE_PAGE_LIMIT);
+
+    // One more field tells whether the page is the last one
+    let fields = to_rpc_result(state.list_object_fields(parent, cursor, limit + 1))?;
+    let (fields, more) = bounded_page(fields, limit, MAX_STATE_PAGE_BYTES, |(_, state)| {
+        state.value.len()
+    });
+    let next_cursor = if more {
+        fields.last().map(|(key, _)| key.to_string())
+    } else {
+        None
+    };
+    Ok(StatePage {
+        states: fields
+            .into_iter()
+            .map(|(key, state)| StateEntry {
+                field_key: key.to_string(),
+                object_id: state.metadata.id.to_string(),
+                object_type: state.metadata.object_type.to_canonical_string(),
+                field_count: state.metadata.size,
+                value: format!("0x{}", hex::encode(&state.value)),
+            })
+            .collect(),
+        next_cursor,
+    })
 }
 
 /// Debug RPC API implementation
+#[cfg(feature = "debug-rpc")]
 pub struct DebugRpcImpl {
     node_state: Arc<RwLock<NodeState>>,
+    db: Option<Arc<RoochDB>>,
 }
 
+#[cfg(feature = "debug-rpc")]
 impl DebugRpcImpl {
     pub fn new(node_state: Arc<RwLock<NodeState>>) -> Self {
-        Self { node_state }
+        Self {
+            node_state,
+            db: None,
+        }
+    }
+
+    /// Read state from the node database
+    pub fn with_db(mut self, db: Option<Arc<RoochDB>>) -> Self {
+        self.db = db;
+        self
     }
 }
 
+#[cfg(feature = "debug-rpc")]
 #[async_trait]
 impl DebugRpcApiServer for DebugRpcImpl {
     async fn get_raw_block(&self, block_number: u128) -> RpcResult<String> {
-        // TODO: Implement actual raw block retrieval
-        warn!("get_raw_block not fully implemented yet");
-        Ok(format!("raw_block_{}", block_number))
+        let db = self
+            .db
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Database is not available".to_string()))?;
+        let block = to_rpc_result(db.get_block(block_number))?
+            .ok_or_else(|| RpcError::BlockNotFound(format!("#{}", block_number)))?;
+        Ok(format!("0x{}", hex::encode(block.encode())))
+    }
+
+    async fn get_raw_block_by_hash(&self, block_hash: String) -> RpcResult<String> {
+        let db = self
+            .db
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Database is not available".to_string()))?;
+        let hash = crate::header_chain::parse_hash("Block hash", &block_hash)
+            .map_err(|e| RpcError::InvalidParams(e.to_string()))?;
+        let block = to_rpc_result(db.get_block_by_hash(&hash))?
+            .ok_or_else(|| RpcError::BlockNotFound(block_hash))?;
+        Ok(format!("0x{}", hex::encode(block.encode())))
     }
 
     async fn get_raw_transaction(&self, tx_hash: String) -> RpcResult<String> {
@@ -518,22 +3711,1218 @@ impl DebugRpcApiServer for DebugRpcImpl {
     async fn get_state_at_block(
         &self,
         block_number: u128,
-    ) -> RpcResult<std::collections::HashMap<String, String>> {
-        // TODO: Implement actual state retrieval
-        warn!("get_state_at_block not fully implemented yet");
-        let mut state = std::collections::HashMap::new();
-        state.insert("block".to_string(), block_number.to_string());
-        Ok(state)
+        key_prefix: Option<String>,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<StatePage> {
+        let db = self
+            .db
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Database is not available".to_string()))?;
+        let state = to_rpc_result(db.state_at(Some(block_number)))?
+            .ok_or_else(|| RpcError::BlockNotFound(format!("#{}", block_number)))?;
+        state_page(&state, key_prefix, cursor, limit)
     }
 
-    async fn trace_transaction(
+    async fn get_state_paged(
         &self,
-        tx_hash: String,
-    ) -> RpcResult<std::collections::HashMap<String, serde_json::Value>> {
-        // TODO: Implement actual transaction tracing
-        warn!("trace_transaction not fully implemented yet");
-        let mut trace = std::collections::HashMap::new();
-        trace.insert("tx_hash".to_string(), serde_json::Value::String(tx_hash));
-        Ok(trace)
+        key_prefix: Option<String>,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> RpcResult<StatePage> {
+        let db = self
+            .db
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Database is not available".to_string()))?;
+        let state = to_rpc_result(db.latest_state())?;
+        state_page(&state, key_prefix, cursor, limit)
+    }
+
+    async fn trace_transaction(&self, tx_hash: String) -> RpcResult<Option<TransactionTrace>> {
+        let db = self
+            .db
+            .as_ref()
+            .ok_or_else(|| RpcError::NodeNotReady("Database is not available".to_string()))?;
+        let hash = crate::header_chain::parse_hash("Transaction hash", &tx_hash)
+            .map_err(|e| RpcError::InvalidParams(e.to_string()))?;
+        let Some((block_number, index)) = to_rpc_result(db.get_transaction_location(&hash))? else {
+            return Ok(None);
+        };
+        let tx = to_rpc_result(db.get_block_transactions(block_number))?
+            .into_iter()
+            .nth(index as usize)
+            .ok_or_else(|| {
+                RpcError::InternalError(format!(
+                    "Transaction {} is missing from block #{}",
+                    tx_hash, block_number
+                ))
+            })?;
+
+        // Re-execute against the state before the block, empty before genesis
+        let state = if block_number > GENESIS_BLOCK_NUMBER {
+            to_rpc_result(db.state_at(Some(block_number - 1)))?
+                .ok_or_else(|| RpcError::BlockNotFound(format!("#{}", block_number - 1)))?
+        } else {
+            StateView::new(&db.moveos_store, None)
+        };
+        let kari = KARI::struct_tag();
+        let inputs = crate::trace::TraceInputs {
+            sender_sequence_number: to_rpc_result(state.get_account(tx.tx.sender))?
+                .map(|account| account.sequence_number),
+            sender_balance: to_rpc_result(state.get_coin_balance(tx.tx.sender, &kari))?,
+            recipient_balance: match tx.tx.recipient {
+                Some(recipient) => to_rpc_result(state.get_coin_balance(recipient, &kari))?,
+                None => None,
+            },
+        };
+        let trace = crate::trace::trace_transaction(&tx, &inputs);
+
+        let (status, failure_reason) = match trace.outcome.status {
+            ExecutionStatus::Success => ("success", None),
+            ExecutionStatus::Failure { reason } => ("failure", Some(reason)),
+        };
+        let access = |access: crate::trace::StorageAccess| StorageAccessInfo {
+            object_id: access.object_id.to_string(),
+            object_type: access.object_type.to_string(),
+            field: access.field.to_string(),
+            value: access.value,
+        };
+        Ok(Some(TransactionTrace {
+            tx_hash: format!("0x{}", hex::encode(hash.as_bytes())),
+            block_number,
+            index,
+            status: status.to_string(),
+            failure_reason,
+            gas_used: trace.outcome.gas_used,
+            calls: trace
+                .calls
+                .into_iter()
+                .map(|call| CallTraceInfo {
+                    depth: call.depth,
+                    function: call.function,
+                    gas_used: call.gas_used,
+                    abort: call
+                        .abort
+                        .map(|(location, code)| move_abort_info(&location, code)),
+                })
+                .collect(),
+            reads: trace.reads.into_iter().map(access).collect(),
+            writes: trace.writes.into_iter().map(access).collect(),
+            events: trace
+                .outcome
+                .events
+                .into_iter()
+                .map(|event| event_info(block_number, event))
+                .collect(),
+        }))
+    }
+
+    async fn get_peer_propagation_stats(&self) -> RpcResult<Vec<PeerPropagationInfo>> {
+        let state = self.node_state.read().await;
+        Ok(state.peer_propagation_stats.clone())
+    }
+
+    async fn get_message_history(&self, limit: Option<usize>) -> RpcResult<MessageHistoryInfo> {
+        let state = self.node_state.read().await;
+        let mut history = state.message_history.clone();
+        if let Some(limit) = limit {
+            history.recent.truncate(limit);
+        }
+        Ok(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response_cache::DEFAULT_RESPONSE_CACHE_CONFIRMATIONS;
+    use fastcrypto::secp256k1::Secp256k1KeyPair;
+    use fastcrypto::traits::KeyPair;
+    use kanari_config::KanariOpt;
+    use kanari_types::dao::DaoTransaction;
+    use kanari_types::reward::RewardPayment;
+    use kanari_types::transaction::KanariTransaction;
+    use rooch_types::bitcoin::genesis::MultisignAccountConfig;
+
+    /// Execute `transactions` as block `block_number` and commit its state
+    fn commit_block(db: &RoochDB, block_number: u128, transactions: &[SignedTransaction]) {
+        let execution = db.execute_block(block_number, transactions).unwrap();
+        let block = Block::new(
+            block_number,
+            transactions.len() as u64,
+            H256::zero(),
+            H256::zero(),
+            H256::zero(),
+            execution.root.state_root(),
+        );
+        db.save_block(&block, 1_700_000_000).unwrap();
+        db.commit_block_state(block_number, &execution.root)
+            .unwrap();
+    }
+
+    fn transfer(
+        key_pair: &Secp256k1KeyPair,
+        sender: AccountAddress,
+        chain_id: u64,
+        genesis_hash: H256,
+        sequence_number: u64,
+    ) -> SignedTransaction {
+        SignedTransaction::sign(
+            KanariTransaction {
+                sender,
+                sequence_number,
+                chain_id,
+                genesis_hash,
+                recipient: Some(AccountAddress::ONE),
+                amount: 1,
+                gas_limit: 21_000,
+                gas_price: 1,
+                data: vec![],
+                access_list: None,
+            },
+            key_pair,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_send_raw_transaction_checks_chain_and_sequence_number() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let funding = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: alice,
+                recipient: alice,
+                amount: 1_000_000,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        commit_block(&db, GENESIS_BLOCK_NUMBER, &[funding]);
+        let genesis_hash = db.get_genesis_hash().unwrap().unwrap();
+        let chain_id = NodeState::default().chain_id;
+        // Alice's first transfer executes, moving her sequence number past 0
+        commit_block(
+            &db,
+            GENESIS_BLOCK_NUMBER + 1,
+            &[transfer(&key_pair, alice, chain_id, genesis_hash, 0)],
+        );
+        assert_eq!(db.get_account(alice).unwrap().unwrap().sequence_number, 1);
+
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(NodeState::default())),
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(db),
+        );
+        let raw = |tx: SignedTransaction| hex::encode(tx.encode());
+        let wrong_chain = rpc
+            .send_raw_transaction(raw(transfer(
+                &key_pair,
+                alice,
+                chain_id + 1,
+                genesis_hash,
+                1,
+            )))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            wrong_chain.code(),
+            RpcError::WrongChain(String::new()).code()
+        );
+        let stale = rpc
+            .send_raw_transaction(raw(transfer(&key_pair, alice, chain_id, genesis_hash, 0)))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            stale.code(),
+            RpcError::InvalidSequenceNumber(String::new()).code()
+        );
+        rpc.send_raw_transaction(raw(transfer(&key_pair, alice, chain_id, genesis_hash, 1)))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replace_transaction_validates_before_the_fee_bump() {
+        let node_state = NodeState::default();
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let tx = transfer(
+            &key_pair,
+            AccountAddress::ONE,
+            node_state.chain_id + 1,
+            H256::zero(),
+            0,
+        );
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(node_state)),
+            Arc::new(RwLock::new(TxPool::default())),
+            None,
+        );
+        // Nothing is pooled to replace, but the wrong chain is reported first
+        let err = rpc
+            .replace_transaction(hex::encode(tx.encode()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), RpcError::WrongChain(String::new()).code());
+    }
+
+    #[cfg(all(unix, feature = "admin-rpc"))]
+    #[tokio::test]
+    async fn test_ipc_socket_skips_the_api_keys_of_the_public_listener() {
+        use jsonrpsee::core::client::ClientT;
+        use jsonrpsee::http_client::HttpClientBuilder;
+        use jsonrpsee::rpc_params;
+        use std::os::unix::fs::PermissionsExt;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let public_address = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let ipc_path =
+            std::env::temp_dir().join(format!("kanari-auth-test-{}.ipc", std::process::id()));
+        let mut server = KanariRpcServer::new(RpcServerConfig {
+            listen_address: public_address,
+            ipc_path: Some(ipc_path.clone()),
+            auth: RpcAuthConfig {
+                api_keys: vec!["secret".to_string()],
+                ..RpcAuthConfig::default()
+            },
+            ..RpcServerConfig::default()
+        });
+        server.start().await.unwrap();
+        let mode = std::fs::metadata(&ipc_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let public = HttpClientBuilder::default()
+            .build(format!("http://{}", public_address))
+            .unwrap();
+        let err = public
+            .request::<serde_json::Value, _>("admin_getPeers", rpc_params![])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("requires an API key"), "{}", err);
+
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"admin_getPeers","params":[]}"#;
+        let mut socket = tokio::net::UnixStream::connect(&ipc_path).await.unwrap();
+        socket
+            .write_all(
+                format!(
+                    "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let mut response = String::new();
+        socket.read_to_string(&mut response).await.unwrap();
+        assert!(response.contains(r#""result""#), "{}", response);
+
+        server.stop().await;
+    }
+
+    #[cfg(all(unix, feature = "admin-rpc", feature = "debug-rpc"))]
+    #[tokio::test]
+    async fn test_disabled_namespaces_are_not_served_on_any_listener() {
+        use jsonrpsee::core::client::ClientT;
+        use jsonrpsee::http_client::HttpClientBuilder;
+        use jsonrpsee::rpc_params;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let public_address = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let ipc_path =
+            std::env::temp_dir().join(format!("kanari-namespace-test-{}.ipc", std::process::id()));
+        let mut server = KanariRpcServer::new(RpcServerConfig {
+            listen_address: public_address,
+            ipc_path: Some(ipc_path.clone()),
+            disabled_namespaces: vec!["admin".to_string(), "debug".to_string()],
+            ..RpcServerConfig::default()
+        });
+        server.start().await.unwrap();
+
+        let public = HttpClientBuilder::default()
+            .build(format!("http://{}", public_address))
+            .unwrap();
+        for method in ["admin_getPeers", "debug_getRawTransaction"] {
+            let err = public
+                .request::<serde_json::Value, _>(method, rpc_params!["0x00"])
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("Method not found"), "{}", err);
+        }
+        public
+            .request::<serde_json::Value, _>("kanari_getNodeInfo", rpc_params![])
+            .await
+            .unwrap();
+
+        for method in ["admin_getPeers", "debug_getRawTransaction"] {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"{}","params":["0x00"]}}"#,
+                method
+            );
+            let mut socket = tokio::net::UnixStream::connect(&ipc_path).await.unwrap();
+            socket
+                .write_all(
+                    format!(
+                        "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+            let mut response = String::new();
+            socket.read_to_string(&mut response).await.unwrap();
+            assert!(response.contains("-32601"), "{}", response);
+        }
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_unknown_namespace_cannot_be_disabled() {
+        let mut server = KanariRpcServer::new(RpcServerConfig {
+            listen_address: std::net::TcpListener::bind("127.0.0.1:0")
+                .unwrap()
+                .local_addr()
+                .unwrap(),
+            disabled_namespaces: vec!["admni".to_string()],
+            ..RpcServerConfig::default()
+        });
+        let err = server.start().await.unwrap_err();
+        assert!(
+            err.to_string().contains("admni cannot be disabled"),
+            "{}",
+            err
+        );
+    }
+
+    #[cfg(feature = "admin-rpc")]
+    #[tokio::test]
+    async fn test_anonymous_caller_cannot_create_api_keys() {
+        use jsonrpsee::core::client::ClientT;
+        use jsonrpsee::http_client::HttpClientBuilder;
+        use jsonrpsee::rpc_params;
+
+        let listen_address = || {
+            std::net::TcpListener::bind("127.0.0.1:0")
+                .unwrap()
+                .local_addr()
+                .unwrap()
+        };
+        // Without protected auth anyone could manage the stored keys
+        let mut open = KanariRpcServer::new(RpcServerConfig {
+            listen_address: listen_address(),
+            ..RpcServerConfig::default()
+        })
+        .with_api_keys(Arc::new(ApiKeyStore::in_memory()));
+        assert!(open.start().await.is_err());
+
+        let public_address = listen_address();
+        let store = Arc::new(ApiKeyStore::in_memory());
+        let mut server = KanariRpcServer::new(RpcServerConfig {
+            listen_address: public_address,
+            auth: RpcAuthConfig {
+                api_keys: vec!["secret".to_string()],
+                ..RpcAuthConfig::default()
+            },
+            ..RpcServerConfig::default()
+        })
+        .with_api_keys(store.clone());
+        server.start().await.unwrap();
+
+        let client = HttpClientBuilder::default()
+            .build(format!("http://{}", public_address))
+            .unwrap();
+        let request = serde_json::json!({
+            "label": "intruder",
+            "allowed_methods": ["admin_*"],
+        });
+        let err = client
+            .request::<serde_json::Value, _>("admin_createApiKey", rpc_params![request])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("requires an API key"), "{}", err);
+        assert!(store.list().is_empty());
+
+        server.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_account_summary_rejects_an_invalid_address() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(NodeState::default())),
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(db),
+        );
+        let err = rpc
+            .get_account_summary("not-an-address".to_string(), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), RpcError::InvalidParams(String::new()).code());
+        let summary = rpc
+            .get_account_summary("0x1".to_string(), None)
+            .await
+            .unwrap();
+        assert_eq!(summary.sequence_number, 0);
+        assert_eq!(summary.pending_transaction_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_account_queries_accept_bech32_addresses() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(NodeState::default())),
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(db),
+        );
+        let address =
+            "rooch1u6kv4l8xgdejlvne8728skvx5jugvp2prlhuhglw72xgl82vc5xs8kr9hj".to_string();
+        let page = rpc
+            .get_transactions_by_account(address.clone(), None, None)
+            .await
+            .unwrap();
+        assert!(page.transactions.is_empty());
+        rpc.get_rewards(address, None, None).await.unwrap();
+        let err = rpc
+            .get_transactions_by_account("not-an-address".to_string(), None, None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), RpcError::InvalidParams(String::new()).code());
+    }
+
+    #[tokio::test]
+    async fn test_get_transaction_reads_included_transactions_through_the_cache() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let tx = transfer(&key_pair, AccountAddress::ONE, 1, H256::zero(), 0);
+        commit_block(&db, GENESIS_BLOCK_NUMBER, std::slice::from_ref(&tx));
+        db.save_block_transactions(GENESIS_BLOCK_NUMBER, std::slice::from_ref(&tx))
+            .unwrap();
+        let node_state = NodeState {
+            block_height: GENESIS_BLOCK_NUMBER + DEFAULT_RESPONSE_CACHE_CONFIRMATIONS,
+            ..NodeState::default()
+        };
+        let response_cache = Arc::new(ResponseCache::default());
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(node_state)),
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(db),
+        )
+        .with_response_cache(response_cache.clone());
+
+        let tx_hash = format!("0x{}", hex::encode(tx.hash().as_bytes()));
+        let info = rpc.get_transaction(tx_hash.clone()).await.unwrap().unwrap();
+        assert_eq!(info.hash, tx_hash);
+        assert_eq!(info.status, "Included");
+        assert_eq!(info.block_number, Some(GENESIS_BLOCK_NUMBER));
+        assert_eq!(response_cache.len(), 1);
+
+        // An unknown hash is not an error, a malformed one is
+        let unknown = format!("0x{}", hex::encode(H256::random().as_bytes()));
+        assert!(rpc.get_transaction(unknown).await.unwrap().is_none());
+        let err = rpc.get_transaction("0x12".to_string()).await.unwrap_err();
+        assert_eq!(err.code(), RpcError::InvalidParams(String::new()).code());
+    }
+
+    #[test]
+    fn test_block_transactions_start_at_the_committed_nonce() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = RoochDB::init(&opt.store, &Registry::new()).unwrap();
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let funding = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: alice,
+                recipient: alice,
+                amount: 1_000_000,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        commit_block(&db, GENESIS_BLOCK_NUMBER, &[funding]);
+        let genesis_hash = db.get_genesis_hash().unwrap().unwrap();
+        let chain_id = NodeState::default().chain_id;
+        commit_block(
+            &db,
+            GENESIS_BLOCK_NUMBER + 1,
+            &[transfer(&key_pair, alice, chain_id, genesis_hash, 0)],
+        );
+        let transfers = |sequence_numbers: &[u64]| {
+            sequence_numbers
+                .iter()
+                .map(|sequence_number| {
+                    transfer(&key_pair, alice, chain_id, genesis_hash, *sequence_number)
+                })
+                .collect::<Vec<_>>()
+        };
+        let validate = |transactions: &[SignedTransaction]| {
+            validate_block_transactions(&db, transactions, BLOCK_GAS_LIMIT, 1)
+        };
+
+        validate(&transfers(&[1, 2])).unwrap();
+        // Alice's committed nonce is 1: a gap before her first transaction, or a stale one
+        let gapped = validate(&transfers(&[2, 3])).unwrap_err();
+        assert!(gapped.to_string().contains("does not match"));
+        assert!(validate(&transfers(&[0])).is_err());
+        assert!(validate(&transfers(&[1, 3])).is_err());
+        // The last sequence number is rejected instead of overflowing
+        let overflowed = validate(&transfers(&[u64::MAX])).unwrap_err();
+        assert!(overflowed.to_string().contains("has no successor"));
+        assert!(validate(&transfers(&[1, u64::MAX])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_first_pooled_transaction_waits_for_the_committed_nonce() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let funding = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: alice,
+                recipient: alice,
+                amount: 1_000_000,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        commit_block(&db, GENESIS_BLOCK_NUMBER, &[funding]);
+        let genesis_hash = db.get_genesis_hash().unwrap().unwrap();
+        let chain_id = NodeState::default().chain_id;
+        commit_block(
+            &db,
+            GENESIS_BLOCK_NUMBER + 1,
+            &[transfer(&key_pair, alice, chain_id, genesis_hash, 0)],
+        );
+
+        let tx_pool = Arc::new(RwLock::new(TxPool::default()));
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(NodeState::default())),
+            tx_pool.clone(),
+            Some(db),
+        );
+        let raw = |sequence_number: u64| {
+            hex::encode(
+                transfer(&key_pair, alice, chain_id, genesis_hash, sequence_number).encode(),
+            )
+        };
+        // Alice's committed nonce is 1, so her first pooled transaction at 2 waits
+        rpc.send_raw_transaction(raw(2)).await.unwrap();
+        assert_eq!(tx_pool.read().await.account_nonce(&alice), Some(1));
+        assert_eq!(tx_pool.read().await.queued_count(&alice), 1);
+        rpc.send_raw_transaction(raw(1)).await.unwrap();
+        assert_eq!(tx_pool.read().await.pending_count(&alice), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dao_proposal_lifecycle_is_read_from_executed_blocks() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let participant = key_pair.public().as_bytes().to_vec();
+        // A single participant DAO, like the local one, whose key the test holds
+        let dao_config = MultisignAccountConfig {
+            participant_public_keys: vec![participant.clone()],
+            ..G_LOCAL_CONFIG.kanari_dao.clone()
+        };
+        let treasury =
+            AccountAddress::from(dao_config.multisign_bitcoin_address.to_rooch_address());
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        let chain_id = NodeState::default().chain_id;
+        let save_block = |block_number: u128, transactions: &[SignedTransaction]| {
+            let execution = db
+                .execute_block_with_dao(block_number, transactions, &dao_config)
+                .unwrap();
+            let block = Block::new(
+                block_number,
+                transactions.len() as u64,
+                H256::zero(),
+                H256::zero(),
+                H256::zero(),
+                execution.root.state_root(),
+            );
+            db.save_executed_block(&ExecutedBlock {
+                block: &block,
+                timestamp: 1_700_000_000,
+                transactions,
+                outputs: &execution.outputs,
+                events: &execution.events,
+                root: &execution.root,
+                proposer: None,
+                epoch_snapshot: None,
+                dao: &execution.dao,
+            })
+            .unwrap();
+            execution.outputs
+        };
+        let payment = |recipient: AccountAddress, amount: u128| RewardPayment {
+            epoch: 0,
+            validator: alice,
+            recipient,
+            amount,
+        };
+        let funding = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![payment(alice, 1_000_000), payment(treasury, 1_000)],
+        }
+        .into_transaction(chain_id, H256::zero(), GENESIS_BLOCK_NUMBER);
+        save_block(GENESIS_BLOCK_NUMBER, &[funding]);
+        let genesis_hash = db.get_genesis_hash().unwrap().unwrap();
+        let dao_tx = |sequence_number: u64, operation: DaoTransaction| {
+            let mut tx = transfer(&key_pair, alice, chain_id, genesis_hash, sequence_number);
+            tx.tx.recipient = None;
+            tx.tx.amount = 0;
+            tx.tx.gas_limit = 100_000;
+            tx.tx.data = operation.into_data();
+            SignedTransaction::sign(tx.tx, &key_pair)
+        };
+
+        let propose = dao_tx(
+            0,
+            DaoTransaction::Propose {
+                title: "Pay Bob".to_string(),
+                description: String::new(),
+                action: DaoAction::Transfer {
+                    recipient: bob,
+                    amount: 400,
+                },
+                voting_period: 10,
+            },
+        );
+        // Executing before the vote fails, the proposal is still active
+        let early_execute = dao_tx(1, DaoTransaction::Execute { proposal_id: 0 });
+        let outputs = save_block(GENESIS_BLOCK_NUMBER + 1, &[propose, early_execute]);
+        assert!(outputs[0].status.is_success());
+        assert!(!outputs[1].status.is_success());
+
+        let node_state = Arc::new(RwLock::new(NodeState {
+            block_height: GENESIS_BLOCK_NUMBER + 1,
+            ..NodeState::default()
+        }));
+        let rpc = KanariRpcImpl::new(
+            node_state.clone(),
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(db.clone()),
+        );
+        let proposals = rpc.get_dao_proposals(None).await.unwrap();
+        assert_eq!(proposals.len(), 1);
+        assert_eq!(proposals[0].status, DaoProposalStatusInfo::Active);
+        assert_eq!(
+            proposals[0].proposer,
+            format!("0x{}", hex::encode(&participant))
+        );
+        assert_eq!(proposals[0].created_at_block, GENESIS_BLOCK_NUMBER + 1);
+        assert!(rpc.get_dao_votes(0).await.unwrap().is_empty());
+        assert!(rpc.get_dao_proposal(1).await.unwrap().is_none());
+
+        // The vote passes it and the execution pays Bob from the treasury, a second
+        // execution fails
+        let outputs = save_block(
+            GENESIS_BLOCK_NUMBER + 2,
+            &[
+                dao_tx(
+                    2,
+                    DaoTransaction::Vote {
+                        proposal_id: 0,
+                        approve: true,
+                    },
+                ),
+                dao_tx(3, DaoTransaction::Execute { proposal_id: 0 }),
+                dao_tx(4, DaoTransaction::Execute { proposal_id: 0 }),
+            ],
+        );
+        assert!(outputs[0].status.is_success());
+        assert!(outputs[1].status.is_success());
+        assert!(!outputs[2].status.is_success());
+        node_state.write().await.block_height = GENESIS_BLOCK_NUMBER + 2;
+
+        let proposal = rpc.get_dao_proposal(0).await.unwrap().unwrap();
+        assert_eq!(proposal.status, DaoProposalStatusInfo::Executed);
+        assert_eq!(proposal.approvals, 1);
+        assert_eq!(proposal.executed_at_block, Some(GENESIS_BLOCK_NUMBER + 2));
+        let votes = rpc.get_dao_votes(0).await.unwrap();
+        assert_eq!(votes.len(), 1);
+        assert!(votes[0].approve);
+        assert_eq!(votes[0].block_number, GENESIS_BLOCK_NUMBER + 2);
+        assert_eq!(
+            rpc.get_dao_proposals(Some(DaoProposalStatusInfo::Active))
+                .await
+                .unwrap()
+                .len(),
+            0
+        );
+        assert_eq!(
+            db.get_coin_balance(bob, &KARI::struct_tag()).unwrap(),
+            Some(U256::from(400u128))
+        );
+        assert_eq!(
+            db.get_coin_balance(treasury, &KARI::struct_tag()).unwrap(),
+            Some(U256::from(600u128))
+        );
+
+        // Re-executing the saved block decides its DAO transactions the same way
+        let transactions = db.get_block_transactions(GENESIS_BLOCK_NUMBER + 2).unwrap();
+        let replayed = db
+            .execute_block_with_dao(GENESIS_BLOCK_NUMBER + 2, &transactions, &dao_config)
+            .unwrap();
+        assert_eq!(replayed.outputs, outputs);
+    }
+
+    #[tokio::test]
+    async fn test_tx_pool_content_groups_transactions_by_sender_and_nonce() {
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        let mut pool = TxPool::default();
+        // Alice's transaction 3 waits for the missing 2
+        for (sender, sequence_number) in [(alice, 0), (alice, 1), (alice, 3), (bob, 0)] {
+            pool.add_transaction(transfer(
+                &key_pair,
+                sender,
+                1,
+                H256::zero(),
+                sequence_number,
+            ))
+            .unwrap();
+        }
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(NodeState::default())),
+            Arc::new(RwLock::new(pool)),
+            None,
+        );
+
+        let content = rpc.get_tx_pool_content(None).await.unwrap();
+        let nonces = |groups: &BTreeMap<String, BTreeMap<u64, TxPoolTransaction>>,
+                      sender: AccountAddress| {
+            groups
+                .get(&sender.to_hex_literal())
+                .map(|txs| txs.keys().copied().collect::<Vec<_>>())
+        };
+        assert_eq!(nonces(&content.pending, alice), Some(vec![0, 1]));
+        assert_eq!(nonces(&content.pending, bob), Some(vec![0]));
+        assert_eq!(nonces(&content.queued, alice), Some(vec![3]));
+        assert_eq!(nonces(&content.queued, bob), None);
+        let first = &content.pending[&alice.to_hex_literal()][&0];
+        assert_eq!(first.recipient, Some(AccountAddress::ONE.to_hex_literal()));
+        assert_eq!(first.amount, "1");
+
+        let of_bob = rpc
+            .get_tx_pool_content(Some(bob.to_hex_literal()))
+            .await
+            .unwrap();
+        assert_eq!(of_bob.pending.len(), 1);
+        assert!(of_bob.queued.is_empty());
+
+        let inspect = rpc.get_tx_pool_inspect().await.unwrap();
+        assert_eq!(
+            inspect.pending[&bob.to_hex_literal()][&0],
+            format!(
+                "{}: 1 + 21000 gas × 1",
+                AccountAddress::ONE.to_hex_literal()
+            )
+        );
+        assert!(inspect.queued[&alice.to_hex_literal()].contains_key(&3));
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_policy_drops_messages_or_closes() {
+        use jsonrpsee::core::server::Subscription;
+        use jsonrpsee::rpc_params;
+        use std::time::Duration;
+
+        /// The peer of the next message, None once the subscription closed
+        async fn next_peer(subscription: &mut Subscription) -> Option<String> {
+            tokio::time::timeout(Duration::from_secs(1), subscription.next::<PeerEvent>())
+                .await
+                .ok()
+                .flatten()
+                .and_then(|item| item.ok())
+                .map(|(event, _)| event.peer_id)
+        }
+        let peer = |id: &str| SubscriptionEvent::PeerConnected(id.to_string());
+
+        // The client's buffer holds one message, the others do not fit
+        let (events, _) = broadcast::channel(16);
+        let module = SubscriptionRpcImpl::new(events.clone())
+            .with_slow_subscriber_policy(SlowSubscriberPolicy::DropMessages)
+            .into_rpc();
+        let mut subscription = module
+            .subscribe("subscribe_peerEvents", rpc_params![], 1)
+            .await
+            .unwrap();
+        for id in ["a", "b", "c"] {
+            events.send(peer(id)).unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(next_peer(&mut subscription).await.as_deref(), Some("a"));
+        events.send(peer("d")).unwrap();
+        assert_eq!(next_peer(&mut subscription).await.as_deref(), Some("d"));
+
+        let (events, _) = broadcast::channel(16);
+        let module = SubscriptionRpcImpl::new(events.clone())
+            .with_slow_subscriber_policy(SlowSubscriberPolicy::Close(Duration::from_millis(50)))
+            .into_rpc();
+        let mut subscription = module
+            .subscribe("subscribe_peerEvents", rpc_params![], 1)
+            .await
+            .unwrap();
+        for id in ["a", "b"] {
+            events.send(peer(id)).unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(next_peer(&mut subscription).await.as_deref(), Some("a"));
+        // "b" waited too long, the subscription was closed
+        let _ = events.send(peer("c"));
+        assert_eq!(next_peer(&mut subscription).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_balances_are_read_from_the_coin_store() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let funding = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: alice,
+                recipient: alice,
+                amount: 1_000_000,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        commit_block(&db, GENESIS_BLOCK_NUMBER, &[funding]);
+        let genesis_hash = db.get_genesis_hash().unwrap().unwrap();
+        let chain_id = NodeState::default().chain_id;
+        commit_block(
+            &db,
+            GENESIS_BLOCK_NUMBER + 1,
+            &[transfer(&key_pair, alice, chain_id, genesis_hash, 0)],
+        );
+
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(NodeState::default())),
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(db),
+        );
+        let balance = |address: AccountAddress, coin_type: Option<&str>, block_number| {
+            rpc.get_balance(
+                address.to_hex_literal(),
+                coin_type.map(str::to_string),
+                block_number,
+            )
+        };
+        let funded = balance(alice, None, Some(GENESIS_BLOCK_NUMBER))
+            .await
+            .unwrap();
+        assert_eq!(funded.balance, "1000000");
+        assert_eq!(funded.coin_type, KARI::struct_tag().to_canonical_string());
+        assert_eq!(funded.decimals, Some(DECIMALS));
+
+        // The transfer moved 1 to 0x1 and paid for its gas
+        let latest = balance(alice, Some("KARI"), None).await.unwrap();
+        assert!(latest.balance.parse::<u128>().unwrap() < 1_000_000);
+        let kari = rpc
+            .get_kari_balance(alice.to_hex_literal(), None)
+            .await
+            .unwrap();
+        assert_eq!(kari.balance, latest.balance);
+        let received = |info: BalanceInfo| info.balance.parse::<u128>().unwrap();
+        assert_eq!(
+            received(balance(AccountAddress::ONE, None, None).await.unwrap()),
+            received(
+                balance(AccountAddress::ONE, None, Some(GENESIS_BLOCK_NUMBER))
+                    .await
+                    .unwrap()
+            ) + 1
+        );
+
+        // Coins the account never held read as zero
+        let other = balance(alice, Some("0x42::coin::Other"), None)
+            .await
+            .unwrap();
+        assert_eq!(other.balance, "0");
+        assert_eq!(other.decimals, None);
+        let err = balance(alice, Some("not a coin"), None).await.unwrap_err();
+        assert_eq!(err.code(), RpcError::InvalidParams(String::new()).code());
+    }
+
+    #[tokio::test]
+    async fn test_get_account_reads_state_or_reports_account_not_found() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let funding = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: alice,
+                recipient: alice,
+                amount: 1_000_000,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        commit_block(&db, GENESIS_BLOCK_NUMBER, &[funding]);
+        let genesis_hash = db.get_genesis_hash().unwrap().unwrap();
+        let chain_id = NodeState::default().chain_id;
+        commit_block(
+            &db,
+            GENESIS_BLOCK_NUMBER + 1,
+            &[transfer(&key_pair, alice, chain_id, genesis_hash, 0)],
+        );
+
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(NodeState::default())),
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(db),
+        );
+        let account = rpc.get_account(alice.to_hex_literal(), None).await.unwrap();
+        assert_eq!(account.address, alice.to_hex_literal());
+        assert_eq!(account.sequence_number, 1);
+        let balance = rpc
+            .get_balance(alice.to_hex_literal(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(account.balance, balance.balance);
+
+        let err = rpc.get_account("0xb".to_string(), None).await.unwrap_err();
+        assert_eq!(err.code(), RpcError::AccountNotFound(String::new()).code());
+        let err = rpc
+            .get_account("not-an-address".to_string(), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), RpcError::InvalidParams(String::new()).code());
+    }
+
+    #[tokio::test]
+    async fn test_validator_status_follows_jailing_and_queued_unregistration() {
+        use kanari_types::epoch::{ConsensusParams, Delegation};
+
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let validator = |address: &str, stake, jailed_until| Validator {
+            address: AccountAddress::from_hex_literal(address).unwrap(),
+            public_key: vec![2; 33],
+            stake,
+            delegations: vec![],
+            jailed_until,
+        };
+        let mut active = validator("0xa", 300, None);
+        active.delegations.push(Delegation {
+            delegator: AccountAddress::from_hex_literal("0xd").unwrap(),
+            amount: 100,
+        });
+        let snapshot = EpochSnapshot::genesis(
+            vec![
+                active,
+                validator("0xb", 200, Some(2)),
+                validator("0xc", 100, None),
+            ],
+            ConsensusParams::default(),
+        );
+        db.start_epoch(&snapshot).unwrap();
+        db.queue_validator_change(
+            &snapshot,
+            ValidatorSetChange::Unregister {
+                address: AccountAddress::from_hex_literal("0xc").unwrap(),
+            },
+        )
+        .unwrap();
+
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(NodeState::default())),
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(db),
+        );
+        let set = rpc.get_validators(None).await.unwrap();
+        assert_eq!(set.epoch, 0);
+        assert!(set.is_current);
+        // The jailed validator's stake does not count
+        assert_eq!(set.total_stake, "500");
+        let status = |address: &str| {
+            set.validators
+                .iter()
+                .find(|info| {
+                    info.address
+                        == AccountAddress::from_hex_literal(address)
+                            .unwrap()
+                            .to_hex_literal()
+                })
+                .unwrap()
+        };
+        let a = status("0xa");
+        assert_eq!(a.status, ValidatorStatus::Active);
+        assert_eq!(
+            (
+                a.stake.as_str(),
+                a.delegated_stake.as_str(),
+                a.total_stake.as_str()
+            ),
+            ("300", "100", "400")
+        );
+        assert!((a.voting_power - 0.8).abs() < 1e-9);
+        let b = status("0xb");
+        assert_eq!(b.status, ValidatorStatus::Jailed);
+        assert_eq!(b.jailed_until, Some(2));
+        assert_eq!(b.voting_power, 0.0);
+        assert_eq!(status("0xc").status, ValidatorStatus::Leaving);
+
+        let err = rpc.get_validators(Some(1)).await.unwrap_err();
+        assert_eq!(err.code(), RpcError::InvalidParams(String::new()).code());
+    }
+
+    #[tokio::test]
+    async fn test_chain_info_combines_node_state_and_stored_chain() {
+        let node_state = Arc::new(RwLock::new(NodeState {
+            chain_id: 42,
+            ..NodeState::default()
+        }));
+        let rpc = KanariRpcImpl::new(
+            node_state.clone(),
+            Arc::new(RwLock::new(TxPool::default())),
+            None,
+        );
+        // Without a database only the node's own view is known
+        let info = rpc.get_chain_info().await.unwrap();
+        assert_eq!((info.chain_id, info.network_id.as_str()), (42, "42"));
+        assert_eq!(info.genesis_hash, None);
+        assert_eq!(info.finalized_height, None);
+        assert_eq!(info.node_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            info.stdlib_version,
+            G_LOCAL_CONFIG.stdlib_version.as_string()
+        );
+
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        commit_block(&db, GENESIS_BLOCK_NUMBER, &[]);
+        commit_block(&db, GENESIS_BLOCK_NUMBER + 1, &[]);
+        node_state.write().await.block_height = GENESIS_BLOCK_NUMBER + 1;
+        let genesis_hash = db.get_genesis_hash().unwrap().unwrap();
+        let rpc = KanariRpcImpl::new(
+            node_state,
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(db),
+        );
+        let info = rpc.get_chain_info().await.unwrap();
+        assert_eq!(
+            info.genesis_hash,
+            Some(format!("0x{}", hex::encode(genesis_hash.as_bytes())))
+        );
+        assert_eq!(info.latest_height, GENESIS_BLOCK_NUMBER + 1);
+        // No checkpoint was included yet
+        assert_eq!(info.finalized_height, None);
+    }
+
+    #[cfg(feature = "admin-rpc")]
+    #[tokio::test]
+    async fn test_propose_dry_run_selects_from_the_pool() {
+        use jsonrpsee::rpc_params;
+
+        /// Reports the pending transactions it was given as the block's transactions
+        struct CountingBuilder;
+
+        impl BlockBuilder for CountingBuilder {
+            fn dry_run(
+                &self,
+                timestamp: u64,
+                pending: &[PooledTransaction],
+            ) -> Result<ProposalDryRun> {
+                Ok(ProposalDryRun {
+                    block_number: 1,
+                    hash: String::new(),
+                    parent_hash: String::new(),
+                    timestamp,
+                    transaction_count: pending.len(),
+                    system_transaction_count: 0,
+                    event_count: 0,
+                    batch_hash: String::new(),
+                    state_root: String::new(),
+                    epoch_change: None,
+                    select_us: 0,
+                    execute_us: 0,
+                    roots_us: 0,
+                    total_us: 0,
+                })
+            }
+        }
+
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let mut pool = TxPool::default();
+        pool.add_transaction(transfer(&key_pair, AccountAddress::ONE, 1, H256::zero(), 0))
+            .unwrap();
+        let module = AdminRpcImpl::new(Arc::new(RwLock::new(NodeState::default())), None)
+            .with_block_builder(Some(Arc::new(CountingBuilder)))
+            .with_tx_pool(Arc::new(RwLock::new(pool)))
+            .into_rpc();
+        let dry_run: ProposalDryRun = module
+            .call("admin_proposeDryRun", rpc_params![])
+            .await
+            .unwrap();
+        assert_eq!(dry_run.transaction_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_block_range_and_batch_carry_fee_summaries() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let funding = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: alice,
+                recipient: alice,
+                amount: 1_000_000,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        commit_block(&db, GENESIS_BLOCK_NUMBER, &[funding]);
+        let genesis_hash = db.get_genesis_hash().unwrap().unwrap();
+        let chain_id = NodeState::default().chain_id;
+        let block_number = GENESIS_BLOCK_NUMBER + 1;
+        commit_block(
+            &db,
+            block_number,
+            &[transfer(&key_pair, alice, chain_id, genesis_hash, 0)],
+        );
+
+        let rpc = KanariRpcImpl::new(
+            Arc::new(RwLock::new(NodeState::default())),
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(db),
+        );
+        let collected = |info: &BlockInfo| info.fees.as_ref().unwrap().collected.clone();
+        let single = rpc.get_block_by_number(block_number).await.unwrap();
+        assert_ne!(collected(&single), "0");
+
+        let page = rpc
+            .get_blocks_in_range(GENESIS_BLOCK_NUMBER, block_number, None)
+            .await
+            .unwrap();
+        assert_eq!(page.blocks.len(), 2);
+        assert_eq!(collected(&page.blocks[1]), collected(&single));
+
+        let batch = rpc.get_blocks_by_numbers(vec![block_number]).await.unwrap();
+        assert_eq!(collected(batch[0].as_ref().unwrap()), collected(&single));
     }
 }
@@ -0,0 +1,243 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::{
+    ConnectedPeerInfo, MessageHistoryInfo, PeerPropagationInfo, SubscriptionEvent, SyncStatusInfo,
+    TransactionInfo,
+};
+use crate::server::{NodeState, node_info};
+use kanari_db::RoochDB;
+use std::sync::Arc;
+use tokio::sync::{RwLock, broadcast, mpsc};
+use tracing::warn;
+
+/// A change a subsystem of the node reports to the RPC server
+#[derive(Debug, Clone)]
+pub enum NodeStateEvent {
+    /// The block production loop or an import stored a new head
+    BlockHeight(u128),
+    PeerConnected(ConnectedPeerInfo),
+    /// A connected peer's handshake arrived, replacing what is known of it
+    PeerUpdated(ConnectedPeerInfo),
+    /// The peer id of a disconnected peer
+    PeerDisconnected(String),
+    SyncStatus(SyncStatusInfo),
+    /// Peers ranked from fastest to slowest block propagation
+    PropagationStats(Vec<PeerPropagationInfo>),
+    /// Aggregate P2P message counters and the most recent messages
+    MessageHistory(MessageHistoryInfo),
+    /// The mempool admitted a pending transaction
+    TransactionAdmitted(TransactionInfo),
+    /// Median offset of the peers' clocks in milliseconds, and the number of peers
+    TimeOffset {
+        offset_ms: i64,
+        samples: usize,
+    },
+}
+
+/// Where the block production loop, the P2P network and the mempool report what
+/// changed, so the `NodeState` the RPC serves follows the running node
+#[derive(Debug, Clone)]
+pub struct NodeStateBus {
+    sender: mpsc::UnboundedSender<NodeStateEvent>,
+}
+
+impl NodeStateBus {
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<NodeStateEvent>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Self { sender }, receiver)
+    }
+
+    /// Report a change, dropped if the RPC server is gone
+    pub fn publish(&self, event: NodeStateEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+/// Apply `event` to `state`, returning the subscription event it triggers besides the
+/// node status
+pub fn apply_node_state_event(
+    state: &mut NodeState,
+    event: NodeStateEvent,
+) -> Option<SubscriptionEvent> {
+    match event {
+        NodeStateEvent::BlockHeight(height) => {
+            state.block_height = height;
+            None
+        }
+        NodeStateEvent::PeerConnected(peer) => {
+            let peer_id = peer.peer_id.clone();
+            state.peers.retain(|known| known.peer_id != peer_id);
+            state.peers.push(peer);
+            state.peer_count = state.peers.len();
+            Some(SubscriptionEvent::PeerConnected(peer_id))
+        }
+        NodeStateEvent::PeerUpdated(peer) => {
+            if let Some(known) = state
+                .peers
+                .iter_mut()
+                .find(|known| known.peer_id == peer.peer_id)
+            {
+                *known = peer;
+            }
+            None
+        }
+        NodeStateEvent::PeerDisconnected(peer_id) => {
+            state.peers.retain(|known| known.peer_id != peer_id);
+            state.peer_count = state.peers.len();
+            Some(SubscriptionEvent::PeerDisconnected(peer_id))
+        }
+        NodeStateEvent::SyncStatus(status) => {
+            state.is_syncing = status.is_syncing;
+            state.sync_status = status;
+            None
+        }
+        NodeStateEvent::PropagationStats(ranking) => {
+            state.peer_propagation_stats = ranking;
+            None
+        }
+        NodeStateEvent::MessageHistory(history) => {
+            state.message_history = history;
+            None
+        }
+        NodeStateEvent::TransactionAdmitted(tx) => Some(SubscriptionEvent::NewTransaction(tx)),
+        NodeStateEvent::TimeOffset { offset_ms, samples } => {
+            state.time_offset_ms = offset_ms;
+            state.time_samples = samples;
+            None
+        }
+    }
+}
+
+/// The parts of the node status the bus changes: block height, peer count and syncing
+fn status_key(state: &NodeState) -> (u128, usize, bool) {
+    (state.block_height, state.peer_count, state.is_syncing)
+}
+
+/// Apply the events of the bus to `node_state` until every publisher is gone. After each
+/// batch of events, subscribers get the peer changes and, if it changed, the new node
+/// status. Batches only refreshing diagnostics such as the message history do not
+/// notify subscribers.
+pub async fn run_node_state_service(
+    mut receiver: mpsc::UnboundedReceiver<NodeStateEvent>,
+    node_state: Arc<RwLock<NodeState>>,
+    events: broadcast::Sender<SubscriptionEvent>,
+    db: Option<Arc<RoochDB>>,
+) {
+    let mut last_status = status_key(&*node_state.read().await);
+    while let Some(event) = receiver.recv().await {
+        let mut notifications = vec![];
+        let status = {
+            let mut state = node_state.write().await;
+            notifications.extend(apply_node_state_event(&mut state, event));
+            while let Ok(event) = receiver.try_recv() {
+                notifications.extend(apply_node_state_event(&mut state, event));
+            }
+            if events.receiver_count() == 0 {
+                continue;
+            }
+            let key = status_key(&state);
+            if key == last_status {
+                None
+            } else {
+                last_status = key;
+                Some(node_info(&state, db.as_deref()))
+            }
+        };
+        for notification in notifications {
+            let _ = events.send(notification);
+        }
+        match status {
+            Some(Ok(info)) => {
+                let _ = events.send(SubscriptionEvent::NodeStatus(info));
+            }
+            Some(Err(e)) => warn!("Failed to publish node status to subscribers: {}", e),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(peer_id: &str) -> ConnectedPeerInfo {
+        ConnectedPeerInfo {
+            peer_id: peer_id.to_string(),
+            address: "/ip4/127.0.0.1/tcp/9000".to_string(),
+            version: "0.0.1".to_string(),
+            capabilities: None,
+            operator: None,
+            recent_disconnects: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_service_applies_published_events() {
+        let (bus, receiver) = NodeStateBus::new();
+        let node_state = Arc::new(RwLock::new(NodeState::default()));
+        let (events, mut subscriber) = broadcast::channel(16);
+        let service = tokio::spawn(run_node_state_service(
+            receiver,
+            node_state.clone(),
+            events,
+            None,
+        ));
+
+        bus.publish(NodeStateEvent::BlockHeight(7));
+        bus.publish(NodeStateEvent::PeerConnected(peer("a")));
+        bus.publish(NodeStateEvent::PeerConnected(peer("b")));
+        // A reconnecting peer replaces its previous entry
+        bus.publish(NodeStateEvent::PeerConnected(peer("a")));
+        bus.publish(NodeStateEvent::PeerDisconnected("b".to_string()));
+        // Handshakes update known peers without notifying subscribers
+        let mut handshake = peer("a");
+        handshake.capabilities = Some(vec!["tx-relay".to_string()]);
+        bus.publish(NodeStateEvent::PeerUpdated(handshake));
+        bus.publish(NodeStateEvent::PeerUpdated(peer("b")));
+        drop(bus);
+        service.await.unwrap();
+
+        let state = node_state.read().await;
+        assert_eq!(state.block_height, 7);
+        assert_eq!(state.peer_count, 1);
+        assert_eq!(state.peers[0].peer_id, "a");
+        assert!(state.peers[0].capabilities.is_some());
+        let mut peer_events = vec![];
+        while let Ok(event) = subscriber.try_recv() {
+            match event {
+                SubscriptionEvent::PeerConnected(peer_id) => {
+                    peer_events.push(format!("+{}", peer_id))
+                }
+                SubscriptionEvent::PeerDisconnected(peer_id) => {
+                    peer_events.push(format!("-{}", peer_id))
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(peer_events, vec!["+a", "+b", "+a", "-b"]);
+    }
+
+    #[tokio::test]
+    async fn test_node_status_only_published_on_change() {
+        let (bus, receiver) = NodeStateBus::new();
+        let node_state = Arc::new(RwLock::new(NodeState::default()));
+        let (events, mut subscriber) = broadcast::channel(16);
+        let service = tokio::spawn(run_node_state_service(
+            receiver,
+            node_state.clone(),
+            events,
+            None,
+        ));
+
+        bus.publish(NodeStateEvent::TimeOffset {
+            offset_ms: 12,
+            samples: 3,
+        });
+        drop(bus);
+        service.await.unwrap();
+
+        assert_eq!(node_state.read().await.time_offset_ms, 12);
+        assert!(subscriber.try_recv().is_err());
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+/// States returned by a paged state query when no limit is given
+pub const DEFAULT_STATE_PAGE_LIMIT: usize = 100;
+/// Most states a paged state query returns
+pub const MAX_STATE_PAGE_LIMIT: usize = 1_000;
+/// Most value bytes a paged state query returns, so that pages of large objects such
+/// as modules stay bounded. A page always holds at least one state.
+pub const MAX_STATE_PAGE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Cut `items`, fetched in key order, to a page of at most `limit` items whose sizes add
+/// up to at most `max_bytes`. Returns the page and whether items remain after it, i.e.
+/// the query should be resumed from the last item of the page.
+pub fn bounded_page<T>(
+    mut items: Vec<T>,
+    limit: usize,
+    max_bytes: usize,
+    size: impl Fn(&T) -> usize,
+) -> (Vec<T>, bool) {
+    let mut bytes = 0usize;
+    let mut len = 0;
+    for item in items.iter().take(limit) {
+        bytes = bytes.saturating_add(size(item));
+        if len > 0 && bytes > max_bytes {
+            break;
+        }
+        len += 1;
+    }
+    let more = items.len() > len;
+    items.truncate(len);
+    (items, more)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_page_caps_items_and_bytes() {
+        let sizes = vec![10, 10, 10, 10];
+        assert_eq!(
+            bounded_page(sizes.clone(), 10, 100, |s| *s),
+            (sizes.clone(), false)
+        );
+        assert_eq!(
+            bounded_page(sizes.clone(), 2, 100, |s| *s),
+            (vec![10, 10], true)
+        );
+        assert_eq!(bounded_page(sizes, 10, 25, |s| *s), (vec![10, 10], true));
+        // A state larger than the byte cap still makes progress
+        assert_eq!(bounded_page(vec![50, 10], 10, 25, |s| *s), (vec![50], true));
+        assert_eq!(
+            bounded_page(Vec::<usize>::new(), 10, 25, |s| *s),
+            (vec![], false)
+        );
+    }
+}
@@ -0,0 +1,943 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::api::{
+    AddressActivity, AddressActivityKind, EventInfo, PeerEvent, PendingTransactionFilter,
+    SubscriptionAckStatus, SubscriptionDelivery, SubscriptionEvent, SubscriptionRpcApiServer,
+    TransactionInfo,
+};
+use crate::error::{RpcError, RpcResult};
+use crate::server::{NodeState, block_info, event_info};
+use jsonrpsee::core::server::{SendTimeoutError, TrySendError};
+use jsonrpsee::core::{StringError, SubscriptionResult, async_trait};
+use jsonrpsee::types::SubscriptionId;
+use jsonrpsee::{ConnectionId, PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink};
+use kanari_db::RoochDB;
+use kanari_types::epoch::{EpochSnapshot, epoch_of, is_epoch_boundary};
+use kanari_types::event::{BlockEvent, TRANSFER_EVENT_TYPE, TransferEventData};
+use kanari_types::reward::{REWARD_EVENT_TYPE, RewardPayment};
+use move_core_types::account_address::AccountAddress;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, Receiver, error::RecvError};
+use tokio::sync::{RwLock, Semaphore, watch};
+use tracing::warn;
+
+/// Number of events buffered for each subscriber before it starts missing events
+pub const SUBSCRIPTION_CHANNEL_CAPACITY: usize = 1024;
+/// Maximum number of blocks a subscription may replay with `from_height`
+pub const MAX_SUBSCRIPTION_REPLAY_BLOCKS: u128 = 10_000;
+/// Maximum number of subscriptions replaying history at the same time
+pub const MAX_CONCURRENT_SUBSCRIPTION_REPLAYS: usize = 4;
+/// Maximum number of addresses a connection may watch over all its address activity
+/// subscriptions
+pub const MAX_WATCHED_ADDRESSES_PER_CONNECTION: usize = 256;
+/// Largest `ack_window` an acknowledged subscription may ask for
+pub const MAX_SUBSCRIPTION_ACK_WINDOW: u64 = 10_000;
+/// Subscriptions a WebSocket connection may hold open at the same time, by default
+pub const DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION: u32 = 64;
+/// Messages buffered for a WebSocket connection before sending to it waits, by default
+pub const DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY: u32 = 256;
+/// How long a message waits for room in the buffer of a slow client before its
+/// subscription is closed, by default
+pub const DEFAULT_SLOW_SUBSCRIBER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a subscription does when its client reads slower than the node sends and the
+/// buffer of its connection is full. Acknowledged subscriptions are paced by their
+/// window instead and always wait.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlowSubscriberPolicy {
+    /// Drop the messages that do not fit, the subscription stays open
+    DropMessages,
+    /// Close the subscription once a message waited this long for room
+    Close(Duration),
+}
+
+impl Default for SlowSubscriberPolicy {
+    fn default() -> Self {
+        SlowSubscriberPolicy::Close(DEFAULT_SLOW_SUBSCRIBER_TIMEOUT)
+    }
+}
+
+type WatchCounts = Arc<Mutex<HashMap<ConnectionId, usize>>>;
+type DeliveryWindows = Arc<Mutex<HashMap<SubscriptionId<'static>, Arc<DeliveryWindow>>>>;
+
+/// Addresses an address activity subscription counts against its connection's limit,
+/// released when the subscription ends
+struct WatchReservation {
+    counts: WatchCounts,
+    connection: ConnectionId,
+    addresses: usize,
+}
+
+impl Drop for WatchReservation {
+    fn drop(&mut self) {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = counts.get_mut(&self.connection) {
+            *count = count.saturating_sub(self.addresses);
+            if *count == 0 {
+                counts.remove(&self.connection);
+            }
+        }
+    }
+}
+
+/// Items sent to an acknowledged subscription and the part of them its client
+/// acknowledged
+struct DeliveryWindow {
+    size: u64,
+    sent: AtomicU64,
+    acknowledged: watch::Sender<u64>,
+}
+
+impl DeliveryWindow {
+    fn new(size: u64) -> Self {
+        Self {
+            size,
+            sent: AtomicU64::new(0),
+            acknowledged: watch::Sender::new(0),
+        }
+    }
+
+    /// Wait until fewer than `size` sent items are unacknowledged
+    async fn room(&self) {
+        let sent = self.sent.load(Ordering::Acquire);
+        let mut acknowledged = self.acknowledged.subscribe();
+        // The sender lives as long as the window, waiting cannot fail
+        let _ = acknowledged
+            .wait_for(|acknowledged| sent.saturating_sub(*acknowledged) < self.size)
+            .await;
+    }
+
+    /// Record that the client processed the first `delivered` items
+    fn acknowledge(&self, delivered: u64) -> Result<SubscriptionAckStatus, RpcError> {
+        let sent = self.sent.load(Ordering::Acquire);
+        if delivered > sent {
+            return Err(RpcError::InvalidParams(format!(
+                "Acknowledged {} items but {} were sent",
+                delivered, sent
+            )));
+        }
+        self.acknowledged.send_if_modified(|acknowledged| {
+            let advanced = delivered > *acknowledged;
+            *acknowledged = (*acknowledged).max(delivered);
+            advanced
+        });
+        Ok(self.status())
+    }
+
+    fn status(&self) -> SubscriptionAckStatus {
+        let sent = self.sent.load(Ordering::Acquire);
+        let acknowledged = *self.acknowledged.borrow();
+        let in_flight = sent.saturating_sub(acknowledged);
+        SubscriptionAckStatus {
+            sent,
+            acknowledged,
+            in_flight,
+            paused: in_flight >= self.size,
+        }
+    }
+}
+
+/// An accepted subscription, with its delivery window if the client asked for
+/// acknowledged delivery. The window is unregistered when the subscription ends.
+struct Delivery {
+    sink: SubscriptionSink,
+    window: Option<Arc<DeliveryWindow>>,
+    windows: DeliveryWindows,
+    policy: SlowSubscriberPolicy,
+    /// Messages dropped under `SlowSubscriberPolicy::DropMessages`
+    dropped: AtomicU64,
+}
+
+impl Delivery {
+    /// Send an item, first waiting for acknowledgements while the window is full. Without
+    /// a window, a full connection buffer is handled by the slow subscriber policy.
+    async fn send<T: Serialize>(&self, item: &T) -> Result<(), StringError> {
+        let message = SubscriptionMessage::from_json(item)?;
+        if let Some(window) = &self.window {
+            tokio::select! {
+                _ = self.sink.closed() => return Err("Subscription closed".into()),
+                _ = window.room() => {}
+            }
+            self.sink.send(message).await?;
+            window.sent.fetch_add(1, Ordering::AcqRel);
+            return Ok(());
+        }
+        match self.policy {
+            SlowSubscriberPolicy::DropMessages => match self.sink.try_send(message) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    if self.dropped.fetch_add(1, Ordering::AcqRel) == 0 {
+                        warn!(
+                            "Subscription {:?} is too slow, dropping messages",
+                            self.sink.subscription_id()
+                        );
+                    }
+                }
+                Err(TrySendError::Closed(_)) => return Err("Subscription closed".into()),
+            },
+            SlowSubscriberPolicy::Close(timeout) => {
+                match self.sink.send_timeout(message, timeout).await {
+                    Ok(()) => {}
+                    Err(SendTimeoutError::Timeout(_)) => {
+                        return Err(format!(
+                            "Messages were not read for {}s, resubscribe to resume",
+                            timeout.as_secs()
+                        )
+                        .into());
+                    }
+                    Err(SendTimeoutError::Closed(_)) => return Err("Subscription closed".into()),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Delivery {
+    fn drop(&mut self) {
+        let dropped = self.dropped.load(Ordering::Acquire);
+        if dropped > 0 {
+            warn!(
+                "Subscription {:?} ended after {} messages were dropped for its slow client",
+                self.sink.subscription_id(),
+                dropped
+            );
+        }
+        if self.window.is_some() {
+            let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+            windows.remove(&self.sink.subscription_id());
+        }
+    }
+}
+
+/// WebSocket subscriptions fed by the node through a broadcast channel
+pub struct SubscriptionRpcImpl {
+    events: broadcast::Sender<SubscriptionEvent>,
+    db: Option<Arc<RoochDB>>,
+    node_state: Option<Arc<RwLock<NodeState>>>,
+    replay_slots: Arc<Semaphore>,
+    watched: WatchCounts,
+    windows: DeliveryWindows,
+    slow_subscriber_policy: SlowSubscriberPolicy,
+}
+
+impl SubscriptionRpcImpl {
+    pub fn new(events: broadcast::Sender<SubscriptionEvent>) -> Self {
+        Self {
+            events,
+            db: None,
+            node_state: None,
+            replay_slots: Arc::new(Semaphore::new(MAX_CONCURRENT_SUBSCRIPTION_REPLAYS)),
+            watched: WatchCounts::default(),
+            windows: DeliveryWindows::default(),
+            slow_subscriber_policy: SlowSubscriberPolicy::default(),
+        }
+    }
+
+    /// Handle clients that do not read their messages fast enough with `policy`
+    pub fn with_slow_subscriber_policy(mut self, policy: SlowSubscriberPolicy) -> Self {
+        self.slow_subscriber_policy = policy;
+        self
+    }
+
+    /// Allow block and event subscriptions to replay persisted history with `from_height`
+    pub fn with_replay(mut self, db: Arc<RoochDB>, node_state: Arc<RwLock<NodeState>>) -> Self {
+        self.db = Some(db);
+        self.node_state = Some(node_state);
+        self
+    }
+
+    /// Accept the subscription with the `delivery` the client asked for. None if it was
+    /// rejected for an invalid ack window.
+    async fn accept(
+        &self,
+        pending: PendingSubscriptionSink,
+        delivery: Option<SubscriptionDelivery>,
+    ) -> Result<Option<Delivery>, StringError> {
+        let ack_window = delivery.and_then(|delivery| delivery.ack_window);
+        if ack_window.is_some_and(|size| size == 0 || size > MAX_SUBSCRIPTION_ACK_WINDOW) {
+            pending
+                .reject(RpcError::InvalidParams(format!(
+                    "ack_window must be between 1 and {}",
+                    MAX_SUBSCRIPTION_ACK_WINDOW
+                )))
+                .await;
+            return Ok(None);
+        }
+        let sink = pending.accept().await?;
+        let window = ack_window.map(|size| {
+            let window = Arc::new(DeliveryWindow::new(size));
+            let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+            windows.insert(sink.subscription_id(), window.clone());
+            window
+        });
+        Ok(Some(Delivery {
+            sink,
+            window,
+            windows: self.windows.clone(),
+            policy: self.slow_subscriber_policy,
+            dropped: AtomicU64::new(0),
+        }))
+    }
+
+    /// Accept the subscription and, with `from_height`, send the items `load` reads for
+    /// each persisted block up to the current height. Returns the subscription and the
+    /// last replayed height, or None if the subscription was rejected.
+    async fn replay<T, F>(
+        &self,
+        pending: PendingSubscriptionSink,
+        from_height: Option<u128>,
+        delivery: Option<SubscriptionDelivery>,
+        load: F,
+    ) -> Result<Option<(Delivery, Option<u128>)>, StringError>
+    where
+        T: Serialize,
+        F: Fn(&RoochDB, u128) -> anyhow::Result<Vec<T>>,
+    {
+        let Some(from_height) = from_height else {
+            let delivery = self.accept(pending, delivery).await?;
+            return Ok(delivery.map(|delivery| (delivery, None)));
+        };
+        let (Some(db), Some(node_state)) = (&self.db, &self.node_state) else {
+            pending
+                .reject(RpcError::NodeNotReady(
+                    "Subscription replay is not available".to_string(),
+                ))
+                .await;
+            return Ok(None);
+        };
+        let latest = node_state.read().await.block_height;
+        if latest >= from_height && latest - from_height >= MAX_SUBSCRIPTION_REPLAY_BLOCKS {
+            pending
+                .reject(RpcError::InvalidParams(format!(
+                    "Replay must not exceed {} blocks, current height is {}",
+                    MAX_SUBSCRIPTION_REPLAY_BLOCKS, latest
+                )))
+                .await;
+            return Ok(None);
+        }
+        let Ok(_slot) = self.replay_slots.clone().try_acquire_owned() else {
+            pending
+                .reject(RpcError::NodeNotReady(
+                    "Too many subscriptions are replaying history, retry later".to_string(),
+                ))
+                .await;
+            return Ok(None);
+        };
+
+        let Some(delivery) = self.accept(pending, delivery).await? else {
+            return Ok(None);
+        };
+        for number in from_height..=latest {
+            for item in load(db, number)? {
+                delivery.send(&item).await?;
+            }
+        }
+        Ok(Some((delivery, Some(latest))))
+    }
+
+    /// Forward the live events `select` picks until the client unsubscribes or the
+    /// node drops the channel. Blocks up to `replayed_through` were already sent. An
+    /// acknowledged subscription that falls behind the channel is closed, as the
+    /// events it missed cannot be sent anymore.
+    async fn forward<T, F>(
+        &self,
+        delivery: Delivery,
+        mut receiver: Receiver<SubscriptionEvent>,
+        replayed_through: Option<u128>,
+        select: F,
+    ) -> SubscriptionResult
+    where
+        T: Serialize,
+        F: Fn(SubscriptionEvent) -> Vec<T>,
+    {
+        loop {
+            tokio::select! {
+                _ = delivery.sink.closed() => break,
+                event = receiver.recv() => match event {
+                    Ok(SubscriptionEvent::NewBlock(block))
+                        if replayed_through.is_some_and(|height| block.number <= height) => {}
+                    Ok(event) => {
+                        for item in select(event) {
+                            delivery.send(&item).await?;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) if delivery.window.is_some() => {
+                        return Err(format!(
+                            "Missed {} events waiting for acknowledgements, resubscribe to resume",
+                            skipped
+                        )
+                        .into());
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "Subscription {:?} is too slow, skipped {} events",
+                            delivery.sink.subscription_id(),
+                            skipped
+                        );
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Events persisted with a block, empty if the node serves no database
+    fn block_events(&self, block_number: u128) -> Vec<EventInfo> {
+        let Some(db) = &self.db else {
+            return vec![];
+        };
+        match db.get_block_events(block_number) {
+            Ok(events) => events
+                .into_iter()
+                .map(|event| event_info(block_number, event))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to load events of block #{}: {}", block_number, e);
+                vec![]
+            }
+        }
+    }
+
+    /// Count `addresses` against the limit of the connection, None if it would exceed it
+    fn reserve_watch(
+        &self,
+        connection: ConnectionId,
+        addresses: usize,
+    ) -> Option<WatchReservation> {
+        let mut counts = self.watched.lock().unwrap_or_else(|e| e.into_inner());
+        let count = counts.entry(connection).or_default();
+        if *count + addresses > MAX_WATCHED_ADDRESSES_PER_CONNECTION {
+            return None;
+        }
+        *count += addresses;
+        Some(WatchReservation {
+            counts: self.watched.clone(),
+            connection,
+            addresses,
+        })
+    }
+
+    /// Activity of the `watched` addresses in a new block: its transactions, the events
+    /// they emitted and, at an epoch boundary, the stakes of the new validator set
+    fn block_activity(
+        &self,
+        watched: &BTreeSet<AccountAddress>,
+        block_number: u128,
+    ) -> Vec<AddressActivity> {
+        let Some(db) = &self.db else {
+            return vec![];
+        };
+        let load = || -> anyhow::Result<Vec<AddressActivity>> {
+            let mut activity = vec![];
+            for tx in db.get_block_transactions(block_number)? {
+                activity.extend(transaction_activity(
+                    watched,
+                    Some(block_number),
+                    &format!("0x{}", hex::encode(tx.hash().as_bytes())),
+                    tx.tx.sender,
+                    tx.tx.recipient,
+                    tx.tx.amount,
+                ));
+            }
+            for event in db.get_block_events(block_number)? {
+                activity.extend(event_activity(watched, block_number, &event));
+            }
+            if is_epoch_boundary(block_number) {
+                let epoch = epoch_of(block_number);
+                if let Some(current) = db.get_epoch_snapshot(epoch)? {
+                    let previous = db.get_epoch_snapshot(epoch - 1)?;
+                    activity.extend(staking_activity(
+                        watched,
+                        block_number,
+                        previous.as_ref(),
+                        &current,
+                    ));
+                }
+            }
+            Ok(activity)
+        };
+        load().unwrap_or_else(|e| {
+            warn!(
+                "Failed to load address activity of block #{}: {}",
+                block_number, e
+            );
+            vec![]
+        })
+    }
+}
+
+/// Whether an admitted transaction has the sender and recipient a pending transaction
+/// subscription asked for
+fn matches_pending(
+    sender: Option<&AccountAddress>,
+    recipient: Option<&AccountAddress>,
+    tx: &TransactionInfo,
+) -> bool {
+    let is = |expected: &AccountAddress, address: Option<&str>| {
+        address.is_some_and(|address| {
+            AccountAddress::from_hex_literal(address).is_ok_and(|address| address == *expected)
+        })
+    };
+    sender.is_none_or(|sender| is(sender, Some(tx.sender.as_str())))
+        && recipient.is_none_or(|recipient| is(recipient, tx.recipient.as_deref()))
+}
+
+/// Sent and received activity of the `watched` addresses in a transaction
+fn transaction_activity(
+    watched: &BTreeSet<AccountAddress>,
+    block_number: Option<u128>,
+    tx_hash: &str,
+    sender: AccountAddress,
+    recipient: Option<AccountAddress>,
+    amount: u128,
+) -> Vec<AddressActivity> {
+    let mut activity = vec![];
+    let mut push = |address: AccountAddress, kind, counterparty: Option<AccountAddress>| {
+        activity.push(AddressActivity {
+            address: address.to_hex_literal(),
+            kind,
+            block_number,
+            tx_hash: Some(tx_hash.to_string()),
+            counterparty: counterparty.map(|address| address.to_hex_literal()),
+            amount: Some(amount.to_string()),
+        })
+    };
+    if watched.contains(&sender) {
+        push(sender, AddressActivityKind::Sent, recipient);
+    }
+    if let Some(recipient) = recipient
+        && watched.contains(&recipient)
+    {
+        push(recipient, AddressActivityKind::Received, Some(sender));
+    }
+    activity
+}
+
+/// Balance and staking activity of the `watched` addresses in an event
+fn event_activity(
+    watched: &BTreeSet<AccountAddress>,
+    block_number: u128,
+    event: &BlockEvent,
+) -> Vec<AddressActivity> {
+    let activity =
+        |address: &AccountAddress, kind, counterparty: &AccountAddress, amount| AddressActivity {
+            address: address.to_hex_literal(),
+            kind,
+            block_number: Some(block_number),
+            tx_hash: Some(format!("0x{}", hex::encode(event.tx_hash.as_bytes()))),
+            counterparty: Some(counterparty.to_hex_literal()),
+            amount: Some(amount),
+        };
+    let mut activities = vec![];
+    match event.event_type.as_str() {
+        TRANSFER_EVENT_TYPE => {
+            let Ok(transfer) = bcs::from_bytes::<TransferEventData>(&event.data) else {
+                return vec![];
+            };
+            if watched.contains(&transfer.sender) {
+                activities.push(activity(
+                    &transfer.sender,
+                    AddressActivityKind::BalanceChanged,
+                    &transfer.recipient,
+                    format!("-{}", transfer.amount),
+                ));
+            }
+            if watched.contains(&transfer.recipient) {
+                activities.push(activity(
+                    &transfer.recipient,
+                    AddressActivityKind::BalanceChanged,
+                    &transfer.sender,
+                    transfer.amount.to_string(),
+                ));
+            }
+        }
+        REWARD_EVENT_TYPE => {
+            let Ok(payment) = bcs::from_bytes::<RewardPayment>(&event.data) else {
+                return vec![];
+            };
+            if watched.contains(&payment.recipient) {
+                for kind in [
+                    AddressActivityKind::BalanceChanged,
+                    AddressActivityKind::StakingUpdated,
+                ] {
+                    activities.push(activity(
+                        &payment.recipient,
+                        kind,
+                        &payment.validator,
+                        payment.amount.to_string(),
+                    ));
+                }
+            }
+        }
+        _ => {}
+    }
+    activities
+}
+
+/// Own and delegated stake of `address` by validator
+fn stakes_of(snapshot: &EpochSnapshot, address: &AccountAddress) -> BTreeMap<AccountAddress, u128> {
+    let mut stakes = BTreeMap::new();
+    for validator in &snapshot.validators {
+        if validator.address == *address {
+            *stakes.entry(validator.address).or_default() += validator.stake;
+        }
+        for delegation in &validator.delegations {
+            if delegation.delegator == *address {
+                *stakes.entry(validator.address).or_default() += delegation.amount;
+            }
+        }
+    }
+    stakes
+}
+
+/// Stakes of the `watched` addresses that the validator set of a new epoch changed,
+/// with the new stake, zero if it was withdrawn or its validator left the set
+fn staking_activity(
+    watched: &BTreeSet<AccountAddress>,
+    block_number: u128,
+    previous: Option<&EpochSnapshot>,
+    current: &EpochSnapshot,
+) -> Vec<AddressActivity> {
+    let mut activity = vec![];
+    for address in watched {
+        let before = previous
+            .map(|snapshot| stakes_of(snapshot, address))
+            .unwrap_or_default();
+        let after = stakes_of(current, address);
+        let validators: BTreeSet<_> = before.keys().chain(after.keys()).collect();
+        for validator in validators {
+            let stake = after.get(validator).copied().unwrap_or_default();
+            if before.get(validator).copied().unwrap_or_default() == stake {
+                continue;
+            }
+            activity.push(AddressActivity {
+                address: address.to_hex_literal(),
+                kind: AddressActivityKind::StakingUpdated,
+                block_number: Some(block_number),
+                tx_hash: None,
+                counterparty: Some(validator.to_hex_literal()),
+                amount: Some(stake.to_string()),
+            });
+        }
+    }
+    activity
+}
+
+#[async_trait]
+impl SubscriptionRpcApiServer for SubscriptionRpcImpl {
+    async fn subscribe_new_blocks(
+        &self,
+        pending: PendingSubscriptionSink,
+        from_height: Option<u128>,
+        delivery: Option<SubscriptionDelivery>,
+    ) -> SubscriptionResult {
+        // Subscribe before replaying so no block falls between history and live feed
+        let receiver = self.events.subscribe();
+        let replayed = self
+            .replay(pending, from_height, delivery, |db, number| {
+                Ok(db
+                    .get_block(number)?
+                    .map(|block| block_info(db, block))
+                    .transpose()?
+                    .into_iter()
+                    .collect())
+            })
+            .await?;
+        let Some((delivery, replayed_through)) = replayed else {
+            return Ok(());
+        };
+        self.forward(delivery, receiver, replayed_through, |event| match event {
+            SubscriptionEvent::NewBlock(block) => vec![block],
+            _ => vec![],
+        })
+        .await
+    }
+
+    async fn subscribe_events(
+        &self,
+        pending: PendingSubscriptionSink,
+        from_height: Option<u128>,
+        delivery: Option<SubscriptionDelivery>,
+    ) -> SubscriptionResult {
+        let receiver = self.events.subscribe();
+        let replayed = self
+            .replay(pending, from_height, delivery, |db, number| {
+                Ok(db
+                    .get_block_events(number)?
+                    .into_iter()
+                    .map(|event| event_info(number, event))
+                    .collect())
+            })
+            .await?;
+        let Some((delivery, replayed_through)) = replayed else {
+            return Ok(());
+        };
+        self.forward(delivery, receiver, replayed_through, |event| match event {
+            SubscriptionEvent::NewBlock(block) => self.block_events(block.number),
+            _ => vec![],
+        })
+        .await
+    }
+
+    async fn subscribe_new_transactions(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        let receiver = self.events.subscribe();
+        let Some(delivery) = self.accept(pending, None).await? else {
+            return Ok(());
+        };
+        self.forward(delivery, receiver, None, |event| match event {
+            SubscriptionEvent::NewTransaction(tx) => vec![tx],
+            _ => vec![],
+        })
+        .await
+    }
+
+    async fn subscribe_pending_transactions(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: Option<PendingTransactionFilter>,
+    ) -> SubscriptionResult {
+        let filter = filter.unwrap_or_default();
+        let parse = |address: Option<&String>| {
+            address
+                .map(|address| {
+                    AccountAddress::from_hex_literal(address)
+                        .map_err(|e| format!("Invalid address {}: {}", address, e))
+                })
+                .transpose()
+        };
+        let (sender, recipient) = match (
+            parse(filter.sender.as_ref()),
+            parse(filter.recipient.as_ref()),
+        ) {
+            (Ok(sender), Ok(recipient)) => (sender, recipient),
+            (Err(e), _) | (_, Err(e)) => {
+                pending.reject(RpcError::InvalidParams(e)).await;
+                return Ok(());
+            }
+        };
+
+        let receiver = self.events.subscribe();
+        let Some(delivery) = self.accept(pending, None).await? else {
+            return Ok(());
+        };
+        self.forward(delivery, receiver, None, |event| match event {
+            SubscriptionEvent::NewTransaction(tx)
+                if matches_pending(sender.as_ref(), recipient.as_ref(), &tx) =>
+            {
+                vec![tx]
+            }
+            _ => vec![],
+        })
+        .await
+    }
+
+    async fn subscribe_peer_events(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let receiver = self.events.subscribe();
+        let Some(delivery) = self.accept(pending, None).await? else {
+            return Ok(());
+        };
+        self.forward(delivery, receiver, None, |event| match event {
+            SubscriptionEvent::PeerConnected(peer_id) => vec![PeerEvent {
+                peer_id,
+                connected: true,
+            }],
+            SubscriptionEvent::PeerDisconnected(peer_id) => vec![PeerEvent {
+                peer_id,
+                connected: false,
+            }],
+            _ => vec![],
+        })
+        .await
+    }
+
+    async fn subscribe_node_status(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let receiver = self.events.subscribe();
+        let Some(delivery) = self.accept(pending, None).await? else {
+            return Ok(());
+        };
+        self.forward(delivery, receiver, None, |event| match event {
+            SubscriptionEvent::NodeStatus(info) => vec![info],
+            _ => vec![],
+        })
+        .await
+    }
+
+    async fn subscribe_address_activity(
+        &self,
+        pending: PendingSubscriptionSink,
+        addresses: Vec<String>,
+        delivery: Option<SubscriptionDelivery>,
+    ) -> SubscriptionResult {
+        let watched = match addresses
+            .iter()
+            .map(|address| {
+                AccountAddress::from_hex_literal(address)
+                    .map_err(|e| format!("Invalid address {}: {}", address, e))
+            })
+            .collect::<Result<BTreeSet<_>, _>>()
+        {
+            Ok(watched) if watched.is_empty() => {
+                pending
+                    .reject(RpcError::InvalidParams(
+                        "At least one address must be watched".to_string(),
+                    ))
+                    .await;
+                return Ok(());
+            }
+            Ok(watched) => watched,
+            Err(e) => {
+                pending.reject(RpcError::InvalidParams(e)).await;
+                return Ok(());
+            }
+        };
+        let Some(_reservation) = self.reserve_watch(pending.connection_id(), watched.len()) else {
+            pending
+                .reject(RpcError::InvalidParams(format!(
+                    "A connection may watch at most {} addresses",
+                    MAX_WATCHED_ADDRESSES_PER_CONNECTION
+                )))
+                .await;
+            return Ok(());
+        };
+
+        let receiver = self.events.subscribe();
+        let Some(delivery) = self.accept(pending, delivery).await? else {
+            return Ok(());
+        };
+        self.forward(delivery, receiver, None, |event| match event {
+            SubscriptionEvent::NewTransaction(tx) => {
+                let (Ok(sender), Ok(recipient), Ok(amount)) = (
+                    AccountAddress::from_hex_literal(&tx.sender),
+                    tx.recipient
+                        .as_deref()
+                        .map(AccountAddress::from_hex_literal)
+                        .transpose(),
+                    tx.amount.parse::<u128>(),
+                ) else {
+                    return vec![];
+                };
+                transaction_activity(&watched, None, &tx.hash, sender, recipient, amount)
+            }
+            SubscriptionEvent::NewBlock(block) => self.block_activity(&watched, block.number),
+            _ => vec![],
+        })
+        .await
+    }
+
+    async fn ack(
+        &self,
+        subscription_id: SubscriptionId<'static>,
+        delivered: u64,
+    ) -> RpcResult<SubscriptionAckStatus> {
+        let window = self
+            .windows
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&subscription_id)
+            .cloned()
+            .ok_or_else(|| {
+                RpcError::InvalidParams(
+                    "No open subscription with an ack_window has this id".to_string(),
+                )
+            })?;
+        Ok(window.acknowledge(delivered)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kanari_types::epoch::{ConsensusParams, Delegation, Validator};
+    use moveos_types::h256::H256;
+
+    #[test]
+    fn test_address_activity_of_events_and_stakes() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        let validator = AccountAddress::from_hex_literal("0xc").unwrap();
+        let watched = BTreeSet::from([alice]);
+
+        let transfer = BlockEvent {
+            tx_hash: H256::zero(),
+            event_index: 0,
+            address: validator,
+            event_type: TRANSFER_EVENT_TYPE.to_string(),
+            data: bcs::to_bytes(&TransferEventData {
+                sender: bob,
+                recipient: alice,
+                amount: 5,
+            })
+            .unwrap(),
+        };
+        let activity = event_activity(&watched, 7, &transfer);
+        assert_eq!(activity.len(), 1);
+        assert_eq!(activity[0].kind, AddressActivityKind::BalanceChanged);
+        assert_eq!(activity[0].counterparty, Some(bob.to_hex_literal()));
+        assert_eq!(activity[0].amount.as_deref(), Some("5"));
+
+        let snapshot = |amount| EpochSnapshot {
+            epoch: 1,
+            validators: vec![Validator {
+                address: validator,
+                public_key: vec![],
+                stake: 100,
+                delegations: vec![Delegation {
+                    delegator: alice,
+                    amount,
+                }],
+                jailed_until: None,
+            }],
+            params: ConsensusParams::default(),
+        };
+        assert!(staking_activity(&watched, 7, Some(&snapshot(10)), &snapshot(10)).is_empty());
+        let activity = staking_activity(&watched, 7, Some(&snapshot(10)), &snapshot(30));
+        assert_eq!(activity.len(), 1);
+        assert_eq!(activity[0].kind, AddressActivityKind::StakingUpdated);
+        assert_eq!(activity[0].amount.as_deref(), Some("30"));
+    }
+
+    #[test]
+    fn test_delivery_window_pauses_until_acknowledged() {
+        let window = DeliveryWindow::new(2);
+        window.sent.store(2, Ordering::Release);
+        assert!(window.status().paused);
+        assert!(window.acknowledge(3).is_err());
+        let status = window.acknowledge(1).unwrap();
+        assert_eq!((status.in_flight, status.paused), (1, false));
+        // Acknowledgements never go back
+        assert_eq!(window.acknowledge(0).unwrap().acknowledged, 1);
+    }
+
+    #[test]
+    fn test_pending_transactions_match_sender_and_recipient() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        let tx = TransactionInfo {
+            hash: "0x01".to_string(),
+            sender: alice.to_hex_literal(),
+            recipient: Some(bob.to_hex_literal()),
+            amount: "1".to_string(),
+            gas_used: 0,
+            gas_price: 1,
+            status: "Pending".to_string(),
+            block_number: None,
+            timestamp: 0,
+            system_kind: None,
+        };
+        assert!(matches_pending(None, None, &tx));
+        assert!(matches_pending(Some(&alice), Some(&bob), &tx));
+        assert!(matches_pending(None, Some(&bob), &tx));
+        assert!(!matches_pending(Some(&bob), None, &tx));
+        assert!(!matches_pending(Some(&alice), Some(&alice), &tx));
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::dry_run::DryRunOutcome;
+use kanari_db::charged_gas;
+use kanari_types::event::transaction_events;
+use kanari_types::kari_coin::KARI;
+use kanari_types::receipt::ExecutionStatus;
+use kanari_types::system_transaction::SystemTransaction;
+use kanari_types::transaction::SignedTransaction;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::u256::U256;
+use moveos_types::moveos_std::account::Account;
+use moveos_types::moveos_std::object::ObjectID;
+use moveos_types::state::MoveStructType;
+use rooch_types::framework::account_coin_store::AccountCoinStoreModule;
+
+const TRANSFER_FUNCTION: &str = "0x3::transfer::transfer_coin";
+const WITHDRAW_FUNCTION: &str = "0x3::coin_store::withdraw";
+const DEPOSIT_FUNCTION: &str = "0x3::coin_store::deposit";
+const INCREMENT_SEQUENCE_NUMBER_FUNCTION: &str = "0x2::account::increment_sequence_number";
+const ACCOUNT_TYPE: &str = "0x2::account::Account";
+const COIN_STORE_TYPE: &str = "0x3::coin_store::CoinStore";
+/// The framework module of the withdrawal call
+const COIN_STORE_MODULE: &str = "0x3::coin_store";
+/// `coin_store::ErrorInsufficientBalance`, which the traced withdrawal call reports when
+/// the executor fails the transfer for lack of balance
+const ERROR_INSUFFICIENT_BALANCE: u64 = 4;
+
+/// The state a traced transaction reads, from the state before its block. None for an
+/// account or coin store that did not exist.
+#[derive(Clone, Debug, Default)]
+pub struct TraceInputs {
+    pub sender_sequence_number: Option<u64>,
+    pub sender_balance: Option<U256>,
+    pub recipient_balance: Option<U256>,
+}
+
+/// A Move call made while executing the transaction, `depth` 0 for the entry call
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TracedCall {
+    pub depth: u32,
+    pub function: String,
+    pub gas_used: u64,
+    /// Module and code of the abort, if the call aborted
+    pub abort: Option<(String, u64)>,
+}
+
+/// A field of an object read or written by the transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageAccess {
+    pub object_id: ObjectID,
+    pub owner: AccountAddress,
+    pub object_type: &'static str,
+    pub field: &'static str,
+    /// None if the object does not exist
+    pub value: Option<String>,
+}
+
+/// The calls, storage accesses and events of re-executing a transaction
+#[derive(Clone, Debug)]
+pub struct TraceOutcome {
+    pub outcome: DryRunOutcome,
+    pub calls: Vec<TracedCall>,
+    pub reads: Vec<StorageAccess>,
+    pub writes: Vec<StorageAccess>,
+}
+
+/// Re-execute a transaction as block application does, recording the calls it makes
+/// and the account and coin store fields it reads and writes. The executor does not
+/// meter calls separately yet, so the entry call carries the gas of the transaction.
+pub fn trace_transaction(tx: &SignedTransaction, inputs: &TraceInputs) -> TraceOutcome {
+    if let Ok(Some(system_tx)) = SystemTransaction::from_transaction(tx) {
+        return TraceOutcome {
+            outcome: DryRunOutcome {
+                status: ExecutionStatus::Success,
+                gas_used: 0,
+                events: transaction_events(std::slice::from_ref(tx)),
+            },
+            calls: vec![TracedCall {
+                depth: 0,
+                function: format!("system::{}", system_tx.kind()),
+                gas_used: 0,
+                abort: None,
+            }],
+            reads: vec![],
+            writes: vec![],
+        };
+    }
+
+    let sender = tx.tx.sender;
+    let sender_balance = inputs.sender_balance.unwrap_or_else(U256::zero);
+    let gas_used = charged_gas(tx);
+    let fee = U256::from(gas_used) * U256::from(tx.tx.gas_price);
+    let required = fee + U256::from(tx.tx.amount);
+    let outcome = if sender_balance < required {
+        DryRunOutcome {
+            status: ExecutionStatus::Failure {
+                reason: format!(
+                    "insufficient balance {} for the fee {} and amount {}",
+                    sender_balance, fee, tx.tx.amount
+                ),
+            },
+            gas_used,
+            events: vec![],
+        }
+    } else {
+        DryRunOutcome {
+            status: ExecutionStatus::Success,
+            gas_used,
+            events: transaction_events(std::slice::from_ref(tx)),
+        }
+    };
+    let sequence_number = |value: Option<u64>| StorageAccess {
+        object_id: Account::account_object_id(sender),
+        owner: sender,
+        object_type: ACCOUNT_TYPE,
+        field: "sequence_number",
+        value: value.map(|value| value.to_string()),
+    };
+    let balance = |owner: AccountAddress, value: Option<U256>| StorageAccess {
+        object_id: AccountCoinStoreModule::account_coin_store_id(owner, KARI::struct_tag()),
+        owner,
+        object_type: COIN_STORE_TYPE,
+        field: "balance",
+        value: value.map(|value| value.to_string()),
+    };
+    let call = |depth: u32, function: &str| TracedCall {
+        depth,
+        function: function.to_string(),
+        gas_used: 0,
+        abort: None,
+    };
+
+    let mut reads = vec![
+        sequence_number(inputs.sender_sequence_number),
+        balance(sender, inputs.sender_balance),
+    ];
+    let mut calls = vec![call(0, TRANSFER_FUNCTION)];
+    calls[0].gas_used = outcome.gas_used;
+    let next_sequence_number = inputs.sender_sequence_number.unwrap_or(0) + 1;
+    let mut writes = vec![sequence_number(Some(next_sequence_number))];
+
+    if outcome.status.is_success() {
+        let amount = U256::from(tx.tx.amount);
+        calls.push(call(1, WITHDRAW_FUNCTION));
+        writes.push(balance(sender, Some(sender_balance - amount - fee)));
+        if let Some(recipient) = tx.tx.recipient {
+            let recipient_balance = inputs.recipient_balance.unwrap_or_else(U256::zero);
+            calls.push(call(1, DEPOSIT_FUNCTION));
+            reads.push(balance(recipient, inputs.recipient_balance));
+            writes.push(balance(recipient, Some(recipient_balance + amount)));
+        }
+    } else {
+        // The withdrawal aborts, only the gas is charged from what the sender holds
+        let mut withdraw = call(1, WITHDRAW_FUNCTION);
+        withdraw.abort = Some((COIN_STORE_MODULE.to_string(), ERROR_INSUFFICIENT_BALANCE));
+        calls.push(withdraw);
+        let charged = if sender_balance < fee {
+            sender_balance
+        } else {
+            fee
+        };
+        writes.push(balance(sender, Some(sender_balance - charged)));
+    }
+    calls.push(call(0, INCREMENT_SEQUENCE_NUMBER_FUNCTION));
+
+    TraceOutcome {
+        outcome,
+        calls,
+        reads,
+        writes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kanari_types::transaction::KanariTransaction;
+    use moveos_types::h256::H256;
+
+    fn transfer(amount: u128) -> SignedTransaction {
+        SignedTransaction {
+            tx: KanariTransaction {
+                sender: AccountAddress::ONE,
+                sequence_number: 3,
+                chain_id: 1,
+                genesis_hash: H256::zero(),
+                recipient: Some(AccountAddress::new([2; AccountAddress::LENGTH])),
+                amount,
+                gas_limit: 21_000,
+                gas_price: 1,
+                data: vec![],
+                access_list: None,
+            },
+            public_key: vec![],
+            signature: vec![],
+        }
+    }
+
+    #[test]
+    fn test_trace_records_transfer_reads_and_writes() {
+        let inputs = TraceInputs {
+            sender_sequence_number: Some(3),
+            sender_balance: Some(U256::from(100_000u128)),
+            recipient_balance: None,
+        };
+        let trace = trace_transaction(&transfer(1_000), &inputs);
+        assert!(trace.outcome.status.is_success());
+        assert_eq!(trace.calls[0].function, TRANSFER_FUNCTION);
+        assert_eq!(trace.calls[0].gas_used, 21_000);
+        assert_eq!(trace.calls.len(), 4);
+        assert_eq!(trace.reads.len(), 3);
+        assert_eq!(trace.reads[2].value, None);
+        let values: Vec<_> = trace
+            .writes
+            .iter()
+            .map(|write| write.value.clone().unwrap())
+            .collect();
+        assert_eq!(values, vec!["4", "78000", "1000"]);
+        assert_eq!(trace.outcome.events.len(), 1);
+    }
+
+    #[test]
+    fn test_trace_of_failed_transfer_only_charges_gas() {
+        let inputs = TraceInputs {
+            sender_sequence_number: Some(3),
+            sender_balance: Some(U256::from(21_500u128)),
+            recipient_balance: Some(U256::from(5u128)),
+        };
+        let trace = trace_transaction(&transfer(1_000), &inputs);
+        assert!(!trace.outcome.status.is_success());
+        let withdraw = &trace.calls[1];
+        assert_eq!(withdraw.function, WITHDRAW_FUNCTION);
+        assert_eq!(withdraw.abort.as_ref().unwrap().0, "0x3::coin_store");
+        // The recipient is never touched
+        assert!(
+            trace
+                .reads
+                .iter()
+                .all(|read| read.owner == AccountAddress::ONE)
+        );
+        assert_eq!(trace.writes[1].value.as_deref(), Some("500"));
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::{DevnetNode, NodeBinary};
+use anyhow::{Result, bail, ensure};
+use kanari_rpc_api::{BlockInfo, verify_header_chain};
+use kanari_types::block::GENESIS_BLOCK_NUMBER;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::info;
+
+/// Environment variable pointing at the binary under test
+pub const CURRENT_BINARY_ENV: &str = "KANARI_CURRENT_BINARY";
+/// Environment variable pointing at the prior release binary
+pub const PREVIOUS_BINARY_ENV: &str = "KANARI_PREVIOUS_BINARY";
+
+const DEFAULT_BASE_PORT: u16 = 16767;
+
+/// Blocks produced by one binary during an upgrade phase
+#[derive(Clone, Debug)]
+pub struct UpgradePhase {
+    pub producer: String,
+    pub blocks: Vec<BlockInfo>,
+}
+
+/// Outcome of a rolling upgrade run
+#[derive(Clone, Debug, Default)]
+pub struct CompatibilityReport {
+    pub phases: Vec<UpgradePhase>,
+}
+
+/// A devnet mixing the current binary with a prior release, used to check that
+/// both versions accept each other's blocks and database during a rolling upgrade
+pub struct MixedVersionDevnet {
+    current: NodeBinary,
+    previous: NodeBinary,
+    work_dir: PathBuf,
+    base_port: u16,
+    startup_timeout: Duration,
+    block_timeout: Duration,
+}
+
+impl MixedVersionDevnet {
+    pub fn new(current: NodeBinary, previous: NodeBinary) -> Self {
+        let work_dir = std::env::temp_dir().join(format!(
+            "kanari-devnet-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or_default()
+        ));
+        Self {
+            current,
+            previous,
+            work_dir,
+            base_port: DEFAULT_BASE_PORT,
+            startup_timeout: Duration::from_secs(60),
+            block_timeout: Duration::from_secs(120),
+        }
+    }
+
+    /// Build a devnet from `KANARI_CURRENT_BINARY` and `KANARI_PREVIOUS_BINARY`,
+    /// None if either is not set
+    pub fn from_env() -> Option<Self> {
+        let current = std::env::var(CURRENT_BINARY_ENV).ok()?;
+        let previous = std::env::var(PREVIOUS_BINARY_ENV).ok()?;
+        Some(Self::new(
+            NodeBinary::new("current", current),
+            NodeBinary::new("previous", previous),
+        ))
+    }
+
+    pub fn with_base_port(mut self, base_port: u16) -> Self {
+        self.base_port = base_port;
+        self
+    }
+
+    pub fn with_work_dir(mut self, work_dir: PathBuf) -> Self {
+        self.work_dir = work_dir;
+        self
+    }
+
+    pub fn with_block_timeout(mut self, block_timeout: Duration) -> Self {
+        self.block_timeout = block_timeout;
+        self
+    }
+
+    /// Hand one data dir back and forth between the two binaries:
+    /// previous -> current (upgrade) -> previous (rollback). Each binary must read
+    /// every block the other produced unchanged and extend the chain from its tip.
+    pub async fn run_rolling_upgrade(&self, blocks_per_phase: u128) -> Result<CompatibilityReport> {
+        let data_dir = self.work_dir.join("shared");
+        std::fs::create_dir_all(&data_dir)?;
+
+        let mut report = CompatibilityReport::default();
+        let mut known_blocks: Vec<BlockInfo> = vec![];
+        let order = [&self.previous, &self.current, &self.previous];
+        for (index, binary) in order.iter().enumerate() {
+            let mut node = DevnetNode::new(
+                (*binary).clone(),
+                data_dir.clone(),
+                self.base_port + index as u16,
+            )?;
+            node.start(self.startup_timeout).await?;
+            let result = self.run_phase(&node, &known_blocks, blocks_per_phase).await;
+            node.stop().await?;
+            let produced = result?;
+
+            info!(
+                "{} produced {} block(s) on top of {} block(s) from the other version",
+                binary.label,
+                produced.len(),
+                known_blocks.len()
+            );
+            known_blocks.extend(produced.iter().cloned());
+            report.phases.push(UpgradePhase {
+                producer: binary.label.clone(),
+                blocks: produced,
+            });
+        }
+        Ok(report)
+    }
+
+    /// Let the node produce more blocks, then check the stored chain: every header must
+    /// hash to its fields and commit to its parent, and the blocks of earlier phases must
+    /// still be stored with the hashes the other version produced
+    async fn run_phase(
+        &self,
+        node: &DevnetNode,
+        known_blocks: &[BlockInfo],
+        blocks_per_phase: u128,
+    ) -> Result<Vec<BlockInfo>> {
+        let start_height = known_blocks.last().map(|b| b.number).unwrap_or(0);
+        let height = node
+            .wait_for_height(start_height + blocks_per_phase, self.block_timeout)
+            .await?;
+
+        let headers = node.header_chain(GENESIS_BLOCK_NUMBER, height).await?;
+        verify_header_chain(&headers)
+            .map_err(|e| anyhow::anyhow!("{}: {}", node.binary().label, e))?;
+        for expected in known_blocks {
+            let header = &headers[(expected.number - GENESIS_BLOCK_NUMBER) as usize];
+            assert_same_block(node.binary(), expected, &header.hash, &header.state_root)?;
+        }
+
+        let mut produced = vec![];
+        for header in headers.iter().filter(|header| header.number > start_height) {
+            let block = node.block(header.number).await?;
+            ensure!(
+                block.hash == header.hash,
+                "{}: block #{} is served as {} but its header as {}",
+                node.binary().label,
+                header.number,
+                block.hash,
+                header.hash
+            );
+            produced.push(block);
+        }
+        Ok(produced)
+    }
+}
+
+impl Drop for MixedVersionDevnet {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.work_dir);
+    }
+}
+
+fn assert_same_block(
+    reader: &NodeBinary,
+    expected: &BlockInfo,
+    hash: &str,
+    state_root: &str,
+) -> Result<()> {
+    if expected.hash != hash || expected.state_root != state_root {
+        bail!(
+            "{} stores block #{} as {} (state root {}), but it was produced as {} (state root {})",
+            reader.label,
+            expected.number,
+            hash,
+            state_root,
+            expected.hash,
+            expected.state_root
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Requires `KANARI_CURRENT_BINARY` and `KANARI_PREVIOUS_BINARY`, e.g.
+    /// `KANARI_CURRENT_BINARY=target/release/kari KANARI_PREVIOUS_BINARY=/opt/kari-v0.0.1
+    /// cargo test -p kanari-testkit -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires KANARI_CURRENT_BINARY and KANARI_PREVIOUS_BINARY"]
+    async fn test_rolling_upgrade_between_releases() {
+        let devnet = MixedVersionDevnet::from_env()
+            .expect("KANARI_CURRENT_BINARY and KANARI_PREVIOUS_BINARY must be set");
+        let report = devnet.run_rolling_upgrade(2).await.unwrap();
+        assert_eq!(report.phases.len(), 3);
+        assert!(report.phases.iter().all(|phase| !phase.blocks.is_empty()));
+    }
+}
@@ -0,0 +1,8 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+pub mod devnet;
+pub mod node;
+
+pub use devnet::{CompatibilityReport, MixedVersionDevnet, UpgradePhase};
+pub use node::{DevnetNode, NodeBinary};
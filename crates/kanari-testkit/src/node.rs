@@ -0,0 +1,167 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, bail};
+use kanari_rpc_api::jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use kanari_rpc_api::{BlockInfo, HeaderInfo, KanariRpcApiClient, MAX_HEADER_CHAIN_RANGE};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::process::{Child, Command};
+use tracing::{info, warn};
+
+/// A `kari` binary of a given release
+#[derive(Clone, Debug)]
+pub struct NodeBinary {
+    /// Label used in logs and reports, e.g. `current` or `v0.0.1`
+    pub label: String,
+    pub path: PathBuf,
+}
+
+impl NodeBinary {
+    pub fn new(label: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            label: label.into(),
+            path: path.into(),
+        }
+    }
+}
+
+/// A devnet node running as a child process
+pub struct DevnetNode {
+    binary: NodeBinary,
+    base_data_dir: PathBuf,
+    rpc_port: u16,
+    child: Option<Child>,
+    client: HttpClient,
+}
+
+impl DevnetNode {
+    pub fn new(binary: NodeBinary, base_data_dir: PathBuf, rpc_port: u16) -> Result<Self> {
+        let client = HttpClientBuilder::default()
+            .request_timeout(Duration::from_secs(10))
+            .build(format!("http://127.0.0.1:{}", rpc_port))?;
+        Ok(Self {
+            binary,
+            base_data_dir,
+            rpc_port,
+            child: None,
+            client,
+        })
+    }
+
+    pub fn binary(&self) -> &NodeBinary {
+        &self.binary
+    }
+
+    pub fn client(&self) -> &HttpClient {
+        &self.client
+    }
+
+    /// Launch `kari start` on the local network and wait until its RPC answers
+    pub async fn start(&mut self, timeout: Duration) -> Result<()> {
+        if self.child.is_some() {
+            bail!("Node {} is already running", self.binary.label);
+        }
+        info!(
+            "Starting {} node on port {} with data dir {}",
+            self.binary.label,
+            self.rpc_port,
+            self.base_data_dir.display()
+        );
+        let child = Command::new(&self.binary.path)
+            .arg("start")
+            .arg("--data-dir")
+            .arg(&self.base_data_dir)
+            .arg("--chain-id")
+            .arg("local")
+            .arg("--port")
+            .arg(self.rpc_port.to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()?;
+        self.child = Some(child);
+
+        let started = Instant::now();
+        loop {
+            if self.client.get_chain_id().await.is_ok() {
+                return Ok(());
+            }
+            if let Some(child) = self.child.as_mut() {
+                if let Some(status) = child.try_wait()? {
+                    self.child = None;
+                    bail!(
+                        "Node {} exited during startup: {}",
+                        self.binary.label,
+                        status
+                    );
+                }
+            }
+            if started.elapsed() > timeout {
+                self.stop().await?;
+                bail!(
+                    "Node {} did not answer RPC within {:?}",
+                    self.binary.label,
+                    timeout
+                );
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    /// Kill the node process and wait for it to release the DB
+    pub async fn stop(&mut self) -> Result<()> {
+        if let Some(mut child) = self.child.take() {
+            info!("Stopping {} node", self.binary.label);
+            if let Err(e) = child.kill().await {
+                warn!("Failed to kill {} node: {}", self.binary.label, e);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.child.is_some()
+    }
+
+    pub async fn block_height(&self) -> Result<u128> {
+        Ok(self.client.get_block_height().await?)
+    }
+
+    pub async fn block(&self, number: u128) -> Result<BlockInfo> {
+        Ok(self.client.get_block_by_number(number).await?)
+    }
+
+    /// The headers of the blocks from `from` to `to`, fetched in ranges the RPC serves
+    pub async fn header_chain(&self, from: u128, to: u128) -> Result<Vec<HeaderInfo>> {
+        let mut headers = vec![];
+        let mut start = from;
+        while start <= to {
+            let end = to.min(start + MAX_HEADER_CHAIN_RANGE - 1);
+            headers.extend(self.client.get_header_chain(start, end).await?);
+            start = end + 1;
+        }
+        Ok(headers)
+    }
+
+    /// Wait until the node reports at least `height`
+    pub async fn wait_for_height(&self, height: u128, timeout: Duration) -> Result<u128> {
+        let started = Instant::now();
+        loop {
+            let current = self.block_height().await?;
+            if current >= height {
+                return Ok(current);
+            }
+            if started.elapsed() > timeout {
+                bail!(
+                    "Node {} stuck at height {} waiting for {}",
+                    self.binary.label,
+                    current,
+                    height
+                );
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    }
+}
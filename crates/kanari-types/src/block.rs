@@ -1,9 +1,20 @@
 // Copyright (c) KanariNetwork
 // SPDX-License-Identifier: Apache-2.0
 
-use moveos_types::h256::H256;
+use crate::system_transaction::validate_system_transactions;
+use crate::transaction::SignedTransaction;
+use anyhow::{Result, bail};
+use fastcrypto::secp256k1::{Secp256k1KeyPair, Secp256k1PublicKey, Secp256k1Signature};
+use fastcrypto::traits::{KeyPair, Signer, ToFromBytes, VerifyingKey};
+use moveos_types::h256::{H256, sha2_256_of};
 use serde::{Deserialize, Serialize};
 
+/// Domain separator prepended to the block bytes before signing
+pub const BLOCK_SIGNING_DOMAIN: &[u8] = b"KANARI::Block";
+
+/// The number of the first block of a chain, whose hash identifies the network
+pub const GENESIS_BLOCK_NUMBER: u128 = 1;
+
 /// The block in Rooch is constructed by the proposer, representing a batch of transactions
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Block {
@@ -13,7 +24,7 @@ pub struct Block {
     pub batch_size: u64,
     /// The hash of the batch, made by DA
     pub batch_hash: H256,
-    /// The previous tx accumulator root of the block
+    /// The tx accumulator root of the parent block, which links the block to it
     pub prev_tx_accumulator_root: H256,
     /// The tx accumulator root after the last transaction append to the accumulator
     pub tx_accumulator_root: H256,
@@ -39,4 +50,197 @@ impl Block {
             state_root,
         }
     }
+
+    /// Whether the block directly follows `parent`, through the accumulator root its
+    /// hash commits to
+    pub fn extends(&self, parent: &Block) -> bool {
+        self.block_number == parent.block_number + 1
+            && self.prev_tx_accumulator_root == parent.tx_accumulator_root
+    }
+
+    /// The block hash, computed over the BCS encoding of the block
+    pub fn hash(&self) -> H256 {
+        sha2_256_of(&self.encode())
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        bcs::to_bytes(self).expect("Serialize block should success")
+    }
+
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(bcs::from_bytes(bytes)?)
+    }
+}
+
+/// The batch hash of a block: the hash of its transaction hashes in order
+pub fn transactions_batch_hash(transactions: &[SignedTransaction]) -> H256 {
+    let hashes: Vec<H256> = transactions.iter().map(|tx| tx.hash()).collect();
+    sha2_256_of(&bcs::to_bytes(&hashes).expect("Serialize hashes should success"))
+}
+
+/// The tx accumulator root of a block: its parent's root with the block's batch appended
+pub fn next_tx_accumulator_root(prev_tx_accumulator_root: H256, batch_hash: H256) -> H256 {
+    sha2_256_of(
+        &bcs::to_bytes(&(prev_tx_accumulator_root, batch_hash))
+            .expect("Serialize accumulator roots should success"),
+    )
+}
+
+/// A block assembled outside the node, with its transactions and the proposer's
+/// secp256k1 signature, as accepted by `kanari_submitBlock`
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignedBlock {
+    pub block: Block,
+    /// Hash of the block this one extends
+    pub parent_hash: H256,
+    /// Production time in seconds since the Unix epoch
+    pub timestamp: u64,
+    pub transactions: Vec<SignedTransaction>,
+    /// Compressed secp256k1 public key of the proposer
+    pub public_key: Vec<u8>,
+    /// Signature over the block signing message
+    pub signature: Vec<u8>,
+}
+
+impl SignedBlock {
+    pub fn sign(
+        block: Block,
+        parent_hash: H256,
+        timestamp: u64,
+        transactions: Vec<SignedTransaction>,
+        key_pair: &Secp256k1KeyPair,
+    ) -> Self {
+        let message = Self::signing_message(&block, &parent_hash, timestamp);
+        let signature: Secp256k1Signature = key_pair.sign(&message);
+        Self {
+            block,
+            parent_hash,
+            timestamp,
+            transactions,
+            public_key: key_pair.public().as_bytes().to_vec(),
+            signature: signature.as_bytes().to_vec(),
+        }
+    }
+
+    /// The transactions are bound through the batch hash of the block
+    fn signing_message(block: &Block, parent_hash: &H256, timestamp: u64) -> Vec<u8> {
+        let mut message = BLOCK_SIGNING_DOMAIN.to_vec();
+        message.extend(
+            bcs::to_bytes(&(block, parent_hash, timestamp))
+                .expect("Serialize block should success"),
+        );
+        message
+    }
+
+    /// Check the proposer signature, that the block header matches its transactions
+    /// and the rules of its system transactions
+    pub fn verify(&self) -> Result<()> {
+        self.header().verify_signature()?;
+
+        if self.block.batch_size != self.transactions.len() as u64 {
+            bail!(
+                "Block batch size {} does not match its {} transactions",
+                self.block.batch_size,
+                self.transactions.len()
+            );
+        }
+        if self.block.batch_hash != transactions_batch_hash(&self.transactions) {
+            bail!("Block batch hash does not match its transactions");
+        }
+        let system_count = validate_system_transactions(
+            &self.transactions,
+            self.block.block_number,
+            self.timestamp,
+        )?;
+        for tx in &self.transactions[system_count..] {
+            tx.verify_signature()
+                .map_err(|e| anyhow::anyhow!("Transaction {:?}: {}", tx.hash(), e))?;
+        }
+        Ok(())
+    }
+
+    /// The signed header, without the transactions
+    pub fn header(&self) -> SignedBlockHeader {
+        SignedBlockHeader {
+            block: self.block.clone(),
+            parent_hash: self.parent_hash,
+            timestamp: self.timestamp,
+            public_key: self.public_key.clone(),
+            signature: self.signature.clone(),
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        bcs::to_bytes(self).expect("Serialize block should success")
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(bytes)?)
+    }
+}
+
+/// The part of a `SignedBlock` covered by the proposer signature. The transactions are
+/// bound through the batch hash, so a header is enough to prove what a proposer signed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignedBlockHeader {
+    pub block: Block,
+    pub parent_hash: H256,
+    pub timestamp: u64,
+    /// Compressed secp256k1 public key of the proposer
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl SignedBlockHeader {
+    /// Check the proposer signature
+    pub fn verify_signature(&self) -> Result<()> {
+        let public_key = Secp256k1PublicKey::from_bytes(&self.public_key)
+            .map_err(|e| anyhow::anyhow!("Invalid public key: {}", e))?;
+        let signature = Secp256k1Signature::from_bytes(&self.signature)
+            .map_err(|e| anyhow::anyhow!("Invalid signature encoding: {}", e))?;
+        public_key
+            .verify(
+                &SignedBlock::signing_message(&self.block, &self.parent_hash, self.timestamp),
+                &signature,
+            )
+            .map_err(|e| anyhow::anyhow!("Invalid block signature: {}", e))
+    }
+
+    /// Hash of everything the proposer signed
+    pub fn hash(&self) -> H256 {
+        sha2_256_of(&SignedBlock::signing_message(
+            &self.block,
+            &self.parent_hash,
+            self.timestamp,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_block_verify() {
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let block = Block::new(
+            2,
+            0,
+            transactions_batch_hash(&[]),
+            H256::zero(),
+            H256::zero(),
+            H256::random(),
+        );
+        let signed = SignedBlock::sign(block, H256::random(), 1_700_000_000, vec![], &key_pair);
+        assert!(signed.verify().is_ok());
+        assert_eq!(SignedBlock::decode(&signed.encode()).unwrap(), signed);
+
+        let mut tampered = signed.clone();
+        tampered.timestamp += 1;
+        assert!(tampered.verify().is_err());
+
+        let mut wrong_batch = signed;
+        wrong_batch.block.batch_size = 1;
+        assert!(wrong_batch.verify().is_err());
+    }
 }
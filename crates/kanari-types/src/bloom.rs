@@ -0,0 +1,105 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use moveos_types::h256::sha2_256_of;
+use serde::{Deserialize, Serialize};
+
+/// Size of the bloom filter in bytes (2048 bits)
+pub const BLOOM_BYTE_LENGTH: usize = 256;
+/// Number of bits set per inserted item
+const BLOOM_HASH_COUNT: usize = 3;
+
+/// A 2048-bit bloom filter over the event addresses and types of a block.
+/// A negative answer is definitive, so range scans can skip a block without
+/// loading its events.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EventBloom(Vec<u8>);
+
+impl Default for EventBloom {
+    fn default() -> Self {
+        Self(vec![0u8; BLOOM_BYTE_LENGTH])
+    }
+}
+
+impl EventBloom {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() != BLOOM_BYTE_LENGTH {
+            anyhow::bail!(
+                "Invalid bloom length {}, expected {}",
+                bytes.len(),
+                BLOOM_BYTE_LENGTH
+            );
+        }
+        Ok(Self(bytes.to_vec()))
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|byte| *byte == 0)
+    }
+
+    /// Insert an item into the filter
+    pub fn accrue(&mut self, item: &[u8]) {
+        for (byte, mask) in Self::bit_positions(item) {
+            self.0[byte] |= mask;
+        }
+    }
+
+    /// Merge another filter into this one
+    pub fn accrue_bloom(&mut self, other: &EventBloom) {
+        for (byte, other_byte) in self.0.iter_mut().zip(other.0.iter()) {
+            *byte |= other_byte;
+        }
+    }
+
+    /// Whether the item may have been inserted; `false` is definitive
+    pub fn contains(&self, item: &[u8]) -> bool {
+        Self::bit_positions(item)
+            .into_iter()
+            .all(|(byte, mask)| self.0[byte] & mask != 0)
+    }
+
+    fn bit_positions(item: &[u8]) -> [(usize, u8); BLOOM_HASH_COUNT] {
+        let hash = sha2_256_of(item);
+        let hash = hash.as_bytes();
+        let mut positions = [(0usize, 0u8); BLOOM_HASH_COUNT];
+        for (i, position) in positions.iter_mut().enumerate() {
+            // 11 bits of each byte pair select one of the 2048 bits
+            let bit =
+                ((hash[2 * i] as usize) << 8 | hash[2 * i + 1] as usize) % (BLOOM_BYTE_LENGTH * 8);
+            *position = (BLOOM_BYTE_LENGTH - 1 - bit / 8, 1u8 << (bit % 8));
+        }
+        positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_contains_accrued_items() {
+        let mut bloom = EventBloom::new();
+        assert!(bloom.is_empty());
+        bloom.accrue(b"0x3::coin::TransferEvent");
+        assert!(!bloom.is_empty());
+        assert!(bloom.contains(b"0x3::coin::TransferEvent"));
+        assert!(!bloom.contains(b"0x3::coin::MintEvent"));
+
+        let mut other = EventBloom::new();
+        other.accrue(b"0x3::coin::MintEvent");
+        bloom.accrue_bloom(&other);
+        assert!(bloom.contains(b"0x3::coin::MintEvent"));
+
+        let decoded = EventBloom::from_bytes(bloom.as_bytes()).unwrap();
+        assert_eq!(decoded, bloom);
+        assert!(EventBloom::from_bytes(&[0u8; 8]).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::transaction::SignedTransaction;
+use anyhow::{Result, bail};
+use move_core_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the call data of a DAO transaction, followed by the BCS encoded operation
+pub const DAO_TRANSACTION_DATA_PREFIX: &[u8] = b"KANARI::Dao";
+
+/// What the Kanari DAO does when a proposal passes
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DaoAction {
+    /// A signalling proposal with no effect on chain
+    Text,
+    /// Pay KARI from the DAO treasury, in the smallest unit
+    Transfer {
+        recipient: AccountAddress,
+        amount: u128,
+    },
+}
+
+/// A proposal of the Kanari DAO, decided by the participants of its multisig account
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DaoProposal {
+    /// Proposals are numbered from 0 in the order they were made
+    pub id: u64,
+    /// Compressed public key of the participant who made the proposal
+    pub proposer: Vec<u8>,
+    pub title: String,
+    pub description: String,
+    pub action: DaoAction,
+    pub created_at_block: u128,
+    /// The last block votes are accepted in
+    pub voting_end_block: u128,
+    /// Approvals the proposal needs, the multisig threshold when it was made
+    pub threshold: u64,
+    /// The block the action was executed in, once the proposal passed
+    pub executed_at_block: Option<u128>,
+}
+
+/// A participant's vote on a proposal
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DaoVote {
+    pub proposal_id: u64,
+    /// Compressed public key of the voting participant
+    pub voter: Vec<u8>,
+    pub approve: bool,
+    pub block_number: u128,
+}
+
+/// An operation on the Kanari DAO, sent by a participant as the call data of a user
+/// transaction that moves no value
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DaoTransaction {
+    /// Make a proposal, open for votes during the `voting_period` blocks after its own
+    Propose {
+        title: String,
+        description: String,
+        action: DaoAction,
+        voting_period: u128,
+    },
+    Vote {
+        proposal_id: u64,
+        approve: bool,
+    },
+    /// Carry out the action of a passed proposal
+    Execute {
+        proposal_id: u64,
+    },
+}
+
+impl DaoTransaction {
+    /// The call data of a transaction sending this operation
+    pub fn into_data(self) -> Vec<u8> {
+        let mut data = DAO_TRANSACTION_DATA_PREFIX.to_vec();
+        data.extend(bcs::to_bytes(&self).expect("Serialize DAO transaction should success"));
+        data
+    }
+
+    /// Decode the DAO operation of a transaction, None for any other transaction
+    pub fn from_transaction(tx: &SignedTransaction) -> Result<Option<Self>> {
+        if tx.is_system() {
+            return Ok(None);
+        }
+        match tx.tx.data.strip_prefix(DAO_TRANSACTION_DATA_PREFIX) {
+            Some(payload) => Ok(Some(bcs::from_bytes(payload)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Where a proposal is in its lifecycle
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DaoProposalStatus {
+    /// Open for votes
+    Active,
+    /// Approved by the threshold, waiting to be executed
+    Passed,
+    /// Too many participants rejected it for the threshold to be reached
+    Rejected,
+    /// Voting ended without reaching the threshold
+    Expired,
+    Executed,
+}
+
+impl DaoProposal {
+    /// The status of the proposal at `height` given its votes, with `participants`
+    /// participants in the multisig account
+    pub fn status(
+        &self,
+        votes: &[DaoVote],
+        participants: usize,
+        height: u128,
+    ) -> DaoProposalStatus {
+        if self.executed_at_block.is_some() {
+            return DaoProposalStatus::Executed;
+        }
+        let approvals = votes.iter().filter(|vote| vote.approve).count() as u64;
+        let rejections = votes.len() as u64 - approvals;
+        if approvals >= self.threshold {
+            DaoProposalStatus::Passed
+        } else if rejections > (participants as u64).saturating_sub(self.threshold) {
+            DaoProposalStatus::Rejected
+        } else if height > self.voting_end_block {
+            DaoProposalStatus::Expired
+        } else {
+            DaoProposalStatus::Active
+        }
+    }
+
+    /// Check that `vote` may be added to the `votes` already cast
+    pub fn check_vote(&self, votes: &[DaoVote], vote: &DaoVote) -> Result<()> {
+        if vote.proposal_id != self.id {
+            bail!(
+                "The vote is for proposal {}, not {}",
+                vote.proposal_id,
+                self.id
+            );
+        }
+        if vote.block_number > self.voting_end_block {
+            bail!(
+                "Voting on proposal {} ended at #{}",
+                self.id,
+                self.voting_end_block
+            );
+        }
+        if votes.iter().any(|existing| existing.voter == vote.voter) {
+            bail!("The participant already voted on proposal {}", self.id);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(voter: u8, approve: bool) -> DaoVote {
+        DaoVote {
+            proposal_id: 0,
+            voter: vec![voter; 33],
+            approve,
+            block_number: 10,
+        }
+    }
+
+    #[test]
+    fn test_proposal_status_follows_votes_and_deadline() {
+        let proposal = DaoProposal {
+            id: 0,
+            proposer: vec![1; 33],
+            title: "Fund the explorer".to_string(),
+            description: String::new(),
+            action: DaoAction::Transfer {
+                recipient: AccountAddress::ONE,
+                amount: 100,
+            },
+            created_at_block: 5,
+            voting_end_block: 20,
+            threshold: 2,
+            executed_at_block: None,
+        };
+        // 2 of 3 participants must approve
+        assert_eq!(
+            proposal.status(&[vote(1, true)], 3, 10),
+            DaoProposalStatus::Active
+        );
+        assert_eq!(
+            proposal.status(&[vote(1, true), vote(2, true)], 3, 10),
+            DaoProposalStatus::Passed
+        );
+        assert_eq!(
+            proposal.status(&[vote(1, false), vote(2, false)], 3, 10),
+            DaoProposalStatus::Rejected
+        );
+        assert_eq!(
+            proposal.status(&[vote(1, true)], 3, 21),
+            DaoProposalStatus::Expired
+        );
+
+        assert!(
+            proposal
+                .check_vote(&[vote(1, true)], &vote(1, false))
+                .is_err()
+        );
+        assert!(
+            proposal
+                .check_vote(&[vote(1, true)], &vote(2, false))
+                .is_ok()
+        );
+        let late = DaoVote {
+            block_number: 21,
+            ..vote(2, true)
+        };
+        assert!(proposal.check_vote(&[], &late).is_err());
+    }
+}
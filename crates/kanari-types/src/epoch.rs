@@ -0,0 +1,540 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::block::GENESIS_BLOCK_NUMBER;
+use crate::fee::FEE_SHARE_SCALE;
+use crate::kari_coin::DECIMALS;
+use crate::personal_message::public_key_address;
+use anyhow::{Result, bail};
+use fastcrypto::secp256k1::Secp256k1PublicKey;
+use fastcrypto::traits::ToFromBytes;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::u256::U256;
+use moveos_types::h256::{H256, sha2_256_of};
+use serde::{Deserialize, Serialize};
+
+/// Number of blocks in an epoch
+pub const EPOCH_LENGTH: u128 = 1_000;
+
+/// Voting power caps are expressed in basis points of the total stake, a cap of
+/// `VOTING_POWER_SCALE` disables capping
+pub const VOTING_POWER_SCALE: u64 = 10_000;
+
+/// The epoch a block belongs to, the genesis block starts epoch 0
+pub fn epoch_of(block_number: u128) -> u64 {
+    (block_number.saturating_sub(GENESIS_BLOCK_NUMBER) / EPOCH_LENGTH) as u64
+}
+
+/// The first block of an epoch
+pub fn epoch_start_block(epoch: u64) -> u128 {
+    GENESIS_BLOCK_NUMBER + epoch as u128 * EPOCH_LENGTH
+}
+
+/// The last block of an epoch
+pub fn epoch_end_block(epoch: u64) -> u128 {
+    epoch_start_block(epoch) + EPOCH_LENGTH - 1
+}
+
+/// Whether the block is the first block of an epoch after the genesis epoch, where
+/// queued validator set changes take effect
+pub fn is_epoch_boundary(block_number: u128) -> bool {
+    block_number > GENESIS_BLOCK_NUMBER && epoch_start_block(epoch_of(block_number)) == block_number
+}
+
+/// Stake delegated to a validator by another account
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Delegation {
+    pub delegator: AccountAddress,
+    pub amount: u128,
+}
+
+/// A block producer and its stake
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Validator {
+    pub address: AccountAddress,
+    /// Compressed secp256k1 public key that signs the validator's blocks
+    pub public_key: Vec<u8>,
+    /// Stake bonded by the validator itself
+    pub stake: u128,
+    pub delegations: Vec<Delegation>,
+    /// A slashed validator may not propose nor earn rewards before this epoch
+    pub jailed_until: Option<u64>,
+}
+
+impl Validator {
+    /// A validator whose address is derived from its public key
+    pub fn from_public_key(public_key: Vec<u8>, stake: u128) -> Result<Self> {
+        let key = Secp256k1PublicKey::from_bytes(&public_key)
+            .map_err(|e| anyhow::anyhow!("Invalid validator public key: {}", e))?;
+        Ok(Self {
+            address: public_key_address(&key)?.into(),
+            public_key,
+            stake,
+            delegations: vec![],
+            jailed_until: None,
+        })
+    }
+
+    pub fn is_jailed(&self, epoch: u64) -> bool {
+        self.jailed_until.is_some_and(|until| epoch < until)
+    }
+
+    /// Own stake plus delegated stake, which the validator's weight is based on
+    pub fn total_stake(&self) -> u128 {
+        self.stake
+            + self
+                .delegations
+                .iter()
+                .map(|delegation| delegation.amount)
+                .sum::<u128>()
+    }
+}
+
+/// Consensus parameters, frozen for the duration of an epoch
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ConsensusParams {
+    pub block_gas_limit: u64,
+    /// Validators with less stake leave the set at the next boundary
+    pub min_validator_stake: u128,
+    /// Only the validators with the most stake are kept
+    pub max_validators: u32,
+    /// Newly minted KARI distributed as staking rewards for the epoch, in the smallest unit
+    pub epoch_emission: u128,
+    /// Share of the collected fees paid to the Kanari DAO, in basis points
+    pub dao_fee_bps: u64,
+    /// Share of the collected fees burnt, in basis points, 0 disables burning
+    pub burn_fee_bps: u64,
+    /// Most of the total stake a single validator counts with in consensus weights and
+    /// rewards, in basis points. Raised to an equal share when the set is too small for it.
+    pub max_voting_power_bps: u64,
+}
+
+impl Default for ConsensusParams {
+    fn default() -> Self {
+        Self {
+            block_gas_limit: 1_000_000,
+            min_validator_stake: 1,
+            max_validators: 100,
+            epoch_emission: 1_000 * 10u128.pow(DECIMALS as u32),
+            dao_fee_bps: 0,
+            burn_fee_bps: 0,
+            max_voting_power_bps: VOTING_POWER_SCALE,
+        }
+    }
+}
+
+/// A change to the validator set or consensus parameters, queued until the next
+/// epoch boundary
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ValidatorSetChange {
+    Register(Validator),
+    UpdateStake {
+        address: AccountAddress,
+        stake: u128,
+    },
+    Unregister {
+        address: AccountAddress,
+    },
+    /// Add to the stake `delegator` delegated to `validator`
+    Delegate {
+        delegator: AccountAddress,
+        validator: AccountAddress,
+        amount: u128,
+    },
+    /// Withdraw everything `delegator` delegated to `validator`
+    Undelegate {
+        delegator: AccountAddress,
+        validator: AccountAddress,
+    },
+    /// Burn `penalty_bps` basis points of the own and delegated stake of a validator
+    /// and jail it for `jail_epochs` epochs, starting with the next one
+    Slash {
+        address: AccountAddress,
+        penalty_bps: u64,
+        jail_epochs: u64,
+    },
+    /// Parameters left as None keep their current value
+    UpdateParams {
+        block_gas_limit: Option<u64>,
+        min_validator_stake: Option<u128>,
+        max_validators: Option<u32>,
+        epoch_emission: Option<u128>,
+        dao_fee_bps: Option<u64>,
+        burn_fee_bps: Option<u64>,
+        max_voting_power_bps: Option<u64>,
+    },
+}
+
+/// The validator set and consensus parameters of an epoch
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EpochSnapshot {
+    pub epoch: u64,
+    /// Validators ordered by stake, highest first
+    pub validators: Vec<Validator>,
+    pub params: ConsensusParams,
+}
+
+impl EpochSnapshot {
+    pub fn genesis(validators: Vec<Validator>, params: ConsensusParams) -> Self {
+        Self::with_validators(0, validators, params)
+    }
+
+    fn with_validators(
+        epoch: u64,
+        mut validators: Vec<Validator>,
+        params: ConsensusParams,
+    ) -> Self {
+        validators.retain(|validator| validator.total_stake() >= params.min_validator_stake);
+        validators.sort_by(|a, b| {
+            b.total_stake()
+                .cmp(&a.total_stake())
+                .then(a.address.cmp(&b.address))
+        });
+        validators.truncate(params.max_validators as usize);
+        Self {
+            epoch,
+            validators,
+            params,
+        }
+    }
+
+    /// The snapshot of the next epoch, with `changes` applied in order
+    pub fn next(&self, changes: &[ValidatorSetChange]) -> Result<Self> {
+        let mut validators = self.validators.clone();
+        let mut params = self.params.clone();
+        for change in changes {
+            match change {
+                ValidatorSetChange::Register(validator) => {
+                    if validators.iter().any(|v| v.address == validator.address) {
+                        bail!("Validator {} is already registered", validator.address);
+                    }
+                    validators.push(validator.clone());
+                }
+                ValidatorSetChange::UpdateStake { address, stake } => {
+                    match validators.iter_mut().find(|v| &v.address == address) {
+                        Some(validator) => validator.stake = *stake,
+                        None => bail!("Validator {} is not registered", address),
+                    }
+                }
+                ValidatorSetChange::Unregister { address } => {
+                    validators.retain(|v| &v.address != address);
+                }
+                ValidatorSetChange::Delegate {
+                    delegator,
+                    validator,
+                    amount,
+                } => {
+                    let Some(target) = validators.iter_mut().find(|v| &v.address == validator)
+                    else {
+                        bail!("Validator {} is not registered", validator);
+                    };
+                    match target
+                        .delegations
+                        .iter_mut()
+                        .find(|d| &d.delegator == delegator)
+                    {
+                        Some(delegation) => delegation.amount += amount,
+                        None => target.delegations.push(Delegation {
+                            delegator: *delegator,
+                            amount: *amount,
+                        }),
+                    }
+                }
+                ValidatorSetChange::Undelegate {
+                    delegator,
+                    validator,
+                } => {
+                    let Some(target) = validators.iter_mut().find(|v| &v.address == validator)
+                    else {
+                        bail!("Validator {} is not registered", validator);
+                    };
+                    target.delegations.retain(|d| &d.delegator != delegator);
+                }
+                ValidatorSetChange::Slash {
+                    address,
+                    penalty_bps,
+                    jail_epochs,
+                } => {
+                    // The validator may have left the set since the offence
+                    if let Some(target) = validators.iter_mut().find(|v| &v.address == address) {
+                        let slash = |stake: u128| {
+                            stake - stake * (*penalty_bps).min(10_000) as u128 / 10_000
+                        };
+                        target.stake = slash(target.stake);
+                        for delegation in &mut target.delegations {
+                            delegation.amount = slash(delegation.amount);
+                        }
+                        let until = self.epoch + 1 + jail_epochs;
+                        target.jailed_until = Some(target.jailed_until.unwrap_or(0).max(until));
+                    }
+                }
+                ValidatorSetChange::UpdateParams {
+                    block_gas_limit,
+                    min_validator_stake,
+                    max_validators,
+                    epoch_emission,
+                    dao_fee_bps,
+                    burn_fee_bps,
+                    max_voting_power_bps,
+                } => {
+                    params.block_gas_limit = block_gas_limit.unwrap_or(params.block_gas_limit);
+                    params.min_validator_stake =
+                        min_validator_stake.unwrap_or(params.min_validator_stake);
+                    params.max_validators = max_validators.unwrap_or(params.max_validators);
+                    params.epoch_emission = epoch_emission.unwrap_or(params.epoch_emission);
+                    params.dao_fee_bps = dao_fee_bps.unwrap_or(params.dao_fee_bps);
+                    params.burn_fee_bps = burn_fee_bps.unwrap_or(params.burn_fee_bps);
+                    params.max_voting_power_bps =
+                        max_voting_power_bps.unwrap_or(params.max_voting_power_bps);
+                    if params.dao_fee_bps.saturating_add(params.burn_fee_bps) > FEE_SHARE_SCALE {
+                        bail!(
+                            "The DAO and burn fee shares exceed {} basis points",
+                            FEE_SHARE_SCALE
+                        );
+                    }
+                    if params.max_voting_power_bps == 0
+                        || params.max_voting_power_bps > VOTING_POWER_SCALE
+                    {
+                        bail!(
+                            "The voting power cap must be between 1 and {} basis points",
+                            VOTING_POWER_SCALE
+                        );
+                    }
+                }
+            }
+        }
+        Ok(Self::with_validators(self.epoch + 1, validators, params))
+    }
+
+    pub fn start_block(&self) -> u128 {
+        epoch_start_block(self.epoch)
+    }
+
+    pub fn end_block(&self) -> u128 {
+        epoch_end_block(self.epoch)
+    }
+
+    /// Stake of the validators that are not jailed
+    pub fn total_stake(&self) -> u128 {
+        self.validators
+            .iter()
+            .filter(|validator| !validator.is_jailed(self.epoch))
+            .map(Validator::total_stake)
+            .sum()
+    }
+
+    /// Share of the total stake held by a validator, zero while it is jailed
+    pub fn stake_weight(&self, address: &AccountAddress) -> Option<f64> {
+        let total = self.total_stake();
+        self.validators
+            .iter()
+            .find(|validator| &validator.address == address)
+            .map(|validator| match total {
+                _ if validator.is_jailed(self.epoch) => 0.0,
+                0 => 0.0,
+                total => validator.total_stake() as f64 / total as f64,
+            })
+    }
+
+    /// The stake each validator counts with in consensus weights and rewards, in the
+    /// order of `validators`: its own and delegated stake, zero while jailed, with no
+    /// validator above `max_voting_power_bps` of the total stake. The stake cut from
+    /// capped validators is spread over the others in proportion to their stake.
+    pub fn effective_stakes(&self) -> Vec<u128> {
+        let stakes: Vec<u128> = self
+            .validators
+            .iter()
+            .map(|validator| {
+                if validator.is_jailed(self.epoch) {
+                    0
+                } else {
+                    validator.total_stake()
+                }
+            })
+            .collect();
+        let total = U256::from(stakes.iter().sum::<u128>());
+        let active = stakes.iter().filter(|stake| **stake > 0).count() as u64;
+        if active == 0 {
+            return stakes;
+        }
+        let cap_bps = self
+            .params
+            .max_voting_power_bps
+            .min(VOTING_POWER_SCALE)
+            .max(VOTING_POWER_SCALE.div_ceil(active));
+        let cap = total * U256::from(cap_bps as u128) / U256::from(VOTING_POWER_SCALE as u128);
+
+        // Capping a validator raises the share of the others, repeat until none exceeds it
+        let mut capped = vec![false; stakes.len()];
+        let (remaining_power, remaining_stake) = loop {
+            let capped_power = cap * U256::from(capped.iter().filter(|c| **c).count() as u128);
+            let remaining_power = if total > capped_power {
+                total - capped_power
+            } else {
+                U256::zero()
+            };
+            let remaining_stake = U256::from(
+                stakes
+                    .iter()
+                    .zip(&capped)
+                    .filter(|(_, capped)| !**capped)
+                    .map(|(stake, _)| *stake)
+                    .sum::<u128>(),
+            );
+            let mut newly_capped = false;
+            for (stake, capped) in stakes.iter().zip(capped.iter_mut()) {
+                if !*capped
+                    && *stake > 0
+                    && U256::from(*stake) * remaining_power / remaining_stake > cap
+                {
+                    *capped = true;
+                    newly_capped = true;
+                }
+            }
+            if !newly_capped {
+                break (remaining_power, remaining_stake);
+            }
+        };
+        stakes
+            .iter()
+            .zip(capped)
+            .map(|(stake, capped)| {
+                if capped {
+                    cap.unchecked_as_u128()
+                } else if *stake == 0 {
+                    0
+                } else {
+                    (U256::from(*stake) * remaining_power / remaining_stake).unchecked_as_u128()
+                }
+            })
+            .collect()
+    }
+
+    /// Share of the total stake a validator counts with after the voting power cap, zero
+    /// while it is jailed
+    pub fn voting_power(&self, address: &AccountAddress) -> Option<f64> {
+        let total = self.total_stake();
+        self.validators
+            .iter()
+            .zip(self.effective_stakes())
+            .find(|(validator, _)| &validator.address == address)
+            .map(|(_, effective)| match total {
+                0 => 0.0,
+                total => effective as f64 / total as f64,
+            })
+    }
+
+    /// The validator signing with this public key, jailed or not
+    pub fn validator_by_key(&self, public_key: &[u8]) -> Option<&Validator> {
+        self.validators
+            .iter()
+            .find(|validator| validator.public_key == public_key)
+    }
+
+    /// Whether a block signed by this public key may be accepted in the epoch
+    pub fn is_validator_key(&self, public_key: &[u8]) -> bool {
+        self.validator_by_key(public_key)
+            .is_some_and(|validator| !validator.is_jailed(self.epoch))
+    }
+
+    /// Commits to the whole snapshot, recorded on chain at the epoch boundary
+    pub fn hash(&self) -> H256 {
+        sha2_256_of(&bcs::to_bytes(self).expect("Serialize epoch snapshot should success"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator(byte: u8, stake: u128) -> Validator {
+        Validator {
+            address: AccountAddress::new([byte; AccountAddress::LENGTH]),
+            public_key: vec![byte; 33],
+            stake,
+            delegations: vec![],
+            jailed_until: None,
+        }
+    }
+
+    #[test]
+    fn test_epoch_boundaries() {
+        assert_eq!(epoch_of(GENESIS_BLOCK_NUMBER), 0);
+        assert_eq!(epoch_of(epoch_end_block(0)), 0);
+        assert_eq!(epoch_of(epoch_start_block(1)), 1);
+        assert!(!is_epoch_boundary(GENESIS_BLOCK_NUMBER));
+        assert!(is_epoch_boundary(epoch_start_block(1)));
+        assert!(!is_epoch_boundary(epoch_start_block(1) + 1));
+    }
+
+    #[test]
+    fn test_next_snapshot_applies_queued_changes() {
+        let genesis = EpochSnapshot::genesis(
+            vec![validator(1, 10), validator(2, 30)],
+            ConsensusParams::default(),
+        );
+        assert_eq!(genesis.validators[0].stake, 30);
+        assert_eq!(genesis.stake_weight(&validator(2, 0).address), Some(0.75));
+
+        let next = genesis
+            .next(&[
+                ValidatorSetChange::Register(validator(3, 50)),
+                ValidatorSetChange::UpdateStake {
+                    address: validator(1, 0).address,
+                    stake: 5,
+                },
+                ValidatorSetChange::UpdateParams {
+                    block_gas_limit: None,
+                    min_validator_stake: Some(10),
+                    max_validators: None,
+                    epoch_emission: None,
+                    dao_fee_bps: None,
+                    burn_fee_bps: None,
+                    max_voting_power_bps: None,
+                },
+            ])
+            .unwrap();
+        assert_eq!(next.epoch, 1);
+        // The validator below the new minimum stake left the set
+        assert_eq!(
+            next.validators.iter().map(|v| v.stake).collect::<Vec<_>>(),
+            vec![50, 30]
+        );
+        assert_ne!(next.hash(), genesis.hash());
+        assert!(
+            genesis
+                .next(&[ValidatorSetChange::Register(validator(1, 1))])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_voting_power_cap_spreads_excess_stake() {
+        let params = ConsensusParams {
+            max_voting_power_bps: 2_000,
+            ..Default::default()
+        };
+        let mut validators = vec![validator(1, 600)];
+        validators.extend((2..=7).map(|byte| validator(byte, 50)));
+        validators.push(validator(8, 100));
+        let snapshot = EpochSnapshot::genesis(validators, params.clone());
+        // 600 is capped at 20% of 1000, the 200 left above it go to the others by stake
+        assert_eq!(
+            snapshot.effective_stakes(),
+            vec![200, 200, 100, 100, 100, 100, 100, 100]
+        );
+        assert_eq!(snapshot.stake_weight(&validator(1, 0).address), Some(0.6));
+        assert_eq!(snapshot.voting_power(&validator(1, 0).address), Some(0.2));
+
+        // Two validators cannot be held under 20% each, they get an equal share
+        let snapshot = EpochSnapshot::genesis(vec![validator(1, 90), validator(2, 10)], params);
+        assert_eq!(snapshot.effective_stakes(), vec![50, 50]);
+
+        // Without a cap the stake counts as is
+        let snapshot = EpochSnapshot::genesis(
+            vec![validator(1, 90), validator(2, 10)],
+            ConsensusParams::default(),
+        );
+        assert_eq!(snapshot.effective_stakes(), vec![90, 10]);
+    }
+}
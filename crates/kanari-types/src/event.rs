@@ -0,0 +1,112 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::bloom::EventBloom;
+use crate::reward::REWARD_EVENT_TYPE;
+use crate::system_transaction::SystemTransaction;
+use crate::transaction::SignedTransaction;
+use move_core_types::account_address::AccountAddress;
+use moveos_types::h256::H256;
+use rooch_types::addresses::ROOCH_FRAMEWORK_ADDRESS;
+use serde::{Deserialize, Serialize};
+
+/// Type of the event recorded for each value transfer applied in a block
+pub const TRANSFER_EVENT_TYPE: &str = "0x3::coin::TransferEvent";
+
+/// BCS payload of a transfer event
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransferEventData {
+    pub sender: AccountAddress,
+    pub recipient: AccountAddress,
+    pub amount: u128,
+}
+
+/// An event emitted by a transaction included in a block
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockEvent {
+    /// The transaction that emitted the event
+    pub tx_hash: H256,
+    /// The index of the event within the block
+    pub event_index: u64,
+    /// The address of the module that emitted the event
+    pub address: AccountAddress,
+    /// The fully qualified event type, e.g. `0x3::coin::TransferEvent`
+    pub event_type: String,
+    /// BCS encoded event data
+    pub data: Vec<u8>,
+}
+
+impl BlockEvent {
+    /// Whether the event matches the optional address and type criteria
+    pub fn matches(&self, address: Option<&AccountAddress>, event_type: Option<&str>) -> bool {
+        address.is_none_or(|address| &self.address == address)
+            && event_type.is_none_or(|event_type| self.event_type == event_type)
+    }
+
+    /// Whether the event was emitted by one of `addresses` and has one of `event_types`.
+    /// An empty list matches anything.
+    pub fn matches_any(&self, addresses: &[AccountAddress], event_types: &[String]) -> bool {
+        (addresses.is_empty() || addresses.contains(&self.address))
+            && (event_types.is_empty() || event_types.contains(&self.event_type))
+    }
+}
+
+/// The events recorded when a block's transactions are applied, in block order: a
+/// transfer event per value transfer and a reward event per staking reward payment
+pub fn transaction_events(transactions: &[SignedTransaction]) -> Vec<BlockEvent> {
+    transactions
+        .iter()
+        .flat_map(|tx| {
+            let mut events = vec![];
+            if let Some(recipient) = tx.tx.recipient {
+                if tx.tx.amount > 0 {
+                    events.push((
+                        TRANSFER_EVENT_TYPE,
+                        bcs::to_bytes(&TransferEventData {
+                            sender: tx.tx.sender,
+                            recipient,
+                            amount: tx.tx.amount,
+                        })
+                        .expect("Serialize transfer event should success"),
+                    ));
+                }
+            }
+            if let Ok(Some(SystemTransaction::RewardDistribution { payments, .. })) =
+                SystemTransaction::from_transaction(tx)
+            {
+                events.extend(payments.iter().map(|payment| {
+                    (
+                        REWARD_EVENT_TYPE,
+                        bcs::to_bytes(payment).expect("Serialize reward event should success"),
+                    )
+                }));
+            }
+            if events.is_empty() {
+                return vec![];
+            }
+            let tx_hash = tx.hash();
+            events
+                .into_iter()
+                .map(|(event_type, data)| (tx_hash, event_type, data))
+                .collect::<Vec<_>>()
+        })
+        .enumerate()
+        .map(|(event_index, (tx_hash, event_type, data))| BlockEvent {
+            tx_hash,
+            event_index: event_index as u64,
+            address: ROOCH_FRAMEWORK_ADDRESS,
+            event_type: event_type.to_string(),
+            data,
+        })
+        .collect()
+}
+
+/// Build the bloom filter over the addresses and types of a block's events
+pub fn events_bloom(events: &[BlockEvent]) -> EventBloom {
+    let mut bloom = EventBloom::new();
+    for event in events {
+        bloom.accrue(event.address.as_ref());
+        bloom.accrue(event.event_type.as_bytes());
+    }
+    bloom
+}
@@ -0,0 +1,181 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::block::SignedBlockHeader;
+use crate::epoch::{ValidatorSetChange, epoch_of};
+use anyhow::{Result, bail};
+use move_core_types::account_address::AccountAddress;
+use moveos_types::h256::{H256, sha2_256_of};
+use serde::{Deserialize, Serialize};
+
+/// Share of the own and delegated stake burnt for signing two blocks at one height,
+/// in basis points
+pub const DOUBLE_SIGN_PENALTY_BPS: u64 = 500;
+
+/// Number of epochs a double signing validator is jailed for
+pub const DOUBLE_SIGN_JAIL_EPOCHS: u64 = 2;
+
+/// Proof that a validator signed two different blocks at the same height
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DoubleSignEvidence {
+    pub first: SignedBlockHeader,
+    pub second: SignedBlockHeader,
+}
+
+impl DoubleSignEvidence {
+    /// Identifies the offence independently of the order of the two headers
+    pub fn hash(&self) -> H256 {
+        let (mut a, mut b) = (self.first.hash(), self.second.hash());
+        if b < a {
+            std::mem::swap(&mut a, &mut b);
+        }
+        sha2_256_of(&bcs::to_bytes(&(a, b)).expect("Serialize evidence should success"))
+    }
+
+    pub fn block_number(&self) -> u128 {
+        self.first.block.block_number
+    }
+
+    /// The public key of the offender
+    pub fn public_key(&self) -> &[u8] {
+        &self.first.public_key
+    }
+
+    /// Check that both headers are signed by the same key, at the same height, and
+    /// differ
+    pub fn verify(&self) -> Result<()> {
+        if self.first.public_key != self.second.public_key {
+            bail!("The two headers are signed by different keys");
+        }
+        if self.first.block.block_number != self.second.block.block_number {
+            bail!(
+                "The headers are at different heights #{} and #{}",
+                self.first.block.block_number,
+                self.second.block.block_number
+            );
+        }
+        if self.first.hash() == self.second.hash() {
+            bail!("The two headers are identical");
+        }
+        self.first
+            .verify_signature()
+            .map_err(|e| anyhow::anyhow!("First header: {}", e))?;
+        self.second
+            .verify_signature()
+            .map_err(|e| anyhow::anyhow!("Second header: {}", e))?;
+        Ok(())
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        bcs::to_bytes(self).expect("Serialize evidence should success")
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(bytes)?)
+    }
+}
+
+/// Verified evidence as stored by the node, with the penalty it triggered
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EvidenceRecord {
+    pub evidence: DoubleSignEvidence,
+    pub offender: AccountAddress,
+    /// The epoch of the height both blocks were signed at
+    pub epoch: u64,
+    /// Seconds since the Unix epoch when the node accepted the evidence
+    pub submitted_at: u64,
+    /// The epoch whose boundary applies the slashing. Until then the offender is
+    /// refused as a proposer based on the record alone.
+    pub slashed_from_epoch: u64,
+    pub penalty_bps: u64,
+    pub jail_epochs: u64,
+}
+
+impl EvidenceRecord {
+    /// Evidence accepted during `current_epoch`
+    pub fn new(
+        evidence: DoubleSignEvidence,
+        offender: AccountAddress,
+        current_epoch: u64,
+        submitted_at: u64,
+    ) -> Self {
+        Self {
+            epoch: epoch_of(evidence.block_number()),
+            evidence,
+            offender,
+            submitted_at,
+            slashed_from_epoch: current_epoch + 1,
+            penalty_bps: DOUBLE_SIGN_PENALTY_BPS,
+            jail_epochs: DOUBLE_SIGN_JAIL_EPOCHS,
+        }
+    }
+
+    /// The change applied to the validator set at the next epoch boundary
+    pub fn slash(&self) -> ValidatorSetChange {
+        ValidatorSetChange::Slash {
+            address: self.offender,
+            penalty_bps: self.penalty_bps,
+            jail_epochs: self.jail_epochs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::{Block, SignedBlock, transactions_batch_hash};
+    use fastcrypto::secp256k1::Secp256k1KeyPair;
+    use fastcrypto::traits::KeyPair;
+
+    fn header(
+        key_pair: &Secp256k1KeyPair,
+        block_number: u128,
+        timestamp: u64,
+    ) -> SignedBlockHeader {
+        let block = Block::new(
+            block_number,
+            0,
+            transactions_batch_hash(&[]),
+            H256::zero(),
+            H256::zero(),
+            H256::zero(),
+        );
+        SignedBlock::sign(block, H256::zero(), timestamp, vec![], key_pair).header()
+    }
+
+    #[test]
+    fn test_verify_double_sign_evidence() {
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let evidence = DoubleSignEvidence {
+            first: header(&key_pair, 5, 1_700_000_000),
+            second: header(&key_pair, 5, 1_700_000_001),
+        };
+        assert!(evidence.verify().is_ok());
+        let swapped = DoubleSignEvidence {
+            first: evidence.second.clone(),
+            second: evidence.first.clone(),
+        };
+        assert_eq!(swapped.hash(), evidence.hash());
+
+        let same = DoubleSignEvidence {
+            first: evidence.first.clone(),
+            second: evidence.first.clone(),
+        };
+        assert!(same.verify().is_err());
+        let other_height = DoubleSignEvidence {
+            first: evidence.first.clone(),
+            second: header(&key_pair, 6, 1_700_000_000),
+        };
+        assert!(other_height.verify().is_err());
+        let other_key = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let other_signer = DoubleSignEvidence {
+            first: evidence.first.clone(),
+            second: header(&other_key, 5, 1_700_000_001),
+        };
+        assert!(other_signer.verify().is_err());
+
+        let mut forged = evidence;
+        forged.second.timestamp += 1;
+        assert!(forged.verify().is_err());
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::epoch::ConsensusParams;
+use crate::transaction::SignedTransaction;
+use move_core_types::u256::U256;
+use serde::{Deserialize, Serialize};
+
+/// Fee shares are expressed in basis points of the collected fees
+pub const FEE_SHARE_SCALE: u64 = 10_000;
+
+/// Where the fees of a block went
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeSummary {
+    /// Fees charged for the gas the user transactions used
+    pub collected: u128,
+    /// Unused part of the maximum fees, returned to the senders
+    pub refunded: u128,
+    /// Share of the collected fees paid to the Kanari DAO
+    pub to_dao: u128,
+    /// Share of the collected fees burnt
+    pub burned: u128,
+    /// The rest of the collected fees, added to the staking rewards of the epoch
+    pub to_validators: u128,
+}
+
+impl FeeSummary {
+    /// Fees of the user transactions of a block, each charged `gas_used` gas at its gas
+    /// price and refunded the rest of its maximum fee, split by the shares of `params`.
+    /// Rounding dust of the DAO and burn shares goes to the validators.
+    pub fn new(
+        transactions: &[SignedTransaction],
+        gas_used: impl Fn(&SignedTransaction) -> u64,
+        params: &ConsensusParams,
+    ) -> Self {
+        let mut summary = FeeSummary::default();
+        for tx in transactions.iter().filter(|tx| !tx.is_system()) {
+            let charged = gas_used(tx).min(tx.tx.gas_limit) as u128 * tx.tx.gas_price as u128;
+            summary.collected += charged;
+            summary.refunded += tx.tx.max_fee() - charged;
+        }
+        let share = |bps: u64| {
+            (U256::from(summary.collected) * U256::from(bps as u128)
+                / U256::from(FEE_SHARE_SCALE as u128))
+            .unchecked_as_u128()
+        };
+        summary.to_dao = share(params.dao_fee_bps);
+        summary.burned = share(params.burn_fee_bps);
+        summary.to_validators = summary.collected - summary.to_dao - summary.burned;
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::KanariTransaction;
+    use move_core_types::account_address::AccountAddress;
+    use moveos_types::h256::H256;
+
+    #[test]
+    fn test_fees_are_split_after_refunds() {
+        let tx = SignedTransaction {
+            tx: KanariTransaction {
+                sender: AccountAddress::ONE,
+                sequence_number: 0,
+                chain_id: 1,
+                genesis_hash: H256::zero(),
+                recipient: None,
+                amount: 0,
+                gas_limit: 1_000,
+                gas_price: 3,
+                data: vec![],
+                access_list: None,
+            },
+            public_key: vec![],
+            signature: vec![],
+        };
+        let params = ConsensusParams {
+            dao_fee_bps: 1_000,
+            burn_fee_bps: 2_500,
+            ..Default::default()
+        };
+        let transactions = vec![tx.clone(), tx];
+        let summary = FeeSummary::new(&transactions, |_| 500, &params);
+        assert_eq!(
+            summary,
+            FeeSummary {
+                collected: 3_000,
+                refunded: 3_000,
+                to_dao: 300,
+                burned: 750,
+                to_validators: 1_950,
+            }
+        );
+
+        // Without shares everything goes to the validators
+        let summary = FeeSummary::new(&transactions, |_| 500, &ConsensusParams::default());
+        assert_eq!(summary.to_validators, summary.collected);
+    }
+}
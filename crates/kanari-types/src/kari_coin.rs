@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 use rooch_types::addresses::ROOCH_FRAMEWORK_ADDRESS;
 
+use anyhow::{Result, bail};
 use move_core_types::{
     account_address::AccountAddress, ident_str, identifier::IdentStr, u256::U256,
 };
@@ -9,6 +10,50 @@ use moveos_types::state::MoveStructType;
 
 pub const MODULE_NAME: &IdentStr = ident_str!("kanari");
 pub const DECIMALS: u8 = 18;
+/// Fraction digits shown by default when an amount is rendered for people
+pub const DISPLAY_DECIMALS: u8 = 6;
+
+/// A unit amounts may be written in, `decimals` is the number of smallest units it
+/// holds as a power of ten
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Denomination {
+    pub name: &'static str,
+    pub symbol: &'static str,
+    pub decimals: u8,
+}
+
+pub const KARI_DENOMINATION: Denomination = Denomination {
+    name: "kari",
+    symbol: "KARI",
+    decimals: DECIMALS,
+};
+pub const MILLIKARI_DENOMINATION: Denomination = Denomination {
+    name: "millikari",
+    symbol: "mKARI",
+    decimals: DECIMALS - 3,
+};
+/// The smallest unit, the one amounts are stored and transferred in
+pub const ATTOKARI_DENOMINATION: Denomination = Denomination {
+    name: "attokari",
+    symbol: "aKARI",
+    decimals: 0,
+};
+
+/// Denominations from the largest to the smallest
+pub const DENOMINATIONS: [Denomination; 3] = [
+    KARI_DENOMINATION,
+    MILLIKARI_DENOMINATION,
+    ATTOKARI_DENOMINATION,
+];
+
+impl Denomination {
+    /// The denomination with this symbol, or this name in any case
+    pub fn find(unit: &str) -> Option<Denomination> {
+        DENOMINATIONS
+            .into_iter()
+            .find(|d| d.symbol == unit || d.name.eq_ignore_ascii_case(unit))
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct KARI;
@@ -24,3 +69,109 @@ impl KARI {
         U256::from(10u64.pow(DECIMALS as u32)) * value.into()
     }
 }
+
+/// Render an amount of smallest units in a unit with `decimals`, exactly and without
+/// trailing zeros, e.g. `1500000000000000000` with 18 decimals is `1.5`
+pub fn format_amount(amount: u128, decimals: u8) -> String {
+    format_amount_rounded(amount, decimals, decimals)
+}
+
+/// Render an amount with at most `max_fraction_digits` fraction digits. Digits beyond
+/// are cut off, rounding toward zero, so a rendered balance never exceeds the real one.
+pub fn format_amount_rounded(amount: u128, decimals: u8, max_fraction_digits: u8) -> String {
+    let scale = 10u128.pow(decimals as u32);
+    let whole = amount / scale;
+    let fraction = format!("{:0width$}", amount % scale, width = decimals as usize);
+    let fraction = fraction[..max_fraction_digits.min(decimals) as usize].trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Render an amount of smallest units in KARI with the default display precision and
+/// the symbol, e.g. `1.5 KARI`
+pub fn format_kari(amount: u128) -> String {
+    format!(
+        "{} {}",
+        format_amount_rounded(amount, DECIMALS, DISPLAY_DECIMALS),
+        KARI_DENOMINATION.symbol
+    )
+}
+
+/// Parse a decimal amount in a unit with `decimals` into smallest units. Amounts with
+/// more fraction digits than the unit has are rejected rather than rounded.
+pub fn parse_amount(amount: &str, decimals: u8) -> Result<u128> {
+    let amount = amount.trim().replace('_', "");
+    let (whole, fraction) = amount.split_once('.').unwrap_or((&amount, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        bail!("Invalid amount {}", amount);
+    }
+    if fraction.len() > decimals as usize {
+        bail!(
+            "Amount {} has more than {} fraction digits",
+            amount,
+            decimals
+        );
+    }
+    let scale = 10u128.pow(decimals as u32);
+    let fraction_scale = 10u128.pow((decimals as usize - fraction.len()) as u32);
+    let whole = if whole.is_empty() {
+        0
+    } else {
+        whole.parse::<u128>()?
+    };
+    let fraction = if fraction.is_empty() {
+        0
+    } else {
+        fraction.parse::<u128>()?
+    };
+    whole
+        .checked_mul(scale)
+        .and_then(|whole| whole.checked_add(fraction * fraction_scale))
+        .ok_or_else(|| anyhow::anyhow!("Amount {} is too large", amount))
+}
+
+/// Parse an amount with an optional unit, e.g. `1.5`, `1.5 KARI`, `250mKARI` or
+/// `7 attokari`, into smallest units. Without a unit the amount is in KARI.
+pub fn parse_kari(amount: &str) -> Result<u128> {
+    let amount = amount.trim();
+    let split = amount
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(amount.len());
+    let (value, unit) = amount.split_at(split);
+    let denomination = match unit.trim() {
+        "" => KARI_DENOMINATION,
+        unit => Denomination::find(unit).ok_or_else(|| anyhow::anyhow!("Unknown unit {}", unit))?,
+    };
+    parse_amount(value, denomination.decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_and_parse_round_trip() {
+        let one_and_half = 15 * 10u128.pow(DECIMALS as u32 - 1);
+        assert_eq!(format_amount(one_and_half, DECIMALS), "1.5");
+        assert_eq!(format_amount(0, DECIMALS), "0");
+        assert_eq!(format_kari(one_and_half + 999), "1.5 KARI");
+        assert_eq!(format_amount_rounded(1_999, 3, 2), "1.99");
+
+        assert_eq!(parse_kari("1.5").unwrap(), one_and_half);
+        assert_eq!(parse_kari("1500 mKARI").unwrap(), one_and_half);
+        assert_eq!(parse_kari("7 attokari").unwrap(), 7);
+        assert_eq!(parse_kari(".5KARI").unwrap(), one_and_half / 3);
+        assert!(parse_kari("1.5 aKARI").is_err());
+        assert!(parse_kari("1e3").is_err());
+        assert!(parse_kari("1.5 dogecoin").is_err());
+        assert!(parse_amount("340282366920938463463374607431768211456", 0).is_err());
+    }
+}
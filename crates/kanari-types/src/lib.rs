@@ -1,3 +1,15 @@
 pub mod block;
+pub mod bloom;
+pub mod dao;
+pub mod epoch;
+pub mod event;
+pub mod evidence;
+pub mod fee;
 pub mod genesis_config;
 pub mod kari_coin;
+pub mod parallel;
+pub mod personal_message;
+pub mod receipt;
+pub mod reward;
+pub mod system_transaction;
+pub mod transaction;
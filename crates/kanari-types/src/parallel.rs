@@ -0,0 +1,118 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::transaction::SignedTransaction;
+use move_core_types::account_address::AccountAddress;
+use std::collections::BTreeSet;
+
+/// Transactions that can execute at the same time, by their position in the block
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionBatch {
+    pub transactions: Vec<usize>,
+    /// Accounts declared by the transactions, None for a transaction without access list
+    accounts: Option<BTreeSet<AccountAddress>>,
+}
+
+impl ExecutionBatch {
+    fn conflicts_with(&self, accounts: &BTreeSet<AccountAddress>) -> bool {
+        self.accounts
+            .as_ref()
+            .is_none_or(|batch_accounts| !batch_accounts.is_disjoint(accounts))
+    }
+}
+
+/// Split the transactions of a block into batches executed one after another, the
+/// transactions of a batch in parallel, with the same outcome as executing them in
+/// block order. A transaction joins the earliest batch after the last one declaring
+/// an account it declares. A transaction without access list runs alone, as a barrier
+/// the following transactions are not moved across.
+///
+/// The hints are not trusted: the executor compares the accounts a transaction touched
+/// with `KanariTransaction::undeclared_accounts` and re-executes the batch in order if
+/// any hint was wrong.
+pub fn schedule_batches(transactions: &[SignedTransaction]) -> Vec<ExecutionBatch> {
+    let mut batches: Vec<ExecutionBatch> = vec![];
+    for (index, tx) in transactions.iter().enumerate() {
+        let Some(accounts) = tx.tx.declared_accounts() else {
+            batches.push(ExecutionBatch {
+                transactions: vec![index],
+                accounts: None,
+            });
+            continue;
+        };
+        let first_free = batches
+            .iter()
+            .rposition(|batch| batch.conflicts_with(&accounts))
+            .map_or(0, |position| position + 1);
+        match batches.get_mut(first_free) {
+            Some(batch) => {
+                batch.transactions.push(index);
+                if let Some(batch_accounts) = &mut batch.accounts {
+                    batch_accounts.extend(accounts);
+                }
+            }
+            None => batches.push(ExecutionBatch {
+                transactions: vec![index],
+                accounts: Some(accounts),
+            }),
+        }
+    }
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::KanariTransaction;
+    use moveos_types::h256::H256;
+
+    fn transfer(
+        sender: AccountAddress,
+        recipient: AccountAddress,
+        access_list: Option<Vec<AccountAddress>>,
+    ) -> SignedTransaction {
+        SignedTransaction {
+            tx: KanariTransaction {
+                sender,
+                sequence_number: 0,
+                chain_id: 1,
+                genesis_hash: H256::zero(),
+                recipient: Some(recipient),
+                amount: 1,
+                gas_limit: 21_000,
+                gas_price: 1,
+                data: vec![],
+                access_list,
+            },
+            public_key: vec![],
+            signature: vec![],
+        }
+    }
+
+    #[test]
+    fn test_conflicting_and_unhinted_transactions_are_ordered() {
+        let account = |n: u8| AccountAddress::new([n; AccountAddress::LENGTH]);
+        let transactions = vec![
+            transfer(account(1), account(2), Some(vec![])),
+            transfer(account(3), account(4), Some(vec![])),
+            // Touches account 2, so it waits for the first transfer
+            transfer(account(5), account(6), Some(vec![account(2)])),
+            // No hints, runs alone
+            transfer(account(7), account(8), None),
+            transfer(account(9), account(10), Some(vec![])),
+        ];
+        let batches: Vec<Vec<usize>> = schedule_batches(&transactions)
+            .into_iter()
+            .map(|batch| batch.transactions)
+            .collect();
+        assert_eq!(batches, vec![vec![0, 1], vec![2], vec![3], vec![4]]);
+
+        // A wrong hint is caught after execution
+        let tx = &transactions[0].tx;
+        assert!(tx.undeclared_accounts(&[account(1), account(2)]).is_empty());
+        assert_eq!(
+            tx.undeclared_accounts(&[account(1), account(11)]),
+            vec![account(11)]
+        );
+    }
+}
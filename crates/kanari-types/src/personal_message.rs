@@ -0,0 +1,109 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use fastcrypto::secp256k1::{Secp256k1KeyPair, Secp256k1PublicKey, Secp256k1Signature};
+use fastcrypto::traits::{KeyPair, Signer, ToFromBytes, VerifyingKey};
+use moveos_types::h256::H256;
+use rooch_types::address::RoochAddress;
+use rooch_types::crypto::PublicKey;
+use serde::{Deserialize, Serialize};
+
+/// Prefix of every personal message, so a signed message can never be replayed as a transaction
+pub const PERSONAL_MESSAGE_PREFIX: &[u8] = b"\x19Kanari Signed Message:\n";
+
+/// The bytes actually signed for a personal message:
+/// `"\x19Kanari Signed Message:\n" || len(message) as decimal || message`
+pub fn personal_message_bytes(message: &[u8]) -> Vec<u8> {
+    let mut bytes = PERSONAL_MESSAGE_PREFIX.to_vec();
+    bytes.extend(message.len().to_string().as_bytes());
+    bytes.extend(message);
+    bytes
+}
+
+/// The personal message a sender signs to cancel a pooled transaction. It names the
+/// transaction's hash, so the signature can not cancel another transaction later
+/// pooled with the same sequence number.
+pub fn cancel_transaction_message(tx_hash: &H256) -> String {
+    format!(
+        "Cancel Kanari transaction 0x{}",
+        hex::encode(tx_hash.as_bytes())
+    )
+}
+
+/// A secp256k1 signature over a personal message together with the signer's public key
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PersonalMessageSignature {
+    /// Compressed secp256k1 public key of the signer
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl PersonalMessageSignature {
+    pub fn sign(message: &[u8], key_pair: &Secp256k1KeyPair) -> Self {
+        let signature: Secp256k1Signature = key_pair.sign(&personal_message_bytes(message));
+        Self {
+            public_key: key_pair.public().as_bytes().to_vec(),
+            signature: signature.as_bytes().to_vec(),
+        }
+    }
+
+    /// The address of the signer derived from the embedded public key
+    pub fn signer(&self) -> Result<RoochAddress> {
+        let public_key = Secp256k1PublicKey::from_bytes(&self.public_key)
+            .map_err(|e| anyhow::anyhow!("Invalid public key: {}", e))?;
+        public_key_address(&public_key)
+    }
+
+    /// Check the signature over the message and that it was made by `address`
+    pub fn verify(&self, message: &[u8], address: &RoochAddress) -> Result<()> {
+        let public_key = Secp256k1PublicKey::from_bytes(&self.public_key)
+            .map_err(|e| anyhow::anyhow!("Invalid public key: {}", e))?;
+        let signature = Secp256k1Signature::from_bytes(&self.signature)
+            .map_err(|e| anyhow::anyhow!("Invalid signature encoding: {}", e))?;
+        public_key
+            .verify(&personal_message_bytes(message), &signature)
+            .map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))?;
+
+        let signer = public_key_address(&public_key)?;
+        if &signer != address {
+            anyhow::bail!("Message was signed by {}, not {}", signer, address);
+        }
+        Ok(())
+    }
+}
+
+/// Derive the Rooch address of a secp256k1 public key from its Bitcoin address
+pub fn public_key_address(public_key: &Secp256k1PublicKey) -> Result<RoochAddress> {
+    let public_key = PublicKey::Secp256k1(public_key.into());
+    Ok(public_key.bitcoin_address()?.to_rooch_address())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify_personal_message() {
+        let key_pair = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let address = public_key_address(key_pair.public()).unwrap();
+        let message = b"Login to example.com at 2026-10-16T00:00:00Z";
+
+        let signature = PersonalMessageSignature::sign(message, &key_pair);
+        assert_eq!(signature.signer().unwrap(), address);
+        signature.verify(message, &address).unwrap();
+        assert!(signature.verify(b"another message", &address).is_err());
+
+        let other = Secp256k1KeyPair::generate(&mut rand::thread_rng());
+        let other_address = public_key_address(other.public()).unwrap();
+        assert!(signature.verify(message, &other_address).is_err());
+    }
+
+    #[test]
+    fn test_personal_message_is_domain_separated() {
+        assert_eq!(
+            personal_message_bytes(b"hi"),
+            b"\x19Kanari Signed Message:\n2hi".to_vec()
+        );
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::event::BlockEvent;
+use crate::transaction::SignedTransaction;
+use moveos_types::h256::H256;
+use serde::{Deserialize, Serialize};
+
+/// Outcome of executing a transaction included in a block
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ExecutionStatus {
+    Success,
+    /// The transaction was included and charged, but its effects were discarded
+    Failure {
+        reason: String,
+    },
+}
+
+impl ExecutionStatus {
+    pub fn is_success(&self) -> bool {
+        matches!(self, ExecutionStatus::Success)
+    }
+}
+
+/// The result of executing one transaction of a block
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionOutput {
+    pub status: ExecutionStatus,
+    pub gas_used: u64,
+}
+
+/// What executing a transaction of a block did, recorded when the block is saved
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    pub tx_hash: H256,
+    /// The block including the transaction
+    pub block_number: u128,
+    /// The position of the transaction in the block
+    pub index: u64,
+    pub status: ExecutionStatus,
+    pub gas_used: u64,
+    /// The events the transaction emitted, in block order
+    pub events: Vec<BlockEvent>,
+}
+
+/// The receipts of the transactions of a block, each with its execution `outputs` and
+/// the block events it emitted
+pub fn block_receipts(
+    block_number: u128,
+    transactions: &[SignedTransaction],
+    outputs: &[TransactionOutput],
+    events: &[BlockEvent],
+) -> Vec<TransactionReceipt> {
+    transactions
+        .iter()
+        .zip(outputs)
+        .enumerate()
+        .map(|(index, (tx, output))| {
+            let tx_hash = tx.hash();
+            TransactionReceipt {
+                tx_hash,
+                block_number,
+                index: index as u64,
+                status: output.status.clone(),
+                gas_used: output.gas_used,
+                events: events
+                    .iter()
+                    .filter(|event| event.tx_hash == tx_hash)
+                    .cloned()
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::transaction_events;
+    use crate::transaction::KanariTransaction;
+    use move_core_types::account_address::AccountAddress;
+
+    #[test]
+    fn test_receipts_carry_their_own_events() {
+        let transfer = |amount: u128| SignedTransaction {
+            tx: KanariTransaction {
+                sender: AccountAddress::ONE,
+                sequence_number: 0,
+                chain_id: 1,
+                genesis_hash: H256::zero(),
+                recipient: Some(AccountAddress::new([2; AccountAddress::LENGTH])),
+                amount,
+                gas_limit: 21_000,
+                gas_price: 1,
+                data: vec![],
+                access_list: None,
+            },
+            public_key: vec![],
+            signature: vec![],
+        };
+        // A zero amount transfer emits no event
+        let transactions = vec![transfer(5), transfer(0)];
+        let events = transaction_events(&transactions);
+        let outputs = vec![
+            TransactionOutput {
+                status: ExecutionStatus::Success,
+                gas_used: 21_000,
+            };
+            2
+        ];
+        let receipts = block_receipts(7, &transactions, &outputs, &events);
+
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].tx_hash, transactions[0].hash());
+        assert_eq!(receipts[0].events, events);
+        assert_eq!(receipts[1].index, 1);
+        assert!(receipts[1].events.is_empty());
+        assert!(receipts.iter().all(|receipt| {
+            receipt.block_number == 7 && receipt.status.is_success() && receipt.gas_used == 21_000
+        }));
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::epoch::EpochSnapshot;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::u256::U256;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Type of the event recorded for each reward payment
+pub const REWARD_EVENT_TYPE: &str = "0x3::staking::RewardEvent";
+
+/// Performance is expressed in basis points, a validator that proposed at least its
+/// stake weighted share of the blocks scores `PERFORMANCE_SCALE`
+pub const PERFORMANCE_SCALE: u128 = 10_000;
+
+/// A reward paid at the end of an epoch, also the BCS payload of a reward event
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RewardPayment {
+    /// The epoch the reward was earned in
+    pub epoch: u64,
+    /// The validator whose stake earned the reward
+    pub validator: AccountAddress,
+    /// The validator itself or one of its delegators
+    pub recipient: AccountAddress,
+    pub amount: u128,
+}
+
+/// Performance of each validator of the snapshot in basis points, from the number of
+/// blocks each public key proposed during the epoch against its share of the effective
+/// stake. Jailed validators score zero, everyone else scores full performance when no
+/// block was proposed by a validator, e.g. before the set was populated.
+pub fn validator_performance(
+    snapshot: &EpochSnapshot,
+    proposed_blocks: &HashMap<Vec<u8>, u64>,
+) -> Vec<u128> {
+    let total_stake = U256::from(snapshot.total_stake());
+    let proposed = |public_key: &Vec<u8>| proposed_blocks.get(public_key).copied().unwrap_or(0);
+    let total_proposed: u64 = snapshot
+        .validators
+        .iter()
+        .map(|validator| proposed(&validator.public_key))
+        .sum();
+    snapshot
+        .validators
+        .iter()
+        .zip(snapshot.effective_stakes())
+        .map(|(validator, stake)| {
+            if validator.is_jailed(snapshot.epoch) {
+                return 0;
+            }
+            if total_proposed == 0 {
+                return PERFORMANCE_SCALE;
+            }
+            if stake == 0 {
+                return 0;
+            }
+            // proposed / (total_proposed * stake / total_stake), capped at 1
+            let performance = U256::from(proposed(&validator.public_key) as u128)
+                * total_stake
+                * U256::from(PERFORMANCE_SCALE)
+                / (U256::from(total_proposed as u128) * U256::from(stake));
+            performance
+                .min(U256::from(PERFORMANCE_SCALE))
+                .unchecked_as_u128()
+        })
+        .collect()
+}
+
+/// Split the epoch emission plus the collected fees between the validators, weighted
+/// by effective stake and performance, then between each validator and its delegators
+/// by stake. Rounding dust of a validator's share goes to the validator, dust of the pool is not
+/// paid out.
+pub fn distribute_rewards(
+    snapshot: &EpochSnapshot,
+    fees: u128,
+    proposed_blocks: &HashMap<Vec<u8>, u64>,
+) -> Vec<RewardPayment> {
+    let pool = U256::from(snapshot.params.epoch_emission.saturating_add(fees));
+    let weights: Vec<U256> = snapshot
+        .effective_stakes()
+        .into_iter()
+        .zip(validator_performance(snapshot, proposed_blocks))
+        .map(|(stake, performance)| U256::from(stake) * U256::from(performance))
+        .collect();
+    let total_weight = weights
+        .iter()
+        .fold(U256::zero(), |total, weight| total + *weight);
+    if total_weight == U256::zero() {
+        return vec![];
+    }
+
+    let mut payments = vec![];
+    for (validator, weight) in snapshot.validators.iter().zip(weights) {
+        let share = (pool * weight / total_weight).unchecked_as_u128();
+        let stake = validator.total_stake();
+        if share == 0 || stake == 0 {
+            continue;
+        }
+        let mut paid = 0;
+        for delegation in &validator.delegations {
+            let amount = (U256::from(share) * U256::from(delegation.amount) / U256::from(stake))
+                .unchecked_as_u128();
+            if amount > 0 {
+                paid += amount;
+                payments.push(RewardPayment {
+                    epoch: snapshot.epoch,
+                    validator: validator.address,
+                    recipient: delegation.delegator,
+                    amount,
+                });
+            }
+        }
+        if share > paid {
+            payments.push(RewardPayment {
+                epoch: snapshot.epoch,
+                validator: validator.address,
+                recipient: validator.address,
+                amount: share - paid,
+            });
+        }
+    }
+    payments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::epoch::{ConsensusParams, Delegation, Validator};
+
+    fn validator(byte: u8, stake: u128, delegations: Vec<Delegation>) -> Validator {
+        Validator {
+            address: AccountAddress::new([byte; AccountAddress::LENGTH]),
+            public_key: vec![byte; 33],
+            stake,
+            delegations,
+            jailed_until: None,
+        }
+    }
+
+    #[test]
+    fn test_distribute_rewards_by_stake_and_performance() {
+        let delegator = AccountAddress::new([9; AccountAddress::LENGTH]);
+        let snapshot = EpochSnapshot::genesis(
+            vec![
+                validator(
+                    1,
+                    30,
+                    vec![Delegation {
+                        delegator,
+                        amount: 10,
+                    }],
+                ),
+                validator(2, 40, vec![]),
+                validator(3, 20, vec![]),
+            ],
+            ConsensusParams {
+                epoch_emission: 900,
+                ..Default::default()
+            },
+        );
+        // Validator 3 proposed half of its expected 4 out of 20 blocks
+        let proposed = HashMap::from([(vec![1; 33], 9), (vec![2; 33], 9), (vec![3; 33], 2)]);
+        assert_eq!(
+            validator_performance(&snapshot, &proposed),
+            vec![PERFORMANCE_SCALE, PERFORMANCE_SCALE, PERFORMANCE_SCALE / 2]
+        );
+
+        let payments = distribute_rewards(&snapshot, 100, &proposed);
+        let paid_to = |recipient: u8| {
+            let recipient = AccountAddress::new([recipient; AccountAddress::LENGTH]);
+            payments
+                .iter()
+                .filter(|payment| payment.recipient == recipient)
+                .map(|payment| payment.amount)
+                .sum::<u128>()
+        };
+        // Weights 40 : 40 : 10 over a pool of 1000
+        assert_eq!(paid_to(2), 444);
+        assert_eq!(paid_to(9), 111);
+        assert_eq!(paid_to(1), 333);
+        assert_eq!(paid_to(3), 111);
+
+        // Without any proposal everyone is weighted by stake alone
+        let payments = distribute_rewards(&snapshot, 0, &HashMap::new());
+        assert_eq!(
+            payments.iter().map(|payment| payment.amount).sum::<u128>(),
+            900
+        );
+
+        // Capped at 34% the two largest validators give up stake weight to the third
+        let capped = EpochSnapshot {
+            params: ConsensusParams {
+                epoch_emission: 900,
+                max_voting_power_bps: 3_400,
+                ..Default::default()
+            },
+            ..snapshot
+        };
+        assert_eq!(capped.effective_stakes(), vec![34, 34, 32]);
+        let payments = distribute_rewards(&capped, 0, &HashMap::new());
+        let paid_to = |recipient: u8| {
+            let recipient = AccountAddress::new([recipient; AccountAddress::LENGTH]);
+            payments
+                .iter()
+                .filter(|payment| payment.recipient == recipient)
+                .map(|payment| payment.amount)
+                .sum::<u128>()
+        };
+        assert_eq!(paid_to(1) + paid_to(9), 306);
+        assert_eq!(paid_to(3), 288);
+    }
+}
@@ -0,0 +1,293 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::epoch::{epoch_of, is_epoch_boundary};
+use crate::reward::RewardPayment;
+use crate::transaction::{KanariTransaction, SignedTransaction};
+use anyhow::{Result, bail};
+use move_core_types::account_address::AccountAddress;
+use moveos_types::h256::H256;
+use serde::{Deserialize, Serialize};
+
+/// The sender of every system transaction. No key controls it, so user transactions
+/// from this address can never carry a valid signature.
+pub const SYSTEM_TRANSACTION_SENDER: AccountAddress = AccountAddress::ZERO;
+
+/// Prefix of the call data of a system transaction, followed by the BCS encoded kind
+pub const SYSTEM_TRANSACTION_DATA_PREFIX: &[u8] = b"KANARI::System";
+
+/// Blocks between two checkpoint transactions
+pub const CHECKPOINT_INTERVAL: u128 = 100;
+
+/// Number of slots reserved for system transactions at the start of each block
+pub const SYSTEM_TRANSACTION_SLOTS: usize = 5;
+
+/// A transaction generated by the node itself. Each kind has a reserved slot at the
+/// start of the block, see `slot`, and pays no fee.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SystemTransaction {
+    /// The block timestamp and the epoch the block belongs to
+    TimestampUpdate { timestamp: u64, epoch: u64 },
+    /// Commits the state root of an earlier block
+    Checkpoint {
+        block_number: u128,
+        state_root: H256,
+    },
+    /// The aggregated value of an oracle feed for a round
+    OracleAggregation {
+        feed: String,
+        round: u64,
+        value: u128,
+    },
+    /// Starts a new epoch in its first block, committing to its validator set and
+    /// consensus parameters
+    EpochChange { epoch: u64, snapshot_hash: H256 },
+    /// Pays the staking rewards of the epoch that just ended, in the first block of
+    /// the next one
+    RewardDistribution {
+        epoch: u64,
+        payments: Vec<RewardPayment>,
+    },
+}
+
+impl SystemTransaction {
+    pub fn timestamp_update(timestamp: u64, block_number: u128) -> Self {
+        SystemTransaction::TimestampUpdate {
+            timestamp,
+            epoch: epoch_of(block_number),
+        }
+    }
+
+    /// The reserved slot of this kind
+    pub fn slot(&self) -> usize {
+        match self {
+            SystemTransaction::TimestampUpdate { .. } => 0,
+            SystemTransaction::EpochChange { .. } => 1,
+            SystemTransaction::RewardDistribution { .. } => 2,
+            SystemTransaction::Checkpoint { .. } => 3,
+            SystemTransaction::OracleAggregation { .. } => 4,
+        }
+    }
+
+    /// Name shown in receipts and RPC output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SystemTransaction::TimestampUpdate { .. } => "timestamp_update",
+            SystemTransaction::Checkpoint { .. } => "checkpoint",
+            SystemTransaction::OracleAggregation { .. } => "oracle_aggregation",
+            SystemTransaction::EpochChange { .. } => "epoch_change",
+            SystemTransaction::RewardDistribution { .. } => "reward_distribution",
+        }
+    }
+
+    /// Wrap into the transaction included in block `block_number`. The block number
+    /// is used as sequence number so system transactions of different blocks have
+    /// different hashes.
+    pub fn into_transaction(
+        self,
+        chain_id: u64,
+        genesis_hash: H256,
+        block_number: u128,
+    ) -> SignedTransaction {
+        let mut data = SYSTEM_TRANSACTION_DATA_PREFIX.to_vec();
+        data.extend(bcs::to_bytes(&self).expect("Serialize system transaction should success"));
+        SignedTransaction {
+            tx: KanariTransaction {
+                sender: SYSTEM_TRANSACTION_SENDER,
+                sequence_number: block_number as u64,
+                chain_id,
+                genesis_hash,
+                recipient: None,
+                amount: 0,
+                gas_limit: 0,
+                gas_price: 0,
+                data,
+                access_list: None,
+            },
+            public_key: vec![],
+            signature: vec![],
+        }
+    }
+
+    /// Decode a system transaction, None for a user transaction
+    pub fn from_transaction(tx: &SignedTransaction) -> Result<Option<Self>> {
+        if !tx.is_system() {
+            return Ok(None);
+        }
+        let Some(payload) = tx.tx.data.strip_prefix(SYSTEM_TRANSACTION_DATA_PREFIX) else {
+            bail!("System transaction {:?} has no system payload", tx.hash());
+        };
+        Ok(Some(bcs::from_bytes(payload)?))
+    }
+
+    /// Check the kind specific rules against the block it is included in
+    fn validate(&self, block_number: u128, block_timestamp: u64) -> Result<()> {
+        match self {
+            SystemTransaction::TimestampUpdate { timestamp, epoch } => {
+                if *timestamp != block_timestamp {
+                    bail!(
+                        "Timestamp update {} does not match the block timestamp {}",
+                        timestamp,
+                        block_timestamp
+                    );
+                }
+                if *epoch != epoch_of(block_number) {
+                    bail!(
+                        "Timestamp update announces epoch {} in block #{} of epoch {}",
+                        epoch,
+                        block_number,
+                        epoch_of(block_number)
+                    );
+                }
+            }
+            SystemTransaction::Checkpoint {
+                block_number: checkpoint,
+                ..
+            } => {
+                if *checkpoint >= block_number {
+                    bail!(
+                        "Checkpoint of block #{} must refer to a block before #{}",
+                        checkpoint,
+                        block_number
+                    );
+                }
+            }
+            SystemTransaction::OracleAggregation { feed, .. } => {
+                if feed.is_empty() {
+                    bail!("Oracle aggregation has an empty feed name");
+                }
+            }
+            SystemTransaction::EpochChange { epoch, .. } => {
+                if !is_epoch_boundary(block_number) || *epoch != epoch_of(block_number) {
+                    bail!(
+                        "Epoch change to epoch {} in block #{} which does not start it",
+                        epoch,
+                        block_number
+                    );
+                }
+            }
+            SystemTransaction::RewardDistribution { epoch, payments } => {
+                if !is_epoch_boundary(block_number) || *epoch + 1 != epoch_of(block_number) {
+                    bail!(
+                        "Rewards of epoch {} distributed in block #{} which does not end it",
+                        epoch,
+                        block_number
+                    );
+                }
+                if let Some(payment) = payments.iter().find(|payment| payment.epoch != *epoch) {
+                    bail!(
+                        "Reward distribution of epoch {} pays a reward of epoch {}",
+                        epoch,
+                        payment.epoch
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check the system transactions of a block: they come first, at most one per reserved
+/// slot and in slot order, are unsigned, move no value, pay no fee and satisfy the
+/// rules of their kind. Returns the number of system transactions.
+pub fn validate_system_transactions(
+    transactions: &[SignedTransaction],
+    block_number: u128,
+    block_timestamp: u64,
+) -> Result<usize> {
+    let system_count = transactions.iter().take_while(|tx| tx.is_system()).count();
+    if let Some(tx) = transactions[system_count..]
+        .iter()
+        .find(|tx| tx.is_system())
+    {
+        bail!(
+            "System transaction {:?} is not in a reserved slot at the start of the block",
+            tx.hash()
+        );
+    }
+    if system_count > SYSTEM_TRANSACTION_SLOTS {
+        bail!(
+            "Block has {} system transactions, only {} slots are reserved",
+            system_count,
+            SYSTEM_TRANSACTION_SLOTS
+        );
+    }
+
+    let mut next_slot = 0;
+    for tx in &transactions[..system_count] {
+        let system = SystemTransaction::from_transaction(tx)?.ok_or_else(|| {
+            anyhow::anyhow!("Transaction {:?} is not a system transaction", tx.hash())
+        })?;
+        if system.slot() < next_slot {
+            bail!(
+                "System transaction {} is out of its reserved slot order",
+                system.kind()
+            );
+        }
+        next_slot = system.slot() + 1;
+        if tx.tx.gas_price != 0
+            || tx.tx.gas_limit != 0
+            || tx.tx.amount != 0
+            || tx.tx.recipient.is_some()
+        {
+            bail!(
+                "System transaction {} must not pay fees or move value",
+                system.kind()
+            );
+        }
+        if !tx.public_key.is_empty() || !tx.signature.is_empty() {
+            bail!("System transaction {} must not be signed", system.kind());
+        }
+        if tx.tx.sequence_number != block_number as u64 {
+            bail!(
+                "System transaction {} has sequence number {}, expected the block number {}",
+                system.kind(),
+                tx.tx.sequence_number,
+                block_number
+            );
+        }
+        system.validate(block_number, block_timestamp)?;
+    }
+    Ok(system_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_system_transactions() {
+        let genesis_hash = H256::random();
+        let timestamp = 1_700_000_000;
+        let update = SystemTransaction::timestamp_update(timestamp, 200).into_transaction(
+            1,
+            genesis_hash,
+            200,
+        );
+        let checkpoint = SystemTransaction::Checkpoint {
+            block_number: 199,
+            state_root: H256::random(),
+        }
+        .into_transaction(1, genesis_hash, 200);
+        assert_eq!(
+            SystemTransaction::from_transaction(&update).unwrap(),
+            Some(SystemTransaction::timestamp_update(timestamp, 200))
+        );
+
+        let ordered = vec![update.clone(), checkpoint.clone()];
+        assert_eq!(
+            validate_system_transactions(&ordered, 200, timestamp).unwrap(),
+            2
+        );
+        // Out of slot order, and a stale timestamp
+        assert!(
+            validate_system_transactions(&[checkpoint.clone(), update.clone()], 200, timestamp)
+                .is_err()
+        );
+        assert!(validate_system_transactions(&ordered, 200, timestamp + 1).is_err());
+
+        let mut paid = update;
+        paid.tx.gas_price = 1;
+        assert!(validate_system_transactions(&[paid], 200, timestamp).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::system_transaction::SYSTEM_TRANSACTION_SENDER;
+use anyhow::{Result, bail};
+use fastcrypto::secp256k1::{Secp256k1KeyPair, Secp256k1PublicKey, Secp256k1Signature};
+use fastcrypto::traits::{KeyPair, Signer, ToFromBytes, VerifyingKey};
+use move_core_types::account_address::AccountAddress;
+use moveos_types::h256::{H256, sha2_256_of};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Domain separator prepended to the transaction bytes before signing
+pub const TRANSACTION_SIGNING_DOMAIN: &[u8] = b"KANARI::Transaction";
+
+/// Gas charged to a transaction whose access list missed an account it touched
+pub const WRONG_ACCESS_LIST_GAS_PENALTY: u64 = 5_000;
+
+/// Gas every user transaction uses, covering the signature check and the transfer
+pub const TRANSACTION_BASE_GAS: u64 = 21_000;
+
+/// Gas used per byte of call data
+pub const DATA_BYTE_GAS: u64 = 16;
+
+/// The unsigned transaction payload
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct KanariTransaction {
+    /// The account sending the transaction
+    pub sender: AccountAddress,
+    /// The sender's sequence number (nonce)
+    pub sequence_number: u64,
+    /// The chain the transaction is valid for
+    pub chain_id: u64,
+    /// The genesis block hash of the network the transaction was built against,
+    /// so a transaction can not be replayed on another network with the same chain id
+    pub genesis_hash: H256,
+    /// The account receiving the transfer, if any
+    pub recipient: Option<AccountAddress>,
+    /// The amount transferred, in the smallest KARI unit
+    pub amount: u128,
+    /// Maximum gas units the transaction may use
+    pub gas_limit: u64,
+    /// Price per gas unit
+    pub gas_price: u64,
+    /// Opaque call data
+    pub data: Vec<u8>,
+    /// Accounts the transaction reads or writes besides the sender and recipient, a
+    /// hint letting the parallel execution scheduler run it alongside transactions
+    /// touching other accounts. Without it the transaction runs on its own.
+    pub access_list: Option<Vec<AccountAddress>>,
+}
+
+impl KanariTransaction {
+    /// The canonical transaction hash, computed over the BCS encoding
+    pub fn hash(&self) -> H256 {
+        sha2_256_of(&bcs::to_bytes(self).expect("Serialize transaction should success"))
+    }
+
+    /// The domain separated message that is signed by the sender
+    pub fn signing_message(&self) -> Vec<u8> {
+        let mut message = TRANSACTION_SIGNING_DOMAIN.to_vec();
+        message.extend(bcs::to_bytes(self).expect("Serialize transaction should success"));
+        message
+    }
+
+    /// The maximum fee the sender may pay
+    pub fn max_fee(&self) -> u128 {
+        self.gas_limit as u128 * self.gas_price as u128
+    }
+
+    /// The gas executing the transaction takes: the base gas plus its call data
+    pub fn intrinsic_gas(&self) -> u64 {
+        TRANSACTION_BASE_GAS.saturating_add(DATA_BYTE_GAS.saturating_mul(self.data.len() as u64))
+    }
+
+    /// The accounts the transaction declares it touches, None if it has no access list
+    pub fn declared_accounts(&self) -> Option<BTreeSet<AccountAddress>> {
+        let access_list = self.access_list.as_ref()?;
+        let mut accounts: BTreeSet<AccountAddress> = access_list.iter().copied().collect();
+        accounts.insert(self.sender);
+        accounts.extend(self.recipient);
+        Some(accounts)
+    }
+
+    /// The accounts touched during execution that the access list did not declare.
+    /// Executing such a transaction in parallel was unsafe, so it is re-executed in
+    /// order and charged `WRONG_ACCESS_LIST_GAS_PENALTY`.
+    pub fn undeclared_accounts<'a>(
+        &self,
+        touched: impl IntoIterator<Item = &'a AccountAddress>,
+    ) -> Vec<AccountAddress> {
+        let Some(declared) = self.declared_accounts() else {
+            return vec![];
+        };
+        touched
+            .into_iter()
+            .filter(|account| !declared.contains(account))
+            .copied()
+            .collect()
+    }
+
+    /// Check that the transaction was built for the given network
+    pub fn check_network(&self, chain_id: u64, genesis_hash: &H256) -> Result<()> {
+        if self.chain_id != chain_id {
+            bail!(
+                "Transaction is for chain id {}, but the network has chain id {}",
+                self.chain_id,
+                chain_id
+            );
+        }
+        if &self.genesis_hash != genesis_hash {
+            bail!(
+                "Transaction is for genesis {:?}, but the network has genesis {:?}",
+                self.genesis_hash,
+                genesis_hash
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A transaction with the sender's secp256k1 signature
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    pub tx: KanariTransaction,
+    /// Compressed secp256k1 public key of the signer
+    pub public_key: Vec<u8>,
+    /// Signature over the transaction signing message
+    pub signature: Vec<u8>,
+}
+
+impl SignedTransaction {
+    pub fn sign(tx: KanariTransaction, key_pair: &Secp256k1KeyPair) -> Self {
+        let signature: Secp256k1Signature = key_pair.sign(&tx.signing_message());
+        Self {
+            tx,
+            public_key: key_pair.public().as_bytes().to_vec(),
+            signature: signature.as_bytes().to_vec(),
+        }
+    }
+
+    /// The hash of the signed transaction is the hash of its payload
+    pub fn hash(&self) -> H256 {
+        self.tx.hash()
+    }
+
+    /// Whether the node generated this transaction, see `SystemTransaction`
+    pub fn is_system(&self) -> bool {
+        self.tx.sender == SYSTEM_TRANSACTION_SENDER
+    }
+
+    /// Check that the signature was produced by the embedded public key
+    pub fn verify_signature(&self) -> Result<()> {
+        let public_key = Secp256k1PublicKey::from_bytes(&self.public_key)
+            .map_err(|e| anyhow::anyhow!("Invalid public key: {}", e))?;
+        let signature = Secp256k1Signature::from_bytes(&self.signature)
+            .map_err(|e| anyhow::anyhow!("Invalid signature encoding: {}", e))?;
+        public_key
+            .verify(&self.tx.signing_message(), &signature)
+            .map_err(|e| anyhow::anyhow!("Invalid signature: {}", e))
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        bcs::to_bytes(self).expect("Serialize transaction should success")
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(bytes)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_network() {
+        let tx = KanariTransaction {
+            sender: AccountAddress::ONE,
+            sequence_number: 0,
+            chain_id: 2,
+            genesis_hash: H256::random(),
+            recipient: None,
+            amount: 0,
+            gas_limit: 21_000,
+            gas_price: 1,
+            data: vec![],
+            access_list: None,
+        };
+        assert!(tx.check_network(2, &tx.genesis_hash).is_ok());
+        assert!(tx.check_network(1, &tx.genesis_hash).is_err());
+        assert!(tx.check_network(2, &H256::random()).is_err());
+    }
+
+    #[test]
+    fn test_intrinsic_gas_covers_call_data() {
+        let mut tx = KanariTransaction {
+            sender: AccountAddress::ONE,
+            sequence_number: 0,
+            chain_id: 2,
+            genesis_hash: H256::zero(),
+            recipient: None,
+            amount: 0,
+            gas_limit: 100_000,
+            gas_price: 1,
+            data: vec![],
+            access_list: None,
+        };
+        assert_eq!(tx.intrinsic_gas(), TRANSACTION_BASE_GAS);
+        tx.data = vec![0; 10];
+        assert_eq!(
+            tx.intrinsic_gas(),
+            TRANSACTION_BASE_GAS + 10 * DATA_BYTE_GAS
+        );
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use kanari_config::alerting_config::{AlertRule, AlertSink, AlertingConfig};
+use kanari_rpc_api::NodeState;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+
+const BYTES_PER_GB: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertStatus {
+    Firing,
+    Resolved,
+}
+
+/// A state change of a rule, delivered to every sink
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertNotification {
+    pub rule: String,
+    pub status: AlertStatus,
+    pub message: String,
+    pub timestamp: u64,
+}
+
+/// Internal metrics a round of rules is evaluated against
+#[derive(Debug, Clone)]
+pub struct HealthSample {
+    pub block_height: u128,
+    /// Connected peers, None while the P2P network is not running
+    pub peer_count: Option<usize>,
+    pub fork_detected: bool,
+    /// Whether the disk guard switched the node to read-only mode
+    pub read_only: bool,
+    /// Time since the block height last changed
+    pub since_last_block: Duration,
+    /// Free bytes of the volumes referenced by disk rules
+    pub free_disk_bytes: HashMap<PathBuf, u64>,
+}
+
+fn rule_name(rule: &AlertRule) -> &'static str {
+    match rule {
+        AlertRule::NoBlockProduced { .. } => "no_block_produced",
+        AlertRule::PeerCountBelow { .. } => "peer_count_below",
+        AlertRule::DiskSpaceBelow { .. } => "disk_space_below",
+        AlertRule::ForkDetected => "fork_detected",
+        AlertRule::ReadOnlyMode => "read_only_mode",
+    }
+}
+
+/// Evaluate a rule, returning the alert message while it is firing
+pub fn evaluate_rule(rule: &AlertRule, sample: &HealthSample, data_dir: &Path) -> Option<String> {
+    match rule {
+        AlertRule::NoBlockProduced { minutes } => {
+            (sample.since_last_block >= Duration::from_secs(minutes * 60)).then(|| {
+                format!(
+                    "No block produced for {} minute(s), height stuck at #{}",
+                    sample.since_last_block.as_secs() / 60,
+                    sample.block_height
+                )
+            })
+        }
+        AlertRule::PeerCountBelow { min_peers } => {
+            let peer_count = sample.peer_count?;
+            (peer_count < *min_peers).then(|| {
+                format!(
+                    "Peer count {} is below the minimum of {}",
+                    peer_count, min_peers
+                )
+            })
+        }
+        AlertRule::DiskSpaceBelow { path, min_free_gb } => {
+            let path = path.as_deref().unwrap_or(data_dir);
+            let free_bytes = *sample.free_disk_bytes.get(path)?;
+            (free_bytes < min_free_gb * BYTES_PER_GB).then(|| {
+                format!(
+                    "Free disk space on {} is {} GB, below {} GB",
+                    path.display(),
+                    free_bytes / BYTES_PER_GB,
+                    min_free_gb
+                )
+            })
+        }
+        AlertRule::ForkDetected => sample.fork_detected.then(|| {
+            format!(
+                "Local state diverged from the trusted node at height #{}",
+                sample.block_height
+            )
+        }),
+        AlertRule::ReadOnlyMode => sample.read_only.then(|| {
+            format!(
+                "Disk almost full, block production and transaction admission stopped at height #{}",
+                sample.block_height
+            )
+        }),
+    }
+}
+
+/// Evaluates alerting rules on an interval and notifies the sinks when a rule
+/// starts or stops firing, for operators without a Prometheus/Alertmanager stack.
+pub struct AlertEngine {
+    config: AlertingConfig,
+    data_dir: PathBuf,
+    node_state: Arc<RwLock<NodeState>>,
+    fork_detected: Arc<AtomicBool>,
+    network_running: Arc<AtomicBool>,
+    http_client: reqwest::Client,
+    firing: HashMap<usize, bool>,
+    last_height: u128,
+    last_height_change: Instant,
+}
+
+impl AlertEngine {
+    pub fn new(
+        config: AlertingConfig,
+        data_dir: PathBuf,
+        node_state: Arc<RwLock<NodeState>>,
+        fork_detected: Arc<AtomicBool>,
+        network_running: Arc<AtomicBool>,
+    ) -> Result<Self> {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(Self {
+            config,
+            data_dir,
+            node_state,
+            fork_detected,
+            network_running,
+            http_client,
+            firing: HashMap::new(),
+            last_height: 0,
+            last_height_change: Instant::now(),
+        })
+    }
+
+    /// Run the engine until the task is dropped
+    pub async fn run(mut self) {
+        info!(
+            "Alerting engine started with {} rule(s) and {} sink(s)",
+            self.config.rules.len(),
+            self.config.sinks.len()
+        );
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            self.config.evaluation_interval_secs.max(1),
+        ));
+        loop {
+            interval.tick().await;
+            let sample = self.sample().await;
+            for notification in self.evaluate(&sample) {
+                self.notify(&notification).await;
+            }
+        }
+    }
+
+    async fn sample(&mut self) -> HealthSample {
+        let (block_height, peer_count, read_only) = {
+            let state = self.node_state.read().await;
+            (state.block_height, state.peer_count, state.read_only)
+        };
+        if block_height != self.last_height {
+            self.last_height = block_height;
+            self.last_height_change = Instant::now();
+        }
+
+        let mut free_disk_bytes = HashMap::new();
+        for rule in &self.config.rules {
+            if let AlertRule::DiskSpaceBelow { path, .. } = rule {
+                let path = path.clone().unwrap_or_else(|| self.data_dir.clone());
+                match fs2::available_space(&path) {
+                    Ok(free) => {
+                        free_disk_bytes.insert(path, free);
+                    }
+                    Err(e) => warn!(
+                        "Failed to read free disk space of {}: {}",
+                        path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+
+        HealthSample {
+            block_height,
+            peer_count: self
+                .network_running
+                .load(Ordering::SeqCst)
+                .then_some(peer_count),
+            fork_detected: self.fork_detected.load(Ordering::SeqCst),
+            read_only,
+            since_last_block: self.last_height_change.elapsed(),
+            free_disk_bytes,
+        }
+    }
+
+    /// Evaluate all rules and return the notifications for rules that changed state
+    pub fn evaluate(&mut self, sample: &HealthSample) -> Vec<AlertNotification> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut notifications = vec![];
+        for (index, rule) in self.config.rules.iter().enumerate() {
+            let result = evaluate_rule(rule, sample, &self.data_dir);
+            let was_firing = self.firing.get(&index).copied().unwrap_or(false);
+            let notification = match (&result, was_firing) {
+                (Some(message), false) => Some((AlertStatus::Firing, message.clone())),
+                (None, true) => Some((AlertStatus::Resolved, "Condition cleared".to_string())),
+                _ => None,
+            };
+            self.firing.insert(index, result.is_some());
+
+            if let Some((status, message)) = notification {
+                notifications.push(AlertNotification {
+                    rule: rule_name(rule).to_string(),
+                    status,
+                    message,
+                    timestamp,
+                });
+            }
+        }
+        notifications
+    }
+
+    async fn notify(&self, notification: &AlertNotification) {
+        for sink in &self.config.sinks {
+            if let Err(e) = self.deliver(sink, notification).await {
+                error!("Failed to deliver alert {}: {}", notification.rule, e);
+            }
+        }
+    }
+
+    async fn deliver(&self, sink: &AlertSink, notification: &AlertNotification) -> Result<()> {
+        match sink {
+            AlertSink::Log => {
+                match notification.status {
+                    AlertStatus::Firing => error!(
+                        "ALERT [{}] firing: {}",
+                        notification.rule, notification.message
+                    ),
+                    AlertStatus::Resolved => info!("ALERT [{}] resolved", notification.rule),
+                }
+                Ok(())
+            }
+            AlertSink::Webhook { url } => {
+                self.http_client
+                    .post(url)
+                    .json(notification)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                Ok(())
+            }
+            AlertSink::Email { to, from } => {
+                let to = to.join(", ");
+                let from = from
+                    .clone()
+                    .unwrap_or_else(|| "kanari@localhost".to_string());
+                let email = format!(
+                    "To: {}\nFrom: {}\nSubject: [kanari] {} {:?}\n\n{}\n",
+                    to, from, notification.rule, notification.status, notification.message
+                );
+                tokio::task::spawn_blocking(move || send_mail(&email)).await?
+            }
+        }
+    }
+}
+
+/// Hand an RFC 822 message to the local MTA
+fn send_mail(email: &str) -> Result<()> {
+    let mut child = Command::new("sendmail")
+        .arg("-t")
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin.write_all(email.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("sendmail exited with {}", status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> HealthSample {
+        HealthSample {
+            block_height: 10,
+            peer_count: Some(5),
+            fork_detected: false,
+            read_only: false,
+            since_last_block: Duration::from_secs(30),
+            free_disk_bytes: HashMap::from([(PathBuf::from("/data"), 50 * BYTES_PER_GB)]),
+        }
+    }
+
+    #[test]
+    fn test_rule_evaluation() {
+        let data_dir = Path::new("/data");
+        let mut sample = sample();
+        let rules = [
+            AlertRule::NoBlockProduced { minutes: 1 },
+            AlertRule::PeerCountBelow { min_peers: 3 },
+            AlertRule::DiskSpaceBelow {
+                path: None,
+                min_free_gb: 10,
+            },
+            AlertRule::ForkDetected,
+            AlertRule::ReadOnlyMode,
+        ];
+        assert!(
+            rules
+                .iter()
+                .all(|rule| evaluate_rule(rule, &sample, data_dir).is_none())
+        );
+
+        sample.since_last_block = Duration::from_secs(120);
+        sample.peer_count = Some(1);
+        sample
+            .free_disk_bytes
+            .insert(PathBuf::from("/data"), BYTES_PER_GB);
+        sample.fork_detected = true;
+        sample.read_only = true;
+        assert!(
+            rules
+                .iter()
+                .all(|rule| evaluate_rule(rule, &sample, data_dir).is_some())
+        );
+
+        // Without a running network there is no peer count to alert on
+        sample.peer_count = None;
+        assert!(evaluate_rule(&rules[1], &sample, data_dir).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_notifies_only_on_state_change() {
+        let config = AlertingConfig {
+            evaluation_interval_secs: 1,
+            rules: vec![AlertRule::PeerCountBelow { min_peers: 3 }],
+            sinks: vec![AlertSink::Log],
+        };
+        let mut engine = AlertEngine::new(
+            config,
+            PathBuf::from("/data"),
+            Arc::new(RwLock::new(NodeState::default())),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(true)),
+        )
+        .unwrap();
+
+        let mut sample = sample();
+        sample.peer_count = Some(0);
+        let fired = engine.evaluate(&sample);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].status, AlertStatus::Firing);
+        assert!(engine.evaluate(&sample).is_empty());
+
+        sample.peer_count = Some(5);
+        let resolved = engine.evaluate(&sample);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].status, AlertStatus::Resolved);
+    }
+}
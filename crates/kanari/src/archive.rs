@@ -0,0 +1,284 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow, bail};
+use kanari_config::archive_config::ArchiveConfig;
+use kanari_db::{ExecutedBlock, RoochDB};
+use kanari_types::block::Block;
+use kanari_types::event::BlockEvent;
+use kanari_types::transaction::SignedTransaction;
+use moveos_types::h256::{H256, sha2_256_of};
+use opendal::{ErrorKind, Operator, services::S3};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Object listing every archived batch, written after each batch upload
+pub const ARCHIVE_MANIFEST_PATH: &str = "manifest.json";
+
+/// A block with everything needed to hydrate a node from the archive
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedBlock {
+    pub block: Block,
+    pub timestamp: Option<u64>,
+    /// The block receipts
+    pub events: Vec<BlockEvent>,
+    /// Account and hash indexes are rebuilt from these on restore
+    pub transactions: Vec<SignedTransaction>,
+}
+
+/// One archived object of consecutive blocks
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveBatchEntry {
+    pub first_block: u128,
+    pub last_block: u128,
+    pub path: String,
+    /// SHA2-256 of the object bytes
+    pub sha256: H256,
+    pub size: u64,
+    /// Hash of the last block, checked again after download
+    pub last_block_hash: H256,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub batches: Vec<ArchiveBatchEntry>,
+}
+
+impl ArchiveManifest {
+    /// The first block not archived yet
+    pub fn next_block(&self) -> u128 {
+        self.batches
+            .last()
+            .map(|batch| batch.last_block + 1)
+            .unwrap_or(1)
+    }
+}
+
+/// Archived blocks in an S3-compatible bucket
+pub struct ArchiveStore {
+    operator: Operator,
+}
+
+impl ArchiveStore {
+    pub fn new(config: &ArchiveConfig) -> Result<Self> {
+        let bucket = config
+            .bucket
+            .as_deref()
+            .ok_or_else(|| anyhow!("No archive bucket configured"))?;
+        let mut builder = S3::default()
+            .bucket(bucket)
+            .region(config.region())
+            .root(config.prefix());
+        if let Some(endpoint) = &config.endpoint {
+            builder = builder.endpoint(endpoint);
+        }
+        Ok(Self {
+            operator: Operator::new(builder)?.finish(),
+        })
+    }
+
+    /// The manifest, empty if nothing was archived yet
+    pub async fn manifest(&self) -> Result<ArchiveManifest> {
+        match self.operator.read(ARCHIVE_MANIFEST_PATH).await {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes.to_vec())?),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(ArchiveManifest::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn write_manifest(&self, manifest: &ArchiveManifest) -> Result<()> {
+        self.operator
+            .write(ARCHIVE_MANIFEST_PATH, serde_json::to_vec_pretty(manifest)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Upload a batch of consecutive blocks and record it in the manifest
+    pub async fn upload_batch(
+        &self,
+        manifest: &mut ArchiveManifest,
+        blocks: Vec<ArchivedBlock>,
+    ) -> Result<ArchiveBatchEntry> {
+        let (first, last) = match (blocks.first(), blocks.last()) {
+            (Some(first), Some(last)) => (first.block.block_number, last.block.block_number),
+            _ => bail!("An archive batch must contain at least one block"),
+        };
+        if first != manifest.next_block() {
+            bail!(
+                "Batch starts at block #{} but the archive continues at #{}",
+                first,
+                manifest.next_block()
+            );
+        }
+        let bytes = bcs::to_bytes(&blocks)?;
+        let entry = ArchiveBatchEntry {
+            first_block: first,
+            last_block: last,
+            path: format!("batches/{:020}-{:020}.bcs", first, last),
+            sha256: sha2_256_of(&bytes),
+            size: bytes.len() as u64,
+            last_block_hash: blocks[blocks.len() - 1].block.hash(),
+        };
+        self.operator.write(&entry.path, bytes).await?;
+        manifest.batches.push(entry.clone());
+        self.write_manifest(manifest).await?;
+        Ok(entry)
+    }
+
+    /// Download a batch and check it against its manifest entry
+    pub async fn download_batch(&self, entry: &ArchiveBatchEntry) -> Result<Vec<ArchivedBlock>> {
+        let bytes = self.operator.read(&entry.path).await?.to_vec();
+        if bytes.len() as u64 != entry.size || sha2_256_of(&bytes) != entry.sha256 {
+            bail!(
+                "Archived batch {} does not match its manifest hash",
+                entry.path
+            );
+        }
+        let blocks: Vec<ArchivedBlock> = bcs::from_bytes(&bytes)?;
+        let numbers_match = blocks
+            .iter()
+            .map(|archived| archived.block.block_number)
+            .eq(entry.first_block..=entry.last_block);
+        if !numbers_match
+            || blocks.last().map(|archived| archived.block.hash()) != Some(entry.last_block_hash)
+        {
+            bail!(
+                "Archived batch {} does not hold the listed blocks",
+                entry.path
+            );
+        }
+        Ok(blocks)
+    }
+}
+
+/// Read the blocks `first..=last` with their receipts and transactions
+pub fn read_blocks(db: &RoochDB, first: u128, last: u128) -> Result<Vec<ArchivedBlock>> {
+    (first..=last)
+        .map(|block_number| {
+            let block = db
+                .get_block(block_number)?
+                .ok_or_else(|| anyhow!("Block #{} not found", block_number))?;
+            Ok(ArchivedBlock {
+                block,
+                timestamp: db.get_block_timestamp(block_number)?,
+                events: db.get_block_events(block_number)?,
+                transactions: db.get_block_transactions(block_number)?,
+            })
+        })
+        .collect()
+}
+
+/// Re-execute an archived block on the state its parent left, check it reaches the
+/// recorded state root, then persist it and rebuild its indexes
+pub fn write_block(db: &RoochDB, archived: &ArchivedBlock) -> Result<()> {
+    let block_number = archived.block.block_number;
+    let execution = db.execute_block(block_number, &archived.transactions)?;
+    if execution.root.state_root() != archived.block.state_root {
+        bail!(
+            "Archived block #{} records state root {:?}, re-execution reached {:?}",
+            block_number,
+            archived.block.state_root,
+            execution.root.state_root()
+        );
+    }
+    db.save_executed_block(&ExecutedBlock {
+        block: &archived.block,
+        timestamp: archived.timestamp.unwrap_or_default(),
+        transactions: &archived.transactions,
+        outputs: &execution.outputs,
+        events: &archived.events,
+        root: &execution.root,
+        proposer: None,
+        epoch_snapshot: None,
+        dao: &execution.dao,
+    })
+}
+
+/// Background task uploading finalized blocks to the archive in fixed size batches
+pub struct Archiver {
+    config: ArchiveConfig,
+    store: ArchiveStore,
+    db: Arc<RoochDB>,
+}
+
+impl Archiver {
+    pub fn new(config: ArchiveConfig, db: Arc<RoochDB>) -> Result<Self> {
+        Ok(Self {
+            store: ArchiveStore::new(&config)?,
+            config,
+            db,
+        })
+    }
+
+    /// Run the archiver until the task is dropped
+    pub async fn run(self: Arc<Self>) {
+        info!(
+            "Block archiver started (batches of {} block(s), {} confirmation(s))",
+            self.config.batch_size(),
+            self.config.confirmations()
+        );
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.interval_secs().max(1)));
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.archive_finalized().await {
+                warn!("Block archiving failed, retrying next round: {}", e);
+            }
+        }
+    }
+
+    /// The first block not archived yet
+    pub async fn archived_until(&self) -> Result<u128> {
+        Ok(self.store.manifest().await?.next_block())
+    }
+
+    /// Upload every complete batch of blocks with enough confirmations
+    pub async fn archive_finalized(&self) -> Result<()> {
+        let Some(latest) = self.db.get_latest_block_number()? else {
+            return Ok(());
+        };
+        let finalized = latest.saturating_sub(self.config.confirmations() as u128);
+        let batch_size = self.config.batch_size() as u128;
+        let mut manifest = self.store.manifest().await?;
+        loop {
+            let first = manifest.next_block();
+            let last = first + batch_size - 1;
+            if last > finalized {
+                return Ok(());
+            }
+            let blocks = read_blocks(&self.db, first, last)?;
+            let entry = self.store.upload_batch(&mut manifest, blocks).await?;
+            info!(
+                "Archived blocks #{}..=#{} to {} ({} bytes)",
+                entry.first_block, entry.last_block, entry.path, entry.size
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_continues_after_last_batch() {
+        let mut manifest = ArchiveManifest::default();
+        assert_eq!(manifest.next_block(), 1);
+        manifest.batches.push(ArchiveBatchEntry {
+            first_block: 1,
+            last_block: 100,
+            path: "batches/1-100.bcs".to_string(),
+            sha256: H256::zero(),
+            size: 0,
+            last_block_hash: H256::zero(),
+        });
+        assert_eq!(manifest.next_block(), 101);
+        let json = serde_json::to_vec(&manifest).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<ArchiveManifest>(&json).unwrap(),
+            manifest
+        );
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use kanari_config::BlockAuditConfig;
+use kanari_db::RoochDB;
+use kanari_types::block::transactions_batch_hash;
+use kanari_types::event::events_bloom;
+use kanari_types::receipt::block_receipts;
+use kanari_types::system_transaction::{CHECKPOINT_INTERVAL, SystemTransaction};
+use prometheus::{IntCounter, Registry};
+use rand::Rng;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Outcome of auditing a single historical block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockAudit {
+    Consistent { block_number: u128 },
+    Diverged { block_number: u128, reason: String },
+    Missing { block_number: u128 },
+}
+
+struct BlockAuditMetrics {
+    blocks_checked: IntCounter,
+    divergences: IntCounter,
+}
+
+impl BlockAuditMetrics {
+    fn new(registry: &Registry) -> Result<Self> {
+        let blocks_checked = IntCounter::new(
+            "kanari_block_audit_checked_total",
+            "Number of historical blocks re-verified by the background auditor",
+        )?;
+        let divergences = IntCounter::new(
+            "kanari_block_audit_divergences_total",
+            "Number of historical blocks whose stored data no longer matches",
+        )?;
+        registry.register(Box::new(blocks_checked.clone()))?;
+        registry.register(Box::new(divergences.clone()))?;
+        Ok(Self {
+            blocks_checked,
+            divergences,
+        })
+    }
+}
+
+/// Low priority background task that randomly samples historical blocks, re-executes
+/// their transactions and compares the results with the stored events, receipts and
+/// checkpointed state roots, catching silent storage corruption.
+pub struct BlockAuditor {
+    config: BlockAuditConfig,
+    db: Arc<RoochDB>,
+    metrics: BlockAuditMetrics,
+}
+
+impl BlockAuditor {
+    pub fn new(config: BlockAuditConfig, db: Arc<RoochDB>, registry: &Registry) -> Result<Self> {
+        Ok(Self {
+            config,
+            db,
+            metrics: BlockAuditMetrics::new(registry)?,
+        })
+    }
+
+    /// Run the auditor until the task is dropped
+    pub async fn run(self) {
+        info!(
+            "Block auditor started (every {}s, {} block(s) per round)",
+            self.config.interval_secs, self.config.samples_per_round
+        );
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            let latest = match self.db.get_latest_block_number() {
+                Ok(Some(latest)) => latest,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Block audit failed to read the latest block: {}", e);
+                    continue;
+                }
+            };
+
+            for _ in 0..self.config.samples_per_round {
+                let block_number = rand::thread_rng().gen_range(1..=latest);
+                match self.audit_block(block_number) {
+                    Ok(BlockAudit::Consistent { block_number }) => {
+                        debug!("Block #{} passed audit", block_number);
+                    }
+                    Ok(BlockAudit::Diverged {
+                        block_number,
+                        reason,
+                    }) => {
+                        self.metrics.divergences.inc();
+                        error!(
+                            "CRITICAL: stored data of block #{} diverged: {}",
+                            block_number, reason
+                        );
+                    }
+                    Ok(BlockAudit::Missing { block_number }) => {
+                        debug!("Block #{} not found, skipping audit", block_number);
+                    }
+                    Err(e) => {
+                        self.metrics.divergences.inc();
+                        error!(
+                            "CRITICAL: block #{} could not be audited: {}",
+                            block_number, e
+                        );
+                    }
+                }
+                // Yield between samples so the audit never competes with block production
+                tokio::task::yield_now().await;
+            }
+        }
+    }
+
+    /// Re-execute one block and verify it against the data stored for it
+    pub fn audit_block(&self, block_number: u128) -> Result<BlockAudit> {
+        let block = match self.db.get_block(block_number)? {
+            Some(block) => block,
+            None => return Ok(BlockAudit::Missing { block_number }),
+        };
+        self.metrics.blocks_checked.inc();
+
+        if block.block_number != block_number {
+            return Ok(BlockAudit::Diverged {
+                block_number,
+                reason: format!(
+                    "stored under #{} but encodes #{}",
+                    block_number, block.block_number
+                ),
+            });
+        }
+
+        // The stored transactions must be the ones the header commits to
+        let transactions = self.db.get_block_transactions(block_number)?;
+        if block.batch_size != transactions.len() as u64
+            || block.batch_hash != transactions_batch_hash(&transactions)
+        {
+            return Ok(BlockAudit::Diverged {
+                block_number,
+                reason: "stored transactions do not match the block batch hash".to_string(),
+            });
+        }
+
+        // Re-execute the transactions on the state the parent left and compare the
+        // results with the stored ones
+        let execution = self.db.execute_block(block_number, &transactions)?;
+        if execution.root.state_root() != block.state_root {
+            return Ok(BlockAudit::Diverged {
+                block_number,
+                reason: format!(
+                    "state root {:?} differs from the re-executed {:?}",
+                    block.state_root,
+                    execution.root.state_root()
+                ),
+            });
+        }
+        let events = execution.events;
+        if self.db.get_block_events(block_number)? != events {
+            return Ok(BlockAudit::Diverged {
+                block_number,
+                reason: "stored events differ from re-executed transactions".to_string(),
+            });
+        }
+        if let Some(stored_bloom) = self.db.get_block_bloom(block_number)? {
+            if events_bloom(&events) != stored_bloom {
+                return Ok(BlockAudit::Diverged {
+                    block_number,
+                    reason: "event bloom does not match the stored events".to_string(),
+                });
+            }
+        }
+        for receipt in block_receipts(block_number, &transactions, &execution.outputs, &events) {
+            // Receipts beyond the retention are pruned
+            if let Some(stored) = self.db.get_transaction_receipt(&receipt.tx_hash)? {
+                if stored != receipt {
+                    return Ok(BlockAudit::Diverged {
+                        block_number,
+                        reason: format!(
+                            "stored receipt of {:?} differs from re-execution",
+                            receipt.tx_hash
+                        ),
+                    });
+                }
+            }
+        }
+
+        // The state root is compared with the checkpoint that committed it
+        if (block_number + 1) % CHECKPOINT_INTERVAL == 0 {
+            let committed = self
+                .db
+                .get_block_transactions(block_number + 1)?
+                .iter()
+                .find_map(|tx| match SystemTransaction::from_transaction(tx) {
+                    Ok(Some(SystemTransaction::Checkpoint {
+                        block_number: checkpoint,
+                        state_root,
+                    })) if checkpoint == block_number => Some(state_root),
+                    _ => None,
+                });
+            if let Some(state_root) = committed.filter(|root| *root != block.state_root) {
+                return Ok(BlockAudit::Diverged {
+                    block_number,
+                    reason: format!(
+                        "state root {:?} differs from the checkpointed {:?}",
+                        block.state_root, state_root
+                    ),
+                });
+            }
+        }
+
+        if let Some(parent) = block_number
+            .checked_sub(1)
+            .filter(|parent| *parent > 0)
+            .map(|parent| self.db.get_block(parent))
+            .transpose()?
+            .flatten()
+        {
+            if !block.extends(&parent) {
+                return Ok(BlockAudit::Diverged {
+                    block_number,
+                    reason: format!("block does not extend its parent #{}", parent.block_number),
+                });
+            }
+        }
+
+        Ok(BlockAudit::Consistent { block_number })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_production::{build_block, save_built_block};
+    use kanari_config::KanariOpt;
+    use kanari_mempool::PooledTransaction;
+    use kanari_types::reward::RewardPayment;
+    use move_core_types::account_address::AccountAddress;
+    use moveos_types::h256::H256;
+
+    #[test]
+    fn test_audit_detects_a_tampered_state_root() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+        let reward = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: AccountAddress::ONE,
+                recipient: AccountAddress::ONE,
+                amount: 100,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 2);
+        let mut last = None;
+        for (block_number, pending) in [(1, vec![]), (2, vec![PooledTransaction::new(reward)])] {
+            let timestamp = 1_700_000_000 + block_number as u64 * 10;
+            let built = build_block(&db, block_number, 1, timestamp, &pending).unwrap();
+            save_built_block(&db, &built, timestamp, None).unwrap();
+            last = Some(built.block);
+        }
+        let auditor = BlockAuditor::new(
+            BlockAuditConfig {
+                interval_secs: 1,
+                samples_per_round: 1,
+            },
+            db.clone(),
+            &Registry::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            auditor.audit_block(2).unwrap(),
+            BlockAudit::Consistent { block_number: 2 }
+        );
+
+        let mut tampered = last.unwrap();
+        tampered.state_root = H256::zero();
+        db.save_block(&tampered, 1_700_000_020).unwrap();
+        assert!(matches!(
+            auditor.audit_block(2).unwrap(),
+            BlockAudit::Diverged { reason, .. } if reason.contains("state root")
+        ));
+    }
+}
@@ -0,0 +1,621 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use fastcrypto::secp256k1::Secp256k1KeyPair;
+use fastcrypto::traits::{KeyPair, ToFromBytes};
+use kanari_db::execution::DaoChanges;
+use kanari_db::{ExecutedBlock, RoochDB};
+use kanari_mempool::{PooledTransaction, TxPool};
+use kanari_rpc_api::{
+    BlockBuilder, DEFAULT_PENDING_SNAPSHOT_MAX_BYTES, MAX_PENDING_SNAPSHOT_COUNT, ProposalDryRun,
+};
+use kanari_types::block::{
+    Block, GENESIS_BLOCK_NUMBER, next_tx_accumulator_root, transactions_batch_hash,
+};
+use kanari_types::epoch::{ConsensusParams, EpochSnapshot, Validator, is_epoch_boundary};
+use kanari_types::event::BlockEvent;
+use kanari_types::receipt::TransactionOutput;
+use kanari_types::system_transaction::{CHECKPOINT_INTERVAL, SystemTransaction};
+use kanari_types::transaction::SignedTransaction;
+use move_core_types::account_address::AccountAddress;
+use moveos_types::h256::H256;
+use moveos_types::moveos_std::object::ObjectMeta;
+use prometheus::{Histogram, HistogramOpts, IntCounter, IntGauge, Registry};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{error, info, instrument, warn};
+
+/// Seconds between the blocks the node produces
+pub const BLOCK_INTERVAL_SECS: u64 = 10;
+
+pub struct BlockProductionMetrics {
+    produced: IntCounter,
+    failed: IntCounter,
+    height: IntGauge,
+    duration: Histogram,
+}
+
+impl BlockProductionMetrics {
+    pub fn new(registry: &Registry) -> Result<Self> {
+        let produced = IntCounter::new(
+            "kanari_blocks_produced_total",
+            "Number of blocks produced and saved by this node",
+        )?;
+        let failed = IntCounter::new(
+            "kanari_block_production_failures_total",
+            "Number of blocks this node failed to produce",
+        )?;
+        let height = IntGauge::new("kanari_block_height", "Height of the latest local block")?;
+        let duration = Histogram::with_opts(HistogramOpts::new(
+            "kanari_block_production_duration_seconds",
+            "Time to build and save a block",
+        ))?;
+        registry.register(Box::new(produced.clone()))?;
+        registry.register(Box::new(failed.clone()))?;
+        registry.register(Box::new(height.clone()))?;
+        registry.register(Box::new(duration.clone()))?;
+        Ok(Self {
+            produced,
+            failed,
+            height,
+            duration,
+        })
+    }
+
+    pub fn observe_produced(&self, block_number: u128, elapsed: Duration) {
+        self.produced.inc();
+        self.height.set(block_number as i64);
+        self.duration.observe(elapsed.as_secs_f64());
+    }
+
+    pub fn observe_failed(&self) {
+        self.failed.inc();
+    }
+}
+
+/// Time spent in each step of building a block
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BuildTimings {
+    pub select: Duration,
+    pub execute: Duration,
+    pub roots: Duration,
+}
+
+/// A block built by this node, not yet saved
+pub struct BuiltBlock {
+    pub block: Block,
+    pub transactions: Vec<SignedTransaction>,
+    /// What executing each transaction did, in block order
+    pub outputs: Vec<TransactionOutput>,
+    pub events: Vec<BlockEvent>,
+    /// The root object after the block, committed once the block is saved
+    pub root: ObjectMeta,
+    /// The validator set the block starts, at an epoch boundary
+    pub epoch_snapshot: Option<EpochSnapshot>,
+    /// The DAO proposals and votes the block changed
+    pub dao: DaoChanges,
+    pub timings: BuildTimings,
+}
+
+/// Build a block on top of the latest saved one: select its transactions from the
+/// `pending` pool snapshot, execute them and compute its roots. Only content addressed
+/// state nodes are written, the latest state is unchanged, so the result can be discarded.
+pub fn build_block(
+    db: &RoochDB,
+    block_number: u128,
+    chain_id: u64,
+    timestamp: u64,
+    pending: &[PooledTransaction],
+) -> Result<BuiltBlock> {
+    let mut timings = BuildTimings::default();
+    // The block links to its parent through the parent's accumulator root
+    let prev_tx_accumulator_root = if block_number == GENESIS_BLOCK_NUMBER {
+        H256::zero()
+    } else {
+        db.get_block(block_number - 1)?
+            .ok_or_else(|| anyhow::anyhow!("Parent block #{} not found", block_number - 1))?
+            .tx_accumulator_root
+    };
+
+    let started = Instant::now();
+    let epoch_snapshot = if is_epoch_boundary(block_number) {
+        db.epoch_snapshot_for_block(block_number)?
+    } else {
+        None
+    };
+    let mut transactions = system_transactions(
+        db,
+        block_number,
+        chain_id,
+        timestamp,
+        epoch_snapshot.as_ref(),
+    )?;
+    let block_gas_limit = db
+        .epoch_snapshot_for_block(block_number)?
+        .map(|snapshot| snapshot.params.block_gas_limit)
+        .unwrap_or(ConsensusParams::default().block_gas_limit);
+    transactions.extend(select_transactions(pending, block_gas_limit));
+    timings.select = started.elapsed();
+
+    let started = Instant::now();
+    let execution = db.execute_block(block_number, &transactions)?;
+    timings.execute = started.elapsed();
+
+    let started = Instant::now();
+    let batch_hash = transactions_batch_hash(&transactions);
+    let tx_accumulator_root = next_tx_accumulator_root(prev_tx_accumulator_root, batch_hash);
+    let state_root = execution.root.state_root();
+    timings.roots = started.elapsed();
+
+    let block = Block::new(
+        block_number,
+        transactions.len() as u64,
+        batch_hash,
+        prev_tx_accumulator_root,
+        tx_accumulator_root,
+        state_root,
+    );
+    Ok(BuiltBlock {
+        block,
+        transactions,
+        outputs: execution.outputs,
+        events: execution.events,
+        root: execution.root,
+        epoch_snapshot,
+        dao: execution.dao,
+        timings,
+    })
+}
+
+/// The pending transactions filling a block after its system transactions, in the
+/// snapshot's order, within the block gas limit. A sender's run stops at its first
+/// transaction that does not fit, so the block has no nonce gap.
+fn select_transactions(
+    pending: &[PooledTransaction],
+    block_gas_limit: u64,
+) -> Vec<SignedTransaction> {
+    let mut gas_used = 0u64;
+    let mut skipped: BTreeSet<AccountAddress> = BTreeSet::new();
+    let mut selected = vec![];
+    for pooled in pending {
+        if skipped.contains(&pooled.sender()) {
+            continue;
+        }
+        match gas_used.checked_add(pooled.tx.tx.gas_limit) {
+            Some(total) if total <= block_gas_limit => {
+                gas_used = total;
+                selected.push(pooled.tx.clone());
+            }
+            _ => {
+                skipped.insert(pooled.sender());
+            }
+        }
+    }
+    selected
+}
+
+/// The height of the block to produce next, on top of the latest saved one
+pub fn next_block_number(db: &RoochDB) -> Result<u128> {
+    Ok(db
+        .get_latest_block_number()?
+        .map_or(GENESIS_BLOCK_NUMBER, |latest| latest + 1))
+}
+
+/// Build and save the block on top of the latest saved one, returning its height and
+/// hash. The height is derived from the database on every call, so a failed attempt
+/// is retried at the same height.
+pub async fn produce_next_block(
+    db: &Arc<RoochDB>,
+    tx_pool: Option<&RwLock<TxPool>>,
+    chain_id: u64,
+    timestamp: u64,
+    signing_key: Option<&Secp256k1KeyPair>,
+) -> Result<(u128, H256)> {
+    let block_number = next_block_number(db)?;
+    let block_hash =
+        create_and_save_block(db, tx_pool, block_number, chain_id, timestamp, signing_key).await?;
+    Ok((block_number, block_hash))
+}
+
+/// Build and save the next block. Its user transactions come from `tx_pool`, which they
+/// leave once the block is saved.
+#[instrument(skip_all, fields(block_number = %block_number))]
+pub async fn create_and_save_block(
+    db: &Arc<RoochDB>,
+    tx_pool: Option<&RwLock<TxPool>>,
+    block_number: u128,
+    chain_id: u64,
+    timestamp: u64,
+    signing_key: Option<&Secp256k1KeyPair>,
+) -> Result<H256> {
+    let pending = match tx_pool {
+        Some(tx_pool) => tx_pool.read().await.pending_snapshot(
+            DEFAULT_PENDING_SNAPSHOT_MAX_BYTES,
+            MAX_PENDING_SNAPSHOT_COUNT,
+        ),
+        None => vec![],
+    };
+    let built = build_block(db, block_number, chain_id, timestamp, &pending)?;
+    info!("Created block #{} at timestamp {}", block_number, timestamp);
+    save_built_block(db, &built, timestamp, signing_key)?;
+    if let Some(tx_pool) = tx_pool {
+        tx_pool.write().await.remove_committed(&built.transactions);
+    }
+
+    Ok(built.block.hash())
+}
+
+/// Save a built block with its events, transactions and receipts and commit the state
+/// it left, in a single write. The proposer of a signed block earns its share of the
+/// epoch rewards.
+pub fn save_built_block(
+    db: &RoochDB,
+    built: &BuiltBlock,
+    timestamp: u64,
+    signing_key: Option<&Secp256k1KeyPair>,
+) -> Result<()> {
+    let block_number = built.block.block_number;
+    let proposer = signing_key.map(|key_pair| key_pair.public().as_bytes().to_vec());
+    let executed = ExecutedBlock {
+        block: &built.block,
+        timestamp,
+        transactions: &built.transactions,
+        outputs: &built.outputs,
+        events: &built.events,
+        root: &built.root,
+        proposer: proposer.as_deref(),
+        epoch_snapshot: built.epoch_snapshot.as_ref(),
+        dao: &built.dao,
+    };
+    if let Err(e) = db.save_executed_block(&executed) {
+        error!("Failed to save block #{} to database: {}", block_number, e);
+        return Err(e);
+    }
+    if let Some(snapshot) = &built.epoch_snapshot {
+        info!(
+            "Epoch {} started with {} validator(s)",
+            snapshot.epoch,
+            snapshot.validators.len()
+        );
+    }
+    Ok(())
+}
+
+/// Builds the next block for `admin_proposeDryRun` along the same path as block
+/// production, so operators can check the node is able to propose before its slot
+pub struct LocalBlockBuilder {
+    db: Arc<RoochDB>,
+    chain_id: u64,
+}
+
+impl LocalBlockBuilder {
+    pub fn new(db: Arc<RoochDB>, chain_id: u64) -> Self {
+        Self { db, chain_id }
+    }
+}
+
+impl BlockBuilder for LocalBlockBuilder {
+    fn dry_run(&self, timestamp: u64, pending: &[PooledTransaction]) -> Result<ProposalDryRun> {
+        let started = Instant::now();
+        let (block_number, parent_hash) = match self.db.get_latest_block_number()? {
+            Some(latest) => (
+                latest + 1,
+                self.db.get_block(latest)?.map(|parent| parent.hash()),
+            ),
+            None => (GENESIS_BLOCK_NUMBER, None),
+        };
+        let built = build_block(&self.db, block_number, self.chain_id, timestamp, pending)?;
+        let total = started.elapsed();
+
+        let hash = |hash: H256| format!("0x{}", hex::encode(hash.as_bytes()));
+        Ok(ProposalDryRun {
+            block_number,
+            hash: hash(built.block.hash()),
+            parent_hash: hash(parent_hash.unwrap_or_else(H256::zero)),
+            timestamp,
+            transaction_count: built.transactions.len(),
+            system_transaction_count: built
+                .transactions
+                .iter()
+                .filter(|tx| tx.is_system())
+                .count(),
+            event_count: built.events.len(),
+            batch_hash: hash(built.block.batch_hash),
+            state_root: hash(built.block.state_root),
+            epoch_change: built.epoch_snapshot.map(|snapshot| snapshot.epoch),
+            select_us: built.timings.select.as_micros() as u64,
+            execute_us: built.timings.execute.as_micros() as u64,
+            roots_us: built.timings.roots.as_micros() as u64,
+            total_us: total.as_micros() as u64,
+        })
+    }
+}
+
+/// Save the validator set of epoch 0, made of the external proposers with equal stake,
+/// with the default consensus parameters or a devnet's block gas limit override
+pub fn ensure_genesis_epoch(
+    db: &RoochDB,
+    proposer_keys: &[Vec<u8>],
+    block_gas_limit: Option<u64>,
+) -> Result<()> {
+    if let Some(genesis) = db.get_epoch_snapshot(0)? {
+        if let Some(block_gas_limit) = block_gas_limit
+            && block_gas_limit != genesis.params.block_gas_limit
+        {
+            warn!(
+                "Block gas limit override {} ignored, the chain started with {}, reset the devnet to apply it",
+                block_gas_limit, genesis.params.block_gas_limit
+            );
+        }
+        return Ok(());
+    }
+    let mut params = ConsensusParams::default();
+    params.block_gas_limit = block_gas_limit.unwrap_or(params.block_gas_limit);
+    let validators = proposer_keys
+        .iter()
+        .map(|key| Validator::from_public_key(key.clone(), params.min_validator_stake))
+        .collect::<Result<Vec<_>>>()?;
+    db.start_epoch(&EpochSnapshot::genesis(validators, params))
+}
+
+/// The system transactions filling the reserved slots of a block produced by this node:
+/// a timestamp update in every block, an epoch change and the reward distribution of
+/// the previous epoch in the first block of an epoch and a checkpoint every
+/// `CHECKPOINT_INTERVAL` blocks
+fn system_transactions(
+    db: &RoochDB,
+    block_number: u128,
+    chain_id: u64,
+    timestamp: u64,
+    epoch_snapshot: Option<&EpochSnapshot>,
+) -> Result<Vec<SignedTransaction>> {
+    // Before genesis there is no hash to bind the transactions to
+    let genesis_hash = match db.get_genesis_hash()? {
+        Some(genesis_hash) => genesis_hash,
+        None => return Ok(vec![]),
+    };
+    let mut system = vec![SystemTransaction::timestamp_update(timestamp, block_number)];
+    if let Some(snapshot) = epoch_snapshot {
+        system.push(SystemTransaction::EpochChange {
+            epoch: snapshot.epoch,
+            snapshot_hash: snapshot.hash(),
+        });
+        if let Some(previous) = db.get_epoch_snapshot(snapshot.epoch - 1)? {
+            system.push(SystemTransaction::RewardDistribution {
+                epoch: previous.epoch,
+                payments: db.epoch_rewards(&previous)?,
+            });
+        }
+    }
+    if block_number % CHECKPOINT_INTERVAL == 0 {
+        if let Some(checkpoint) = db.get_block(block_number - 1)? {
+            system.push(SystemTransaction::Checkpoint {
+                block_number: checkpoint.block_number,
+                state_root: checkpoint.state_root,
+            });
+        }
+    }
+    Ok(system
+        .into_iter()
+        .map(|system| system.into_transaction(chain_id, genesis_hash, block_number))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kanari_config::KanariOpt;
+    use kanari_types::kari_coin::KARI;
+    use kanari_types::reward::RewardPayment;
+    use kanari_types::transaction::KanariTransaction;
+    use move_core_types::u256::U256;
+    use moveos_types::state::MoveStructType;
+
+    fn pooled(sender: AccountAddress, sequence_number: u64, gas_limit: u64) -> PooledTransaction {
+        PooledTransaction::new(SignedTransaction {
+            tx: KanariTransaction {
+                sender,
+                sequence_number,
+                chain_id: 1,
+                genesis_hash: H256::zero(),
+                recipient: None,
+                amount: 0,
+                gas_limit,
+                gas_price: 1,
+                data: vec![],
+                access_list: None,
+            },
+            public_key: vec![],
+            signature: vec![],
+        })
+    }
+
+    #[test]
+    fn test_select_transactions_within_gas_limit() {
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        let pending = vec![
+            pooled(alice, 0, 40_000),
+            pooled(bob, 0, 70_000),
+            pooled(alice, 1, 50_000),
+            pooled(bob, 1, 10_000),
+        ];
+
+        // Bob's first transaction does not fit, so his next one is left out as well
+        let selected = select_transactions(&pending, 100_000);
+        let order: Vec<(AccountAddress, u64)> = selected
+            .iter()
+            .map(|tx| (tx.tx.sender, tx.tx.sequence_number))
+            .collect();
+        assert_eq!(order, vec![(alice, 0), (alice, 1)]);
+    }
+
+    /// Save block 1 paying `amount` KARI to `address` through a reward payment
+    fn fund(db: &RoochDB, address: AccountAddress, amount: u128) {
+        let funding = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: address,
+                recipient: address,
+                amount,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        let block =
+            build_block(db, 1, 1, 1_700_000_000, &[PooledTransaction::new(funding)]).unwrap();
+        save_built_block(db, &block, 1_700_000_000, None).unwrap();
+    }
+
+    fn transfer(
+        sender: AccountAddress,
+        sequence_number: u64,
+        recipient: AccountAddress,
+    ) -> PooledTransaction {
+        let mut transfer = pooled(sender, sequence_number, 21_000);
+        transfer.tx.tx.recipient = Some(recipient);
+        transfer.tx.tx.amount = 400;
+        PooledTransaction::new(transfer.tx)
+    }
+
+    #[test]
+    fn test_replayed_transaction_fails_without_effects() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = RoochDB::init(&opt.store, &Registry::new()).unwrap();
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        fund(&db, alice, 1_000_000);
+
+        // The same signed transfer twice in one block, then again in the next one
+        let tx = transfer(alice, 0, bob);
+        let block_2 = build_block(&db, 2, 1, 1_700_000_010, &[tx.clone(), tx.clone()]).unwrap();
+        assert!(block_2.outputs[0].status.is_success());
+        assert!(!block_2.outputs[1].status.is_success());
+        assert_eq!(block_2.outputs[1].gas_used, 0);
+        save_built_block(&db, &block_2, 1_700_000_010, None).unwrap();
+        let block_3 = build_block(&db, 3, 1, 1_700_000_020, &[tx]).unwrap();
+        assert!(!block_3.outputs[0].status.is_success());
+        save_built_block(&db, &block_3, 1_700_000_020, None).unwrap();
+
+        let state = db.state_at(Some(3)).unwrap().unwrap();
+        let balance = |address| {
+            state
+                .get_coin_balance(address, &KARI::struct_tag())
+                .unwrap()
+        };
+        assert_eq!(
+            balance(alice),
+            Some(U256::from(1_000_000u64 - 21_000 - 400))
+        );
+        assert_eq!(balance(bob), Some(U256::from(400u64)));
+        assert_eq!(
+            state.get_account(alice).unwrap().unwrap().sequence_number,
+            1
+        );
+    }
+
+    #[test]
+    fn test_gapped_sequence_number_fails_without_effects() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = RoochDB::init(&opt.store, &Registry::new()).unwrap();
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+        fund(&db, alice, 1_000_000);
+
+        let block_2 = build_block(&db, 2, 1, 1_700_000_010, &[transfer(alice, 1, bob)]).unwrap();
+        assert!(!block_2.outputs[0].status.is_success());
+        assert_eq!(block_2.outputs[0].gas_used, 0);
+        assert!(block_2.events.is_empty());
+        save_built_block(&db, &block_2, 1_700_000_010, None).unwrap();
+
+        let state = db.state_at(Some(2)).unwrap().unwrap();
+        let balance = |address| {
+            state
+                .get_coin_balance(address, &KARI::struct_tag())
+                .unwrap()
+        };
+        assert_eq!(balance(alice), Some(U256::from(1_000_000u64)));
+        assert_eq!(balance(bob), None);
+        assert!(state.get_account(alice).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_production_ticks_on_fresh_db() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &Registry::new()).unwrap());
+
+        let (first, _) = produce_next_block(&db, None, 1, 1_700_000_000, None)
+            .await
+            .unwrap();
+        let (second, _) = produce_next_block(&db, None, 1, 1_700_000_010, None)
+            .await
+            .unwrap();
+        assert_eq!((first, second), (1, 2));
+        assert!(db.get_block(1).unwrap().is_some());
+        assert!(db.get_block(2).unwrap().is_some());
+        assert_eq!(db.get_latest_block_number().unwrap(), Some(2));
+    }
+
+    #[test]
+    fn test_balance_at_parent_block_after_transfer() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = RoochDB::init(&opt.store, &Registry::new()).unwrap();
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let bob = AccountAddress::from_hex_literal("0xb").unwrap();
+
+        // Block 1 funds Alice through a reward payment
+        let funding = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: alice,
+                recipient: alice,
+                amount: 1_000_000,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        let block_1 =
+            build_block(&db, 1, 1, 1_700_000_000, &[PooledTransaction::new(funding)]).unwrap();
+        save_built_block(&db, &block_1, 1_700_000_000, None).unwrap();
+
+        // Block 2 transfers part of it to Bob
+        let mut transfer = pooled(alice, 0, 21_000).tx;
+        transfer.tx.recipient = Some(bob);
+        transfer.tx.amount = 400;
+        let block_2 = build_block(
+            &db,
+            2,
+            1,
+            1_700_000_010,
+            &[PooledTransaction::new(transfer)],
+        )
+        .unwrap();
+        assert!(
+            block_2
+                .outputs
+                .iter()
+                .all(|output| output.status.is_success())
+        );
+        save_built_block(&db, &block_2, 1_700_000_010, None).unwrap();
+        assert_eq!(db.latest_root().unwrap(), Some(block_2.root.clone()));
+        assert_ne!(block_1.block.state_root, block_2.block.state_root);
+
+        let balance = |block_number: u128, address: AccountAddress| {
+            db.state_at(Some(block_number))
+                .unwrap()
+                .unwrap()
+                .get_coin_balance(address, &KARI::struct_tag())
+                .unwrap()
+        };
+        assert_eq!(balance(1, alice), Some(U256::from(1_000_000u64)));
+        assert_eq!(balance(1, bob), None);
+        assert_eq!(
+            balance(2, alice),
+            Some(U256::from(1_000_000u64 - 21_000 - 400))
+        );
+        assert_eq!(balance(2, bob), Some(U256::from(400u64)));
+    }
+}
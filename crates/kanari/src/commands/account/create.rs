@@ -1,8 +1,10 @@
 // Copyright (c) RoochNetwork
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::keystore::open_keystore;
 use async_trait::async_trait;
 use clap::Parser;
+use kanari_config::keystore_config::{KeystoreBackend, KeystoreConfig};
 use rooch::cli_types::{CommandAction, WalletContextOptions};
 use rooch_key::keystore::account_keystore::AccountKeystore;
 use rooch_rpc_api::jsonrpc_types::RoochAddressView;
@@ -19,6 +21,9 @@ pub struct CreateCommand {
     #[clap(flatten)]
     pub context_options: WalletContextOptions,
 
+    #[clap(flatten)]
+    pub keystore: KeystoreConfig,
+
     /// Return command outputs in json format
     #[clap(long, default_value = "false")]
     json: bool,
@@ -27,6 +32,20 @@ pub struct CreateCommand {
 #[async_trait]
 impl CommandAction<Option<RoochAddressView>> for CreateCommand {
     async fn execute(self) -> RoochResult<Option<RoochAddressView>> {
+        if self.keystore.backend != KeystoreBackend::File {
+            let mut keystore = open_keystore(&self.keystore)?;
+            let address = keystore.generate_key(None)?;
+            if self.json {
+                return Ok(Some(address.into()));
+            }
+            println!(
+                "Generated new keypair for address [{}] in the {} keystore",
+                address,
+                keystore.backend()
+            );
+            return Ok(None);
+        }
+
         let mut context = self.context_options.build_require_password()?;
         let password = context.get_password();
         let result = context.keystore.generate_and_add_new_key(password)?;
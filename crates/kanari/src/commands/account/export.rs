@@ -0,0 +1,60 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::keystore::interop::{KeyFormat, encode_key};
+use crate::keystore::open_keystore;
+use async_trait::async_trait;
+use bitcoin::NetworkKind;
+use clap::Parser;
+use kanari_config::keystore_config::{KeystoreBackend, KeystoreConfig};
+use rooch::cli_types::CommandAction;
+use rooch_types::address::RoochAddress;
+use rooch_types::error::RoochResult;
+use serde_json::{Value, json};
+use std::str::FromStr;
+
+/// Export an account key for a Rooch keystore or a Bitcoin wallet. The output is the
+/// unencrypted private key, keep it secret.
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    /// The address whose key is exported
+    #[clap(long)]
+    pub address: String,
+
+    /// Format of the exported private key
+    #[clap(long, value_enum)]
+    pub format: KeyFormat,
+
+    /// Use the testnet WIF prefix
+    #[clap(long)]
+    pub testnet: bool,
+
+    #[clap(flatten)]
+    pub keystore: KeystoreConfig,
+}
+
+#[async_trait]
+impl CommandAction<Value> for ExportCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let address = RoochAddress::from_str(&self.address)?;
+        let keystore = open_keystore(&self.keystore)?;
+        let password = match self.keystore.backend {
+            KeystoreBackend::File => Some(
+                rpassword::prompt_password("Enter the keystore password: ")
+                    .map_err(anyhow::Error::from)?,
+            ),
+            KeystoreBackend::Keychain | KeystoreBackend::Env => None,
+        };
+        let key_pair = keystore.get_key_pair(&address, password)?;
+        let network = if self.testnet {
+            NetworkKind::Test
+        } else {
+            NetworkKind::Main
+        };
+
+        Ok(json!({
+            "address": address.to_string(),
+            "private_key": encode_key(self.format, &key_pair, network)?,
+        }))
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::keystore::interop::{KeyFormat, decode_key};
+use crate::keystore::open_keystore;
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_config::keystore_config::{KeystoreBackend, KeystoreConfig};
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use serde_json::{Value, json};
+
+/// Import a private key exported by a Rooch keystore or a Bitcoin wallet, keeping the
+/// Bitcoin address based identity it has there
+#[derive(Debug, Parser)]
+pub struct ImportCommand {
+    /// Format of the private key
+    #[clap(long, value_enum)]
+    pub format: KeyFormat,
+
+    /// The encoded private key. Prompted for when omitted, so it stays out of the shell
+    /// history
+    #[clap(long)]
+    pub key: Option<String>,
+
+    #[clap(flatten)]
+    pub keystore: KeystoreConfig,
+}
+
+#[async_trait]
+impl CommandAction<Value> for ImportCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let encoded = match self.key {
+            Some(key) => key,
+            None => rpassword::prompt_password("Enter the private key: ")
+                .map_err(anyhow::Error::from)?,
+        };
+        let key_pair = decode_key(self.format, &encoded)?;
+
+        let mut keystore = open_keystore(&self.keystore)?;
+        let password = match self.keystore.backend {
+            KeystoreBackend::File => Some(
+                rpassword::prompt_password("Enter the keystore password: ")
+                    .map_err(anyhow::Error::from)?,
+            ),
+            KeystoreBackend::Keychain | KeystoreBackend::Env => None,
+        };
+        let address = keystore.import_key(key_pair, password)?;
+
+        Ok(json!({
+            "address": address.to_string(),
+            "keystore": keystore.backend().to_string(),
+        }))
+    }
+}
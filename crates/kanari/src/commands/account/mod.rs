@@ -1 +1,19 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Subcommand;
+
 pub mod create;
+pub mod export;
+pub mod import;
+pub mod sign_message;
+pub mod verify_message;
+
+/// Move keys between Kanari and other wallets
+#[derive(Debug, Subcommand)]
+pub enum AccountCommand {
+    /// Import a Rooch or Bitcoin (WIF) private key into the keystore
+    Import(import::ImportCommand),
+    /// Export an account key as a Rooch or Bitcoin (WIF) private key
+    Export(export::ExportCommand),
+}
@@ -0,0 +1,63 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::keystore::open_keystore;
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_config::keystore_config::{KeystoreBackend, KeystoreConfig};
+use kanari_types::personal_message::PersonalMessageSignature;
+use rooch::cli_types::CommandAction;
+use rooch_types::address::RoochAddress;
+use rooch_types::crypto::RoochKeyPair;
+use rooch_types::error::RoochResult;
+use serde_json::{Value, json};
+use std::str::FromStr;
+
+/// Sign an arbitrary message with an account key, e.g. for off-chain login.
+/// The message is domain separated so the signature can never be used as a transaction.
+#[derive(Debug, Parser)]
+pub struct SignMessageCommand {
+    /// The address whose key signs the message
+    #[clap(long)]
+    pub address: String,
+
+    /// The message to sign, as UTF-8 text
+    #[clap(long)]
+    pub message: String,
+
+    #[clap(flatten)]
+    pub keystore: KeystoreConfig,
+}
+
+#[async_trait]
+impl CommandAction<Value> for SignMessageCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let address = RoochAddress::from_str(&self.address)?;
+        let keystore = open_keystore(&self.keystore)?;
+        let password = match self.keystore.backend {
+            KeystoreBackend::File => Some(
+                rpassword::prompt_password("Enter the keystore password: ")
+                    .map_err(anyhow::Error::from)?,
+            ),
+            KeystoreBackend::Keychain | KeystoreBackend::Env => None,
+        };
+
+        let signature = match keystore.get_key_pair(&address, password)? {
+            RoochKeyPair::Secp256k1(key_pair) => {
+                PersonalMessageSignature::sign(self.message.as_bytes(), &key_pair)
+            }
+            _ => {
+                return Err(
+                    anyhow::anyhow!("Only secp256k1 keys can sign personal messages").into(),
+                );
+            }
+        };
+
+        Ok(json!({
+            "address": address.to_string(),
+            "message": self.message,
+            "public_key": format!("0x{}", hex::encode(&signature.public_key)),
+            "signature": format!("0x{}", hex::encode(&signature.signature)),
+        }))
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_types::personal_message::PersonalMessageSignature;
+use rooch::cli_types::CommandAction;
+use rooch_types::address::RoochAddress;
+use rooch_types::error::RoochResult;
+use serde_json::{Value, json};
+use std::str::FromStr;
+
+/// Verify a message signature produced by `sign-message` offline
+#[derive(Debug, Parser)]
+pub struct VerifyMessageCommand {
+    /// The address expected to have signed the message
+    #[clap(long)]
+    pub address: String,
+
+    /// The signed message, as UTF-8 text
+    #[clap(long)]
+    pub message: String,
+
+    /// Hex encoded signature
+    #[clap(long)]
+    pub signature: String,
+
+    /// Hex encoded compressed secp256k1 public key of the signer
+    #[clap(long)]
+    pub public_key: String,
+}
+
+#[async_trait]
+impl CommandAction<Value> for VerifyMessageCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let address = RoochAddress::from_str(&self.address)?;
+        let signature = PersonalMessageSignature {
+            public_key: decode_hex(&self.public_key)?,
+            signature: decode_hex(&self.signature)?,
+        };
+
+        let (valid, error) = match signature.verify(self.message.as_bytes(), &address) {
+            Ok(()) => (true, Value::Null),
+            Err(e) => (false, Value::String(e.to_string())),
+        };
+
+        Ok(json!({
+            "address": address.to_string(),
+            "valid": valid,
+            "error": error,
+        }))
+    }
+}
+
+fn decode_hex(encoded: &str) -> anyhow::Result<Vec<u8>> {
+    let encoded = encoded.trim();
+    Ok(hex::decode(encoded.strip_prefix("0x").unwrap_or(encoded))?)
+}
@@ -0,0 +1,13 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Subcommand;
+
+pub mod restore;
+
+/// Long-term block archive tools
+#[derive(Debug, Subcommand)]
+pub enum ArchiveCommand {
+    /// Hydrate a node database from an S3-compatible block archive
+    Restore(restore::RestoreCommand),
+}
@@ -0,0 +1,74 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::archive::{ArchiveStore, write_block};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_config::KanariOpt;
+use kanari_config::archive_config::ArchiveConfig;
+use kanari_db::RoochDB;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use rooch_types::rooch_network::RoochChainID;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+/// Download archived batches newer than the local chain, verify them against the
+/// manifest hashes and write the blocks, receipts and transactions to the database.
+#[derive(Debug, Parser)]
+pub struct RestoreCommand {
+    #[clap(flatten)]
+    pub archive: ArchiveConfig,
+
+    /// Stop after this block, the last archived block by default
+    #[clap(long)]
+    pub to_block: Option<u128>,
+
+    /// Data dir of the DB to restore into, $HOME/.kanari by default
+    #[clap(long = "data-dir", short = 'd')]
+    pub base_data_dir: Option<PathBuf>,
+
+    #[clap(long, short = 'n')]
+    pub chain_id: Option<RoochChainID>,
+}
+
+#[async_trait]
+impl CommandAction<Value> for RestoreCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        if !self.archive.is_enabled() {
+            return Err(anyhow!("--archive-bucket is required").into());
+        }
+        let opt = KanariOpt::new_with_default(self.base_data_dir, self.chain_id, None)?;
+        let db = RoochDB::init(&opt.store, &prometheus::Registry::new())?;
+        let store = ArchiveStore::new(&self.archive)?;
+        let manifest = store.manifest().await?;
+
+        let local_latest = db.get_latest_block_number()?.unwrap_or(0);
+        let to_block = self.to_block.unwrap_or(u128::MAX);
+        let mut restored_blocks = 0u64;
+        let mut restored_batches = 0u64;
+        for entry in manifest
+            .batches
+            .iter()
+            .filter(|entry| entry.last_block > local_latest && entry.first_block <= to_block)
+        {
+            let blocks = store.download_batch(entry).await?;
+            for archived in blocks.iter().filter(|archived| {
+                archived.block.block_number > local_latest
+                    && archived.block.block_number <= to_block
+            }) {
+                write_block(&db, archived)?;
+                restored_blocks += 1;
+            }
+            restored_batches += 1;
+        }
+
+        Ok(json!({
+            "archived_batches": manifest.batches.len(),
+            "restored_batches": restored_batches,
+            "restored_blocks": restored_blocks,
+            "latest_block": db.get_latest_block_number()?,
+        }))
+    }
+}
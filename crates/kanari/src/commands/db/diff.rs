@@ -0,0 +1,246 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_config::KanariOpt;
+use kanari_db::RoochDB;
+use kanari_db::state_diff::diff_state_roots;
+use move_core_types::account_address::AccountAddress;
+use moveos_types::h256::H256;
+use moveos_types::state::ObjectState;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use rooch_types::rooch_network::RoochChainID;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// One side of a diff: a height in the `--data-dir` DB, or another data dir
+/// (e.g. a snapshot taken before a release) at `path[@height]`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotRef {
+    Height(u128),
+    Snapshot {
+        base_data_dir: PathBuf,
+        height: Option<u128>,
+    },
+}
+
+impl FromStr for SnapshotRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(height) = s.parse::<u128>() {
+            return Ok(SnapshotRef::Height(height));
+        }
+        match s.rsplit_once('@') {
+            Some((path, height)) if height.parse::<u128>().is_ok() => Ok(SnapshotRef::Snapshot {
+                base_data_dir: PathBuf::from(path),
+                height: Some(height.parse()?),
+            }),
+            _ => Ok(SnapshotRef::Snapshot {
+                base_data_dir: PathBuf::from(s),
+                height: None,
+            }),
+        }
+    }
+}
+
+/// Report accounts, balances and objects that differ between two state snapshots
+#[derive(Debug, Parser)]
+pub struct DiffCommand {
+    /// A block height, or a snapshot data dir with an optional height (`path[@height]`)
+    pub snapshot_a: SnapshotRef,
+
+    /// A block height, or a snapshot data dir with an optional height (`path[@height]`)
+    pub snapshot_b: SnapshotRef,
+
+    /// Data dir of the DB that plain heights refer to, $HOME/.kanari by default
+    #[clap(long = "data-dir", short = 'd')]
+    pub base_data_dir: Option<PathBuf>,
+
+    #[clap(long, short = 'n')]
+    pub chain_id: Option<RoochChainID>,
+
+    /// Maximum number of changed objects to list
+    #[clap(long, default_value = "100")]
+    pub limit: usize,
+}
+
+impl DiffCommand {
+    fn open_db(
+        &self,
+        dbs: &mut HashMap<Option<PathBuf>, RoochDB>,
+        base_data_dir: Option<PathBuf>,
+    ) -> Result<RoochDB> {
+        if let Some(db) = dbs.get(&base_data_dir) {
+            return Ok(db.clone());
+        }
+        if let Some(dir) = &base_data_dir {
+            if !dir.exists() {
+                return Err(anyhow!("Data dir {} does not exist", dir.display()));
+            }
+        }
+        let opt = KanariOpt::new_with_default(base_data_dir.clone(), self.chain_id.clone(), None)?;
+        let db = RoochDB::init(&opt.store, &prometheus::Registry::new())?;
+        dbs.insert(base_data_dir, db.clone());
+        Ok(db)
+    }
+
+    /// Open the DB of a snapshot and resolve the state root and height to compare
+    fn resolve(
+        &self,
+        dbs: &mut HashMap<Option<PathBuf>, RoochDB>,
+        snapshot: &SnapshotRef,
+    ) -> Result<(RoochDB, u128, H256)> {
+        let (db, height) = match snapshot {
+            SnapshotRef::Height(height) => (
+                self.open_db(dbs, self.base_data_dir.clone())?,
+                Some(*height),
+            ),
+            SnapshotRef::Snapshot {
+                base_data_dir,
+                height,
+            } => (self.open_db(dbs, Some(base_data_dir.clone()))?, *height),
+        };
+        let height = match height {
+            Some(height) => height,
+            None => db
+                .get_latest_block_number()?
+                .ok_or_else(|| anyhow!("Snapshot {:?} has no blocks", snapshot))?,
+        };
+        let block = db
+            .get_block(height)?
+            .ok_or_else(|| anyhow!("Block #{} not found in {:?}", height, snapshot))?;
+        Ok((db, height, block.state_root))
+    }
+}
+
+fn objects_json<'a>(states: impl Iterator<Item = &'a ObjectState>, limit: usize) -> Vec<Value> {
+    states
+        .take(limit)
+        .map(|state| {
+            json!({
+                "id": state.metadata.id.to_string(),
+                "type": state.metadata.object_type.to_string(),
+                "owner": state.metadata.owner.to_hex_literal(),
+            })
+        })
+        .collect()
+}
+
+fn addresses_json(addresses: &[AccountAddress]) -> Vec<String> {
+    addresses.iter().map(|a| a.to_hex_literal()).collect()
+}
+
+#[async_trait]
+impl CommandAction<Value> for DiffCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let mut dbs = HashMap::new();
+        let (db_a, height_a, root_a) = self.resolve(&mut dbs, &self.snapshot_a)?;
+        let (db_b, height_b, root_b) = self.resolve(&mut dbs, &self.snapshot_b)?;
+        let diff = diff_state_roots(&db_a.moveos_store, root_a, &db_b.moveos_store, root_b)?;
+
+        let balances: Vec<Value> = diff
+            .balance_changes()
+            .iter()
+            .map(|change| {
+                json!({
+                    "owner": change.owner.to_hex_literal(),
+                    "coin_type": change.coin_type,
+                    "before": change.before.map(|b| b.to_string()),
+                    "after": change.after.map(|b| b.to_string()),
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            "a": { "height": height_a, "state_root": root_a },
+            "b": { "height": height_b, "state_root": root_b },
+            "identical": diff.is_empty(),
+            "accounts": {
+                "added": addresses_json(&diff.added_accounts()),
+                "removed": addresses_json(&diff.removed_accounts()),
+            },
+            "balances": balances,
+            "objects": {
+                "added_count": diff.added.len(),
+                "removed_count": diff.removed.len(),
+                "changed_count": diff.changed.len(),
+                "added": objects_json(diff.added.iter(), self.limit),
+                "removed": objects_json(diff.removed.iter(), self.limit),
+                "changed": objects_json(diff.changed.iter().map(|(_, after)| after), self.limit),
+            },
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_production::{build_block, save_built_block};
+    use kanari_mempool::PooledTransaction;
+    use kanari_types::reward::RewardPayment;
+    use kanari_types::system_transaction::SystemTransaction;
+    use move_core_types::u256::U256;
+
+    #[test]
+    fn test_parse_snapshot_ref() {
+        assert_eq!(
+            SnapshotRef::from_str("42").unwrap(),
+            SnapshotRef::Height(42)
+        );
+        assert_eq!(
+            SnapshotRef::from_str("/backup/kanari@7").unwrap(),
+            SnapshotRef::Snapshot {
+                base_data_dir: PathBuf::from("/backup/kanari"),
+                height: Some(7),
+            }
+        );
+        assert_eq!(
+            SnapshotRef::from_str("/backup/node@a").unwrap(),
+            SnapshotRef::Snapshot {
+                base_data_dir: PathBuf::from("/backup/node@a"),
+                height: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_between_heights_reports_balance_changes() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = RoochDB::init(&opt.store, &prometheus::Registry::new()).unwrap();
+        let alice = AccountAddress::from_hex_literal("0xa").unwrap();
+        let reward = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: alice,
+                recipient: alice,
+                amount: 700,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 2);
+        for (block_number, pending) in [(1, vec![]), (2, vec![PooledTransaction::new(reward)])] {
+            let timestamp = 1_700_000_000 + block_number as u64 * 10;
+            let built = build_block(&db, block_number, 1, timestamp, &pending).unwrap();
+            save_built_block(&db, &built, timestamp, None).unwrap();
+        }
+
+        let command = DiffCommand::parse_from(["diff", "1", "2"]);
+        let mut dbs = HashMap::from([(None, db.clone())]);
+        let (db_a, _, root_a) = command.resolve(&mut dbs, &command.snapshot_a).unwrap();
+        let (db_b, _, root_b) = command.resolve(&mut dbs, &command.snapshot_b).unwrap();
+        assert_eq!(root_b, db.get_block(2).unwrap().unwrap().state_root);
+        let diff =
+            diff_state_roots(&db_a.moveos_store, root_a, &db_b.moveos_store, root_b).unwrap();
+        let changes = diff.balance_changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].owner, alice);
+        assert_eq!(changes[0].before, None);
+        assert_eq!(changes[0].after, Some(U256::from(700u64)));
+    }
+}
@@ -0,0 +1,13 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Subcommand;
+
+pub mod diff;
+
+/// Offline database tools
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+    /// Compare the state of two snapshots or two heights
+    Diff(diff::DiffCommand),
+}
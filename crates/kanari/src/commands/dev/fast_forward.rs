@@ -0,0 +1,77 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::devnet_opt;
+use crate::block_production::{BLOCK_INTERVAL_SECS, create_and_save_block};
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_db::RoochDB;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use rooch_types::rooch_network::RoochChainID;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Append empty blocks to a stopped devnet, with timestamps `--block-time` apart, to
+/// reach heights and times such as vesting cliffs and epoch boundaries. The blocks
+/// carry the same system transactions as produced ones, so epochs change and
+/// rewards are paid on the way.
+#[derive(Debug, Parser)]
+pub struct FastForwardCommand {
+    /// Number of blocks to produce
+    #[clap(long)]
+    pub blocks: u64,
+
+    /// Seconds the block timestamp advances per block
+    #[clap(long, default_value_t = BLOCK_INTERVAL_SECS)]
+    pub block_time: u64,
+
+    /// Data dir of the devnet, $HOME/.kanari by default
+    #[clap(long = "data-dir", short = 'd')]
+    pub base_data_dir: Option<PathBuf>,
+
+    #[clap(long, short = 'n')]
+    pub chain_id: Option<RoochChainID>,
+}
+
+#[async_trait]
+impl CommandAction<Value> for FastForwardCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        if self.blocks == 0 {
+            return Err(anyhow!("--blocks must be greater than 0").into());
+        }
+        let opt = devnet_opt(self.base_data_dir.clone(), self.chain_id.clone())?;
+        let chain_id = opt.chain_id().id();
+        // A running node holds the database lock
+        let db = Arc::new(
+            RoochDB::init(&opt.store, &prometheus::Registry::new())
+                .context("Failed to open the database, stop the node before fast-forwarding")?,
+        );
+        // The node sets up genesis and the first epoch from its config
+        let latest = db.get_latest_block_number()?.ok_or_else(|| {
+            anyhow!("The devnet has no blocks yet, start the node once before fast-forwarding")
+        })?;
+
+        // Continue from the last block, or from now if the chain was idle
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut timestamp = db.get_block_timestamp(latest)?.unwrap_or(now).max(now);
+        let to = latest + self.blocks as u128;
+        for block_number in latest + 1..=to {
+            timestamp += self.block_time;
+            create_and_save_block(&db, None, block_number, chain_id, timestamp, None).await?;
+        }
+        Ok(json!({
+            "chain_id": opt.chain_id().to_string(),
+            "from": latest + 1,
+            "to": to,
+            "blocks": self.blocks,
+            "last_timestamp": timestamp,
+        }))
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, bail};
+use clap::Subcommand;
+use kanari_config::KanariOpt;
+use rooch_types::rooch_network::{BuiltinChainID, RoochChainID};
+use std::path::PathBuf;
+
+pub mod fast_forward;
+pub mod reset;
+
+/// Local devnet tools, which refuse to touch any chain but `local` and `dev`
+#[derive(Debug, Subcommand)]
+pub enum DevCommand {
+    /// Wipe the chain state, keeping keys and config
+    Reset(reset::ResetCommand),
+    /// Produce empty blocks instantly to reach a height or time
+    FastForward(fast_forward::FastForwardCommand),
+}
+
+/// Load the config of a devnet data dir
+fn devnet_opt(base_data_dir: Option<PathBuf>, chain_id: Option<RoochChainID>) -> Result<KanariOpt> {
+    let opt = KanariOpt::new_with_default(base_data_dir, chain_id, None)?;
+    let chain_id = opt.chain_id();
+    if !matches!(
+        chain_id,
+        RoochChainID::Builtin(BuiltinChainID::Local | BuiltinChainID::Dev)
+    ) {
+        bail!(
+            "Chain {} is not a devnet, dev commands only run on local and dev chains",
+            chain_id
+        );
+    }
+    Ok(opt)
+}
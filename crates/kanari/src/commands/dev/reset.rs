@@ -0,0 +1,52 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::devnet_opt;
+use anyhow::Context;
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_db::RoochDB;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use rooch_types::rooch_network::RoochChainID;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+/// Delete the blocks, state and pending transactions of a devnet. The keystore and the
+/// config are kept, so the next start begins a fresh chain with the same accounts.
+#[derive(Debug, Parser)]
+pub struct ResetCommand {
+    /// Data dir of the devnet, $HOME/.kanari by default
+    #[clap(long = "data-dir", short = 'd')]
+    pub base_data_dir: Option<PathBuf>,
+
+    #[clap(long, short = 'n')]
+    pub chain_id: Option<RoochChainID>,
+}
+
+#[async_trait]
+impl CommandAction<Value> for ResetCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let opt = devnet_opt(self.base_data_dir, self.chain_id)?;
+        // A running node holds the database lock
+        let db = RoochDB::init(&opt.store, &prometheus::Registry::new())
+            .context("Failed to open the database, stop the node before resetting")?;
+        let height = db.get_latest_block_number()?;
+        drop(db);
+
+        let db_dir = opt.store.get_kanari_db_dir();
+        std::fs::remove_dir_all(&db_dir)
+            .with_context(|| format!("Failed to remove {}", db_dir.display()))?;
+        let mempool_path = opt.mempool_path();
+        let mempool_removed = mempool_path.exists();
+        if mempool_removed {
+            std::fs::remove_file(&mempool_path)?;
+        }
+        Ok(json!({
+            "chain_id": opt.chain_id().to_string(),
+            "removed_height": height,
+            "db_dir": db_dir.display().to_string(),
+            "mempool_removed": mempool_removed,
+        }))
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::RawInput;
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_types::block::Block;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use serde_json::{Value, json};
+
+/// Decode a raw BCS encoded block offline and print it with its computed hash
+#[derive(Debug, Parser)]
+pub struct InspectBlockCommand {
+    #[clap(flatten)]
+    pub input: RawInput,
+}
+
+#[async_trait]
+impl CommandAction<Value> for InspectBlockCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let bytes = self.input.read_bytes()?;
+        let block = Block::decode(&bytes)?;
+
+        Ok(json!({
+            "hash": block.hash(),
+            "size_bytes": bytes.len(),
+            "block_number": block.block_number.to_string(),
+            "batch_size": block.batch_size,
+            "batch_hash": block.batch_hash,
+            "prev_tx_accumulator_root": block.prev_tx_accumulator_root,
+            "tx_accumulator_root": block.tx_accumulator_root,
+            "state_root": block.state_root,
+            // Blocks are not signed by the proposer yet
+            "signature": Value::Null,
+        }))
+    }
+}
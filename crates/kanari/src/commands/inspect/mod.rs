@@ -0,0 +1,48 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+
+pub mod block;
+pub mod tx;
+
+/// Raw bytes to inspect, given inline as hex or read from a file
+#[derive(Debug, Args)]
+pub struct RawInput {
+    /// Hex encoded bytes, with or without the 0x prefix
+    #[clap(long, conflicts_with = "file", required_unless_present = "file")]
+    pub hex: Option<String>,
+
+    /// File containing the raw bytes, or their hex encoding
+    #[clap(long)]
+    pub file: Option<PathBuf>,
+}
+
+impl RawInput {
+    pub fn read_bytes(&self) -> Result<Vec<u8>> {
+        match (&self.hex, &self.file) {
+            (Some(hex), _) => decode_hex(hex),
+            (None, Some(path)) => {
+                let content = std::fs::read(path)?;
+                // Files exported from RPC or logs are usually hex text, DB dumps are raw bytes
+                match std::str::from_utf8(&content) {
+                    Ok(text) if is_hex(text.trim()) => decode_hex(text),
+                    _ => Ok(content),
+                }
+            }
+            (None, None) => anyhow::bail!("Either --hex or --file must be specified"),
+        }
+    }
+}
+
+fn is_hex(text: &str) -> bool {
+    let text = text.strip_prefix("0x").unwrap_or(text);
+    !text.is_empty() && text.len() % 2 == 0 && text.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>> {
+    let text = text.trim();
+    Ok(hex::decode(text.strip_prefix("0x").unwrap_or(text))?)
+}
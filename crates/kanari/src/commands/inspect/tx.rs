@@ -0,0 +1,60 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::RawInput;
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_types::kari_coin::format_kari;
+use kanari_types::system_transaction::SystemTransaction;
+use kanari_types::transaction::SignedTransaction;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use serde_json::{Value, json};
+
+/// Decode a raw BCS encoded signed transaction offline, print its hash and check the signature
+#[derive(Debug, Parser)]
+pub struct InspectTxCommand {
+    #[clap(flatten)]
+    pub input: RawInput,
+}
+
+#[async_trait]
+impl CommandAction<Value> for InspectTxCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let bytes = self.input.read_bytes()?;
+        let signed_tx = SignedTransaction::decode(&bytes)?;
+        let tx = &signed_tx.tx;
+
+        let (signature_valid, signature_error) = match signed_tx.verify_signature() {
+            Ok(()) => (true, Value::Null),
+            Err(e) => (false, Value::String(e.to_string())),
+        };
+        // System transactions are unsigned, their payload is shown decoded instead
+        let system = SystemTransaction::from_transaction(&signed_tx)?;
+
+        Ok(json!({
+            "hash": signed_tx.hash(),
+            "size_bytes": bytes.len(),
+            "sender": tx.sender.to_hex_literal(),
+            "sequence_number": tx.sequence_number,
+            "chain_id": tx.chain_id,
+            "genesis_hash": tx.genesis_hash,
+            "recipient": tx.recipient.map(|r| r.to_hex_literal()),
+            "amount": tx.amount.to_string(),
+            "amount_display": format_kari(tx.amount),
+            "gas_limit": tx.gas_limit,
+            "gas_price": tx.gas_price,
+            "max_fee": tx.max_fee().to_string(),
+            "max_fee_display": format_kari(tx.max_fee()),
+            "data": format!("0x{}", hex::encode(&tx.data)),
+            "access_list": tx.access_list.as_ref().map(|accounts| {
+                accounts.iter().map(|a| a.to_hex_literal()).collect::<Vec<_>>()
+            }),
+            "public_key": format!("0x{}", hex::encode(&signed_tx.public_key)),
+            "signature": format!("0x{}", hex::encode(&signed_tx.signature)),
+            "signature_valid": signature_valid,
+            "signature_error": signature_error,
+            "system": system,
+        }))
+    }
+}
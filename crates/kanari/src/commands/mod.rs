@@ -1 +1,9 @@
 pub mod account;
+pub mod archive;
+pub mod db;
+pub mod dev;
+pub mod inspect;
+pub mod service;
+pub mod state;
+pub mod support_bundle;
+pub mod tx;
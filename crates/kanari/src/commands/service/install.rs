@@ -0,0 +1,191 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{RestartPolicy, ServiceTarget, UnitSpec, ensure_systemd, render_unit};
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use clap::{CommandFactory, Parser};
+use kanari_config::KanariOpt;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Variables outside the node's options the unit keeps from the installing shell
+const PASSED_ENVIRONMENT: &[&str] = &["RUST_LOG"];
+
+/// Generate a unit running `kari start` with the given arguments, resolved against the
+/// data dir they select, then enable it. Only the variables the node reads are copied
+/// from the current environment, into an environment file readable by its owner alone,
+/// so secrets stay out of the unit and the process list.
+#[derive(Debug, Parser)]
+pub struct InstallCommand {
+    #[clap(flatten)]
+    pub target: ServiceTarget,
+
+    /// When systemd restarts the node
+    #[clap(long, value_enum, default_value_t = RestartPolicy::OnFailure)]
+    pub restart: RestartPolicy,
+
+    /// Append the node's output to this file instead of the journal
+    #[clap(long)]
+    pub log_file: Option<PathBuf>,
+
+    /// Account a system unit runs the node as, which must own the data dir
+    #[clap(long)]
+    pub run_as: Option<String>,
+
+    /// Start the node once the unit is enabled
+    #[clap(long)]
+    pub now: bool,
+
+    /// Print the unit without installing it
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// Arguments of `kari start`, after `--`
+    #[clap(last = true)]
+    pub start_args: Vec<String>,
+}
+
+#[async_trait]
+impl CommandAction<Value> for InstallCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        if !self.dry_run {
+            ensure_systemd()?;
+        }
+        if self.target.user && self.run_as.is_some() {
+            return Err(anyhow!("--run-as only applies to system units").into());
+        }
+
+        let mut opt = KanariOpt::try_parse_from(
+            std::iter::once("start".to_string()).chain(self.start_args.iter().cloned()),
+        )
+        .map_err(|e| anyhow!("Invalid start arguments: {}", e))?;
+        let mut start_args = self.start_args.clone();
+        match &opt.base_data_dir {
+            Some(dir) if dir.as_os_str() == "TMP" => {
+                return Err(anyhow!("A service cannot run on a temporary data dir").into());
+            }
+            Some(dir) if dir.is_relative() => {
+                return Err(anyhow!("--data-dir {} must be absolute", dir.display()).into());
+            }
+            _ => {}
+        }
+        opt.init()?;
+        opt.validate().map_err(anyhow::Error::from)?;
+        let base_data_dir = opt.base().base_data_dir().to_path_buf();
+        if opt.base_data_dir.is_none() {
+            // The default data dir depends on the home of the account running the node
+            start_args.push("--data-dir".to_string());
+            start_args.push(base_data_dir.to_string_lossy().into_owned());
+        }
+
+        let program = std::env::current_exe()
+            .and_then(|exe| exe.canonicalize())
+            .context("Failed to locate the kari binary")?;
+        let environment = service_environment()?;
+        let environment_file = base_data_dir.join(format!("{}.env", self.target.name));
+        let spec = UnitSpec {
+            description: format!("Kanari node ({})", opt.chain_id()),
+            exec_start: std::iter::once(program.to_string_lossy().into_owned())
+                .chain(std::iter::once("start".to_string()))
+                .chain(start_args)
+                .collect(),
+            working_directory: base_data_dir.clone(),
+            environment_file: (!environment.is_empty()).then(|| environment_file.clone()),
+            restart: self.restart,
+            log_file: self.log_file.clone(),
+            run_as: self.run_as.clone(),
+            user_unit: self.target.user,
+        };
+        let unit = render_unit(&spec);
+        let unit_path = self.target.unit_path()?;
+        let variables: Vec<&str> = environment.iter().map(|(name, _)| name.as_str()).collect();
+        if self.dry_run {
+            return Ok(json!({
+                "unit_path": unit_path,
+                "unit": unit,
+                "environment": variables,
+            }));
+        }
+
+        if spec.environment_file.is_some() {
+            write_environment_file(&environment_file, &environment)?;
+        }
+        if let Some(dir) = unit_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&unit_path, &unit)
+            .with_context(|| format!("Failed to write {}", unit_path.display()))?;
+        self.target.systemctl(&["daemon-reload"])?;
+        let unit_name = self.target.unit_name();
+        let mut enable = vec!["enable", unit_name.as_str()];
+        if self.now {
+            enable.push("--now");
+        }
+        self.target.systemctl(&enable)?;
+        Ok(json!({
+            "unit": unit_name,
+            "unit_path": unit_path,
+            "data_dir": base_data_dir,
+            "environment": variables,
+            "started": self.now,
+        }))
+    }
+}
+
+/// The variables of the current environment the node reads: those backing its options
+/// and `PASSED_ENVIRONMENT`
+fn service_environment() -> Result<Vec<(String, String)>> {
+    let mut names: BTreeSet<String> = KanariOpt::command()
+        .get_arguments()
+        .filter_map(|arg| arg.get_env())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect();
+    names.extend(PASSED_ENVIRONMENT.iter().map(|name| name.to_string()));
+    let mut environment = vec![];
+    for name in names {
+        if let Ok(value) = std::env::var(&name) {
+            if value.contains('\n') {
+                bail!(
+                    "{} spans several lines, which an environment file cannot hold",
+                    name
+                );
+            }
+            environment.push((name, value));
+        }
+    }
+    Ok(environment)
+}
+
+/// Write `NAME="value"` lines readable by the owner alone
+fn write_environment_file(path: &Path, environment: &[(String, String)]) -> Result<()> {
+    let content: String = environment
+        .iter()
+        .map(|(name, value)| {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("{}=\"{}\"\n", name, value)
+        })
+        .collect();
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    // The mode only applies to new files, restrict an existing one before writing
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{ServiceTarget, ensure_systemd, unit_log_file};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::Parser;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use std::process::Command;
+
+/// Print the last lines the node logged, from the journal or from the log file the
+/// unit appends to, and keep printing new ones with `--follow`
+#[derive(Debug, Parser)]
+pub struct LogsCommand {
+    #[clap(flatten)]
+    pub target: ServiceTarget,
+
+    /// Lines printed before following
+    #[clap(long, short = 'n', default_value_t = 100)]
+    pub lines: usize,
+
+    /// Keep printing lines as the node logs them
+    #[clap(long, short = 'f')]
+    pub follow: bool,
+}
+
+#[async_trait]
+impl CommandAction<()> for LogsCommand {
+    async fn execute(self) -> RoochResult<()> {
+        ensure_systemd()?;
+        let unit_path = self.target.unit_path()?;
+        let unit = std::fs::read_to_string(&unit_path)
+            .map_err(|e| anyhow!("Unit {} is not installed: {}", unit_path.display(), e))?;
+        let lines = self.lines.to_string();
+        let mut command = match unit_log_file(&unit) {
+            Some(log_file) => {
+                let mut command = Command::new("tail");
+                command.args(["-n", lines.as_str()]).arg(log_file);
+                if self.follow {
+                    command.arg("-F");
+                }
+                command
+            }
+            None => {
+                let mut command = self.target.command("journalctl");
+                command
+                    .args(["--unit", self.target.unit_name().as_str()])
+                    .args(["--lines", lines.as_str()])
+                    .arg("--no-pager");
+                if self.follow {
+                    command.arg("--follow");
+                }
+                command
+            }
+        };
+        // The output goes straight to the terminal
+        let status = command.status()?;
+        if !status.success() {
+            return Err(anyhow!("Reading the logs failed with {}", status).into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow, bail};
+use clap::{Args, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub mod install;
+pub mod logs;
+pub mod status;
+
+/// Unit name used when `--name` is not given
+pub const DEFAULT_SERVICE_NAME: &str = "kanari";
+
+/// Run the node as a systemd service
+#[derive(Debug, Subcommand)]
+pub enum ServiceCommand {
+    /// Generate, enable and optionally start a unit running `kari start`
+    Install(install::InstallCommand),
+    /// Show the state of the unit
+    Status(status::StatusCommand),
+    /// Print the logs of the unit, from the journal or its log file
+    Logs(logs::LogsCommand),
+}
+
+/// The unit a service command manages
+#[derive(Debug, Args)]
+pub struct ServiceTarget {
+    /// Name of the unit, without `.service`
+    #[clap(long, default_value = DEFAULT_SERVICE_NAME)]
+    pub name: String,
+
+    /// Manage a unit of the current user's service manager instead of a system unit
+    #[clap(long)]
+    pub user: bool,
+}
+
+impl ServiceTarget {
+    pub fn unit_name(&self) -> String {
+        format!("{}.service", self.name)
+    }
+
+    /// Where the unit file is installed
+    pub fn unit_path(&self) -> Result<PathBuf> {
+        let dir = if self.user {
+            let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+                Some(dir) => PathBuf::from(dir),
+                None => std::env::var_os("HOME")
+                    .map(|home| PathBuf::from(home).join(".config"))
+                    .ok_or_else(|| anyhow!("Neither XDG_CONFIG_HOME nor HOME is set"))?,
+            };
+            config_dir.join("systemd").join("user")
+        } else {
+            PathBuf::from("/etc/systemd/system")
+        };
+        Ok(dir.join(self.unit_name()))
+    }
+
+    /// `systemctl` or `journalctl` addressing the service manager of the unit
+    pub fn command(&self, program: &str) -> Command {
+        let mut command = Command::new(program);
+        if self.user {
+            command.arg("--user");
+        }
+        command
+    }
+
+    /// Run `systemctl` with `args`, failing with its error output
+    pub fn systemctl(&self, args: &[&str]) -> Result<String> {
+        let output = self.command("systemctl").args(args).output()?;
+        if !output.status.success() {
+            bail!(
+                "systemctl {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Only systemd is managed for now
+pub fn ensure_systemd() -> Result<()> {
+    if !cfg!(target_os = "linux") {
+        bail!(
+            "kari service manages systemd units, other service managers are not supported yet: \
+             run `kari start` under a service wrapper such as WinSW"
+        );
+    }
+    if !Path::new("/run/systemd/system").exists() {
+        bail!("systemd is not running on this host");
+    }
+    Ok(())
+}
+
+/// When systemd restarts the node after it exits
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RestartPolicy {
+    Always,
+    OnFailure,
+    No,
+}
+
+impl RestartPolicy {
+    fn as_systemd(&self) -> &'static str {
+        match self {
+            RestartPolicy::Always => "always",
+            RestartPolicy::OnFailure => "on-failure",
+            RestartPolicy::No => "no",
+        }
+    }
+}
+
+/// What a generated unit runs and how
+#[derive(Debug)]
+pub struct UnitSpec {
+    pub description: String,
+    /// The program and its arguments
+    pub exec_start: Vec<String>,
+    pub working_directory: PathBuf,
+    /// File holding the environment of the node, None for an empty environment
+    pub environment_file: Option<PathBuf>,
+    pub restart: RestartPolicy,
+    /// File stdout and stderr are appended to, None for the journal
+    pub log_file: Option<PathBuf>,
+    /// Account a system unit runs as, None for root or the user of a user unit
+    pub run_as: Option<String>,
+    pub user_unit: bool,
+}
+
+/// Quote a word of a unit file command line, escaping what systemd would expand
+pub fn systemd_quote(word: &str) -> String {
+    let escaped = word
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$");
+    format!("\"{}\"", escaped)
+}
+
+/// The unit file of a spec
+pub fn render_unit(spec: &UnitSpec) -> String {
+    let mut unit = format!(
+        "[Unit]\n\
+         Description={}\n\
+         After=network-online.target\n\
+         Wants=network-online.target\n\
+         StartLimitIntervalSec=300\n\
+         StartLimitBurst=5\n\
+         \n\
+         [Service]\n\
+         Type=simple\n",
+        spec.description
+    );
+    let exec_start: Vec<String> = spec
+        .exec_start
+        .iter()
+        .map(|word| systemd_quote(word))
+        .collect();
+    unit.push_str(&format!("ExecStart={}\n", exec_start.join(" ")));
+    unit.push_str(&format!(
+        "WorkingDirectory={}\n",
+        systemd_quote(&spec.working_directory.to_string_lossy())
+    ));
+    if let Some(run_as) = &spec.run_as {
+        unit.push_str(&format!("User={}\n", run_as));
+    }
+    if let Some(environment_file) = &spec.environment_file {
+        unit.push_str(&format!(
+            "EnvironmentFile={}\n",
+            environment_file.to_string_lossy()
+        ));
+    }
+    unit.push_str(&format!(
+        "Restart={}\nRestartSec=5\n",
+        spec.restart.as_systemd()
+    ));
+    // The node flushes its state on SIGTERM, give it time before SIGKILL
+    unit.push_str("KillSignal=SIGTERM\nTimeoutStopSec=60\nLimitNOFILE=65536\n");
+    match &spec.log_file {
+        Some(log_file) => {
+            let target = format!("append:{}", log_file.to_string_lossy());
+            unit.push_str(&format!(
+                "StandardOutput={}\nStandardError={}\n",
+                target, target
+            ));
+        }
+        None => unit.push_str("StandardOutput=journal\nStandardError=journal\n"),
+    }
+    let wanted_by = if spec.user_unit {
+        "default.target"
+    } else {
+        "multi-user.target"
+    };
+    unit.push_str(&format!("\n[Install]\nWantedBy={}\n", wanted_by));
+    unit
+}
+
+/// The file a unit appends its output to, None if it logs to the journal
+pub fn unit_log_file(unit: &str) -> Option<PathBuf> {
+    unit.lines()
+        .find_map(|line| line.trim().strip_prefix("StandardOutput=append:"))
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_unit_quotes_arguments_and_routes_logs() {
+        let spec = UnitSpec {
+            description: "Kanari node".to_string(),
+            exec_start: vec![
+                "/usr/local/bin/kari".to_string(),
+                "start".to_string(),
+                "--data-dir".to_string(),
+                "/var/lib/kanari 1".to_string(),
+                "--rpc-cors-origin".to_string(),
+                "https://example.com/$path%".to_string(),
+            ],
+            working_directory: PathBuf::from("/var/lib/kanari 1"),
+            environment_file: Some(PathBuf::from("/var/lib/kanari 1/kanari.env")),
+            restart: RestartPolicy::OnFailure,
+            log_file: Some(PathBuf::from("/var/log/kanari.log")),
+            run_as: Some("kanari".to_string()),
+            user_unit: false,
+        };
+        let unit = render_unit(&spec);
+        assert!(unit.contains(
+            "ExecStart=\"/usr/local/bin/kari\" \"start\" \"--data-dir\" \"/var/lib/kanari 1\" \
+             \"--rpc-cors-origin\" \"https://example.com/$$path%%\"\n"
+        ));
+        assert!(unit.contains("User=kanari\n"));
+        assert!(unit.contains("Restart=on-failure\n"));
+        assert!(unit.contains("WantedBy=multi-user.target\n"));
+        assert_eq!(
+            unit_log_file(&unit),
+            Some(PathBuf::from("/var/log/kanari.log"))
+        );
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{ServiceTarget, ensure_systemd, unit_log_file};
+use async_trait::async_trait;
+use clap::Parser;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use serde_json::{Map, Value, json};
+
+/// Unit properties reported by `kari service status`
+const STATUS_PROPERTIES: &[&str] = &[
+    "LoadState",
+    "ActiveState",
+    "SubState",
+    "UnitFileState",
+    "MainPID",
+    "NRestarts",
+    "ExecMainStartTimestamp",
+    "Result",
+];
+
+/// Whether the unit is installed, enabled and running, and how often it restarted
+#[derive(Debug, Parser)]
+pub struct StatusCommand {
+    #[clap(flatten)]
+    pub target: ServiceTarget,
+}
+
+#[async_trait]
+impl CommandAction<Value> for StatusCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        ensure_systemd()?;
+        let unit_path = self.target.unit_path()?;
+        let unit_name = self.target.unit_name();
+        let properties = self.target.systemctl(&[
+            "show",
+            unit_name.as_str(),
+            &format!("--property={}", STATUS_PROPERTIES.join(",")),
+        ])?;
+        let properties: Map<String, Value> = properties
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(name, value)| (name.to_string(), Value::String(value.to_string())))
+            .collect();
+        let log_file = std::fs::read_to_string(&unit_path)
+            .ok()
+            .and_then(|unit| unit_log_file(&unit));
+        Ok(json!({
+            "unit": unit_name,
+            "unit_path": unit_path,
+            "installed": unit_path.exists(),
+            "log_file": log_file,
+            "properties": properties,
+        }))
+    }
+}
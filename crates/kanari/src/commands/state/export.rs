@@ -0,0 +1,258 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use clap::{Parser, ValueEnum};
+use kanari_config::KanariOpt;
+use kanari_db::RoochDB;
+use kanari_db::state_diff::{coin_balance, for_each_field};
+use move_core_types::u256::U256;
+use move_resource_viewer::MoveValueAnnotator;
+use moveos_store::MoveOSStore;
+use moveos_types::moveos_std::account::Account;
+use moveos_types::moveos_std::object::ObjectMeta;
+use moveos_types::state::{MoveStructType, ObjectState};
+use moveos_types::state_resolver::RootObjectResolver;
+use rooch::cli_types::CommandAction;
+use rooch_rpc_api::jsonrpc_types::AnnotatedMoveStructView;
+use rooch_types::error::RoochResult;
+use rooch_types::rooch_network::RoochChainID;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// A single JSON document
+    Json,
+    /// One JSON object per line, followed by a summary line
+    Jsonl,
+}
+
+/// Write a deterministic, human-readable dump of the state at a height. Objects are
+/// written in state key order as they are read, so memory use does not grow with the state.
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    /// The block height to export, the latest block by default
+    #[clap(long)]
+    pub at_block: Option<u128>,
+
+    #[clap(long, value_enum, default_value = "json")]
+    pub format: ExportFormat,
+
+    /// The file the dump is written to
+    #[clap(long, short = 'o')]
+    pub output: PathBuf,
+
+    /// Data dir of the DB to export, $HOME/.kanari by default
+    #[clap(long = "data-dir", short = 'd')]
+    pub base_data_dir: Option<PathBuf>,
+
+    #[clap(long, short = 'n')]
+    pub chain_id: Option<RoochChainID>,
+}
+
+type Annotator<'a> = MoveValueAnnotator<'a, RootObjectResolver<'a, MoveOSStore>>;
+
+/// Totals reported after the dump, for supply verification
+#[derive(Default)]
+struct ExportSummary {
+    objects: u64,
+    accounts: u64,
+    undecodable: u64,
+    supply: BTreeMap<String, U256>,
+}
+
+impl ExportSummary {
+    fn to_json(&self) -> Value {
+        let supply: BTreeMap<&String, String> = self
+            .supply
+            .iter()
+            .map(|(coin_type, total)| (coin_type, total.to_string()))
+            .collect();
+        json!({
+            "objects": self.objects,
+            "accounts": self.accounts,
+            "undecodable": self.undecodable,
+            "supply": supply,
+        })
+    }
+}
+
+/// Decode an object value with its Move layout, falling back to the raw bytes
+fn decode_state(annotator: &Annotator<'_>, state: &ObjectState) -> (Value, bool) {
+    match state
+        .clone()
+        .into_annotated_state(annotator)
+        .map_err(|e| e.to_string())
+        .and_then(|annotated| {
+            serde_json::to_value(AnnotatedMoveStructView::from(annotated.decoded_value))
+                .map_err(|e| e.to_string())
+        }) {
+        Ok(value) => (value, true),
+        Err(e) => (
+            json!({ "raw": format!("0x{}", hex::encode(&state.value)), "decode_error": e }),
+            false,
+        ),
+    }
+}
+
+fn object_json(
+    annotator: &Annotator<'_>,
+    state: &ObjectState,
+    summary: &mut ExportSummary,
+) -> Value {
+    let (value, decoded) = decode_state(annotator, state);
+    if !decoded {
+        summary.undecodable += 1;
+    }
+    json!({
+        "id": state.metadata.id.to_string(),
+        "type": state.metadata.object_type.to_string(),
+        "owner": state.metadata.owner.to_hex_literal(),
+        "size": state.metadata.size,
+        "value": value,
+    })
+}
+
+impl ExportCommand {
+    fn export(&self, db: &RoochDB, height: u128, root: ObjectMeta) -> Result<ExportSummary> {
+        let store = &db.moveos_store;
+        let state_root = root.state_root();
+        let resolver = RootObjectResolver::new(root, store);
+        let annotator = MoveValueAnnotator::new(&resolver);
+        let mut out = BufWriter::new(File::create(&self.output)?);
+        let mut summary = ExportSummary::default();
+
+        if self.format == ExportFormat::Json {
+            write!(
+                out,
+                "{{\"height\":{},\"state_root\":{},\"objects\":[",
+                height,
+                serde_json::to_string(&state_root)?
+            )?;
+        }
+        for_each_field(store, state_root, |_, state| {
+            let mut record = object_json(&annotator, &state, &mut summary);
+            if state.metadata.object_type == Account::type_tag() {
+                summary.accounts += 1;
+                // Account resources are stored as fields of the account object
+                let mut resources = vec![];
+                if let Some(account_root) = state.metadata.state_root {
+                    for_each_field(store, account_root, |_, resource| {
+                        resources.push(object_json(&annotator, &resource, &mut summary));
+                        Ok(())
+                    })?;
+                }
+                record["resources"] = Value::Array(resources);
+            }
+            if let Some((_, coin_type, balance)) = coin_balance(&state) {
+                let total = summary.supply.entry(coin_type).or_insert_with(U256::zero);
+                *total = *total + balance;
+            }
+
+            if self.format == ExportFormat::Json && summary.objects > 0 {
+                write!(out, ",")?;
+            }
+            serde_json::to_writer(&mut out, &record)?;
+            if self.format == ExportFormat::Jsonl {
+                writeln!(out)?;
+            }
+            summary.objects += 1;
+            Ok(())
+        })?;
+
+        match self.format {
+            ExportFormat::Json => writeln!(out, "],\"summary\":{}}}", summary.to_json())?,
+            ExportFormat::Jsonl => writeln!(out, "{}", json!({ "summary": summary.to_json() }))?,
+        }
+        out.flush()?;
+        Ok(summary)
+    }
+}
+
+#[async_trait]
+impl CommandAction<Value> for ExportCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let opt =
+            KanariOpt::new_with_default(self.base_data_dir.clone(), self.chain_id.clone(), None)?;
+        let db = RoochDB::init(&opt.store, &prometheus::Registry::new())?;
+        let height = match self.at_block {
+            Some(height) => height,
+            None => db
+                .get_latest_block_number()?
+                .ok_or_else(|| anyhow!("The database has no blocks"))?,
+        };
+        // The state the block left, as recorded by executing it
+        let root = db
+            .state_at(Some(height))?
+            .and_then(|state| state.root().cloned())
+            .ok_or_else(|| anyhow!("Block #{} not found", height))?;
+        let state_root = root.state_root();
+
+        let summary = self.export(&db, height, root)?;
+        let mut result = summary.to_json();
+        result["height"] = json!(height);
+        result["state_root"] = json!(state_root);
+        result["output"] = json!(self.output.display().to_string());
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_production::{build_block, save_built_block};
+    use kanari_mempool::PooledTransaction;
+    use kanari_types::kari_coin::KARI;
+    use kanari_types::reward::RewardPayment;
+    use kanari_types::system_transaction::SystemTransaction;
+    use move_core_types::account_address::AccountAddress;
+    use moveos_types::h256::H256;
+
+    #[test]
+    fn test_export_at_block_holds_its_state() {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = RoochDB::init(&opt.store, &prometheus::Registry::new()).unwrap();
+        let reward = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: AccountAddress::ONE,
+                recipient: AccountAddress::ONE,
+                amount: 900,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 1);
+        let built =
+            build_block(&db, 1, 1, 1_700_000_000, &[PooledTransaction::new(reward)]).unwrap();
+        save_built_block(&db, &built, 1_700_000_000, None).unwrap();
+
+        let command = ExportCommand::parse_from([
+            "export",
+            "--at-block",
+            "1",
+            "--format",
+            "jsonl",
+            "--output",
+            std::env::temp_dir()
+                .join(format!("kanari-export-{}.jsonl", std::process::id()))
+                .to_str()
+                .unwrap(),
+        ]);
+        let summary = command
+            .export(&db, 1, db.get_block_root(1).unwrap().unwrap())
+            .unwrap();
+        std::fs::remove_file(&command.output).unwrap();
+        assert_eq!(summary.objects, 1);
+        assert_eq!(
+            summary
+                .supply
+                .get(&KARI::struct_tag().to_canonical_string()),
+            Some(&U256::from(900u64))
+        );
+    }
+}
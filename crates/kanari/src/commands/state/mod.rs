@@ -0,0 +1,13 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Subcommand;
+
+pub mod export;
+
+/// Offline state tools
+#[derive(Debug, Subcommand)]
+pub enum StateCommand {
+    /// Dump all account states and Move resources at a height for audits
+    Export(export::ExportCommand),
+}
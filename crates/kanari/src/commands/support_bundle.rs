@@ -0,0 +1,98 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::logging::RECENT_LOG_LINES;
+use crate::post_mortem::PostMortemBundle;
+use anyhow::Result;
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_config::KanariOpt;
+use kanari_rpc_api::jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use kanari_rpc_api::{AdminRpcApiClient, KanariRpcApiClient};
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Write the post-mortem bundle a crashing node writes, on demand for a bug report:
+/// the node's configuration with secrets redacted, its state, latest block, peers and
+/// metrics as served by its RPC, and the end of its log file
+#[derive(Debug, Parser)]
+pub struct SupportBundleCommand {
+    /// The configuration the node is started with
+    #[clap(flatten)]
+    pub config: KanariOpt,
+
+    /// RPC endpoint of the node, its local `--port` by default
+    #[clap(long)]
+    pub rpc_url: Option<String>,
+
+    /// File the node's logs are written to, e.g. by its service manager
+    #[clap(long)]
+    pub log_file: Option<PathBuf>,
+
+    /// Log lines taken from the end of `--log-file`
+    #[clap(long, default_value_t = RECENT_LOG_LINES)]
+    pub log_lines: usize,
+}
+
+#[async_trait]
+impl CommandAction<Value> for SupportBundleCommand {
+    async fn execute(mut self) -> RoochResult<Value> {
+        self.config.init()?;
+        let rpc_url = self
+            .rpc_url
+            .clone()
+            .unwrap_or_else(|| format!("http://127.0.0.1:{}", self.config.port()));
+        let mut bundle = PostMortemBundle::new("on demand".to_string(), &self.config);
+
+        // A node that is down still gets a bundle, with what is readable offline
+        let client = HttpClientBuilder::default()
+            .build(&rpc_url)
+            .map_err(anyhow::Error::from)?;
+        if let Err(e) = collect_from_node(&client, &mut bundle).await {
+            warn!("Node at {} did not answer: {}", rpc_url, e);
+        }
+        if let Some(metrics_port) = self.config.metrics_port {
+            let url = format!("http://127.0.0.1:{}/metrics", metrics_port);
+            match fetch_metrics(&url).await {
+                Ok(metrics) => bundle.metrics = Some(metrics),
+                Err(e) => warn!("Failed to read metrics from {}: {}", url, e),
+            }
+        }
+        if let Some(log_file) = &self.log_file {
+            bundle.logs = tail_lines(log_file, self.log_lines)?;
+        }
+
+        let path = bundle.write(self.config.base().data_dir())?;
+        Ok(json!({
+            "path": path,
+            "node_reachable": bundle.node.is_some(),
+            "peers": bundle.peers.len(),
+            "log_lines": bundle.logs.len(),
+        }))
+    }
+}
+
+async fn collect_from_node(client: &HttpClient, bundle: &mut PostMortemBundle) -> Result<()> {
+    bundle.node = Some(client.get_node_info().await?);
+    bundle.latest_block = client.get_latest_block().await.ok();
+    // Nodes may be built without the admin namespace
+    bundle.peers = client.get_peers().await.unwrap_or_default();
+    Ok(())
+}
+
+async fn fetch_metrics(url: &str) -> Result<String> {
+    Ok(reqwest::get(url).await?.error_for_status()?.text().await?)
+}
+
+/// The last `count` lines of a file
+fn tail_lines(path: &Path, count: usize) -> Result<Vec<String>> {
+    let text = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = text.lines().collect();
+    Ok(lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect())
+}
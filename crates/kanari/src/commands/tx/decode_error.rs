@@ -0,0 +1,253 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use clap::Parser;
+use framework_release::error_descriptions::ERROR_DESCRIPTIONS;
+use kanari_rpc_api::describe_rpc_error_code;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use serde_json::{Value, json};
+use std::path::Path;
+
+/// Categories of the Move `std::error` convention, where an abort code is `category << 16 | reason`
+const MOVE_ERROR_CATEGORIES: &[(u64, &str)] = &[
+    (0x1, "INVALID_ARGUMENT"),
+    (0x2, "OUT_OF_RANGE"),
+    (0x3, "INVALID_STATE"),
+    (0x4, "UNAUTHENTICATED"),
+    (0x5, "PERMISSION_DENIED"),
+    (0x6, "NOT_FOUND"),
+    (0x7, "ABORTED"),
+    (0x8, "ALREADY_EXISTS"),
+    (0x9, "RESOURCE_EXHAUSTED"),
+    (0xA, "CANCELLED"),
+    (0xB, "INTERNAL"),
+    (0xC, "NOT_IMPLEMENTED"),
+    (0xD, "UNAVAILABLE"),
+];
+
+/// A failure code extracted from the command line or a receipt
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FailureCode {
+    /// A Move abort code, optionally with the module that aborted
+    MoveAbort { code: u64, module: Option<String> },
+    /// A JSON-RPC error code returned by the node
+    Rpc { code: i32, message: Option<String> },
+}
+
+/// Explain a failed transaction's abort or status code without reading the framework source
+#[derive(Debug, Parser)]
+pub struct DecodeErrorCommand {
+    /// A numeric code (decimal or 0x hex, negative for node RPC errors), or a JSON
+    /// receipt or RPC error response containing one
+    #[clap(allow_negative_numbers = true)]
+    pub code_or_receipt: String,
+
+    /// The module that aborted, e.g. `0x6::block`, to disambiguate abort codes
+    #[clap(long)]
+    pub module: Option<String>,
+}
+
+#[async_trait]
+impl CommandAction<Value> for DecodeErrorCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let mut failure = parse_failure(&self.code_or_receipt)?;
+        if let (FailureCode::MoveAbort { module, .. }, Some(explicit)) = (&mut failure, self.module)
+        {
+            *module = Some(explicit);
+        }
+
+        Ok(match failure {
+            FailureCode::MoveAbort { code, module } => explain_abort(code, module.as_deref()),
+            FailureCode::Rpc { code, message } => {
+                let (name, explanation) = describe_rpc_error_code(code)
+                    .map(|(name, explanation)| (Value::from(name), Value::from(explanation)))
+                    .unwrap_or((Value::Null, Value::Null));
+                json!({
+                    "kind": "rpc_error",
+                    "code": code,
+                    "code_name": name,
+                    "explanation": explanation,
+                    "message": message,
+                })
+            }
+        })
+    }
+}
+
+fn parse_code(text: &str) -> Option<u64> {
+    let text = text.trim();
+    match text.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+fn parse_failure(input: &str) -> Result<FailureCode> {
+    if let Some(code) = parse_code(input) {
+        return Ok(FailureCode::MoveAbort { code, module: None });
+    }
+    if let Ok(code) = input.trim().parse::<i32>() {
+        return Ok(FailureCode::Rpc {
+            code,
+            message: None,
+        });
+    }
+
+    let content = std::fs::read_to_string(Path::new(input))
+        .map_err(|e| anyhow!("{} is neither a code nor a readable receipt: {}", input, e))?;
+    let receipt: Value = serde_json::from_str(&content)?;
+    failure_from_json(&receipt)
+        .ok_or_else(|| anyhow!("No abort code or RPC error code found in {}", input))
+}
+
+/// Find an abort code (`abort_code` with an optional `location`) or a JSON-RPC
+/// `error.code` anywhere in a receipt
+fn failure_from_json(value: &Value) -> Option<FailureCode> {
+    let object = value.as_object()?;
+    if let Some(abort_code) = object.get("abort_code") {
+        let code = match abort_code {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) => parse_code(s),
+            _ => None,
+        }?;
+        let module = object
+            .get("location")
+            .and_then(|l| l.as_str())
+            .map(String::from);
+        return Some(FailureCode::MoveAbort { code, module });
+    }
+    if let Some(error) = object.get("error").and_then(|e| e.as_object()) {
+        if let Some(code) = error.get("code").and_then(|c| c.as_i64()) {
+            return Some(FailureCode::Rpc {
+                code: code as i32,
+                message: error
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .map(String::from),
+            });
+        }
+    }
+    object.values().find_map(failure_from_json)
+}
+
+/// `0x0006::Block` and `0x6::block` name the same module
+fn normalize_module(module: &str) -> String {
+    match module.split_once("::") {
+        Some((address, name)) => {
+            let address = address.trim_start_matches("0x").trim_start_matches('0');
+            let address = if address.is_empty() { "0" } else { address };
+            format!("0x{}::{}", address, name).to_lowercase()
+        }
+        None => module.to_lowercase(),
+    }
+}
+
+fn explain_abort(code: u64, module: Option<&str>) -> Value {
+    let (category, reason) = (code >> 16, code & 0xFFFF);
+    let category_name = MOVE_ERROR_CATEGORIES
+        .iter()
+        .find(|(c, _)| *c == category)
+        .map(|(_, name)| *name);
+
+    let mut matches = vec![];
+    for mapping in ERROR_DESCRIPTIONS.values() {
+        for (module_name, errors) in &mapping.module_error_maps {
+            if let Some(module) = module {
+                if normalize_module(module_name) != normalize_module(module) {
+                    continue;
+                }
+            }
+            // Codes are either raw error constants or `category << 16 | reason`
+            let description = errors
+                .get(&code)
+                .or_else(|| category_name.and_then(|_| errors.get(&reason)));
+            if let Some(description) = description {
+                matches.push(json!({
+                    "module": module_name,
+                    "code_name": description.code_name,
+                    "explanation": description.code_description,
+                }));
+            }
+        }
+    }
+
+    json!({
+        "kind": "move_abort",
+        "code": code,
+        "category": category_name,
+        "reason": category_name.map(|_| reason),
+        "module": module,
+        // Several modules may share a code; pass --module to narrow it down
+        "matches": matches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_failure_codes_and_receipts() {
+        assert_eq!(
+            parse_failure("0x10001").unwrap(),
+            FailureCode::MoveAbort {
+                code: 0x10001,
+                module: None
+            }
+        );
+        assert_eq!(
+            parse_failure("-32001").unwrap(),
+            FailureCode::Rpc {
+                code: -32001,
+                message: None
+            }
+        );
+
+        let receipt = json!({
+            "execution_info": {
+                "status": { "type": "moveabort", "location": "0x6::block", "abort_code": "1" }
+            }
+        });
+        assert_eq!(
+            failure_from_json(&receipt),
+            Some(FailureCode::MoveAbort {
+                code: 1,
+                module: Some("0x6::block".to_string())
+            })
+        );
+        let rpc_error = json!({
+            "jsonrpc": "2.0",
+            "error": { "code": -32002, "message": "Block not found: #9" }
+        });
+        assert!(matches!(
+            failure_from_json(&rpc_error),
+            Some(FailureCode::Rpc { code: -32002, .. })
+        ));
+
+        assert_eq!(normalize_module("0x0006::Block"), "0x6::block");
+    }
+
+    #[test]
+    fn test_negative_rpc_code_is_not_a_flag() {
+        let command = DecodeErrorCommand::try_parse_from(["decode-error", "-32002"]).unwrap();
+        assert_eq!(command.code_or_receipt, "-32002");
+        assert!(
+            DecodeErrorCommand::try_parse_from([
+                "decode-error",
+                "-32002",
+                "--module",
+                "0x6::block"
+            ])
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_explain_framework_abort() {
+        let explained = explain_abort(1, Some("0x6::block"));
+        assert_eq!(explained["matches"][0]["code_name"], "ErrorAlreadyExists");
+    }
+}
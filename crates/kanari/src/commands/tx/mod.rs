@@ -0,0 +1,16 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Subcommand;
+
+pub mod decode_error;
+pub mod send;
+
+/// Transaction commands
+#[derive(Debug, Subcommand)]
+pub enum TxCommand {
+    /// Broadcast a signed transaction after checking it targets the connected network
+    Send(send::SendCommand),
+    /// Explain an on-chain abort code or node error code
+    DecodeError(decode_error::DecodeErrorCommand),
+}
@@ -0,0 +1,78 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::commands::inspect::RawInput;
+use async_trait::async_trait;
+use clap::Parser;
+use kanari_rpc_api::KanariRpcApiClient;
+use kanari_rpc_api::jsonrpsee::http_client::HttpClientBuilder;
+use kanari_types::transaction::SignedTransaction;
+use moveos_types::h256::H256;
+use rooch::cli_types::CommandAction;
+use rooch_types::error::RoochResult;
+use serde_json::{Value, json};
+use std::str::FromStr;
+use tracing::warn;
+
+/// Broadcast a raw signed transaction, refusing to send it to a network other than
+/// the one it was built for
+#[derive(Debug, Parser)]
+pub struct SendCommand {
+    #[clap(flatten)]
+    pub input: RawInput,
+
+    /// RPC endpoint of the node to broadcast to
+    #[clap(long, default_value = "http://127.0.0.1:6767")]
+    pub rpc_url: String,
+
+    /// Broadcast even if the transaction's chain id or genesis hash differs from the node's
+    #[clap(long)]
+    pub allow_network_mismatch: bool,
+}
+
+#[async_trait]
+impl CommandAction<Value> for SendCommand {
+    async fn execute(self) -> RoochResult<Value> {
+        let bytes = self.input.read_bytes()?;
+        let signed_tx = SignedTransaction::decode(&bytes)?;
+        let client = HttpClientBuilder::default()
+            .build(&self.rpc_url)
+            .map_err(anyhow::Error::from)?;
+
+        let node_info = client.get_node_info().await.map_err(anyhow::Error::from)?;
+        let network_check = match &node_info.genesis_hash {
+            Some(genesis_hash) => {
+                let genesis_hash = H256::from_str(genesis_hash)?;
+                signed_tx
+                    .tx
+                    .check_network(node_info.chain_id, &genesis_hash)
+            }
+            None => Err(anyhow::anyhow!(
+                "Node at {} has no genesis block yet",
+                self.rpc_url
+            )),
+        };
+        if let Err(e) = &network_check {
+            if !self.allow_network_mismatch {
+                return Err(anyhow::anyhow!(
+                    "{}. Pass --allow-network-mismatch to broadcast anyway",
+                    e
+                )
+                .into());
+            }
+            warn!("Broadcasting despite network mismatch: {}", e);
+        }
+
+        let tx_hash = client
+            .send_raw_transaction(format!("0x{}", hex::encode(&bytes)))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        Ok(json!({
+            "hash": tx_hash,
+            "chain_id": signed_tx.tx.chain_id,
+            "genesis_hash": signed_tx.tx.genesis_hash,
+            "network_mismatch_overridden": network_check.is_err(),
+        }))
+    }
+}
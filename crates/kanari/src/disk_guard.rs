@@ -0,0 +1,211 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use kanari_config::DiskGuardConfig;
+use kanari_db::maintenance::{MaintenanceScheduler, MaintenanceTask};
+use kanari_mempool::TxPool;
+use kanari_rpc_api::NodeState;
+use prometheus::{IntGauge, Registry};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+
+/// Minimum time between two early pruning runs while the disk stays low
+const EARLY_PRUNING_INTERVAL: Duration = Duration::from_secs(600);
+
+/// How full the volume holding the data dir is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskState {
+    Normal,
+    /// Below the warning threshold, receipts are pruned early
+    Low,
+    /// Below the critical threshold, the node is read-only
+    Critical,
+}
+
+impl DiskState {
+    /// The state for `free_bytes`. Read-only mode is only left once the free space is
+    /// back above the warning threshold, so the node does not flap around the critical one.
+    pub fn next(self, free_bytes: u64, config: &DiskGuardConfig) -> Self {
+        if free_bytes < config.critical_free_bytes {
+            DiskState::Critical
+        } else if free_bytes >= config.warning_free_bytes {
+            DiskState::Normal
+        } else if self == DiskState::Critical {
+            DiskState::Critical
+        } else {
+            DiskState::Low
+        }
+    }
+}
+
+struct DiskGuardMetrics {
+    free_bytes: IntGauge,
+    read_only: IntGauge,
+}
+
+impl DiskGuardMetrics {
+    fn new(registry: &Registry) -> Result<Self> {
+        let free_bytes = IntGauge::new(
+            "kanari_disk_free_bytes",
+            "Free bytes of the volume holding the data dir",
+        )?;
+        let read_only = IntGauge::new(
+            "kanari_read_only_mode",
+            "Whether the node is in emergency read-only mode because its disk is almost full",
+        )?;
+        registry.register(Box::new(free_bytes.clone()))?;
+        registry.register(Box::new(read_only.clone()))?;
+        Ok(Self {
+            free_bytes,
+            read_only,
+        })
+    }
+}
+
+/// Background task watching the free space of the data dir. Below the warning
+/// threshold it prunes receipts early, below the critical threshold it stops block
+/// production and mempool admission, so the database is never cut off mid-write.
+pub struct DiskGuard {
+    config: DiskGuardConfig,
+    data_dir: PathBuf,
+    read_only: Arc<AtomicBool>,
+    node_state: Arc<RwLock<NodeState>>,
+    tx_pool: Arc<RwLock<TxPool>>,
+    maintenance: Arc<MaintenanceScheduler>,
+    metrics: DiskGuardMetrics,
+}
+
+impl DiskGuard {
+    pub fn new(
+        config: DiskGuardConfig,
+        data_dir: PathBuf,
+        read_only: Arc<AtomicBool>,
+        node_state: Arc<RwLock<NodeState>>,
+        tx_pool: Arc<RwLock<TxPool>>,
+        maintenance: Arc<MaintenanceScheduler>,
+        registry: &Registry,
+    ) -> Result<Self> {
+        Ok(Self {
+            config,
+            data_dir,
+            read_only,
+            node_state,
+            tx_pool,
+            maintenance,
+            metrics: DiskGuardMetrics::new(registry)?,
+        })
+    }
+
+    /// Run the guard until the task is dropped
+    pub async fn run(self) {
+        info!(
+            "Disk guard started (warning below {} bytes, read-only below {} bytes free)",
+            self.config.warning_free_bytes, self.config.critical_free_bytes
+        );
+        let mut state = DiskState::Normal;
+        let mut last_pruning: Option<Instant> = None;
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            let free_bytes = match fs2::available_space(&self.data_dir) {
+                Ok(free_bytes) => free_bytes,
+                Err(e) => {
+                    warn!(
+                        "Failed to read free disk space of {}: {}",
+                        self.data_dir.display(),
+                        e
+                    );
+                    continue;
+                }
+            };
+            self.metrics.free_bytes.set(free_bytes as i64);
+
+            let next = state.next(free_bytes, &self.config);
+            if next != state {
+                match next {
+                    DiskState::Normal => info!(
+                        "Free disk space of {} is back to {} bytes",
+                        self.data_dir.display(),
+                        free_bytes
+                    ),
+                    DiskState::Low => warn!(
+                        "Free disk space of {} is down to {} bytes, pruning receipts early",
+                        self.data_dir.display(),
+                        free_bytes
+                    ),
+                    DiskState::Critical => error!(
+                        "CRITICAL: free disk space of {} is down to {} bytes, stopping block production and transaction admission",
+                        self.data_dir.display(),
+                        free_bytes
+                    ),
+                }
+                self.set_read_only(next == DiskState::Critical).await;
+                state = next;
+            }
+
+            if state != DiskState::Normal
+                && last_pruning.is_none_or(|at| at.elapsed() >= EARLY_PRUNING_INTERVAL)
+            {
+                last_pruning = Some(Instant::now());
+                self.prune_early(state).await;
+            }
+        }
+    }
+
+    async fn set_read_only(&self, read_only: bool) {
+        if self.read_only.swap(read_only, Ordering::SeqCst) == read_only {
+            return;
+        }
+        self.metrics.read_only.set(read_only as i64);
+        self.tx_pool.write().await.set_read_only(read_only);
+        self.node_state.write().await.read_only = read_only;
+        if !read_only {
+            info!("Leaving read-only mode, block production and transaction admission resume");
+        }
+    }
+
+    /// Prune receipts now instead of in the next maintenance window, then compact to
+    /// release the space unless the disk is too full for the compaction's own writes
+    async fn prune_early(&self, state: DiskState) {
+        if !self.maintenance.schedules(MaintenanceTask::Pruning) {
+            return;
+        }
+        let mut tasks = vec![MaintenanceTask::Pruning];
+        if state == DiskState::Low {
+            tasks.push(MaintenanceTask::Compaction);
+        }
+        for task in tasks {
+            if let Err(e) = self.maintenance.trigger(Some(task)).await {
+                warn!("Early storage maintenance task {} not run: {}", task, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_mode_is_left_above_the_warning_threshold() {
+        let config = DiskGuardConfig {
+            warning_free_bytes: 100,
+            critical_free_bytes: 10,
+            interval_secs: 1,
+        };
+        let state = DiskState::Normal.next(50, &config);
+        assert_eq!(state, DiskState::Low);
+        let state = state.next(5, &config);
+        assert_eq!(state, DiskState::Critical);
+        // Freeing a little space does not resume writes yet
+        let state = state.next(50, &config);
+        assert_eq!(state, DiskState::Critical);
+        assert_eq!(state.next(100, &config), DiskState::Normal);
+    }
+}
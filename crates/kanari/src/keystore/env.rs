@@ -0,0 +1,125 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Keystore, decode_private_key, key_pair_address};
+use anyhow::Result;
+use kanari_config::keystore_config::KeystoreBackend;
+use rooch_types::address::RoochAddress;
+use rooch_types::crypto::RoochKeyPair;
+
+/// Read-only keystore built from hex encoded private keys in an environment variable,
+/// intended for containers where keys are injected by the orchestrator.
+pub struct EnvKeystore {
+    env_var: String,
+    keys: Vec<(RoochAddress, RoochKeyPair)>,
+}
+
+impl EnvKeystore {
+    pub fn from_env(env_var: &str) -> Result<Self> {
+        let value = std::env::var(env_var).unwrap_or_default();
+        let keys = value
+            .split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(|encoded| {
+                let key_pair = decode_private_key(encoded)?;
+                Ok((key_pair_address(&key_pair)?, key_pair))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if keys.is_empty() {
+            tracing::warn!("No keys found in environment variable {}", env_var);
+        }
+
+        Ok(Self {
+            env_var: env_var.to_string(),
+            keys,
+        })
+    }
+}
+
+impl Keystore for EnvKeystore {
+    fn backend(&self) -> KeystoreBackend {
+        KeystoreBackend::Env
+    }
+
+    fn addresses(&self) -> Result<Vec<RoochAddress>> {
+        Ok(self.keys.iter().map(|(address, _)| *address).collect())
+    }
+
+    fn get_key_pair(
+        &self,
+        address: &RoochAddress,
+        _password: Option<String>,
+    ) -> Result<RoochKeyPair> {
+        self.keys
+            .iter()
+            .find(|(a, _)| a == address)
+            .map(|(_, key_pair)| key_pair.copy())
+            .ok_or_else(|| anyhow::anyhow!("No key for address {} in ${}", address, self.env_var))
+    }
+
+    fn generate_key(&mut self, _password: Option<String>) -> Result<RoochAddress> {
+        anyhow::bail!(
+            "The env keystore is read-only, add the key to ${} instead",
+            self.env_var
+        )
+    }
+
+    fn import_key(
+        &mut self,
+        _key_pair: RoochKeyPair,
+        _password: Option<String>,
+    ) -> Result<RoochAddress> {
+        anyhow::bail!(
+            "The env keystore is read-only, add the key to ${} instead",
+            self.env_var
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret(byte: u8) -> String {
+        let mut secret = [0u8; 32];
+        secret[31] = byte;
+        hex::encode(secret)
+    }
+
+    #[test]
+    fn test_env_keystore_reads_comma_separated_keys() {
+        let env_var = "KANARI_TEST_ENV_KEYSTORE_KEYS";
+        // SAFETY: the variable is only used by this test
+        unsafe {
+            std::env::set_var(env_var, format!("{}, ,0x{},", secret(1), secret(2)));
+        }
+        let keystore = EnvKeystore::from_env(env_var).unwrap();
+        unsafe {
+            std::env::remove_var(env_var);
+        }
+
+        let addresses = keystore.addresses().unwrap();
+        assert_eq!(addresses.len(), 2);
+        let first = decode_private_key(&secret(1)).unwrap();
+        assert_eq!(addresses[0], key_pair_address(&first).unwrap());
+        assert!(keystore.get_key_pair(&addresses[1], None).is_ok());
+
+        let unknown = key_pair_address(&decode_private_key(&secret(3)).unwrap()).unwrap();
+        let err = keystore.get_key_pair(&unknown, None).unwrap_err();
+        assert!(err.to_string().contains(env_var));
+    }
+
+    #[test]
+    fn test_env_keystore_is_read_only() {
+        let env_var = "KANARI_TEST_ENV_KEYSTORE_READ_ONLY";
+        let mut keystore = EnvKeystore::from_env(env_var).unwrap();
+        assert!(keystore.addresses().unwrap().is_empty());
+
+        let err = keystore.generate_key(None).unwrap_err();
+        assert!(err.to_string().contains("read-only"));
+        let key_pair = decode_private_key(&secret(1)).unwrap();
+        let err = keystore.import_key(key_pair, None).unwrap_err();
+        assert!(err.to_string().contains("read-only"));
+    }
+}
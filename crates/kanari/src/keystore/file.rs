@@ -0,0 +1,67 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Keystore, key_pair_address};
+use anyhow::Result;
+use kanari_config::keystore_config::KeystoreBackend;
+use rooch_key::keystore::account_keystore::AccountKeystore;
+use rooch_key::keystore::file_keystore::FileBasedKeystore;
+use rooch_types::address::RoochAddress;
+use rooch_types::crypto::RoochKeyPair;
+use rooch_types::key_struct::EncryptionData;
+use std::path::{Path, PathBuf};
+
+/// Encrypted keystore file, compatible with the Rooch keystore format
+pub struct FileKeystore {
+    path: PathBuf,
+    inner: FileBasedKeystore,
+}
+
+impl FileKeystore {
+    pub fn load(path: &Path) -> Result<Self> {
+        let inner = FileBasedKeystore::load(&path.to_path_buf())?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            inner,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Keystore for FileKeystore {
+    fn backend(&self) -> KeystoreBackend {
+        KeystoreBackend::File
+    }
+
+    fn addresses(&self) -> Result<Vec<RoochAddress>> {
+        Ok(self.inner.addresses())
+    }
+
+    fn get_key_pair(
+        &self,
+        address: &RoochAddress,
+        password: Option<String>,
+    ) -> Result<RoochKeyPair> {
+        self.inner.get_key_pair(address, password)
+    }
+
+    fn generate_key(&mut self, password: Option<String>) -> Result<RoochAddress> {
+        let result = self.inner.generate_and_add_new_key(password)?;
+        Ok(result.address)
+    }
+
+    fn import_key(
+        &mut self,
+        key_pair: RoochKeyPair,
+        password: Option<String>,
+    ) -> Result<RoochAddress> {
+        let address = key_pair_address(&key_pair)?;
+        let encryption = EncryptionData::encrypt_with_type(&key_pair, password)?;
+        self.inner
+            .add_address_encryption_data_to_keys(address, encryption)?;
+        Ok(address)
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::private_key_bytes;
+use anyhow::{Result, bail};
+use bitcoin::{NetworkKind, PrivateKey};
+use clap::ValueEnum;
+use rooch_types::crypto::RoochKeyPair;
+
+/// Script functions a single-key output descriptor may wrap its key in
+const DESCRIPTOR_FUNCTIONS: &[&str] = &["wpkh", "pkh", "tr", "sh"];
+
+/// Private key formats of other wallets that keys are imported from and exported to.
+/// Kanari identities are derived from the Bitcoin address of the key, as in genesis,
+/// so an imported key keeps the Rooch address it had in Rooch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum KeyFormat {
+    /// Bech32 `roochsecretkey1...` private key, as exported from a Rooch keystore
+    Rooch,
+    /// Bitcoin wallet import format, or a single-key descriptor such as `wpkh(<wif>)`
+    Wif,
+}
+
+/// Decode a private key exported by a Rooch or Bitcoin wallet
+pub fn decode_key(format: KeyFormat, encoded: &str) -> Result<RoochKeyPair> {
+    let encoded = encoded.trim();
+    match format {
+        KeyFormat::Rooch => RoochKeyPair::from_bech32(encoded)
+            .map_err(|e| anyhow::anyhow!("Invalid Rooch private key: {}", e)),
+        KeyFormat::Wif => {
+            let key = PrivateKey::from_wif(descriptor_key(encoded)?)?;
+            if !key.compressed {
+                bail!(
+                    "Uncompressed WIF keys are not supported, their Bitcoin addresses differ from Kanari identities"
+                );
+            }
+            RoochKeyPair::from_secp256k1_bytes(&key.inner.secret_bytes())
+        }
+    }
+}
+
+/// Encode a key pair for a Rooch or Bitcoin wallet, `network` sets the WIF prefix
+pub fn encode_key(
+    format: KeyFormat,
+    key_pair: &RoochKeyPair,
+    network: NetworkKind,
+) -> Result<String> {
+    match format {
+        KeyFormat::Rooch => key_pair
+            .export_private_key()
+            .map_err(|e| anyhow::anyhow!("Failed to export Rooch private key: {}", e)),
+        KeyFormat::Wif => {
+            Ok(PrivateKey::from_slice(&private_key_bytes(key_pair)?, network)?.to_wif())
+        }
+    }
+}
+
+/// The key of a single-key descriptor such as `wpkh([d34db33f/84h/0h/0h]<wif>)#checksum`,
+/// or the input itself when it is not a descriptor. The identity is derived from the key,
+/// not from the descriptor's script.
+fn descriptor_key(descriptor: &str) -> Result<&str> {
+    let mut key = descriptor
+        .split_once('#')
+        .map_or(descriptor, |(key, _)| key);
+    while let Some((function, inner)) = key.split_once('(') {
+        if !DESCRIPTOR_FUNCTIONS.contains(&function) {
+            bail!("Unsupported descriptor function {}", function);
+        }
+        key = inner
+            .strip_suffix(')')
+            .ok_or_else(|| anyhow::anyhow!("Unbalanced descriptor {}", descriptor))?;
+    }
+    if let Some(origin_end) = key.find(']') {
+        key = &key[origin_end + 1..];
+    }
+    if key.starts_with("xprv") || key.starts_with("tprv") {
+        bail!("Extended keys are not supported, export the single key as WIF instead");
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wif_and_descriptor_keys_round_trip() {
+        // The WIF of the private key 1
+        let wif = "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn";
+        let key_pair = decode_key(KeyFormat::Wif, wif).unwrap();
+        let mut expected = [0u8; 32];
+        expected[31] = 1;
+        assert_eq!(private_key_bytes(&key_pair).unwrap(), expected.to_vec());
+        assert_eq!(
+            encode_key(KeyFormat::Wif, &key_pair, NetworkKind::Main).unwrap(),
+            wif
+        );
+
+        let descriptor = format!("wpkh([d34db33f/84h/0h/0h]{})#8fhd9pwu", wif);
+        let from_descriptor = decode_key(KeyFormat::Wif, &descriptor).unwrap();
+        assert_eq!(
+            private_key_bytes(&from_descriptor).unwrap(),
+            expected.to_vec()
+        );
+
+        assert!(decode_key(KeyFormat::Wif, "wpkh(xprv9s21ZrQH143K/0/*)").is_err());
+        // The same key, uncompressed
+        assert!(
+            decode_key(
+                KeyFormat::Wif,
+                "5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf"
+            )
+            .is_err()
+        );
+    }
+}
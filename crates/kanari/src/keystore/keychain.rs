@@ -0,0 +1,110 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Keystore, decode_private_key, key_pair_address, private_key_bytes};
+use anyhow::Result;
+use kanari_config::keystore_config::KeystoreBackend;
+use keyring::Entry;
+use rand::RngCore;
+use rooch_types::address::RoochAddress;
+use rooch_types::crypto::RoochKeyPair;
+use std::str::FromStr;
+
+/// Keychain entry holding the comma separated list of stored addresses
+const INDEX_ENTRY: &str = "index";
+
+/// Keys stored in the OS keychain (macOS Keychain, Windows Credential Store, Secret Service).
+/// The keychain protects the secrets, so passwords are not used.
+pub struct KeychainKeystore {
+    service: String,
+}
+
+impl KeychainKeystore {
+    pub fn new(service: &str) -> Self {
+        Self {
+            service: service.to_string(),
+        }
+    }
+
+    fn entry(&self, user: &str) -> Result<Entry> {
+        Ok(Entry::new(&self.service, user)?)
+    }
+
+    fn read_index(&self) -> Result<Vec<String>> {
+        match self.entry(INDEX_ENTRY)?.get_password() {
+            Ok(index) => Ok(index
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect()),
+            Err(keyring::Error::NoEntry) => Ok(vec![]),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write_index(&self, index: &[String]) -> Result<()> {
+        self.entry(INDEX_ENTRY)?.set_password(&index.join(","))?;
+        Ok(())
+    }
+
+    /// Store the hex encoded private key under its address and index the address
+    fn store_key(&self, key_pair: &RoochKeyPair) -> Result<RoochAddress> {
+        let address = key_pair_address(key_pair)?;
+        self.entry(&address.to_string())?
+            .set_password(&hex::encode(private_key_bytes(key_pair)?))?;
+
+        let mut index = self.read_index()?;
+        if !index.contains(&address.to_string()) {
+            index.push(address.to_string());
+            self.write_index(&index)?;
+        }
+        Ok(address)
+    }
+}
+
+impl Keystore for KeychainKeystore {
+    fn backend(&self) -> KeystoreBackend {
+        KeystoreBackend::Keychain
+    }
+
+    fn addresses(&self) -> Result<Vec<RoochAddress>> {
+        self.read_index()?
+            .iter()
+            .map(|address| RoochAddress::from_str(address))
+            .collect()
+    }
+
+    fn get_key_pair(
+        &self,
+        address: &RoochAddress,
+        _password: Option<String>,
+    ) -> Result<RoochKeyPair> {
+        let secret = match self.entry(&address.to_string())?.get_password() {
+            Ok(secret) => secret,
+            Err(keyring::Error::NoEntry) => {
+                anyhow::bail!(
+                    "No key for address {} in keychain {}",
+                    address,
+                    self.service
+                )
+            }
+            Err(e) => return Err(e.into()),
+        };
+        decode_private_key(&secret)
+    }
+
+    fn generate_key(&mut self, _password: Option<String>) -> Result<RoochAddress> {
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let key_pair = RoochKeyPair::from_secp256k1_bytes(&secret)?;
+        self.store_key(&key_pair)
+    }
+
+    fn import_key(
+        &mut self,
+        key_pair: RoochKeyPair,
+        _password: Option<String>,
+    ) -> Result<RoochAddress> {
+        self.store_key(&key_pair)
+    }
+}
@@ -0,0 +1,126 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use fastcrypto::secp256k1::Secp256k1KeyPair;
+use kanari_config::keystore_config::{KEYSTORE_PASSWORD_ENV_VAR, KeystoreBackend, KeystoreConfig};
+use rooch_types::address::RoochAddress;
+use rooch_types::crypto::RoochKeyPair;
+
+pub mod env;
+pub mod file;
+pub mod interop;
+pub mod keychain;
+
+pub use env::EnvKeystore;
+pub use file::FileKeystore;
+pub use keychain::KeychainKeystore;
+
+/// Common interface over the signing key backends used by the CLI and the node
+pub trait Keystore: Send + Sync {
+    /// The backend this keystore is served from
+    fn backend(&self) -> KeystoreBackend;
+
+    /// All addresses with a key in this keystore
+    fn addresses(&self) -> Result<Vec<RoochAddress>>;
+
+    /// Load the key pair of an address, the password is ignored by backends without encryption
+    fn get_key_pair(
+        &self,
+        address: &RoochAddress,
+        password: Option<String>,
+    ) -> Result<RoochKeyPair>;
+
+    /// Generate a new key pair, store it and return its address
+    fn generate_key(&mut self, password: Option<String>) -> Result<RoochAddress>;
+
+    /// Store an existing key pair, e.g. one imported from another wallet, and return its
+    /// address
+    fn import_key(
+        &mut self,
+        key_pair: RoochKeyPair,
+        password: Option<String>,
+    ) -> Result<RoochAddress>;
+
+    /// Whether the keystore holds a key for the address
+    fn contains(&self, address: &RoochAddress) -> Result<bool> {
+        Ok(self.addresses()?.contains(address))
+    }
+}
+
+/// Open the keystore backend selected in the config
+pub fn open_keystore(config: &KeystoreConfig) -> Result<Box<dyn Keystore>> {
+    let keystore: Box<dyn Keystore> = match config.backend {
+        KeystoreBackend::File => Box::new(FileKeystore::load(&config.keystore_path()?)?),
+        KeystoreBackend::Keychain => Box::new(KeychainKeystore::new(config.keychain_service())),
+        KeystoreBackend::Env => Box::new(EnvKeystore::from_env(config.env_var())?),
+    };
+    Ok(keystore)
+}
+
+/// Load the key the node signs its blocks with from the keystore backend selected in the
+/// config. The file keystore password is read from `KANARI_KEYSTORE_PASSWORD`.
+pub fn load_signing_key(
+    config: &KeystoreConfig,
+    address: &RoochAddress,
+) -> Result<Secp256k1KeyPair> {
+    let keystore = open_keystore(config)?;
+    let password = match config.backend {
+        KeystoreBackend::File => std::env::var(KEYSTORE_PASSWORD_ENV_VAR).ok(),
+        _ => None,
+    };
+    match keystore.get_key_pair(address, password)? {
+        RoochKeyPair::Secp256k1(key_pair) => Ok(key_pair),
+        _ => anyhow::bail!("Only secp256k1 keys can sign blocks"),
+    }
+}
+
+/// Derive the Rooch address of a key pair from its Bitcoin address
+pub(crate) fn key_pair_address(key_pair: &RoochKeyPair) -> Result<RoochAddress> {
+    Ok(key_pair.public().bitcoin_address()?.to_rooch_address())
+}
+
+/// The raw bytes of a secp256k1 private key
+pub(crate) fn private_key_bytes(key_pair: &RoochKeyPair) -> Result<Vec<u8>> {
+    match key_pair {
+        RoochKeyPair::Secp256k1(key_pair) => Ok(key_pair.secret.as_ref().to_vec()),
+        _ => anyhow::bail!("Only secp256k1 keys can be exported"),
+    }
+}
+
+/// Decode a hex encoded secp256k1 private key, with or without the 0x prefix
+pub(crate) fn decode_private_key(encoded: &str) -> Result<RoochKeyPair> {
+    let encoded = encoded.trim();
+    let bytes = hex::decode(encoded.strip_prefix("0x").unwrap_or(encoded))?;
+    if bytes.len() != 32 {
+        anyhow::bail!(
+            "Invalid private key length: expected 32 bytes, got {}",
+            bytes.len()
+        );
+    }
+    RoochKeyPair::from_secp256k1_bytes(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_private_key_accepts_hex_with_and_without_prefix() {
+        let mut secret = [0u8; 32];
+        secret[31] = 1;
+        let encoded = hex::encode(secret);
+
+        let key_pair = decode_private_key(&encoded).unwrap();
+        assert_eq!(private_key_bytes(&key_pair).unwrap(), secret.to_vec());
+        let prefixed = decode_private_key(&format!(" 0x{}\n", encoded)).unwrap();
+        assert_eq!(
+            key_pair_address(&prefixed).unwrap(),
+            key_pair_address(&key_pair).unwrap()
+        );
+
+        let err = decode_private_key(&hex::encode([1u8; 31])).unwrap_err();
+        assert!(err.to_string().contains("expected 32 bytes, got 31"));
+        assert!(decode_private_key("0xnot-hex").is_err());
+    }
+}
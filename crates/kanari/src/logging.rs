@@ -0,0 +1,241 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, bail};
+use kanari_config::TracingExportConfig;
+use kanari_rpc_api::LogLevelController;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{Config, Sampler};
+use opentelemetry_sdk::{Resource, runtime};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Registry, fmt, reload};
+
+const DEFAULT_LOG_LEVEL: &str = "info";
+/// Key of the default directive in the levels map
+const DEFAULT_TARGET: &str = "*";
+/// Log lines kept in memory for post-mortem bundles
+pub const RECENT_LOG_LINES: usize = 1_000;
+
+static RECENT_LOGS: OnceLock<RecentLogs> = OnceLock::new();
+
+/// The last log lines written, without colors, kept so that a post-mortem bundle can
+/// include them after a panic
+#[derive(Clone)]
+pub struct RecentLogs {
+    capacity: usize,
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl RecentLogs {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    fn push(&self, text: &str) {
+        // A panic while logging must not hide the lines from the panic hook
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_string());
+        }
+    }
+
+    /// The kept lines, oldest first
+    pub fn lines(&self) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines.iter().cloned().collect()
+    }
+}
+
+/// Buffers one formatted event and keeps its lines when dropped
+pub struct RecentLogWriter {
+    logs: RecentLogs,
+    buffer: Vec<u8>,
+}
+
+impl Write for RecentLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RecentLogWriter {
+    fn drop(&mut self) {
+        self.logs.push(&String::from_utf8_lossy(&self.buffer));
+    }
+}
+
+impl<'a> MakeWriter<'a> for RecentLogs {
+    type Writer = RecentLogWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RecentLogWriter {
+            logs: self.clone(),
+            buffer: vec![],
+        }
+    }
+}
+
+/// The last log lines of this process, oldest first. Empty before `init`.
+pub fn recent_logs() -> Vec<String> {
+    RECENT_LOGS.get().map(RecentLogs::lines).unwrap_or_default()
+}
+
+/// Tracing filter that can be changed at runtime, e.g. through `admin_setLogLevel`
+pub struct ReloadableLogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+    /// Level per module target, `*` holds the default level
+    levels: Mutex<BTreeMap<String, String>>,
+}
+
+/// Install the global subscriber, starting from `RUST_LOG` or `info`. With `export`,
+/// the spans passing the filter are also exported over OTLP, so a slow call can be
+/// followed across the RPC, mempool, block production and P2P spans.
+pub fn init(export: Option<&TracingExportConfig>) -> Result<Arc<ReloadableLogFilter>> {
+    let directives = std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default();
+    let levels = parse_directives(&directives);
+    let filter = EnvFilter::try_new(to_directives(&levels))
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_LEVEL));
+    let (filter, handle) = reload::Layer::new(filter);
+    let otel = match export {
+        Some(export) => {
+            let tracer = opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(&export.endpoint),
+                )
+                .with_trace_config(
+                    Config::default()
+                        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                            export.sample_ratio,
+                        ))))
+                        .with_resource(Resource::new(vec![KeyValue::new(
+                            "service.name",
+                            export.service_name.clone(),
+                        )])),
+                )
+                .install_batch(runtime::Tokio)?;
+            Some(tracing_opentelemetry::layer().with_tracer(tracer))
+        }
+        None => None,
+    };
+    let recent_logs = RECENT_LOGS
+        .get_or_init(|| RecentLogs::new(RECENT_LOG_LINES))
+        .clone();
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(fmt::layer().with_ansi(false).with_writer(recent_logs))
+        .with(otel)
+        .init();
+    if let Some(export) = export {
+        tracing::info!(
+            "Exporting tracing spans to {} as {}",
+            export.endpoint,
+            export.service_name
+        );
+    }
+    Ok(Arc::new(ReloadableLogFilter {
+        handle,
+        levels: Mutex::new(levels),
+    }))
+}
+
+/// Flush the spans still buffered for export
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+fn parse_directives(directives: &str) -> BTreeMap<String, String> {
+    let mut levels = BTreeMap::new();
+    levels.insert(DEFAULT_TARGET.to_string(), DEFAULT_LOG_LEVEL.to_string());
+    for directive in directives
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        match directive.split_once('=') {
+            Some((target, level)) => levels.insert(target.to_string(), level.to_lowercase()),
+            None => levels.insert(DEFAULT_TARGET.to_string(), directive.to_lowercase()),
+        };
+    }
+    levels
+}
+
+fn to_directives(levels: &BTreeMap<String, String>) -> String {
+    levels
+        .iter()
+        .map(|(target, level)| match target.as_str() {
+            DEFAULT_TARGET => level.clone(),
+            // Crate names use `-`, tracing targets use `_`
+            _ => format!("{}={}", target.replace('-', "_"), level),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl LogLevelController for ReloadableLogFilter {
+    fn set_level(&self, target: &str, level: &str) -> Result<()> {
+        let level = level.to_lowercase();
+        LevelFilter::from_str(&level)
+            .map_err(|_| anyhow::anyhow!("Invalid log level {}", level))?;
+        let target = target.trim();
+        if target.is_empty() || target.contains([',', '=', ' ']) {
+            bail!("Invalid log target {:?}", target);
+        }
+
+        let mut levels = self.levels.lock().unwrap();
+        let mut updated = levels.clone();
+        updated.insert(target.to_string(), level);
+        let filter = EnvFilter::try_new(to_directives(&updated))?;
+        self.handle.reload(filter)?;
+        *levels = updated;
+        Ok(())
+    }
+
+    fn levels(&self) -> BTreeMap<String, String> {
+        self.levels.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives_round_trip() {
+        let levels = parse_directives("warn,kanari-p2p=debug, libp2p=off");
+        assert_eq!(levels["*"], "warn");
+        assert_eq!(levels["kanari-p2p"], "debug");
+        assert_eq!(to_directives(&levels), "warn,kanari_p2p=debug,libp2p=off");
+        assert_eq!(to_directives(&parse_directives("")), "info");
+    }
+
+    #[test]
+    fn test_recent_logs_keep_the_last_lines() {
+        let logs = RecentLogs::new(2);
+        logs.make_writer().write_all(b"first\n").unwrap();
+        logs.make_writer().write_all(b"second\nthird\n").unwrap();
+        assert_eq!(logs.lines(), vec!["second", "third"]);
+    }
+}
@@ -1,19 +1,72 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use kanari_config::KanariOpt;
 use kanari_db::RoochDB;
-use kanari_rpc_api::{KanariRpcServer, RpcServerConfig};
-use kanari_types::block::Block;
-use moveos_types::h256::H256;
+use kanari_mempool::MempoolLimits;
+#[cfg(feature = "p2p")]
+use kanari_p2p::network::NetworkCommand;
+#[cfg(feature = "p2p")]
+use kanari_rpc_api::SubscriptionEvent;
+use kanari_rpc_api::{
+    ApiKeyStore, CorsConfig, DEFAULT_MAX_BLOCKS_PER_BATCH,
+    DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION, DEFAULT_RESPONSE_CACHE_CAPACITY,
+    DEFAULT_RESPONSE_CACHE_CONFIRMATIONS, DEFAULT_RESPONSE_CACHE_TTL,
+    DEFAULT_SLOW_SUBSCRIBER_TIMEOUT, DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY, KanariRpcServer,
+    NodeStateEvent, PluginConfig, PluginServerConfig, RateLimit, RateLimitConfig,
+    ResponseCacheConfig, RpcAuthConfig, RpcServerConfig, SlowSubscriberPolicy,
+};
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "p2p")]
+use tokio::sync::broadcast::error::RecvError;
 
 use tracing::{error, info, warn};
 
+mod alerting;
+mod archive;
+mod block_auditor;
+mod block_production;
 mod commands;
+mod disk_guard;
+mod keystore;
+mod logging;
+mod maintenance;
+#[cfg(feature = "p2p")]
+mod p2p;
+mod ports;
+mod post_mortem;
+mod state_root_verifier;
+mod view_function;
 
+use alerting::AlertEngine;
+use archive::Archiver;
+use block_auditor::BlockAuditor;
+use block_production::{
+    BLOCK_INTERVAL_SECS, BlockProductionMetrics, LocalBlockBuilder, ensure_genesis_epoch,
+    next_block_number, produce_next_block,
+};
+use commands::account::AccountCommand;
 use commands::account::create::CreateCommand;
+use commands::account::sign_message::SignMessageCommand;
+use commands::account::verify_message::VerifyMessageCommand;
+use commands::archive::ArchiveCommand;
+use commands::db::DbCommand;
+use commands::dev::DevCommand;
+use commands::inspect::{block::InspectBlockCommand, tx::InspectTxCommand};
+use commands::service::ServiceCommand;
+use commands::state::StateCommand;
+use commands::support_bundle::SupportBundleCommand;
+use commands::tx::TxCommand;
+use disk_guard::DiskGuard;
+use logging::ReloadableLogFilter;
+use maintenance::maintenance_scheduler;
+#[cfg(feature = "p2p")]
+use p2p::P2pService;
+use post_mortem::PostMortem;
 use rooch::cli_types::CommandAction;
+use state_root_verifier::StateRootVerifier;
+use view_function::MoveViewExecutor;
 
 #[derive(Parser)]
 #[clap(name = "kari", author = "The Kanari Core Contributors L3")]
@@ -35,19 +88,88 @@ enum Commands {
         #[clap(flatten)]
         create_command: CreateCommand,
     },
+    /// Import and export account keys in Rooch and Bitcoin wallet formats
+    Account {
+        #[clap(subcommand)]
+        command: AccountCommand,
+    },
+    /// Sign an arbitrary message with an account key
+    SignMessage {
+        #[clap(flatten)]
+        command: SignMessageCommand,
+    },
+    /// Verify a signed message
+    VerifyMessage {
+        #[clap(flatten)]
+        command: VerifyMessageCommand,
+    },
+    /// Decode raw block bytes offline
+    InspectBlock {
+        #[clap(flatten)]
+        command: InspectBlockCommand,
+    },
+    /// Decode raw transaction bytes offline
+    InspectTx {
+        #[clap(flatten)]
+        command: InspectTxCommand,
+    },
+    /// Inspect and compare the local database offline
+    Db {
+        #[clap(subcommand)]
+        command: DbCommand,
+    },
+    /// Restore blocks from a long-term archive
+    Archive {
+        #[clap(subcommand)]
+        command: ArchiveCommand,
+    },
+    /// Export chain state offline
+    State {
+        #[clap(subcommand)]
+        command: StateCommand,
+    },
+    /// Send transactions to a node
+    Tx {
+        #[clap(subcommand)]
+        command: TxCommand,
+    },
+    /// Reset and fast-forward a local devnet
+    Dev {
+        #[clap(subcommand)]
+        command: DevCommand,
+    },
+    /// Run the node as a systemd service
+    Service {
+        #[clap(subcommand)]
+        command: ServiceCommand,
+    },
+    /// Write a post-mortem bundle of a node for a bug report
+    SupportBundle {
+        #[clap(flatten)]
+        command: SupportBundleCommand,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
-
     let cli = Cli::parse();
 
+    // Initialize tracing
+    let tracing_export = match &cli.command {
+        Commands::Start { config } => config.tracing_export_config(),
+        _ => None,
+    };
+    let log_filter = logging::init(tracing_export.as_ref())?;
+
     match cli.command {
         Commands::Start { config } => {
             info!("Starting Kanari node...");
-            start_node(config).await?;
+            let result = start_node(config, log_filter).await;
+            if let Err(e) = &result {
+                post_mortem::fatal_error(e);
+            }
+            logging::shutdown();
+            result?;
         }
         Commands::Create { create_command } => {
             info!("Creating new account...");
@@ -56,14 +178,87 @@ async fn main() -> Result<()> {
                 info!("Account created with address: {:?}", address);
             }
         }
+        Commands::Account { command } => {
+            let output = match command {
+                AccountCommand::Import(command) => command.execute().await?,
+                AccountCommand::Export(command) => command.execute().await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::SignMessage { command } => {
+            let output = command.execute().await?;
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::VerifyMessage { command } => {
+            let output = command.execute().await?;
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::InspectBlock { command } => {
+            let output = command.execute().await?;
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::InspectTx { command } => {
+            let output = command.execute().await?;
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::Db { command } => {
+            let output = match command {
+                DbCommand::Diff(command) => command.execute().await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::Archive { command } => {
+            let output = match command {
+                ArchiveCommand::Restore(command) => command.execute().await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::State { command } => {
+            let output = match command {
+                StateCommand::Export(command) => command.execute().await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::Tx { command } => {
+            let output = match command {
+                TxCommand::Send(command) => command.execute().await?,
+                TxCommand::DecodeError(command) => command.execute().await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::Dev { command } => {
+            let output = match command {
+                DevCommand::Reset(command) => command.execute().await?,
+                DevCommand::FastForward(command) => command.execute().await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::Service { command } => {
+            let output = match command {
+                ServiceCommand::Install(command) => command.execute().await?,
+                ServiceCommand::Status(command) => command.execute().await?,
+                ServiceCommand::Logs(command) => {
+                    // Logs are printed as they are read
+                    command.execute().await?;
+                    return Ok(());
+                }
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        Commands::SupportBundle { command } => {
+            let output = command.execute().await?;
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
     }
 
     Ok(())
 }
 
-async fn start_node(mut config: KanariOpt) -> Result<()> {
+async fn start_node(mut config: KanariOpt, log_filter: Arc<ReloadableLogFilter>) -> Result<()> {
     // Initialize the configuration first
     config.init()?;
+    config.validate()?;
+    resolve_ports(&mut config)?;
 
     info!("Kanari node configuration: {:?}", config);
     info!("Starting Kanari blockchain node...");
@@ -81,32 +276,357 @@ async fn start_node(mut config: KanariOpt) -> Result<()> {
         }
     };
 
+    // Load the node signing key from the configured keystore backend
+    let signing_key = match &config.sequencer_account {
+        Some(sequencer_account) => {
+            let keystore_config = config.keystore_config();
+            let address = sequencer_account.parse()?;
+            let key_pair = keystore::load_signing_key(keystore_config, &address).map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to load sequencer account {} from the {} keystore: {}",
+                    sequencer_account,
+                    keystore_config.backend,
+                    e
+                )
+            })?;
+            info!(
+                "Signing blocks with sequencer account {} from the {} keystore",
+                sequencer_account, keystore_config.backend
+            );
+            Some(key_pair)
+        }
+        None => {
+            warn!("No sequencer account set, produced blocks are not signed");
+            None
+        }
+    };
+
     // Start RPC server
-    let rpc_port = config.port.unwrap_or(6767);
+    let rpc_port = config.port();
     let rpc_config = RpcServerConfig {
         listen_address: format!("0.0.0.0:{}", rpc_port).parse()?,
         max_connections: 1000,
         max_request_body_size: 64 * 1024 * 1024,  // 64MB
         max_response_body_size: 64 * 1024 * 1024, // 64MB
-        enable_cors: true,
+        cors: CorsConfig {
+            allowed_origins: config.rpc_cors_origin.clone(),
+            allowed_headers: if config.rpc_cors_header.is_empty() {
+                CorsConfig::default().allowed_headers
+            } else {
+                config.rpc_cors_header.clone()
+            },
+            allowed_methods: if config.rpc_cors_method.is_empty() {
+                CorsConfig::default().allowed_methods
+            } else {
+                config.rpc_cors_method.clone()
+            },
+            permissive: config.rpc_cors_permissive,
+        },
         enable_ws: true,
+        disabled_namespaces: config.rpc_disable_namespace.clone(),
+        enable_experimental: config.rpc_enable_experimental,
         batch_requests_limit: 100,
+        max_blocks_per_batch: config
+            .rpc_max_blocks_per_batch
+            .unwrap_or(DEFAULT_MAX_BLOCKS_PER_BATCH),
+        ipc_path: config.rpc_ipc_path(),
+        rest_listen_address: config
+            .rest_port
+            .map(|port| SocketAddr::from(([0, 0, 0, 0], port))),
+        metrics_listen_address: metrics_listen_address(&config),
+        plugins: config
+            .plugin_socket_path()
+            .map(|path| {
+                anyhow::Ok(PluginServerConfig {
+                    path,
+                    plugins: config
+                        .plugins()?
+                        .into_iter()
+                        .map(|(name, token, limit)| PluginConfig {
+                            name,
+                            token,
+                            rate_limit: limit.map(|(requests_per_second, burst)| RateLimit {
+                                requests_per_second,
+                                burst,
+                            }),
+                        })
+                        .collect(),
+                })
+            })
+            .transpose()?,
+        rate_limit: RateLimitConfig {
+            per_ip: config
+                .rpc_rate_limit()
+                .map(|(requests_per_second, burst)| RateLimit {
+                    requests_per_second,
+                    burst,
+                }),
+            per_method: config
+                .rpc_method_rate_limits()?
+                .into_iter()
+                .map(|(method, requests_per_second, burst)| {
+                    (
+                        method,
+                        RateLimit {
+                            requests_per_second,
+                            burst,
+                        },
+                    )
+                })
+                .collect(),
+        },
+        auth: RpcAuthConfig {
+            api_keys: config
+                .rpc_api_key
+                .iter()
+                .map(|key| key.trim().to_string())
+                .collect(),
+            ..Default::default()
+        },
+        max_subscriptions_per_connection: config
+            .rpc_max_subscriptions_per_connection
+            .unwrap_or(DEFAULT_MAX_SUBSCRIPTIONS_PER_CONNECTION),
+        subscription_buffer_capacity: config
+            .rpc_subscription_buffer_capacity
+            .unwrap_or(DEFAULT_SUBSCRIPTION_BUFFER_CAPACITY),
+        slow_subscriber_policy: if config.rpc_drop_slow_subscriber_messages {
+            SlowSubscriberPolicy::DropMessages
+        } else {
+            SlowSubscriberPolicy::Close(
+                config
+                    .rpc_slow_subscriber_timeout_secs
+                    .map(std::time::Duration::from_secs)
+                    .unwrap_or(DEFAULT_SLOW_SUBSCRIBER_TIMEOUT),
+            )
+        },
+        response_cache: ResponseCacheConfig {
+            capacity: config
+                .rpc_response_cache_size
+                .unwrap_or(DEFAULT_RESPONSE_CACHE_CAPACITY),
+            ttl: config
+                .rpc_response_cache_ttl_secs
+                .map(std::time::Duration::from_secs)
+                .unwrap_or(DEFAULT_RESPONSE_CACHE_TTL),
+            confirmations: config
+                .rpc_response_cache_confirmations
+                .unwrap_or(DEFAULT_RESPONSE_CACHE_CONFIRMATIONS),
+        },
+    };
+
+    let listen_address = rpc_config.listen_address;
+    // Stored API keys are managed through the admin namespace, which needs the
+    // configured keys to be protected
+    let rpc_auth_enabled = rpc_config.auth.is_enabled();
+
+    let param_overrides = config.param_overrides()?;
+    if !param_overrides.is_empty() {
+        warn!(
+            "Chain parameters overridden: {:?}",
+            param_overrides.entries()
+        );
+    }
+    let default_limits = MempoolLimits::default();
+    let mempool_limits = MempoolLimits {
+        max_pending_per_sender: config
+            .mempool_max_pending_per_sender
+            .unwrap_or(default_limits.max_pending_per_sender),
+        max_pending_bytes_per_sender: config
+            .mempool_max_pending_bytes_per_sender
+            .unwrap_or(default_limits.max_pending_bytes_per_sender),
+        fee_bump_depth: config
+            .mempool_fee_bump_depth
+            .unwrap_or(default_limits.fee_bump_depth),
+        max_queued_per_sender: config
+            .mempool_max_queued_per_sender
+            .unwrap_or(default_limits.max_queued_per_sender),
+        queued_ttl: config
+            .mempool_queued_ttl_secs
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(default_limits.queued_ttl),
+        min_replacement_bump_pct: config
+            .mempool_min_replacement_bump_pct
+            .unwrap_or(default_limits.min_replacement_bump_pct),
+        min_gas_price: param_overrides
+            .min_gas_price
+            .unwrap_or(default_limits.min_gas_price),
+        ..default_limits
+    };
+
+    let proposer_keys = config
+        .external_proposer_keys
+        .iter()
+        .map(|key| hex::decode(key.strip_prefix("0x").unwrap_or(key)))
+        .collect::<Result<Vec<_>, _>>()?;
+    ensure_genesis_epoch(&db, &proposer_keys, param_overrides.block_gas_limit)?;
+
+    // Upload finalized blocks to long-term storage if a bucket is configured, continuously
+    // or in the maintenance windows
+    let archiver = match config.archive_config() {
+        Some(archive_config) => Some(Arc::new(Archiver::new(archive_config.clone(), db.clone())?)),
+        None => None,
     };
+    if let Some(archiver) = &archiver
+        && !config.maintenance.archive_uploads
+    {
+        tokio::spawn(archiver.clone().run());
+    }
+
+    // Run compaction, pruning and scheduled archive uploads in off-peak windows
+    let maintenance = Arc::new(maintenance_scheduler(
+        config.maintenance_config(),
+        db.clone(),
+        archiver,
+        &registry,
+    )?);
+    tokio::spawn(maintenance.clone().run());
+
+    let mut rpc_server = KanariRpcServer::new(rpc_config)
+        .with_db(db.clone())
+        .with_mempool_limits(mempool_limits)
+        .with_log_controller(log_filter)
+        .with_maintenance(maintenance.clone())
+        .with_block_builder(Arc::new(LocalBlockBuilder::new(
+            db.clone(),
+            config.chain_id().id(),
+        )))
+        .with_metrics_registry(registry.clone())
+        .with_block_proposers(proposer_keys);
+    if rpc_auth_enabled {
+        rpc_server = rpc_server.with_api_keys(Arc::new(ApiKeyStore::load(config.api_keys_path())?));
+    } else {
+        info!("Stored API keys are disabled, set rpc_api_key to manage them");
+    }
+    // A database without the framework state can not run Move view functions yet
+    match MoveViewExecutor::new(db.clone()) {
+        Ok(executor) => rpc_server = rpc_server.with_view_executor(Arc::new(executor)),
+        Err(e) => warn!("Move view functions are unavailable: {}", e),
+    }
+    let chain_id = config.chain_id().id();
+    let p2p_port = config.network.p2p_port;
+    rpc_server
+        .update_node_state(|state| {
+            state.chain_id = chain_id;
+            state.rpc_port = Some(rpc_port);
+            state.p2p_port = Some(p2p_port);
+            state.param_overrides = param_overrides.entries();
+        })
+        .await;
+
+    // Leave a bundle in the data dir for bug reports if the node panics or fails
+    post_mortem::install(PostMortem::new(
+        config.base().data_dir().to_path_buf(),
+        config.clone(),
+        rpc_server.get_node_state(),
+        db.clone(),
+        registry.clone(),
+    ));
+    let bootstrap_nodes = config.bootstrap_nodes();
+    if bootstrap_nodes.is_empty() {
+        info!("No bootstrap nodes for chain {}", chain_id);
+    } else {
+        info!("Bootstrap nodes: {}", bootstrap_nodes.join(", "));
+    }
+
+    // Restore transactions that were pending when the node last shut down, each
+    // sender's from its committed sequence number
+    let mempool_path = config.mempool_path();
+    let restored = rpc_server
+        .get_tx_pool()
+        .write()
+        .await
+        .load(&mempool_path, |sender| {
+            Ok(db
+                .get_account(*sender)?
+                .map_or(0, |account| account.sequence_number))
+        });
+    match restored {
+        Ok(0) => {}
+        Ok(restored) => info!("Restored {} pending transaction(s)", restored),
+        Err(e) => warn!("Failed to restore pending transactions: {}", e),
+    }
 
-    let mut rpc_server = KanariRpcServer::new(rpc_config);
-    
     // Start the RPC server
-    rpc_server.start().await?;
+    rpc_server
+        .start()
+        .await
+        .with_context(|| format!("Failed to start the RPC server on {}", listen_address))?;
+
+    // The gRPC service forwards to the same handlers, over the same state and database
+    #[cfg(feature = "grpc")]
+    let grpc_shutdown = match config.grpc_port {
+        Some(grpc_port) => {
+            let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+            let service = kanari_grpc::KanariGrpcService::new(rpc_server.kanari_rpc_impl());
+            let address = SocketAddr::from(([0, 0, 0, 0], grpc_port));
+            tokio::spawn(async move {
+                let shutdown = async {
+                    let _ = stopped.await;
+                };
+                if let Err(e) = kanari_grpc::serve(address, service, shutdown).await {
+                    error!("gRPC server on {} failed: {}", address, e);
+                }
+            });
+            Some(stop)
+        }
+        None => None,
+    };
+    #[cfg(not(feature = "grpc"))]
+    let grpc_shutdown: Option<tokio::sync::oneshot::Sender<()>> = {
+        if config.grpc_port.is_some() {
+            warn!("Built without the grpc feature, grpc_port is ignored");
+        }
+        None
+    };
+
+    // Join the P2P network, which reports its peers to the node state
+    #[cfg(feature = "p2p")]
+    let (p2p_commands, p2p_running, p2p_task) = {
+        let p2p_service = P2pService::new(&config, rpc_server.state_bus(), &registry).await?;
+        let operator = p2p_service.local_operator();
+        rpc_server
+            .update_node_state(|state| state.operator = operator)
+            .await;
+        let p2p_commands = p2p_service.commands();
+        // Announce every new head to peers, whether produced here or imported through
+        // kanari_submitBlock
+        let mut new_blocks = rpc_server.subscribe_events();
+        let announce = p2p_commands.clone();
+        tokio::spawn(async move {
+            loop {
+                match new_blocks.recv().await {
+                    Ok(SubscriptionEvent::NewBlock(block)) => {
+                        let _ = announce.send(NetworkCommand::SetLocalHeight(block.number));
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+        let p2p_running = p2p_service.running();
+        let (p2p_stop, p2p_stopped) = tokio::sync::oneshot::channel::<()>();
+        let p2p_task = tokio::spawn(async move {
+            let shutdown = async {
+                let _ = p2p_stopped.await;
+            };
+            if let Err(e) = p2p_service.run(shutdown).await {
+                error!("P2P network failed: {}", e);
+            }
+        });
+        (p2p_commands, p2p_running, Some((p2p_stop, p2p_task)))
+    };
+    #[cfg(not(feature = "p2p"))]
+    let (p2p_running, p2p_task) = {
+        info!("Built without the p2p feature, the node does not join the P2P network");
+        (Arc::new(AtomicBool::new(false)), None)
+    };
 
     info!("Node is running on port: {}", rpc_port);
-    info!(
-        "RPC server is running on http://0.0.0.0:{}",
-        rpc_port
-    );
+    info!("RPC server is running on http://0.0.0.0:{}", rpc_port);
     info!(
         "Data directory: {:?}",
         config
             .base_data_dir
+            .clone()
             .unwrap_or_else(|| std::path::PathBuf::from(".kanari"))
     );
 
@@ -114,6 +634,11 @@ async fn start_node(mut config: KanariOpt) -> Result<()> {
     match db.get_latest_block_number() {
         Ok(Some(latest_block_number)) => {
             info!("Latest block in database: #{}", latest_block_number);
+            rpc_server
+                .state_bus()
+                .publish(NodeStateEvent::BlockHeight(latest_block_number));
+            #[cfg(feature = "p2p")]
+            let _ = p2p_commands.send(NetworkCommand::SetLocalHeight(latest_block_number));
             // Display the latest block details
             if let Ok(Some(latest_block)) = db.get_block(latest_block_number) {
                 info!("Latest block details: {:?}", latest_block);
@@ -127,74 +652,245 @@ async fn start_node(mut config: KanariOpt) -> Result<()> {
         }
     }
 
-    // Start with the next block number
-    let mut block_number = match db.get_latest_block_number()? {
-        Some(latest) => latest + 1,
-        None => 1,
-    };
+    // Cross-check state roots against a trusted node if configured
+    let production_halted = Arc::new(AtomicBool::new(false));
+    let fork_detected = Arc::new(AtomicBool::new(false));
+    if let Some(verifier_config) = config.state_root_verifier_config() {
+        let verifier = StateRootVerifier::new(
+            verifier_config,
+            db.clone(),
+            production_halted.clone(),
+            fork_detected.clone(),
+        )?;
+        tokio::spawn(verifier.run());
+    }
+
+    // Evaluate operator alerting rules if configured
+    if let Some(alerting_config) = config.alerting_config()? {
+        let engine = AlertEngine::new(
+            alerting_config,
+            config.base().data_dir().to_path_buf(),
+            rpc_server.get_node_state(),
+            fork_detected.clone(),
+            p2p_running.clone(),
+        )?;
+        tokio::spawn(engine.run());
+    }
+
+    // Stop writing before the data dir volume runs out of space
+    let read_only = Arc::new(AtomicBool::new(false));
+    if let Some(disk_guard_config) = config.disk_guard_config() {
+        let guard = DiskGuard::new(
+            disk_guard_config,
+            config.base().data_dir().to_path_buf(),
+            read_only.clone(),
+            rpc_server.get_node_state(),
+            rpc_server.get_tx_pool(),
+            maintenance.clone(),
+            &registry,
+        )?;
+        tokio::spawn(guard.run());
+    }
+
+    // Continuously re-verify random historical blocks in the background
+    if let Some(audit_config) = config.block_audit_config() {
+        let auditor = BlockAuditor::new(audit_config, db.clone(), &registry)?;
+        tokio::spawn(auditor.run());
+    }
 
-    // Create a sample block every 10 seconds to demonstrate block saving functionality
+    let import_lock = rpc_server.import_lock();
+    let block_metrics = BlockProductionMetrics::new(&registry)?;
+    // Node status subscribers are notified once the new height is applied
+    let state_bus = rpc_server.state_bus();
+    let tx_pool = rpc_server.get_tx_pool();
+
+    // The height of the next block, only advanced once a block is saved
+    let mut block_number = next_block_number(&db)?;
+
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    // Create a sample block every `BLOCK_INTERVAL_SECS`, unless overridden, to demonstrate
+    // block saving functionality. A block in progress is always finished before shutting down.
+    let block_interval = param_overrides
+        .block_interval_secs
+        .unwrap_or(BLOCK_INTERVAL_SECS);
     loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(block_interval)) => {}
+            _ = &mut shutdown => break,
+        }
+
+        if production_halted.load(Ordering::SeqCst) {
+            error!(
+                "Block production is halted, skipping block #{}",
+                block_number
+            );
+            continue;
+        }
+        if read_only.load(Ordering::SeqCst) {
+            warn!("Node is read-only, skipping block #{}", block_number);
+            continue;
+        }
 
-        block_number += 1;
-        match create_and_save_block(&db, block_number).await {
-            Ok(block_hash) => {
+        // External proposers may have imported blocks through kanari_submitBlock, the
+        // lock keeps them from importing one at the same height meanwhile
+        let _import = import_lock.lock().await;
+        // Peers' clocks correct the local one, as when validating submitted blocks
+        let timestamp = rpc_server.get_node_state().read().await.network_time_secs();
+        let started = std::time::Instant::now();
+        let saved = produce_next_block(
+            &db,
+            Some(&tx_pool),
+            chain_id,
+            timestamp,
+            signing_key.as_ref(),
+        )
+        .await;
+        match saved {
+            Ok((saved_number, block_hash)) => {
+                block_number = saved_number;
+                block_metrics.observe_produced(block_number, started.elapsed());
+                state_bus.publish(NodeStateEvent::BlockHeight(block_number));
                 info!(
                     "Successfully created and saved block #{} with hash: {}",
                     block_number,
                     hex::encode(block_hash.as_bytes())
                 );
+                if let Err(e) = rpc_server.publish_new_block(block_number) {
+                    warn!(
+                        "Failed to publish block #{} to subscribers: {}",
+                        block_number, e
+                    );
+                }
+                block_number += 1;
             }
             Err(e) => {
+                block_metrics.observe_failed();
                 error!("Failed to create block #{}: {}", block_number, e);
             }
         }
     }
-}
 
-async fn create_and_save_block(db: &Arc<RoochDB>, block_number: u128) -> Result<H256> {
-    // Get current timestamp
-    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    if let Some(stop) = grpc_shutdown {
+        let _ = stop.send(());
+    }
+    let drain_timeout = config.drain_timeout();
+    if tokio::time::timeout(
+        drain_timeout,
+        drain_node(&mut rpc_server, &mempool_path, p2p_task),
+    )
+    .await
+    .is_err()
+    {
+        warn!("Shutdown drain did not finish within {:?}", drain_timeout);
+    }
+    info!("Kanari node stopped");
+    Ok(())
+}
 
-    // Create a new block
-    let prev_hash = if block_number == 1 {
-        H256::zero() // Genesis block
-    } else {
-        // In a real implementation, this would be the hash of the previous block
-        // For now, let's try to get the previous block's hash
-        match db.get_block(block_number - 1)? {
-            Some(prev_block) => prev_block.batch_hash,
-            None => H256::zero(), // Fallback if previous block not found
+/// Wait for Ctrl-C or SIGTERM
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
         }
     };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
 
-    let batch_hash = H256::random(); // In a real implementation, this would be computed from transactions
-    let tx_accumulator_root = H256::random();
-    let state_root = H256::random();
-
-    let block = Block::new(
-        block_number,
-        0, // batch_size - no transactions in this demo
-        batch_hash,
-        prev_hash,
-        tx_accumulator_root,
-        state_root,
-    );
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+    info!("Shutdown requested, draining node");
+}
+
+/// Check that every listener port can be bound before anything is started, moving
+/// occupied ones to free ports when `--port-auto` is set
+fn resolve_ports(config: &mut KanariOpt) -> Result<()> {
+    let any = IpAddr::from([0, 0, 0, 0]);
+    let rpc_port = ports::resolve_port("RPC", any, config.port(), config.port_auto, &[])?;
+    config.port = Some(rpc_port);
+    let mut taken = vec![rpc_port];
+    let auto = config.port_auto;
+    resolve_optional_port("REST", &mut config.rest_port, auto, &mut taken)?;
+    resolve_optional_port("gRPC", &mut config.grpc_port, auto, &mut taken)?;
+    resolve_optional_port("Metrics", &mut config.metrics_port, auto, &mut taken)?;
+    config.network.p2p_port =
+        ports::resolve_port("P2P", any, config.network.p2p_port, auto, &taken)?;
+    Ok(())
+}
 
-    info!("Created block #{} at timestamp {}", block_number, timestamp);
+/// The metrics listener, served by builds with the `metrics` feature
+#[cfg(feature = "metrics")]
+fn metrics_listen_address(config: &KanariOpt) -> Option<SocketAddr> {
+    config
+        .metrics_port
+        .map(|port| SocketAddr::from(([0, 0, 0, 0], port)))
+}
 
-    // Actually save the block to the database
-    match db.save_block(&block) {
-        Ok(()) => {
-            info!("Block #{} successfully saved to database", block_number);
-        }
-        Err(e) => {
-            error!("Failed to save block #{} to database: {}", block_number, e);
-            return Err(e);
-        }
+#[cfg(not(feature = "metrics"))]
+fn metrics_listen_address(config: &KanariOpt) -> Option<SocketAddr> {
+    if config.metrics_port.is_some() {
+        warn!("Built without the metrics feature, metrics_port is ignored");
+    }
+    None
+}
+
+/// Resolve the port of an optional listener, if it is enabled
+fn resolve_optional_port(
+    service: &str,
+    port: &mut Option<u16>,
+    auto: bool,
+    taken: &mut Vec<u16>,
+) -> Result<()> {
+    if let Some(configured) = *port {
+        let any = IpAddr::from([0, 0, 0, 0]);
+        let resolved = ports::resolve_port(service, any, configured, auto, taken)?;
+        *port = Some(resolved);
+        taken.push(resolved);
     }
+    Ok(())
+}
 
-    // Return the batch_hash as the block identifier
-    Ok(batch_hash)
+/// The stop signal of the running P2P network and its task
+type P2pTask = (
+    tokio::sync::oneshot::Sender<()>,
+    tokio::task::JoinHandle<()>,
+);
+
+/// Stop taking transactions, persist the mempool, close RPC connections and leave
+/// the P2P network if it runs
+async fn drain_node(
+    rpc_server: &mut KanariRpcServer,
+    mempool_path: &std::path::Path,
+    p2p: Option<P2pTask>,
+) {
+    // The P2P network says goodbye to its peers while the RPC drains
+    let p2p_task = p2p.map(|(stop, task)| {
+        let _ = stop.send(());
+        task
+    });
+    let tx_pool = rpc_server.get_tx_pool();
+    let mut pool = tx_pool.write().await;
+    pool.close();
+    match pool.save(mempool_path) {
+        Ok(saved) => info!("Saved {} pending transaction(s)", saved),
+        Err(e) => error!("Failed to save pending transactions: {}", e),
+    }
+    drop(pool);
+    rpc_server.stop().await;
+    if let Some(p2p_task) = p2p_task
+        && let Err(e) = p2p_task.await
+    {
+        error!("P2P network task failed: {}", e);
+    }
 }
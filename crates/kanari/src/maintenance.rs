@@ -0,0 +1,68 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::archive::Archiver;
+use anyhow::{Result, anyhow};
+use kanari_config::maintenance_config::MaintenanceConfig;
+use kanari_db::RoochDB;
+use kanari_db::maintenance::{MaintenanceJob, MaintenanceScheduler, MaintenanceTask};
+use prometheus::Registry;
+use std::sync::Arc;
+use tracing::info;
+
+/// The storage maintenance of the node: compaction always, receipt pruning if a
+/// retention is configured, and archive uploads if they are scheduled. Receipts are
+/// never pruned before `archiver` uploaded them.
+pub fn maintenance_scheduler(
+    config: &MaintenanceConfig,
+    db: Arc<RoochDB>,
+    archiver: Option<Arc<Archiver>>,
+    registry: &Registry,
+) -> Result<MaintenanceScheduler> {
+    let compact_db = db.clone();
+    let compaction: MaintenanceJob = Arc::new(move || {
+        let db = compact_db.clone();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || db.compact())
+                .await
+                .map_err(|e| anyhow!("Compaction task failed: {}", e))?
+        })
+    });
+    let mut scheduler = MaintenanceScheduler::new(config.windows()?, registry)?
+        .with_job(MaintenanceTask::Compaction, compaction);
+
+    if let Some(retention) = config.prune_receipts_after {
+        let archiver = archiver.clone();
+        let pruning: MaintenanceJob = Arc::new(move || {
+            let (db, archiver) = (db.clone(), archiver.clone());
+            Box::pin(async move {
+                let Some(latest) = db.get_latest_block_number()? else {
+                    return Ok(());
+                };
+                let mut until = latest.saturating_sub(retention as u128);
+                // Receipts are only deleted once they are archived
+                if let Some(archiver) = archiver {
+                    until = until.min(archiver.archived_until().await?);
+                }
+                let pruned = tokio::task::spawn_blocking(move || db.prune_block_receipts(until))
+                    .await
+                    .map_err(|e| anyhow!("Pruning task failed: {}", e))??;
+                info!(
+                    "Pruned the receipts of {} block(s) below #{}",
+                    pruned, until
+                );
+                Ok(())
+            })
+        });
+        scheduler = scheduler.with_job(MaintenanceTask::Pruning, pruning);
+    }
+
+    if let Some(archiver) = archiver.filter(|_| config.archive_uploads) {
+        let archive_upload: MaintenanceJob = Arc::new(move || {
+            let archiver = archiver.clone();
+            Box::pin(async move { archiver.archive_finalized().await })
+        });
+        scheduler = scheduler.with_job(MaintenanceTask::ArchiveUpload, archive_upload);
+    }
+    Ok(scheduler)
+}
@@ -0,0 +1,326 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Context, Result};
+use kanari_config::KanariOpt;
+use kanari_config::network_config::NodeRole;
+use kanari_p2p::history::{HistoryEntry, message_type_name};
+use kanari_p2p::network::{NetworkCommand, NetworkEvent};
+use kanari_p2p::{
+    Capabilities, DisconnectRecord, Multiaddr, Node, OperatorMetadata, P2PConfig, P2PNetwork,
+    PeerInfo, SyncStatus,
+};
+use kanari_rpc_api::{
+    ConnectedPeerInfo, MessageHistoryInfo, NodeStateBus, NodeStateEvent, OperatorCertificateInfo,
+    OperatorInfo, PeerDisconnectInfo, PeerPropagationInfo, RecentMessageInfo, SyncPeerInfo,
+    SyncStatusInfo,
+};
+use prometheus::Registry;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+/// Name the node announces itself with on the P2P network
+const P2P_NODE_NAME: &str = "kari";
+
+/// The P2P settings of the node: its P2P port, bootstrap nodes and sentry role
+pub fn p2p_config(config: &KanariOpt) -> Result<P2PConfig> {
+    let network = &config.network;
+    let listen_address: Multiaddr = format!("/ip4/0.0.0.0/tcp/{}", network.p2p_port).parse()?;
+    let bootstrap_peers = config
+        .bootstrap_nodes()
+        .iter()
+        .map(|node| bootstrap_address(node))
+        .collect::<Result<Vec<_>>>()?;
+    let mut p2p_config = P2PConfig::new()
+        .with_listen_addresses(vec![listen_address])
+        .with_bootstrap_peers(bootstrap_peers)
+        .with_max_connections(network.max_peers as u32)
+        .with_message_counters_path(config.message_counters_path())
+        .with_drain_timeout(config.drain_timeout())
+        .with_identity_key_path(config.p2p_key_path())
+        .with_role(network.node_role, network.private_peers.clone());
+    if let Some(name) = &network.operator_name {
+        p2p_config = p2p_config.with_operator(OperatorMetadata {
+            name: name.clone(),
+            contact: network.operator_contact.clone(),
+            website: network.operator_website.clone(),
+        });
+    }
+    if !network.enable_discovery {
+        p2p_config.enable_mdns = false;
+        p2p_config.enable_kademlia = false;
+    }
+    p2p_config.validate()?;
+    Ok(p2p_config)
+}
+
+/// The multiaddress of an `address:port` or `hostname:port` bootstrap node
+fn bootstrap_address(node: &str) -> Result<Multiaddr> {
+    let address = match node.parse::<SocketAddr>() {
+        Ok(SocketAddr::V4(address)) => format!("/ip4/{}/tcp/{}", address.ip(), address.port()),
+        Ok(SocketAddr::V6(address)) => format!("/ip6/{}/tcp/{}", address.ip(), address.port()),
+        Err(_) => {
+            let (host, port) = node
+                .rsplit_once(':')
+                .with_context(|| format!("Bootstrap node {} has no port", node))?;
+            format!("/dns/{}/tcp/{}", host, port)
+        }
+    };
+    address
+        .parse()
+        .with_context(|| format!("Invalid bootstrap node {}", node))
+}
+
+/// Runs the P2P network of the node and reports what changes on it to the node state
+/// bus, so the RPC serves the live peers and block timestamps follow the network time
+pub struct P2pService {
+    network: P2PNetwork,
+    events: mpsc::UnboundedReceiver<NetworkEvent>,
+    commands: mpsc::UnboundedSender<NetworkCommand>,
+    state_bus: NodeStateBus,
+    running: Arc<AtomicBool>,
+}
+
+impl P2pService {
+    pub async fn new(
+        config: &KanariOpt,
+        state_bus: NodeStateBus,
+        registry: &Registry,
+    ) -> Result<Self> {
+        let chain_id = config.chain_id().id();
+        let node = match config.network.node_role {
+            NodeRole::Validator => Node::new_validator(P2P_NODE_NAME.to_string(), chain_id),
+            NodeRole::Full | NodeRole::Sentry => Node::new(P2P_NODE_NAME.to_string(), chain_id),
+        };
+        let mut network = P2PNetwork::new(p2p_config(config)?, node)
+            .await?
+            .with_metrics_registry(registry)?;
+        let (sender, events) = mpsc::unbounded_channel();
+        network.set_event_sender(sender);
+        let (commands, receiver) = mpsc::unbounded_channel();
+        network.set_command_receiver(receiver);
+        Ok(Self {
+            network,
+            events,
+            commands,
+            state_bus,
+            running: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Where the node sends commands to the running network, e.g. its new height
+    pub fn commands(&self) -> mpsc::UnboundedSender<NetworkCommand> {
+        self.commands.clone()
+    }
+
+    /// Whether the network is running, i.e. the peer count of the node state is live
+    pub fn running(&self) -> Arc<AtomicBool> {
+        self.running.clone()
+    }
+
+    /// The operator metadata the node publishes, with the certificate signing it
+    pub fn local_operator(&self) -> Option<OperatorInfo> {
+        self.network
+            .local_operator()
+            .map(|certificate| OperatorInfo {
+                name: certificate.metadata.name.clone(),
+                contact: certificate.metadata.contact.clone(),
+                website: certificate.metadata.website.clone(),
+                verified: Some(certificate.verify(&certificate.peer_id).is_ok()),
+                certificate: Some(OperatorCertificateInfo {
+                    peer_id: certificate.peer_id.clone(),
+                    public_key: format!("0x{}", hex::encode(&certificate.public_key)),
+                    issued_at: certificate.issued_at,
+                    signature: format!("0x{}", hex::encode(&certificate.signature)),
+                }),
+            })
+    }
+
+    /// Join the network and run it until `shutdown` completes, then leave it
+    pub async fn run(self, shutdown: impl Future<Output = ()>) -> Result<()> {
+        let Self {
+            mut network,
+            events,
+            state_bus,
+            running,
+            ..
+        } = self;
+        network.start().await?;
+        running.store(true, Ordering::SeqCst);
+        let forwarder = tokio::spawn(forward_events(events, state_bus));
+        let result = network.run_until(shutdown).await;
+        running.store(false, Ordering::SeqCst);
+        // Dropping the network closes the event channel, which ends the forwarder
+        drop(network);
+        forwarder.await?;
+        result
+    }
+}
+
+/// Apply the events of the network to the node state until the network is gone
+async fn forward_events(
+    mut events: mpsc::UnboundedReceiver<NetworkEvent>,
+    state_bus: NodeStateBus,
+) {
+    while let Some(event) = events.recv().await {
+        match event {
+            NetworkEvent::PeerConnected {
+                info,
+                recent_disconnects,
+            } => {
+                let peer = connected_peer_info(&info, None, &recent_disconnects);
+                state_bus.publish(NodeStateEvent::PeerConnected(peer));
+            }
+            NetworkEvent::PeerUpdated {
+                info,
+                negotiated,
+                recent_disconnects,
+            } => {
+                let peer = connected_peer_info(&info, negotiated.as_ref(), &recent_disconnects);
+                state_bus.publish(NodeStateEvent::PeerUpdated(peer));
+            }
+            NetworkEvent::PeerDisconnected(peer_id) => {
+                state_bus.publish(NodeStateEvent::PeerDisconnected(peer_id));
+            }
+            NetworkEvent::BlockReceived(block_hash) => {
+                debug!("Block {} received from the network", block_hash);
+            }
+            NetworkEvent::TransactionReceived(tx_hash) => {
+                debug!("Transaction {} received from the network", tx_hash);
+            }
+            NetworkEvent::MessageReceived(message) => {
+                debug!("{:?} received from the network", message.msg_type);
+            }
+            NetworkEvent::PropagationStats(ranking) => {
+                let ranking = ranking
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, stats)| PeerPropagationInfo {
+                        peer_id: stats.peer_id,
+                        rank: index + 1,
+                        avg_latency_ms: stats.avg_latency_ms,
+                        last_latency_ms: stats.last_latency_ms,
+                        blocks_received: stats.blocks_received,
+                        first_deliveries: stats.first_deliveries,
+                    })
+                    .collect();
+                state_bus.publish(NodeStateEvent::PropagationStats(ranking));
+            }
+            NetworkEvent::MessageHistory {
+                total_messages,
+                counts_by_type,
+                recent,
+            } => {
+                state_bus.publish(NodeStateEvent::MessageHistory(MessageHistoryInfo {
+                    total_messages,
+                    counts_by_type,
+                    recent: recent.iter().map(recent_message_info).collect(),
+                }));
+            }
+            NetworkEvent::SyncStatus(status) => {
+                state_bus.publish(NodeStateEvent::SyncStatus(sync_status_info(status)));
+            }
+            NetworkEvent::TimeOffset(offset) => {
+                state_bus.publish(NodeStateEvent::TimeOffset {
+                    offset_ms: offset.offset_ms,
+                    samples: offset.samples,
+                });
+            }
+        }
+    }
+}
+
+/// The RPC view of a peer, with the feature flags negotiated with it and why it
+/// disconnected lately
+fn connected_peer_info(
+    info: &PeerInfo,
+    negotiated: Option<&Capabilities>,
+    recent_disconnects: &[DisconnectRecord],
+) -> ConnectedPeerInfo {
+    ConnectedPeerInfo {
+        peer_id: info.id.clone(),
+        address: info.address.clone(),
+        version: info.version.clone(),
+        capabilities: negotiated.map(|capabilities| {
+            capabilities
+                .iter()
+                .map(|capability| capability.to_string())
+                .collect()
+        }),
+        operator: info.operator.as_ref().map(|operator| OperatorInfo {
+            name: operator.metadata.name.clone(),
+            contact: operator.metadata.contact.clone(),
+            website: operator.metadata.website.clone(),
+            verified: Some(operator.verified),
+            certificate: None,
+        }),
+        recent_disconnects: recent_disconnects
+            .iter()
+            .map(|record| PeerDisconnectInfo {
+                timestamp: record
+                    .timestamp
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                reason: record.reason.as_str().to_string(),
+                initiator: record.initiator.as_str().to_string(),
+                detail: record.detail.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn recent_message_info(entry: &HistoryEntry) -> RecentMessageInfo {
+    let message = &entry.message;
+    RecentMessageInfo {
+        id: message.id.to_string(),
+        msg_type: message_type_name(&message.msg_type),
+        sender: message.sender.clone(),
+        timestamp: message.timestamp,
+        payload_size: message.payload.len(),
+    }
+}
+
+fn sync_status_info(status: SyncStatus) -> SyncStatusInfo {
+    SyncStatusInfo {
+        is_syncing: status.is_syncing,
+        current_height: status.current_height,
+        target_height: status.target_height,
+        blocks_per_second: status.blocks_per_second,
+        eta_seconds: status.eta_seconds,
+        active_peers: status
+            .active_peers
+            .into_iter()
+            .map(|peer| SyncPeerInfo {
+                peer_id: peer.peer_id,
+                height: peer.height,
+                blocks_received: peer.blocks_received,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bootstrap_address() {
+        assert_eq!(
+            bootstrap_address("10.0.0.1:6778").unwrap().to_string(),
+            "/ip4/10.0.0.1/tcp/6778"
+        );
+        assert_eq!(
+            bootstrap_address("boot-1.testnet.kanari.site:6778")
+                .unwrap()
+                .to_string(),
+            "/dns/boot-1.testnet.kanari.site/tcp/6778"
+        );
+        assert!(bootstrap_address("boot-1.testnet.kanari.site").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow};
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr, TcpListener};
+use tracing::warn;
+
+/// How many ports above the configured one `--port-auto` tries
+const PORT_AUTO_SEARCH_RANGE: u16 = 100;
+
+/// Check that `service` can bind `ip:port`. With `auto`, an occupied port is
+/// replaced by the next free one not in `taken`; the chosen port is returned.
+pub fn resolve_port(
+    service: &str,
+    ip: IpAddr,
+    port: u16,
+    auto: bool,
+    taken: &[u16],
+) -> Result<u16> {
+    let error = match TcpListener::bind(SocketAddr::new(ip, port)) {
+        Ok(_) if !taken.contains(&port) => return Ok(port),
+        Ok(_) => None,
+        Err(e) => Some(e),
+    };
+    if !auto {
+        return Err(bind_error(service, SocketAddr::new(ip, port), error));
+    }
+
+    let free = (1..=PORT_AUTO_SEARCH_RANGE)
+        .filter_map(|offset| port.checked_add(offset))
+        .find(|candidate| {
+            !taken.contains(candidate) && TcpListener::bind(SocketAddr::new(ip, *candidate)).is_ok()
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "{} port {} is in use and no free port was found up to {}",
+                service,
+                port,
+                port.saturating_add(PORT_AUTO_SEARCH_RANGE)
+            )
+        })?;
+    warn!(
+        "{} port {} is in use, using port {} instead",
+        service, port, free
+    );
+    println!("{} port: {}", service, free);
+    Ok(free)
+}
+
+/// Describe why `service` can not bind `addr`, naming the process holding it if possible
+pub fn bind_error(service: &str, addr: SocketAddr, error: Option<std::io::Error>) -> anyhow::Error {
+    let reason = match &error {
+        Some(e) if e.kind() == ErrorKind::AddrInUse => "is already in use".to_string(),
+        Some(e) if e.kind() == ErrorKind::PermissionDenied => {
+            "needs elevated privileges (ports below 1024)".to_string()
+        }
+        Some(e) => format!("can not be bound: {}", e),
+        None => "is already used by another Kanari service".to_string(),
+    };
+    let owner = port_owner(addr.port())
+        .map(|owner| format!(" by {}", owner))
+        .unwrap_or_default();
+    anyhow!(
+        "{} address {} {}{}. Choose another port or pass --port-auto to pick a free one",
+        service,
+        addr,
+        reason,
+        owner
+    )
+}
+
+/// The process listening on a TCP port, e.g. `kari (pid 4242)`, read from procfs
+#[cfg(target_os = "linux")]
+fn port_owner(port: u16) -> Option<String> {
+    let inode = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .find_map(|table| listening_socket_inode(&table, port))?;
+    let socket = format!("socket:[{}]", inode);
+    for process in std::fs::read_dir("/proc").ok()?.flatten() {
+        let Ok(pid) = process.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        let owns_socket = fds.flatten().any(|fd| {
+            std::fs::read_link(fd.path()).is_ok_and(|target| target.to_string_lossy() == socket)
+        });
+        if owns_socket {
+            let name = std::fs::read_to_string(process.path().join("comm")).unwrap_or_default();
+            return Some(format!("{} (pid {})", name.trim(), pid));
+        }
+    }
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+fn port_owner(_port: u16) -> Option<String> {
+    None
+}
+
+/// Inode of the listening socket on `port` in a `/proc/net/tcp` table
+#[cfg(target_os = "linux")]
+fn listening_socket_inode(table: &str, port: u16) -> Option<u64> {
+    const TCP_LISTEN: &str = "0A";
+    table.lines().skip(1).find_map(|line| {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let local_port = columns.get(1)?.rsplit_once(':')?.1;
+        if u16::from_str_radix(local_port, 16).ok()? != port || *columns.get(3)? != TCP_LISTEN {
+            return None;
+        }
+        columns.get(9)?.parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_resolve_port_skips_occupied_ports() {
+        let ip = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let listener = TcpListener::bind(SocketAddr::new(ip, 0)).unwrap();
+        let occupied = listener.local_addr().unwrap().port();
+
+        let error = resolve_port("RPC", ip, occupied, false, &[]).unwrap_err();
+        assert!(error.to_string().contains("already in use"));
+
+        let chosen = resolve_port("RPC", ip, occupied, true, &[]).unwrap();
+        assert_ne!(chosen, occupied);
+        let next = resolve_port("P2P", ip, occupied, true, &[chosen]).unwrap();
+        assert_ne!(next, chosen);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_listening_socket_inode() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 00000000:1A6F 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 123456 1\n";
+        assert_eq!(listening_socket_inode(table, 6767), Some(123456));
+        assert_eq!(listening_socket_inode(table, 6768), None);
+    }
+}
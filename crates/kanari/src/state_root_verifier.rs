@@ -0,0 +1,207 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use kanari_config::StateRootVerifierConfig;
+use kanari_db::RoochDB;
+use kanari_rpc_api::KanariRpcApiClient;
+use kanari_rpc_api::jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use moveos_types::h256::H256;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Outcome of a single state root cross-check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateRootCheck {
+    Match {
+        height: u128,
+    },
+    Mismatch {
+        height: u128,
+        local: H256,
+        remote: H256,
+    },
+    NoCommonHeight,
+}
+
+/// Periodically compares the local state root with a trusted remote node at the
+/// latest height both nodes have, raising a critical alert on divergence.
+pub struct StateRootVerifier {
+    config: StateRootVerifierConfig,
+    db: Arc<RoochDB>,
+    client: HttpClient,
+    halted: Arc<AtomicBool>,
+    /// Set once a mismatch was seen, read by the alerting engine
+    fork_detected: Arc<AtomicBool>,
+}
+
+impl StateRootVerifier {
+    pub fn new(
+        config: StateRootVerifierConfig,
+        db: Arc<RoochDB>,
+        halted: Arc<AtomicBool>,
+        fork_detected: Arc<AtomicBool>,
+    ) -> Result<Self> {
+        let client = HttpClientBuilder::default()
+            .request_timeout(Duration::from_secs(30))
+            .build(&config.trusted_rpc_url)?;
+        Ok(Self {
+            config,
+            db,
+            client,
+            halted,
+            fork_detected,
+        })
+    }
+
+    /// Run the verifier until the task is dropped
+    pub async fn run(self) {
+        info!(
+            "State root verifier started against {} (every {}s, halt on mismatch: {})",
+            self.config.trusted_rpc_url,
+            self.config.check_interval_secs,
+            self.config.halt_on_mismatch
+        );
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.check_interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            match self.check_once().await {
+                Ok(StateRootCheck::Match { height }) => {
+                    debug!("State root at block #{} matches trusted node", height);
+                }
+                Ok(StateRootCheck::Mismatch {
+                    height,
+                    local,
+                    remote,
+                }) => {
+                    error!(
+                        "CRITICAL: state root mismatch at block #{}: local {:?}, trusted node {:?}",
+                        height, local, remote
+                    );
+                    self.fork_detected.store(true, Ordering::SeqCst);
+                    if self.config.halt_on_mismatch && !self.halted.swap(true, Ordering::SeqCst) {
+                        error!("Halting block production because of state root mismatch");
+                    }
+                }
+                Ok(StateRootCheck::NoCommonHeight) => {
+                    debug!("No common block height with trusted node yet");
+                }
+                Err(e) => {
+                    warn!("State root cross-check failed: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Compare the state roots at the latest common height once
+    pub async fn check_once(&self) -> Result<StateRootCheck> {
+        let local_height = match self.db.get_latest_block_number()? {
+            Some(height) => height,
+            None => return Ok(StateRootCheck::NoCommonHeight),
+        };
+        let remote_height = self.client.get_block_height().await?;
+        let height = local_height.min(remote_height);
+        if height == 0 {
+            return Ok(StateRootCheck::NoCommonHeight);
+        }
+
+        let local = match self.db.get_block(height)? {
+            Some(block) => block.state_root,
+            None => return Ok(StateRootCheck::NoCommonHeight),
+        };
+        let remote_block = self.client.get_block_by_number(height).await?;
+        let remote = H256::from_str(&remote_block.state_root)?;
+
+        if local == remote {
+            Ok(StateRootCheck::Match { height })
+        } else {
+            Ok(StateRootCheck::Mismatch {
+                height,
+                local,
+                remote,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_production::{build_block, save_built_block};
+    use kanari_config::KanariOpt;
+    use kanari_mempool::{PooledTransaction, TxPool};
+    use kanari_rpc_api::jsonrpsee::server::ServerBuilder;
+    use kanari_rpc_api::{KanariRpcApiServer, KanariRpcImpl, NodeState};
+    use kanari_types::reward::RewardPayment;
+    use kanari_types::system_transaction::SystemTransaction;
+    use move_core_types::account_address::AccountAddress;
+    use moveos_types::moveos_std::object::ObjectMeta;
+    use tokio::sync::RwLock;
+
+    /// A node that applied the same three blocks, the second one paying a reward
+    fn node_with_blocks() -> Arc<RoochDB> {
+        let opt = KanariOpt::new_with_temp_store().unwrap();
+        let db = Arc::new(RoochDB::init(&opt.store, &prometheus::Registry::new()).unwrap());
+        let reward = SystemTransaction::RewardDistribution {
+            epoch: 0,
+            payments: vec![RewardPayment {
+                epoch: 0,
+                validator: AccountAddress::ONE,
+                recipient: AccountAddress::ONE,
+                amount: 500,
+            }],
+        }
+        .into_transaction(1, H256::zero(), 2);
+        for block_number in 1..=3u128 {
+            let timestamp = 1_700_000_000 + block_number as u64 * 10;
+            let pending = match block_number {
+                2 => vec![PooledTransaction::new(reward.clone())],
+                _ => vec![],
+            };
+            let built = build_block(&db, block_number, 1, timestamp, &pending).unwrap();
+            save_built_block(&db, &built, timestamp, None).unwrap();
+        }
+        db
+    }
+
+    #[tokio::test]
+    async fn test_nodes_applying_the_same_blocks_agree() {
+        let (local, trusted) = (node_with_blocks(), node_with_blocks());
+        let state_root = local.get_block(3).unwrap().unwrap().state_root;
+        assert_ne!(state_root, ObjectMeta::genesis_root().state_root());
+
+        let node_state = Arc::new(RwLock::new(NodeState {
+            block_height: 3,
+            ..Default::default()
+        }));
+        let rpc = KanariRpcImpl::new(
+            node_state,
+            Arc::new(RwLock::new(TxPool::default())),
+            Some(trusted),
+        );
+        let server = ServerBuilder::default().build("127.0.0.1:0").await.unwrap();
+        let trusted_rpc_url = format!("http://{}", server.local_addr().unwrap());
+        let handle = server.start(rpc.into_rpc());
+
+        let verifier = StateRootVerifier::new(
+            StateRootVerifierConfig {
+                trusted_rpc_url,
+                check_interval_secs: 1,
+                halt_on_mismatch: true,
+            },
+            local,
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+        )
+        .unwrap();
+        assert_eq!(
+            verifier.check_once().await.unwrap(),
+            StateRootCheck::Match { height: 3 }
+        );
+        handle.stop().unwrap();
+    }
+}
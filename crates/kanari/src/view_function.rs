@@ -0,0 +1,87 @@
+// Copyright (c) KanariNetwork
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Result, anyhow};
+use kanari_db::RoochDB;
+use kanari_rpc_api::{
+    ViewFunctionExecutor, ViewFunctionResult, ViewFunctionReturnValue, move_abort_info,
+};
+use move_core_types::vm_status::{AbortLocation, VMStatus};
+use move_resource_viewer::MoveValueAnnotator;
+use moveos::moveos::{MoveOS, MoveOSConfig};
+use moveos_types::moveos_std::object::ObjectMeta;
+use moveos_types::state_resolver::RootObjectResolver;
+use moveos_types::transaction::FunctionCall;
+use rooch_genesis::FrameworkGasParameters;
+use rooch_rpc_api::jsonrpc_types::AnnotatedMoveValueView;
+use std::sync::{Arc, Mutex};
+
+/// Runs Move view functions against a state root with the framework natives.
+/// Nothing a view function writes is committed.
+pub struct MoveViewExecutor {
+    db: Arc<RoochDB>,
+    moveos: Mutex<MoveOS>,
+}
+
+impl MoveViewExecutor {
+    /// Load the natives with the gas parameters of the latest state, which needs the
+    /// framework to be deployed
+    pub fn new(db: Arc<RoochDB>) -> Result<Self> {
+        let root = latest_root(&db)?;
+        let resolver = RootObjectResolver::new(root, &db.moveos_store);
+        let gas_parameters = FrameworkGasParameters::load_from_chain(&resolver)?;
+        let moveos = MoveOS::new(
+            db.moveos_store.clone(),
+            gas_parameters.all_natives(),
+            MoveOSConfig::default(),
+            vec![],
+            vec![],
+        )?;
+        Ok(Self {
+            db,
+            moveos: Mutex::new(moveos),
+        })
+    }
+}
+
+fn latest_root(db: &RoochDB) -> Result<ObjectMeta> {
+    db.latest_root()?
+        .ok_or_else(|| anyhow!("The state has no root yet, the framework is not deployed"))
+}
+
+impl ViewFunctionExecutor for MoveViewExecutor {
+    fn execute(&self, root: ObjectMeta, call: FunctionCall) -> Result<ViewFunctionResult> {
+        let result = self
+            .moveos
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .execute_view_function(root.clone(), call);
+
+        let resolver = RootObjectResolver::new(root, &self.db.moveos_store);
+        let annotator = MoveValueAnnotator::new(&resolver);
+        let return_values = result
+            .return_values
+            .unwrap_or_default()
+            .into_iter()
+            .map(|value| {
+                let decoded = annotator.view_value(&value.type_tag, &value.value)?;
+                Ok(ViewFunctionReturnValue {
+                    type_tag: value.type_tag.to_canonical_string(),
+                    value: format!("0x{}", hex::encode(&value.value)),
+                    decoded_value: serde_json::to_value(AnnotatedMoveValueView::from(decoded))?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let abort = match &result.vm_status {
+            VMStatus::MoveAbort(AbortLocation::Module(module), code) => {
+                Some(move_abort_info(&module.short_str_lossless(), *code))
+            }
+            _ => None,
+        };
+        Ok(ViewFunctionResult {
+            vm_status: format!("{:?}", result.vm_status),
+            abort,
+            return_values,
+        })
+    }
+}